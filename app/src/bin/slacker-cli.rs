@@ -0,0 +1,25 @@
+use anyhow::Result;
+use clap::Parser;
+use dotenvy::dotenv;
+use slacker::{
+    cli::{run, Cli},
+    config::config::Config,
+};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+    // Quiet by default so command output stays readable; RUST_LOG overrides
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("warn")),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    let cli = Cli::parse();
+    let config = Config::load()?;
+
+    run(cli, config).await
+}
@@ -0,0 +1,500 @@
+//! `slacker-cli`: routine maintenance against the configured database without going
+//! through the HTTP API. Reads the same environment as the server.
+
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use sea_orm::DbErr;
+use serde::Serialize;
+
+use crate::{
+    config::{config::Config, workspaces::WorkspaceConfig},
+    core::{server::create_state, state::AppState},
+    database::connect::{connect_database, run_migrations},
+    models::{
+        message::Model as Message, task::TaskStatus, workspace::WorkspaceStatus,
+        workspace_settings::MessageRedaction,
+    },
+    repos::{
+        messages::MessagesRepo, tasks::TasksRepo, workspace_settings::WorkspaceSettingsRepo,
+        workspaces::WorkspacesRepo,
+    },
+    services::{
+        database_backups,
+        doctor::{self, Severity},
+        workspace::{
+            fetch_bot_identity, get_workspace_tokens, import_workspaces_yaml, offboard_workspace,
+            save_workspace, OffboardMode,
+        },
+    },
+    sockets::slack_bot::InitialSyncer,
+    utils::{
+        pagination::{Pagination, MAX_LIMIT},
+        redaction::redact,
+    },
+};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "slacker-cli",
+    about = "Operational tasks for a Slacker deployment"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Apply pending database migrations
+    Migrate,
+    /// Import workspaces.yaml and create default settings for every workspace
+    Seed {
+        #[arg(long, default_value = "workspaces.yaml")]
+        file: String,
+    },
+    /// Add, list or remove workspaces
+    #[command(subcommand)]
+    Workspace(WorkspaceCommand),
+    /// Manage workspace tokens
+    #[command(subcommand)]
+    Token(TokenCommand),
+    /// Import Slack history for a workspace now, in this process
+    Sync {
+        #[arg(long)]
+        workspace: String,
+        /// Only sync this Slack member instead of everyone in the workspace
+        #[arg(long)]
+        member: Option<String>,
+    },
+    /// Write data to stdout
+    #[command(subcommand)]
+    Export(ExportCommand),
+    /// Convert stored message text
+    #[command(subcommand)]
+    Messages(MessagesCommand),
+    /// Check the config, database and Slack tokens and report what needs fixing
+    Doctor,
+    /// Back up the database to `[backup]` storage, or restore it
+    #[command(subcommand)]
+    Backup(BackupCommand),
+}
+
+#[derive(Debug, Subcommand)]
+enum BackupCommand {
+    /// Write a backup of every table
+    Create,
+    /// Backups in the configured storage, newest first
+    List,
+    /// Replace all data with a backup
+    Restore {
+        #[arg(long)]
+        name: String,
+        /// Confirm that every server is in maintenance mode or stopped
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum WorkspaceCommand {
+    /// Store a workspace and its tokens; running servers start its bot on their next reload
+    Add {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        app_token: String,
+        #[arg(long)]
+        bot_token: String,
+        /// Slack team of the workspace, needed with org-wide tokens of an Enterprise
+        /// Grid install
+        #[arg(long)]
+        team_id: Option<String>,
+    },
+    List,
+    /// Archive a workspace, or delete it with all its data when `--purge` is given
+    Remove {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        purge: bool,
+        /// Only report what would be removed
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum TokenCommand {
+    /// Replace the app and/or bot token of a workspace
+    Rotate {
+        #[arg(long)]
+        workspace: String,
+        #[arg(long)]
+        app_token: Option<String>,
+        #[arg(long)]
+        bot_token: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum MessagesCommand {
+    /// Encrypt messages stored in plain text, after turning on `encrypt_messages`
+    Encrypt,
+    /// Store every message in plain text again, before turning off `encrypt_messages`
+    Decrypt,
+}
+
+#[derive(Debug, Subcommand)]
+enum ExportCommand {
+    Tasks {
+        /// Only tasks of this workspace
+        #[arg(long)]
+        workspace: Option<String>,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportedTask {
+    id: String,
+    status: TaskStatus,
+    workspace: Option<String>,
+    assigned_to: String,
+    assigned_by: Option<String>,
+    created_at: String,
+    message: Option<String>,
+}
+
+pub async fn run(cli: Cli, config: Config) -> Result<()> {
+    match cli.command {
+        Command::Migrate => {
+            let db = connect_database(config).await?;
+            run_migrations(&db).await?;
+            println!("Migrations applied");
+            Ok(())
+        }
+        Command::Doctor => doctor(&config).await,
+        // Everything else runs against the same state the server builds
+        command => run_with_state(command, create_state(config).await?).await,
+    }
+}
+
+async fn run_with_state(command: Command, state: Arc<AppState>) -> Result<()> {
+    let db = &state.database;
+    let encryption_key = &state.config.encryption_key;
+    let workspaces_repo = WorkspacesRepo::new(db.clone());
+
+    match command {
+        Command::Migrate | Command::Doctor => unreachable!("handled before the state is built"),
+        Command::Seed { file } => {
+            let imported = import_workspaces_yaml(db, &file, encryption_key).await?;
+            println!("Imported {} workspace(s) from {}", imported, file);
+
+            let settings_repo = WorkspaceSettingsRepo::new(db.clone());
+            for workspace in workspaces_repo.get_all().await? {
+                settings_repo.get_or_create(&workspace.id).await?;
+            }
+            println!("Default settings in place for every workspace");
+        }
+        Command::Workspace(WorkspaceCommand::Add {
+            name,
+            app_token,
+            bot_token,
+            team_id,
+        }) => {
+            validate_tokens(Some(&app_token), Some(&bot_token))?;
+
+            let bot = fetch_bot_identity(&bot_token).await?;
+            let team_id = bot
+                .workspace_team(team_id.as_deref())
+                .map_err(anyhow::Error::msg)?;
+            let tokens = WorkspaceConfig {
+                app_token,
+                bot_token,
+                enterprise_id: bot.org_wide_enterprise_id(),
+            };
+            let workspace =
+                save_workspace(db, &name, &tokens, Some(team_id), None, encryption_key).await?;
+            println!("Saved workspace '{}' ({})", workspace.name, workspace.id);
+        }
+        Command::Workspace(WorkspaceCommand::List) => {
+            for workspace in workspaces_repo.get_all().await? {
+                let status = match workspace.status {
+                    WorkspaceStatus::Active => "active",
+                    WorkspaceStatus::Archived => "archived",
+                };
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    workspace.name,
+                    status,
+                    workspace.slack_team_id.as_deref().unwrap_or("-"),
+                    workspace.id
+                );
+            }
+        }
+        Command::Workspace(WorkspaceCommand::Remove {
+            name,
+            purge,
+            dry_run,
+        }) => {
+            let workspace = workspaces_repo.get_by_name(&name).await?;
+            let mode = if purge {
+                OffboardMode::Purge
+            } else {
+                OffboardMode::Archive
+            };
+
+            let report =
+                offboard_workspace(db, &state.message_encryption, &workspace, mode, dry_run)
+                    .await?;
+            if !dry_run {
+                state.cache.invalidate_workspace(&workspace.id).await;
+            }
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        Command::Token(TokenCommand::Rotate {
+            workspace,
+            app_token,
+            bot_token,
+        }) => {
+            if app_token.is_none() && bot_token.is_none() {
+                bail!("Pass --app-token, --bot-token or both");
+            }
+            validate_tokens(app_token.as_deref(), bot_token.as_deref())?;
+
+            let workspace = workspaces_repo.get_by_name(&workspace).await?;
+            let existing = get_workspace_tokens(db, &workspace.id, encryption_key).await?;
+            let tokens = WorkspaceConfig {
+                app_token: app_token.unwrap_or(existing.app_token),
+                bot_token: bot_token.unwrap_or(existing.bot_token),
+                enterprise_id: existing.enterprise_id,
+            };
+
+            save_workspace(db, &workspace.name, &tokens, None, None, encryption_key).await?;
+            state.cache.invalidate_workspace(&workspace.id).await;
+            println!(
+                "Rotated tokens of '{}'; restart running servers to reconnect its bot",
+                workspace.name
+            );
+        }
+        Command::Sync { workspace, member } => {
+            let workspace = workspaces_repo.get_by_name(&workspace).await?;
+            let tokens = get_workspace_tokens(db, &workspace.id, encryption_key).await?;
+            let syncer = InitialSyncer::new(&workspace, tokens.bot_token, &state);
+
+            match member {
+                Some(member) => syncer.perform_initial_sync(&member).await?,
+                None => syncer.perform_initial_sync_for_all_users().await?,
+            }
+            println!("Synced '{}'", workspace.name);
+        }
+        Command::Messages(command) => {
+            let encrypt = matches!(command, MessagesCommand::Encrypt);
+            if encrypt && !state.config.encrypt_messages {
+                bail!("Turn on encrypt_messages first, or new messages stay in plain text");
+            }
+
+            let messages_repo = MessagesRepo::new(db.clone(), state.message_encryption.clone());
+            let tasks_repo = TasksRepo::new(db.clone());
+            let (mut converted, mut skipped) = (0, 0);
+            let mut pagination = Pagination::first(MAX_LIMIT);
+            loop {
+                let page = messages_repo.get_page(&pagination).await?;
+                let next = pagination.next(&page);
+
+                for message in page.items {
+                    if !encrypt {
+                        messages_repo.reseal(message, "", false).await?;
+                        converted += 1;
+                        continue;
+                    }
+
+                    // The key belongs to the workspace of the message's task, or of
+                    // the task it was merged into
+                    let task = match tasks_repo.get_task_by_message_id(message.id.clone()).await {
+                        Ok(task) => Some(task),
+                        Err(DbErr::RecordNotFound(_)) => match &message.merged_into_task_id {
+                            Some(task_id) => tasks_repo.get(task_id.clone()).await.ok(),
+                            None => None,
+                        },
+                        Err(e) => return Err(e.into()),
+                    };
+                    match task.and_then(|task| task.workspace_id) {
+                        Some(workspace_id) => {
+                            messages_repo.reseal(message, &workspace_id, true).await?;
+                            converted += 1;
+                        }
+                        None => skipped += 1,
+                    }
+                }
+
+                match next {
+                    Some(next) => pagination = next,
+                    None => break,
+                }
+            }
+
+            println!(
+                "{} {} message(s), skipped {} without a workspace",
+                if encrypt { "Encrypted" } else { "Decrypted" },
+                converted,
+                skipped
+            );
+        }
+        Command::Backup(BackupCommand::List) => {
+            for backup in database_backups::list(&state.config.backup).await? {
+                println!(
+                    "{}\t{}\t{}",
+                    backup.name, backup.size_bytes, backup.location
+                );
+            }
+        }
+        Command::Backup(BackupCommand::Create) => {
+            let backup = database_backups::create(db, &state.config.backup).await?;
+            println!("Wrote {} ({} bytes)", backup.location, backup.size_bytes);
+        }
+        Command::Backup(BackupCommand::Restore { name, yes }) => {
+            // This process can't see the servers' maintenance mode, so ask for it
+            if !yes {
+                bail!("Restoring replaces all data. Turn on maintenance mode on every server or stop them, then pass --yes");
+            }
+            let report = database_backups::restore(db, &state.config.backup, &name).await?;
+            for workspace in workspaces_repo.get_all().await? {
+                state.cache.invalidate_workspace(&workspace.id).await;
+            }
+            println!("Restored {} row(s) from {}", report.rows, report.name);
+        }
+        Command::Export(ExportCommand::Tasks { workspace, format }) => {
+            let workspace_names: HashMap<String, String> = workspaces_repo
+                .get_all()
+                .await?
+                .into_iter()
+                .map(|w| (w.id, w.name))
+                .collect();
+
+            let tasks_repo = TasksRepo::new(state.read_database.clone());
+            let tasks = match workspace {
+                Some(name) => {
+                    let workspace = workspaces_repo.get_by_name(&name).await?;
+                    tasks_repo.get_by_workspace(workspace.id).await?
+                }
+                None => tasks_repo.get_all_tasks().await?,
+            };
+
+            let message_ids = tasks.iter().map(|t| t.message_id.clone()).collect();
+            let messages: HashMap<String, Message> = MessagesRepo::new(
+                state.read_database.clone(),
+                state.message_encryption.clone(),
+            )
+            .get_by_ids(message_ids)
+            .await?
+            .into_iter()
+            .map(|m| (m.id.clone(), m))
+            .collect();
+
+            // Messages stored before a workspace turned on redaction are redacted now
+            let settings_repo = WorkspaceSettingsRepo::new(state.read_database.clone());
+            let mut redactions: HashMap<String, MessageRedaction> = HashMap::new();
+            for workspace_id in tasks.iter().filter_map(|t| t.workspace_id.as_ref()) {
+                if !redactions.contains_key(workspace_id) {
+                    let redaction = settings_repo.get_message_redaction(workspace_id).await?;
+                    redactions.insert(workspace_id.clone(), redaction);
+                }
+            }
+
+            let tasks: Vec<ExportedTask> = tasks
+                .into_iter()
+                .map(|task| ExportedTask {
+                    workspace: task
+                        .workspace_id
+                        .as_ref()
+                        .and_then(|id| workspace_names.get(id).cloned()),
+                    message: messages.get(&task.message_id).map(|m| {
+                        let redaction = task
+                            .workspace_id
+                            .as_ref()
+                            .and_then(|id| redactions.get(id))
+                            .copied()
+                            .unwrap_or_default();
+                        redact(&m.content, redaction)
+                    }),
+                    id: task.id,
+                    status: task.status,
+                    assigned_to: task.assigned_to,
+                    assigned_by: task.assigned_by,
+                    created_at: task.created_at.and_utc().to_rfc3339(),
+                })
+                .collect();
+
+            match format {
+                ExportFormat::Json => println!("{}", serde_json::to_string_pretty(&tasks)?),
+                ExportFormat::Csv => print_tasks_csv(&tasks)?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Print every finding; fails when any check found an error
+pub async fn doctor(config: &Config) -> Result<()> {
+    let findings = doctor::run_checks(config).await;
+    for finding in &findings {
+        println!("{}", finding);
+    }
+
+    let errors = findings
+        .iter()
+        .filter(|finding| finding.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        bail!("{} check(s) failed", errors);
+    }
+    Ok(())
+}
+
+fn validate_tokens(app_token: Option<&str>, bot_token: Option<&str>) -> Result<()> {
+    if app_token.is_some_and(|t| !t.starts_with("xapp-")) {
+        bail!("Invalid app token format. Should start with 'xapp-'");
+    }
+    if bot_token.is_some_and(|t| !t.starts_with("xoxb-")) {
+        bail!("Invalid bot token format. Should start with 'xoxb-'");
+    }
+    Ok(())
+}
+
+fn print_tasks_csv(tasks: &[ExportedTask]) -> Result<()> {
+    println!("id,status,workspace,assigned_to,assigned_by,created_at,message");
+    for task in tasks {
+        let status = serde_json::to_value(&task.status)?;
+        let fields = [
+            task.id.as_str(),
+            status.as_str().unwrap_or_default(),
+            task.workspace.as_deref().unwrap_or_default(),
+            task.assigned_to.as_str(),
+            task.assigned_by.as_deref().unwrap_or_default(),
+            task.created_at.as_str(),
+            task.message.as_deref().unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.into_iter().map(csv_field).collect();
+        println!("{}", row.join(","));
+    }
+    Ok(())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
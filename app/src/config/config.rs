@@ -1,8 +1,132 @@
-use serde::Deserialize;
+use figment::{
+    providers::{Env, Format, Toml},
+    value::Uncased,
+    Figment,
+};
+use serde::{Deserialize, Deserializer};
+
+/// Config file read when `SLACKER_CONFIG` doesn't point elsewhere; it may be missing
+const DEFAULT_CONFIG_FILE: &str = "slacker.toml";
+
+/// Nested sections; env vars with their prefix fill them, e.g. `GOOGLE_CLIENT_ID`
+/// sets `client_id` under `[google]`
+const SECTIONS: [&str; 5] = ["google", "slack", "jwt", "cors", "log"];
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct GoogleConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+}
+
+/// "Sign in with Slack" over OpenID Connect, offered next to Google once the
+/// client id and secret are set
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct SlackConfig {
+    #[serde(default)]
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: String,
+    #[serde(default)]
+    pub redirect_uri: String,
+}
+
+impl SlackConfig {
+    pub fn login_enabled(&self) -> bool {
+        !self.client_id.is_empty() && !self.client_secret.is_empty()
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct JwtConfig {
+    pub secret: String,
+    #[serde(default = "default_jwt_expiry")]
+    pub expiry_hours: i64,
+    /// Lifetime of tokens issued by `POST /api/admin/impersonate`
+    #[serde(default = "default_impersonation_expiry")]
+    pub impersonation_expiry_minutes: i64,
+}
+
+/// Which browser origins may call the API
+#[derive(Debug, Deserialize, Clone)]
+pub struct CorsConfig {
+    /// Exact origins, e.g. "https://tasks.example.com"; a comma separated list in
+    /// `CORS_ALLOWED_ORIGINS`. `frontend_url` is always allowed
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub allowed_origins: Vec<String>,
+    /// Also allow `http://localhost` and `http://127.0.0.1` on any port. Defaults to
+    /// on while `frontend_url` itself is local, so dev servers work out of the box
+    pub allow_localhost: Option<bool>,
+    /// Let browsers send credentials along, needed for the Authorization header
+    #[serde(default = "default_cors_allow_credentials")]
+    pub allow_credentials: bool,
+    /// How long browsers may cache a preflight answer
+    #[serde(default = "default_cors_max_age")]
+    pub max_age_secs: u64,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allow_localhost: None,
+            allow_credentials: default_cors_allow_credentials(),
+            max_age_secs: default_cors_max_age(),
+        }
+    }
+}
+
+/// Log files written next to stdout, as JSON lines
+#[derive(Debug, Deserialize, Clone)]
+pub struct LogConfig {
+    /// Directory of the log files; none are written when unset
+    pub dir: Option<String>,
+    /// How many log files are kept, one per day unless a day outgrows `max_file_mb`
+    #[serde(default = "default_log_retention_files")]
+    pub retention_files: usize,
+    /// Size at which the day's log continues in a new file
+    #[serde(default = "default_log_max_file_mb")]
+    pub max_file_mb: u64,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            retention_files: default_log_retention_files(),
+            max_file_mb: default_log_max_file_mb(),
+        }
+    }
+}
+
+/// Where database backups are kept
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct BackupConfig {
+    /// Directory of the backup files
+    pub dir: Option<String>,
+    /// S3 or S3-compatible bucket, used instead of `dir` when set
+    pub s3: Option<S3Config>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct S3Config {
+    pub bucket: String,
+    pub region: String,
+    /// e.g. `https://minio.internal:9000`; AWS S3 of `region` when unset
+    pub endpoint: Option<String>,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Put in front of backup names, e.g. `slacker/`
+    #[serde(default)]
+    pub prefix: String,
+}
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub database_url: String,
+    /// Optional read replica. Read-only queries such as the task board use it,
+    /// everything else stays on `database_url`
+    pub database_read_url: Option<String>,
     #[serde(default = "default_port")]
     pub port: u16,
     #[serde(default = "default_server_ip")]
@@ -14,25 +138,127 @@ pub struct Config {
     #[serde(default = "default_min_connections")]
     pub min_connections: u32,
 
-    pub google_client_id: String,
-    pub google_client_secret: String,
-    pub google_redirect_uri: String,
+    /// How long a query waits for a free pooled connection before failing
+    #[serde(default = "default_acquire_timeout")]
+    pub acquire_timeout_secs: u64,
+    /// Postgres cancels statements running longer than this; no limit when unset
+    pub statement_timeout_ms: Option<u64>,
+    /// Log queries slower than this with the repository method that ran them; off
+    /// when unset
+    pub slow_query_ms: Option<u64>,
+
+    pub google: GoogleConfig,
+
+    #[serde(default)]
+    pub slack: SlackConfig,
 
     /// Frontend URL for OAuth callback redirects
     #[serde(default = "default_frontend_url")]
     pub frontend_url: String,
 
-    pub jwt_secret: String,
-    #[serde(default = "default_jwt_expiry")]
-    pub jwt_expiry_hours: i64,
+    pub jwt: JwtConfig,
+
+    #[serde(default)]
+    pub cors: CorsConfig,
+
+    #[serde(default)]
+    pub log: LogConfig,
+
+    #[serde(default)]
+    pub backup: BackupConfig,
 
     /// Master key for encrypting workspace tokens
     /// IMPORTANT: Keep this secret and don't lose it!
     #[serde(default = "default_encryption_key")]
     pub encryption_key: String,
 
+    /// Encrypt message text at rest with a key per workspace derived from
+    /// `encryption_key`. Existing messages are converted with `slacker-cli messages encrypt`
+    #[serde(default)]
+    pub encrypt_messages: bool,
+
     /// Super admin email - this user can always configure workspaces and invite other admins
     pub admin_email: String,
+
+    /// Seconds to wait for in-flight requests and bot events on shutdown
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout_secs: u64,
+
+    /// SMTP server for outgoing email. When unset, emails are logged instead of sent
+    pub smtp_host: Option<String>,
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    /// Sender address, e.g. "Slacker <noreply@example.com>"
+    #[serde(default = "default_smtp_from")]
+    pub smtp_from: String,
+
+    /// How long a workspace invitation link stays valid
+    #[serde(default = "default_invitation_expiry")]
+    pub invitation_expiry_hours: i64,
+
+    /// Redis for the shared cache, e.g. "redis://localhost:6379". When unset, values
+    /// are cached in process memory
+    pub redis_url: Option<String>,
+    /// How long cached Slack lookups, settings and tokens are reused
+    #[serde(default = "default_cache_ttl")]
+    pub cache_ttl_secs: u64,
+    /// Serve the boards of workspaces with at least this many tasks from a snapshot
+    /// kept in the cache, see `services::board_snapshots`. Such boards only show
+    /// tasks created in the active workspace. Off when unset
+    #[serde(default)]
+    pub board_snapshot_min_tasks: Option<u64>,
+
+    /// How often workspaces.yaml and the stored workspaces are checked for added or
+    /// removed workspaces, which also renews the bot leases
+    #[serde(default = "default_workspace_reload_interval")]
+    pub workspace_reload_interval_secs: u64,
+
+    /// How long a lease on a bot or the scheduler outlives its holder. Another
+    /// instance takes over this long after a crash; keep it at a few reload intervals
+    #[serde(default = "default_lease_ttl")]
+    pub lease_ttl_secs: u64,
+
+    /// Requests slower than this are logged and counted as over budget
+    #[serde(default = "default_latency_budget")]
+    pub latency_budget_ms: u64,
+    /// Window the p99 latency of each route is computed over
+    #[serde(default = "default_latency_window")]
+    pub latency_window_secs: u64,
+    /// Notify the super admin when a route's p99 latency goes over budget, at most
+    /// once per route and window
+    #[serde(default)]
+    pub latency_alerts: bool,
+
+    /// How long the bot event log at /api/admin/bot-events keeps events
+    #[serde(default = "default_bot_event_retention_days")]
+    pub bot_event_retention_days: i64,
+    /// Most events the bot event log keeps per workspace; older ones go first
+    #[serde(default = "default_bot_event_limit")]
+    pub bot_event_limit: u64,
+
+    /// How long the response to a POST with an `Idempotency-Key` header is replayed
+    /// to retries with the same key
+    #[serde(default = "default_idempotency_key_ttl")]
+    pub idempotency_key_ttl_hours: i64,
+
+    /// How long deleted tasks are reported by /api/tasks/board/changes; older
+    /// cursors have to load the whole board again
+    #[serde(default = "default_task_tombstone_retention_days")]
+    pub task_tombstone_retention_days: i64,
+
+    /// Move status changes of tasks completed this many days ago, and messages of
+    /// merged duplicates this old, to archive tables; see `services::archive`. Off
+    /// when unset
+    #[serde(default)]
+    pub archive_after_days: Option<i64>,
+
+    /// Domain Mailgun receives task emails on, e.g. "tasks.example.com". Each
+    /// workspace gets its own address there; email-in is off when unset
+    pub inbound_email_domain: Option<String>,
+    /// Mailgun webhook signing key, verifies inbound email deliveries
+    pub mailgun_signing_key: Option<String>,
 }
 
 fn default_port() -> u16 {
@@ -47,11 +273,86 @@ fn default_max_connections() -> u32 {
 fn default_min_connections() -> u32 {
     2
 }
+fn default_acquire_timeout() -> u64 {
+    30
+}
 
 fn default_jwt_expiry() -> i64 {
     168
 }
 
+fn default_impersonation_expiry() -> i64 {
+    30
+}
+
+fn default_shutdown_timeout() -> u64 {
+    10
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+fn default_smtp_from() -> String {
+    "Slacker <noreply@localhost>".to_string()
+}
+
+fn default_invitation_expiry() -> i64 {
+    72
+}
+
+fn default_cache_ttl() -> u64 {
+    300
+}
+
+fn default_workspace_reload_interval() -> u64 {
+    30
+}
+
+fn default_lease_ttl() -> u64 {
+    90
+}
+
+fn default_latency_budget() -> u64 {
+    1000
+}
+
+fn default_latency_window() -> u64 {
+    300
+}
+
+fn default_bot_event_retention_days() -> i64 {
+    7
+}
+
+fn default_bot_event_limit() -> u64 {
+    10_000
+}
+
+fn default_idempotency_key_ttl() -> i64 {
+    24
+}
+
+fn default_task_tombstone_retention_days() -> i64 {
+    7
+}
+
+fn default_log_retention_files() -> usize {
+    14
+}
+
+fn default_log_max_file_mb() -> u64 {
+    100
+}
+
+fn default_cors_allow_credentials() -> bool {
+    true
+}
+
+fn default_cors_max_age() -> u64 {
+    3600
+}
+
 fn default_frontend_url() -> String {
     "http://localhost:5173".to_string()
 }
@@ -62,7 +363,45 @@ fn default_encryption_key() -> String {
 }
 
 impl Config {
-    pub fn load_envs() -> Result<Self, envy::Error> {
-        envy::from_env()
+    /// Load `slacker.toml` (or the file in `SLACKER_CONFIG`) with env vars on top
+    pub fn load() -> Result<Self, figment::Error> {
+        let path =
+            std::env::var("SLACKER_CONFIG").unwrap_or_else(|_| DEFAULT_CONFIG_FILE.to_string());
+
+        Figment::new()
+            .merge(Toml::file(path))
+            .merge(Env::raw().map(nest_env_key))
+            .extract()
+    }
+}
+
+/// A list in TOML, or a comma separated string as env vars come
+fn comma_separated<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum List {
+        Items(Vec<String>),
+        Joined(String),
+    }
+
+    let items = match List::deserialize(deserializer)? {
+        List::Items(items) => items,
+        List::Joined(joined) => joined.split(',').map(str::to_string).collect(),
+    };
+    Ok(items
+        .into_iter()
+        .map(|item| item.trim().trim_end_matches('/').to_string())
+        .filter(|item| !item.is_empty())
+        .collect())
+}
+
+/// `JWT_EXPIRY_HOURS` -> `jwt.expiry_hours`; keys outside a section stay flat
+fn nest_env_key(key: &figment::value::UncasedStr) -> Uncased<'_> {
+    let key = key.as_str().to_ascii_lowercase();
+    for section in SECTIONS {
+        if let Some(field) = key.strip_prefix(section).and_then(|k| k.strip_prefix('_')) {
+            return format!("{}.{}", section, field).into();
+        }
     }
+    key.into()
 }
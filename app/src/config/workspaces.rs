@@ -8,6 +8,10 @@ use crate::utils::encryption::{decrypt, encrypt, is_encrypted};
 pub struct WorkspaceConfig {
     pub app_token: String,
     pub bot_token: String,
+    /// Enterprise Grid org the tokens were installed on org-wide. The same tokens
+    /// then serve every workspace of the org, each set up with its own team id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enterprise_id: Option<String>,
 }
 
 impl WorkspaceConfig {
@@ -16,6 +20,7 @@ impl WorkspaceConfig {
         Ok(Self {
             app_token: encrypt(&self.app_token, encryption_key)?,
             bot_token: encrypt(&self.bot_token, encryption_key)?,
+            enterprise_id: self.enterprise_id.clone(),
         })
     }
 
@@ -37,6 +42,7 @@ impl WorkspaceConfig {
         Ok(Self {
             app_token,
             bot_token,
+            enterprise_id: self.enterprise_id.clone(),
         })
     }
 }
@@ -0,0 +1,77 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::models::task::Model as Task;
+
+/// How many events a slow subscriber may fall behind before it starts missing updates
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoardChange {
+    Created,
+    Updated,
+    /// The task is gone, e.g. merged into another as a duplicate
+    Deleted,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BoardEvent {
+    pub workspace_name: String,
+    pub change: BoardChange,
+    pub task: Task,
+}
+
+/// Fan-out of task changes to live board subscribers (WebSocket clients)
+#[derive(Debug, Clone)]
+pub struct BoardEventsManager {
+    sender: broadcast::Sender<BoardEvent>,
+    /// Moves on every task change this instance sees; board ETags derive from it
+    version: Arc<AtomicU64>,
+}
+
+impl Default for BoardEventsManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BoardEventsManager {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        // Start from the boot time so ETags from before a restart don't match
+        let version = chrono::Utc::now().timestamp_millis() as u64;
+        Self {
+            sender,
+            version: Arc::new(AtomicU64::new(version)),
+        }
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Relaxed)
+    }
+
+    /// Mark boards as changed without an event, e.g. after a sync or a purge
+    pub fn touch(&self) {
+        self.version.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Publish a task change. Dropped silently when nobody is listening.
+    pub fn publish(&self, workspace_name: &str, change: BoardChange, task: Task) {
+        self.touch();
+        let _ = self.sender.send(BoardEvent {
+            workspace_name: workspace_name.to_string(),
+            change,
+            task,
+        });
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<BoardEvent> {
+        self.sender.subscribe()
+    }
+}
@@ -2,8 +2,9 @@ use chrono::{DateTime, Utc};
 use serde::Serialize;
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::RwLock;
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct BotStatus {
     pub workspace_name: String,
     pub is_connected: bool,
@@ -94,6 +95,12 @@ impl BotStatusManager {
         }
     }
 
+    /// Forget a workspace's bot, e.g. after it was stopped
+    pub async fn remove(&self, workspace_name: &str) {
+        let mut statuses = self.statuses.write().await;
+        statuses.remove(workspace_name);
+    }
+
     /// Get status for a specific workspace
     pub async fn get_status(&self, workspace_name: &str) -> Option<BotStatus> {
         let statuses = self.statuses.read().await;
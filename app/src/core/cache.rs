@@ -0,0 +1,190 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use redis::{aio::ConnectionManager, AsyncCommands};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::config::config::Config;
+
+/// Entries kept in process memory before expired ones are swept
+const MEMORY_SWEEP_THRESHOLD: usize = 10_000;
+
+/// Cache keys, kept in one place so invalidation matches what was cached
+pub mod keys {
+    pub fn emoji_mappings(workspace_id: &str) -> String {
+        format!("emoji_mappings:{}", workspace_id)
+    }
+
+    pub fn status_resolution(workspace_id: &str) -> String {
+        format!("status_resolution:{}", workspace_id)
+    }
+
+    pub fn reaction_policy(workspace_id: &str) -> String {
+        format!("reaction_policy:{}", workspace_id)
+    }
+
+    pub fn private_channel_policy(workspace_id: &str) -> String {
+        format!("private_channel_policy:{}", workspace_id)
+    }
+
+    /// Whether a channel is private, a group DM or a DM
+    pub fn channel_privacy(workspace_id: &str, channel: &str) -> String {
+        format!("channel_privacy:{}:{}", workspace_id, channel)
+    }
+
+    pub fn keyword_triggers(workspace_id: &str) -> String {
+        format!("keyword_triggers:{}", workspace_id)
+    }
+
+    pub fn channels(workspace_id: &str) -> String {
+        format!("channels:{}", workspace_id)
+    }
+
+    pub fn custom_emoji(workspace_id: &str) -> String {
+        format!("custom_emoji:{}", workspace_id)
+    }
+
+    pub fn slack_user(workspace_id: &str, email: &str) -> String {
+        format!("slack_user:{}:{}", workspace_id, email.to_lowercase())
+    }
+
+    pub fn workspace_tokens(workspace_id: &str) -> String {
+        format!("workspace_tokens:{}", workspace_id)
+    }
+
+    pub fn board_snapshot(workspace_id: &str) -> String {
+        format!("board_snapshot:{}", workspace_id)
+    }
+}
+
+type MemoryStore = Arc<RwLock<HashMap<String, (String, Instant)>>>;
+
+/// Cache for Slack lookups, settings and decrypted tokens. Shared values go to Redis
+/// when `REDIS_URL` is set and to process memory otherwise. A failing Redis only
+/// means cache misses.
+#[derive(Clone)]
+pub struct Cache {
+    memory: MemoryStore,
+    redis: Option<ConnectionManager>,
+    ttl: Duration,
+}
+
+impl std::fmt::Debug for Cache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cache")
+            .field("redis", &self.redis.is_some())
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+impl Cache {
+    pub async fn from_config(config: &Config) -> Result<Self> {
+        let redis = match config.redis_url.as_deref().filter(|u| !u.is_empty()) {
+            Some(url) => {
+                let client = redis::Client::open(url)?;
+                info!("Using Redis cache");
+                Some(ConnectionManager::new(client).await?)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            memory: Default::default(),
+            redis,
+            ttl: Duration::from_secs(config.cache_ttl_secs),
+        })
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let raw = match &self.redis {
+            Some(redis) => match redis.clone().get::<_, Option<String>>(key).await {
+                Ok(raw) => raw,
+                Err(e) => {
+                    warn!("[Cache] Redis get {} failed: {}", key, e);
+                    None
+                }
+            },
+            None => self.get_memory(key).await,
+        };
+
+        serde_json::from_str(&raw?).ok()
+    }
+
+    pub async fn set<T: Serialize>(&self, key: &str, value: &T) {
+        let Ok(raw) = serde_json::to_string(value) else {
+            return;
+        };
+
+        match &self.redis {
+            Some(redis) => {
+                if let Err(e) = redis
+                    .clone()
+                    .set_ex::<_, _, ()>(key, raw, self.ttl.as_secs())
+                    .await
+                {
+                    warn!("[Cache] Redis set {} failed: {}", key, e);
+                }
+            }
+            None => self.set_memory(key, raw).await,
+        }
+    }
+
+    /// Like `get`, but never reads Redis. For secrets such as decrypted tokens.
+    pub async fn get_local<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        serde_json::from_str(&self.get_memory(key).await?).ok()
+    }
+
+    /// Like `set`, but the value never leaves this process
+    pub async fn set_local<T: Serialize>(&self, key: &str, value: &T) {
+        if let Ok(raw) = serde_json::to_string(value) {
+            self.set_memory(key, raw).await;
+        }
+    }
+
+    /// Drop a key from memory and Redis
+    pub async fn delete(&self, key: &str) {
+        self.memory.write().await.remove(key);
+
+        if let Some(redis) = &self.redis {
+            if let Err(e) = redis.clone().del::<_, ()>(key).await {
+                warn!("[Cache] Redis delete {} failed: {}", key, e);
+            }
+        }
+    }
+
+    /// Drop everything cached for a workspace, e.g. after its tokens changed
+    pub async fn invalidate_workspace(&self, workspace_id: &str) {
+        self.delete(&keys::workspace_tokens(workspace_id)).await;
+        self.delete(&keys::emoji_mappings(workspace_id)).await;
+        self.delete(&keys::status_resolution(workspace_id)).await;
+        self.delete(&keys::reaction_policy(workspace_id)).await;
+        self.delete(&keys::private_channel_policy(workspace_id))
+            .await;
+        self.delete(&keys::keyword_triggers(workspace_id)).await;
+        self.delete(&keys::channels(workspace_id)).await;
+        self.delete(&keys::custom_emoji(workspace_id)).await;
+        self.delete(&keys::board_snapshot(workspace_id)).await;
+    }
+
+    async fn get_memory(&self, key: &str) -> Option<String> {
+        let memory = self.memory.read().await;
+        let (raw, expires_at) = memory.get(key)?;
+        (*expires_at > Instant::now()).then(|| raw.clone())
+    }
+
+    async fn set_memory(&self, key: &str, raw: String) {
+        let now = Instant::now();
+        let mut memory = self.memory.write().await;
+        if memory.len() >= MEMORY_SWEEP_THRESHOLD {
+            memory.retain(|_, (_, expires_at)| *expires_at > now);
+        }
+        memory.insert(key.to_string(), (raw, now + self.ttl));
+    }
+}
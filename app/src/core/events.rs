@@ -0,0 +1,61 @@
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::models::task::{Model as Task, TaskStatus};
+
+/// How many events a slow consumer may fall behind before it starts missing some
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Something that happened in Slacker that features besides the one that made it
+/// happen care about. Board changes for live boards and REST hooks go through
+/// `BoardEventsManager`; these drive notifications and the audit log.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DomainEvent {
+    TaskCreated {
+        task: Task,
+        /// Picked up from channel history rather than tasked just now
+        from_history: bool,
+    },
+    TaskTransitioned {
+        from: TaskStatus,
+        task: Task,
+    },
+    WorkspaceLinked {
+        person_id: String,
+        workspace_id: String,
+    },
+    BotDisconnected {
+        workspace_id: String,
+        workspace_name: String,
+        reason: Option<String>,
+    },
+}
+
+/// Fan-out of domain events to the consumers running on this instance
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<DomainEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Publish an event. Dropped silently when no consumer is running.
+    pub fn publish(&self, event: DomainEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<DomainEvent> {
+        self.sender.subscribe()
+    }
+}
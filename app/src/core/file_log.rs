@@ -0,0 +1,221 @@
+//! Optional log sink for installs without a log aggregator: every event is also
+//! written as a JSON line to a file in `[log] dir`. A new file starts every day
+//! (UTC) or when the current one reaches `max_file_mb`, and only the newest
+//! `retention_files` are kept.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    sync::Mutex,
+};
+
+use chrono::{NaiveDate, SecondsFormat, Utc};
+use serde_json::{Map, Value};
+use tracing::{
+    field::{Field, Visit},
+    Event, Subscriber,
+};
+use tracing_subscriber::{layer::Context, Layer};
+
+use crate::config::config::LogConfig;
+
+const FILE_PREFIX: &str = "slacker.";
+const FILE_SUFFIX: &str = ".jsonl";
+
+pub struct FileLogLayer {
+    file: Mutex<LogFile>,
+}
+
+impl FileLogLayer {
+    /// None when no log directory is configured
+    pub fn new(config: &LogConfig) -> io::Result<Option<Self>> {
+        let Some(dir) = &config.dir else {
+            return Ok(None);
+        };
+        fs::create_dir_all(dir)?;
+        Ok(Some(Self {
+            file: Mutex::new(LogFile::new(
+                PathBuf::from(dir),
+                config.max_file_mb.saturating_mul(1024 * 1024),
+                config.retention_files,
+            )),
+        }))
+    }
+}
+
+impl<S: Subscriber> Layer<S> for FileLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut line = Map::new();
+        line.insert(
+            "timestamp".to_string(),
+            Utc::now()
+                .to_rfc3339_opts(SecondsFormat::Millis, true)
+                .into(),
+        );
+        line.insert("level".to_string(), metadata.level().as_str().into());
+        line.insert("target".to_string(), metadata.target().into());
+        event.record(&mut JsonFields(&mut line));
+
+        let mut bytes = Value::Object(line).to_string().into_bytes();
+        bytes.push(b'\n');
+        if let Ok(mut file) = self.file.lock() {
+            // Logging about a broken log file would come back here
+            if let Err(e) = file.write(&bytes) {
+                eprintln!("Failed to write log file: {}", e);
+            }
+        }
+    }
+}
+
+/// Fields of an event, the formatted message under `message`
+struct JsonFields<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonFields<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value).into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+}
+
+struct LogFile {
+    dir: PathBuf,
+    max_bytes: u64,
+    retention_files: usize,
+    file: Option<File>,
+    date: NaiveDate,
+    /// Files the day's log was split into so far, besides the first
+    part: u32,
+    written: u64,
+}
+
+impl LogFile {
+    fn new(dir: PathBuf, max_bytes: u64, retention_files: usize) -> Self {
+        Self {
+            dir,
+            max_bytes,
+            retention_files,
+            file: None,
+            date: NaiveDate::MIN,
+            part: 0,
+            written: 0,
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let today = Utc::now().date_naive();
+        if self.file.is_none() || today != self.date {
+            self.open(today, 0)?;
+        } else if self.written > 0 && self.written + bytes.len() as u64 > self.max_bytes {
+            self.open(today, self.part + 1)?;
+        }
+
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(bytes)?;
+            self.written += bytes.len() as u64;
+        }
+        Ok(())
+    }
+
+    /// Append to the first file of the day from `part` on that has room, e.g. after
+    /// a restart, then drop the oldest files
+    fn open(&mut self, date: NaiveDate, mut part: u32) -> io::Result<()> {
+        loop {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.dir.join(file_name(date, part)))?;
+            let written = file.metadata()?.len();
+            if written < self.max_bytes {
+                self.file = Some(file);
+                self.date = date;
+                self.part = part;
+                self.written = written;
+                break;
+            }
+            part += 1;
+        }
+        self.prune()
+    }
+
+    fn prune(&self) -> io::Result<()> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            if let Some(position) = parse_file_name(&entry.file_name().to_string_lossy()) {
+                files.push((position, entry.path()));
+            }
+        }
+        // Newest first, so the open file is always kept
+        files.sort_by(|a, b| b.0.cmp(&a.0));
+        for (_, path) in files.into_iter().skip(self.retention_files.max(1)) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// `slacker.2026-03-16.jsonl`, then `slacker.2026-03-16.1.jsonl` and so on
+fn file_name(date: NaiveDate, part: u32) -> String {
+    match part {
+        0 => format!("{}{}{}", FILE_PREFIX, date, FILE_SUFFIX),
+        part => format!("{}{}.{}{}", FILE_PREFIX, date, part, FILE_SUFFIX),
+    }
+}
+
+/// Date and part of a log file, None for other files
+fn parse_file_name(name: &str) -> Option<(NaiveDate, u32)> {
+    let stem = name.strip_prefix(FILE_PREFIX)?.strip_suffix(FILE_SUFFIX)?;
+    let (date, part) = match stem.split_once('.') {
+        Some((date, part)) => (date, part.parse().ok()?),
+        None => (stem, 0),
+    };
+    Some((date.parse().ok()?, part))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_large_days_and_keeps_newest_files() {
+        let dir = std::env::temp_dir().join(format!("slacker-log-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut log = LogFile::new(dir.clone(), 10, 2);
+
+        for _ in 0..4 {
+            log.write(b"0123456789\n").unwrap();
+        }
+
+        let today = Utc::now().date_naive();
+        let mut names: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort_by_key(|name| parse_file_name(name));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(names, vec![file_name(today, 2), file_name(today, 3)]);
+    }
+}
@@ -0,0 +1,217 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use reqwest::{Client, StatusCode};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::{
+    core::state::AppState,
+    integrations::{jira, linear},
+    models::job::JobStatus,
+    repos::{
+        hook_subscriptions::HookSubscriptionsRepo, jobs::JobsRepo, workspaces::WorkspacesRepo,
+    },
+    services::{hooks, workspace::get_cached_workspace_tokens},
+    sockets::slack_bot::InitialSyncer,
+};
+
+/// How many jobs run concurrently, e.g. an initial sync next to webhook deliveries
+const WORKER_COUNT: usize = 4;
+
+/// How long an idle worker waits before looking for new jobs
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A running job not finished after this long is assumed lost with its worker
+const LOCK_TIMEOUT_MINUTES: i64 = 30;
+
+/// Attempts before a job is dead-lettered
+const MAX_ATTEMPTS: i32 = 5;
+
+/// Work that must survive a crash, stored in the `jobs` table until done
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Job {
+    /// Import reacted messages from Slack history, for one member or everyone linked
+    InitialSync {
+        workspace_id: String,
+        slack_member_id: Option<String>,
+    },
+    /// POST a JSON body to a webhook URL
+    WebhookDelivery {
+        url: String,
+        body: serde_json::Value,
+    },
+    /// POST a task event to a REST hook; a 410 Gone answer unsubscribes it
+    HookDelivery {
+        subscription_id: String,
+        url: String,
+        body: serde_json::Value,
+    },
+    /// Move the Jira issue of a completed task to done
+    JiraTransition { task_id: String },
+    /// Create a Linear issue for a new task
+    LinearIssue { task_id: String },
+}
+
+impl Job {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::InitialSync { .. } => "initial_sync",
+            Self::WebhookDelivery { .. } => "webhook_delivery",
+            Self::HookDelivery { .. } => "hook_delivery",
+            Self::JiraTransition { .. } => "jira_transition",
+            Self::LinearIssue { .. } => "linear_issue",
+        }
+    }
+}
+
+/// Queue a job for the workers
+pub async fn enqueue(db: &DatabaseConnection, job: Job) -> Result<()> {
+    let kind = job.kind().to_string();
+    let payload = serde_json::to_value(&job)?;
+    let job = JobsRepo::new(db.clone())
+        .create(kind, payload, MAX_ATTEMPTS)
+        .await?;
+
+    info!("[Jobs] Queued {} job {}", job.kind, job.id);
+    Ok(())
+}
+
+/// Run the job workers until shutdown
+pub async fn run_workers(state: Arc<AppState>, shutdown_token: CancellationToken) {
+    let workers: Vec<_> = (0..WORKER_COUNT)
+        .map(|_| tokio::spawn(run_worker(state.clone(), shutdown_token.clone())))
+        .collect();
+
+    for worker in workers {
+        let _ = worker.await;
+    }
+}
+
+async fn run_worker(state: Arc<AppState>, shutdown_token: CancellationToken) {
+    let repo = JobsRepo::new(state.database.clone());
+    let http_client = Client::new();
+
+    while !shutdown_token.is_cancelled() {
+        // Jobs stay queued during maintenance
+        if state.maintenance.is_enabled() {
+            tokio::select! {
+                _ = shutdown_token.cancelled() => break,
+                _ = tokio::time::sleep(POLL_INTERVAL) => continue,
+            }
+        }
+
+        let stale_before =
+            chrono::Utc::now().naive_utc() - chrono::Duration::minutes(LOCK_TIMEOUT_MINUTES);
+
+        let job = match repo.claim_next(stale_before).await {
+            Ok(Some(job)) => job,
+            Ok(None) => {
+                tokio::select! {
+                    _ = shutdown_token.cancelled() => break,
+                    _ = tokio::time::sleep(POLL_INTERVAL) => continue,
+                }
+            }
+            Err(e) => {
+                error!("[Jobs] Failed to claim a job: {}", e);
+                tokio::time::sleep(POLL_INTERVAL).await;
+                continue;
+            }
+        };
+
+        let result = match serde_json::from_value::<Job>(job.payload.clone()) {
+            Ok(payload) => execute(&state, &http_client, payload).await,
+            Err(e) => Err(anyhow::anyhow!("Invalid job payload: {}", e)),
+        };
+
+        let outcome = match result {
+            Ok(()) => repo.complete(job).await,
+            Err(e) => {
+                // 30s, 1m, 2m, ... capped at an hour
+                let backoff = 30 * 2i64.pow(job.attempts.clamp(1, 8) as u32 - 1);
+                let retry_at =
+                    chrono::Utc::now().naive_utc() + chrono::Duration::seconds(backoff.min(3600));
+                warn!(
+                    "[Jobs] {} job {} failed (attempt {}/{}): {}",
+                    job.kind, job.id, job.attempts, job.max_attempts, e
+                );
+                repo.fail(job, e.to_string(), retry_at).await
+            }
+        };
+
+        match outcome {
+            Ok(job) if job.status == JobStatus::Dead => {
+                error!("[Jobs] {} job {} moved to dead letters", job.kind, job.id)
+            }
+            Ok(_) => {}
+            Err(e) => error!("[Jobs] Failed to record job result: {}", e),
+        }
+    }
+}
+
+async fn execute(state: &AppState, http_client: &Client, job: Job) -> Result<()> {
+    match job {
+        Job::InitialSync {
+            workspace_id,
+            slack_member_id,
+        } => {
+            let workspace = WorkspacesRepo::new(state.database.clone())
+                .get(&workspace_id)
+                .await?;
+            let tokens = get_cached_workspace_tokens(
+                &state.cache,
+                &state.database,
+                &workspace.id,
+                &state.config.encryption_key,
+            )
+            .await?;
+
+            let syncer = InitialSyncer::new(&workspace, tokens.bot_token, state);
+
+            match slack_member_id {
+                Some(member_id) => syncer.perform_initial_sync(&member_id).await,
+                None => syncer.perform_initial_sync_for_all_users().await,
+            }
+        }
+        Job::WebhookDelivery { url, body } => {
+            http_client
+                .post(&url)
+                .json(&body)
+                .timeout(Duration::from_secs(10))
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        }
+        Job::HookDelivery {
+            subscription_id,
+            url,
+            body,
+        } => {
+            let repo = HookSubscriptionsRepo::new(state.database.clone());
+            // Nothing for hooks unsubscribed meanwhile; the secret is read now, so
+            // rotating it also covers deliveries already queued
+            let Some(subscription) = repo.get(&subscription_id).await? else {
+                return Ok(());
+            };
+            let secret = hooks::signing_secret(&subscription, &state.config.encryption_key)?;
+
+            let response = hooks::deliver(http_client, &url, &body, secret.as_deref()).await?;
+            if response.status() == StatusCode::GONE {
+                info!(
+                    "[Hooks] {} is gone, unsubscribing hook {}",
+                    url, subscription_id
+                );
+                repo.delete_by_id(&subscription_id).await?;
+                return Ok(());
+            }
+            response.error_for_status()?;
+            Ok(())
+        }
+        Job::JiraTransition { task_id } => jira::transition_task_issue(state, &task_id).await,
+        Job::LinearIssue { task_id } => linear::push_task(state, &task_id).await,
+    }
+}
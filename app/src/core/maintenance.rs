@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::watch;
+use utoipa::ToSchema;
+
+/// Shown to clients whose writes are refused while no message was given
+pub const DEFAULT_MAINTENANCE_MESSAGE: &str =
+    "Slacker is under maintenance, changes are paused. Please try again in a few minutes.";
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Maintenance {
+    pub message: String,
+    pub since: DateTime<Utc>,
+    /// Email of the admin who turned maintenance on
+    pub enabled_by: String,
+}
+
+/// Maintenance mode of this instance. While it is on, mutating API requests are
+/// refused and bots queue Slack events instead of persisting tasks.
+#[derive(Debug, Clone)]
+pub struct MaintenanceMode {
+    sender: Arc<watch::Sender<Option<Maintenance>>>,
+}
+
+impl Default for MaintenanceMode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MaintenanceMode {
+    pub fn new() -> Self {
+        Self {
+            sender: Arc::new(watch::Sender::new(None)),
+        }
+    }
+
+    pub fn enable(&self, message: Option<String>, enabled_by: &str) -> Maintenance {
+        let maintenance = Maintenance {
+            message: message
+                .map(|m| m.trim().to_string())
+                .filter(|m| !m.is_empty())
+                .unwrap_or_else(|| DEFAULT_MAINTENANCE_MESSAGE.to_string()),
+            since: Utc::now(),
+            enabled_by: enabled_by.to_string(),
+        };
+        self.sender.send_replace(Some(maintenance.clone()));
+        maintenance
+    }
+
+    /// Turn maintenance off, returns false if it was not on
+    pub fn disable(&self) -> bool {
+        self.sender.send_replace(None).is_some()
+    }
+
+    pub fn current(&self) -> Option<Maintenance> {
+        self.sender.borrow().clone()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.sender.borrow().is_some()
+    }
+
+    /// Notified whenever maintenance is turned on or off
+    pub fn subscribe(&self) -> watch::Receiver<Option<Maintenance>> {
+        self.sender.subscribe()
+    }
+}
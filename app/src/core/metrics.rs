@@ -0,0 +1,155 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Latencies kept per route for its p99; older ones leave the window early when a
+/// route is this busy
+const MAX_SAMPLES: usize = 1000;
+
+/// A p99 over fewer requests says little, so no alert is raised below this
+const MIN_ALERT_SAMPLES: usize = 20;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RouteMetrics {
+    /// Method and route pattern, e.g. `GET /api/tasks/:task_id`
+    pub route: String,
+    /// Requests since the instance started
+    pub requests: u64,
+    /// Requests slower than `latency_budget_ms` since the instance started
+    pub over_budget: u64,
+    /// 99th percentile latency within `latency_window_secs`
+    pub p99_ms: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct RouteLatency {
+    requests: u64,
+    over_budget: u64,
+    /// When requests finished and how long they took, oldest first
+    recent: VecDeque<(Instant, Duration)>,
+    alerted_at: Option<Instant>,
+}
+
+impl RouteLatency {
+    fn forget_before(&mut self, cutoff: Instant) {
+        while self.recent.front().is_some_and(|(at, _)| *at < cutoff) {
+            self.recent.pop_front();
+        }
+    }
+
+    fn p99(&self) -> Option<Duration> {
+        p99(self.recent.iter().map(|(_, elapsed)| *elapsed))
+    }
+}
+
+/// Request counters and latencies per route of this instance, in memory
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    routes: Arc<Mutex<HashMap<String, RouteLatency>>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a finished request. Returns the route's p99 latency when it is over
+    /// `budget` within `window` and the route was not alerted about during the
+    /// last `window`.
+    pub fn record(
+        &self,
+        route: &str,
+        elapsed: Duration,
+        budget: Duration,
+        window: Duration,
+    ) -> Option<Duration> {
+        let now = Instant::now();
+        let mut routes = self.routes.lock().unwrap();
+        let latency = routes.entry(route.to_string()).or_default();
+
+        latency.requests += 1;
+        if elapsed > budget {
+            latency.over_budget += 1;
+        }
+        latency.recent.push_back((now, elapsed));
+        if latency.recent.len() > MAX_SAMPLES {
+            latency.recent.pop_front();
+        }
+        if let Some(cutoff) = now.checked_sub(window) {
+            latency.forget_before(cutoff);
+        }
+
+        if latency.recent.len() < MIN_ALERT_SAMPLES
+            || latency
+                .alerted_at
+                .is_some_and(|at| now.duration_since(at) < window)
+        {
+            return None;
+        }
+        let p99 = latency.p99().filter(|p99| *p99 > budget)?;
+        latency.alerted_at = Some(now);
+        Some(p99)
+    }
+
+    /// Every route that was requested, busiest first
+    pub fn routes(&self, window: Duration) -> Vec<RouteMetrics> {
+        let now = Instant::now();
+        let mut routes = self.routes.lock().unwrap();
+        let mut metrics: Vec<RouteMetrics> = routes
+            .iter_mut()
+            .map(|(route, latency)| {
+                if let Some(cutoff) = now.checked_sub(window) {
+                    latency.forget_before(cutoff);
+                }
+                RouteMetrics {
+                    route: route.clone(),
+                    requests: latency.requests,
+                    over_budget: latency.over_budget,
+                    p99_ms: latency.p99().map(|p99| p99.as_millis() as u64),
+                }
+            })
+            .collect();
+        metrics.sort_by(|a, b| b.requests.cmp(&a.requests).then(a.route.cmp(&b.route)));
+        metrics
+    }
+}
+
+/// Nearest-rank 99th percentile
+fn p99(durations: impl Iterator<Item = Duration>) -> Option<Duration> {
+    let mut durations: Vec<Duration> = durations.collect();
+    if durations.is_empty() {
+        return None;
+    }
+    durations.sort();
+    let rank = (durations.len() * 99).div_ceil(100);
+    Some(durations[rank - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alerts_once_per_window_when_p99_is_over_budget() {
+        let metrics = Metrics::new();
+        let budget = Duration::from_millis(500);
+        let window = Duration::from_secs(300);
+
+        let mut alerts = Vec::new();
+        for _ in 0..MIN_ALERT_SAMPLES * 2 {
+            alerts.push(metrics.record("GET /api/tasks", Duration::from_secs(1), budget, window));
+        }
+
+        let raised: Vec<_> = alerts.into_iter().flatten().collect();
+        assert_eq!(raised, vec![Duration::from_secs(1)]);
+        assert_eq!(
+            metrics.routes(window)[0].over_budget,
+            MIN_ALERT_SAMPLES as u64 * 2
+        );
+    }
+}
@@ -1,3 +1,13 @@
+pub mod board_events;
 pub mod bot_status;
+pub mod cache;
+pub mod events;
+pub mod file_log;
+pub mod jobs;
+pub mod maintenance;
+pub mod metrics;
+pub mod reload;
+pub mod scheduler;
 pub mod server;
+pub mod shutdown;
 pub mod state;
@@ -0,0 +1,71 @@
+use std::{sync::Arc, time::SystemTime};
+
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::core::state::{AppState, WORKSPACES_FILE};
+
+/// Bots a reload started and stopped, by workspace name
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ReloadReport {
+    pub imported: usize,
+    pub started: Vec<String>,
+    pub stopped: Vec<String>,
+}
+
+impl ReloadReport {
+    pub fn is_empty(&self) -> bool {
+        self.imported == 0 && self.started.is_empty() && self.stopped.is_empty()
+    }
+}
+
+fn modified_at(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Keeps running bots in line with the configured workspaces until shutdown.
+/// workspaces.yaml is imported again whenever it changes, and every tick bots are
+/// started for workspaces added to the database and stopped for removed ones, and
+/// bot leases are renewed.
+pub async fn run_config_watcher(state: Arc<AppState>, shutdown_token: CancellationToken) {
+    // Bot leases are renewed here, so never wait longer than a third of their TTL
+    let interval = state
+        .config
+        .workspace_reload_interval_secs
+        .clamp(1, (state.config.lease_ttl_secs / 3).max(1));
+    let interval = std::time::Duration::from_secs(interval);
+    let mut last_modified = modified_at(WORKSPACES_FILE);
+
+    info!("[Reload] Watching workspaces every {:?}", interval);
+    loop {
+        tokio::select! {
+            _ = shutdown_token.cancelled() => break,
+            _ = tokio::time::sleep(interval) => {}
+        }
+
+        let modified = modified_at(WORKSPACES_FILE);
+        let mut report = ReloadReport::default();
+        if modified != last_modified {
+            last_modified = modified;
+            report.imported = state.import_configured_workspaces().await;
+        }
+
+        match state.sync_bots().await {
+            Ok((started, stopped)) => {
+                report.started = started;
+                report.stopped = stopped;
+            }
+            Err(e) => error!("[Reload] Failed to load workspaces: {}", e),
+        }
+
+        if !report.is_empty() {
+            info!(
+                "[Reload] Imported {}, started {:?}, stopped {:?}",
+                report.imported, report.started, report.stopped
+            );
+        }
+    }
+    info!("[Reload] Stopped");
+}
@@ -0,0 +1,195 @@
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use cron::Schedule;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+use crate::{
+    core::state::AppState,
+    integrations::notion,
+    models::scheduled_job::{Model as ScheduledJob, ScheduledJobKind},
+    repos::{leases::LeasesRepo, scheduled_jobs::ScheduledJobsRepo},
+    services::{
+        archive::archive_old_history,
+        deprovision::deprovision_deactivated_members,
+        digest::{send_daily_digests, send_weekly_summaries},
+        reminders::send_due_reminders,
+        retention::purge_expired_data,
+        sla, standups,
+    },
+    utils::time::parse_timezone,
+};
+
+/// How often the scheduler looks for due jobs
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Only the instance holding this lease runs scheduled jobs
+const SCHEDULER_LEASE: &str = "scheduler";
+
+/// Built-in jobs created on startup. Digests fire hourly and each run only reaches the
+/// people for whom it is the delivery hour in their own timezone. Reminders are
+/// checked every minute, SLA policies every five.
+const DEFAULT_JOBS: [(&str, ScheduledJobKind, &str); 8] = [
+    ("daily_digest", ScheduledJobKind::DailyDigest, "0 0 * * * *"),
+    (
+        "weekly_summary",
+        ScheduledJobKind::WeeklySummary,
+        "0 0 * * * *",
+    ),
+    (
+        "retention_purge",
+        ScheduledJobKind::RetentionPurge,
+        "0 30 3 * * *",
+    ),
+    (
+        "notion_export",
+        ScheduledJobKind::NotionExport,
+        "0 15 * * * *",
+    ),
+    (
+        "deactivated_members",
+        ScheduledJobKind::DeactivatedMembers,
+        "0 0 4 * * *",
+    ),
+    ("reminders", ScheduledJobKind::Reminders, "0 * * * * *"),
+    (
+        "archive_history",
+        ScheduledJobKind::ArchiveHistory,
+        "0 0 5 * * *",
+    ),
+    (
+        "sla_breaches",
+        ScheduledJobKind::SlaBreaches,
+        "0 */5 * * * *",
+    ),
+];
+
+/// Next time (UTC) a cron expression fires after `after`, evaluated in `timezone`
+pub fn next_run_after(
+    cron: &str,
+    timezone: &str,
+    after: NaiveDateTime,
+) -> Result<Option<NaiveDateTime>> {
+    let schedule = Schedule::from_str(cron)?;
+    let tz = parse_timezone(timezone)
+        .ok_or_else(|| anyhow::anyhow!("Unknown timezone: {}", timezone))?;
+
+    Ok(schedule
+        .after(&after.and_utc().with_timezone(&tz))
+        .next()
+        .map(|at| at.naive_utc()))
+}
+
+/// Run a job now. Manual runs reach every subscriber regardless of their local time.
+/// Returns how many notifications or reminders were sent, rows purged or archived,
+/// tasks exported, members deprovisioned, standup answers posted or SLA breaches found.
+pub async fn run_job(state: &AppState, job: &ScheduledJob, manual: bool) -> Result<usize> {
+    match job.kind {
+        ScheduledJobKind::DailyDigest => send_daily_digests(state, !manual).await,
+        ScheduledJobKind::WeeklySummary => send_weekly_summaries(state, !manual).await,
+        ScheduledJobKind::RetentionPurge => {
+            let purged = purge_expired_data(state).await?;
+            // Purged tasks leave boards without an event
+            state.board_events.touch();
+            Ok(purged)
+        }
+        ScheduledJobKind::NotionExport => notion::export_all(state).await,
+        ScheduledJobKind::DeactivatedMembers => deprovision_deactivated_members(state).await,
+        ScheduledJobKind::Reminders => send_due_reminders(state).await,
+        ScheduledJobKind::ArchiveHistory => archive_old_history(state).await,
+        ScheduledJobKind::Standup => standups::send_questions(state, job_workspace(job)?).await,
+        ScheduledJobKind::StandupSummary => {
+            standups::post_summary(state, job_workspace(job)?).await
+        }
+        ScheduledJobKind::SlaBreaches => sla::check_breaches(state).await,
+    }
+}
+
+fn job_workspace(job: &ScheduledJob) -> Result<&str> {
+    job.workspace_id
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("Job {} has no workspace", job.name))
+}
+
+/// Runs due jobs until shutdown. Schedules live in `scheduled_jobs`, so a run missed
+/// while the process was down happens once on the next start.
+pub async fn run_scheduler(state: Arc<AppState>, shutdown_token: CancellationToken) {
+    let repo = ScheduledJobsRepo::new(state.database.clone());
+    let leases_repo = LeasesRepo::new(state.database.clone());
+    for (name, kind, cron) in DEFAULT_JOBS {
+        if let Err(e) = repo.create_if_missing(name, kind, cron, "UTC").await {
+            error!("[Scheduler] Failed to create job {}: {}", name, e);
+        }
+    }
+
+    info!("[Scheduler] Started");
+    loop {
+        tokio::select! {
+            _ = shutdown_token.cancelled() => break,
+            _ = tokio::time::sleep(TICK_INTERVAL) => {}
+        }
+
+        // Due jobs run on the first tick after maintenance
+        if state.maintenance.is_enabled() {
+            continue;
+        }
+
+        // Another instance runs the jobs while it renews the lease each tick
+        match leases_repo
+            .acquire(SCHEDULER_LEASE, &state.instance_id, state.lease_ttl())
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) => {
+                error!("[Scheduler] Failed to acquire lease: {}", e);
+                continue;
+            }
+        }
+
+        if let Err(e) = run_due_jobs(&state, &repo).await {
+            error!("[Scheduler] Failed to load due jobs: {}", e);
+        }
+    }
+    info!("[Scheduler] Stopped");
+}
+
+async fn run_due_jobs(state: &AppState, repo: &ScheduledJobsRepo) -> Result<()> {
+    let now = chrono::Utc::now().naive_utc();
+
+    for job in repo.get_due(now).await? {
+        let next_run_at = match next_run_after(&job.cron, &job.timezone, now) {
+            Ok(next) => next,
+            Err(e) => {
+                error!(
+                    "[Scheduler] Job {} has an invalid schedule: {}",
+                    job.name, e
+                );
+                continue;
+            }
+        };
+
+        // First time this job is seen: only schedule it
+        if job.next_run_at.is_none() {
+            repo.set_next_run(job, next_run_at).await?;
+            continue;
+        }
+
+        let error = match run_job(state, &job, false).await {
+            Ok(count) => {
+                info!("[Scheduler] Job {} processed {} item(s)", job.name, count);
+                None
+            }
+            Err(e) => {
+                error!("[Scheduler] Job {} failed: {}", job.name, e);
+                Some(e.to_string())
+            }
+        };
+
+        repo.record_run(job, now, next_run_at, error).await?;
+    }
+
+    Ok(())
+}
@@ -1,35 +1,71 @@
 use anyhow::Result;
-use sea_orm::DatabaseConnection;
 use std::sync::Arc;
 
 use axum::Router;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
 use crate::{
     config::config::Config,
-    core::{bot_status::BotStatusManager, state::AppState},
+    core::{
+        board_events::BoardEventsManager, bot_status::BotStatusManager, cache::Cache,
+        events::EventBus, maintenance::MaintenanceMode, metrics::Metrics, state::AppState,
+    },
     database::{
-        connect::{connect_database, run_migrations},
+        connect::{connect_database, connect_read_database, run_migrations},
         // seed::seed_default_user,
     },
+    repos::{messages::MessageEncryption, stores::Stores},
     routes::create_routers,
+    services::{mailer::Mailer, notifications::Notifications, task_keys},
+    utils::crypto::generate_uuid,
 };
 
-pub async fn create_server(
-    config: Config,
-) -> Result<(Router<()>, DatabaseConnection, BotStatusManager)> {
+pub async fn create_server(config: Config) -> Result<(Router<()>, Arc<AppState>)> {
+    let state = create_state(config).await?;
+    let app = create_routers(state.clone());
+
+    Ok((app, state))
+}
+
+/// Connect, migrate and build the shared state without starting bots or background tasks
+pub async fn create_state(config: Config) -> Result<Arc<AppState>> {
     let db_conn = connect_database(config.clone()).await?;
     run_migrations(&db_conn).await?;
+    // Tasks created before keys existed get theirs once
+    task_keys::assign_missing_keys(&db_conn).await?;
+    let read_db_conn = connect_read_database(&config, &db_conn).await?;
     // seed_default_user(&db_conn, &config).await?;
+    let mailer = Mailer::from_config(&config)?;
+    let cache = Cache::from_config(&config).await?;
+    let notifier = Notifications::new(
+        db_conn.clone(),
+        mailer.clone(),
+        cache.clone(),
+        config.encryption_key.clone(),
+    );
 
-    let bot_status = BotStatusManager::new();
-
-    let state = AppState {
-        database: db_conn.clone(),
+    let message_encryption =
+        MessageEncryption::new(&config.encryption_key, config.encrypt_messages);
+    let state = Arc::new(AppState {
+        message_encryption,
+        stores: Stores::new(db_conn.clone()),
+        read_stores: Stores::new(read_db_conn.clone()),
+        database: db_conn,
+        read_database: read_db_conn,
         config,
-        bot_status: bot_status.clone(),
-    };
-
-    let app = create_routers(Arc::new(state));
+        bot_status: BotStatusManager::new(),
+        board_events: BoardEventsManager::new(),
+        events: EventBus::new(),
+        shutdown_token: CancellationToken::new(),
+        bot_tasks: TaskTracker::new(),
+        running_bots: Default::default(),
+        instance_id: generate_uuid(),
+        mailer,
+        notifier,
+        cache,
+        maintenance: MaintenanceMode::new(),
+        metrics: Metrics::new(),
+    });
 
-    Ok((app, db_conn, bot_status))
+    Ok(state)
 }
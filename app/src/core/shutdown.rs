@@ -0,0 +1,27 @@
+use tokio::signal;
+use tracing::info;
+
+/// Resolves once the process receives Ctrl+C (SIGINT) or SIGTERM
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received Ctrl+C, shutting down gracefully..."),
+        _ = terminate => info!("Received SIGTERM, shutting down gracefully..."),
+    }
+}
@@ -1,36 +1,375 @@
-use sea_orm::DatabaseConnection;
-use tokio_util::sync::CancellationToken;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use sea_orm::{DatabaseConnection, DbErr};
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use tracing::{error, info};
 
 use crate::config::config::Config;
+use crate::models::{bot_incident::BotIncidentKind, workspace::Model as Workspace};
+use crate::repos::{
+    bot_incidents::BotIncidentsRepo, leases::LeasesRepo, messages::MessageEncryption,
+    stores::Stores, workspaces::WorkspacesRepo,
+};
+use crate::services::board_snapshots::run_snapshot_invalidator;
+use crate::services::event_consumers::{
+    run_activity_consumer, run_audit_consumer, run_notification_consumer, run_slack_status_consumer,
+};
+use crate::services::hooks::run_hook_dispatcher;
+use crate::services::mailer::Mailer;
+use crate::services::notifications::Notifications;
+use crate::services::workspace::{get_active_workspaces_with_tokens, import_workspaces_yaml};
 use crate::sockets::slack_bot::SlackBot;
 
-use super::bot_status::BotStatusManager;
+use super::{
+    board_events::BoardEventsManager,
+    bot_status::BotStatusManager,
+    cache::Cache,
+    events::{DomainEvent, EventBus},
+    jobs::run_workers,
+    maintenance::MaintenanceMode,
+    metrics::Metrics,
+    reload::{run_config_watcher, ReloadReport},
+    scheduler::run_scheduler,
+};
+
+/// Workspaces file imported on startup and whenever it changes
+pub const WORKSPACES_FILE: &str = "workspaces.yaml";
+
+/// Lease that lets one instance run the bot of a workspace
+fn bot_lease(workspace_id: &str) -> String {
+    format!("bot:{}", workspace_id)
+}
 
 #[derive(Clone, Debug)]
 pub struct AppState {
     pub database: DatabaseConnection,
+    /// Read replica for read-only queries, or the primary when none is configured.
+    /// Replicas lag behind, so never read from it to decide what to write.
+    pub read_database: DatabaseConnection,
+    /// Repositories on `database`, behind traits so tests can swap them for fakes
+    pub stores: Stores,
+    /// The same repositories on `read_database`
+    pub read_stores: Stores,
+    /// Key of message text at rest, for every `MessagesRepo`
+    pub message_encryption: MessageEncryption,
     pub config: Config,
     pub bot_status: BotStatusManager,
+    pub board_events: BoardEventsManager,
+    /// Domain events for consumers such as notifications and the audit log
+    pub events: EventBus,
+    /// Cancelled when the process is shutting down; every bot listens to a child of it
+    pub shutdown_token: CancellationToken,
+    /// Tracks running bots and their syncs, the scheduler and job workers so shutdown can wait for in-flight work to finish
+    pub bot_tasks: TaskTracker,
+    /// Cancellation token of the running bot for each workspace, keyed by workspace id
+    pub running_bots: Arc<Mutex<HashMap<String, CancellationToken>>>,
+    /// Identifies this process as the holder of leases when several instances share
+    /// the database
+    pub instance_id: String,
+    pub mailer: Mailer,
+    pub notifier: Notifications,
+    pub cache: Cache,
+    pub maintenance: MaintenanceMode,
+    pub metrics: Metrics,
 }
 
 impl AppState {
     /// Spawn a new SlackBot for a workspace in the background
-    pub fn spawn_bot(&self, workspace_name: String, app_token: String, bot_token: String) {
+    pub fn spawn_bot(&self, workspace: Workspace, app_token: String, bot_token: String) {
+        let bot = SlackBot::new(&workspace, app_token, bot_token, self);
+        let workspace_name = workspace.name;
         let db = self.database.clone();
         let bot_status = self.bot_status.clone();
+        let events = self.events.clone();
+        let token = self.shutdown_token.child_token();
 
-        tokio::spawn(async move {
-            let bot = SlackBot::new(workspace_name.clone(), app_token, bot_token, db, bot_status);
-            let token = CancellationToken::new();
+        // Replace any bot already running for this workspace
+        if let Some(previous) = self
+            .running_bots
+            .lock()
+            .unwrap()
+            .insert(workspace.id.clone(), token.clone())
+        {
+            previous.cancel();
+        }
 
-            info!(
-                "Dynamically starting SlackBot for workspace: {}",
-                workspace_name
-            );
-            if let Err(e) = bot.start(token).await {
+        self.bot_tasks.spawn(async move {
+            info!("Starting SlackBot for workspace: {}", workspace_name);
+            let result = bot.start(token.clone()).await;
+            // Mark this bot as no longer running, without touching its replacement
+            token.cancel();
+
+            if let Err(e) = result {
                 error!("SlackBot for workspace {} failed: {}", workspace_name, e);
+                bot_status
+                    .set_disconnected(&workspace_name, Some(e.to_string()))
+                    .await;
+                events.publish(DomainEvent::BotDisconnected {
+                    workspace_id: workspace.id.clone(),
+                    workspace_name: workspace_name.clone(),
+                    reason: Some(e.to_string()),
+                });
+                let incidents_repo = BotIncidentsRepo::new(db);
+                if let Err(e) = incidents_repo
+                    .create(&workspace.id, BotIncidentKind::Error, Some(e.to_string()))
+                    .await
+                {
+                    error!("Failed to record bot incident: {}", e);
+                }
             }
         });
     }
+
+    /// Take the workspace's bot lease from whichever instance has it and spawn the bot here
+    pub async fn start_bot(
+        &self,
+        workspace: Workspace,
+        app_token: String,
+        bot_token: String,
+    ) -> Result<(), DbErr> {
+        LeasesRepo::new(self.database.clone())
+            .take_over(
+                &bot_lease(&workspace.id),
+                &self.instance_id,
+                self.lease_ttl(),
+            )
+            .await?;
+        self.spawn_bot(workspace, app_token, bot_token);
+        Ok(())
+    }
+
+    pub fn lease_ttl(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.config.lease_ttl_secs as i64)
+    }
+
+    /// Give up every lease of this instance so others take over without waiting for
+    /// them to expire
+    pub async fn release_leases(&self) {
+        match LeasesRepo::new(self.database.clone())
+            .release_all(&self.instance_id)
+            .await
+        {
+            Ok(released) => info!("Released {} lease(s)", released),
+            Err(e) => error!("Failed to release leases: {}", e),
+        }
+    }
+
+    pub fn is_bot_running(&self, workspace_id: &str) -> bool {
+        self.running_bots
+            .lock()
+            .unwrap()
+            .get(workspace_id)
+            .is_some_and(|token| !token.is_cancelled())
+    }
+
+    /// Stop the running bot of a workspace, returns false if none was running
+    pub async fn stop_bot(&self, workspace: &Workspace) -> bool {
+        let token = self.running_bots.lock().unwrap().remove(&workspace.id);
+        self.bot_status.remove(&workspace.name).await;
+        self.release_bot_lease(&workspace.id).await;
+
+        match token {
+            Some(token) => {
+                info!("Stopping SlackBot for workspace: {}", workspace.name);
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    async fn release_bot_lease(&self, workspace_id: &str) {
+        if let Err(e) = LeasesRepo::new(self.database.clone())
+            .release(&bot_lease(workspace_id), &self.instance_id)
+            .await
+        {
+            error!("Failed to release bot lease of {}: {}", workspace_id, e);
+        }
+    }
+
+    /// Start the job scheduler in the background; it stops with the shutdown token
+    pub fn spawn_scheduler(self: &Arc<Self>) {
+        let state = self.clone();
+        let token = self.shutdown_token.child_token();
+        self.bot_tasks.spawn(run_scheduler(state, token));
+    }
+
+    /// Start the job queue workers in the background; they stop with the shutdown token
+    pub fn spawn_job_workers(self: &Arc<Self>) {
+        let state = self.clone();
+        let token = self.shutdown_token.child_token();
+        self.bot_tasks.spawn(run_workers(state, token));
+    }
+
+    /// Start queueing REST hook deliveries for task changes made on this instance; it
+    /// stops with the shutdown token
+    pub fn spawn_hook_dispatcher(self: &Arc<Self>) {
+        let state = self.clone();
+        let token = self.shutdown_token.child_token();
+        self.bot_tasks.spawn(run_hook_dispatcher(state, token));
+    }
+
+    /// Start dropping board snapshots of workspaces whose tasks change on this
+    /// instance; it stops with the shutdown token
+    pub fn spawn_snapshot_invalidator(self: &Arc<Self>) {
+        let state = self.clone();
+        let token = self.shutdown_token.child_token();
+        self.bot_tasks.spawn(run_snapshot_invalidator(state, token));
+    }
+
+    /// Start the consumers of domain events made on this instance; they stop with the
+    /// shutdown token
+    pub fn spawn_event_consumers(self: &Arc<Self>) {
+        let token = self.shutdown_token.child_token();
+        self.bot_tasks
+            .spawn(run_notification_consumer(self.clone(), token.clone()));
+        self.bot_tasks
+            .spawn(run_slack_status_consumer(self.clone(), token.clone()));
+        self.bot_tasks
+            .spawn(run_activity_consumer(self.clone(), token.clone()));
+        self.bot_tasks
+            .spawn(run_audit_consumer(self.clone(), token));
+    }
+
+    /// Start the workspace config watcher in the background; it stops with the shutdown token
+    pub fn spawn_config_watcher(self: &Arc<Self>) {
+        let state = self.clone();
+        let token = self.shutdown_token.child_token();
+        self.bot_tasks.spawn(run_config_watcher(state, token));
+    }
+
+    /// Import workspaces still only configured in workspaces.yaml, then spawn a bot
+    /// for every active workspace with stored tokens
+    pub async fn spawn_configured_bots(&self) {
+        if let Err(e) = self.reload_workspaces().await {
+            error!("Failed to load workspaces: {}", e);
+            error!("SlackBots will not start.");
+        }
+    }
+
+    /// Import workspaces.yaml, then start bots for new workspaces and stop those of
+    /// workspaces that were removed or archived
+    pub async fn reload_workspaces(&self) -> anyhow::Result<ReloadReport> {
+        let imported = self.import_configured_workspaces().await;
+        let (started, stopped) = self.sync_bots().await?;
+        info!(
+            "Reloaded workspaces: {} running, {} started, {} stopped",
+            self.running_bots.lock().unwrap().len(),
+            started.len(),
+            stopped.len()
+        );
+
+        Ok(ReloadReport {
+            imported,
+            started,
+            stopped,
+        })
+    }
+
+    /// Import workspaces still only configured in workspaces.yaml, returns how many
+    pub async fn import_configured_workspaces(&self) -> usize {
+        let encryption_key = &self.config.encryption_key;
+
+        match import_workspaces_yaml(&self.database, WORKSPACES_FILE, encryption_key).await {
+            Ok(0) => 0,
+            Ok(imported) => {
+                info!("Imported {} workspaces from {}", imported, WORKSPACES_FILE);
+                imported
+            }
+            Err(e) => {
+                error!("Failed to import {}: {}", WORKSPACES_FILE, e);
+                0
+            }
+        }
+    }
+
+    /// Start bots for active workspaces whose lease this instance holds or can take,
+    /// and stop bots of workspaces that are no longer active or whose lease another
+    /// instance took. Running bots renew their lease here, so this must run more
+    /// often than `lease_ttl_secs`. A bot that failed keeps its slot but not its
+    /// lease, so another instance may run it; here it only restarts on an explicit
+    /// reconnect. Returns the started and stopped workspace names.
+    pub async fn sync_bots(&self) -> anyhow::Result<(Vec<String>, Vec<String>)> {
+        let encryption_key = &self.config.encryption_key;
+        let workspaces = get_active_workspaces_with_tokens(&self.database, encryption_key).await?;
+        let leases_repo = LeasesRepo::new(self.database.clone());
+
+        let mut started = Vec::new();
+        let mut stopped = Vec::new();
+        for (workspace, tokens) in &workspaces {
+            let has_slot = self
+                .running_bots
+                .lock()
+                .unwrap()
+                .contains_key(&workspace.id);
+            if has_slot && !self.is_bot_running(&workspace.id) {
+                self.release_bot_lease(&workspace.id).await;
+                continue;
+            }
+
+            let holds_lease = leases_repo
+                .acquire(
+                    &bot_lease(&workspace.id),
+                    &self.instance_id,
+                    self.lease_ttl(),
+                )
+                .await?;
+
+            match (holds_lease, has_slot) {
+                (true, false) => {
+                    started.push(workspace.name.clone());
+                    self.spawn_bot(
+                        workspace.clone(),
+                        tokens.app_token.clone(),
+                        tokens.bot_token.clone(),
+                    );
+                }
+                (false, true) => {
+                    info!(
+                        "Another instance took over the bot of workspace {}",
+                        workspace.name
+                    );
+                    self.stop_bot(workspace).await;
+                    stopped.push(workspace.name.clone());
+                }
+                _ => {}
+            }
+        }
+
+        let removed: Vec<String> = self
+            .running_bots
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|id| !workspaces.iter().any(|(w, _)| &w.id == *id))
+            .cloned()
+            .collect();
+
+        if !removed.is_empty() {
+            // Archived workspaces are still found by id; deleted ones only have a token
+            let known = WorkspacesRepo::new(self.database.clone()).get_all().await?;
+            for id in removed {
+                match known.iter().find(|w| w.id == id) {
+                    Some(workspace) => {
+                        self.stop_bot(workspace).await;
+                        stopped.push(workspace.name.clone());
+                    }
+                    None => {
+                        let token = self.running_bots.lock().unwrap().remove(&id);
+                        if let Some(token) = token {
+                            info!("Stopping SlackBot for deleted workspace: {}", id);
+                            token.cancel();
+                        }
+                        self.release_bot_lease(&id).await;
+                        stopped.push(id);
+                    }
+                }
+            }
+        }
+
+        Ok((started, stopped))
+    }
 }
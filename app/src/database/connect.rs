@@ -1,7 +1,14 @@
 use migration::{Migrator, MigratorTrait};
-use sea_orm::{ConnectOptions, Database, DatabaseConnection, DbErr};
-use std::{env, time::Duration};
+use sea_orm::{ConnectOptions, Database, DatabaseBackend, DatabaseConnection, DbErr};
+use serde::Serialize;
+use std::{
+    backtrace::Backtrace,
+    env,
+    time::{Duration, Instant},
+};
 use thiserror::Error;
+use tracing::{info, warn};
+use utoipa::ToSchema;
 
 use crate::config::config::Config;
 
@@ -24,33 +31,173 @@ pub enum DatabaseError {
 }
 
 pub async fn connect_database(config: Config) -> Result<DatabaseConnection, DatabaseError> {
-    let database_url = config.database_url;
+    connect(&config.database_url, &config).await
+}
+
+/// Connect to the read replica when `database_read_url` is set, otherwise reuse
+/// the primary pool so callers never need to check
+pub async fn connect_read_database(
+    config: &Config,
+    primary: &DatabaseConnection,
+) -> Result<DatabaseConnection, DatabaseError> {
+    match config
+        .database_read_url
+        .as_deref()
+        .filter(|u| !u.is_empty())
+    {
+        Some(url) => {
+            info!("Routing read-only queries to the read replica");
+            connect(url, config).await
+        }
+        None => Ok(primary.clone()),
+    }
+}
 
-    if !database_url.starts_with("postgres://") {
+async fn connect(database_url: &str, config: &Config) -> Result<DatabaseConnection, DatabaseError> {
+    let is_sqlite = database_url.starts_with("sqlite:");
+    let is_postgres =
+        database_url.starts_with("postgres://") || database_url.starts_with("postgresql://");
+
+    if is_sqlite && !cfg!(feature = "sqlite") {
         return Err(DatabaseError::ConfigError(
-            "Invalid Database URL - It must start with postgres://".to_string(),
+            "SQLite support is not compiled in - rebuild with --features sqlite".to_string(),
         ));
     }
 
-    let max_connections: u32 = config.max_connections;
-    let min_connections: u32 = config.min_connections;
+    if !is_sqlite && !is_postgres {
+        return Err(DatabaseError::ConfigError(
+            "Invalid Database URL - It must start with postgres:// or sqlite:".to_string(),
+        ));
+    }
+
+    // SQLite allows a single writer, so one connection avoids "database is locked" errors
+    let (max_connections, min_connections) = if is_sqlite {
+        (1, 1)
+    } else {
+        (config.max_connections, config.min_connections)
+    };
 
-    let mut opt = ConnectOptions::new(&database_url);
+    let mut opt = ConnectOptions::new(database_url);
     opt.max_connections(max_connections)
         .min_connections(min_connections)
         .connect_timeout(Duration::from_secs(5))
+        .acquire_timeout(Duration::from_secs(config.acquire_timeout_secs))
         .idle_timeout(Duration::from_secs(300))
         .sqlx_logging(false);
+    if let Some(timeout) = config.statement_timeout_ms {
+        opt.map_sqlx_postgres_opts(move |options| {
+            options.options([("statement_timeout", timeout.to_string())])
+        });
+    }
 
-    let db = Database::connect(opt)
+    let mut db = Database::connect(opt)
         .await
         .map_err(|e| DatabaseError::ConnectionError(e))?;
 
+    if let Some(threshold) = config.slow_query_ms.map(Duration::from_millis) {
+        db.set_metric_callback(move |query| {
+            if query.elapsed < threshold {
+                return;
+            }
+            // Only slow queries pay for the backtrace
+            let caller = repo_method(&Backtrace::force_capture().to_string());
+            warn!(
+                elapsed_ms = query.elapsed.as_millis() as u64,
+                repo = caller.as_deref().unwrap_or("unknown"),
+                failed = query.failed,
+                "Slow query: {}",
+                query.statement.sql
+            );
+        });
+    }
+
     Ok(db)
 }
 
+/// Innermost repository method in a backtrace, e.g. `TasksRepo::get_page`
+fn repo_method(backtrace: &str) -> Option<String> {
+    backtrace.lines().find_map(|line| {
+        let path = line.trim().split_once(": ")?.1;
+        // Trait methods show as `<Type as Trait>::method`
+        let path = match path.strip_prefix('<').and_then(|p| p.split_once(" as ")) {
+            Some((ty, rest)) => format!("{}::{}", ty, rest.split_once(">::")?.1),
+            None => path.to_string(),
+        };
+        let method = path.strip_prefix("slacker::repos::")?;
+        let method = method.split("::{{closure}}").next()?;
+        // Drop the module, keep `Type::method`
+        let (_, method) = method.split_once("::")?;
+        Some(method.to_string())
+    })
+}
+
+/// Connections of a pool, as sqlx counts them
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PoolMetrics {
+    pub max_connections: u32,
+    /// Open connections, in use or idle
+    pub size: u32,
+    pub idle: u32,
+    pub in_use: u32,
+    /// How long taking a connection from the pool took just now
+    pub acquire_ms: Option<u64>,
+}
+
+/// Usage of a Postgres pool; None for SQLite, which uses one connection
+pub async fn pool_metrics(db: &DatabaseConnection, max_connections: u32) -> Option<PoolMetrics> {
+    if db.get_database_backend() != DatabaseBackend::Postgres {
+        return None;
+    }
+    let pool = db.get_postgres_connection_pool();
+    let size = pool.size();
+    let idle = pool.num_idle() as u32;
+
+    let started = Instant::now();
+    let acquire_ms = match pool.acquire().await {
+        Ok(_) => Some(started.elapsed().as_millis() as u64),
+        Err(e) => {
+            warn!("Failed to take a connection from the pool: {}", e);
+            None
+        }
+    };
+
+    Some(PoolMetrics {
+        max_connections,
+        size,
+        idle,
+        in_use: size.saturating_sub(idle),
+        acquire_ms,
+    })
+}
+
 pub async fn run_migrations(connection: &DatabaseConnection) -> Result<(), DatabaseError> {
     Migrator::up(connection, None).await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_repo_method_in_a_backtrace() {
+        let backtrace = "   0: std::backtrace::Backtrace::force_capture
+   1: slacker::database::connect::connect::{{closure}}
+   9: slacker::repos::tasks::TasksRepo::get_page::{{closure}}
+  10: slacker::handlers::tasks::get_tasks::{{closure}}";
+
+        assert_eq!(
+            repo_method(backtrace).as_deref(),
+            Some("TasksRepo::get_page")
+        );
+        assert_eq!(
+            repo_method(
+                "   4: <slacker::repos::tasks::TasksRepo as slacker::repos::stores::TasksStore>::get_assigned_page::{{closure}}"
+            )
+            .as_deref(),
+            Some("TasksRepo::get_assigned_page")
+        );
+        assert_eq!(repo_method("   0: main"), None);
+    }
+}
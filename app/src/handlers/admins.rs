@@ -1,15 +1,38 @@
 use std::sync::Arc;
 
-use axum::{extract::State, Json};
+use axum::{
+    extract::{Query, State},
+    Extension,
+};
 use serde::{Deserialize, Serialize};
 use tracing::{error, info};
+use utoipa::ToSchema;
+use validator::Validate;
 
 use crate::{
-    core::state::AppState, models::person::Model as Person,
-    repos::workspace_admins::WorkspaceAdminsRepo, utils::response::APIError,
+    core::{metrics::RouteMetrics, state::AppState},
+    database::connect::{pool_metrics, PoolMetrics},
+    middlewares::auth::Impersonation,
+    models::{
+        audit_log::Model as AuditLog, job::JobStatus, person::Model as Person, task::TaskStatus,
+        workspace_link::WorkspaceRole,
+    },
+    repos::{
+        audit_logs::AuditLogsRepo, bot_incidents::BotIncidentsRepo, changes::ChangesRepo,
+        jobs::JobsRepo, messages::MessagesRepo, persons::PersonsRepo, tasks::TasksRepo,
+        workspace_admins::WorkspaceAdminsRepo, workspace_links::WorkspaceLinksRepo,
+        workspaces::WorkspacesRepo,
+    },
+    services::email_templates::Email,
+    utils::{
+        json::{Json, ValidatedJson},
+        jwt::create_impersonation_jwt,
+        pagination::{CursorQuery, Pagination},
+        response::APIError,
+    },
 };
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AdminInfo {
     pub id: String,
     pub email: String,
@@ -18,20 +41,35 @@ pub struct AdminInfo {
     pub is_active: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AdminListResponse {
     pub admins: Vec<AdminInfo>,
     pub is_super_admin: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PermissionCheckResponse {
     pub can_configure_workspaces: bool,
     pub is_super_admin: bool,
     pub has_workspace_access: bool,
+    /// Role in the active workspace, None without one
+    pub role: Option<WorkspaceRole>,
+    /// False for viewers, whose task changes are refused, so their controls can
+    /// be hidden
+    pub can_edit_tasks: bool,
 }
 
 /// Check if the current user can configure workspaces
+#[utoipa::path(
+    get,
+    path = "/api/admins/permissions",
+    tag = "admins",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Permissions of the current user", body = PermissionCheckResponse),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
 pub async fn check_permissions(
     State(state): State<Arc<AppState>>,
     person: Person,
@@ -49,15 +87,33 @@ pub async fn check_permissions(
         .await
         .unwrap_or_default();
     let has_workspace_access = !user_links.is_empty() && user_links.iter().any(|l| l.is_linked);
+    let role = user_links
+        .iter()
+        .find(|l| l.is_active && l.is_linked)
+        .map(|l| l.role);
+    let can_configure_workspaces = is_super_admin || is_invited_admin;
 
     Ok(Json(PermissionCheckResponse {
-        can_configure_workspaces: is_super_admin || is_invited_admin,
+        can_configure_workspaces,
         is_super_admin,
         has_workspace_access,
+        role,
+        can_edit_tasks: can_configure_workspaces || role != Some(WorkspaceRole::Viewer),
     }))
 }
 
 /// List all admins (only accessible by super admin or existing admins)
+#[utoipa::path(
+    get,
+    path = "/api/admins",
+    tag = "admins",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "All workspace admins", body = AdminListResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires admin permission"),
+    )
+)]
 pub async fn list_admins(
     State(state): State<Arc<AppState>>,
     person: Person,
@@ -93,12 +149,13 @@ pub async fn list_admins(
     }))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct InviteAdminRequest {
+    #[validate(email)]
     pub email: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct InviteAdminResponse {
     pub success: bool,
     pub message: String,
@@ -106,10 +163,23 @@ pub struct InviteAdminResponse {
 }
 
 /// Invite a new admin (only super admin or existing admins can do this)
+#[utoipa::path(
+    post,
+    path = "/api/admins/invite",
+    tag = "admins",
+    request_body = InviteAdminRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Invite result", body = InviteAdminResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires admin permission"),
+        (status = 422, description = "Invalid email address"),
+    )
+)]
 pub async fn invite_admin(
     State(state): State<Arc<AppState>>,
     person: Person,
-    Json(payload): Json<InviteAdminRequest>,
+    ValidatedJson(payload): ValidatedJson<InviteAdminRequest>,
 ) -> Result<Json<InviteAdminResponse>, APIError> {
     let is_super_admin = person.email == state.config.admin_email;
 
@@ -187,12 +257,25 @@ pub async fn invite_admin(
     }))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct RevokeAdminRequest {
     pub email: String,
 }
 
 /// Revoke admin access (only super admin or the original inviter can do this)
+#[utoipa::path(
+    post,
+    path = "/api/admins/revoke",
+    tag = "admins",
+    request_body = RevokeAdminRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Admin revoked", body = InviteAdminResponse),
+        (status = 400, description = "Cannot revoke this admin"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires admin permission"),
+    )
+)]
 pub async fn revoke_admin(
     State(state): State<Arc<AppState>>,
     person: Person,
@@ -252,6 +335,52 @@ pub async fn revoke_admin(
     }))
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditLogListResponse {
+    /// Newest first
+    pub logs: Vec<AuditLog>,
+    /// Pass as `cursor` for the next page, None on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Page through the audit log - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/admins/audit-logs",
+    tag = "admins",
+    params(CursorQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Page of audit log entries", body = AuditLogListResponse),
+        (status = 400, description = "Invalid cursor"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires admin permission"),
+    )
+)]
+pub async fn list_audit_logs(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Query(query): Query<CursorQuery>,
+) -> Result<Json<AuditLogListResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+    let pagination = Pagination::from_query(&query)?;
+
+    let page = AuditLogsRepo::new(state.read_database.clone())
+        .get_page(&pagination)
+        .await
+        .map_err(|e| {
+            error!("Failed to get audit logs: {}", e);
+            APIError::InternalServerError("Failed to get audit logs".to_string())
+        })?;
+
+    Ok(Json(AuditLogListResponse {
+        logs: page.items,
+        next_cursor: page.next_cursor,
+    }))
+}
+
 /// Helper function to check if a person can configure workspaces
 pub async fn can_configure_workspaces(state: &AppState, email: &str) -> bool {
     if email == state.config.admin_email {
@@ -261,3 +390,344 @@ pub async fn can_configure_workspaces(state: &AppState, email: &str) -> bool {
     let admins_repo = WorkspaceAdminsRepo::new(state.database.clone());
     admins_repo.is_admin(email).await.unwrap_or(false)
 }
+
+/// Whether a person only views a workspace: linked to it as a viewer and not a
+/// workspace admin
+pub async fn is_read_only(state: &AppState, person: &Person, workspace_id: &str) -> bool {
+    let viewer = WorkspaceLinksRepo::new(state.database.clone())
+        .get_by_person_and_workspace(person.id.clone(), workspace_id.to_string())
+        .await
+        .is_ok_and(|link| link.is_linked && link.role == WorkspaceRole::Viewer);
+    viewer && !can_configure_workspaces(state, &person.email).await
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImpersonateRequest {
+    pub person_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImpersonateResponse {
+    /// Bearer token acting as `person`; responses to it carry `X-Impersonated-By`
+    pub token: String,
+    /// Unix timestamp after which the token is rejected
+    pub expires_at: i64,
+    pub person: Person,
+}
+
+/// Get a short-lived token to act as another person - REQUIRES SUPER ADMIN
+#[utoipa::path(
+    post,
+    path = "/api/admin/impersonate",
+    tag = "admins",
+    request_body = ImpersonateRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Impersonation token", body = ImpersonateResponse),
+        (status = 400, description = "Cannot impersonate this person"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires super admin"),
+        (status = 404, description = "Person not found"),
+    )
+)]
+pub async fn impersonate(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    impersonation: Option<Extension<Impersonation>>,
+    Json(payload): Json<ImpersonateRequest>,
+) -> Result<Json<ImpersonateResponse>, APIError> {
+    if impersonation.is_some() || person.email != state.config.admin_email {
+        return Err(APIError::Forbidden);
+    }
+
+    let target = PersonsRepo::new(state.database.clone())
+        .get_by_id(payload.person_id)
+        .await?;
+    if target.id == person.id || target.email == state.config.admin_email {
+        return Err(APIError::BadRequest(
+            "Cannot impersonate the super admin".to_string(),
+        ));
+    }
+
+    let (token, expires_at) = create_impersonation_jwt(
+        target.email.clone(),
+        target.id.clone(),
+        person.id.clone(),
+        &state.config.jwt.secret,
+        state.config.jwt.impersonation_expiry_minutes,
+    )
+    .map_err(|e| {
+        error!("Failed to create impersonation token: {}", e);
+        APIError::InternalServerError("Failed to create impersonation token".to_string())
+    })?;
+
+    AuditLogsRepo::new(state.database.clone())
+        .record(
+            Some(&person.id),
+            "impersonation.started",
+            Some(&target.id),
+            None,
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to audit impersonation: {}", e);
+            APIError::InternalServerError("Failed to create impersonation token".to_string())
+        })?;
+
+    info!("{} is impersonating {}", person.email, target.email);
+
+    Ok(Json(ImpersonateResponse {
+        token,
+        expires_at,
+        person: target,
+    }))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TestEmailResponse {
+    pub sent_to: String,
+}
+
+/// Send a test email to yourself to check the SMTP settings - REQUIRES ADMIN
+/// PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/admin/test-email",
+    tag = "admins",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The SMTP server took the email", body = TestEmailResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 503, description = "SMTP is not configured or the server refused the email"),
+    )
+)]
+pub async fn send_test_email(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<TestEmailResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+    if !state.mailer.is_enabled() {
+        return Err(APIError::ServiceUnavailable(
+            "SMTP is not configured; set SMTP_HOST to send email".to_string(),
+        ));
+    }
+
+    state
+        .mailer
+        .send(&person.email, &Email::test(&person.email))
+        .await
+        .map_err(|e| {
+            error!("Failed to send test email to {}: {}", person.email, e);
+            APIError::ServiceUnavailable(format!("The test email could not be sent: {}", e))
+        })?;
+
+    info!("Sent a test email to {}", person.email);
+    Ok(Json(TestEmailResponse {
+        sent_to: person.email,
+    }))
+}
+
+/// How many recent errors the overview lists
+const OVERVIEW_ERRORS: u64 = 10;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskStatusCounts {
+    pub blank: u64,
+    pub in_progress: u64,
+    pub blocked: u64,
+    pub completed: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ActivityCounts {
+    /// Slack messages and other task sources stored
+    pub messages: u64,
+    pub tasks_created: u64,
+    pub status_changes: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobQueueCounts {
+    /// Waiting to run, including retries
+    pub pending: u64,
+    pub running: u64,
+    /// Gave up after their last attempt
+    pub dead: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecentError {
+    /// `job:<kind>` or `bot:<workspace>`
+    pub source: String,
+    pub message: String,
+    pub occurred_at: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OverviewResponse {
+    pub workspaces: u64,
+    /// Bots connected to Slack from the instance that answered
+    pub bots_connected: u64,
+    /// People linked to at least one workspace
+    pub linked_users: u64,
+    pub tasks: TaskStatusCounts,
+    pub last_24h: ActivityCounts,
+    pub jobs: JobQueueCounts,
+    /// Failed jobs and bot errors, newest first
+    pub recent_errors: Vec<RecentError>,
+}
+
+/// Instance-wide numbers for the admin dashboard - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/admin/overview",
+    tag = "admins",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Instance overview", body = OverviewResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+    )
+)]
+pub async fn get_overview(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<OverviewResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    overview(&state).await.map(Json).map_err(|e| {
+        error!("Failed to build admin overview: {}", e);
+        APIError::InternalServerError("Failed to build admin overview".to_string())
+    })
+}
+
+async fn overview(state: &AppState) -> Result<OverviewResponse, sea_orm::DbErr> {
+    let db = state.read_database.clone();
+    let tasks_repo = TasksRepo::new(db.clone());
+    let jobs_repo = JobsRepo::new(db.clone());
+    let since = (chrono::Utc::now() - chrono::Duration::hours(24)).naive_utc();
+
+    let workspaces = WorkspacesRepo::new(db.clone()).get_all().await?;
+    let bots_connected = state
+        .bot_status
+        .get_all_statuses()
+        .await
+        .iter()
+        .filter(|status| status.is_connected)
+        .count() as u64;
+
+    let mut recent_errors: Vec<(chrono::NaiveDateTime, RecentError)> = Vec::new();
+    for job in jobs_repo.get_recently_failed(OVERVIEW_ERRORS).await? {
+        recent_errors.push((
+            job.updated_at,
+            RecentError {
+                source: format!("job:{}", job.kind),
+                message: job.last_error.unwrap_or_default(),
+                occurred_at: job.updated_at.and_utc().to_rfc3339(),
+            },
+        ));
+    }
+    for incident in BotIncidentsRepo::new(db.clone())
+        .get_recent_errors(OVERVIEW_ERRORS)
+        .await?
+    {
+        let workspace = workspaces
+            .iter()
+            .find(|workspace| workspace.id == incident.workspace_id)
+            .map_or(incident.workspace_id.as_str(), |workspace| {
+                workspace.name.as_str()
+            });
+        recent_errors.push((
+            incident.created_at,
+            RecentError {
+                source: format!("bot:{}", workspace),
+                message: incident.message.unwrap_or_default(),
+                occurred_at: incident.created_at.and_utc().to_rfc3339(),
+            },
+        ));
+    }
+    recent_errors.sort_by(|a, b| b.0.cmp(&a.0));
+    recent_errors.truncate(OVERVIEW_ERRORS as usize);
+
+    Ok(OverviewResponse {
+        workspaces: workspaces.len() as u64,
+        bots_connected,
+        linked_users: WorkspaceLinksRepo::new(db.clone())
+            .count_linked_people()
+            .await?,
+        tasks: TaskStatusCounts {
+            blank: tasks_repo.count_by_status(TaskStatus::Blank).await?,
+            in_progress: tasks_repo.count_by_status(TaskStatus::InProgress).await?,
+            blocked: tasks_repo.count_by_status(TaskStatus::Blocked).await?,
+            completed: tasks_repo.count_by_status(TaskStatus::Completed).await?,
+        },
+        last_24h: ActivityCounts {
+            messages: MessagesRepo::new(db.clone(), state.message_encryption.clone())
+                .count_since(since)
+                .await?,
+            tasks_created: tasks_repo.count_created_since(since).await?,
+            status_changes: ChangesRepo::new(db).count_since(since).await?,
+        },
+        jobs: JobQueueCounts {
+            pending: jobs_repo.count_by_status(JobStatus::Pending).await?,
+            running: jobs_repo.count_by_status(JobStatus::Running).await?,
+            dead: jobs_repo.count_by_status(JobStatus::Dead).await?,
+        },
+        recent_errors: recent_errors.into_iter().map(|(_, error)| error).collect(),
+    })
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MetricsResponse {
+    pub latency_budget_ms: u64,
+    pub latency_window_secs: u64,
+    /// Routes requested since this instance started, busiest first
+    pub routes: Vec<RouteMetrics>,
+    /// Connection pool of the primary database; None on SQLite
+    pub database: Option<PoolMetrics>,
+    /// Connection pool of the read replica, when one is configured
+    pub read_database: Option<PoolMetrics>,
+}
+
+/// Request counts and latency per route, and database pool usage, of the instance
+/// answering - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/admin/metrics",
+    tag = "admins",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Metrics of this instance", body = MetricsResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+    )
+)]
+pub async fn get_metrics(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<MetricsResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let window = std::time::Duration::from_secs(state.config.latency_window_secs);
+    let max_connections = state.config.max_connections;
+    let database = pool_metrics(&state.database, max_connections).await;
+    let read_database = match state.config.database_read_url.as_deref() {
+        Some(url) if !url.is_empty() => pool_metrics(&state.read_database, max_connections).await,
+        _ => None,
+    };
+
+    Ok(Json(MetricsResponse {
+        latency_budget_ms: state.config.latency_budget_ms,
+        latency_window_secs: state.config.latency_window_secs,
+        routes: state.metrics.routes(window),
+        database,
+        read_database,
+    }))
+}
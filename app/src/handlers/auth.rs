@@ -1,24 +1,58 @@
 use std::sync::Arc;
 
 use crate::{
-    config::workspaces::WorkspacesConfig,
-    core::state::AppState,
+    core::{events::DomainEvent, state::AppState},
     models::person::Model as Person,
-    repos::{persons::PersonsRepo, workspace_links::WorkspaceLinksRepo},
-    services::user::fetch_user_by_email_with_config,
-    utils::{jwt::create_jwt, response::APIError},
+    repos::{
+        persons::PersonsRepo, sessions::SessionsRepo, workspace_links::WorkspaceLinksRepo,
+        workspaces::WorkspacesRepo,
+    },
+    services::{
+        invitation::accept_pending_invitations, user::lookup_user_by_email,
+        workspace::get_active_workspaces_with_tokens,
+    },
+    utils::{crypto::generate_uuid, json::Json, jwt::create_session_jwt, response::APIError},
 };
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Redirect},
-    Json,
 };
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
 use reqwest::Client;
-use serde::Deserialize;
-use tracing::{error, info};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
 
-#[derive(Debug, Deserialize)]
+/// Longest user agent kept on a session, in characters
+const MAX_USER_AGENT_CHARS: usize = 255;
+
+/// Cookie binding a Slack sign-in to the browser that started it; holds the
+/// `state` and `nonce` sent to Slack as `state:nonce`
+const SLACK_OAUTH_COOKIE: &str = "slack_oauth";
+
+/// How long a Slack sign-in may take, in seconds
+const SLACK_OAUTH_MAX_AGE_SECS: i64 = 600;
+
+/// Client address as forwarded by the reverse proxy in front of the app
+fn client_ip(headers: &HeaderMap) -> Option<String> {
+    let forwarded = headers
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next());
+    let real_ip = || {
+        headers
+            .get("x-real-ip")
+            .and_then(|value| value.to_str().ok())
+    };
+    forwarded
+        .or_else(real_ip)
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty())
+        .map(str::to_string)
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct GoogleCallbackQuery {
     code: String,
 }
@@ -35,19 +69,139 @@ struct GoogleUserInfo {
     picture: Option<String>,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SlackCallbackQuery {
+    code: String,
+    state: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackTokenResponse {
+    ok: bool,
+    access_token: Option<String>,
+    id_token: Option<String>,
+    error: Option<String>,
+}
+
+/// Claims of the ID token Slack issues with the access token
+#[derive(Debug, Deserialize)]
+struct SlackIdToken {
+    nonce: Option<String>,
+}
+
+/// Claims of `openid.connect.userInfo`; Slack namespaces its own with URLs
+#[derive(Debug, Deserialize)]
+struct SlackUserInfo {
+    ok: bool,
+    error: Option<String>,
+    #[serde(rename = "https://slack.com/user_id")]
+    user_id: Option<String>,
+    #[serde(rename = "https://slack.com/team_id")]
+    team_id: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    name: Option<String>,
+}
+
+/// `Set-Cookie` value of the Slack sign-in cookie; a max age of 0 removes it
+fn slack_oauth_cookie(redirect_uri: &str, value: &str, max_age_secs: i64) -> String {
+    let secure = if redirect_uri.starts_with("https://") {
+        "; Secure"
+    } else {
+        ""
+    };
+    format!(
+        "{}={}; Path=/api/auth/slack; Max-Age={}; HttpOnly; SameSite=Lax{}",
+        SLACK_OAUTH_COOKIE, value, max_age_secs, secure
+    )
+}
+
+/// `state` and `nonce` of the Slack sign-in this browser started, if any
+fn slack_oauth_from_cookie(headers: &HeaderMap) -> Option<(String, String)> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|cookie| {
+            let value = cookie.trim().strip_prefix(SLACK_OAUTH_COOKIE)?;
+            let (state, nonce) = value.strip_prefix('=')?.split_once(':')?;
+            Some((state.to_string(), nonce.to_string()))
+        })
+        .filter(|(state, nonce)| !state.is_empty() && !nonce.is_empty())
+}
+
+/// Nonce of an ID token Slack returned from the token endpoint. The token came
+/// straight from Slack over TLS, so its signature isn't checked, only that it was
+/// issued by Slack for this app and hasn't expired.
+fn slack_id_token_nonce(
+    id_token: &str,
+    client_id: &str,
+) -> Result<Option<String>, jsonwebtoken::errors::Error> {
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.insecure_disable_signature_validation();
+    validation.set_audience(&[client_id]);
+    validation.set_issuer(&["https://slack.com"]);
+
+    let token = decode::<SlackIdToken>(id_token, &DecodingKey::from_secret(&[]), &validation)?;
+    Ok(token.claims.nonce)
+}
+
+/// Ways to sign in the login page offers
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuthProvidersResponse {
+    pub google: bool,
+    pub slack: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/auth/providers",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Enabled sign-in providers", body = AuthProvidersResponse),
+    )
+)]
+pub async fn auth_providers(State(state): State<Arc<AppState>>) -> Json<AuthProvidersResponse> {
+    Json(AuthProvidersResponse {
+        google: true,
+        slack: state.config.slack.login_enabled(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/auth/google",
+    tag = "auth",
+    responses(
+        (status = 307, description = "Redirect to Google consent screen"),
+    )
+)]
 pub async fn google_login(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     let auth_url = format!(
         "https://accounts.google.com/o/oauth2/v2/auth?client_id={}&redirect_uri={}&response_type=code&scope=openid%20email%20profile",
-        state.config.google_client_id,
-        urlencoding::encode(&state.config.google_redirect_uri)
+        state.config.google.client_id,
+        urlencoding::encode(&state.config.google.redirect_uri)
     );
 
     Redirect::temporary(&auth_url)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/auth/google/callback",
+    tag = "auth",
+    params(("code" = String, Query, description = "OAuth authorization code")),
+    responses(
+        (status = 307, description = "Redirect to the frontend with a session token"),
+        (status = 500, description = "Google authentication failed"),
+    )
+)]
 pub async fn google_callback(
     State(state): State<Arc<AppState>>,
     Query(query): Query<GoogleCallbackQuery>,
+    headers: HeaderMap,
 ) -> Result<Redirect, APIError> {
     let http_client = Client::new();
 
@@ -55,9 +209,9 @@ pub async fn google_callback(
         .post("https://oauth2.googleapis.com/token")
         .form(&[
             ("code", query.code.as_str()),
-            ("client_id", state.config.google_client_id.as_str()),
-            ("client_secret", state.config.google_client_secret.as_str()),
-            ("redirect_uri", state.config.google_redirect_uri.as_str()),
+            ("client_id", state.config.google.client_id.as_str()),
+            ("client_secret", state.config.google.client_secret.as_str()),
+            ("redirect_uri", state.config.google.redirect_uri.as_str()),
             ("grant_type", "authorization_code"),
         ])
         .send()
@@ -104,33 +258,25 @@ pub async fn google_callback(
         Err(_) => {
             info!("Signing up unregistered user: {}", user_info.name);
 
-            // Try to load workspaces config - it's OK if none exist
-            let workspaces_config =
-                WorkspacesConfig::load_and_decrypt("workspaces.yaml", &state.config.encryption_key)
-                    .ok();
-
-            let workspace_names = workspaces_config
-                .as_ref()
-                .map(|c| c.list_workspaces())
-                .unwrap_or_default();
+            // Load configured workspaces - it's OK if none exist
+            let workspaces =
+                get_active_workspaces_with_tokens(&state.database, &state.config.encryption_key)
+                    .await
+                    .unwrap_or_default();
 
             // Try to find user in any workspace (if workspaces exist)
-            let mut found_workspace: Option<(String, String, String)> = None;
-            if let Some(ref config) = workspaces_config {
-                for workspace_name in workspace_names.iter() {
-                    if let Some(workspace_config) = config.get_workspace(workspace_name) {
-                        if let Ok((slack_member_id, slack_name)) = fetch_user_by_email_with_config(
-                            &workspace_config.bot_token,
-                            &state.config.google_client_id,
-                            &user_info.email,
-                        )
-                        .await
-                        {
-                            found_workspace =
-                                Some((workspace_name.clone(), slack_member_id, slack_name));
-                            break;
-                        }
-                    }
+            let mut found_workspace = None;
+            for (workspace, workspace_config) in workspaces {
+                if let Ok((slack_member_id, slack_name)) = lookup_user_by_email(
+                    &state,
+                    &workspace.id,
+                    &workspace_config.bot_token,
+                    &user_info.email,
+                )
+                .await
+                {
+                    found_workspace = Some((workspace, slack_member_id, slack_name));
+                    break;
                 }
             }
 
@@ -161,12 +307,12 @@ pub async fn google_callback(
                 })?;
 
             // Auto-link to the workspace where they were found (if any)
-            if let Some((workspace_name, slack_member_id, _)) = found_workspace {
+            if let Some((workspace, slack_member_id, _)) = found_workspace {
                 let workspace_links_repo = WorkspaceLinksRepo::new(state.database.clone());
                 if let Err(e) = workspace_links_repo
                     .link_workspace(
                         created_person.id.clone(),
-                        workspace_name.clone(),
+                        workspace.id.clone(),
                         slack_member_id,
                     )
                     .await
@@ -176,8 +322,12 @@ pub async fn google_callback(
                 } else {
                     info!(
                         "Auto-linked {} to workspace: {}",
-                        user_info.email, workspace_name
+                        user_info.email, workspace.name
                     );
+                    state.events.publish(DomainEvent::WorkspaceLinked {
+                        person_id: created_person.id.clone(),
+                        workspace_id: workspace.id,
+                    });
                 }
             } else {
                 info!(
@@ -190,11 +340,51 @@ pub async fn google_callback(
         }
     };
 
-    let token = create_jwt(
-        user_info.email.clone(),
+    sign_in(&state, &headers, person).await
+}
+
+/// Finish a sign-in: accept pending invitations, open a session and hand its token
+/// to the frontend
+async fn sign_in(
+    state: &AppState,
+    headers: &HeaderMap,
+    person: Person,
+) -> Result<Redirect, APIError> {
+    // Complete any workspace invitations sent to this email
+    match accept_pending_invitations(&state.database, &state.events, &person).await {
+        Ok(accepted) if !accepted.is_empty() => info!(
+            "Linked {} to {} invited workspace(s)",
+            person.email,
+            accepted.len()
+        ),
+        Ok(_) => {}
+        Err(e) => error!("Failed to accept invitations for {}: {}", person.email, e),
+    }
+
+    // One session per sign-in, so each device can be signed out on its own
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(|agent| agent.chars().take(MAX_USER_AGENT_CHARS).collect());
+    let session = SessionsRepo::new(state.database.clone())
+        .create(
+            &person.id,
+            user_agent,
+            client_ip(headers),
+            state.config.jwt.expiry_hours,
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to create session: {}", e);
+            APIError::InternalServerError("Failed to create session".to_string())
+        })?;
+
+    let token = create_session_jwt(
+        person.email.clone(),
         person.id.clone(),
-        &state.config.jwt_secret,
-        state.config.jwt_expiry_hours,
+        session.id,
+        &state.config.jwt.secret,
+        state.config.jwt.expiry_hours,
     )
     .map_err(|e| {
         error!("Failed to create JWT: {}", e);
@@ -213,6 +403,274 @@ pub async fn google_callback(
     Ok(Redirect::temporary(&redirect_url))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/auth/slack",
+    tag = "auth",
+    responses(
+        (status = 307, description = "Redirect to the Slack consent screen, setting the sign-in cookie"),
+        (status = 404, description = "Sign in with Slack is not configured"),
+    )
+)]
+pub async fn slack_login(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, APIError> {
+    let slack = &state.config.slack;
+    if !slack.login_enabled() {
+        return Err(APIError::NotFound(
+            "Sign in with Slack is not configured".to_string(),
+        ));
+    }
+
+    // Slack hands `state` back to the callback and puts `nonce` in the ID token;
+    // both must match the cookie, so a sign-in can't be finished in another browser
+    let oauth_state = generate_uuid();
+    let nonce = generate_uuid();
+    let auth_url = format!(
+        "https://slack.com/openid/connect/authorize?client_id={}&redirect_uri={}&response_type=code&scope=openid%20email%20profile&state={}&nonce={}",
+        urlencoding::encode(&slack.client_id),
+        urlencoding::encode(&slack.redirect_uri),
+        urlencoding::encode(&oauth_state),
+        urlencoding::encode(&nonce)
+    );
+    let cookie = slack_oauth_cookie(
+        &slack.redirect_uri,
+        &format!("{}:{}", oauth_state, nonce),
+        SLACK_OAUTH_MAX_AGE_SECS,
+    );
+
+    Ok((
+        [(header::SET_COOKIE, cookie)],
+        Redirect::temporary(&auth_url),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/auth/slack/callback",
+    tag = "auth",
+    params(
+        ("code" = String, Query, description = "OAuth authorization code"),
+        ("state" = String, Query, description = "State sent to Slack when the sign-in started"),
+    ),
+    responses(
+        (status = 307, description = "Redirect to the frontend with a session token"),
+        (status = 400, description = "Sign-in expired or started in another browser, or Slack has no verified email"),
+        (status = 404, description = "Sign in with Slack is not configured"),
+        (status = 500, description = "Slack authentication failed"),
+    )
+)]
+pub async fn slack_callback(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SlackCallbackQuery>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, APIError> {
+    let slack = &state.config.slack;
+    if !slack.login_enabled() {
+        return Err(APIError::NotFound(
+            "Sign in with Slack is not configured".to_string(),
+        ));
+    }
+    let nonce = match slack_oauth_from_cookie(&headers) {
+        Some((oauth_state, nonce)) if oauth_state == query.state => nonce,
+        _ => {
+            warn!("Slack callback without a matching sign-in cookie");
+            return Err(APIError::BadRequest(
+                "Sign in with Slack expired or was started elsewhere, try again".to_string(),
+            ));
+        }
+    };
+    let http_client = Client::new();
+
+    let token_response = http_client
+        .post("https://slack.com/api/openid.connect.token")
+        .form(&[
+            ("code", query.code.as_str()),
+            ("client_id", slack.client_id.as_str()),
+            ("client_secret", slack.client_secret.as_str()),
+            ("redirect_uri", slack.redirect_uri.as_str()),
+            ("grant_type", "authorization_code"),
+        ])
+        .send()
+        .await
+        .map_err(|e| {
+            error!("Failed to exchange Slack code: {}", e);
+            APIError::InternalServerError("Failed to authenticate with Slack".to_string())
+        })?
+        .json::<SlackTokenResponse>()
+        .await
+        .map_err(|e| {
+            error!("Failed to parse Slack token response: {}", e);
+            APIError::InternalServerError("Failed to authenticate with Slack".to_string())
+        })?;
+    let (access_token, id_token) = match (token_response.access_token, token_response.id_token) {
+        (Some(access_token), Some(id_token)) if token_response.ok => (access_token, id_token),
+        _ => {
+            error!(
+                "Slack refused the code: {}",
+                token_response.error.unwrap_or_default()
+            );
+            return Err(APIError::InternalServerError(
+                "Failed to authenticate with Slack".to_string(),
+            ));
+        }
+    };
+    match slack_id_token_nonce(&id_token, &slack.client_id) {
+        Ok(Some(token_nonce)) if token_nonce == nonce => {}
+        Ok(_) => {
+            warn!("Slack ID token has no nonce or not the one sent");
+            return Err(APIError::BadRequest(
+                "Sign in with Slack expired or was started elsewhere, try again".to_string(),
+            ));
+        }
+        Err(e) => {
+            error!("Invalid Slack ID token: {}", e);
+            return Err(APIError::InternalServerError(
+                "Failed to authenticate with Slack".to_string(),
+            ));
+        }
+    }
+
+    let user_info = http_client
+        .get("https://slack.com/api/openid.connect.userInfo")
+        .bearer_auth(&access_token)
+        .send()
+        .await
+        .map_err(|e| {
+            error!("Failed to get Slack user info: {}", e);
+            APIError::InternalServerError("Failed to get the user info".to_string())
+        })?
+        .json::<SlackUserInfo>()
+        .await
+        .map_err(|e| {
+            error!("Failed to parse Slack user info response: {}", e);
+            APIError::InternalServerError("Failed to get the user info".to_string())
+        })?;
+    let (Some(slack_member_id), Some(team_id)) = (user_info.user_id, user_info.team_id) else {
+        error!(
+            "Slack returned no user: {}",
+            user_info.error.unwrap_or_default()
+        );
+        return Err(APIError::InternalServerError(
+            "Failed to get the user info".to_string(),
+        ));
+    };
+    // People are matched to accounts by email, so it has to be one Slack vouches for
+    let email = match user_info.email {
+        Some(email) if user_info.ok && user_info.email_verified => email,
+        _ => {
+            return Err(APIError::BadRequest(
+                "Slack has no verified email for this account".to_string(),
+            ))
+        }
+    };
+    let name = user_info.name.unwrap_or_else(|| email.clone());
+
+    info!("User authenticated with Slack: {} ({})", name, email);
+
+    let persons_repo = PersonsRepo::new(state.database.clone());
+    let person = match persons_repo.get_by_email(email.clone()).await {
+        Ok(person) => {
+            info!("Existing user logged in: {}", person.name);
+            person
+        }
+        Err(_) => {
+            info!("Signing up unregistered user: {}", name);
+            persons_repo
+                .create(name, false, slack_member_id.clone(), email.clone())
+                .await
+                .map_err(|e| {
+                    error!("Failed to create person entity: {}", e);
+                    APIError::InternalServerError("Failed to create person entity".to_string())
+                })?
+        }
+    };
+    if person.external_id.is_empty() {
+        if let Err(e) = persons_repo
+            .update_external_id(person.id.clone(), slack_member_id.clone())
+            .await
+        {
+            error!("Failed to update person's external_id: {}", e);
+        }
+    }
+
+    // Slack already told us who the user is in their team, so a workspace set up for
+    // that team is linked without looking the email up
+    if let Ok(workspace) = WorkspacesRepo::new(state.database.clone())
+        .get_active_by_slack_team_id(&team_id)
+        .await
+    {
+        link_signed_in_member(&state, &person, &workspace.id, &slack_member_id).await;
+    }
+
+    let redirect = sign_in(&state, &headers, person).await?;
+    // The sign-in cookie is used up
+    let cookie = slack_oauth_cookie(&slack.redirect_uri, "", 0);
+    Ok(([(header::SET_COOKIE, cookie)], redirect))
+}
+
+/// Link someone who signed in with Slack to the workspace of their team. People
+/// already on the workspace, unlinked by an admin or with their Slack user taken
+/// by someone else are left alone.
+async fn link_signed_in_member(
+    state: &AppState,
+    person: &Person,
+    workspace_id: &str,
+    slack_member_id: &str,
+) {
+    let workspace_links_repo = WorkspaceLinksRepo::new(state.database.clone());
+    if workspace_links_repo
+        .get_by_person_and_workspace(person.id.clone(), workspace_id.to_string())
+        .await
+        .is_ok()
+    {
+        return;
+    }
+    if workspace_links_repo
+        .get_by_slack_member_id_and_workspace(slack_member_id.to_string(), workspace_id.to_string())
+        .await
+        .is_ok()
+    {
+        warn!(
+            "Slack user {} is already linked to someone else in workspace {}",
+            slack_member_id, workspace_id
+        );
+        return;
+    }
+
+    match workspace_links_repo
+        .link_workspace(
+            person.id.clone(),
+            workspace_id.to_string(),
+            slack_member_id.to_string(),
+        )
+        .await
+    {
+        Ok(_) => {
+            info!(
+                "Auto-linked {} to workspace {} as {}",
+                person.email, workspace_id, slack_member_id
+            );
+            state.events.publish(DomainEvent::WorkspaceLinked {
+                person_id: person.id.clone(),
+                workspace_id: workspace_id.to_string(),
+            });
+        }
+        // Log but don't fail - user can link manually later
+        Err(e) => error!("Failed to auto-link workspace: {}", e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/auth/me",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Current user", body = Person),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
 pub async fn get_me(person: Person) -> Result<Json<Person>, StatusCode> {
     Ok(Json(person))
 }
@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::{
+    core::state::AppState,
+    models::{availability::Model as Availability, person::Model as Person},
+    repos::availability::AvailabilityRepo,
+    utils::{
+        json::Json,
+        response::{APIError, FieldError},
+    },
+};
+
+/// Longest time out of office one period covers
+const MAX_PERIOD_DAYS: i64 = 366;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AvailabilityRequest {
+    /// First day out of office, in your timezone
+    pub starts_on: NaiveDate,
+    /// Last day out of office, in your timezone
+    pub ends_on: NaiveDate,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AvailabilityListResponse {
+    /// Current and upcoming periods, earliest first
+    pub availability: Vec<Availability>,
+}
+
+/// Current and upcoming out-of-office periods of the current user
+#[utoipa::path(
+    get,
+    path = "/api/me/availability",
+    tag = "me",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Out-of-office periods", body = AvailabilityListResponse),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
+pub async fn list_availability(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<AvailabilityListResponse>, APIError> {
+    let today = Utc::now().with_timezone(&person.tz()).date_naive();
+    let availability = AvailabilityRepo::new(state.read_database.clone())
+        .get_by_person(&person.id, today)
+        .await
+        .map_err(|e| {
+            error!("Failed to load availability: {}", e);
+            APIError::InternalServerError("Failed to load availability".to_string())
+        })?;
+
+    Ok(Json(AvailabilityListResponse { availability }))
+}
+
+/// Mark days out of office. Meanwhile channel rules and the assignee suggestion
+/// pass you over and nobody can hand you an orphaned task.
+#[utoipa::path(
+    post,
+    path = "/api/me/availability",
+    tag = "me",
+    request_body = AvailabilityRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Created period", body = Availability),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 422, description = "Ends before it starts, is in the past or is too long"),
+    )
+)]
+pub async fn create_availability(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Json(payload): Json<AvailabilityRequest>,
+) -> Result<(StatusCode, Json<Availability>), APIError> {
+    let today = Utc::now().with_timezone(&person.tz()).date_naive();
+    let invalid = |message: String| {
+        APIError::UnprocessableEntity(vec![FieldError {
+            field: "ends_on".to_string(),
+            message,
+        }])
+    };
+    if payload.ends_on < payload.starts_on {
+        return Err(invalid("must not be before starts_on".to_string()));
+    }
+    if payload.ends_on < today {
+        return Err(invalid("must not be in the past".to_string()));
+    }
+    if (payload.ends_on - payload.starts_on).num_days() >= MAX_PERIOD_DAYS {
+        return Err(invalid(format!(
+            "a period covers at most {} days",
+            MAX_PERIOD_DAYS
+        )));
+    }
+
+    let note = payload
+        .note
+        .map(|note| note.trim().to_string())
+        .filter(|note| !note.is_empty());
+    let availability = AvailabilityRepo::new(state.database.clone())
+        .create(&person.id, payload.starts_on, payload.ends_on, note)
+        .await
+        .map_err(|e| {
+            error!("Failed to save availability: {}", e);
+            APIError::InternalServerError("Failed to save availability".to_string())
+        })?;
+
+    info!(
+        "User {} is out of office from {} to {}",
+        person.email, availability.starts_on, availability.ends_on
+    );
+    Ok((StatusCode::CREATED, Json(availability)))
+}
+
+/// Remove an out-of-office period
+#[utoipa::path(
+    delete,
+    path = "/api/me/availability/{availability_id}",
+    tag = "me",
+    params(("availability_id" = String, Path, description = "Period id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Period deleted"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Period not found"),
+    )
+)]
+pub async fn delete_availability(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(availability_id): Path<String>,
+) -> Result<StatusCode, APIError> {
+    let availability_repo = AvailabilityRepo::new(state.database.clone());
+    let availability = availability_repo
+        .get(&person.id, &availability_id)
+        .await?
+        .ok_or_else(|| APIError::NotFound("Period not found".to_string()))?;
+
+    availability_repo.delete(availability).await.map_err(|e| {
+        error!("Failed to delete availability: {}", e);
+        APIError::InternalServerError("Failed to delete availability".to_string())
+    })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+};
+use futures_util::StreamExt;
+use serde::Deserialize;
+use tracing::{error, info};
+use utoipa::IntoParams;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    models::person::Model as Person,
+    services::backup::{self, BackupImportReport, BackupRecord, Importer},
+    utils::{json::Json, response::APIError},
+};
+
+/// Longest line an import reads; records are far smaller
+const MAX_LINE_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BackupQuery {
+    /// Workspace name
+    pub workspace: String,
+}
+
+/// Stream every task of a workspace with its messages, history and people as
+/// newline-delimited JSON, e.g. to move the workspace to another instance with
+/// `POST /api/admin/import` - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/admin/export/messages",
+    tag = "admins",
+    params(BackupQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "One JSON record per line, each with a `kind` of person, message, task or change", content_type = "application/x-ndjson"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn export_messages(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Query(query): Query<BackupQuery>,
+) -> Result<Response, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+    let workspace = find_workspace(&state, &query.workspace).await?;
+
+    info!(
+        "User {} is exporting workspace {}",
+        person.email, workspace.name
+    );
+    // An error ends the body early, so the client sees a truncated download
+    let workspace_name = workspace.name.clone();
+    let records = backup::export(
+        state.read_database.clone(),
+        state.message_encryption.clone(),
+        workspace.id,
+    );
+    let records = records.inspect(move |chunk| {
+        if let Err(e) = chunk {
+            error!("Failed to export workspace {}: {}", workspace_name, e);
+        }
+    });
+
+    let mut response = Body::from_stream(records).into_response();
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(backup::CONTENT_TYPE),
+    );
+    if let Ok(disposition) = HeaderValue::from_str(&format!(
+        "attachment; filename=\"{}.ndjson\"",
+        query.workspace
+    )) {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_DISPOSITION, disposition);
+    }
+    Ok(response)
+}
+
+/// Restore a backup of `GET /api/admin/export/messages` into a workspace, read
+/// line by line as it is uploaded. Records this instance already has are skipped,
+/// so a failed import can be sent again - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/admin/import",
+    tag = "admins",
+    params(BackupQuery),
+    request_body(
+        content = String,
+        description = "A backup as exported, one JSON record per line",
+        content_type = "application/x-ndjson"
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Records imported", body = BackupImportReport),
+        (status = 400, description = "A line is not a backup record"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn import_backup(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Query(query): Query<BackupQuery>,
+    body: Body,
+) -> Result<Json<BackupImportReport>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+    let workspace = find_workspace(&state, &query.workspace).await?;
+
+    info!(
+        "User {} is importing a backup into workspace {}",
+        person.email, workspace.name
+    );
+    let db_error = |e: sea_orm::DbErr| {
+        error!("Failed to import backup: {}", e);
+        APIError::InternalServerError("Failed to import backup".to_string())
+    };
+
+    let mut importer = Importer::new(
+        state.database.clone(),
+        state.message_encryption.clone(),
+        &workspace,
+    );
+    let mut body = body.into_data_stream();
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut line_number = 0;
+    let mut done = false;
+    while !done {
+        match body.next().await {
+            Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+            Some(Err(e)) => {
+                return Err(APIError::BadRequest(format!(
+                    "Failed to read the backup: {}",
+                    e
+                )))
+            }
+            // The last line may lack its newline
+            None => {
+                buffer.push(b'\n');
+                done = true;
+            }
+        }
+
+        while let Some(end) = buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            line_number += 1;
+            let line = line.trim_ascii();
+            if line.is_empty() {
+                continue;
+            }
+            let record: BackupRecord = serde_json::from_slice(line).map_err(|e| {
+                APIError::BadRequest(format!(
+                    "Line {} is not a backup record: {}",
+                    line_number, e
+                ))
+            })?;
+            importer.add(record).await.map_err(db_error)?;
+        }
+        if buffer.len() > MAX_LINE_BYTES {
+            return Err(APIError::BadRequest(format!(
+                "Line {} is longer than {} bytes",
+                line_number + 1,
+                MAX_LINE_BYTES
+            )));
+        }
+    }
+
+    let report = importer.finish().await.map_err(db_error)?;
+    info!(
+        "Imported {} task(s) and {} message(s) into workspace {}, skipped {} record(s)",
+        report.tasks, report.messages, workspace.name, report.skipped
+    );
+    Ok(Json(report))
+}
@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    core::state::AppState,
+    models::{
+        board_view::{BoardViewFilter, Model as BoardView},
+        person::Model as Person,
+    },
+    repos::board_views::BoardViewsRepo,
+    utils::{
+        json::{Json, ValidatedJson},
+        response::APIError,
+        validation,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct BoardViewRequest {
+    /// Unique among your views
+    #[validate(custom(function = "validation::not_blank"))]
+    pub name: String,
+    #[serde(default)]
+    pub filter: BoardViewFilter,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BoardViewListResponse {
+    pub views: Vec<BoardView>,
+}
+
+/// Trimmed name and filter without blank values; a name taken by another view of
+/// the person is a conflict
+async fn validate_view(
+    views_repo: &BoardViewsRepo,
+    person: &Person,
+    payload: BoardViewRequest,
+    view_id: Option<&str>,
+) -> Result<(String, BoardViewFilter), APIError> {
+    let name = payload.name.trim().to_string();
+
+    let taken = views_repo
+        .get_by_name(&person.id, &name)
+        .await?
+        .is_some_and(|existing| Some(existing.id.as_str()) != view_id);
+    if taken {
+        return Err(APIError::Conflict(format!("View {} already exists", name)));
+    }
+
+    let trimmed = |value: Option<String>| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let filter = payload.filter;
+    Ok((
+        name,
+        BoardViewFilter {
+            assignee: trimmed(filter.assignee),
+            labels: filter
+                .labels
+                .into_iter()
+                .filter_map(|label| trimmed(Some(label)))
+                .collect(),
+            channel: trimmed(filter.channel),
+            statuses: filter.statuses,
+        },
+    ))
+}
+
+/// Saved board views of the current user
+#[utoipa::path(
+    get,
+    path = "/api/me/views",
+    tag = "me",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Views by name", body = BoardViewListResponse),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
+pub async fn list_board_views(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<BoardViewListResponse>, APIError> {
+    let views = BoardViewsRepo::new(state.read_database.clone())
+        .get_by_person(&person.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load board views: {}", e);
+            APIError::InternalServerError("Failed to load board views".to_string())
+        })?;
+
+    Ok(Json(BoardViewListResponse { views }))
+}
+
+/// Save a board view
+#[utoipa::path(
+    post,
+    path = "/api/me/views",
+    tag = "me",
+    request_body = BoardViewRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Created view", body = BoardView),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 409, description = "A view with the name exists"),
+        (status = 422, description = "Missing name"),
+    )
+)]
+pub async fn create_board_view(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    ValidatedJson(payload): ValidatedJson<BoardViewRequest>,
+) -> Result<(StatusCode, Json<BoardView>), APIError> {
+    let views_repo = BoardViewsRepo::new(state.database.clone());
+    let (name, filter) = validate_view(&views_repo, &person, payload, None).await?;
+
+    let view = views_repo
+        .create(&person.id, name, &filter)
+        .await
+        .map_err(|e| {
+            error!("Failed to create board view: {}", e);
+            APIError::InternalServerError("Failed to create board view".to_string())
+        })?;
+
+    Ok((StatusCode::CREATED, Json(view)))
+}
+
+/// Rename a board view or change its filter
+#[utoipa::path(
+    put,
+    path = "/api/me/views/{view_id}",
+    tag = "me",
+    params(("view_id" = String, Path, description = "View id")),
+    request_body = BoardViewRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Updated view", body = BoardView),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "View not found"),
+        (status = 409, description = "Another view has the name"),
+        (status = 422, description = "Missing name"),
+    )
+)]
+pub async fn update_board_view(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(view_id): Path<String>,
+    ValidatedJson(payload): ValidatedJson<BoardViewRequest>,
+) -> Result<Json<BoardView>, APIError> {
+    let views_repo = BoardViewsRepo::new(state.database.clone());
+    let view = views_repo
+        .get(&person.id, &view_id)
+        .await?
+        .ok_or_else(|| APIError::NotFound("View not found".to_string()))?;
+    let (name, filter) = validate_view(&views_repo, &person, payload, Some(&view.id)).await?;
+
+    let view = views_repo.update(view, name, &filter).await.map_err(|e| {
+        error!("Failed to update board view: {}", e);
+        APIError::InternalServerError("Failed to update board view".to_string())
+    })?;
+
+    Ok(Json(view))
+}
+
+/// Delete a board view
+#[utoipa::path(
+    delete,
+    path = "/api/me/views/{view_id}",
+    tag = "me",
+    params(("view_id" = String, Path, description = "View id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "View deleted"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "View not found"),
+    )
+)]
+pub async fn delete_board_view(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(view_id): Path<String>,
+) -> Result<StatusCode, APIError> {
+    let views_repo = BoardViewsRepo::new(state.database.clone());
+    let view = views_repo
+        .get(&person.id, &view_id)
+        .await?
+        .ok_or_else(|| APIError::NotFound("View not found".to_string()))?;
+
+    views_repo.delete(view).await.map_err(|e| {
+        error!("Failed to delete board view: {}", e);
+        APIError::InternalServerError("Failed to delete board view".to_string())
+    })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
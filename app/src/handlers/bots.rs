@@ -0,0 +1,347 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    core::{bot_status::BotStatus, reload::ReloadReport, state::AppState},
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    models::{
+        bot_event::{BotEventOutcome, Model as BotEvent},
+        bot_incident::{BotIncidentKind, Model as BotIncident},
+        person::Model as Person,
+    },
+    repos::{
+        bot_events::{BotEventFilter, BotEventsRepo},
+        bot_incidents::BotIncidentsRepo,
+        workspaces::WorkspacesRepo,
+    },
+    services::workspace::get_cached_workspace_tokens,
+    sockets::slack_bot::{can_replay, SlackBot},
+    utils::{
+        json::Json,
+        pagination::{CursorQuery, Pagination},
+        response::APIError,
+    },
+};
+
+/// How many past incidents are returned per workspace
+const RECENT_INCIDENTS_LIMIT: u64 = 20;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BotInfo {
+    pub workspace_name: String,
+    /// Whether a bot task is currently running for the workspace
+    pub is_running: bool,
+    /// Live status; None until the bot has connected since the last restart
+    pub status: Option<BotStatus>,
+    /// Newest first
+    pub recent_incidents: Vec<BotIncident>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BotListResponse {
+    pub bots: Vec<BotInfo>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReconnectBotResponse {
+    pub success: bool,
+    pub message: String,
+}
+
+/// List bot status and recent incidents for every workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/admin/bots",
+    tag = "admins",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Bot status per workspace", body = BotListResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+    )
+)]
+pub async fn list_bots(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<BotListResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspaces_repo = WorkspacesRepo::new(state.database.clone());
+    let incidents_repo = BotIncidentsRepo::new(state.database.clone());
+
+    let workspaces = workspaces_repo.get_active().await.map_err(|e| {
+        error!("Failed to load workspaces: {}", e);
+        APIError::InternalServerError("Failed to load workspaces".to_string())
+    })?;
+
+    let mut bots = Vec::with_capacity(workspaces.len());
+    for workspace in workspaces {
+        let recent_incidents = incidents_repo
+            .get_recent(&workspace.id, RECENT_INCIDENTS_LIMIT)
+            .await
+            .map_err(|e| {
+                error!("Failed to load bot incidents: {}", e);
+                APIError::InternalServerError("Failed to load bot incidents".to_string())
+            })?;
+
+        bots.push(BotInfo {
+            is_running: state.is_bot_running(&workspace.id),
+            status: state.bot_status.get_status(&workspace.name).await,
+            workspace_name: workspace.name,
+            recent_incidents,
+        });
+    }
+
+    Ok(Json(BotListResponse { bots }))
+}
+
+/// Restart the bot of a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/admin/bots/{workspace_name}/reconnect",
+    tag = "admins",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Bot restarting", body = ReconnectBotResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn reconnect_bot(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<ReconnectBotResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let tokens = get_cached_workspace_tokens(
+        &state.cache,
+        &state.database,
+        &workspace.id,
+        &state.config.encryption_key,
+    )
+    .await
+    .map_err(|e| {
+        error!(
+            "Failed to load tokens for workspace {}: {}",
+            workspace_name, e
+        );
+        APIError::InternalServerError("Failed to load workspace configuration".to_string())
+    })?;
+
+    info!(
+        "User {} requested reconnect for workspace {}",
+        person.email, workspace_name
+    );
+
+    let incidents_repo = BotIncidentsRepo::new(state.database.clone());
+    if let Err(e) = incidents_repo
+        .create(
+            &workspace.id,
+            BotIncidentKind::ReconnectRequested,
+            Some(format!("Requested by {}", person.email)),
+        )
+        .await
+    {
+        error!("Failed to record bot incident: {}", e);
+    }
+
+    // Replaces the running bot, if any, also when another instance runs it
+    state
+        .start_bot(workspace, tokens.app_token, tokens.bot_token)
+        .await
+        .map_err(|e| {
+            error!("Failed to start bot: {}", e);
+            APIError::InternalServerError("Failed to start bot".to_string())
+        })?;
+
+    Ok(Json(ReconnectBotResponse {
+        success: true,
+        message: format!("Reconnecting bot for workspace '{}'", workspace_name),
+    }))
+}
+
+/// Import workspaces.yaml and start or stop bots for added or removed workspaces now,
+/// instead of waiting for the periodic reload - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/admin/reload-config",
+    tag = "admins",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Bots started and stopped by the reload", body = ReloadReport),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+    )
+)]
+pub async fn reload_config(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<ReloadReport>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    info!("User {} requested a workspace reload", person.email);
+
+    let report = state.reload_workspaces().await.map_err(|e| {
+        error!("Failed to reload workspaces: {}", e);
+        APIError::InternalServerError("Failed to reload workspaces".to_string())
+    })?;
+
+    Ok(Json(report))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BotEventsQuery {
+    /// Only events of this workspace
+    pub workspace: Option<String>,
+    pub outcome: Option<BotEventOutcome>,
+    /// e.g. `reaction_added` or `shortcut`
+    pub event_type: Option<String>,
+    /// Slack channel id
+    pub channel: Option<String>,
+    /// `next_cursor` of the previous page; the first page without one
+    pub cursor: Option<String>,
+    /// Events per page, 20 by default and at most 100
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BotEventListResponse {
+    /// Newest first
+    pub events: Vec<BotEvent>,
+    /// Pass as `cursor` for the next page, None on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Page through the events the bots processed and what came of them, e.g. why a
+/// reaction did not create a task - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/admin/bot-events",
+    tag = "admins",
+    params(BotEventsQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Page of bot events", body = BotEventListResponse),
+        (status = 400, description = "Invalid cursor"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn list_bot_events(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Query(query): Query<BotEventsQuery>,
+) -> Result<Json<BotEventListResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+    let pagination = Pagination::from_query(&CursorQuery {
+        cursor: query.cursor,
+        limit: query.limit,
+    })?;
+
+    let workspace_id = match query.workspace {
+        Some(name) => Some(find_workspace(&state, &name).await?.id),
+        None => None,
+    };
+    let filter = BotEventFilter {
+        workspace_id,
+        outcome: query.outcome,
+        event_type: query.event_type,
+        channel: query.channel,
+    };
+
+    let page = BotEventsRepo::new(state.read_database.clone())
+        .get_page(&filter, &pagination)
+        .await
+        .map_err(|e| {
+            error!("Failed to get bot events: {}", e);
+            APIError::InternalServerError("Failed to get bot events".to_string())
+        })?;
+
+    Ok(Json(BotEventListResponse {
+        events: page.items,
+        next_cursor: page.next_cursor,
+    }))
+}
+
+/// Run a logged bot event through the handlers again, re-fetching the message and
+/// its reactions, e.g. after fixing emoji mappings or linking the author. Only
+/// message and reaction events can be replayed - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/admin/bot-events/{id}/replay",
+    tag = "admins",
+    params(("id" = String, Path, description = "Bot event ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The replay, logged as a new bot event", body = BotEvent),
+        (status = 400, description = "The event can't be replayed"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Bot event not found"),
+    )
+)]
+pub async fn replay_bot_event(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(id): Path<String>,
+) -> Result<Json<BotEvent>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let event = BotEventsRepo::new(state.database.clone()).get(&id).await?;
+    if !can_replay(&event) {
+        return Err(APIError::BadRequest(format!(
+            "{} events can't be replayed",
+            event.event_type
+        )));
+    }
+
+    let workspace = WorkspacesRepo::new(state.database.clone())
+        .get(&event.workspace_id)
+        .await?;
+    let tokens = get_cached_workspace_tokens(
+        &state.cache,
+        &state.database,
+        &workspace.id,
+        &state.config.encryption_key,
+    )
+    .await
+    .map_err(|e| {
+        error!(
+            "Failed to load tokens for workspace {}: {}",
+            workspace.name, e
+        );
+        APIError::InternalServerError("Failed to load workspace configuration".to_string())
+    })?;
+
+    info!(
+        "User {} replaying bot event {} of workspace {}",
+        person.email, event.id, workspace.name
+    );
+
+    let bot = SlackBot::new(&workspace, tokens.app_token, tokens.bot_token, &state);
+    let replayed = bot.replay(&event).await.map_err(|e| {
+        error!("Failed to replay bot event {}: {}", event.id, e);
+        APIError::InternalServerError("Failed to replay bot event".to_string())
+    })?;
+
+    Ok(Json(replayed))
+}
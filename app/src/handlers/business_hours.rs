@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use tracing::{error, info};
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    models::{person::Model as Person, workspace_settings::BusinessHours},
+    repos::workspace_settings::WorkspaceSettingsRepo,
+    services::business_hours,
+    utils::{json::Json, response::APIError},
+};
+
+/// Business hours of a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/business-hours",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Business hours", body = BusinessHours),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or no business hours set"),
+    )
+)]
+pub async fn get_business_hours(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<BusinessHours>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_business_hours(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load business hours: {}", e);
+            APIError::InternalServerError("Failed to load business hours".to_string())
+        })?
+        .ok_or_else(|| APIError::NotFound("No business hours are set".to_string()))?;
+
+    Ok(Json(settings))
+}
+
+/// Set the business hours and holidays of a workspace. Task ages, SLAs and
+/// escalation timers then count only working hours - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/business-hours",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = BusinessHours,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Saved business hours", body = BusinessHours),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 422, description = "Invalid time, timezone, days or holidays"),
+    )
+)]
+pub async fn update_business_hours(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<BusinessHours>,
+) -> Result<Json<BusinessHours>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+    business_hours::calendar(&payload).map_err(APIError::UnprocessableEntity)?;
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let mut holidays = payload.holidays;
+    holidays.sort();
+    holidays.dedup();
+    let settings = BusinessHours {
+        start: payload.start.trim().to_string(),
+        end: payload.end.trim().to_string(),
+        timezone: payload.timezone,
+        days: payload.days.trim().to_string(),
+        holidays,
+    };
+    WorkspaceSettingsRepo::new(state.database.clone())
+        .update_business_hours(&workspace.id, Some(settings.clone()))
+        .await
+        .map_err(|e| {
+            error!("Failed to save business hours: {}", e);
+            APIError::InternalServerError("Failed to save business hours".to_string())
+        })?;
+
+    info!(
+        "User {} set the business hours of workspace {} to {}-{} on {}",
+        person.email, workspace_name, settings.start, settings.end, settings.days
+    );
+    Ok(Json(settings))
+}
+
+/// Remove the business hours of a workspace, so time counts around the clock
+/// again - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_name}/business-hours",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Business hours removed"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or no business hours set"),
+    )
+)]
+pub async fn delete_business_hours(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<StatusCode, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings_repo = WorkspaceSettingsRepo::new(state.database.clone());
+    let set = settings_repo
+        .get_business_hours(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load business hours: {}", e);
+            APIError::InternalServerError("Failed to load business hours".to_string())
+        })?
+        .is_some();
+    if !set {
+        return Err(APIError::NotFound("No business hours are set".to_string()));
+    }
+
+    settings_repo
+        .update_business_hours(&workspace.id, None)
+        .await
+        .map_err(|e| {
+            error!("Failed to remove business hours: {}", e);
+            APIError::InternalServerError("Failed to remove business hours".to_string())
+        })?;
+
+    info!(
+        "User {} removed the business hours of workspace {}",
+        person.email, workspace_name
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,265 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use sea_orm::DbErr;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    models::{channel_rule::Model as ChannelRule, person::Model as Person, task::TaskPriority},
+    repos::{
+        channel_rules::{ChannelRuleDefaults, ChannelRulesRepo},
+        persons::PersonsRepo,
+        projects::ProjectsRepo,
+        workspace_links::WorkspaceLinksRepo,
+    },
+    utils::{json::Json, response::APIError},
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateChannelRuleRequest {
+    /// Linked member new tasks are assigned to instead of the message's author
+    pub assignee_email: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub priority: Option<TaskPriority>,
+    /// Project of the workspace new tasks are grouped under
+    pub project_id: Option<String>,
+    /// Create no tasks from the channel at all
+    #[serde(default)]
+    pub ignored: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChannelRuleResponse {
+    /// Slack channel id
+    pub channel: String,
+    pub assignee_email: Option<String>,
+    pub labels: Vec<String>,
+    pub priority: Option<TaskPriority>,
+    pub project_id: Option<String>,
+    pub ignored: bool,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChannelRuleListResponse {
+    pub rules: Vec<ChannelRuleResponse>,
+}
+
+async fn rule_response(state: &AppState, rule: ChannelRule) -> ChannelRuleResponse {
+    let assignee_email = match rule.assignee_id.clone() {
+        Some(assignee_id) => PersonsRepo::new(state.database.clone())
+            .get_by_id(assignee_id)
+            .await
+            .ok()
+            .map(|person| person.email),
+        None => None,
+    };
+
+    ChannelRuleResponse {
+        labels: rule.labels(),
+        channel: rule.channel,
+        assignee_email,
+        priority: rule.priority,
+        project_id: rule.project_id,
+        ignored: rule.ignored,
+        updated_at: rule.updated_at.and_utc().to_rfc3339(),
+    }
+}
+
+/// Channel rules of a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/channel-rules",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Channel rules", body = ChannelRuleListResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn list_channel_rules(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<ChannelRuleListResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let rules = ChannelRulesRepo::new(state.database.clone())
+        .get_by_workspace(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load channel rules: {}", e);
+            APIError::InternalServerError("Failed to load channel rules".to_string())
+        })?;
+
+    let mut responses = Vec::with_capacity(rules.len());
+    for rule in rules {
+        responses.push(rule_response(&state, rule).await);
+    }
+    Ok(Json(ChannelRuleListResponse { rules: responses }))
+}
+
+/// Set the rule of a Slack channel; it applies to tasks created afterwards -
+/// REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/channel-rules/{channel}",
+    tag = "workspaces",
+    params(
+        ("workspace_name" = String, Path, description = "Workspace name"),
+        ("channel" = String, Path, description = "Slack channel id"),
+    ),
+    request_body = UpdateChannelRuleRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Saved channel rule", body = ChannelRuleResponse),
+        (status = 400, description = "Assignee is not a linked member or the project is not in the workspace"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn update_channel_rule(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path((workspace_name, channel)): Path<(String, String)>,
+    Json(payload): Json<UpdateChannelRuleRequest>,
+) -> Result<Json<ChannelRuleResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+
+    let assignee_id = match payload.assignee_email.as_deref().map(str::trim) {
+        Some(email) if !email.is_empty() => {
+            let not_member = || {
+                APIError::BadRequest(format!("{} is not a linked member of the workspace", email))
+            };
+            let assignee = match PersonsRepo::new(state.database.clone())
+                .get_by_email(email.to_lowercase())
+                .await
+            {
+                Ok(assignee) => assignee,
+                Err(DbErr::RecordNotFound(_)) => return Err(not_member()),
+                Err(e) => return Err(e.into()),
+            };
+            let linked = WorkspaceLinksRepo::new(state.database.clone())
+                .get_by_person_and_workspace(assignee.id.clone(), workspace.id.clone())
+                .await
+                .is_ok_and(|link| link.is_linked);
+            if !linked {
+                return Err(not_member());
+            }
+            Some(assignee.id)
+        }
+        _ => None,
+    };
+
+    let project_id = match payload.project_id.as_deref().map(str::trim) {
+        Some(project_id) if !project_id.is_empty() => {
+            let project = ProjectsRepo::new(state.database.clone())
+                .get(&workspace.id, project_id)
+                .await?
+                .ok_or_else(|| {
+                    APIError::BadRequest(format!(
+                        "{} is not a project of the workspace",
+                        project_id
+                    ))
+                })?;
+            Some(project.id)
+        }
+        _ => None,
+    };
+
+    let labels: Vec<String> = payload
+        .labels
+        .iter()
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty())
+        .collect();
+
+    let rule = ChannelRulesRepo::new(state.database.clone())
+        .upsert(
+            &workspace.id,
+            &channel,
+            ChannelRuleDefaults {
+                assignee_id,
+                labels,
+                priority: payload.priority,
+                project_id,
+                ignored: payload.ignored,
+            },
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to save channel rule: {}", e);
+            APIError::InternalServerError("Failed to save channel rule".to_string())
+        })?;
+
+    info!(
+        "User {} updated the rule of channel {} in workspace {}",
+        person.email, channel, workspace_name
+    );
+    Ok(Json(rule_response(&state, rule).await))
+}
+
+/// Remove the rule of a Slack channel - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_name}/channel-rules/{channel}",
+    tag = "workspaces",
+    params(
+        ("workspace_name" = String, Path, description = "Workspace name"),
+        ("channel" = String, Path, description = "Slack channel id"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Rule removed"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or the channel has no rule"),
+    )
+)]
+pub async fn delete_channel_rule(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path((workspace_name, channel)): Path<(String, String)>,
+) -> Result<StatusCode, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let deleted = ChannelRulesRepo::new(state.database.clone())
+        .delete(&workspace.id, &channel)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete channel rule: {}", e);
+            APIError::InternalServerError("Failed to delete channel rule".to_string())
+        })?;
+
+    if !deleted {
+        return Err(APIError::NotFound("The channel has no rule".to_string()));
+    }
+
+    info!(
+        "User {} removed the rule of channel {} in workspace {}",
+        person.email, channel, workspace_name
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,219 @@
+use std::{collections::HashSet, sync::Arc};
+
+use axum::extract::{Path, State};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    models::{person::Model as Person, workspace::Model as Workspace},
+    repos::workspace_settings::WorkspaceSettingsRepo,
+    services::workspace::get_cached_workspace_tokens,
+    sockets::slack_api::{SlackChannel, SlackClient},
+    utils::{
+        json::Json,
+        response::{APIError, FieldError},
+    },
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WorkspaceChannel {
+    /// Slack channel id
+    pub id: String,
+    pub name: String,
+    /// Whether the bot is in the channel and sees its reactions
+    pub is_member: bool,
+    /// Whether the bot joins the channel whenever it connects
+    pub auto_join: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WorkspaceChannelListResponse {
+    pub channels: Vec<WorkspaceChannel>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct JoinChannelsRequest {
+    /// Ids of public channels; replaces the earlier selection. Channels left out
+    /// are not left, the bot only stops joining them.
+    pub channels: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChannelJoinFailure {
+    pub channel: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JoinChannelsResponse {
+    /// Public channels of the workspace after joining
+    pub channels: Vec<WorkspaceChannel>,
+    /// Selected channels Slack did not let the bot join
+    pub failed: Vec<ChannelJoinFailure>,
+}
+
+async fn slack_client(state: &AppState, workspace: &Workspace) -> Result<SlackClient, APIError> {
+    let tokens = get_cached_workspace_tokens(
+        &state.cache,
+        &state.database,
+        &workspace.id,
+        &state.config.encryption_key,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to load tokens of {}: {}", workspace.name, e);
+        APIError::InternalServerError("Failed to load workspace tokens".to_string())
+    })?;
+    Ok(SlackClient::http(tokens.bot_token))
+}
+
+async fn public_channels(slack: &SlackClient) -> Result<Vec<SlackChannel>, APIError> {
+    slack.list_public_channels().await.map_err(|e| {
+        warn!("Failed to list Slack channels: {}", e);
+        APIError::BadRequest(format!("Cannot list Slack channels: {}", e))
+    })
+}
+
+fn channel_responses(channels: Vec<SlackChannel>, auto_join: &[String]) -> Vec<WorkspaceChannel> {
+    let mut channels: Vec<WorkspaceChannel> = channels
+        .into_iter()
+        .map(|channel| WorkspaceChannel {
+            auto_join: auto_join.contains(&channel.id),
+            id: channel.id,
+            name: channel.name,
+            is_member: channel.is_member,
+        })
+        .collect();
+    channels.sort_by(|a, b| a.name.cmp(&b.name));
+    channels
+}
+
+/// Public Slack channels of a workspace, with those the bot is in or joins -
+/// REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/channels",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Public channels by name", body = WorkspaceChannelListResponse),
+        (status = 400, description = "Slack refused to list the channels"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn list_workspace_channels(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<WorkspaceChannelListResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let auto_join = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_auto_join_channels(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load auto-join channels: {}", e);
+            APIError::InternalServerError("Failed to load auto-join channels".to_string())
+        })?;
+    let channels = public_channels(&slack_client(&state, &workspace).await?).await?;
+
+    Ok(Json(WorkspaceChannelListResponse {
+        channels: channel_responses(channels, &auto_join),
+    }))
+}
+
+/// Have the bot join public channels, so reactions in them reach Slacker, and
+/// join them again whenever it connects - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/{workspace_name}/channels/join",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = JoinChannelsRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Channels joined", body = JoinChannelsResponse),
+        (status = 400, description = "Slack refused to list the channels"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 422, description = "Not a public channel of the workspace"),
+    )
+)]
+pub async fn join_workspace_channels(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<JoinChannelsRequest>,
+) -> Result<Json<JoinChannelsResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let slack = slack_client(&state, &workspace).await?;
+    let mut channels = public_channels(&slack).await?;
+
+    let public: HashSet<&str> = channels.iter().map(|c| c.id.as_str()).collect();
+    let mut selected: Vec<String> = Vec::new();
+    let mut errors = Vec::new();
+    for (index, channel) in payload.channels.iter().enumerate() {
+        let channel = channel.trim();
+        if !public.contains(channel) {
+            errors.push(FieldError {
+                field: format!("channels[{}]", index),
+                message: "Not a public channel of the workspace".to_string(),
+            });
+        } else if !selected.iter().any(|id| id == channel) {
+            selected.push(channel.to_string());
+        }
+    }
+    if !errors.is_empty() {
+        return Err(APIError::UnprocessableEntity(errors));
+    }
+
+    let mut failed = Vec::new();
+    for channel in channels.iter_mut() {
+        if !selected.contains(&channel.id) || channel.is_member {
+            continue;
+        }
+        match slack.join_channel(&channel.id).await {
+            Ok(()) => channel.is_member = true,
+            Err(e) => {
+                warn!("Failed to join channel {}: {}", channel.name, e);
+                failed.push(ChannelJoinFailure {
+                    channel: channel.id.clone(),
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    WorkspaceSettingsRepo::new(state.database.clone())
+        .update_auto_join_channels(&workspace.id, selected.clone())
+        .await
+        .map_err(|e| {
+            error!("Failed to save auto-join channels: {}", e);
+            APIError::InternalServerError("Failed to save auto-join channels".to_string())
+        })?;
+
+    info!(
+        "User {} set {} auto-join channel(s) in workspace {}",
+        person.email,
+        selected.len(),
+        workspace_name
+    );
+    Ok(Json(JoinChannelsResponse {
+        channels: channel_responses(channels, &selected),
+        failed,
+    }))
+}
@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::{
+    core::state::AppState,
+    models::person::Model as Person,
+    repos::workspaces::WorkspacesRepo,
+    services::database_backups::{self, BackupFile, RestoreReport},
+    utils::{json::Json, response::APIError},
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BackupListResponse {
+    /// Newest first
+    pub backups: Vec<BackupFile>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RestoreBackupRequest {
+    /// Name of a backup, e.g. `slacker-backup-20260316T040000Z.ndjson`
+    pub name: String,
+}
+
+/// Backups of the database in the configured storage - REQUIRES SUPER ADMIN
+#[utoipa::path(
+    get,
+    path = "/api/admin/backups",
+    tag = "admins",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Stored backups", body = BackupListResponse),
+        (status = 400, description = "No backup storage is configured"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires super admin"),
+    )
+)]
+pub async fn list_backups(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<BackupListResponse>, APIError> {
+    check_backups_available(&state, &person)?;
+
+    let backups = database_backups::list(&state.config.backup)
+        .await
+        .map_err(|e| {
+            error!("Failed to list backups: {}", e);
+            APIError::InternalServerError("Failed to list backups".to_string())
+        })?;
+    Ok(Json(BackupListResponse { backups }))
+}
+
+/// Back up every table of the database to the configured directory or S3
+/// bucket. Also accepted in maintenance mode - REQUIRES SUPER ADMIN
+#[utoipa::path(
+    post,
+    path = "/api/admin/backups",
+    tag = "admins",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Backup written", body = BackupFile),
+        (status = 400, description = "No backup storage is configured, or the database is not Postgres"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires super admin"),
+    )
+)]
+pub async fn create_backup(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<BackupFile>, APIError> {
+    check_backups_available(&state, &person)?;
+
+    info!("User {} is backing up the database", person.email);
+    let backup = database_backups::create(&state.database, &state.config.backup)
+        .await
+        .map_err(|e| {
+            error!("Failed to back up the database: {}", e);
+            APIError::InternalServerError("Failed to back up the database".to_string())
+        })?;
+    Ok(Json(backup))
+}
+
+/// Replace all data with a backup. Only accepted in maintenance mode, so nothing
+/// is written meanwhile; turn it on for every instance first - REQUIRES SUPER
+/// ADMIN
+#[utoipa::path(
+    post,
+    path = "/api/admin/backups/restore",
+    tag = "admins",
+    request_body = RestoreBackupRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Backup restored", body = RestoreReport),
+        (status = 400, description = "No backup storage is configured, or the database is not Postgres"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires super admin"),
+        (status = 404, description = "Backup not found"),
+        (status = 409, description = "Maintenance mode is off"),
+    )
+)]
+pub async fn restore_backup(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Json(payload): Json<RestoreBackupRequest>,
+) -> Result<Json<RestoreReport>, APIError> {
+    check_backups_available(&state, &person)?;
+    if !state.maintenance.is_enabled() {
+        return Err(APIError::Conflict(
+            "Turn on maintenance mode before restoring a backup".to_string(),
+        ));
+    }
+
+    let backups = database_backups::list(&state.config.backup)
+        .await
+        .map_err(|e| {
+            error!("Failed to list backups: {}", e);
+            APIError::InternalServerError("Failed to list backups".to_string())
+        })?;
+    if !backups.iter().any(|backup| backup.name == payload.name) {
+        return Err(APIError::NotFound("Backup not found".to_string()));
+    }
+
+    info!(
+        "User {} is restoring the database from {}",
+        person.email, payload.name
+    );
+    let report = database_backups::restore(&state.database, &state.config.backup, &payload.name)
+        .await
+        .map_err(|e| {
+            error!("Failed to restore {}: {}", payload.name, e);
+            APIError::InternalServerError(format!("Failed to restore the backup: {}", e))
+        })?;
+
+    // Cached workspaces and boards describe the data that was replaced
+    if let Ok(workspaces) = WorkspacesRepo::new(state.database.clone()).get_all().await {
+        for workspace in workspaces {
+            state.cache.invalidate_workspace(&workspace.id).await;
+        }
+    }
+    Ok(Json(report))
+}
+
+fn check_backups_available(state: &AppState, person: &Person) -> Result<(), APIError> {
+    if person.email != state.config.admin_email {
+        return Err(APIError::Forbidden);
+    }
+    match database_backups::unavailable_reason(&state.database, &state.config.backup) {
+        Some(reason) => Err(APIError::BadRequest(reason)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    integrations::discord,
+    models::{person::Model as Person, workspace_settings::DiscordSettings},
+    repos::workspace_settings::WorkspaceSettingsRepo,
+    utils::{
+        encryption::encrypt,
+        json::{Json, ValidatedJson},
+        response::{APIError, FieldError},
+        validation,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct UpdateDiscordConnectionRequest {
+    /// Incoming webhook of the Discord channel, from its Integrations settings
+    #[validate(custom(function = "validation::https_url"))]
+    pub webhook_url: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DiscordConnectionResponse {
+    pub webhook_name: String,
+}
+
+impl From<DiscordSettings> for DiscordConnectionResponse {
+    fn from(settings: DiscordSettings) -> Self {
+        Self {
+            webhook_name: settings.webhook_name,
+        }
+    }
+}
+
+/// Discord channel following a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/discord",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Discord connection, without the webhook URL", body = DiscordConnectionResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or Discord not connected"),
+    )
+)]
+pub async fn get_discord_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<DiscordConnectionResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_discord_settings(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load Discord connection: {}", e);
+            APIError::InternalServerError("Failed to load Discord connection".to_string())
+        })?
+        .ok_or_else(|| APIError::NotFound("Discord is not connected".to_string()))?;
+
+    Ok(Json(settings.into()))
+}
+
+/// Have a Discord channel follow the workspace's task activity, or replace the
+/// channel. Created and moved tasks are posted there unless the workspace routes
+/// task activity elsewhere - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/discord",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = UpdateDiscordConnectionRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Saved Discord connection", body = DiscordConnectionResponse),
+        (status = 400, description = "Discord does not know the webhook"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 422, description = "Not a Discord webhook URL"),
+    )
+)]
+pub async fn update_discord_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    ValidatedJson(payload): ValidatedJson<UpdateDiscordConnectionRequest>,
+) -> Result<Json<DiscordConnectionResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let webhook_url = payload.webhook_url.trim();
+    if !discord::is_webhook_url(webhook_url) {
+        return Err(APIError::UnprocessableEntity(vec![FieldError {
+            field: "webhook_url".to_string(),
+            message: "must be a Discord webhook URL".to_string(),
+        }]));
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+
+    let webhook_name = discord::webhook_name(&Client::new(), webhook_url)
+        .await
+        .map_err(|e| {
+            warn!(
+                "Discord webhook of {} cannot be used: {}",
+                workspace_name, e
+            );
+            APIError::BadRequest(format!("Cannot use Discord webhook: {}", e))
+        })?;
+
+    let encrypted_url = encrypt(webhook_url, &state.config.encryption_key).map_err(|e| {
+        error!("Failed to encrypt Discord webhook: {}", e);
+        APIError::InternalServerError("Failed to save Discord connection".to_string())
+    })?;
+
+    info!(
+        "User {} connecting workspace {} to Discord webhook {}",
+        person.email, workspace_name, webhook_name
+    );
+
+    let settings = DiscordSettings {
+        webhook_url: encrypted_url,
+        webhook_name,
+    };
+    WorkspaceSettingsRepo::new(state.database.clone())
+        .update_discord(&workspace.id, Some(settings.clone()))
+        .await
+        .map_err(|e| {
+            error!("Failed to save Discord connection: {}", e);
+            APIError::InternalServerError("Failed to save Discord connection".to_string())
+        })?;
+
+    Ok(Json(settings.into()))
+}
+
+/// Stop posting a workspace's task activity to Discord - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_name}/discord",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Discord disconnected"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or Discord not connected"),
+    )
+)]
+pub async fn delete_discord_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<StatusCode, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings_repo = WorkspaceSettingsRepo::new(state.database.clone());
+    let connected = settings_repo
+        .get_discord_settings(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load Discord connection: {}", e);
+            APIError::InternalServerError("Failed to load Discord connection".to_string())
+        })?
+        .is_some();
+    if !connected {
+        return Err(APIError::NotFound("Discord is not connected".to_string()));
+    }
+
+    settings_repo
+        .update_discord(&workspace.id, None)
+        .await
+        .map_err(|e| {
+            error!("Failed to disconnect Discord: {}", e);
+            APIError::InternalServerError("Failed to disconnect Discord".to_string())
+        })?;
+
+    info!(
+        "User {} disconnected workspace {} from Discord",
+        person.email, workspace_name
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,217 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Form,
+};
+use serde::Serialize;
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    integrations::email::{self, InboundEmail},
+    models::person::Model as Person,
+    repos::workspace_settings::WorkspaceSettingsRepo,
+    utils::{json::Json, response::APIError},
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InboundEmailAddressResponse {
+    /// Emails sent here by linked members become tasks assigned to them
+    pub address: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InboundEmailResult {
+    /// Task created from the email; null when the sender is not a linked member
+    pub task_id: Option<String>,
+}
+
+fn inbound_email_domain(state: &AppState) -> Result<&str, APIError> {
+    state
+        .config
+        .inbound_email_domain
+        .as_deref()
+        .ok_or_else(|| APIError::BadRequest("Inbound email is not configured".to_string()))
+}
+
+/// Inbound email address of a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/inbound-email",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Inbound address", body = InboundEmailAddressResponse),
+        (status = 400, description = "Inbound email is not configured on this server"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or it has no address"),
+    )
+)]
+pub async fn get_inbound_email_address(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<InboundEmailAddressResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+    let domain = inbound_email_domain(&state)?;
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let token = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_by_workspace(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load workspace settings: {}", e);
+            APIError::InternalServerError("Failed to load workspace settings".to_string())
+        })?
+        .and_then(|settings| settings.inbound_email_token)
+        .ok_or_else(|| APIError::NotFound("The workspace has no inbound address".to_string()))?;
+
+    Ok(Json(InboundEmailAddressResponse {
+        address: format!("{}@{}", token, domain),
+    }))
+}
+
+/// Give a workspace a new inbound email address; the previous one stops working -
+/// REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/{workspace_name}/inbound-email",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "New inbound address", body = InboundEmailAddressResponse),
+        (status = 400, description = "Inbound email is not configured on this server"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn rotate_inbound_email_address(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<InboundEmailAddressResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+    let domain = inbound_email_domain(&state)?;
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    // Lower case only, mail servers may change the case of the local part
+    let token = nanoid::nanoid!(24).to_lowercase();
+    WorkspaceSettingsRepo::new(state.database.clone())
+        .set_inbound_email_token(&workspace.id, Some(token.clone()))
+        .await
+        .map_err(|e| {
+            error!("Failed to save inbound email address: {}", e);
+            APIError::InternalServerError("Failed to save inbound email address".to_string())
+        })?;
+
+    info!(
+        "User {} created a new inbound email address for workspace {}",
+        person.email, workspace_name
+    );
+    Ok(Json(InboundEmailAddressResponse {
+        address: format!("{}@{}", token, domain),
+    }))
+}
+
+/// Turn off email-in for a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_name}/inbound-email",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Address removed"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn delete_inbound_email_address(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<StatusCode, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    WorkspaceSettingsRepo::new(state.database.clone())
+        .set_inbound_email_token(&workspace.id, None)
+        .await
+        .map_err(|e| {
+            error!("Failed to remove inbound email address: {}", e);
+            APIError::InternalServerError("Failed to remove inbound email address".to_string())
+        })?;
+
+    info!(
+        "User {} turned off inbound email for workspace {}",
+        person.email, workspace_name
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Receives emails forwarded by a Mailgun inbound route; each becomes a task
+/// assigned to its sender
+#[utoipa::path(
+    post,
+    path = "/api/integrations/email/inbound",
+    tag = "integrations",
+    responses(
+        (status = 200, description = "Email processed or ignored", body = InboundEmailResult),
+        (status = 401, description = "Invalid signature"),
+        (status = 404, description = "Inbound email is not configured or the address is unknown"),
+    )
+)]
+pub async fn inbound_email(
+    State(state): State<Arc<AppState>>,
+    Form(payload): Form<InboundEmail>,
+) -> Result<Json<InboundEmailResult>, APIError> {
+    let (Some(domain), Some(signing_key)) = (
+        state.config.inbound_email_domain.as_deref(),
+        state.config.mailgun_signing_key.as_deref(),
+    ) else {
+        return Err(APIError::NotFound(
+            "Inbound email is not configured".to_string(),
+        ));
+    };
+
+    if !email::verify_signature(signing_key, &payload) {
+        return Err(APIError::UnAuthorized);
+    }
+
+    let not_found = || APIError::NotFound("Unknown inbound address".to_string());
+    let token = email::recipient_token(&payload.recipient, domain).ok_or_else(not_found)?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_by_inbound_email_token(&token.to_lowercase())
+        .await
+        .map_err(|e| {
+            error!("Failed to load workspace settings: {}", e);
+            APIError::InternalServerError("Failed to load workspace settings".to_string())
+        })?
+        .ok_or_else(not_found)?;
+
+    let task = email::create_task(&state, &settings.workspace_id, payload)
+        .await
+        .map_err(|e| {
+            error!("Failed to create task from email: {}", e);
+            APIError::InternalServerError("Failed to create task".to_string())
+        })?;
+
+    Ok(Json(InboundEmailResult {
+        task_id: task.map(|task| task.id),
+    }))
+}
@@ -0,0 +1,290 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    integrations::github::{self, GithubWebhook},
+    models::{github_repository::Model as GithubRepository, person::Model as Person},
+    repos::github_repositories::GithubRepositoriesRepo,
+    utils::{
+        encryption::{decrypt, encrypt},
+        json::Json,
+        response::APIError,
+    },
+};
+
+const WEBHOOK_PATH: &str = "/api/integrations/github/webhook";
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConnectGithubRepositoryRequest {
+    /// "owner/repo"
+    pub repository: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GithubRepositoryInfo {
+    pub id: String,
+    pub repository: String,
+    pub created_at: String,
+}
+
+impl From<GithubRepository> for GithubRepositoryInfo {
+    fn from(repository: GithubRepository) -> Self {
+        Self {
+            id: repository.id,
+            repository: repository.full_name,
+            created_at: repository.created_at.and_utc().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GithubRepositoryListResponse {
+    pub repositories: Vec<GithubRepositoryInfo>,
+    /// Payload URL to use for every repository, on the server's public URL
+    pub webhook_path: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConnectGithubRepositoryResponse {
+    pub repository: GithubRepositoryInfo,
+    /// Add a webhook to the repository with this payload URL, on the server's
+    /// public URL, content type application/json and the "Issues" and
+    /// "Pull requests" events
+    pub webhook_path: String,
+    /// Secret of that webhook; only shown here
+    pub webhook_secret: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GithubWebhookResponse {
+    /// Tasks completed by this delivery
+    pub completed_tasks: Vec<String>,
+}
+
+/// GitHub repositories connected to a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/github",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Connected repositories", body = GithubRepositoryListResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn list_github_repositories(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<GithubRepositoryListResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let repositories = GithubRepositoriesRepo::new(state.database.clone())
+        .get_by_workspace(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load GitHub repositories: {}", e);
+            APIError::InternalServerError("Failed to load GitHub repositories".to_string())
+        })?;
+
+    Ok(Json(GithubRepositoryListResponse {
+        repositories: repositories.into_iter().map(Into::into).collect(),
+        webhook_path: WEBHOOK_PATH.to_string(),
+    }))
+}
+
+/// Connect a GitHub repository to a workspace so its pull requests can complete
+/// tasks - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/{workspace_name}/github",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = ConnectGithubRepositoryRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Repository connected, with the webhook to add on GitHub", body = ConnectGithubRepositoryResponse),
+        (status = 400, description = "Invalid or already connected repository"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn connect_github_repository(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<ConnectGithubRepositoryRequest>,
+) -> Result<Json<ConnectGithubRepositoryResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let full_name = payload.repository.trim().to_lowercase();
+    let valid = full_name
+        .split_once('/')
+        .is_some_and(|(owner, repo)| !owner.is_empty() && !repo.is_empty() && !repo.contains('/'));
+    if !valid {
+        return Err(APIError::BadRequest(
+            "Repository must look like owner/repo".to_string(),
+        ));
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let repo = GithubRepositoriesRepo::new(state.database.clone());
+    let existing = repo.get_by_full_name(&full_name).await.map_err(|e| {
+        error!("Failed to load GitHub repository: {}", e);
+        APIError::InternalServerError("Failed to connect GitHub repository".to_string())
+    })?;
+    if existing.is_some() {
+        return Err(APIError::BadRequest(format!(
+            "{} is already connected to a workspace",
+            full_name
+        )));
+    }
+
+    let webhook_secret = nanoid::nanoid!(32);
+    let encrypted_secret = encrypt(&webhook_secret, &state.config.encryption_key).map_err(|e| {
+        error!("Failed to encrypt webhook secret: {}", e);
+        APIError::InternalServerError("Failed to connect GitHub repository".to_string())
+    })?;
+
+    let repository = repo
+        .create(&workspace.id, &full_name, encrypted_secret)
+        .await
+        .map_err(|e| {
+            error!("Failed to save GitHub repository: {}", e);
+            APIError::InternalServerError("Failed to connect GitHub repository".to_string())
+        })?;
+
+    info!(
+        "User {} connected GitHub repository {} to workspace {}",
+        person.email, full_name, workspace_name
+    );
+
+    Ok(Json(ConnectGithubRepositoryResponse {
+        repository: repository.into(),
+        webhook_path: WEBHOOK_PATH.to_string(),
+        webhook_secret,
+    }))
+}
+
+/// Disconnect a GitHub repository from a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_name}/github/{repository_id}",
+    tag = "workspaces",
+    params(
+        ("workspace_name" = String, Path, description = "Workspace name"),
+        ("repository_id" = String, Path, description = "Id of the connected repository"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Repository disconnected"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace or repository not found"),
+    )
+)]
+pub async fn disconnect_github_repository(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path((workspace_name, repository_id)): Path<(String, String)>,
+) -> Result<StatusCode, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let deleted = GithubRepositoriesRepo::new(state.database.clone())
+        .delete(&workspace.id, &repository_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete GitHub repository: {}", e);
+            APIError::InternalServerError("Failed to disconnect GitHub repository".to_string())
+        })?;
+
+    if !deleted {
+        return Err(APIError::NotFound("Repository not connected".to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Receives GitHub "Issues" and "Pull requests" webhooks. Mentions of
+/// `SLK-<task id>` link the task; merging a pull request that mentions a task
+/// completes it and replies in its Slack thread.
+#[utoipa::path(
+    post,
+    path = "/api/integrations/github/webhook",
+    tag = "integrations",
+    responses(
+        (status = 200, description = "Event processed", body = GithubWebhookResponse),
+        (status = 400, description = "Malformed payload"),
+        (status = 401, description = "Invalid signature"),
+        (status = 404, description = "Repository not connected"),
+    )
+)]
+pub async fn github_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<GithubWebhookResponse>, APIError> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let event = header("x-github-event");
+    let signature = header("x-hub-signature-256");
+
+    let payload: GithubWebhook = serde_json::from_slice(&body)
+        .map_err(|e| APIError::BadRequest(format!("Invalid payload: {}", e)))?;
+
+    // The repository picks the secret the delivery must be signed with
+    let repository = GithubRepositoriesRepo::new(state.database.clone())
+        .get_by_full_name(&payload.repository.full_name)
+        .await
+        .map_err(|e| {
+            error!("Failed to load GitHub repository: {}", e);
+            APIError::InternalServerError("Failed to load GitHub repository".to_string())
+        })?
+        .ok_or_else(|| APIError::NotFound("Repository not connected".to_string()))?;
+
+    let secret =
+        decrypt(&repository.webhook_secret, &state.config.encryption_key).map_err(|e| {
+            error!("Failed to decrypt webhook secret: {}", e);
+            APIError::InternalServerError("Failed to verify webhook".to_string())
+        })?;
+    if !github::verify_signature(&secret, &body, &signature) {
+        return Err(APIError::UnAuthorized);
+    }
+
+    let completed = github::handle_event(&state, &repository, &event, payload)
+        .await
+        .map_err(|e| {
+            error!("Failed to process GitHub webhook: {}", e);
+            APIError::InternalServerError("Failed to process webhook".to_string())
+        })?;
+
+    Ok(Json(GithubWebhookResponse {
+        completed_tasks: completed.into_iter().map(|task| task.id).collect(),
+    }))
+}
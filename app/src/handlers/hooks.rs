@@ -0,0 +1,380 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::is_read_only, workspaces::find_workspace},
+    models::{
+        hook_subscription::{HookEvent, Model as HookSubscription},
+        person::Model as Person,
+        workspace::Model as Workspace,
+    },
+    repos::{
+        hook_subscriptions::HookSubscriptionsRepo, workspace_links::WorkspaceLinksRepo,
+        workspaces::WorkspacesRepo,
+    },
+    services::hooks::{self, sample_payload, HookPayload},
+    utils::{
+        encryption::encrypt,
+        json::{Json, ValidatedJson},
+        response::APIError,
+        validation,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct SubscribeHookRequest {
+    /// URL each event is POSTed to; answering 410 Gone unsubscribes it
+    #[validate(custom(function = "validation::http_url"))]
+    pub target_url: String,
+    pub event: HookEvent,
+    /// Workspace whose tasks to follow, defaults to your active workspace
+    pub workspace: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SubscribeHookResponse {
+    #[serde(flatten)]
+    pub hook: HookSubscription,
+    /// Signs every delivery; only shown here and when rotated
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HookSecretResponse {
+    /// Deliveries are signed with this from now on
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HookTestResponse {
+    /// Status the target URL answered
+    pub status: u16,
+    /// The target answered with a 2xx status
+    pub delivered: bool,
+    /// The delivery carried a signature; false for hooks without a secret yet
+    pub signed: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HookListResponse {
+    pub hooks: Vec<HookSubscription>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SampleHookQuery {
+    pub event: HookEvent,
+}
+
+/// The workspace a hook follows: the named one, which the person must be linked
+/// to, or their active workspace
+async fn hook_workspace(
+    state: &AppState,
+    person: &Person,
+    name: Option<&str>,
+) -> Result<Workspace, APIError> {
+    let links_repo = WorkspaceLinksRepo::new(state.database.clone());
+
+    let workspace = match name {
+        Some(name) => find_workspace(state, name).await?,
+        None => {
+            let link = links_repo
+                .get_active_workspace(person.id.clone())
+                .await
+                .map_err(|_| APIError::BadRequest("No active workspace".to_string()))?;
+            WorkspacesRepo::new(state.database.clone())
+                .get(&link.workspace_id)
+                .await?
+        }
+    };
+
+    let linked = links_repo
+        .get_by_person_and_workspace(person.id.clone(), workspace.id.clone())
+        .await
+        .is_ok_and(|link| link.is_linked);
+    if !linked {
+        return Err(APIError::Forbidden);
+    }
+
+    Ok(workspace)
+}
+
+/// One of the person's hooks
+async fn own_hook(
+    state: &AppState,
+    person: &Person,
+    hook_id: &str,
+) -> Result<HookSubscription, APIError> {
+    let subscription = HookSubscriptionsRepo::new(state.database.clone())
+        .get(hook_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load hook subscription: {}", e);
+            APIError::InternalServerError("Failed to load hook".to_string())
+        })?;
+
+    subscription
+        .filter(|subscription| subscription.person_id == person.id)
+        .ok_or_else(|| APIError::NotFound("Hook not found".to_string()))
+}
+
+fn encrypt_secret(state: &AppState, secret: &str) -> Result<String, APIError> {
+    encrypt(secret, &state.config.encryption_key).map_err(|e| {
+        error!("Failed to encrypt hook secret: {}", e);
+        APIError::InternalServerError("Failed to store hook secret".to_string())
+    })
+}
+
+/// Subscribe a URL to task events of a workspace (REST hooks, as used by Zapier and
+/// Make). Deliveries carry `X-Slacker-Timestamp` and `X-Slacker-Signature`
+/// headers; the signature is `sha256=` and the hex HMAC-SHA256, keyed with the
+/// returned secret, of `<timestamp>.<raw body>`
+#[utoipa::path(
+    post,
+    path = "/api/hooks/subscribe",
+    tag = "hooks",
+    request_body = SubscribeHookRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Subscribed, with the signing secret", body = SubscribeHookResponse),
+        (status = 400, description = "No active workspace"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Not linked to the workspace, or only as a viewer"),
+        (status = 404, description = "Workspace not found"),
+        (status = 422, description = "Invalid target URL"),
+    )
+)]
+pub async fn subscribe_hook(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    ValidatedJson(payload): ValidatedJson<SubscribeHookRequest>,
+) -> Result<(StatusCode, Json<SubscribeHookResponse>), APIError> {
+    let target_url = payload.target_url.trim();
+
+    let workspace = hook_workspace(&state, &person, payload.workspace.as_deref()).await?;
+    // The workspace is in the body, out of reach of `reject_viewer_writes`
+    if is_read_only(&state, &person, &workspace.id).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let secret = hooks::new_secret();
+    let encrypted_secret = encrypt_secret(&state, &secret)?;
+    let subscription = HookSubscriptionsRepo::new(state.database.clone())
+        .create(
+            &person.id,
+            &workspace.id,
+            payload.event,
+            target_url,
+            encrypted_secret,
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to create hook subscription: {}", e);
+            APIError::InternalServerError("Failed to subscribe".to_string())
+        })?;
+
+    info!(
+        "User {} subscribed hook {} to {:?} in {}",
+        person.email, subscription.id, subscription.event, workspace.name
+    );
+    Ok((
+        StatusCode::CREATED,
+        Json(SubscribeHookResponse {
+            hook: subscription,
+            secret,
+        }),
+    ))
+}
+
+/// Your REST hook subscriptions
+#[utoipa::path(
+    get,
+    path = "/api/hooks",
+    tag = "hooks",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Subscriptions", body = HookListResponse),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
+pub async fn list_hooks(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<HookListResponse>, APIError> {
+    let hooks = HookSubscriptionsRepo::new(state.read_database.clone())
+        .get_by_person(&person.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to list hook subscriptions: {}", e);
+            APIError::InternalServerError("Failed to list hooks".to_string())
+        })?;
+
+    Ok(Json(HookListResponse { hooks }))
+}
+
+/// Unsubscribe one of your REST hooks
+#[utoipa::path(
+    delete,
+    path = "/api/hooks/{hook_id}",
+    tag = "hooks",
+    params(("hook_id" = String, Path, description = "Subscription id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Unsubscribed"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "No such subscription of yours"),
+    )
+)]
+pub async fn unsubscribe_hook(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(hook_id): Path<String>,
+) -> Result<StatusCode, APIError> {
+    let deleted = HookSubscriptionsRepo::new(state.database.clone())
+        .delete(&person.id, &hook_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete hook subscription: {}", e);
+            APIError::InternalServerError("Failed to unsubscribe".to_string())
+        })?;
+
+    if !deleted {
+        return Err(APIError::NotFound("Hook not found".to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Example payloads of an event, for setting up an automation before one happens
+#[utoipa::path(
+    get,
+    path = "/api/hooks/sample",
+    tag = "hooks",
+    params(SampleHookQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Sample payloads", body = Vec<HookPayload>),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
+pub async fn sample_hook(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Query(query): Query<SampleHookQuery>,
+) -> Result<Json<Vec<HookPayload>>, APIError> {
+    let workspace = hook_workspace(&state, &person, None)
+        .await
+        .map(|workspace| workspace.name)
+        .unwrap_or_else(|_| "sample-workspace".to_string());
+
+    Ok(Json(vec![sample_payload(query.event, &workspace)]))
+}
+
+/// POST a signed sample payload of the hook's event to its target URL, to check
+/// the receiver before real events arrive. A 410 Gone answer does not unsubscribe
+#[utoipa::path(
+    post,
+    path = "/api/hooks/{hook_id}/test",
+    tag = "hooks",
+    params(("hook_id" = String, Path, description = "Subscription id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "What the target answered", body = HookTestResponse),
+        (status = 400, description = "The target URL could not be reached"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "No such subscription of yours"),
+    )
+)]
+pub async fn test_hook(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(hook_id): Path<String>,
+) -> Result<Json<HookTestResponse>, APIError> {
+    let subscription = own_hook(&state, &person, &hook_id).await?;
+    let secret =
+        hooks::signing_secret(&subscription, &state.config.encryption_key).map_err(|e| {
+            error!("Failed to decrypt hook secret: {}", e);
+            APIError::InternalServerError("Failed to sign the test delivery".to_string())
+        })?;
+
+    let workspace = WorkspacesRepo::new(state.database.clone())
+        .get(&subscription.workspace_id)
+        .await
+        .map(|workspace| workspace.name)
+        .unwrap_or_else(|_| "sample-workspace".to_string());
+    let body = serde_json::to_value(sample_payload(subscription.event.clone(), &workspace))
+        .map_err(|e| {
+            error!("Failed to serialize sample payload: {}", e);
+            APIError::InternalServerError("Failed to build the test delivery".to_string())
+        })?;
+
+    let response = hooks::deliver(
+        &Client::new(),
+        &subscription.target_url,
+        &body,
+        secret.as_deref(),
+    )
+    .await
+    .map_err(|e| {
+        warn!("Test delivery of hook {} failed: {}", hook_id, e);
+        APIError::BadRequest(format!("Test delivery failed: {}", e))
+    })?;
+
+    info!(
+        "User {} tested hook {}, target answered {}",
+        person.email,
+        hook_id,
+        response.status()
+    );
+    Ok(Json(HookTestResponse {
+        status: response.status().as_u16(),
+        delivered: response.status().is_success(),
+        signed: secret.is_some(),
+    }))
+}
+
+/// Replace the signing secret of a hook; deliveries from now on, including queued
+/// ones, are signed with the new one. Also gives older hooks their first secret
+#[utoipa::path(
+    post,
+    path = "/api/hooks/{hook_id}/secret/rotate",
+    tag = "hooks",
+    params(("hook_id" = String, Path, description = "Subscription id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The new secret", body = HookSecretResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "No such subscription of yours"),
+    )
+)]
+pub async fn rotate_hook_secret(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(hook_id): Path<String>,
+) -> Result<Json<HookSecretResponse>, APIError> {
+    let subscription = own_hook(&state, &person, &hook_id).await?;
+
+    let secret = hooks::new_secret();
+    let encrypted_secret = encrypt_secret(&state, &secret)?;
+    HookSubscriptionsRepo::new(state.database.clone())
+        .set_secret(subscription, encrypted_secret)
+        .await
+        .map_err(|e| {
+            error!("Failed to rotate hook secret: {}", e);
+            APIError::InternalServerError("Failed to rotate the secret".to_string())
+        })?;
+
+    info!(
+        "User {} rotated the secret of hook {}",
+        person.email, hook_id
+    );
+    Ok(Json(HookSecretResponse { secret }))
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::Redirect,
+};
+use tracing::{error, info};
+
+use crate::{
+    core::state::AppState, models::invitation::InvitationStatus,
+    repos::invitations::InvitationsRepo, utils::response::APIError,
+};
+
+/// Accept link sent by email. Linking happens when the invitee signs in with Google,
+/// so a valid invitation just starts the login flow.
+#[utoipa::path(
+    get,
+    path = "/api/invitations/{token}/accept",
+    tag = "workspaces",
+    params(("token" = String, Path, description = "Invitation token from the email")),
+    responses(
+        (status = 307, description = "Redirect to Google sign-in, or to the app if already accepted"),
+        (status = 400, description = "Invitation has expired"),
+        (status = 404, description = "Invitation not found"),
+    )
+)]
+pub async fn accept_invitation(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> Result<Redirect, APIError> {
+    let invitations_repo = InvitationsRepo::new(state.database.clone());
+    let invitation = invitations_repo.get_by_token(&token).await?;
+
+    match invitation.status {
+        InvitationStatus::Accepted => {
+            return Ok(Redirect::temporary(&state.config.frontend_url));
+        }
+        InvitationStatus::Expired => {
+            return Err(APIError::BadRequest("Invitation has expired".to_string()));
+        }
+        InvitationStatus::Pending if invitation.is_expired() => {
+            invitations_repo
+                .set_status(invitation, InvitationStatus::Expired)
+                .await
+                .map_err(|e| {
+                    error!("Failed to expire invitation: {}", e);
+                    APIError::InternalServerError("Failed to load invitation".to_string())
+                })?;
+            return Err(APIError::BadRequest("Invitation has expired".to_string()));
+        }
+        InvitationStatus::Pending => {}
+    }
+
+    info!("Invitation for {} opened, starting login", invitation.email);
+    Ok(Redirect::temporary("/api/auth/google"))
+}
@@ -0,0 +1,326 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::{
+    core::state::AppState,
+    handlers::{
+        admins::can_configure_workspaces, tasks::can_manage_task, workspaces::find_workspace,
+    },
+    integrations::jira::{self, JiraWebhook},
+    models::{jira_connection::Model as JiraConnection, person::Model as Person},
+    repos::{jira_connections::JiraConnectionsRepo, tasks::TasksRepo},
+    utils::{
+        encryption::encrypt,
+        json::{Json, ValidatedJson},
+        response::APIError,
+        validation,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct UpdateJiraConnectionRequest {
+    /// Jira Cloud site, e.g. "https://example.atlassian.net"
+    #[validate(custom(function = "validation::https_url"))]
+    pub base_url: String,
+    /// Account the API token belongs to
+    #[validate(email)]
+    pub email: String,
+    #[validate(custom(function = "validation::not_blank"))]
+    pub api_token: String,
+    /// Project new issues are created in, e.g. "OPS"
+    #[validate(custom(function = "validation::not_blank"))]
+    pub project_key: String,
+    /// Defaults to "Task"
+    pub issue_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JiraConnectionResponse {
+    pub base_url: String,
+    pub email: String,
+    pub project_key: String,
+    pub issue_type: String,
+    /// Register this path, on the server's public URL, as a Jira webhook for
+    /// "Issue updated" events so finished issues complete their tasks
+    pub webhook_path: String,
+    pub updated_at: String,
+}
+
+impl From<JiraConnection> for JiraConnectionResponse {
+    fn from(connection: JiraConnection) -> Self {
+        Self {
+            webhook_path: format!(
+                "/api/integrations/jira/webhook?token={}",
+                connection.webhook_secret
+            ),
+            base_url: connection.base_url,
+            email: connection.email,
+            project_key: connection.project_key,
+            issue_type: connection.issue_type,
+            updated_at: connection.updated_at.and_utc().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JiraIssueResponse {
+    pub issue_key: String,
+    pub issue_url: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct JiraWebhookQuery {
+    /// Webhook secret of the workspace's Jira connection
+    pub token: String,
+}
+
+async fn load_connection(
+    state: &AppState,
+    workspace_id: &str,
+) -> Result<Option<JiraConnection>, APIError> {
+    JiraConnectionsRepo::new(state.database.clone())
+        .get_by_workspace(workspace_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load Jira connection: {}", e);
+            APIError::InternalServerError("Failed to load Jira connection".to_string())
+        })
+}
+
+/// Jira connection of a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/jira",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Jira connection, without the API token", body = JiraConnectionResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or Jira not connected"),
+    )
+)]
+pub async fn get_jira_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<JiraConnectionResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let connection = load_connection(&state, &workspace.id)
+        .await?
+        .ok_or_else(|| APIError::NotFound("Jira is not connected".to_string()))?;
+
+    Ok(Json(connection.into()))
+}
+
+/// Connect a workspace to Jira or replace its connection - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/jira",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = UpdateJiraConnectionRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Saved Jira connection", body = JiraConnectionResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 422, description = "Invalid connection settings"),
+    )
+)]
+pub async fn update_jira_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    ValidatedJson(payload): ValidatedJson<UpdateJiraConnectionRequest>,
+) -> Result<Json<JiraConnectionResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let api_token =
+        encrypt(payload.api_token.trim(), &state.config.encryption_key).map_err(|e| {
+            error!("Failed to encrypt Jira token: {}", e);
+            APIError::InternalServerError("Failed to save Jira connection".to_string())
+        })?;
+
+    info!(
+        "User {} connecting workspace {} to Jira project {}",
+        person.email, workspace_name, payload.project_key
+    );
+
+    let connection = JiraConnectionsRepo::new(state.database.clone())
+        .upsert(JiraConnection {
+            id: String::new(),
+            workspace_id: workspace.id,
+            base_url: payload.base_url.trim_end_matches('/').to_string(),
+            email: payload.email.trim().to_string(),
+            api_token,
+            project_key: payload.project_key.trim().to_uppercase(),
+            issue_type: payload.issue_type.unwrap_or_else(|| "Task".to_string()),
+            webhook_secret: nanoid::nanoid!(32),
+            updated_at: chrono::Utc::now().naive_utc(),
+        })
+        .await
+        .map_err(|e| {
+            error!("Failed to save Jira connection: {}", e);
+            APIError::InternalServerError("Failed to save Jira connection".to_string())
+        })?;
+
+    Ok(Json(connection.into()))
+}
+
+/// Disconnect a workspace from Jira; tasks keep their issue keys - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_name}/jira",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Jira disconnected"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or Jira not connected"),
+    )
+)]
+pub async fn delete_jira_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<StatusCode, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let deleted = JiraConnectionsRepo::new(state.database.clone())
+        .delete_by_workspace(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete Jira connection: {}", e);
+            APIError::InternalServerError("Failed to delete Jira connection".to_string())
+        })?;
+
+    if !deleted {
+        return Err(APIError::NotFound("Jira is not connected".to_string()));
+    }
+
+    info!(
+        "User {} disconnected workspace {} from Jira",
+        person.email, workspace_name
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Create a Jira issue from a task in its workspace's Jira project. Allowed for the
+/// task's assignee, whoever assigned it and workspace admins.
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{task_id}/jira",
+    tag = "tasks",
+    params(("task_id" = String, Path, description = "Task id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Created issue", body = JiraIssueResponse),
+        (status = 400, description = "Task already has an issue or its workspace has no Jira connection"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Not involved in the task"),
+        (status = 404, description = "Task not found"),
+    )
+)]
+pub async fn create_jira_issue(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(task_id): Path<String>,
+) -> Result<Json<JiraIssueResponse>, APIError> {
+    let task = TasksRepo::new(state.database.clone())
+        .get(task_id)
+        .await
+        .map_err(|_| APIError::NotFound("Task not found".to_string()))?;
+
+    if !can_manage_task(&state, &person, &task).await {
+        return Err(APIError::Forbidden);
+    }
+
+    if let Some(issue_key) = &task.jira_issue_key {
+        return Err(APIError::BadRequest(format!(
+            "Task already has Jira issue {}",
+            issue_key
+        )));
+    }
+
+    let workspace_id = task
+        .workspace_id
+        .clone()
+        .ok_or_else(|| APIError::BadRequest("Task has no workspace".to_string()))?;
+    let connection = load_connection(&state, &workspace_id)
+        .await?
+        .ok_or_else(|| {
+            APIError::BadRequest("The task's workspace is not connected to Jira".to_string())
+        })?;
+
+    let task = jira::create_issue(&state, &connection, task)
+        .await
+        .map_err(|e| {
+            error!("Failed to create Jira issue: {}", e);
+            APIError::InternalServerError("Failed to create Jira issue".to_string())
+        })?;
+    let issue_key = task.jira_issue_key.unwrap_or_default();
+
+    Ok(Json(JiraIssueResponse {
+        issue_url: jira::issue_url(&connection, &issue_key),
+        issue_key,
+    }))
+}
+
+/// Receives Jira "Issue updated" webhooks; an issue moved to a done status
+/// completes its task
+#[utoipa::path(
+    post,
+    path = "/api/integrations/jira/webhook",
+    tag = "integrations",
+    params(JiraWebhookQuery),
+    responses(
+        (status = 200, description = "Event processed or ignored"),
+        (status = 401, description = "Unknown webhook token"),
+    )
+)]
+pub async fn jira_webhook(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<JiraWebhookQuery>,
+    Json(payload): Json<JiraWebhook>,
+) -> Result<StatusCode, APIError> {
+    let connection = JiraConnectionsRepo::new(state.database.clone())
+        .get_by_webhook_secret(&query.token)
+        .await
+        .map_err(|e| {
+            error!("Failed to load Jira connection: {}", e);
+            APIError::InternalServerError("Failed to load Jira connection".to_string())
+        })?
+        .ok_or(APIError::UnAuthorized)?;
+
+    match jira::handle_webhook(&state, &connection, payload).await {
+        Ok(Some(task)) => info!("[Jira] Issue done, completed task {}", task.id),
+        Ok(None) => {}
+        // Jira does not retry deliveries, so answering with an error gains nothing
+        Err(e) => warn!("[Jira] Failed to process webhook: {}", e),
+    }
+
+    Ok(StatusCode::OK)
+}
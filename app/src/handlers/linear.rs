@@ -0,0 +1,276 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    integrations::linear::{self, LinearClient, LinearWebhook},
+    models::{person::Model as Person, workspace_settings::LinearSettings},
+    repos::workspace_settings::WorkspaceSettingsRepo,
+    utils::{
+        encryption::{decrypt, encrypt},
+        json::{Json, ValidatedJson},
+        response::APIError,
+        validation,
+    },
+};
+
+const WEBHOOK_PATH: &str = "/api/integrations/linear/webhook";
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct UpdateLinearConnectionRequest {
+    /// Personal API key of the account issues are created as
+    #[validate(custom(function = "validation::not_blank"))]
+    pub api_key: String,
+    /// Team new tasks are pushed to
+    #[validate(custom(function = "validation::not_blank"))]
+    pub team_id: String,
+    /// Signing secret Linear shows for the webhook pointing at `webhook_path`
+    #[validate(custom(function = "validation::not_blank"))]
+    pub webhook_secret: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LinearConnectionResponse {
+    pub team_id: String,
+    /// Register this path, on the server's public URL, as a Linear webhook for
+    /// issue events so completed issues complete their tasks
+    pub webhook_path: String,
+}
+
+impl From<LinearSettings> for LinearConnectionResponse {
+    fn from(settings: LinearSettings) -> Self {
+        Self {
+            team_id: settings.team_id,
+            webhook_path: WEBHOOK_PATH.to_string(),
+        }
+    }
+}
+
+/// Linear connection of a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/linear",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Linear connection, without its secrets", body = LinearConnectionResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or Linear not connected"),
+    )
+)]
+pub async fn get_linear_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<LinearConnectionResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_linear_settings(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load Linear connection: {}", e);
+            APIError::InternalServerError("Failed to load Linear connection".to_string())
+        })?
+        .ok_or_else(|| APIError::NotFound("Linear is not connected".to_string()))?;
+
+    Ok(Json(settings.into()))
+}
+
+/// Connect a workspace to a Linear team or replace its connection - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/linear",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = UpdateLinearConnectionRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Saved Linear connection", body = LinearConnectionResponse),
+        (status = 400, description = "The API key cannot reach the team"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 422, description = "Missing settings"),
+    )
+)]
+pub async fn update_linear_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    ValidatedJson(payload): ValidatedJson<UpdateLinearConnectionRequest>,
+) -> Result<Json<LinearConnectionResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let api_key = payload.api_key.trim();
+    let team_id = payload.team_id.trim();
+    let webhook_secret = payload.webhook_secret.trim();
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+
+    let team = LinearClient::new(api_key.to_string())
+        .team(team_id)
+        .await
+        .map_err(|e| {
+            warn!("Linear team {} is not reachable: {}", team_id, e);
+            APIError::BadRequest(format!("Could not reach Linear team {}: {}", team_id, e))
+        })?;
+
+    let encryption_key = &state.config.encryption_key;
+    let (api_key, webhook_secret) = encrypt(api_key, encryption_key)
+        .and_then(|api_key| Ok((api_key, encrypt(webhook_secret, encryption_key)?)))
+        .map_err(|e| {
+            error!("Failed to encrypt Linear secrets: {}", e);
+            APIError::InternalServerError("Failed to save Linear connection".to_string())
+        })?;
+
+    info!(
+        "User {} connecting workspace {} to Linear team {}",
+        person.email, workspace_name, team.name
+    );
+
+    let settings = LinearSettings {
+        team_id: team_id.to_string(),
+        api_key,
+        webhook_secret,
+    };
+    WorkspaceSettingsRepo::new(state.database.clone())
+        .update_linear(&workspace.id, Some(settings.clone()))
+        .await
+        .map_err(|e| {
+            error!("Failed to save Linear connection: {}", e);
+            APIError::InternalServerError("Failed to save Linear connection".to_string())
+        })?;
+
+    Ok(Json(settings.into()))
+}
+
+/// Disconnect a workspace from Linear; tasks keep their issues - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_name}/linear",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Linear disconnected"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or Linear not connected"),
+    )
+)]
+pub async fn delete_linear_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<StatusCode, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings_repo = WorkspaceSettingsRepo::new(state.database.clone());
+    let connected = settings_repo
+        .get_linear_settings(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load Linear connection: {}", e);
+            APIError::InternalServerError("Failed to load Linear connection".to_string())
+        })?
+        .is_some();
+    if !connected {
+        return Err(APIError::NotFound("Linear is not connected".to_string()));
+    }
+
+    settings_repo
+        .update_linear(&workspace.id, None)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete Linear connection: {}", e);
+            APIError::InternalServerError("Failed to delete Linear connection".to_string())
+        })?;
+
+    info!(
+        "User {} disconnected workspace {} from Linear",
+        person.email, workspace_name
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Receives Linear issue webhooks; an issue moved to a completed state completes
+/// its task
+#[utoipa::path(
+    post,
+    path = "/api/integrations/linear/webhook",
+    tag = "integrations",
+    responses(
+        (status = 200, description = "Event processed or ignored"),
+        (status = 400, description = "Malformed payload"),
+        (status = 401, description = "Signature does not match a connected team"),
+    )
+)]
+pub async fn linear_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, APIError> {
+    let signature = headers
+        .get("linear-signature")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+
+    let payload: LinearWebhook = serde_json::from_slice(&body)
+        .map_err(|e| APIError::BadRequest(format!("Invalid payload: {}", e)))?;
+    let Some(issue) = payload.issue() else {
+        return Ok(StatusCode::OK);
+    };
+
+    // The issue's team picks the workspace, and with it the secret the delivery
+    // must be signed with
+    let connected = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_all_with_linear()
+        .await
+        .map_err(|e| {
+            error!("Failed to load Linear connections: {}", e);
+            APIError::InternalServerError("Failed to load Linear connections".to_string())
+        })?;
+    let workspace_id = connected
+        .into_iter()
+        .find(|settings| {
+            settings.get_linear_settings().is_some_and(|linear| {
+                linear.team_id == issue.team_id
+                    && decrypt(&linear.webhook_secret, &state.config.encryption_key)
+                        .is_ok_and(|secret| linear::verify_signature(&secret, &body, signature))
+            })
+        })
+        .map(|settings| settings.workspace_id)
+        .ok_or(APIError::UnAuthorized)?;
+
+    match linear::handle_webhook(&state, &workspace_id, issue).await {
+        Ok(_) => Ok(StatusCode::OK),
+        Err(e) => {
+            // Linear retries failed deliveries
+            error!("[Linear] Failed to process webhook: {}", e);
+            Err(APIError::InternalServerError(
+                "Failed to process webhook".to_string(),
+            ))
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::{
+    core::{maintenance::Maintenance, state::AppState},
+    handlers::admins::can_configure_workspaces,
+    models::person::Model as Person,
+    utils::{json::Json, response::APIError},
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateMaintenanceRequest {
+    pub enabled: bool,
+    /// Shown to clients whose changes are refused, a default message when omitted
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MaintenanceResponse {
+    pub enabled: bool,
+    /// None while maintenance is off
+    pub maintenance: Option<Maintenance>,
+}
+
+impl MaintenanceResponse {
+    fn new(maintenance: Option<Maintenance>) -> Self {
+        Self {
+            enabled: maintenance.is_some(),
+            maintenance,
+        }
+    }
+}
+
+/// Whether this instance is in maintenance mode - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/admin/maintenance",
+    tag = "admins",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Maintenance state", body = MaintenanceResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+    )
+)]
+pub async fn get_maintenance(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<MaintenanceResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    Ok(Json(MaintenanceResponse::new(state.maintenance.current())))
+}
+
+/// Turn maintenance mode on or off - REQUIRES ADMIN PERMISSION
+///
+/// While it is on, mutating endpoints answer 503 and bots queue Slack events
+/// instead of saving tasks, so migrations can run safely. Queued events are
+/// processed once it is turned off. Only affects this instance.
+#[utoipa::path(
+    post,
+    path = "/api/admin/maintenance",
+    tag = "admins",
+    request_body = UpdateMaintenanceRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Maintenance state", body = MaintenanceResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+    )
+)]
+pub async fn update_maintenance(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Json(payload): Json<UpdateMaintenanceRequest>,
+) -> Result<Json<MaintenanceResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    if payload.enabled {
+        state.maintenance.enable(payload.message, &person.email);
+        info!("User {} turned maintenance mode on", person.email);
+    } else if state.maintenance.disable() {
+        info!("User {} turned maintenance mode off", person.email);
+    }
+
+    Ok(Json(MaintenanceResponse::new(state.maintenance.current())))
+}
+
+/// Readiness probe: 503 while in maintenance mode or when the database is unreachable
+#[utoipa::path(
+    get,
+    path = "/api/readyz",
+    tag = "admins",
+    responses(
+        (status = 200, description = "Ready to serve traffic"),
+        (status = 503, description = "In maintenance mode or the database is unreachable"),
+    )
+)]
+pub async fn readiness(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    if let Some(maintenance) = state.maintenance.current() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({
+                "status": "maintenance",
+                "message": maintenance.message,
+                "since": maintenance.since,
+            })),
+        );
+    }
+
+    if let Err(e) = state.database.ping().await {
+        error!("Readiness check failed to reach the database: {}", e);
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({"status": "unavailable"})),
+        );
+    }
+
+    (StatusCode::OK, Json(serde_json::json!({"status": "ready"})))
+}
@@ -0,0 +1,394 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::{
+    core::state::AppState,
+    i18n::Locale,
+    models::{
+        notification_setting::{Model as NotificationSettings, NotificationChannel},
+        person::Model as Person,
+    },
+    repos::{
+        calendar_feeds::CalendarFeedsRepo, messages::MessagesRepo,
+        notification_settings::NotificationSettingsRepo, persons::PersonsRepo, tasks::TasksRepo,
+    },
+    services::{
+        calendar::render_feed,
+        privacy::{erase_person, export_person_data, DataExport, ErasureReport},
+    },
+    utils::{
+        json::{Json, ValidatedJson},
+        response::{APIError, FieldError},
+        validation,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct UpdateProfileRequest {
+    #[validate(custom(function = "validation::not_blank"))]
+    pub name: Option<String>,
+    /// IANA timezone name, e.g. `Europe/Berlin`
+    #[validate(custom(function = "validation::timezone"))]
+    pub timezone: Option<String>,
+    /// Empty string removes the avatar
+    #[validate(custom(function = "validation::http_url_or_empty"))]
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct UpdateNotificationSettingsRequest {
+    pub dm_on_assignment: bool,
+    pub daily_digest: bool,
+    pub weekly_summary: bool,
+    #[serde(default)]
+    pub channel: NotificationChannel,
+    /// Required when `channel` is `webhook`
+    #[validate(custom(function = "validation::http_url_or_empty"))]
+    pub webhook_url: Option<String>,
+    /// Language of bot messages and digests; leave out to follow the workspace
+    #[serde(default)]
+    pub locale: Option<Locale>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CalendarFeedResponse {
+    /// Subscribe to this path, on the server's public URL, from a calendar app.
+    /// Anyone with the link can read the feed.
+    pub feed_path: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CalendarFeedQuery {
+    /// Feed token from `POST /api/me/calendar-feed`
+    pub token: String,
+}
+
+/// Profile of the current user
+#[utoipa::path(
+    get,
+    path = "/api/me",
+    tag = "me",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Current user", body = Person),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
+pub async fn get_profile(person: Person) -> Json<Person> {
+    Json(person)
+}
+
+/// Update name, timezone or avatar of the current user
+#[utoipa::path(
+    patch,
+    path = "/api/me",
+    tag = "me",
+    request_body = UpdateProfileRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Updated profile", body = Person),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 422, description = "Invalid name, timezone or avatar URL"),
+    )
+)]
+pub async fn update_profile(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    ValidatedJson(payload): ValidatedJson<UpdateProfileRequest>,
+) -> Result<Json<Person>, APIError> {
+    let name = payload.name.map(|name| name.trim().to_string());
+    let timezone = payload.timezone.map(|tz| tz.trim().to_string());
+    let avatar_url = payload.avatar_url.map(|url| url.trim().to_string());
+
+    let persons_repo = PersonsRepo::new(state.database.clone());
+    let person = persons_repo
+        .update_profile(person, name, timezone, avatar_url)
+        .await
+        .map_err(|e| {
+            error!("Failed to update profile: {}", e);
+            APIError::InternalServerError("Failed to update profile".to_string())
+        })?;
+
+    Ok(Json(person))
+}
+
+/// Notification settings of the current user
+#[utoipa::path(
+    get,
+    path = "/api/me/notifications",
+    tag = "me",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Current notification settings", body = NotificationSettings),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
+pub async fn get_notification_settings(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<NotificationSettings>, APIError> {
+    let repo = NotificationSettingsRepo::new(state.database.clone());
+
+    let settings = repo.get_or_default(&person.id).await.map_err(|e| {
+        error!("Failed to load notification settings: {}", e);
+        APIError::InternalServerError("Failed to load notification settings".to_string())
+    })?;
+
+    Ok(Json(settings))
+}
+
+/// Replace the notification settings of the current user
+#[utoipa::path(
+    put,
+    path = "/api/me/notifications",
+    tag = "me",
+    request_body = UpdateNotificationSettingsRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Saved notification settings", body = NotificationSettings),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 422, description = "Missing or invalid webhook URL, or a workspace channel"),
+    )
+)]
+pub async fn update_notification_settings(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    ValidatedJson(payload): ValidatedJson<UpdateNotificationSettingsRequest>,
+) -> Result<Json<NotificationSettings>, APIError> {
+    let webhook_url = payload
+        .webhook_url
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+
+    if payload.channel.is_workspace_channel() {
+        return Err(APIError::UnprocessableEntity(vec![FieldError {
+            field: "channel".to_string(),
+            message: "posts for the whole workspace; use it in workspace notification routes"
+                .to_string(),
+        }]));
+    }
+    if payload.channel == NotificationChannel::Webhook && webhook_url.is_none() {
+        return Err(APIError::UnprocessableEntity(vec![FieldError {
+            field: "webhook_url".to_string(),
+            message: "is required for the webhook channel".to_string(),
+        }]));
+    }
+
+    let repo = NotificationSettingsRepo::new(state.database.clone());
+    let settings = repo
+        .upsert(NotificationSettings {
+            dm_on_assignment: payload.dm_on_assignment,
+            daily_digest: payload.daily_digest,
+            weekly_summary: payload.weekly_summary,
+            channel: payload.channel,
+            webhook_url,
+            locale: payload.locale,
+            ..NotificationSettings::default_for(&person.id)
+        })
+        .await
+        .map_err(|e| {
+            error!("Failed to save notification settings: {}", e);
+            APIError::InternalServerError("Failed to save notification settings".to_string())
+        })?;
+
+    Ok(Json(settings))
+}
+
+/// Download everything stored about the current user as one JSON file
+#[utoipa::path(
+    get,
+    path = "/api/me/export",
+    tag = "me",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Data export, sent as an attachment", body = DataExport),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
+pub async fn export_data(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Response, APIError> {
+    info!("User {} exporting their data", person.email);
+
+    let export = export_person_data(&state.database, &state.message_encryption, person)
+        .await
+        .map_err(|e| {
+            error!("Failed to export user data: {}", e);
+            APIError::InternalServerError("Failed to export data".to_string())
+        })?;
+
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"slacker-export.json\"",
+        )],
+        Json(export),
+    )
+        .into_response())
+}
+
+/// Erase the current user: anonymize their messages and delete their links,
+/// settings and invitations. The session stops working afterwards.
+#[utoipa::path(
+    delete,
+    path = "/api/me",
+    tag = "me",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Account erased", body = ErasureReport),
+        (status = 400, description = "The super admin cannot be erased"),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
+pub async fn delete_account(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<ErasureReport>, APIError> {
+    if person.email == state.config.admin_email {
+        return Err(APIError::BadRequest(
+            "The super admin account cannot be erased".to_string(),
+        ));
+    }
+
+    info!("User {} erasing their account", person.email);
+
+    let report = erase_person(&state.database, &state.message_encryption, person)
+        .await
+        .map_err(|e| {
+            error!("Failed to erase user: {}", e);
+            APIError::InternalServerError("Failed to erase account".to_string())
+        })?;
+
+    Ok(Json(report))
+}
+
+/// Create the current user's calendar feed link, revoking any previous link
+#[utoipa::path(
+    post,
+    path = "/api/me/calendar-feed",
+    tag = "me",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "New feed link", body = CalendarFeedResponse),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
+pub async fn create_calendar_feed(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<CalendarFeedResponse>, APIError> {
+    let feed = CalendarFeedsRepo::new(state.database.clone())
+        .rotate(&person.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to create calendar feed: {}", e);
+            APIError::InternalServerError("Failed to create calendar feed".to_string())
+        })?;
+
+    Ok(Json(CalendarFeedResponse {
+        feed_path: format!("/api/me/tasks.ics?token={}", feed.token),
+        created_at: feed.created_at.and_utc().to_rfc3339(),
+    }))
+}
+
+/// Revoke the current user's calendar feed link
+#[utoipa::path(
+    delete,
+    path = "/api/me/calendar-feed",
+    tag = "me",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Feed revoked"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "No feed to revoke"),
+    )
+)]
+pub async fn delete_calendar_feed(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<StatusCode, APIError> {
+    let deleted = CalendarFeedsRepo::new(state.database.clone())
+        .delete_by_person(&person.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to revoke calendar feed: {}", e);
+            APIError::InternalServerError("Failed to revoke calendar feed".to_string())
+        })?;
+
+    if !deleted {
+        return Err(APIError::NotFound("No calendar feed".to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// iCalendar feed of the tasks assigned to the feed's owner that have a due date.
+/// Calendar apps cannot send a bearer token, so the feed token authenticates.
+#[utoipa::path(
+    get,
+    path = "/api/me/tasks.ics",
+    tag = "me",
+    params(CalendarFeedQuery),
+    responses(
+        (status = 200, description = "text/calendar feed"),
+        (status = 401, description = "Unknown or revoked feed token"),
+    )
+)]
+pub async fn calendar_feed(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CalendarFeedQuery>,
+) -> Result<Response, APIError> {
+    let feed = CalendarFeedsRepo::new(state.read_database.clone())
+        .get_by_token(&query.token)
+        .await
+        .map_err(|e| {
+            error!("Failed to load calendar feed: {}", e);
+            APIError::InternalServerError("Failed to load calendar feed".to_string())
+        })?
+        .ok_or(APIError::UnAuthorized)?;
+
+    let tasks = TasksRepo::new(state.read_database.clone())
+        .get_assigned_with_due_date(&feed.person_id)
+        .await?;
+    let message_ids = tasks.iter().map(|t| t.message_id.clone()).collect();
+    let mut messages = MessagesRepo::new(
+        state.read_database.clone(),
+        state.message_encryption.clone(),
+    )
+    .get_by_ids(message_ids)
+    .await?;
+
+    let tasks: Vec<_> = tasks
+        .into_iter()
+        .map(|task| {
+            let message = messages
+                .iter()
+                .position(|m| m.id == task.message_id)
+                .map(|i| messages.swap_remove(i));
+            (task, message)
+        })
+        .collect();
+
+    let body = render_feed(&tasks, chrono::Utc::now().naive_utc());
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "inline; filename=\"slacker-tasks.ics\"",
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
@@ -1,4 +1,37 @@
 pub mod admins;
 pub mod auth;
+pub mod availability;
+pub mod backup;
+pub mod board_views;
+pub mod bots;
+pub mod business_hours;
+pub mod channel_rules;
+pub mod channels;
+pub mod database_backups;
+pub mod discord;
+pub mod email;
+pub mod github;
+pub mod hooks;
+pub mod invitations;
+pub mod jira;
+pub mod linear;
+pub mod maintenance;
+pub mod me;
+pub mod notification_templates;
+pub mod notion;
+pub mod orphaned_tasks;
+pub mod projects;
+pub mod reminders;
+pub mod retention;
+pub mod schedules;
+pub mod sessions;
+pub mod sla_policies;
+pub mod slack_status;
+pub mod standups;
+pub mod task_import;
+pub mod task_templates;
 pub mod tasks;
+pub mod teams;
+pub mod workload;
 pub mod workspaces;
+pub mod ws;
@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    models::{
+        notification_setting::NotificationKind, person::Model as Person,
+        workspace_settings::NotificationTemplate,
+    },
+    repos::workspace_settings::WorkspaceSettingsRepo,
+    services::notification_templates::{self, TEMPLATE_KINDS},
+    utils::{
+        json::Json,
+        response::{APIError, FieldError},
+    },
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TemplateVariables {
+    pub kind: NotificationKind,
+    /// Names a template of the kind may use as `{{name}}`
+    pub variables: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct NotificationTemplatesRequest {
+    /// Replaces the earlier templates; kinds left out get the built-in text again
+    pub templates: Vec<NotificationTemplate>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationTemplatesResponse {
+    pub templates: Vec<NotificationTemplate>,
+    /// Variables each kind of template may use
+    pub variables: Vec<TemplateVariables>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationTemplatePreview {
+    /// None when the template keeps the built-in subject
+    pub subject: Option<String>,
+    /// None when the template keeps the built-in body
+    pub body: Option<String>,
+}
+
+fn templates_response(templates: Vec<NotificationTemplate>) -> NotificationTemplatesResponse {
+    NotificationTemplatesResponse {
+        templates,
+        variables: TEMPLATE_KINDS
+            .into_iter()
+            .map(|kind| TemplateVariables {
+                kind,
+                variables: notification_templates::variables(kind)
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+/// Notification templates of a workspace, with the variables each kind may use -
+/// REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/notification-templates",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Notification templates", body = NotificationTemplatesResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn get_notification_templates(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<NotificationTemplatesResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let templates = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_notification_templates(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load notification templates: {}", e);
+            APIError::InternalServerError("Failed to load notification templates".to_string())
+        })?;
+
+    Ok(Json(templates_response(templates)))
+}
+
+/// Replace the notification templates of a workspace; an empty list removes them.
+/// Notifications about the workspace of a templated kind use the template's subject
+/// and body, with its `{{variables}}` filled in - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/notification-templates",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = NotificationTemplatesRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Notification templates updated", body = NotificationTemplatesResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 422, description = "Duplicate kind, unknown variable or template too long"),
+    )
+)]
+pub async fn update_notification_templates(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<NotificationTemplatesRequest>,
+) -> Result<Json<NotificationTemplatesResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let mut errors = Vec::new();
+    for (index, template) in payload.templates.iter().enumerate() {
+        let field = format!("templates[{}]", index);
+        if payload.templates[..index]
+            .iter()
+            .any(|other| other.kind == template.kind)
+        {
+            errors.push(FieldError {
+                field: format!("{}.kind", field),
+                message: "already has a template".to_string(),
+            });
+        }
+        errors.extend(notification_templates::validate(template, &field));
+    }
+    if !errors.is_empty() {
+        return Err(APIError::UnprocessableEntity(errors));
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .update_notification_templates(&workspace.id, payload.templates)
+        .await
+        .map_err(|e| {
+            error!("Failed to save notification templates: {}", e);
+            APIError::InternalServerError("Failed to save notification templates".to_string())
+        })?;
+
+    info!(
+        "User {} updated the notification templates of workspace {}",
+        person.email, workspace_name
+    );
+    Ok(Json(templates_response(
+        settings.get_notification_templates(),
+    )))
+}
+
+/// Render a notification template with sample data, without saving it - REQUIRES
+/// ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/{workspace_name}/notification-templates/preview",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = NotificationTemplate,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Rendered template", body = NotificationTemplatePreview),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 422, description = "Unknown variable or template too long"),
+    )
+)]
+pub async fn preview_notification_template(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<NotificationTemplate>,
+) -> Result<Json<NotificationTemplatePreview>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+    find_workspace(&state, &workspace_name).await?;
+
+    let errors = notification_templates::validate(&payload, "template");
+    if !errors.is_empty() {
+        return Err(APIError::UnprocessableEntity(errors));
+    }
+
+    let variables =
+        notification_templates::sample_variables(payload.kind, &state.config.frontend_url);
+    Ok(Json(NotificationTemplatePreview {
+        subject: payload
+            .subject
+            .map(|subject| notification_templates::render(&subject, &variables)),
+        body: payload
+            .body
+            .map(|body| notification_templates::render(&body, &variables)),
+    }))
+}
@@ -0,0 +1,253 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    integrations::notion,
+    models::{notion_connection::Model as NotionConnection, person::Model as Person},
+    repos::notion_connections::NotionConnectionsRepo,
+    utils::{
+        encryption::encrypt,
+        json::{Json, ValidatedJson},
+        response::APIError,
+        validation,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct UpdateNotionConnectionRequest {
+    /// Internal integration token; the database must be shared with the integration
+    #[validate(custom(function = "validation::not_blank"))]
+    pub token: String,
+    #[validate(custom(function = "validation::not_blank"))]
+    pub database_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotionConnectionResponse {
+    pub database_id: String,
+    /// Property completed tasks' titles are written to
+    pub title_property: String,
+    pub updated_at: String,
+}
+
+impl From<NotionConnection> for NotionConnectionResponse {
+    fn from(connection: NotionConnection) -> Self {
+        Self {
+            database_id: connection.database_id,
+            title_property: connection.title_property,
+            updated_at: connection.updated_at.and_utc().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotionExportResponse {
+    /// Completed tasks appended to the database by this run
+    pub exported: usize,
+}
+
+async fn load_connection(
+    state: &AppState,
+    workspace_id: &str,
+) -> Result<NotionConnection, APIError> {
+    NotionConnectionsRepo::new(state.database.clone())
+        .get_by_workspace(workspace_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load Notion connection: {}", e);
+            APIError::InternalServerError("Failed to load Notion connection".to_string())
+        })?
+        .ok_or_else(|| APIError::NotFound("Notion is not connected".to_string()))
+}
+
+/// Notion connection of a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/notion",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Notion connection, without the token", body = NotionConnectionResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or Notion not connected"),
+    )
+)]
+pub async fn get_notion_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<NotionConnectionResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let connection = load_connection(&state, &workspace.id).await?;
+
+    Ok(Json(connection.into()))
+}
+
+/// Connect a workspace to a Notion database or replace its connection. The
+/// "Assignee", "Completed" and "Slack" properties are added to the database when
+/// missing - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/notion",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = UpdateNotionConnectionRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Saved Notion connection", body = NotionConnectionResponse),
+        (status = 400, description = "The database cannot be used"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 422, description = "Missing settings"),
+    )
+)]
+pub async fn update_notion_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    ValidatedJson(payload): ValidatedJson<UpdateNotionConnectionRequest>,
+) -> Result<Json<NotionConnectionResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let token = payload.token.trim();
+    let database_id = payload.database_id.trim();
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+
+    let title_property = notion::prepare_database(token, database_id)
+        .await
+        .map_err(|e| {
+            warn!("Notion database {} cannot be used: {}", database_id, e);
+            APIError::BadRequest(format!("Cannot use Notion database: {}", e))
+        })?;
+
+    let token = encrypt(token, &state.config.encryption_key).map_err(|e| {
+        error!("Failed to encrypt Notion token: {}", e);
+        APIError::InternalServerError("Failed to save Notion connection".to_string())
+    })?;
+
+    info!(
+        "User {} connecting workspace {} to Notion database {}",
+        person.email, workspace_name, database_id
+    );
+
+    let connection = NotionConnectionsRepo::new(state.database.clone())
+        .upsert(NotionConnection {
+            id: String::new(),
+            workspace_id: workspace.id,
+            token,
+            database_id: database_id.to_string(),
+            title_property,
+            updated_at: chrono::Utc::now().naive_utc(),
+        })
+        .await
+        .map_err(|e| {
+            error!("Failed to save Notion connection: {}", e);
+            APIError::InternalServerError("Failed to save Notion connection".to_string())
+        })?;
+
+    Ok(Json(connection.into()))
+}
+
+/// Disconnect a workspace from Notion; exported pages stay - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_name}/notion",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Notion disconnected"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or Notion not connected"),
+    )
+)]
+pub async fn delete_notion_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<StatusCode, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let deleted = NotionConnectionsRepo::new(state.database.clone())
+        .delete_by_workspace(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete Notion connection: {}", e);
+            APIError::InternalServerError("Failed to delete Notion connection".to_string())
+        })?;
+
+    if !deleted {
+        return Err(APIError::NotFound("Notion is not connected".to_string()));
+    }
+
+    info!(
+        "User {} disconnected workspace {} from Notion",
+        person.email, workspace_name
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Append the workspace's completed tasks to its Notion database now, instead of
+/// waiting for the hourly export - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/{workspace_name}/export/notion",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Export finished", body = NotionExportResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or Notion not connected"),
+    )
+)]
+pub async fn export_to_notion(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<NotionExportResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let connection = load_connection(&state, &workspace.id).await?;
+
+    info!(
+        "User {} exporting completed tasks of {} to Notion",
+        person.email, workspace_name
+    );
+    let exported = notion::export_completed_tasks(&state, &connection)
+        .await
+        .map_err(|e| {
+            error!("Failed to export to Notion: {}", e);
+            APIError::InternalServerError("Failed to export to Notion".to_string())
+        })?;
+
+    Ok(Json(NotionExportResponse { exported }))
+}
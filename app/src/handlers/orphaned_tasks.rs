@@ -0,0 +1,186 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::extract::{Path, State};
+use sea_orm::DbErr;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, tasks::TaskResponse, workspaces::find_workspace},
+    models::{message::Model as Message, person::Model as Person, task::Model as Task},
+    repos::{
+        messages::MessagesRepo, persons::PersonsRepo, tasks::TasksRepo,
+        workspace_links::WorkspaceLinksRepo,
+    },
+    services::{
+        availability,
+        task_service::{status_since, TaskService},
+    },
+    utils::{
+        json::{Json, ValidatedJson},
+        response::APIError,
+    },
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrphanedTaskListResponse {
+    /// Oldest first
+    pub tasks: Vec<TaskResponse>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct AssignOrphanedTaskRequest {
+    /// Linked member who takes the task over
+    #[validate(email)]
+    pub assignee_email: String,
+}
+
+/// Open tasks whose assignee left Slack and that no channel rule could hand over -
+/// REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/orphaned-tasks",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Orphaned tasks", body = OrphanedTaskListResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn list_orphaned_tasks(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<OrphanedTaskListResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let tasks = TasksRepo::new(state.read_database.clone())
+        .get_orphaned(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load orphaned tasks: {}", e);
+            APIError::InternalServerError("Failed to load orphaned tasks".to_string())
+        })?;
+
+    let message_ids = tasks.iter().map(|t| t.message_id.clone()).collect();
+    let messages: HashMap<String, Message> = MessagesRepo::new(
+        state.read_database.clone(),
+        state.message_encryption.clone(),
+    )
+    .get_by_ids(message_ids)
+    .await?
+    .into_iter()
+    .map(|m| (m.id.clone(), m))
+    .collect();
+
+    let since = status_since(&state.read_database, &tasks).await?;
+    let tz = person.tz();
+    let tasks = tasks
+        .into_iter()
+        .filter_map(|task| {
+            let message = messages.get(&task.message_id)?.clone();
+            let status_since = since.get(&task.id).copied().flatten();
+            Some(TaskResponse::new(task, message, status_since, tz))
+        })
+        .collect();
+
+    Ok(Json(OrphanedTaskListResponse { tasks }))
+}
+
+/// Hand an orphaned task to a linked member - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/orphaned-tasks/{task_id}",
+    tag = "workspaces",
+    params(
+        ("workspace_name" = String, Path, description = "Workspace name"),
+        ("task_id" = String, Path, description = "Task id"),
+    ),
+    request_body = AssignOrphanedTaskRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Reassigned task", body = Task),
+        (status = 400, description = "Assignee is not a linked member of the workspace"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace or orphaned task not found"),
+        (status = 409, description = "Assignee is out of office"),
+        (status = 422, description = "Invalid email address"),
+    )
+)]
+pub async fn assign_orphaned_task(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path((workspace_name, task_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<AssignOrphanedTaskRequest>,
+) -> Result<Json<Task>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let tasks_repo = TasksRepo::new(state.database.clone());
+    let task = tasks_repo
+        .get(task_id)
+        .await
+        .ok()
+        .filter(|task| {
+            task.orphaned_at.is_some()
+                && task.workspace_id.as_deref() == Some(workspace.id.as_str())
+        })
+        .ok_or_else(|| APIError::NotFound("Orphaned task not found".to_string()))?;
+
+    let email = payload.assignee_email.trim().to_lowercase();
+    let not_member =
+        || APIError::BadRequest(format!("{} is not a linked member of the workspace", email));
+    let assignee = match PersonsRepo::new(state.database.clone())
+        .get_by_email(email.clone())
+        .await
+    {
+        Ok(assignee) => assignee,
+        Err(DbErr::RecordNotFound(_)) => return Err(not_member()),
+        Err(e) => return Err(e.into()),
+    };
+    let linked = WorkspaceLinksRepo::new(state.database.clone())
+        .get_by_person_and_workspace(assignee.id.clone(), workspace.id.clone())
+        .await
+        .is_ok_and(|link| link.is_linked);
+    if !linked {
+        return Err(not_member());
+    }
+    let away_until = availability::away_until(&state.database, &assignee)
+        .await
+        .map_err(|e| {
+            error!("Failed to load availability: {}", e);
+            APIError::InternalServerError("Failed to load availability".to_string())
+        })?;
+    if let Some(until) = away_until {
+        return Err(APIError::Conflict(format!(
+            "{} is out of office until {}",
+            email, until
+        )));
+    }
+
+    let task = TaskService::from_state(&state)
+        .assign(task, &assignee, &person)
+        .await
+        .map_err(|e| {
+            error!("Failed to reassign task: {}", e);
+            APIError::InternalServerError("Failed to reassign task".to_string())
+        })?;
+
+    info!(
+        "User {} assigned orphaned task {} to {}",
+        person.email, task.id, assignee.email
+    );
+    Ok(Json(task))
+}
@@ -0,0 +1,255 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    models::{person::Model as Person, project::Model as Project, workspace::Model as Workspace},
+    repos::{projects::ProjectsRepo, workspace_links::WorkspaceLinksRepo},
+    utils::{
+        json::{Json, ValidatedJson},
+        response::APIError,
+        validation,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct ProjectRequest {
+    /// Unique within the workspace
+    #[validate(custom(function = "validation::not_blank"))]
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectListResponse {
+    pub projects: Vec<Project>,
+}
+
+/// Projects, task templates and SLA policies are listed to linked members and
+/// workspace admins
+pub(crate) async fn readable_workspace(
+    state: &AppState,
+    person: &Person,
+    name: &str,
+) -> Result<Workspace, APIError> {
+    let workspace = find_workspace(state, name).await?;
+    let linked = WorkspaceLinksRepo::new(state.database.clone())
+        .get_by_person_and_workspace(person.id.clone(), workspace.id.clone())
+        .await
+        .is_ok_and(|link| link.is_linked);
+    if !linked && !can_configure_workspaces(state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    Ok(workspace)
+}
+
+/// Trimmed name and description; a name taken by another project of the workspace
+/// is a conflict
+async fn validate_project(
+    projects_repo: &ProjectsRepo,
+    workspace: &Workspace,
+    payload: ProjectRequest,
+    project_id: Option<&str>,
+) -> Result<(String, Option<String>), APIError> {
+    let name = payload.name.trim().to_string();
+
+    let taken = projects_repo
+        .get_by_name(&workspace.id, &name)
+        .await?
+        .is_some_and(|existing| Some(existing.id.as_str()) != project_id);
+    if taken {
+        return Err(APIError::Conflict(format!(
+            "Project {} already exists in the workspace",
+            name
+        )));
+    }
+
+    let description = payload
+        .description
+        .map(|description| description.trim().to_string())
+        .filter(|description| !description.is_empty());
+    Ok((name, description))
+}
+
+/// Projects of a workspace
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/projects",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Projects by name", body = ProjectListResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Not linked to the workspace"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn list_projects(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<ProjectListResponse>, APIError> {
+    let workspace = readable_workspace(&state, &person, &workspace_name).await?;
+    let projects = ProjectsRepo::new(state.read_database.clone())
+        .get_by_workspace(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load projects: {}", e);
+            APIError::InternalServerError("Failed to load projects".to_string())
+        })?;
+
+    Ok(Json(ProjectListResponse { projects }))
+}
+
+/// Create a project - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/{workspace_name}/projects",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = ProjectRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Created project", body = Project),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 409, description = "A project with the name exists"),
+        (status = 422, description = "Missing name"),
+    )
+)]
+pub async fn create_project(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    ValidatedJson(payload): ValidatedJson<ProjectRequest>,
+) -> Result<(StatusCode, Json<Project>), APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let projects_repo = ProjectsRepo::new(state.database.clone());
+    let (name, description) = validate_project(&projects_repo, &workspace, payload, None).await?;
+
+    let project = projects_repo
+        .create(&workspace.id, &name, description)
+        .await
+        .map_err(|e| {
+            error!("Failed to create project: {}", e);
+            APIError::InternalServerError("Failed to create project".to_string())
+        })?;
+
+    info!(
+        "User {} created project {} in workspace {}",
+        person.email, project.name, workspace_name
+    );
+    Ok((StatusCode::CREATED, Json(project)))
+}
+
+/// Rename or describe a project - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/projects/{project_id}",
+    tag = "workspaces",
+    params(
+        ("workspace_name" = String, Path, description = "Workspace name"),
+        ("project_id" = String, Path, description = "Project id"),
+    ),
+    request_body = ProjectRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Updated project", body = Project),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace or project not found"),
+        (status = 409, description = "Another project has the name"),
+        (status = 422, description = "Missing name"),
+    )
+)]
+pub async fn update_project(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path((workspace_name, project_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<ProjectRequest>,
+) -> Result<Json<Project>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let projects_repo = ProjectsRepo::new(state.database.clone());
+    let project = projects_repo
+        .get(&workspace.id, &project_id)
+        .await?
+        .ok_or_else(|| APIError::NotFound("Project not found".to_string()))?;
+    let (name, description) =
+        validate_project(&projects_repo, &workspace, payload, Some(&project.id)).await?;
+
+    let project = projects_repo
+        .update(project, &name, description)
+        .await
+        .map_err(|e| {
+            error!("Failed to update project: {}", e);
+            APIError::InternalServerError("Failed to update project".to_string())
+        })?;
+
+    Ok(Json(project))
+}
+
+/// Delete a project; its tasks stay, without a project - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_name}/projects/{project_id}",
+    tag = "workspaces",
+    params(
+        ("workspace_name" = String, Path, description = "Workspace name"),
+        ("project_id" = String, Path, description = "Project id"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Project deleted"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace or project not found"),
+    )
+)]
+pub async fn delete_project(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path((workspace_name, project_id)): Path<(String, String)>,
+) -> Result<StatusCode, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let projects_repo = ProjectsRepo::new(state.database.clone());
+    let project = projects_repo
+        .get(&workspace.id, &project_id)
+        .await?
+        .ok_or_else(|| APIError::NotFound("Project not found".to_string()))?;
+
+    let name = project.name.clone();
+    projects_repo.delete(project).await.map_err(|e| {
+        error!("Failed to delete project: {}", e);
+        APIError::InternalServerError("Failed to delete project".to_string())
+    })?;
+
+    info!(
+        "User {} deleted project {} in workspace {}",
+        person.email, name, workspace_name
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
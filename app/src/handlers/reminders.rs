@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    core::state::AppState,
+    models::{person::Model as Person, reminder::Model as Reminder},
+    repos::{reminders::RemindersRepo, workspace_links::WorkspaceLinksRepo},
+    services::reminders::remind_at,
+    utils::{
+        json::{Json, ValidatedJson},
+        response::{APIError, FieldError},
+        validation,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct CreateReminderRequest {
+    /// How long from now, e.g. `30m`, `4h`, `2d`, `1w`, `tomorrow` or `next monday`.
+    /// Days and weekdays land at 9:00 in your timezone.
+    #[validate(custom(function = "validation::not_blank"))]
+    pub remind_in: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReminderListResponse {
+    pub reminders: Vec<Reminder>,
+}
+
+/// Get a DM about a task later. Allowed for members of the task's workspace.
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{task_id}/reminders",
+    tag = "tasks",
+    params(("task_id" = String, Path, description = "Task id")),
+    request_body = CreateReminderRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Created reminder", body = Reminder),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Not linked to the task's workspace"),
+        (status = 404, description = "Task not found"),
+        (status = 422, description = "Unrecognized delay"),
+    )
+)]
+pub async fn create_reminder(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(task_id): Path<String>,
+    ValidatedJson(payload): ValidatedJson<CreateReminderRequest>,
+) -> Result<(StatusCode, Json<Reminder>), APIError> {
+    let task = state
+        .stores
+        .tasks
+        .get(task_id)
+        .await
+        .map_err(|_| APIError::NotFound("Task not found".to_string()))?;
+
+    let linked = match task.workspace_id.clone() {
+        Some(workspace_id) => WorkspaceLinksRepo::new(state.database.clone())
+            .get_by_person_and_workspace(person.id.clone(), workspace_id)
+            .await
+            .is_ok_and(|link| link.is_linked),
+        None => false,
+    };
+    if !linked {
+        return Err(APIError::Forbidden);
+    }
+
+    let Some(at) = remind_at(&person, &payload.remind_in) else {
+        return Err(APIError::UnprocessableEntity(vec![FieldError {
+            field: "remind_in".to_string(),
+            message: "use a delay such as 30m, 4h, 2d, 1w, tomorrow or next monday".to_string(),
+        }]));
+    };
+
+    let reminder = RemindersRepo::new(state.database.clone())
+        .create(&task.id, &person.id, at)
+        .await
+        .map_err(|e| {
+            error!("Failed to create reminder: {}", e);
+            APIError::InternalServerError("Failed to create reminder".to_string())
+        })?;
+
+    info!(
+        "User {} set a reminder on task {} for {}",
+        person.email, task.id, at
+    );
+    Ok((StatusCode::CREATED, Json(reminder)))
+}
+
+/// Reminders of the current user that are not done, soonest first
+#[utoipa::path(
+    get,
+    path = "/api/me/reminders",
+    tag = "me",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Open reminders", body = ReminderListResponse),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
+pub async fn list_reminders(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<ReminderListResponse>, APIError> {
+    let reminders = RemindersRepo::new(state.read_database.clone())
+        .get_open_by_person(&person.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load reminders: {}", e);
+            APIError::InternalServerError("Failed to load reminders".to_string())
+        })?;
+
+    Ok(Json(ReminderListResponse { reminders }))
+}
+
+/// Cancel a reminder
+#[utoipa::path(
+    delete,
+    path = "/api/me/reminders/{reminder_id}",
+    tag = "me",
+    params(("reminder_id" = String, Path, description = "Reminder id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Reminder deleted"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Reminder not found"),
+    )
+)]
+pub async fn delete_reminder(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(reminder_id): Path<String>,
+) -> Result<StatusCode, APIError> {
+    let reminders_repo = RemindersRepo::new(state.database.clone());
+    let reminder = reminders_repo
+        .get_by_id(&reminder_id)
+        .await?
+        .filter(|reminder| reminder.person_id == person.id)
+        .ok_or_else(|| APIError::NotFound("Reminder not found".to_string()))?;
+
+    reminders_repo.delete(reminder).await.map_err(|e| {
+        error!("Failed to delete reminder: {}", e);
+        APIError::InternalServerError("Failed to delete reminder".to_string())
+    })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use serde::Deserialize;
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    models::{
+        person::Model as Person,
+        retention_policy::{Model as RetentionPolicy, RetentionMode},
+    },
+    repos::retention_policies::RetentionPoliciesRepo,
+    services::retention::{apply_policy, RetentionReport},
+    utils::{json::Json, response::APIError},
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateRetentionPolicyRequest {
+    /// Days to keep completed tasks, omit to keep them forever
+    pub completed_task_days: Option<i32>,
+    /// Days to keep task messages of any status, omit to keep them forever
+    pub message_days: Option<i32>,
+    /// Days to keep bot incidents, omit to keep them forever
+    pub incident_days: Option<i32>,
+    #[serde(default)]
+    pub mode: RetentionMode,
+}
+
+impl UpdateRetentionPolicyRequest {
+    fn into_policy(self, workspace_id: &str) -> Result<RetentionPolicy, APIError> {
+        let days = [
+            self.completed_task_days,
+            self.message_days,
+            self.incident_days,
+        ];
+        if days.into_iter().flatten().any(|days| days < 1) {
+            return Err(APIError::BadRequest(
+                "Retention periods must be at least 1 day".to_string(),
+            ));
+        }
+
+        Ok(RetentionPolicy {
+            completed_task_days: self.completed_task_days,
+            message_days: self.message_days,
+            incident_days: self.incident_days,
+            mode: self.mode,
+            ..RetentionPolicy::default_for(workspace_id)
+        })
+    }
+}
+
+/// Data retention policy of a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/retention",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Current retention policy", body = RetentionPolicy),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn get_retention_policy(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<RetentionPolicy>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let policy = RetentionPoliciesRepo::new(state.database.clone())
+        .get_or_default(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load retention policy: {}", e);
+            APIError::InternalServerError("Failed to load retention policy".to_string())
+        })?;
+
+    Ok(Json(policy))
+}
+
+/// Replace the data retention policy of a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/retention",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = UpdateRetentionPolicyRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Saved retention policy", body = RetentionPolicy),
+        (status = 400, description = "Invalid retention period"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn update_retention_policy(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<UpdateRetentionPolicyRequest>,
+) -> Result<Json<RetentionPolicy>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let policy = payload.into_policy(&workspace.id)?;
+
+    info!(
+        "User {} updating retention policy for workspace: {}",
+        person.email, workspace_name
+    );
+
+    let policy = RetentionPoliciesRepo::new(state.database.clone())
+        .upsert(policy)
+        .await
+        .map_err(|e| {
+            error!("Failed to save retention policy: {}", e);
+            APIError::InternalServerError("Failed to save retention policy".to_string())
+        })?;
+
+    Ok(Json(policy))
+}
+
+/// Count what a retention policy would remove right now, without changing anything -
+/// REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/{workspace_name}/retention/preview",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = UpdateRetentionPolicyRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Rows the policy would remove or anonymize", body = RetentionReport),
+        (status = 400, description = "Invalid retention period"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn preview_retention_policy(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<UpdateRetentionPolicyRequest>,
+) -> Result<Json<RetentionReport>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let policy = payload.into_policy(&workspace.id)?;
+
+    let now = chrono::Utc::now().naive_utc();
+    let report = apply_policy(&state.database, &policy, now, true)
+        .await
+        .map_err(|e| {
+            error!("Failed to preview retention policy: {}", e);
+            APIError::InternalServerError("Failed to preview retention policy".to_string())
+        })?;
+
+    Ok(Json(report))
+}
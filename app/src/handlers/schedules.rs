@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use serde::Serialize;
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::{
+    core::{scheduler::run_job, state::AppState},
+    handlers::admins::can_configure_workspaces,
+    models::{person::Model as Person, scheduled_job::Model as ScheduledJob},
+    repos::scheduled_jobs::ScheduledJobsRepo,
+    utils::{json::Json, response::APIError},
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScheduleListResponse {
+    pub jobs: Vec<ScheduledJob>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RunScheduleResponse {
+    pub success: bool,
+    pub message: String,
+    /// Notifications sent, or rows purged, by this run
+    pub sent: usize,
+}
+
+/// List scheduled jobs with their last and next run - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/admin/schedules",
+    tag = "admins",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Scheduled jobs", body = ScheduleListResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+    )
+)]
+pub async fn list_schedules(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<ScheduleListResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let jobs = ScheduledJobsRepo::new(state.database.clone())
+        .get_all()
+        .await
+        .map_err(|e| {
+            error!("Failed to load scheduled jobs: {}", e);
+            APIError::InternalServerError("Failed to load scheduled jobs".to_string())
+        })?;
+
+    Ok(Json(ScheduleListResponse { jobs }))
+}
+
+/// Run a scheduled job now, for all subscribers regardless of their local time - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/admin/schedules/{name}/run",
+    tag = "admins",
+    params(("name" = String, Path, description = "Job name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Job ran", body = RunScheduleResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Job not found"),
+    )
+)]
+pub async fn run_schedule(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(name): Path<String>,
+) -> Result<Json<RunScheduleResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let repo = ScheduledJobsRepo::new(state.database.clone());
+    let job = repo.get_by_name(&name).await?;
+
+    info!("User {} triggered scheduled job {}", person.email, name);
+    let now = chrono::Utc::now().naive_utc();
+    let result = run_job(&state, &job, true).await;
+
+    let next_run_at = job.next_run_at;
+    let error = result.as_ref().err().map(|e| e.to_string());
+    if let Err(e) = repo.record_run(job, now, next_run_at, error).await {
+        error!("Failed to record run of job {}: {}", name, e);
+    }
+
+    match result {
+        Ok(sent) => Ok(Json(RunScheduleResponse {
+            success: true,
+            message: format!("Job '{}' ran", name),
+            sent,
+        })),
+        Err(e) => {
+            error!("Scheduled job {} failed: {}", name, e);
+            Err(APIError::InternalServerError(format!(
+                "Job '{}' failed",
+                name
+            )))
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::{
+    core::state::AppState,
+    middlewares::auth::CurrentSession,
+    models::person::Model as Person,
+    repos::sessions::SessionsRepo,
+    utils::{json::Json, response::APIError},
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionInfo {
+    pub id: String,
+    /// User-Agent of the browser that signed in
+    pub user_agent: Option<String>,
+    /// Address the sign-in came from
+    pub ip_address: Option<String>,
+    pub created_at: NaiveDateTime,
+    /// Accurate to a few minutes
+    pub last_used_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    /// Whether this request was made with the session's token
+    pub current: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionListResponse {
+    /// Most recently used first
+    pub sessions: Vec<SessionInfo>,
+}
+
+/// Devices the current user is signed in on
+#[utoipa::path(
+    get,
+    path = "/api/me/sessions",
+    tag = "me",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Active sessions", body = SessionListResponse),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
+pub async fn list_sessions(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    current: Option<Extension<CurrentSession>>,
+) -> Result<Json<SessionListResponse>, APIError> {
+    let sessions = SessionsRepo::new(state.database.clone())
+        .get_active_by_person(&person.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load sessions: {}", e);
+            APIError::InternalServerError("Failed to load sessions".to_string())
+        })?;
+
+    let current = current.map(|Extension(session)| session.id);
+    let sessions = sessions
+        .into_iter()
+        .map(|session| SessionInfo {
+            current: current.as_ref() == Some(&session.id),
+            id: session.id,
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+            created_at: session.created_at,
+            last_used_at: session.last_used_at,
+            expires_at: session.expires_at,
+        })
+        .collect();
+
+    Ok(Json(SessionListResponse { sessions }))
+}
+
+/// Sign a device out: tokens of the session are refused from then on. Revoking the
+/// current session signs out this device.
+#[utoipa::path(
+    delete,
+    path = "/api/me/sessions/{session_id}",
+    tag = "me",
+    params(("session_id" = String, Path, description = "Session id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Session not found"),
+    )
+)]
+pub async fn revoke_session(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(session_id): Path<String>,
+) -> Result<StatusCode, APIError> {
+    let revoked = SessionsRepo::new(state.database.clone())
+        .delete(&person.id, &session_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to revoke session: {}", e);
+            APIError::InternalServerError("Failed to revoke session".to_string())
+        })?;
+    if !revoked {
+        return Err(APIError::NotFound("Session not found".to_string()));
+    }
+
+    info!("User {} revoked session {}", person.email, session_id);
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,247 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    core::state::AppState,
+    handlers::{
+        admins::can_configure_workspaces, projects::readable_workspace, workspaces::find_workspace,
+    },
+    models::{person::Model as Person, sla_policy::Model as SlaPolicy, task::TaskStatus},
+    repos::sla_policies::{PolicyFields, SlaPoliciesRepo},
+    utils::{
+        json::{Json, ValidatedJson},
+        response::{APIError, FieldError},
+        validation,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct SlaPolicyRequest {
+    /// Unique within the workspace, e.g. `Unblock within a day`
+    #[validate(custom(function = "validation::not_blank"))]
+    pub name: String,
+    /// `InProgress` or `Blocked`
+    pub status: TaskStatus,
+    /// Hours a task may stay in `status`, counted in business hours when the
+    /// workspace has them; at most a year
+    #[validate(range(min = 1, max = 8760))]
+    pub max_hours: i32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SlaPolicyListResponse {
+    pub policies: Vec<SlaPolicy>,
+}
+
+/// Trimmed fields; a name taken by another policy of the workspace is a conflict
+async fn validate_policy(
+    policies_repo: &SlaPoliciesRepo,
+    workspace_id: &str,
+    payload: SlaPolicyRequest,
+    policy_id: Option<&str>,
+) -> Result<PolicyFields, APIError> {
+    if !matches!(payload.status, TaskStatus::InProgress | TaskStatus::Blocked) {
+        return Err(APIError::UnprocessableEntity(vec![FieldError {
+            field: "status".to_string(),
+            message: "Only in progress and blocked tasks can have an SLA".to_string(),
+        }]));
+    }
+
+    let name = payload.name.trim().to_string();
+    let taken = policies_repo
+        .get_by_name(workspace_id, &name)
+        .await?
+        .is_some_and(|existing| Some(existing.id.as_str()) != policy_id);
+    if taken {
+        return Err(APIError::Conflict(format!(
+            "SLA policy {} already exists in the workspace",
+            name
+        )));
+    }
+
+    Ok(PolicyFields {
+        name,
+        status: payload.status,
+        max_hours: payload.max_hours,
+    })
+}
+
+/// SLA policies of a workspace
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/sla-policies",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Policies by name", body = SlaPolicyListResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Not linked to the workspace"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn list_sla_policies(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<SlaPolicyListResponse>, APIError> {
+    let workspace = readable_workspace(&state, &person, &workspace_name).await?;
+    let policies = SlaPoliciesRepo::new(state.read_database.clone())
+        .get_by_workspace(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load SLA policies: {}", e);
+            APIError::InternalServerError("Failed to load SLA policies".to_string())
+        })?;
+
+    Ok(Json(SlaPolicyListResponse { policies }))
+}
+
+/// Create an SLA policy. Tasks staying in its status longer are flagged on the
+/// board and their assignees alerted - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/{workspace_name}/sla-policies",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = SlaPolicyRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Created policy", body = SlaPolicy),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 409, description = "A policy with the name exists"),
+        (status = 422, description = "Missing name, closed status or hours out of range"),
+    )
+)]
+pub async fn create_sla_policy(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    ValidatedJson(payload): ValidatedJson<SlaPolicyRequest>,
+) -> Result<(StatusCode, Json<SlaPolicy>), APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let policies_repo = SlaPoliciesRepo::new(state.database.clone());
+    let fields = validate_policy(&policies_repo, &workspace.id, payload, None).await?;
+
+    let policy = policies_repo
+        .create(&workspace.id, fields)
+        .await
+        .map_err(|e| {
+            error!("Failed to create SLA policy: {}", e);
+            APIError::InternalServerError("Failed to create SLA policy".to_string())
+        })?;
+
+    info!(
+        "User {} created SLA policy {} in workspace {}",
+        person.email, policy.name, workspace_name
+    );
+    Ok((StatusCode::CREATED, Json(policy)))
+}
+
+/// Change an SLA policy; breaches found so far stay - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/sla-policies/{policy_id}",
+    tag = "workspaces",
+    params(
+        ("workspace_name" = String, Path, description = "Workspace name"),
+        ("policy_id" = String, Path, description = "Policy id"),
+    ),
+    request_body = SlaPolicyRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Updated policy", body = SlaPolicy),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace or policy not found"),
+        (status = 409, description = "Another policy has the name"),
+        (status = 422, description = "Missing name, closed status or hours out of range"),
+    )
+)]
+pub async fn update_sla_policy(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path((workspace_name, policy_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<SlaPolicyRequest>,
+) -> Result<Json<SlaPolicy>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let policies_repo = SlaPoliciesRepo::new(state.database.clone());
+    let policy = policies_repo
+        .get(&workspace.id, &policy_id)
+        .await?
+        .ok_or_else(|| APIError::NotFound("SLA policy not found".to_string()))?;
+    let fields = validate_policy(&policies_repo, &workspace.id, payload, Some(&policy.id)).await?;
+
+    let policy = policies_repo.update(policy, fields).await.map_err(|e| {
+        error!("Failed to update SLA policy: {}", e);
+        APIError::InternalServerError("Failed to update SLA policy".to_string())
+    })?;
+
+    Ok(Json(policy))
+}
+
+/// Delete an SLA policy with its breaches - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_name}/sla-policies/{policy_id}",
+    tag = "workspaces",
+    params(
+        ("workspace_name" = String, Path, description = "Workspace name"),
+        ("policy_id" = String, Path, description = "Policy id"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Policy deleted"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace or policy not found"),
+    )
+)]
+pub async fn delete_sla_policy(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path((workspace_name, policy_id)): Path<(String, String)>,
+) -> Result<StatusCode, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let policies_repo = SlaPoliciesRepo::new(state.database.clone());
+    let policy = policies_repo
+        .get(&workspace.id, &policy_id)
+        .await?
+        .ok_or_else(|| APIError::NotFound("SLA policy not found".to_string()))?;
+
+    let name = policy.name.clone();
+    policies_repo.delete(policy).await.map_err(|e| {
+        error!("Failed to delete SLA policy: {}", e);
+        APIError::InternalServerError("Failed to delete SLA policy".to_string())
+    })?;
+    // Breaches of the policy no longer flag tasks
+    state.board_events.touch();
+
+    info!(
+        "User {} deleted SLA policy {} in workspace {}",
+        person.email, name, workspace_name
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,242 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    core::state::AppState,
+    handlers::workspaces::find_workspace,
+    models::{person::Model as Person, workspace::Model as Workspace},
+    repos::{
+        slack_status_settings::SlackStatusSettingsRepo, workspace_links::WorkspaceLinksRepo,
+        workspaces::WorkspacesRepo,
+    },
+    services::{slack_status, workspace::fetch_team_id},
+    utils::{
+        encryption::encrypt,
+        json::{Json, ValidatedJson},
+        response::{APIError, FieldError},
+        validation,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct UpdateSlackStatusRequest {
+    /// Your user token for the workspace, with the `users.profile:write` scope.
+    /// Required the first time; leave out to only turn the sync on or off.
+    #[validate(custom(function = "validation::slack_user_token"))]
+    pub user_token: Option<String>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SlackStatusSettingResponse {
+    pub workspace: String,
+    pub enabled: bool,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SlackStatusSettingListResponse {
+    pub settings: Vec<SlackStatusSettingResponse>,
+}
+
+/// Workspace the person is linked to, or 404 as for one that doesn't exist
+async fn find_linked_workspace(
+    state: &AppState,
+    person: &Person,
+    workspace_name: &str,
+) -> Result<Workspace, APIError> {
+    let workspace = find_workspace(state, workspace_name).await?;
+    let linked = WorkspaceLinksRepo::new(state.database.clone())
+        .get_by_person_and_workspace(person.id.clone(), workspace.id.clone())
+        .await
+        .is_ok_and(|link| link.is_linked);
+    if !linked {
+        return Err(APIError::NotFound(format!(
+            "Workspace '{}' not found",
+            workspace_name
+        )));
+    }
+    Ok(workspace)
+}
+
+/// Workspaces where your Slack status follows your task in progress, without the
+/// tokens
+#[utoipa::path(
+    get,
+    path = "/api/me/slack-status",
+    tag = "me",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Slack status settings", body = SlackStatusSettingListResponse),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
+pub async fn list_slack_status_settings(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+) -> Result<Json<SlackStatusSettingListResponse>, APIError> {
+    let settings = SlackStatusSettingsRepo::new(state.read_database.clone())
+        .get_by_person(&person.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load Slack status settings: {}", e);
+            APIError::InternalServerError("Failed to load Slack status settings".to_string())
+        })?;
+
+    let workspaces_repo = WorkspacesRepo::new(state.read_database.clone());
+    let mut responses = Vec::with_capacity(settings.len());
+    for setting in settings {
+        let workspace = workspaces_repo.get(&setting.workspace_id).await?;
+        responses.push(SlackStatusSettingResponse {
+            workspace: workspace.name,
+            enabled: setting.enabled,
+            updated_at: setting.updated_at.and_utc().to_rfc3339(),
+        });
+    }
+
+    Ok(Json(SlackStatusSettingListResponse {
+        settings: responses,
+    }))
+}
+
+/// Have your Slack status show the task you are working on ("Working on ACME-42"),
+/// cleared when you have none in progress
+#[utoipa::path(
+    put,
+    path = "/api/me/slack-status/{workspace_name}",
+    tag = "me",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = UpdateSlackStatusRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Saved setting", body = SlackStatusSettingResponse),
+        (status = 400, description = "Slack rejected the token or it belongs to another workspace"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Workspace not found or not linked"),
+        (status = 422, description = "Malformed token, or none given the first time"),
+    )
+)]
+pub async fn update_slack_status_setting(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    ValidatedJson(payload): ValidatedJson<UpdateSlackStatusRequest>,
+) -> Result<Json<SlackStatusSettingResponse>, APIError> {
+    let workspace = find_linked_workspace(&state, &person, &workspace_name).await?;
+    let settings_repo = SlackStatusSettingsRepo::new(state.database.clone());
+    let existing = settings_repo.get(&person.id, &workspace.id).await?;
+
+    let user_token = match (payload.user_token, &existing) {
+        (Some(token), _) => {
+            let token = token.trim();
+            let team_id = fetch_team_id(token).await.map_err(|e| {
+                warn!("Slack user token of {} cannot be used: {}", person.email, e);
+                APIError::BadRequest(format!("Could not verify user token with Slack: {}", e))
+            })?;
+            if workspace
+                .slack_team_id
+                .as_ref()
+                .is_some_and(|id| *id != team_id)
+            {
+                return Err(APIError::BadRequest(format!(
+                    "The user token belongs to another Slack workspace than '{}'",
+                    workspace_name
+                )));
+            }
+            encrypt(token, &state.config.encryption_key).map_err(|e| {
+                error!("Failed to encrypt Slack user token: {}", e);
+                APIError::InternalServerError("Failed to save Slack status setting".to_string())
+            })?
+        }
+        (None, Some(existing)) => existing.user_token.clone(),
+        (None, None) => {
+            return Err(APIError::UnprocessableEntity(vec![FieldError {
+                field: "user_token".to_string(),
+                message: "is required to turn the sync on for the first time".to_string(),
+            }]))
+        }
+    };
+
+    let setting = settings_repo
+        .upsert(&person.id, &workspace.id, user_token, payload.enabled)
+        .await
+        .map_err(|e| {
+            error!("Failed to save Slack status setting: {}", e);
+            APIError::InternalServerError("Failed to save Slack status setting".to_string())
+        })?;
+
+    // Catch up now rather than at the next task change
+    let synced = if setting.enabled {
+        slack_status::sync(&state, &person.id, &workspace.id).await
+    } else {
+        slack_status::clear(&state, &setting).await
+    };
+    if let Err(e) = synced {
+        warn!("Failed to sync Slack status of {}: {}", person.email, e);
+    }
+
+    info!(
+        "User {} turned Slack status sync {} in workspace {}",
+        person.email,
+        if setting.enabled { "on" } else { "off" },
+        workspace_name
+    );
+    Ok(Json(SlackStatusSettingResponse {
+        workspace: workspace.name,
+        enabled: setting.enabled,
+        updated_at: setting.updated_at.and_utc().to_rfc3339(),
+    }))
+}
+
+/// Stop syncing your Slack status with a workspace, clear it and forget the token
+#[utoipa::path(
+    delete,
+    path = "/api/me/slack-status/{workspace_name}",
+    tag = "me",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Setting deleted"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Workspace not found or Slack status not synced there"),
+    )
+)]
+pub async fn delete_slack_status_setting(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<StatusCode, APIError> {
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings_repo = SlackStatusSettingsRepo::new(state.database.clone());
+    let setting = settings_repo
+        .get(&person.id, &workspace.id)
+        .await?
+        .ok_or_else(|| APIError::NotFound("Slack status is not synced".to_string()))?;
+
+    if setting.enabled {
+        if let Err(e) = slack_status::clear(&state, &setting).await {
+            warn!("Failed to clear Slack status of {}: {}", person.email, e);
+        }
+    }
+    settings_repo
+        .delete(&person.id, &workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete Slack status setting: {}", e);
+            APIError::InternalServerError("Failed to delete Slack status setting".to_string())
+        })?;
+
+    info!(
+        "User {} stopped Slack status sync in workspace {}",
+        person.email, workspace_name
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
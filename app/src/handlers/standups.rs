@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use tracing::{error, info};
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    models::{person::Model as Person, workspace_settings::StandupSettings},
+    repos::workspace_settings::WorkspaceSettingsRepo,
+    services::standups,
+    utils::{json::Json, response::APIError},
+};
+
+/// Daily standup of a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/standup",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Standup settings", body = StandupSettings),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or no standup set up"),
+    )
+)]
+pub async fn get_standup(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<StandupSettings>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_standup_settings(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load standup settings: {}", e);
+            APIError::InternalServerError("Failed to load standup settings".to_string())
+        })?
+        .ok_or_else(|| APIError::NotFound("No standup is set up".to_string()))?;
+
+    Ok(Json(settings))
+}
+
+/// Run a daily standup in the workspace, or change when it runs. The bot DMs the
+/// questions to every linked member at `ask_at` and posts the answers to `channel`
+/// at `summary_at` - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/standup",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = StandupSettings,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Saved standup settings", body = StandupSettings),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 422, description = "Invalid time, timezone or days"),
+    )
+)]
+pub async fn update_standup(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<StandupSettings>,
+) -> Result<Json<StandupSettings>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+    standups::crons(&payload).map_err(APIError::UnprocessableEntity)?;
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = StandupSettings {
+        channel: payload.channel.trim().to_string(),
+        ask_at: payload.ask_at.trim().to_string(),
+        summary_at: payload.summary_at.trim().to_string(),
+        timezone: payload.timezone,
+        days: payload.days.trim().to_string(),
+    };
+    WorkspaceSettingsRepo::new(state.database.clone())
+        .update_standup(&workspace.id, Some(settings.clone()))
+        .await
+        .map_err(|e| {
+            error!("Failed to save standup settings: {}", e);
+            APIError::InternalServerError("Failed to save standup settings".to_string())
+        })?;
+    standups::schedule(&state.database, &workspace.id, Some(&settings))
+        .await
+        .map_err(|e| {
+            error!("Failed to schedule the standup: {}", e);
+            APIError::InternalServerError("Failed to schedule the standup".to_string())
+        })?;
+
+    info!(
+        "User {} set the standup of workspace {} to {} and {} on {}",
+        person.email, workspace_name, settings.ask_at, settings.summary_at, settings.days
+    );
+    Ok(Json(settings))
+}
+
+/// Stop the daily standup of a workspace. Answers given so far are kept - REQUIRES
+/// ADMIN PERMISSION
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_name}/standup",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Standup stopped"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or no standup set up"),
+    )
+)]
+pub async fn delete_standup(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<StatusCode, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings_repo = WorkspaceSettingsRepo::new(state.database.clone());
+    let running = settings_repo
+        .get_standup_settings(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load standup settings: {}", e);
+            APIError::InternalServerError("Failed to load standup settings".to_string())
+        })?
+        .is_some();
+    if !running {
+        return Err(APIError::NotFound("No standup is set up".to_string()));
+    }
+
+    settings_repo
+        .update_standup(&workspace.id, None)
+        .await
+        .map_err(|e| {
+            error!("Failed to stop the standup: {}", e);
+            APIError::InternalServerError("Failed to stop the standup".to_string())
+        })?;
+    standups::schedule(&state.database, &workspace.id, None)
+        .await
+        .map_err(|e| {
+            error!("Failed to unschedule the standup: {}", e);
+            APIError::InternalServerError("Failed to stop the standup".to_string())
+        })?;
+
+    info!(
+        "User {} stopped the standup of workspace {}",
+        person.email, workspace_name
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap},
+};
+use serde::Serialize;
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    models::person::Model as Person,
+    services::task_import::{self, ImportRow, ImportedRow, MAX_ROWS},
+    utils::{json::Json, response::APIError},
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub failed: usize,
+    /// Outcome of every row, in the order of the upload
+    pub rows: Vec<ImportedRow>,
+}
+
+/// Import existing tasks from a CSV file with a header row, or a JSON array of
+/// rows. Assignees are found by email among the workspace's members. Rows that
+/// can't be imported are reported without stopping the others - REQUIRES ADMIN
+/// PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/{workspace_name}/import",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body(
+        content = Vec<ImportRow>,
+        description = "JSON rows, or a CSV file with title, assignee_email, status and created_at columns",
+        content_type = "text/csv"
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Import report", body = ImportReport),
+        (status = 400, description = "The file can't be read or has too many rows"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 415, description = "Neither CSV nor JSON"),
+    )
+)]
+pub async fn import_tasks(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<ImportReport>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+    let workspace = find_workspace(&state, &workspace_name).await?;
+
+    let mime = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let rows = match mime.as_str() {
+        "text/csv" => task_import::parse_csv(&body).map_err(APIError::BadRequest)?,
+        "application/json" => serde_json::from_str::<Vec<ImportRow>>(&body)
+            .map_err(|e| APIError::BadRequest(format!("Malformed JSON body: {}", e)))?,
+        _ => {
+            return Err(APIError::UnsupportedMediaType(
+                "Expected a request with Content-Type: text/csv or application/json".to_string(),
+            ))
+        }
+    };
+    if rows.len() > MAX_ROWS {
+        return Err(APIError::BadRequest(format!(
+            "An import takes at most {} rows",
+            MAX_ROWS
+        )));
+    }
+
+    info!(
+        "User {} is importing {} row(s) into workspace {}",
+        person.email,
+        rows.len(),
+        workspace_name
+    );
+    let rows = task_import::import_rows(&state, &workspace.id, rows).await;
+    let imported = rows.iter().filter(|row| row.error.is_none()).count();
+
+    Ok(Json(ImportReport {
+        imported,
+        failed: rows.len() - imported,
+        rows,
+    }))
+}
@@ -0,0 +1,318 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    core::state::AppState,
+    handlers::{
+        admins::can_configure_workspaces, projects::readable_workspace, workspaces::find_workspace,
+    },
+    models::{
+        person::Model as Person,
+        task::{Model as Task, TaskPriority},
+        task_template::Model as TaskTemplate,
+        workspace::Model as Workspace,
+    },
+    repos::{
+        task_templates::{TaskTemplatesRepo, TemplateFields},
+        workspace_links::WorkspaceLinksRepo,
+    },
+    services::{task_service::TaskService, task_templates},
+    utils::{
+        json::{Json, ValidatedJson},
+        response::APIError,
+        validation,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct TaskTemplateRequest {
+    /// Unique within the workspace
+    #[validate(custom(function = "validation::not_blank"))]
+    pub name: String,
+    /// Text of new tasks; `{date}`, `{assignee}` and `{details}` are filled in
+    #[validate(custom(function = "validation::not_blank"))]
+    pub title_pattern: String,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub priority: Option<TaskPriority>,
+    #[serde(default)]
+    pub checklist: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskTemplateListResponse {
+    pub templates: Vec<TaskTemplate>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct TaskFromTemplateRequest {
+    /// Fills `{details}` in the title pattern, or follows the title
+    #[serde(default)]
+    pub details: String,
+}
+
+/// Trimmed fields without blank labels or checklist items; a name taken by another
+/// template of the workspace is a conflict
+async fn validate_template(
+    templates_repo: &TaskTemplatesRepo,
+    workspace: &Workspace,
+    payload: TaskTemplateRequest,
+    template_id: Option<&str>,
+) -> Result<TemplateFields, APIError> {
+    let name = payload.name.trim().to_string();
+
+    let taken = templates_repo
+        .get_by_name(&workspace.id, &name)
+        .await?
+        .is_some_and(|existing| Some(existing.id.as_str()) != template_id);
+    if taken {
+        return Err(APIError::Conflict(format!(
+            "Template {} already exists in the workspace",
+            name
+        )));
+    }
+
+    let trimmed = |items: Vec<String>| -> Vec<String> {
+        items
+            .into_iter()
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect()
+    };
+    Ok(TemplateFields {
+        name,
+        title_pattern: payload.title_pattern.trim().to_string(),
+        labels: trimmed(payload.labels),
+        priority: payload.priority,
+        checklist: trimmed(payload.checklist),
+    })
+}
+
+/// Task templates of a workspace
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/task-templates",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Templates by name", body = TaskTemplateListResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Not linked to the workspace"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn list_task_templates(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<TaskTemplateListResponse>, APIError> {
+    let workspace = readable_workspace(&state, &person, &workspace_name).await?;
+    let templates = TaskTemplatesRepo::new(state.read_database.clone())
+        .get_by_workspace(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load task templates: {}", e);
+            APIError::InternalServerError("Failed to load task templates".to_string())
+        })?;
+
+    Ok(Json(TaskTemplateListResponse { templates }))
+}
+
+/// Create a task template - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/{workspace_name}/task-templates",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = TaskTemplateRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Created template", body = TaskTemplate),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 409, description = "A template with the name exists"),
+        (status = 422, description = "Missing name or title pattern"),
+    )
+)]
+pub async fn create_task_template(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    ValidatedJson(payload): ValidatedJson<TaskTemplateRequest>,
+) -> Result<(StatusCode, Json<TaskTemplate>), APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let templates_repo = TaskTemplatesRepo::new(state.database.clone());
+    let fields = validate_template(&templates_repo, &workspace, payload, None).await?;
+
+    let template = templates_repo
+        .create(&workspace.id, fields)
+        .await
+        .map_err(|e| {
+            error!("Failed to create task template: {}", e);
+            APIError::InternalServerError("Failed to create task template".to_string())
+        })?;
+
+    info!(
+        "User {} created task template {} in workspace {}",
+        person.email, template.name, workspace_name
+    );
+    Ok((StatusCode::CREATED, Json(template)))
+}
+
+/// Change a task template; tasks made from it stay as they are - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/task-templates/{template_id}",
+    tag = "workspaces",
+    params(
+        ("workspace_name" = String, Path, description = "Workspace name"),
+        ("template_id" = String, Path, description = "Template id"),
+    ),
+    request_body = TaskTemplateRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Updated template", body = TaskTemplate),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace or template not found"),
+        (status = 409, description = "Another template has the name"),
+        (status = 422, description = "Missing name or title pattern"),
+    )
+)]
+pub async fn update_task_template(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path((workspace_name, template_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<TaskTemplateRequest>,
+) -> Result<Json<TaskTemplate>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let templates_repo = TaskTemplatesRepo::new(state.database.clone());
+    let template = templates_repo
+        .get(&workspace.id, &template_id)
+        .await?
+        .ok_or_else(|| APIError::NotFound("Template not found".to_string()))?;
+    let fields =
+        validate_template(&templates_repo, &workspace, payload, Some(&template.id)).await?;
+
+    let template = templates_repo.update(template, fields).await.map_err(|e| {
+        error!("Failed to update task template: {}", e);
+        APIError::InternalServerError("Failed to update task template".to_string())
+    })?;
+
+    Ok(Json(template))
+}
+
+/// Delete a task template - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_name}/task-templates/{template_id}",
+    tag = "workspaces",
+    params(
+        ("workspace_name" = String, Path, description = "Workspace name"),
+        ("template_id" = String, Path, description = "Template id"),
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Template deleted"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace or template not found"),
+    )
+)]
+pub async fn delete_task_template(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path((workspace_name, template_id)): Path<(String, String)>,
+) -> Result<StatusCode, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let templates_repo = TaskTemplatesRepo::new(state.database.clone());
+    let template = templates_repo
+        .get(&workspace.id, &template_id)
+        .await?
+        .ok_or_else(|| APIError::NotFound("Template not found".to_string()))?;
+
+    let name = template.name.clone();
+    templates_repo.delete(template).await.map_err(|e| {
+        error!("Failed to delete task template: {}", e);
+        APIError::InternalServerError("Failed to delete task template".to_string())
+    })?;
+
+    info!(
+        "User {} deleted task template {} in workspace {}",
+        person.email, name, workspace_name
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Create a task for yourself from a template of a workspace you're linked to
+#[utoipa::path(
+    post,
+    path = "/api/tasks/from-template/{template_id}",
+    tag = "tasks",
+    params(("template_id" = String, Path, description = "Template id")),
+    request_body = TaskFromTemplateRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Created task", body = Task),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Not linked to the template's workspace"),
+        (status = 404, description = "Template not found"),
+    )
+)]
+pub async fn create_task_from_template(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(template_id): Path<String>,
+    Json(payload): Json<TaskFromTemplateRequest>,
+) -> Result<(StatusCode, Json<Task>), APIError> {
+    let template = TaskTemplatesRepo::new(state.database.clone())
+        .get_by_id(&template_id)
+        .await?
+        .ok_or_else(|| APIError::NotFound("Template not found".to_string()))?;
+
+    let linked = WorkspaceLinksRepo::new(state.database.clone())
+        .get_by_person_and_workspace(person.id.clone(), template.workspace_id.clone())
+        .await
+        .is_ok_and(|link| link.is_linked);
+    if !linked {
+        return Err(APIError::Forbidden);
+    }
+
+    let task = task_templates::create_task(
+        &state.database,
+        &state.message_encryption,
+        &TaskService::from_state(&state),
+        &template,
+        person,
+        &payload.details,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to create task from template {}: {}", template.id, e);
+        APIError::InternalServerError("Failed to create task".to_string())
+    })?;
+
+    Ok((StatusCode::CREATED, Json(task)))
+}
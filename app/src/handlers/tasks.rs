@@ -1,176 +1,933 @@
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use crate::{
-    core::state::AppState,
-    models::{change::Model as Change, person::Model as Person, task::TaskStatus},
+    core::{board_events::BoardChange, state::AppState},
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    integrations,
+    models::{
+        board_view::BoardViewFilter,
+        change::Model as Change,
+        github_link::Model as GithubLink,
+        message::{Model as Message, HIDDEN_CHANNEL},
+        person::Model as Person,
+        project::Model as Project,
+        task::{Model as Task, TaskPriority, TaskStatus},
+        workspace_link::{Model as WorkspaceLink, WorkspaceRole},
+    },
     repos::{
-        changes::ChangesRepo, messages::MessagesRepo, tasks::TasksRepo,
+        board_views::BoardViewsRepo,
+        changes::ChangesRepo,
+        github_links::GithubLinksRepo,
+        messages::{MessageEncryption, MessagesRepo},
+        persons::PersonsRepo,
+        projects::ProjectsRepo,
+        stores::TasksStore,
+        task_tombstones::TaskTombstonesRepo,
+        tasks::TasksRepo,
         workspace_links::WorkspaceLinksRepo,
+        workspaces::WorkspacesRepo,
+    },
+    services::{
+        board_snapshots::{self, BoardSnapshot, SnapshotEntry},
+        duplicates,
+        sla::{self, Breaches},
+        task_service::{status_since, Outcome},
+    },
+    utils::{
+        etag::{self, IfMatch},
+        json::Json,
+        pagination::{CursorQuery, Pagination},
+        response::{APIError, APIResponse},
+        time::format_in_timezone,
     },
-    utils::response::{APIError, APIResponse},
 };
 use axum::{
     extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
     Extension,
 };
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use futures_util::future::try_join_all;
+use sea_orm::{DatabaseConnection, DbErr};
 use serde::{Deserialize, Serialize};
-use tracing::warn;
+use tracing::{error, warn};
+use utoipa::{IntoParams, ToSchema};
+
+/// Days without a status change after which an open task is stale on the board
+const DEFAULT_STALE_AFTER_DAYS: i64 = 7;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
 pub struct TaskBoardQuery {
+    /// `true` for tasks you initiated, otherwise tasks you took ownership of
     #[serde(default)]
     pub initiated: Option<bool>,
+    /// Only tasks of this project (id)
+    pub project: Option<String>,
+    /// Only tasks matching the filter of one of your saved views (id)
+    pub view: Option<String>,
+    /// Open tasks that have not changed status for more than this many days are
+    /// stale; 7 by default
+    pub stale_after_days: Option<i64>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MyTasksQuery {
+    pub status: Option<TaskStatus>,
+    /// Only tasks created in this workspace
+    pub workspace: Option<String>,
+    /// `next_cursor` of the previous page; the first page without one
+    pub cursor: Option<String>,
+    /// Tasks per page, 20 by default and at most 100
+    pub limit: Option<u64>,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
 pub struct MessageSummary {
     pub id: String,
     pub content: String,
     pub external_id: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TaskResponse {
     pub id: String,
+    /// Human-readable id such as `ACME-42`
+    pub key: Option<String>,
     pub status: TaskStatus,
     pub assigned_to: String,
     pub created_at: String,
+    pub due_at: Option<String>,
+    pub priority: Option<TaskPriority>,
+    pub labels: Vec<String>,
+    pub project_id: Option<String>,
+    /// Set when the assignee left Slack and the task waits for a new one
+    pub orphaned_at: Option<String>,
+    /// Send back as If-Match or `version` when updating the task
+    pub version: i32,
+    /// Whole days since the task was created
+    pub age_days: i64,
+    /// Whole days since the task last changed status; None when that happened
+    /// before status changes were timed
+    pub days_in_current_status: Option<i64>,
     pub message: MessageSummary,
 }
 
-#[derive(Debug, Serialize)]
+impl TaskResponse {
+    /// `status_since` is when the task entered its status, see `status_since`, and
+    /// `tz` the viewer's timezone, used to render `created_at` and `due_at`
+    pub fn new(task: Task, message: Message, status_since: Option<NaiveDateTime>, tz: Tz) -> Self {
+        let now = Utc::now().naive_utc();
+        Self {
+            age_days: (now - task.created_at).num_days(),
+            days_in_current_status: status_since.map(|since| (now - since).num_days()),
+            labels: task.labels(),
+            priority: task.priority,
+            project_id: task.project_id,
+            id: task.id,
+            key: task.key,
+            version: task.version,
+            status: task.status,
+            assigned_to: task.assigned_to,
+            created_at: format_in_timezone(task.created_at, tz),
+            due_at: task.due_at.map(|due_at| format_in_timezone(due_at, tz)),
+            orphaned_at: task
+                .orphaned_at
+                .map(|orphaned_at| format_in_timezone(orphaned_at, tz)),
+            message: MessageSummary {
+                id: message.id,
+                content: message.content,
+                external_id: message.visible_external_id(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AllMyTasksQuery {
+    pub status: Option<TaskStatus>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WorkspaceTaskResponse {
+    /// Name of the workspace the task belongs to
+    pub workspace: String,
+    #[serde(flatten)]
+    pub task: TaskResponse,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AllMyTasksResponse {
+    /// Newest first
+    pub tasks: Vec<WorkspaceTaskResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskListResponse {
+    /// Newest first
+    pub tasks: Vec<TaskResponse>,
+    pub total: u64,
+    /// Pass as `cursor` for the next page, None on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Open tasks whose status has not changed for longer than `after_days`
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct StaleTasks {
+    pub after_days: i64,
+    pub task_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TaskBoard {
     pub in_progress: Vec<TaskResponse>,
     pub blocked: Vec<TaskResponse>,
     pub completed: Vec<TaskResponse>,
+    pub stale: StaleTasks,
+    /// Ids of open tasks whose stay in their status broke an SLA policy
+    pub sla_breached: Vec<String>,
+    /// Pass as `since` to /api/tasks/board/changes for what changed after this board
+    pub cursor: String,
+}
+
+impl TaskBoard {
+    /// Put the task in its status column, among the stale tasks when it sat there
+    /// too long and among the breached ones when that broke an SLA policy
+    fn add(
+        &mut self,
+        task: Task,
+        message: Message,
+        status_since: Option<NaiveDateTime>,
+        breaches: &Breaches,
+        tz: Tz,
+    ) {
+        if breaches.is_breached(&task, status_since) {
+            self.sla_breached.push(task.id.clone());
+        }
+        let status = task.status.clone();
+        let task = TaskResponse::new(task, message, status_since, tz);
+        if status != TaskStatus::Completed
+            && task
+                .days_in_current_status
+                .is_some_and(|days| days > self.stale.after_days)
+        {
+            self.stale.task_ids.push(task.id.clone());
+        }
+
+        match status {
+            TaskStatus::InProgress => self.in_progress.push(task),
+            TaskStatus::Blocked => self.blocked.push(task),
+            TaskStatus::Completed => self.completed.push(task),
+            TaskStatus::Blank => {}
+        };
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BoardChangesQuery {
+    /// `cursor` of the board or of the previous changes
+    pub since: String,
+    /// As for the board
+    #[serde(default)]
+    pub initiated: Option<bool>,
+    /// As for the board
+    pub project: Option<String>,
+    /// As for the board
+    pub view: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BoardChanges {
+    /// Tasks on the board created or changed since the cursor; replace earlier
+    /// copies by id
+    pub tasks: Vec<TaskResponse>,
+    /// Ids of tasks to take off the board: deleted, or changed so they no longer
+    /// fit it
+    pub removed: Vec<String>,
+    /// Pass as `since` next time
+    pub cursor: String,
+}
+
+/// Opaque position in the board's changes: the time they were read at
+fn changes_cursor(at: NaiveDateTime) -> String {
+    URL_SAFE_NO_PAD.encode(at.and_utc().timestamp_micros().to_string())
+}
+
+fn parse_changes_cursor(cursor: &str) -> Option<NaiveDateTime> {
+    let micros = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()?;
+    Some(DateTime::from_timestamp_micros(micros.parse().ok()?)?.naive_utc())
+}
+
+/// What of a person's tasks their board shows, see `TaskBoardQuery`
+struct BoardScope {
+    workspace_id: String,
+    project: Option<String>,
+    filter: Option<BoardViewFilter>,
+}
+
+impl BoardScope {
+    /// The task's message when the task belongs on the board
+    async fn admit(
+        &self,
+        db: &DatabaseConnection,
+        encryption: &MessageEncryption,
+        task: &Task,
+    ) -> Result<Option<Message>, DbErr> {
+        if self.project.is_some() && task.project_id != self.project {
+            return Ok(None);
+        }
+
+        // Only include tasks where the assignee is linked to the active workspace
+        let person_workspace = WorkspaceLinksRepo::new(db.clone())
+            .get_by_person_and_workspace(task.assigned_to.clone(), self.workspace_id.clone())
+            .await;
+        if !person_workspace.is_ok_and(|link| link.is_linked) {
+            return Ok(None);
+        }
+
+        let message = MessagesRepo::new(db.clone(), encryption.clone())
+            .get_by_id(task.message_id.clone())
+            .await?;
+        Ok(self.shows(task, &message).then_some(message))
+    }
+
+    /// Whether the task fits the project and view, whoever it is assigned to
+    fn shows(&self, task: &Task, message: &Message) -> bool {
+        (self.project.is_none() || task.project_id == self.project)
+            && self
+                .filter
+                .as_ref()
+                .is_none_or(|filter| filter.matches(task, message))
+    }
+}
+
+/// Filter of the person's saved view, when one is asked for
+async fn view_filter(
+    db: &DatabaseConnection,
+    person: &Person,
+    view_id: Option<&str>,
+) -> Result<Option<BoardViewFilter>, APIError> {
+    let Some(view_id) = view_id else {
+        return Ok(None);
+    };
+    let view = BoardViewsRepo::new(db.clone())
+        .get(&person.id, view_id)
+        .await?
+        .ok_or_else(|| APIError::NotFound("View not found".to_string()))?;
+    Ok(Some(view.filter()))
+}
+
+/// Every task the board of the person linked by `link` picks from:
+/// - viewers: every task of the workspace, as they own none
+/// - initiated: tasks the person initiated (they wrote the message, someone else reacted)
+/// - otherwise "My Tasks": tasks the person reacted to, so they're their responsibility
+async fn board_tasks(
+    db: &DatabaseConnection,
+    tasks_store: &dyn TasksStore,
+    link: &WorkspaceLink,
+    initiated: bool,
+) -> Result<Vec<Task>, DbErr> {
+    if link.role == WorkspaceRole::Viewer {
+        TasksRepo::new(db.clone())
+            .get_by_workspace(link.workspace_id.clone())
+            .await
+    } else if initiated {
+        tasks_store
+            .get_assigned_by_others(link.person_id.clone())
+            .await
+    } else {
+        tasks_store.get_initiated_by(link.person_id.clone()).await
+    }
 }
 
-#[derive(Debug, Serialize)]
+/// Whether `board_tasks` picks a task of the workspace for the person
+fn picks(link: &WorkspaceLink, initiated: bool, task: &Task) -> bool {
+    let person_id = link.person_id.as_str();
+    if link.role == WorkspaceRole::Viewer {
+        true
+    } else if initiated {
+        task.assigned_to == person_id
+            && task
+                .assigned_by
+                .as_deref()
+                .is_some_and(|assigned_by| assigned_by != person_id)
+    } else {
+        task.assigned_by.as_deref() == Some(person_id)
+    }
+}
+
+/// Tasks of the workspace most boards would read through, when it has at least
+/// `board_snapshot_min_tasks`; built on a cache miss
+async fn board_snapshot(
+    state: &AppState,
+    workspace_id: &str,
+) -> Result<Option<BoardSnapshot>, DbErr> {
+    let Some(min_tasks) = state.config.board_snapshot_min_tasks else {
+        return Ok(None);
+    };
+    if let Some(snapshot) = board_snapshots::get(state, workspace_id).await {
+        return Ok(Some(snapshot));
+    }
+
+    let db = &state.read_database;
+    let tasks_repo = TasksRepo::new(db.clone());
+    if tasks_repo.count_in_workspace(workspace_id).await? < min_tasks {
+        return Ok(None);
+    }
+
+    // Taken before loading, like the board's cursor
+    let built_at = Utc::now();
+    let version = state.board_events.version();
+    let mut tasks = tasks_repo
+        .get_by_workspace(workspace_id.to_string())
+        .await?;
+    tasks.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    let messages_repo = MessagesRepo::new(db.clone(), state.message_encryption.clone());
+    let mut entries = Vec::with_capacity(tasks.len());
+    // Chunked to stay under the bind parameter limits of the databases
+    for chunk in tasks.chunks(1000) {
+        let message_ids = chunk.iter().map(|task| task.message_id.clone()).collect();
+        let mut messages: HashMap<String, Message> = messages_repo
+            .get_by_ids(message_ids)
+            .await?
+            .into_iter()
+            .map(|message| (message.id.clone(), message))
+            .collect();
+        let since = status_since(db, chunk).await?;
+        entries.extend(chunk.iter().filter_map(|task| {
+            Some(SnapshotEntry {
+                message: messages.remove(&task.message_id)?,
+                status_since: since.get(&task.id).copied().flatten(),
+                task: task.clone(),
+            })
+        }));
+    }
+
+    let snapshot = BoardSnapshot { built_at, entries };
+    // A task changed while loading would be stale in the snapshot until the TTL
+    if state.board_events.version() == version {
+        board_snapshots::store(state, workspace_id, &snapshot).await;
+    }
+    Ok(Some(snapshot))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct MessageDetail {
     pub id: String,
     pub content: String,
     pub external_id: String,
+    /// `private` for messages of an anonymized private channel or DM
     pub channel: String,
     pub timestamp: String,
-    pub slack_link: String,
+    /// None when the channel is left out
+    pub slack_link: Option<String>,
+}
+
+impl From<Message> for MessageDetail {
+    fn from(message: Message) -> Self {
+        if message.channel_hidden {
+            return Self {
+                external_id: message.visible_external_id(),
+                id: message.id,
+                content: message.content,
+                channel: HIDDEN_CHANNEL.to_string(),
+                timestamp: message.timestamp,
+                slack_link: None,
+            };
+        }
+
+        Self {
+            slack_link: Some(message.slack_link()),
+            id: message.id,
+            content: message.content,
+            external_id: message.external_id,
+            channel: message.channel,
+            timestamp: message.timestamp,
+        }
+    }
+}
+
+/// Everything known about a task, see `GET /api/tasks/{task_id}/full`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskFullResponse {
+    pub task: Task,
+    pub message: MessageDetail,
+    pub assignee: Option<Person>,
+    pub assigner: Option<Person>,
+    pub project: Option<Project>,
+    /// Status changes, oldest first
+    pub changes: Vec<Change>,
+    pub github_links: Vec<GithubLink>,
+    /// Messages whose tasks were merged into this one as duplicates
+    pub merged_messages: Vec<MessageDetail>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TaskDetailResponse {
     pub id: String,
+    /// Human-readable id such as `ACME-42`
+    pub key: Option<String>,
     pub status: TaskStatus,
     pub assigned_to: String,
     pub created_at: String,
+    pub priority: Option<TaskPriority>,
+    pub labels: Vec<String>,
+    pub project_id: Option<String>,
     pub message: MessageDetail,
     pub changes: Vec<Change>,
+    /// Jira issue created from the task, see `POST /api/tasks/{task_id}/jira`
+    pub jira_issue_key: Option<String>,
+    /// Linear issue pushed for the task when its workspace is connected to Linear
+    pub linear_issue_id: Option<String>,
+    /// GitHub issues and pull requests that mention the task
+    pub github_links: Vec<GithubLink>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateDueDateRequest {
+    /// RFC 3339 timestamp, `null` removes the due date
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateTaskProjectRequest {
+    /// Project of the task's workspace, `null` removes the task from its project
+    pub project_id: Option<String>,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateTaskRequest {
+    /// Column to move the task to; Blank is not allowed
+    pub status: Option<TaskStatus>,
+    /// Version of the task the update is based on, when not sent as If-Match
+    pub version: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MergeTaskRequest {
+    /// Task to fold into this one; it is deleted
+    pub duplicate_task_id: String,
+}
+
+/// Whether a person may change a task: its assignee, whoever assigned it and
+/// workspace admins may
+pub async fn can_manage_task(state: &AppState, person: &Person, task: &Task) -> bool {
+    task.assigned_to == person.id
+        || task.assigned_by.as_deref() == Some(person.id.as_str())
+        || can_configure_workspaces(state, &person.email).await
+}
+
+/// Page through tasks assigned to the current user
+#[utoipa::path(
+    get,
+    path = "/api/tasks/mine",
+    tag = "tasks",
+    params(MyTasksQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Page of tasks assigned to the current user", body = TaskListResponse),
+        (status = 400, description = "Invalid cursor"),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
 pub async fn get_my_tasks(
     State(state): State<Arc<AppState>>,
     Extension(person): Extension<Person>,
+    Query(query): Query<MyTasksQuery>,
 ) -> Result<APIResponse, APIError> {
-    let tasks_repo = TasksRepo {
-        db: state.database.clone(),
+    let pagination = Pagination::from_query(&CursorQuery {
+        cursor: query.cursor,
+        limit: query.limit,
+    })?;
+
+    let tasks_store = &state.read_stores.tasks;
+    let messages_repo = MessagesRepo::new(
+        state.read_database.clone(),
+        state.message_encryption.clone(),
+    );
+
+    let workspace_id = match query.workspace {
+        Some(name) => Some(find_workspace(&state, &name).await?.id),
+        None => None,
     };
 
-    let tasks = tasks_repo.get_assigned(person.id).await?;
-    let response = APIResponse::json(tasks);
+    let (page, total) = tasks_store
+        .get_assigned_page(person.id, query.status, workspace_id, &pagination)
+        .await?;
+    let tasks = page.items;
 
-    Ok(response)
+    let message_ids = tasks.iter().map(|t| t.message_id.clone()).collect();
+    let messages: HashMap<String, Message> = messages_repo
+        .get_by_ids(message_ids)
+        .await?
+        .into_iter()
+        .map(|m| (m.id.clone(), m))
+        .collect();
+
+    let since = status_since(&state.read_database, &tasks).await?;
+    let tz = person.tz();
+    let tasks = tasks
+        .into_iter()
+        .filter_map(|task| {
+            let message = messages.get(&task.message_id)?.clone();
+            let status_since = since.get(&task.id).copied().flatten();
+            Some(TaskResponse::new(task, message, status_since, tz))
+        })
+        .collect();
+
+    Ok(APIResponse::json(TaskListResponse {
+        tasks,
+        total,
+        next_cursor: page.next_cursor,
+    }))
+}
+
+/// Tasks assigned to the current user in every workspace they are linked to, without
+/// switching the active one
+#[utoipa::path(
+    get,
+    path = "/api/me/tasks/all",
+    tag = "me",
+    params(AllMyTasksQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Tasks of all linked workspaces", body = AllMyTasksResponse),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
+pub async fn get_all_my_tasks(
+    State(state): State<Arc<AppState>>,
+    Extension(person): Extension<Person>,
+    Query(query): Query<AllMyTasksQuery>,
+) -> Result<Json<AllMyTasksResponse>, APIError> {
+    let db = &state.read_database;
+    let load_error = |e: DbErr| {
+        error!("Failed to load tasks across workspaces: {}", e);
+        APIError::InternalServerError("Failed to load tasks".to_string())
+    };
+
+    let links = WorkspaceLinksRepo::new(db.clone())
+        .get_by_person(person.id.clone())
+        .await
+        .map_err(load_error)?;
+
+    // One query per workspace, all at once
+    let per_workspace = links.into_iter().filter(|link| link.is_linked).map(|link| {
+        let status = query.status.clone();
+        let person_id = person.id.clone();
+        async move {
+            let workspace = WorkspacesRepo::new(db.clone())
+                .get(&link.workspace_id)
+                .await?;
+            let tasks = TasksRepo::new(db.clone())
+                .get_assigned_in_workspace(&person_id, &workspace.id, status)
+                .await?;
+            Ok::<_, DbErr>((workspace.name, tasks))
+        }
+    });
+    let mut tasks: Vec<(String, Task)> = try_join_all(per_workspace)
+        .await
+        .map_err(load_error)?
+        .into_iter()
+        .flat_map(|(workspace, tasks)| tasks.into_iter().map(move |task| (workspace.clone(), task)))
+        .collect();
+    tasks.sort_by(|(_, a), (_, b)| b.created_at.cmp(&a.created_at));
+
+    let message_ids = tasks.iter().map(|(_, t)| t.message_id.clone()).collect();
+    let messages: HashMap<String, Message> =
+        MessagesRepo::new(db.clone(), state.message_encryption.clone())
+            .get_by_ids(message_ids)
+            .await
+            .map_err(load_error)?
+            .into_iter()
+            .map(|m| (m.id.clone(), m))
+            .collect();
+    let all_tasks: Vec<Task> = tasks.iter().map(|(_, task)| task.clone()).collect();
+    let since = status_since(db, &all_tasks).await.map_err(load_error)?;
+
+    let tz = person.tz();
+    let tasks = tasks
+        .into_iter()
+        .filter_map(|(workspace, task)| {
+            let message = messages.get(&task.message_id)?.clone();
+            let status_since = since.get(&task.id).copied().flatten();
+            Some(WorkspaceTaskResponse {
+                workspace,
+                task: TaskResponse::new(task, message, status_since, tz),
+            })
+        })
+        .collect();
+
+    Ok(Json(AllMyTasksResponse { tasks }))
 }
 
+/// Tasks in the active workspace grouped by status column. Boards of workspaces
+/// with at least `board_snapshot_min_tasks` tasks come from a snapshot; the `Age`
+/// header tells its age in seconds.
+#[utoipa::path(
+    get,
+    path = "/api/tasks/board",
+    tag = "tasks",
+    params(TaskBoardQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Board grouped by status", body = TaskBoard),
+        (status = 304, description = "Unchanged since the ETag in If-None-Match"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "View not found"),
+    )
+)]
 pub async fn get_tasks_board(
     State(state): State<Arc<AppState>>,
     Extension(person): Extension<Person>,
     Query(query): Query<TaskBoardQuery>,
-) -> Result<APIResponse, APIError> {
-    let tasks_repo = TasksRepo::new(state.database.clone());
-    let messages_repo = MessagesRepo::new(state.database.clone());
-    let workspace_links_repo = WorkspaceLinksRepo::new(state.database.clone());
+    headers: HeaderMap,
+) -> Result<Response, APIError> {
+    // Taken before loading, so changes made meanwhile show up in the next changes
+    let cursor = changes_cursor(Utc::now().naive_utc());
+    let filter = view_filter(&state.read_database, &person, query.view.as_deref()).await?;
+
+    let stale_after_days = query
+        .stale_after_days
+        .unwrap_or(DEFAULT_STALE_AFTER_DAYS)
+        .max(0);
+    let initiated = query.initiated.unwrap_or(false).to_string();
+    // Board events don't cover edits to the view, so its filter is part of the tag
+    let filter_key = filter
+        .as_ref()
+        .and_then(|filter| serde_json::to_string(filter).ok())
+        .unwrap_or_default();
+    let etag = etag::versioned_etag(
+        state.board_events.version(),
+        &[
+            &person.id,
+            &initiated,
+            query.project.as_deref().unwrap_or_default(),
+            &filter_key,
+            person.tz().name(),
+            &stale_after_days.to_string(),
+            // Ages grow without board events
+            &Utc::now()
+                .with_timezone(&person.tz())
+                .date_naive()
+                .to_string(),
+        ],
+    );
+    if let Some(not_modified) = etag::not_modified(&headers, &etag) {
+        return Ok(not_modified);
+    }
+
+    let mut board = TaskBoard {
+        in_progress: vec![],
+        blocked: vec![],
+        completed: vec![],
+        stale: StaleTasks {
+            after_days: stale_after_days,
+            task_ids: vec![],
+        },
+        sla_breached: vec![],
+        cursor,
+    };
 
     // Get active workspace for the user
-    let active_workspace = match workspace_links_repo
+    let active_workspace = match WorkspaceLinksRepo::new(state.read_database.clone())
         .get_active_workspace(person.id.clone())
         .await
     {
         Ok(workspace) => workspace,
         Err(_) => {
             warn!("User {} has no active workspace", person.email);
-            return Ok(APIResponse::json(TaskBoard {
-                in_progress: vec![],
-                blocked: vec![],
-                completed: vec![],
-            }));
+            return Ok(etag::with_etag(APIResponse::json(board), &etag));
         }
     };
-
-    // Get tasks based on query:
-    // - initiated=true: tasks user initiated (they wrote the message, someone else reacted)
-    // - initiated=false/missing: "My Tasks" = tasks user reacted to (they took ownership)
-    let user_tasks = if query.initiated.unwrap_or(false) {
-        // Tasks I initiated: I wrote the message, someone else reacted
-        tasks_repo.get_assigned_by_others(person.id.clone()).await?
-    } else {
-        // My Tasks: I reacted to them, so they're my responsibility
-        tasks_repo.get_initiated_by(person.id.clone()).await?
+    let scope = BoardScope {
+        workspace_id: active_workspace.workspace_id.clone(),
+        project: query.project,
+        filter,
     };
+    let initiated = query.initiated.unwrap_or(false);
+    let breaches = sla::workspace_breaches(&state.read_database, &scope.workspace_id).await?;
 
-    let mut board = TaskBoard {
-        in_progress: vec![],
-        blocked: vec![],
-        completed: vec![],
-    };
+    if let Some(snapshot) = board_snapshot(&state, &scope.workspace_id).await? {
+        let linked: HashSet<String> = WorkspaceLinksRepo::new(state.read_database.clone())
+            .get_by_workspace(scope.workspace_id.clone())
+            .await?
+            .into_iter()
+            .map(|link| link.person_id)
+            .collect();
+        for entry in snapshot.entries {
+            if picks(&active_workspace, initiated, &entry.task)
+                && linked.contains(&entry.task.assigned_to)
+                && scope.shows(&entry.task, &entry.message)
+            {
+                board.add(
+                    entry.task,
+                    entry.message,
+                    entry.status_since,
+                    &breaches,
+                    person.tz(),
+                );
+            }
+        }
+        // Changes since the snapshot was built are still to be fetched
+        board.cursor = changes_cursor(snapshot.built_at.naive_utc());
 
-    for task in user_tasks {
-        // Only include tasks where the assignee is linked to the active workspace
-        let person_workspace = workspace_links_repo
-            .get_by_person_and_workspace(
-                task.assigned_to.clone(),
-                active_workspace.workspace_name.clone(),
-            )
-            .await;
+        let age = (Utc::now() - snapshot.built_at).num_seconds().max(0);
+        return Ok((
+            [(header::AGE, age.to_string())],
+            etag::with_etag(APIResponse::json(board), &etag),
+        )
+            .into_response());
+    }
 
-        // Skip if person not linked to this workspace
-        if person_workspace.is_err() || !person_workspace.as_ref().unwrap().is_linked {
+    let user_tasks = board_tasks(
+        &state.read_database,
+        state.read_stores.tasks.as_ref(),
+        &active_workspace,
+        initiated,
+    )
+    .await?;
+
+    let since = status_since(&state.read_database, &user_tasks).await?;
+    for task in user_tasks {
+        let Some(message) = scope
+            .admit(&state.read_database, &state.message_encryption, &task)
+            .await?
+        else {
             continue;
-        }
+        };
+        let status_since = since.get(&task.id).copied().flatten();
+        board.add(task, message, status_since, &breaches, person.tz());
+    }
 
-        let message = messages_repo.get_by_id(task.message_id.clone()).await?;
+    Ok(etag::with_etag(APIResponse::json(board), &etag))
+}
 
-        let task_response = TaskResponse {
-            id: task.id.clone(),
-            status: task.status.clone(),
-            assigned_to: task.assigned_to.clone(),
-            created_at: task.created_at.to_string(),
-            message: MessageSummary {
-                id: message.id,
-                content: message.content,
-                external_id: message.external_id,
-            },
-        };
+/// What changed on the board since a cursor, for clients that poll instead of
+/// following the board over SSE or WebSocket. Cursors older than
+/// `task_tombstone_retention_days` are refused; load the whole board again then.
+#[utoipa::path(
+    get,
+    path = "/api/tasks/board/changes",
+    tag = "tasks",
+    params(BoardChangesQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Changed and removed tasks", body = BoardChanges),
+        (status = 400, description = "Invalid or expired cursor"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "View not found"),
+    )
+)]
+pub async fn get_board_changes(
+    State(state): State<Arc<AppState>>,
+    Extension(person): Extension<Person>,
+    Query(query): Query<BoardChangesQuery>,
+) -> Result<Json<BoardChanges>, APIError> {
+    let now = Utc::now().naive_utc();
+    let since = parse_changes_cursor(&query.since)
+        .ok_or_else(|| APIError::BadRequest("Invalid cursor".to_string()))?;
+    if since < now - chrono::Duration::days(state.config.task_tombstone_retention_days) {
+        return Err(APIError::BadRequest(
+            "Cursor expired, load the board again".to_string(),
+        ));
+    }
 
-        match task.status {
-            TaskStatus::InProgress => board.in_progress.push(task_response),
-            TaskStatus::Blocked => board.blocked.push(task_response),
-            TaskStatus::Completed => board.completed.push(task_response),
-            TaskStatus::Blank => {}
-        };
+    // A lagging replica would let changes slip behind the cursor
+    let db = &state.database;
+    let filter = view_filter(db, &person, query.view.as_deref()).await?;
+    let mut changes = BoardChanges {
+        tasks: vec![],
+        removed: vec![],
+        cursor: changes_cursor(now),
+    };
+
+    let Ok(active_workspace) = WorkspaceLinksRepo::new(db.clone())
+        .get_active_workspace(person.id.clone())
+        .await
+    else {
+        return Ok(Json(changes));
+    };
+    let scope = BoardScope {
+        workspace_id: active_workspace.workspace_id.clone(),
+        project: query.project,
+        filter,
+    };
+
+    let changed: Vec<Task> = board_tasks(
+        db,
+        state.stores.tasks.as_ref(),
+        &active_workspace,
+        query.initiated.unwrap_or(false),
+    )
+    .await?
+    .into_iter()
+    .filter(|task| task.updated_at > since)
+    .collect();
+    let entered = status_since(db, &changed).await?;
+    for task in changed {
+        match scope.admit(db, &state.message_encryption, &task).await? {
+            Some(message) if task.status != TaskStatus::Blank => {
+                let status_since = entered.get(&task.id).copied().flatten();
+                changes
+                    .tasks
+                    .push(TaskResponse::new(task, message, status_since, person.tz()));
+            }
+            _ => changes.removed.push(task.id),
+        }
     }
 
-    Ok(APIResponse::json(board))
+    let tombstones_repo = TaskTombstonesRepo::new(db.clone());
+    let tombstones = if active_workspace.role == WorkspaceRole::Viewer {
+        tombstones_repo
+            .get_for_workspace_since(&scope.workspace_id, since)
+            .await?
+    } else {
+        tombstones_repo
+            .get_for_person_since(&person.id, since)
+            .await?
+    };
+    changes
+        .removed
+        .extend(tombstones.into_iter().map(|tombstone| tombstone.task_id));
+
+    Ok(Json(changes))
 }
 
+/// Task with its Slack message and status change history
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{task_id}",
+    tag = "tasks",
+    params(("task_id" = String, Path, description = "Task ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Task detail", body = TaskDetailResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Task not found"),
+    )
+)]
 pub async fn get_task_detail(
     State(state): State<Arc<AppState>>,
-    Extension(_person): Extension<Person>,
+    Extension(person): Extension<Person>,
     Path(task_id): Path<String>,
 ) -> Result<APIResponse, APIError> {
-    let tasks_repo = TasksRepo::new(state.database.clone());
-    let messages_repo = MessagesRepo::new(state.database.clone());
-    let changes_repo = ChangesRepo::new(state.database.clone());
+    let messages_repo = MessagesRepo::new(
+        state.read_database.clone(),
+        state.message_encryption.clone(),
+    );
+    let changes_repo = ChangesRepo::new(state.read_database.clone());
 
     // Get task
-    let task = tasks_repo
+    let task = state
+        .read_stores
+        .tasks
         .get(task_id.clone())
         .await
         .map_err(|_| APIError::NotFound("Task not found".to_string()))?;
@@ -180,33 +937,417 @@ pub async fn get_task_detail(
 
     // Get change history
     let changes = changes_repo
-        .get_all_for_task(task_id)
+        .get_all_for_task(task_id.clone())
+        .await
+        .unwrap_or_default();
+    let github_links = GithubLinksRepo::new(state.read_database.clone())
+        .get_for_task(&task_id)
         .await
         .unwrap_or_default();
-
-    // Construct Slack link
-    // Format: https://slack.com/archives/{channel}/p{timestamp_without_dot}
-    let timestamp_for_link = message.timestamp.replace(".", "");
-    let slack_link = format!(
-        "https://slack.com/archives/{}/p{}",
-        message.channel, timestamp_for_link
-    );
 
     let response = TaskDetailResponse {
+        labels: task.labels(),
+        priority: task.priority,
+        project_id: task.project_id,
         id: task.id,
+        key: task.key,
         status: task.status,
         assigned_to: task.assigned_to,
-        created_at: task.created_at.to_string(),
-        message: MessageDetail {
-            id: message.id,
-            content: message.content,
-            external_id: message.external_id,
-            channel: message.channel,
-            timestamp: message.timestamp,
-            slack_link,
-        },
+        created_at: format_in_timezone(task.created_at, person.tz()),
+        jira_issue_key: task.jira_issue_key,
+        linear_issue_id: task.linear_issue_id,
+        github_links,
+        message: message.into(),
         changes,
     };
 
     Ok(APIResponse::json(response))
 }
+
+/// Task with its Slack message and status change history, by key such as `ACME-42`
+#[utoipa::path(
+    get,
+    path = "/api/tasks/key/{key}",
+    tag = "tasks",
+    params(("key" = String, Path, description = "Task key, case-insensitive")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Task detail", body = TaskDetailResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "No task has the key"),
+    )
+)]
+pub async fn get_task_by_key(
+    State(state): State<Arc<AppState>>,
+    Extension(person): Extension<Person>,
+    Path(key): Path<String>,
+) -> Result<APIResponse, APIError> {
+    let task = TasksRepo::new(state.read_database.clone())
+        .get_by_key(&key.to_ascii_uppercase())
+        .await?
+        .ok_or_else(|| APIError::NotFound("Task not found".to_string()))?;
+
+    get_task_detail(State(state), Extension(person), Path(task.id)).await
+}
+
+/// Task with its message, people, project, history, GitHub links and merged
+/// duplicates in one response, one query each
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{task_id}/full",
+    tag = "tasks",
+    params(("task_id" = String, Path, description = "Task ID")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Task with everything related to it", body = TaskFullResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Task not found"),
+    )
+)]
+pub async fn get_task_full(
+    State(state): State<Arc<AppState>>,
+    Path(task_id): Path<String>,
+) -> Result<Json<TaskFullResponse>, APIError> {
+    let db = state.read_database.clone();
+    let task = state
+        .read_stores
+        .tasks
+        .get(task_id.clone())
+        .await
+        .map_err(|_| APIError::NotFound("Task not found".to_string()))?;
+
+    let load_error = |e: DbErr| {
+        error!("Failed to load task {}: {}", task_id, e);
+        APIError::InternalServerError("Failed to load task".to_string())
+    };
+
+    let message = MessagesRepo::new(db.clone(), state.message_encryption.clone())
+        .get_by_id(task.message_id.clone())
+        .await?;
+    let merged_messages = MessagesRepo::new(db.clone(), state.message_encryption.clone())
+        .get_merged_into(&task.id)
+        .await
+        .map_err(load_error)?;
+
+    let person_ids = std::iter::once(task.assigned_to.clone())
+        .chain(task.assigned_by.clone())
+        .collect();
+    let people = PersonsRepo::new(db.clone())
+        .get_by_ids(person_ids)
+        .await
+        .map_err(load_error)?;
+    let person = |id: &str| people.iter().find(|person| person.id == id).cloned();
+
+    let project = match (task.workspace_id.as_deref(), task.project_id.as_deref()) {
+        (Some(workspace_id), Some(project_id)) => ProjectsRepo::new(db.clone())
+            .get(workspace_id, project_id)
+            .await
+            .map_err(load_error)?,
+        _ => None,
+    };
+    let changes = ChangesRepo::new(db.clone())
+        .get_all_for_task(task.id.clone())
+        .await
+        .map_err(load_error)?;
+    let github_links = GithubLinksRepo::new(db)
+        .get_for_task(&task.id)
+        .await
+        .map_err(load_error)?;
+
+    Ok(Json(TaskFullResponse {
+        assignee: person(&task.assigned_to),
+        assigner: task.assigned_by.as_deref().and_then(person),
+        message: message.into(),
+        merged_messages: merged_messages.into_iter().map(Into::into).collect(),
+        project,
+        changes,
+        github_links,
+        task,
+    }))
+}
+
+/// Set or clear the due date of a task. Allowed for the task's assignee, whoever
+/// assigned it and workspace admins.
+#[utoipa::path(
+    put,
+    path = "/api/tasks/{task_id}/due-date",
+    tag = "tasks",
+    params(("task_id" = String, Path, description = "Task id")),
+    request_body = UpdateDueDateRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Updated task", body = Task),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Not involved in the task"),
+        (status = 404, description = "Task not found"),
+    )
+)]
+pub async fn update_task_due_date(
+    State(state): State<Arc<AppState>>,
+    Extension(person): Extension<Person>,
+    Path(task_id): Path<String>,
+    Json(payload): Json<UpdateDueDateRequest>,
+) -> Result<Json<Task>, APIError> {
+    let tasks_store = &state.stores.tasks;
+    let task = tasks_store
+        .get(task_id)
+        .await
+        .map_err(|_| APIError::NotFound("Task not found".to_string()))?;
+
+    if !can_manage_task(&state, &person, &task).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let task = tasks_store
+        .set_due_at(task, payload.due_at.map(|due_at| due_at.naive_utc()))
+        .await
+        .map_err(|e| {
+            error!("Failed to update due date: {}", e);
+            APIError::InternalServerError("Failed to update due date".to_string())
+        })?;
+
+    publish_updated(&state, &task).await;
+
+    Ok(Json(task))
+}
+
+/// Put a task in a project of its workspace or take it out of its project. Allowed
+/// for the task's assignee, whoever assigned it and workspace admins.
+#[utoipa::path(
+    put,
+    path = "/api/tasks/{task_id}/project",
+    tag = "tasks",
+    params(("task_id" = String, Path, description = "Task id")),
+    request_body = UpdateTaskProjectRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Updated task", body = Task),
+        (status = 400, description = "Not a project of the task's workspace"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Not involved in the task"),
+        (status = 404, description = "Task not found"),
+    )
+)]
+pub async fn update_task_project(
+    State(state): State<Arc<AppState>>,
+    Extension(person): Extension<Person>,
+    Path(task_id): Path<String>,
+    Json(payload): Json<UpdateTaskProjectRequest>,
+) -> Result<Json<Task>, APIError> {
+    let tasks_store = &state.stores.tasks;
+    let task = tasks_store
+        .get(task_id)
+        .await
+        .map_err(|_| APIError::NotFound("Task not found".to_string()))?;
+
+    if !can_manage_task(&state, &person, &task).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let project_id = match payload.project_id {
+        Some(project_id) => {
+            let project = match task.workspace_id.as_deref() {
+                Some(workspace_id) => {
+                    ProjectsRepo::new(state.database.clone())
+                        .get(workspace_id, &project_id)
+                        .await?
+                }
+                None => None,
+            };
+            let project = project.ok_or_else(|| {
+                APIError::BadRequest(format!(
+                    "{} is not a project of the task's workspace",
+                    project_id
+                ))
+            })?;
+            Some(project.id)
+        }
+        None => None,
+    };
+
+    let task = tasks_store
+        .set_project(task, project_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to update task project: {}", e);
+            APIError::InternalServerError("Failed to update task project".to_string())
+        })?;
+
+    publish_updated(&state, &task).await;
+
+    Ok(Json(task))
+}
+
+/// Tell boards about a task changed outside `TaskService`
+async fn publish_updated(state: &AppState, task: &Task) {
+    if let Some(workspace_id) = task.workspace_id.clone() {
+        if let Ok(workspace) = WorkspacesRepo::new(state.database.clone())
+            .get(&workspace_id)
+            .await
+        {
+            state
+                .board_events
+                .publish(&workspace.name, BoardChange::Updated, task.clone());
+        }
+    }
+}
+
+/// Update a task. The update names the version of the task it is based on, as
+/// If-Match or `version`, and is refused when the task changed since. Moving it to
+/// another column respects the workspace's WIP limits: a hard limit refuses the
+/// move, a soft one DMs the assignee. The bot reacts with the column's emoji in
+/// Slack. Allowed for the task's assignee, whoever assigned it and workspace admins.
+#[utoipa::path(
+    patch,
+    path = "/api/tasks/{task_id}",
+    tag = "tasks",
+    params(
+        ("task_id" = String, Path, description = "Task id"),
+        ("If-Match" = Option<String>, Header, description = "Version the update is based on, e.g. \"3\""),
+    ),
+    request_body = UpdateTaskRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Updated task, its version as ETag", body = Task),
+        (status = 400, description = "Invalid status or If-Match"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Not involved in the task"),
+        (status = 404, description = "Task not found"),
+        (status = 409, description = "The task changed since the given version, the current task under `current`, or a hard WIP limit refuses the move"),
+        (status = 428, description = "Neither If-Match nor version given"),
+    )
+)]
+pub async fn update_task(
+    State(state): State<Arc<AppState>>,
+    Extension(person): Extension<Person>,
+    Path(task_id): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateTaskRequest>,
+) -> Result<Response, APIError> {
+    let task = state
+        .stores
+        .tasks
+        .get(task_id.clone())
+        .await
+        .map_err(|_| APIError::NotFound("Task not found".to_string()))?;
+
+    if !can_manage_task(&state, &person, &task).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let expected = match etag::if_match(&headers) {
+        IfMatch::Version(version) => Some(version),
+        IfMatch::Any => None,
+        IfMatch::Invalid => {
+            return Err(APIError::BadRequest(
+                "If-Match must name a task version, e.g. \"3\"".to_string(),
+            ))
+        }
+        IfMatch::Missing => Some(payload.version.ok_or_else(|| {
+            APIError::PreconditionRequired(
+                "Send the task version the update is based on as If-Match or `version`".to_string(),
+            )
+        })?),
+    };
+    if expected.is_some_and(|version| version != task.version) {
+        return Err(APIError::StaleVersion(
+            serde_json::to_value(&task).unwrap_or_default(),
+        ));
+    }
+
+    let Some(status) = payload.status.filter(|status| *status != task.status) else {
+        let tag = etag::version_tag(task.version);
+        return Ok(etag::with_etag(Json(task), &tag));
+    };
+    if status == TaskStatus::Blank {
+        return Err(APIError::BadRequest(
+            "Tasks cannot be moved to Blank".to_string(),
+        ));
+    }
+
+    // Another update may land between the check above and the write
+    let outcome = match integrations::set_task_status(&state, task, status, expected).await {
+        Ok(outcome) => outcome,
+        Err(e) if matches!(e.downcast_ref::<DbErr>(), Some(DbErr::RecordNotUpdated)) => {
+            let current = state
+                .stores
+                .tasks
+                .get(task_id)
+                .await
+                .map_err(|_| APIError::NotFound("Task not found".to_string()))?;
+            return Err(APIError::StaleVersion(
+                serde_json::to_value(&current).unwrap_or_default(),
+            ));
+        }
+        Err(e) => {
+            error!("Failed to update task status: {}", e);
+            return Err(APIError::InternalServerError(
+                "Failed to update task".to_string(),
+            ));
+        }
+    };
+    let task = match outcome {
+        Outcome::Done(task) => task,
+        Outcome::Blocked(wip) => return Err(APIError::Conflict(wip.message())),
+    };
+
+    let tag = etag::version_tag(task.version);
+    Ok(etag::with_etag(Json(task), &tag))
+}
+
+/// Merge a duplicate into a task. The duplicate's status history and GitHub links
+/// move over, issue links, due date, priority, project and labels fill in what the task lacks,
+/// then the duplicate is deleted and its Slack message no longer creates tasks. The
+/// person must be allowed to manage both tasks.
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{task_id}/merge",
+    tag = "tasks",
+    params(("task_id" = String, Path, description = "Id of the task to keep")),
+    request_body = MergeTaskRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Task with the duplicate merged in", body = Task),
+        (status = 400, description = "A task cannot be merged into itself"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Not involved in one of the tasks"),
+        (status = 404, description = "Task not found"),
+    )
+)]
+pub async fn merge_task(
+    State(state): State<Arc<AppState>>,
+    Extension(person): Extension<Person>,
+    Path(task_id): Path<String>,
+    Json(payload): Json<MergeTaskRequest>,
+) -> Result<Json<Task>, APIError> {
+    if payload.duplicate_task_id == task_id {
+        return Err(APIError::BadRequest(
+            "A task cannot be merged into itself".to_string(),
+        ));
+    }
+
+    let tasks_store = &state.stores.tasks;
+    let task = tasks_store
+        .get(task_id)
+        .await
+        .map_err(|_| APIError::NotFound("Task not found".to_string()))?;
+    let duplicate = tasks_store
+        .get(payload.duplicate_task_id)
+        .await
+        .map_err(|_| APIError::NotFound("Duplicate task not found".to_string()))?;
+
+    if !can_manage_task(&state, &person, &task).await
+        || !can_manage_task(&state, &person, &duplicate).await
+    {
+        return Err(APIError::Forbidden);
+    }
+
+    let task = duplicates::merge(&state, task, duplicate)
+        .await
+        .map_err(|e| {
+            error!("Failed to merge tasks: {}", e);
+            APIError::InternalServerError("Failed to merge tasks".to_string())
+        })?;
+
+    Ok(Json(task))
+}
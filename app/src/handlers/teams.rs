@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    integrations::teams,
+    models::{person::Model as Person, workspace_settings::TeamsSettings},
+    repos::workspace_settings::WorkspaceSettingsRepo,
+    utils::{
+        encryption::encrypt,
+        json::{Json, ValidatedJson},
+        response::{APIError, FieldError},
+        validation,
+    },
+};
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct UpdateTeamsConnectionRequest {
+    /// Incoming webhook of the Teams channel: the URL of a "Post to a channel when a
+    /// webhook request is received" workflow, or of a legacy connector
+    #[validate(custom(function = "validation::https_url"))]
+    pub webhook_url: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TeamsConnectionResponse {
+    pub webhook_host: String,
+}
+
+impl From<TeamsSettings> for TeamsConnectionResponse {
+    fn from(settings: TeamsSettings) -> Self {
+        Self {
+            webhook_host: settings.webhook_host,
+        }
+    }
+}
+
+/// Microsoft Teams channel following a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/teams",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Teams connection, without the webhook URL", body = TeamsConnectionResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or Teams not connected"),
+    )
+)]
+pub async fn get_teams_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<TeamsConnectionResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_teams_settings(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load Teams connection: {}", e);
+            APIError::InternalServerError("Failed to load Teams connection".to_string())
+        })?
+        .ok_or_else(|| APIError::NotFound("Teams is not connected".to_string()))?;
+
+    Ok(Json(settings.into()))
+}
+
+/// Have a Microsoft Teams channel follow the workspace's task activity, or replace
+/// the channel. A card confirming the connection is posted there first - REQUIRES
+/// ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/teams",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = UpdateTeamsConnectionRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Saved Teams connection", body = TeamsConnectionResponse),
+        (status = 400, description = "Teams refused the confirmation card"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 422, description = "Not a Teams webhook URL"),
+    )
+)]
+pub async fn update_teams_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    ValidatedJson(payload): ValidatedJson<UpdateTeamsConnectionRequest>,
+) -> Result<Json<TeamsConnectionResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let webhook_url = payload.webhook_url.trim();
+    let Some(webhook_host) = teams::webhook_host(webhook_url) else {
+        return Err(APIError::UnprocessableEntity(vec![FieldError {
+            field: "webhook_url".to_string(),
+            message: "must be a Microsoft Teams webhook URL".to_string(),
+        }]));
+    };
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+
+    // Teams webhooks can't be looked up, so posting is the only proof it works
+    teams::post(
+        &Client::new(),
+        webhook_url,
+        "Connected to Slacker",
+        &format!(
+            "Tasks created and moved in the '{}' workspace will be posted here.",
+            workspace_name
+        ),
+    )
+    .await
+    .map_err(|e| {
+        warn!("Teams webhook of {} cannot be used: {}", workspace_name, e);
+        APIError::BadRequest(format!("Cannot use Teams webhook: {}", e))
+    })?;
+
+    let encrypted_url = encrypt(webhook_url, &state.config.encryption_key).map_err(|e| {
+        error!("Failed to encrypt Teams webhook: {}", e);
+        APIError::InternalServerError("Failed to save Teams connection".to_string())
+    })?;
+
+    info!(
+        "User {} connecting workspace {} to Teams webhook on {}",
+        person.email, workspace_name, webhook_host
+    );
+
+    let settings = TeamsSettings {
+        webhook_url: encrypted_url,
+        webhook_host,
+    };
+    WorkspaceSettingsRepo::new(state.database.clone())
+        .update_teams(&workspace.id, Some(settings.clone()))
+        .await
+        .map_err(|e| {
+            error!("Failed to save Teams connection: {}", e);
+            APIError::InternalServerError("Failed to save Teams connection".to_string())
+        })?;
+
+    Ok(Json(settings.into()))
+}
+
+/// Stop posting a workspace's task activity to Microsoft Teams - REQUIRES ADMIN
+/// PERMISSION
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_name}/teams",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Teams disconnected"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found or Teams not connected"),
+    )
+)]
+pub async fn delete_teams_connection(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<StatusCode, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings_repo = WorkspaceSettingsRepo::new(state.database.clone());
+    let connected = settings_repo
+        .get_teams_settings(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load Teams connection: {}", e);
+            APIError::InternalServerError("Failed to load Teams connection".to_string())
+        })?
+        .is_some();
+    if !connected {
+        return Err(APIError::NotFound("Teams is not connected".to_string()));
+    }
+
+    settings_repo
+        .update_teams(&workspace.id, None)
+        .await
+        .map_err(|e| {
+            error!("Failed to disconnect Teams: {}", e);
+            APIError::InternalServerError("Failed to disconnect Teams".to_string())
+        })?;
+
+    info!(
+        "User {} disconnected workspace {} from Teams",
+        person.email, workspace_name
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
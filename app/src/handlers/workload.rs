@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::Response,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::error;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    core::state::AppState,
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    models::{person::Model as Person, workspace_link::WorkspaceRole},
+    repos::workspace_links::WorkspaceLinksRepo,
+    services::workload::{self, MemberWorkload},
+    utils::{etag, json::Json, response::APIError},
+};
+
+/// Completion rates look this many days back unless asked otherwise
+const DEFAULT_WINDOW_DAYS: i64 = 30;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct WorkloadQuery {
+    /// Days of assigned tasks completion rates cover, 30 by default
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WorkloadResponse {
+    pub days: i64,
+    /// Most loaded first
+    pub members: Vec<MemberWorkload>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SuggestedAssigneeResponse {
+    /// None when the workspace has no linked members or all are out of office
+    pub assignee: Option<MemberWorkload>,
+}
+
+/// Whether a person may read the workload of a workspace: admins and its viewers,
+/// who follow it from the outside, may
+async fn can_read_workload(state: &AppState, person: &Person, workspace_name: &str) -> bool {
+    if can_configure_workspaces(state, &person.email).await {
+        return true;
+    }
+    let Ok(workspace) = find_workspace(state, workspace_name).await else {
+        return false;
+    };
+    WorkspaceLinksRepo::new(state.database.clone())
+        .get_by_person_and_workspace(person.id.clone(), workspace.id)
+        .await
+        .is_ok_and(|link| link.is_linked && link.role == WorkspaceRole::Viewer)
+}
+
+/// ETag of a workload report. Task ages grow without any change, so a tag stays
+/// valid for at most a minute
+async fn workload_etag(
+    state: &AppState,
+    person: &Person,
+    workspace_name: &str,
+    report: &str,
+) -> Result<String, APIError> {
+    if !can_read_workload(state, person, workspace_name).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let minute = (Utc::now().timestamp() / 60).to_string();
+    Ok(etag::versioned_etag(
+        state.board_events.version(),
+        &[workspace_name, report, &minute],
+    ))
+}
+
+async fn load_workload(
+    state: &AppState,
+    workspace_name: &str,
+    days: i64,
+) -> Result<Vec<MemberWorkload>, APIError> {
+    let workspace = find_workspace(state, workspace_name).await?;
+    workload::workspace_workload(&state.read_database, &workspace.id, days)
+        .await
+        .map_err(|e| {
+            error!("Failed to compute workload: {}", e);
+            APIError::InternalServerError("Failed to compute workload".to_string())
+        })
+}
+
+/// Open tasks, their average age and completion rates per linked member -
+/// REQUIRES ADMIN PERMISSION or viewing the workspace
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/workload",
+    tag = "workspaces",
+    params(
+        ("workspace_name" = String, Path, description = "Workspace name"),
+        WorkloadQuery,
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Workload per member", body = WorkloadResponse),
+        (status = 304, description = "Unchanged since the ETag in If-None-Match"),
+        (status = 400, description = "Invalid window"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission or the viewer role"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn get_workload(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Query(query): Query<WorkloadQuery>,
+    headers: HeaderMap,
+) -> Result<Response, APIError> {
+    let days = query.days.unwrap_or(DEFAULT_WINDOW_DAYS);
+    if !(1..=365).contains(&days) {
+        return Err(APIError::BadRequest(
+            "days must be between 1 and 365".to_string(),
+        ));
+    }
+
+    let etag = workload_etag(&state, &person, &workspace_name, &days.to_string()).await?;
+    if let Some(not_modified) = etag::not_modified(&headers, &etag) {
+        return Ok(not_modified);
+    }
+
+    let members = load_workload(&state, &workspace_name, days).await?;
+    Ok(etag::with_etag(
+        Json(WorkloadResponse { days, members }),
+        &etag,
+    ))
+}
+
+/// The least loaded linked member who is not out of office, to assign a new task
+/// to - REQUIRES ADMIN PERMISSION or viewing the workspace
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/workload/suggest",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Suggested assignee", body = SuggestedAssigneeResponse),
+        (status = 304, description = "Unchanged since the ETag in If-None-Match"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission or the viewer role"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn suggest_assignee(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, APIError> {
+    let etag = workload_etag(&state, &person, &workspace_name, "suggest").await?;
+    if let Some(not_modified) = etag::not_modified(&headers, &etag) {
+        return Ok(not_modified);
+    }
+
+    let members = load_workload(&state, &workspace_name, DEFAULT_WINDOW_DAYS).await?;
+    let response = SuggestedAssigneeResponse {
+        assignee: workload::suggest_assignee(&members).cloned(),
+    };
+    Ok(etag::with_etag(Json(response), &etag))
+}
@@ -3,29 +3,102 @@ use std::sync::Arc;
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    Json,
 };
 use serde::{Deserialize, Serialize};
-use tracing::{error, info};
+use tracing::{error, info, warn};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
 
 use crate::{
-    config::workspaces::{WorkspaceConfig, WorkspacesConfig},
-    core::state::AppState,
+    config::workspaces::WorkspaceConfig,
+    core::{
+        cache::keys as cache_keys,
+        events::DomainEvent,
+        jobs::{self, Job},
+        state::AppState,
+    },
     handlers::admins::can_configure_workspaces,
+    i18n::Locale,
     models::{
-        person::Model as Person, workspace_link::Model as WorkspaceLink,
-        workspace_settings::EmojiMappings,
+        invitation::Model as Invitation,
+        notification_setting::NotificationKind,
+        person::Model as Person,
+        task::TaskStatus,
+        workspace::Model as Workspace,
+        workspace_link::{Model as WorkspaceLink, WorkspaceRole},
+        workspace_settings::{
+            EmojiMappings, KeywordTrigger, MessageRedaction, NotificationRoute,
+            PrivateChannelPolicy, ReactionPolicy, StatusResolution, WipLimit,
+        },
     },
     repos::{
-        persons::PersonsRepo, workspace_links::WorkspaceLinksRepo,
-        workspace_settings::WorkspaceSettingsRepo,
+        invitations::InvitationsRepo, persons::PersonsRepo, workspace_links::WorkspaceLinksRepo,
+        workspace_settings::WorkspaceSettingsRepo, workspaces::WorkspacesRepo,
+    },
+    services::{
+        diagnostics::{self, DiagnosticCheck},
+        email_templates::Email,
+        emojis::{self, STANDARD_EMOJI},
+        slack_service::STATUS_PRECEDENCE,
+        user::lookup_user_by_email,
+        workspace::{
+            check_app_token, fetch_bot_identity, get_cached_workspace_tokens, get_workspace_tokens,
+            offboard_workspace, save_workspace, BotIdentity, OffboardMode, OffboardReport,
+        },
+    },
+    utils::{
+        crypto::generate_uuid,
+        json::{Json, ValidatedJson},
+        pagination::{CursorQuery, Pagination},
+        response::{APIError, FieldError},
+        validation,
     },
-    services::user::fetch_user_by_email_with_config,
-    utils::{crypto::generate_uuid, response::APIError},
 };
 use axum::extract::Query;
+use sea_orm::DbErr;
+
+/// Resolve a workspace from the name used in paths and request bodies
+pub(crate) async fn find_workspace(
+    state: &AppState,
+    workspace_name: &str,
+) -> Result<Workspace, APIError> {
+    let workspaces_repo = WorkspacesRepo::new(state.database.clone());
+    workspaces_repo
+        .get_by_name(workspace_name)
+        .await
+        .map_err(|e| match e {
+            DbErr::RecordNotFound(_) => {
+                APIError::NotFound(format!("Workspace '{}' not found", workspace_name))
+            }
+            e => {
+                error!("Failed to load workspace {}: {}", workspace_name, e);
+                APIError::InternalServerError("Failed to load workspace".to_string())
+            }
+        })
+}
+
+/// Load the decrypted Slack tokens of a workspace
+async fn find_workspace_tokens(
+    state: &AppState,
+    workspace: &Workspace,
+) -> Result<WorkspaceConfig, APIError> {
+    get_cached_workspace_tokens(
+        &state.cache,
+        &state.database,
+        &workspace.id,
+        &state.config.encryption_key,
+    )
+    .await
+    .map_err(|e| {
+        error!(
+            "Failed to load tokens for workspace {}: {}",
+            workspace.name, e
+        );
+        APIError::InternalServerError("Failed to load workspace configuration".to_string())
+    })
+}
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WorkspaceInfo {
     name: String,
     is_linked: bool,
@@ -39,34 +112,43 @@ pub struct WorkspaceInfo {
     sync_progress: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WorkspaceListResponse {
     workspaces: Vec<WorkspaceInfo>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct LinkWorkspaceRequest {
+    #[validate(custom(function = "validation::not_blank"))]
     workspace_name: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct LinkWorkspaceResponse {
     success: bool,
     message: String,
     link: Option<WorkspaceLink>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/workspaces",
+    tag = "workspaces",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Configured workspaces with link and bot status", body = WorkspaceListResponse),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
 pub async fn list_workspaces(
     State(state): State<Arc<AppState>>,
     person: Person,
 ) -> Result<Json<WorkspaceListResponse>, APIError> {
-    // Load and decrypt workspaces from YAML
-    let workspaces_config =
-        WorkspacesConfig::load_and_decrypt("workspaces.yaml", &state.config.encryption_key)
-            .map_err(|e| {
-                error!("Failed to load workspaces config: {}", e);
-                APIError::InternalServerError("Failed to load workspaces configuration".to_string())
-            })?;
+    let workspaces_repo = WorkspacesRepo::new(state.database.clone());
+    let configured_workspaces = workspaces_repo.get_active().await.map_err(|e| {
+        error!("Failed to load workspaces: {}", e);
+        APIError::InternalServerError("Failed to load workspaces configuration".to_string())
+    })?;
 
     let workspace_links_repo = WorkspaceLinksRepo::new(state.database.clone());
     let user_links = workspace_links_repo
@@ -77,15 +159,16 @@ pub async fn list_workspaces(
     // Get all bot statuses
     let bot_statuses = state.bot_status.get_all_statuses().await;
 
-    let workspace_names = workspaces_config.list_workspaces();
-    let workspaces: Vec<WorkspaceInfo> = workspace_names
-        .iter()
-        .map(|name| {
-            let link = user_links.iter().find(|l| &l.workspace_name == name);
-            let bot_status = bot_statuses.iter().find(|s| &s.workspace_name == name);
+    let workspaces: Vec<WorkspaceInfo> = configured_workspaces
+        .into_iter()
+        .map(|workspace| {
+            let link = user_links.iter().find(|l| l.workspace_id == workspace.id);
+            let bot_status = bot_statuses
+                .iter()
+                .find(|s| s.workspace_name == workspace.name);
 
             WorkspaceInfo {
-                name: name.clone(),
+                name: workspace.name,
                 is_linked: link.map(|l| l.is_linked).unwrap_or(false),
                 is_active: link.map(|l| l.is_active).unwrap_or(false),
                 slack_member_id: link.and_then(|l| l.slack_member_id.clone()),
@@ -103,27 +186,31 @@ pub async fn list_workspaces(
     Ok(Json(WorkspaceListResponse { workspaces }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/link",
+    tag = "workspaces",
+    request_body = LinkWorkspaceRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Workspace linked", body = LinkWorkspaceResponse),
+        (status = 400, description = "User not in Slack workspace"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
 pub async fn link_workspace(
     State(state): State<Arc<AppState>>,
     person: Person,
-    Json(payload): Json<LinkWorkspaceRequest>,
+    ValidatedJson(payload): ValidatedJson<LinkWorkspaceRequest>,
 ) -> Result<Json<LinkWorkspaceResponse>, APIError> {
     info!(
         "Attempting to link {} to workspace: {}",
         person.email, payload.workspace_name
     );
 
-    // Load and decrypt workspace config
-    let workspaces_config =
-        WorkspacesConfig::load_and_decrypt("workspaces.yaml", &state.config.encryption_key)
-            .map_err(|e| {
-                error!("Failed to load workspaces config: {}", e);
-                APIError::InternalServerError("Failed to load workspaces configuration".to_string())
-            })?;
-
-    let workspace_config = workspaces_config
-        .get_workspace(&payload.workspace_name)
-        .ok_or_else(|| APIError::BadRequest("Workspace not found".to_string()))?;
+    let workspace = find_workspace(&state, &payload.workspace_name).await?;
+    let workspace_config = find_workspace_tokens(&state, &workspace).await?;
 
     // Debug: log token prefix to verify it's loading correctly
     info!(
@@ -136,9 +223,10 @@ pub async fn link_workspace(
     );
 
     // Check if user exists in this Slack workspace
-    let (slack_member_id, _slack_name) = fetch_user_by_email_with_config(
+    let (slack_member_id, _slack_name) = lookup_user_by_email(
+        &state,
+        &workspace.id,
         &workspace_config.bot_token,
-        &state.config.google_client_id,
         &person.email,
     )
     .await
@@ -155,7 +243,7 @@ pub async fn link_workspace(
     let link = workspace_links_repo
         .link_workspace(
             person.id.clone(),
-            payload.workspace_name.clone(),
+            workspace.id.clone(),
             slack_member_id.clone(),
         )
         .await
@@ -163,6 +251,10 @@ pub async fn link_workspace(
             error!("Failed to link workspace: {}", e);
             APIError::InternalServerError("Failed to link workspace".to_string())
         })?;
+    state.events.publish(DomainEvent::WorkspaceLinked {
+        person_id: person.id.clone(),
+        workspace_id: workspace.id.clone(),
+    });
 
     // Update person's external_id with Slack member ID if not already set
     if person.external_id.is_empty() {
@@ -179,31 +271,20 @@ pub async fn link_workspace(
     }
 
     // Trigger initial sync in the background
-    let workspace_name = payload.workspace_name.clone();
-    let bot_token = workspace_config.bot_token.clone();
-    let db = state.database.clone();
-    let bot_status = state.bot_status.clone();
-    let member_id = slack_member_id.clone();
-
-    tokio::spawn(async move {
-        let syncer = crate::sockets::slack_bot::InitialSyncer::new(
-            workspace_name.clone(),
-            bot_token,
-            db,
-            bot_status,
-        );
-
-        info!(
-            "Starting initial sync for newly linked workspace: {}",
-            workspace_name
+    if let Err(e) = jobs::enqueue(
+        &state.database,
+        Job::InitialSync {
+            workspace_id: workspace.id.clone(),
+            slack_member_id: Some(slack_member_id.clone()),
+        },
+    )
+    .await
+    {
+        error!(
+            "Failed to queue initial sync for workspace {}: {}",
+            workspace.name, e
         );
-        if let Err(e) = syncer.perform_initial_sync(&member_id).await {
-            error!(
-                "Initial sync failed for workspace {}: {}",
-                workspace_name, e
-            );
-        }
-    });
+    }
 
     Ok(Json(LinkWorkspaceResponse {
         success: true,
@@ -215,15 +296,27 @@ pub async fn link_workspace(
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/unlink",
+    tag = "workspaces",
+    request_body = LinkWorkspaceRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Workspace unlinked", body = LinkWorkspaceResponse),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
 pub async fn unlink_workspace(
     State(state): State<Arc<AppState>>,
     person: Person,
-    Json(payload): Json<LinkWorkspaceRequest>,
+    ValidatedJson(payload): ValidatedJson<LinkWorkspaceRequest>,
 ) -> Result<Json<LinkWorkspaceResponse>, APIError> {
+    let workspace = find_workspace(&state, &payload.workspace_name).await?;
     let workspace_links_repo = WorkspaceLinksRepo::new(state.database.clone());
 
     workspace_links_repo
-        .unlink_workspace(person.id, payload.workspace_name.clone())
+        .unlink_workspace(person.id, workspace.id)
         .await
         .map_err(|e| {
             error!("Failed to unlink workspace: {}", e);
@@ -240,20 +333,33 @@ pub async fn unlink_workspace(
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/switch",
+    tag = "workspaces",
+    request_body = LinkWorkspaceRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Active workspace switched", body = LinkWorkspaceResponse),
+        (status = 400, description = "Workspace not linked"),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
 pub async fn switch_workspace(
     State(state): State<Arc<AppState>>,
     person: Person,
-    Json(payload): Json<LinkWorkspaceRequest>,
+    ValidatedJson(payload): ValidatedJson<LinkWorkspaceRequest>,
 ) -> Result<Json<LinkWorkspaceResponse>, APIError> {
     info!(
         "Switching {} to workspace: {}",
         person.email, payload.workspace_name
     );
 
+    let workspace = find_workspace(&state, &payload.workspace_name).await?;
     let workspace_links_repo = WorkspaceLinksRepo::new(state.database.clone());
 
     let link = workspace_links_repo
-        .set_active_workspace(person.id, payload.workspace_name.clone())
+        .set_active_workspace(person.id, workspace.id)
         .await
         .map_err(|e| {
             error!("Failed to switch workspace: {}", e);
@@ -267,6 +373,16 @@ pub async fn switch_workspace(
     }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/active",
+    tag = "workspaces",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Active workspace link, if any", body = Option<WorkspaceLink>),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
 pub async fn get_active_workspace(
     State(state): State<Arc<AppState>>,
     person: Person,
@@ -279,14 +395,21 @@ pub async fn get_active_workspace(
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct SetupWorkspaceRequest {
+    #[validate(custom(function = "validation::not_blank"))]
     workspace_name: String,
+    #[validate(custom(function = "validation::slack_app_token"))]
     app_token: String,
+    #[validate(custom(function = "validation::slack_bot_token"))]
     bot_token: String,
+    /// Slack team of the workspace. Only needed with org-wide tokens of an
+    /// Enterprise Grid install, which belong to the whole org.
+    #[serde(default)]
+    slack_team_id: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SetupWorkspaceResponse {
     success: bool,
     message: String,
@@ -294,10 +417,24 @@ pub struct SetupWorkspaceResponse {
 
 /// Setup a new workspace - REQUIRES ADMIN PERMISSION
 /// Tokens are encrypted before being stored
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/setup",
+    tag = "workspaces",
+    request_body = SetupWorkspaceRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Workspace configured and bot started", body = SetupWorkspaceResponse),
+        (status = 400, description = "Token rejected by Slack"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 422, description = "Invalid workspace name or token format"),
+    )
+)]
 pub async fn setup_workspace(
     State(state): State<Arc<AppState>>,
     person: Person,
-    Json(payload): Json<SetupWorkspaceRequest>,
+    ValidatedJson(payload): ValidatedJson<SetupWorkspaceRequest>,
 ) -> Result<Json<SetupWorkspaceResponse>, APIError> {
     // Check if user has permission to configure workspaces
     if !can_configure_workspaces(&state, &person.email).await {
@@ -309,55 +446,55 @@ pub async fn setup_workspace(
         person.email, payload.workspace_name
     );
 
-    // Validate tokens
-    if !payload.app_token.starts_with("xapp-") {
-        return Err(APIError::BadRequest(
-            "Invalid app token format. Should start with 'xapp-'".to_string(),
-        ));
-    }
-    if !payload.bot_token.starts_with("xoxb-") {
-        return Err(APIError::BadRequest(
-            "Invalid bot token format. Should start with 'xoxb-'".to_string(),
-        ));
-    }
-
-    // Load and decrypt existing config (to avoid double-encrypting existing tokens)
-    let mut workspaces_config =
-        WorkspacesConfig::load_and_decrypt("workspaces.yaml", &state.config.encryption_key)
-            .unwrap_or_else(|_| WorkspacesConfig::new());
-
-    // Clone tokens for bot spawning before moving into config
-    let app_token_for_bot = payload.app_token.clone();
-    let bot_token_for_bot = payload.bot_token.clone();
+    // Resolve the Slack team so the workspace keeps its identity across renames
+    let bot = fetch_bot_identity(&payload.bot_token).await.map_err(|e| {
+        error!("Failed to verify bot token: {}", e);
+        APIError::BadRequest(format!("Could not verify bot token with Slack: {}", e))
+    })?;
+    let slack_team_id = bot
+        .workspace_team(payload.slack_team_id.as_deref())
+        .map_err(|message| {
+            APIError::UnprocessableEntity(vec![FieldError {
+                field: "slack_team_id".to_string(),
+                message,
+            }])
+        })?;
 
-    // Add workspace with plain tokens (will be encrypted on save)
-    workspaces_config.add_workspace(
-        payload.workspace_name.clone(),
-        WorkspaceConfig {
-            app_token: payload.app_token,
-            bot_token: payload.bot_token,
-        },
-    );
+    let tokens = WorkspaceConfig {
+        app_token: payload.app_token,
+        bot_token: payload.bot_token,
+        enterprise_id: bot.org_wide_enterprise_id(),
+    };
 
-    // Save with encryption
-    workspaces_config
-        .save_encrypted("workspaces.yaml", &state.config.encryption_key)
-        .map_err(|e| {
-            error!("Failed to save workspaces config: {}", e);
-            APIError::InternalServerError("Failed to save workspace configuration".to_string())
-        })?;
+    // Tokens are encrypted before being stored
+    let workspace = save_workspace(
+        &state.database,
+        &payload.workspace_name,
+        &tokens,
+        Some(slack_team_id),
+        Some(person.email.clone()),
+        &state.config.encryption_key,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to save workspace: {}", e);
+        APIError::InternalServerError("Failed to save workspace configuration".to_string())
+    })?;
+    state.cache.invalidate_workspace(&workspace.id).await;
 
     info!(
         "Workspace '{}' configured and encrypted successfully",
         payload.workspace_name
     );
 
-    // Dynamically spawn the bot for this workspace
-    state.spawn_bot(
-        payload.workspace_name.clone(),
-        app_token_for_bot,
-        bot_token_for_bot,
-    );
+    // Dynamically spawn the bot for this workspace, here rather than on another instance
+    state
+        .start_bot(workspace, tokens.app_token, tokens.bot_token)
+        .await
+        .map_err(|e| {
+            error!("Failed to start bot: {}", e);
+            APIError::InternalServerError("Failed to start bot".to_string())
+        })?;
 
     Ok(Json(SetupWorkspaceResponse {
         success: true,
@@ -368,71 +505,224 @@ pub async fn setup_workspace(
     }))
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DeleteWorkspaceQuery {
+    /// `archive` (default) keeps history, `purge` deletes everything
+    pub mode: Option<OffboardMode>,
+    /// Report what would be removed without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeleteWorkspaceResponse {
+    pub success: bool,
+    pub message: String,
+    pub dry_run: bool,
+    pub mode: OffboardMode,
+    pub removed: OffboardReport,
+}
+
+/// Remove a workspace - REQUIRES ADMIN PERMISSION
+/// Stops its bot and removes credentials, then archives or purges its data
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_name}",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name"), DeleteWorkspaceQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Workspace removed, or dry-run report", body = DeleteWorkspaceResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn delete_workspace(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Query(query): Query<DeleteWorkspaceQuery>,
+) -> Result<Json<DeleteWorkspaceResponse>, APIError> {
+    // Check if user has permission to configure workspaces
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let mode = query.mode.unwrap_or_default();
+    info!(
+        "User {} removing workspace {} (mode: {:?}, dry run: {})",
+        person.email, workspace_name, mode, query.dry_run
+    );
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+
+    if !query.dry_run {
+        state.stop_bot(&workspace).await;
+    }
+
+    let removed = offboard_workspace(
+        &state.database,
+        &state.message_encryption,
+        &workspace,
+        mode,
+        query.dry_run,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to remove workspace {}: {}", workspace_name, e);
+        APIError::InternalServerError("Failed to remove workspace".to_string())
+    })?;
+    if !query.dry_run {
+        state.cache.invalidate_workspace(&workspace.id).await;
+    }
+
+    let message = match (query.dry_run, mode) {
+        (true, _) => format!("Dry run: nothing was removed from '{}'", workspace_name),
+        (false, OffboardMode::Archive) => format!("Workspace '{}' archived", workspace_name),
+        (false, OffboardMode::Purge) => format!("Workspace '{}' deleted", workspace_name),
+    };
+
+    Ok(Json(DeleteWorkspaceResponse {
+        success: true,
+        message,
+        dry_run: query.dry_run,
+        mode,
+        removed,
+    }))
+}
+
 // ============== Workspace Settings ==============
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WorkspaceSettingsResponse {
     pub workspace_name: String,
     pub emoji_mappings: EmojiMappings,
+    pub status_resolution: StatusResolution,
+    pub reaction_policy: ReactionPolicy,
+    pub private_channel_policy: PrivateChannelPolicy,
+    /// Language of bot messages for members who did not pick their own
+    pub locale: Locale,
+    /// Statuses from strongest to weakest; decides under `completed_wins` and breaks
+    /// ties under `majority`
+    pub status_precedence: Vec<TaskStatus>,
+    pub message_redaction: MessageRedaction,
     pub has_app_token: bool,
     pub has_bot_token: bool,
 }
 
 /// Get workspace settings including emoji mappings
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/settings",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Workspace settings", body = WorkspaceSettingsResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
 pub async fn get_workspace_settings(
     State(state): State<Arc<AppState>>,
     _person: Person,
     Path(workspace_name): Path<String>,
 ) -> Result<Json<WorkspaceSettingsResponse>, APIError> {
-    // Check if workspace exists
-    let workspaces_config =
-        WorkspacesConfig::load_and_decrypt("workspaces.yaml", &state.config.encryption_key)
-            .map_err(|e| {
-                error!("Failed to load workspaces config: {}", e);
-                APIError::InternalServerError("Failed to load workspaces configuration".to_string())
-            })?;
-
-    let workspace_config = workspaces_config.get_workspace(&workspace_name);
-    if workspace_config.is_none() {
-        return Err(APIError::NotFound(format!(
-            "Workspace '{}' not found",
-            workspace_name
-        )));
-    }
-
-    let config = workspace_config.unwrap();
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let config = find_workspace_tokens(&state, &workspace).await?;
 
-    // Get emoji mappings from database
-    let settings_repo = WorkspaceSettingsRepo::new(state.database.clone());
-    let emoji_mappings = settings_repo
-        .get_emoji_mappings(&workspace_name)
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_by_workspace(&workspace.id)
         .await
         .map_err(|e| {
             error!("Failed to get workspace settings: {}", e);
             APIError::InternalServerError("Failed to get workspace settings".to_string())
         })?;
+    let (
+        emoji_mappings,
+        message_redaction,
+        status_resolution,
+        reaction_policy,
+        private_channel_policy,
+        locale,
+    ) = match settings {
+        Some(settings) => (
+            settings.get_emoji_mappings(),
+            settings.message_redaction,
+            settings.status_resolution,
+            settings.reaction_policy,
+            settings.private_channel_policy,
+            settings.locale,
+        ),
+        None => (
+            EmojiMappings::default_mappings(),
+            MessageRedaction::None,
+            StatusResolution::default(),
+            ReactionPolicy::default(),
+            PrivateChannelPolicy::default(),
+            Locale::default(),
+        ),
+    };
 
     Ok(Json(WorkspaceSettingsResponse {
         workspace_name,
         emoji_mappings,
+        status_resolution,
+        reaction_policy,
+        private_channel_policy,
+        locale,
+        status_precedence: STATUS_PRECEDENCE.to_vec(),
+        message_redaction,
         has_app_token: !config.app_token.is_empty(),
         has_bot_token: !config.bot_token.is_empty(),
     }))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct UpdateTokenRequest {
+    #[validate(custom(function = "validation::slack_app_token"))]
     pub app_token: Option<String>,
+    #[validate(custom(function = "validation::slack_bot_token"))]
     pub bot_token: Option<String>,
+    /// Check the tokens with Slack without saving them
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
-/// Update workspace tokens (app_token and/or bot_token) - REQUIRES ADMIN PERMISSION
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UpdateTokenResponse {
+    pub success: bool,
+    pub message: String,
+    pub dry_run: bool,
+    /// Who the bot token signs in as
+    pub bot: BotIdentity,
+}
+
+/// Update workspace tokens (app_token and/or bot_token). Both are checked with Slack
+/// first, and the running bot is restarted with them - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/tokens",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = UpdateTokenRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Tokens verified, and saved unless dry_run", body = UpdateTokenResponse),
+        (status = 400, description = "Token rejected by Slack or of another Slack team"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 422, description = "Invalid token format"),
+    )
+)]
 pub async fn update_workspace_tokens(
     State(state): State<Arc<AppState>>,
     person: Person,
     Path(workspace_name): Path<String>,
-    Json(payload): Json<UpdateTokenRequest>,
-) -> Result<Json<SetupWorkspaceResponse>, APIError> {
+    ValidatedJson(payload): ValidatedJson<UpdateTokenRequest>,
+) -> Result<Json<UpdateTokenResponse>, APIError> {
     // Check if user has permission to configure workspaces
     if !can_configure_workspaces(&state, &person.email).await {
         return Err(APIError::Forbidden);
@@ -443,68 +733,199 @@ pub async fn update_workspace_tokens(
         person.email, workspace_name
     );
 
-    // Validate tokens if provided
-    if let Some(ref app_token) = payload.app_token {
-        if !app_token.starts_with("xapp-") {
-            return Err(APIError::BadRequest(
-                "Invalid app token format. Should start with 'xapp-'".to_string(),
-            ));
-        }
-    }
-    if let Some(ref bot_token) = payload.bot_token {
-        if !bot_token.starts_with("xoxb-") {
-            return Err(APIError::BadRequest(
-                "Invalid bot token format. Should start with 'xoxb-'".to_string(),
-            ));
-        }
-    }
-
-    // Load existing config
-    let mut workspaces_config =
-        WorkspacesConfig::load_and_decrypt("workspaces.yaml", &state.config.encryption_key)
-            .map_err(|e| {
-                error!("Failed to load workspaces config: {}", e);
-                APIError::InternalServerError("Failed to load workspaces configuration".to_string())
-            })?;
-
-    let existing_config = workspaces_config
-        .get_workspace(&workspace_name)
-        .ok_or_else(|| APIError::NotFound(format!("Workspace '{}' not found", workspace_name)))?
-        .clone();
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let existing_config = find_workspace_tokens(&state, &workspace).await?;
 
     // Update tokens
-    let updated_config = WorkspaceConfig {
+    let mut updated_config = WorkspaceConfig {
         app_token: payload.app_token.unwrap_or(existing_config.app_token),
         bot_token: payload.bot_token.unwrap_or(existing_config.bot_token),
+        enterprise_id: None,
     };
 
-    workspaces_config.add_workspace(workspace_name.clone(), updated_config);
-
-    // Save with encryption
-    workspaces_config
-        .save_encrypted("workspaces.yaml", &state.config.encryption_key)
+    let bot = fetch_bot_identity(&updated_config.bot_token)
+        .await
+        .map_err(|e| {
+            warn!("Bot token for {} rejected: {}", workspace_name, e);
+            APIError::BadRequest(format!("Could not verify bot token with Slack: {}", e))
+        })?;
+    bot.workspace_team(workspace.slack_team_id.as_deref())
+        .map_err(APIError::BadRequest)?;
+    updated_config.enterprise_id = bot.org_wide_enterprise_id();
+    check_app_token(&updated_config.app_token)
+        .await
         .map_err(|e| {
-            error!("Failed to save workspaces config: {}", e);
-            APIError::InternalServerError("Failed to save workspace configuration".to_string())
+            warn!("App token for {} rejected: {}", workspace_name, e);
+            APIError::BadRequest(format!("Could not verify app token with Slack: {}", e))
         })?;
 
+    if payload.dry_run {
+        return Ok(Json(UpdateTokenResponse {
+            success: true,
+            message: format!(
+                "Tokens work for workspace '{}' and were not saved",
+                workspace_name
+            ),
+            dry_run: true,
+            bot,
+        }));
+    }
+
+    // Save with encryption
+    save_workspace(
+        &state.database,
+        &workspace.name,
+        &updated_config,
+        None,
+        None,
+        &state.config.encryption_key,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to save workspace tokens: {}", e);
+        APIError::InternalServerError("Failed to save workspace configuration".to_string())
+    })?;
+    state.cache.invalidate_workspace(&workspace.id).await;
+
     info!("Workspace '{}' tokens updated successfully", workspace_name);
 
-    Ok(Json(SetupWorkspaceResponse {
+    // Replaces the running bot, also when another instance runs it
+    state
+        .start_bot(
+            workspace,
+            updated_config.app_token,
+            updated_config.bot_token,
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to restart bot: {}", e);
+            APIError::InternalServerError("Failed to restart bot".to_string())
+        })?;
+
+    Ok(Json(UpdateTokenResponse {
         success: true,
         message: format!(
-            "Tokens updated for workspace '{}'. Restart the server to apply changes.",
+            "Tokens updated for workspace '{}' and bot restarted with them",
             workspace_name
         ),
+        dry_run: false,
+        bot,
+    }))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DiagnosticsResponse {
+    /// Whether every check passed
+    passed: bool,
+    checks: Vec<DiagnosticCheck>,
+}
+
+/// Check a workspace's Slack app: the bot token authenticates with the right team
+/// and has every scope Slacker uses, and the app token can open Socket Mode
+/// connections - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/diagnostics",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Checklist, also when checks fail", body = DiagnosticsResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn get_workspace_diagnostics(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<DiagnosticsResponse>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    // Uncached, so tokens rotated on another instance are the ones checked
+    let checks =
+        match get_workspace_tokens(&state.database, &workspace.id, &state.config.encryption_key)
+            .await
+        {
+            Ok(tokens) => diagnostics::run(&workspace, &tokens).await,
+            Err(e) => vec![DiagnosticCheck {
+                name: "credentials".to_string(),
+                passed: false,
+                detail: format!("Cannot load the workspace's tokens: {}", e),
+            }],
+        };
+
+    Ok(Json(DiagnosticsResponse {
+        passed: checks.iter().all(|check| check.passed),
+        checks,
+    }))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmojiListResponse {
+    /// The workspace's own emoji
+    custom: Vec<String>,
+    /// Standard emoji shortcodes
+    standard: Vec<String>,
+}
+
+/// List the emoji that can be mapped to statuses in a workspace
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/emojis",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Custom and standard emoji names", body = EmojiListResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn get_workspace_emojis(
+    State(state): State<Arc<AppState>>,
+    _person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<EmojiListResponse>, APIError> {
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let custom = emojis::custom_emoji(&state, &workspace)
+        .await
+        .map_err(|e| {
+            error!("Failed to list custom emoji of {}: {}", workspace_name, e);
+            APIError::InternalServerError("Failed to list emoji".to_string())
+        })?;
+
+    Ok(Json(EmojiListResponse {
+        custom,
+        standard: STANDARD_EMOJI.iter().map(|name| name.to_string()).collect(),
     }))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateEmojiMappingsRequest {
     pub emoji_mappings: EmojiMappings,
 }
 
 /// Update emoji to status mappings for a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/emoji-mappings",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = UpdateEmojiMappingsRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Emoji mappings updated", body = WorkspaceSettingsResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 422, description = "Mapping names an emoji the workspace doesn't have, or maps one emoji to several statuses"),
+    )
+)]
 pub async fn update_emoji_mappings(
     State(state): State<Arc<AppState>>,
     person: Person,
@@ -521,42 +942,71 @@ pub async fn update_emoji_mappings(
         person.email, workspace_name
     );
 
-    // Check if workspace exists
-    let workspaces_config =
-        WorkspacesConfig::load_and_decrypt("workspaces.yaml", &state.config.encryption_key)
-            .map_err(|e| {
-                error!("Failed to load workspaces config: {}", e);
-                APIError::InternalServerError("Failed to load workspaces configuration".to_string())
-            })?;
-
-    if workspaces_config.get_workspace(&workspace_name).is_none() {
-        return Err(APIError::NotFound(format!(
-            "Workspace '{}' not found",
-            workspace_name
-        )));
+    let overlaps = emojis::overlapping_emojis(&payload.emoji_mappings);
+    if !overlaps.is_empty() {
+        return Err(APIError::UnprocessableEntity(overlaps));
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+
+    // Without the custom emoji there is nothing reliable to check against
+    match emojis::custom_emoji(&state, &workspace).await {
+        Ok(custom) => {
+            let errors = emojis::unknown_emojis(&payload.emoji_mappings, &custom);
+            if !errors.is_empty() {
+                return Err(APIError::UnprocessableEntity(errors));
+            }
+        }
+        Err(e) => warn!(
+            "Skipping emoji validation, failed to list custom emoji of {}: {}",
+            workspace_name, e
+        ),
     }
 
     // Update emoji mappings in database
     let settings_repo = WorkspaceSettingsRepo::new(state.database.clone());
     let settings = settings_repo
-        .update_emoji_mappings(&workspace_name, payload.emoji_mappings.clone())
+        .update_emoji_mappings(&workspace.id, payload.emoji_mappings.clone())
         .await
         .map_err(|e| {
             error!("Failed to update emoji mappings: {}", e);
             APIError::InternalServerError("Failed to update emoji mappings".to_string())
         })?;
+    state
+        .cache
+        .delete(&cache_keys::emoji_mappings(&workspace.id))
+        .await;
 
     info!("Emoji mappings updated for workspace '{}'", workspace_name);
 
     Ok(Json(WorkspaceSettingsResponse {
         workspace_name,
         emoji_mappings: settings.get_emoji_mappings(),
+        status_resolution: settings.status_resolution,
+        reaction_policy: settings.reaction_policy,
+        private_channel_policy: settings.private_channel_policy,
+        locale: settings.locale,
+        status_precedence: STATUS_PRECEDENCE.to_vec(),
+        message_redaction: settings.message_redaction,
         has_app_token: true,
         has_bot_token: true,
     }))
 }
 
 /// Reset emoji mappings to defaults - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/{workspace_name}/emoji-mappings/reset",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Emoji mappings reset to defaults", body = WorkspaceSettingsResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
 pub async fn reset_emoji_mappings(
     State(state): State<Arc<AppState>>,
     person: Person,
@@ -572,73 +1022,683 @@ pub async fn reset_emoji_mappings(
         person.email, workspace_name
     );
 
+    let workspace = find_workspace(&state, &workspace_name).await?;
     let default_mappings = EmojiMappings::default_mappings();
 
     let settings_repo = WorkspaceSettingsRepo::new(state.database.clone());
     let settings = settings_repo
-        .update_emoji_mappings(&workspace_name, default_mappings)
+        .update_emoji_mappings(&workspace.id, default_mappings)
         .await
         .map_err(|e| {
             error!("Failed to reset emoji mappings: {}", e);
             APIError::InternalServerError("Failed to reset emoji mappings".to_string())
         })?;
+    state
+        .cache
+        .delete(&cache_keys::emoji_mappings(&workspace.id))
+        .await;
 
     Ok(Json(WorkspaceSettingsResponse {
         workspace_name,
         emoji_mappings: settings.get_emoji_mappings(),
+        status_resolution: settings.status_resolution,
+        reaction_policy: settings.reaction_policy,
+        private_channel_policy: settings.private_channel_policy,
+        locale: settings.locale,
+        status_precedence: STATUS_PRECEDENCE.to_vec(),
+        message_redaction: settings.message_redaction,
         has_app_token: true,
         has_bot_token: true,
     }))
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct KeywordTriggersPayload {
+    pub triggers: Vec<KeywordTrigger>,
+}
+
+/// Keyword triggers that create tasks from messages without a reaction - REQUIRES
+/// ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/keyword-triggers",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Keyword triggers", body = KeywordTriggersPayload),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn get_keyword_triggers(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<KeywordTriggersPayload>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let triggers = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_keyword_triggers(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to get keyword triggers: {}", e);
+            APIError::InternalServerError("Failed to get keyword triggers".to_string())
+        })?;
+
+    Ok(Json(KeywordTriggersPayload { triggers }))
+}
+
+/// Replace the keyword triggers of a workspace; an empty list turns them off. The
+/// Slack app must subscribe to message events (and `app_mention` for mention
+/// triggers) - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/keyword-triggers",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = KeywordTriggersPayload,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Keyword triggers updated", body = KeywordTriggersPayload),
+        (status = 400, description = "A trigger has an empty pattern"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn update_keyword_triggers(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<KeywordTriggersPayload>,
+) -> Result<Json<KeywordTriggersPayload>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let triggers: Vec<KeywordTrigger> = payload
+        .triggers
+        .into_iter()
+        .map(|trigger| KeywordTrigger {
+            pattern: trigger.pattern.trim().to_string(),
+            ..trigger
+        })
+        .collect();
+    if triggers.iter().any(|trigger| trigger.pattern.is_empty()) {
+        return Err(APIError::BadRequest(
+            "Trigger patterns cannot be empty".to_string(),
+        ));
+    }
+
+    info!(
+        "User {} updating keyword triggers for workspace: {}",
+        person.email, workspace_name
+    );
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .update_keyword_triggers(&workspace.id, triggers)
+        .await
+        .map_err(|e| {
+            error!("Failed to update keyword triggers: {}", e);
+            APIError::InternalServerError("Failed to update keyword triggers".to_string())
+        })?;
+    state
+        .cache
+        .delete(&cache_keys::keyword_triggers(&workspace.id))
+        .await;
+
+    Ok(Json(KeywordTriggersPayload {
+        triggers: settings.get_keyword_triggers(),
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WipLimitsPayload {
+    pub limits: Vec<WipLimit>,
+}
+
+/// WIP limits of a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/wip-limits",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "WIP limits", body = WipLimitsPayload),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn get_wip_limits(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<WipLimitsPayload>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let limits = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_wip_limits(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to get WIP limits: {}", e);
+            APIError::InternalServerError("Failed to get WIP limits".to_string())
+        })?;
+
+    Ok(Json(WipLimitsPayload { limits }))
+}
+
+/// Replace the WIP limits of a workspace; an empty list removes them. Limits apply
+/// to the In Progress and Blocked columns, at most one per column and scope -
+/// REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/wip-limits",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = WipLimitsPayload,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "WIP limits updated", body = WipLimitsPayload),
+        (status = 400, description = "Invalid or duplicate limit"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn update_wip_limits(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<WipLimitsPayload>,
+) -> Result<Json<WipLimitsPayload>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    for (idx, limit) in payload.limits.iter().enumerate() {
+        if !matches!(limit.status, TaskStatus::InProgress | TaskStatus::Blocked) {
+            return Err(APIError::BadRequest(
+                "WIP limits apply to InProgress and Blocked only".to_string(),
+            ));
+        }
+        if limit.max_tasks == 0 {
+            return Err(APIError::BadRequest(
+                "max_tasks must be at least 1".to_string(),
+            ));
+        }
+        if payload.limits[..idx]
+            .iter()
+            .any(|other| other.status == limit.status && other.scope == limit.scope)
+        {
+            return Err(APIError::BadRequest(format!(
+                "Duplicate {:?} limit for {:?}",
+                limit.scope, limit.status
+            )));
+        }
+    }
+
+    info!(
+        "User {} updating WIP limits for workspace: {}",
+        person.email, workspace_name
+    );
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .update_wip_limits(&workspace.id, payload.limits)
+        .await
+        .map_err(|e| {
+            error!("Failed to update WIP limits: {}", e);
+            APIError::InternalServerError("Failed to update WIP limits".to_string())
+        })?;
+
+    Ok(Json(WipLimitsPayload {
+        limits: settings.get_wip_limits(),
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NotificationRoutesPayload {
+    pub routes: Vec<NotificationRoute>,
+}
+
+/// Notification routes of a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/notification-routes",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Notification routes", body = NotificationRoutesPayload),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn get_notification_routes(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+) -> Result<Json<NotificationRoutesPayload>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let routes = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_notification_routes(&workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to get notification routes: {}", e);
+            APIError::InternalServerError("Failed to get notification routes".to_string())
+        })?;
+
+    Ok(Json(NotificationRoutesPayload { routes }))
+}
+
+/// Replace the notification routes of a workspace; an empty list removes them.
+/// Notifications about the workspace of a routed kind go out on the route's
+/// channels instead of the one each member chose; members without a webhook URL
+/// are skipped on the webhook channel - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/notification-routes",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = NotificationRoutesPayload,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Notification routes updated", body = NotificationRoutesPayload),
+        (status = 400, description = "Route without channels, duplicate route or task activity sent to a person"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn update_notification_routes(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<NotificationRoutesPayload>,
+) -> Result<Json<NotificationRoutesPayload>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    for (idx, route) in payload.routes.iter().enumerate() {
+        if route.channels.is_empty() {
+            return Err(APIError::BadRequest(format!(
+                "The {:?} route needs at least one channel",
+                route.kind
+            )));
+        }
+        if route.kind == NotificationKind::TaskActivity
+            && !route.channels.iter().all(|c| c.is_workspace_channel())
+        {
+            return Err(APIError::BadRequest(
+                "Task activity only goes to workspace channels, such as discord or teams"
+                    .to_string(),
+            ));
+        }
+        if payload.routes[..idx]
+            .iter()
+            .any(|other| other.kind == route.kind)
+        {
+            return Err(APIError::BadRequest(format!(
+                "Duplicate route for {:?}",
+                route.kind
+            )));
+        }
+    }
+
+    info!(
+        "User {} updating notification routes for workspace: {}",
+        person.email, workspace_name
+    );
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .update_notification_routes(&workspace.id, payload.routes)
+        .await
+        .map_err(|e| {
+            error!("Failed to update notification routes: {}", e);
+            APIError::InternalServerError("Failed to update notification routes".to_string())
+        })?;
+
+    Ok(Json(NotificationRoutesPayload {
+        routes: settings.get_notification_routes(),
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MessageRedactionPayload {
+    pub mode: MessageRedaction,
+}
+
+/// Choose how message text is stored from now on; exports apply the mode to
+/// messages stored before - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/message-redaction",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = MessageRedactionPayload,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Redaction mode updated", body = MessageRedactionPayload),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn update_message_redaction(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<MessageRedactionPayload>,
+) -> Result<Json<MessageRedactionPayload>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    info!(
+        "User {} setting message redaction of workspace {} to {:?}",
+        person.email, workspace_name, payload.mode
+    );
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .update_message_redaction(&workspace.id, payload.mode)
+        .await
+        .map_err(|e| {
+            error!("Failed to update message redaction: {}", e);
+            APIError::InternalServerError("Failed to update message redaction".to_string())
+        })?;
+
+    Ok(Json(MessageRedactionPayload {
+        mode: settings.message_redaction,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StatusResolutionPayload {
+    pub strategy: StatusResolution,
+}
+
+/// Choose how a message's status is picked when its reactions map to several
+/// statuses - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/status-resolution",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = StatusResolutionPayload,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Strategy updated", body = StatusResolutionPayload),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn update_status_resolution(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<StatusResolutionPayload>,
+) -> Result<Json<StatusResolutionPayload>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    info!(
+        "User {} setting status resolution of workspace {} to {:?}",
+        person.email, workspace_name, payload.strategy
+    );
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .update_status_resolution(&workspace.id, payload.strategy)
+        .await
+        .map_err(|e| {
+            error!("Failed to update status resolution: {}", e);
+            APIError::InternalServerError("Failed to update status resolution".to_string())
+        })?;
+    state
+        .cache
+        .delete(&cache_keys::status_resolution(&workspace.id))
+        .await;
+
+    Ok(Json(StatusResolutionPayload {
+        strategy: settings.status_resolution,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReactionPolicyPayload {
+    pub policy: ReactionPolicy,
+}
+
+/// Choose whose reactions move tasks between statuses - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/reaction-policy",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = ReactionPolicyPayload,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Policy updated", body = ReactionPolicyPayload),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn update_reaction_policy(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<ReactionPolicyPayload>,
+) -> Result<Json<ReactionPolicyPayload>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    info!(
+        "User {} setting reaction policy of workspace {} to {:?}",
+        person.email, workspace_name, payload.policy
+    );
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .update_reaction_policy(&workspace.id, payload.policy)
+        .await
+        .map_err(|e| {
+            error!("Failed to update reaction policy: {}", e);
+            APIError::InternalServerError("Failed to update reaction policy".to_string())
+        })?;
+    state
+        .cache
+        .delete(&cache_keys::reaction_policy(&workspace.id))
+        .await;
+
+    Ok(Json(ReactionPolicyPayload {
+        policy: settings.reaction_policy,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PrivateChannelPolicyPayload {
+    pub policy: PrivateChannelPolicy,
+}
+
+/// Choose whether messages in private channels, group DMs and DMs become tasks,
+/// and whether API responses show their channel - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/private-channel-policy",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = PrivateChannelPolicyPayload,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Policy updated", body = PrivateChannelPolicyPayload),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn update_private_channel_policy(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<PrivateChannelPolicyPayload>,
+) -> Result<Json<PrivateChannelPolicyPayload>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    info!(
+        "User {} setting private channel policy of workspace {} to {:?}",
+        person.email, workspace_name, payload.policy
+    );
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .update_private_channel_policy(&workspace.id, payload.policy)
+        .await
+        .map_err(|e| {
+            error!("Failed to update private channel policy: {}", e);
+            APIError::InternalServerError("Failed to update private channel policy".to_string())
+        })?;
+    state
+        .cache
+        .delete(&cache_keys::private_channel_policy(&workspace.id))
+        .await;
+
+    Ok(Json(PrivateChannelPolicyPayload {
+        policy: settings.private_channel_policy,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LocalePayload {
+    pub locale: Locale,
+}
+
+/// Choose the language of bot messages, digests and Slack buttons for members who
+/// did not pick their own - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_name}/locale",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = LocalePayload,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Locale updated", body = LocalePayload),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
+pub async fn update_workspace_locale(
+    State(state): State<Arc<AppState>>,
+    person: Person,
+    Path(workspace_name): Path<String>,
+    Json(payload): Json<LocalePayload>,
+) -> Result<Json<LocalePayload>, APIError> {
+    if !can_configure_workspaces(&state, &person.email).await {
+        return Err(APIError::Forbidden);
+    }
+
+    info!(
+        "User {} setting locale of workspace {} to {:?}",
+        person.email, workspace_name, payload.locale
+    );
+
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let settings = WorkspaceSettingsRepo::new(state.database.clone())
+        .update_locale(&workspace.id, payload.locale)
+        .await
+        .map_err(|e| {
+            error!("Failed to update workspace locale: {}", e);
+            APIError::InternalServerError("Failed to update workspace locale".to_string())
+        })?;
+
+    Ok(Json(LocalePayload {
+        locale: settings.locale,
+    }))
+}
+
 // ============== Workspace Users ==============
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WorkspaceUserInfo {
     pub id: String,
     pub name: String,
     pub email: String,
     pub slack_member_id: Option<String>,
     pub is_active: bool,
+    pub role: WorkspaceRole,
     pub linked_at: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WorkspaceUsersResponse {
+    /// Most recently linked first
     pub users: Vec<WorkspaceUserInfo>,
     pub total: u64,
-    pub page: u64,
-    pub per_page: u64,
-    pub total_pages: u64,
+    /// Pass as `cursor` for the next page, None on the last page
+    pub next_cursor: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct PaginationQuery {
-    pub page: Option<u64>,
-    pub per_page: Option<u64>,
-}
-
-/// Get paginated list of users in a workspace
+/// Page through the linked users of a workspace
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_name}/users",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name"), CursorQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Page of linked users", body = WorkspaceUsersResponse),
+        (status = 400, description = "Invalid cursor"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
 pub async fn get_workspace_users(
     State(state): State<Arc<AppState>>,
     _person: Person,
     Path(workspace_name): Path<String>,
-    Query(pagination): Query<PaginationQuery>,
+    Query(query): Query<CursorQuery>,
 ) -> Result<Json<WorkspaceUsersResponse>, APIError> {
-    let page = pagination.page.unwrap_or(0);
-    let per_page = pagination.per_page.unwrap_or(10).min(100);
+    let pagination = Pagination::from_query(&query)?;
 
+    let workspace = find_workspace(&state, &workspace_name).await?;
     let workspace_links_repo = WorkspaceLinksRepo::new(state.database.clone());
 
-    let (users_with_links, total) = workspace_links_repo
-        .get_workspace_users_paginated(workspace_name.clone(), page, per_page)
+    let (page, total) = workspace_links_repo
+        .get_workspace_users_page(workspace.id, &pagination)
         .await
         .map_err(|e| {
             error!("Failed to get workspace users: {}", e);
             APIError::InternalServerError("Failed to get workspace users".to_string())
         })?;
 
-    let users: Vec<WorkspaceUserInfo> = users_with_links
+    let users: Vec<WorkspaceUserInfo> = page
+        .items
         .into_iter()
         .map(|(link, person)| WorkspaceUserInfo {
             id: person.id,
@@ -646,40 +1706,60 @@ pub async fn get_workspace_users(
             email: person.email,
             slack_member_id: link.slack_member_id,
             is_active: link.is_active,
+            role: link.role,
             linked_at: link.created_at.to_string(),
         })
         .collect();
 
-    let total_pages = (total as f64 / per_page as f64).ceil() as u64;
-
     Ok(Json(WorkspaceUsersResponse {
         users,
         total,
-        page,
-        per_page,
-        total_pages,
+        next_cursor: page.next_cursor,
     }))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct InviteUserRequest {
+    #[validate(email)]
     pub email: String,
+    /// `member` by default; a `viewer` sees the workspace's boards and analytics
+    /// but can't change tasks
+    #[serde(default)]
+    pub role: WorkspaceRole,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct InviteUserResponse {
     pub success: bool,
     pub message: String,
     pub user: Option<WorkspaceUserInfo>,
+    /// Pending invitation the user has to accept by email
+    pub invitation: Option<Invitation>,
 }
 
 /// Invite a user to a workspace by email - REQUIRES ADMIN PERMISSION
-/// Validates that the user exists in the Slack workspace before adding
+/// Validates that the user exists in the Slack workspace, then emails them an accept link.
+/// The user is linked with the invited role once they accept and sign in.
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/{workspace_name}/users/invite",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = InviteUserRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Invite result", body = InviteUserResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+        (status = 422, description = "Invalid email address"),
+    )
+)]
 pub async fn invite_user_to_workspace(
     State(state): State<Arc<AppState>>,
     person: Person,
     Path(workspace_name): Path<String>,
-    Json(payload): Json<InviteUserRequest>,
+    ValidatedJson(payload): ValidatedJson<InviteUserRequest>,
 ) -> Result<Json<InviteUserResponse>, APIError> {
     // Check if user has permission to configure workspaces
     if !can_configure_workspaces(&state, &person.email).await {
@@ -687,26 +1767,19 @@ pub async fn invite_user_to_workspace(
     }
 
     info!(
-        "User {} inviting {} to workspace {}",
-        person.email, payload.email, workspace_name
+        "User {} inviting {} to workspace {} as {:?}",
+        person.email, payload.email, workspace_name, payload.role
     );
 
-    // Load workspace config to get bot token
-    let workspaces_config =
-        WorkspacesConfig::load_and_decrypt("workspaces.yaml", &state.config.encryption_key)
-            .map_err(|e| {
-                error!("Failed to load workspaces config: {}", e);
-                APIError::InternalServerError("Failed to load workspaces configuration".to_string())
-            })?;
-
-    let workspace_config = workspaces_config
-        .get_workspace(&workspace_name)
-        .ok_or_else(|| APIError::NotFound(format!("Workspace '{}' not found", workspace_name)))?;
+    // Load workspace tokens to query Slack with the bot token
+    let workspace = find_workspace(&state, &workspace_name).await?;
+    let workspace_config = find_workspace_tokens(&state, &workspace).await?;
 
     // Check if user exists in Slack workspace
-    let (slack_member_id, slack_name) = match fetch_user_by_email_with_config(
+    let (slack_member_id, slack_name) = match lookup_user_by_email(
+        &state,
+        &workspace.id,
         &workspace_config.bot_token,
-        &state.config.google_client_id,
         &payload.email,
     )
     .await
@@ -718,98 +1791,122 @@ pub async fn invite_user_to_workspace(
                 success: false,
                 message: format!("User with email '{}' was not found in this Slack workspace. They need to be a member of the Slack workspace first.", payload.email),
                 user: None,
+                invitation: None,
             }));
         }
     };
 
     info!("Found Slack user: {} ({})", slack_name, slack_member_id);
 
-    // Check if person exists in our database
+    // An existing member doesn't need an invitation
     let persons_repo = PersonsRepo::new(state.database.clone());
     let workspace_links_repo = WorkspaceLinksRepo::new(state.database.clone());
 
-    let person_model = match persons_repo.get_by_email(payload.email.clone()).await {
-        Ok(p) => p,
-        Err(_) => {
-            // Create new person
-            info!("Creating new person for invited user: {}", payload.email);
-            persons_repo
-                .create(
-                    slack_name.clone(),
-                    false, // is_me - false for invited users
-                    slack_member_id.clone(),
-                    payload.email.clone(),
-                )
-                .await
-                .map_err(|e| {
-                    error!("Failed to create person: {}", e);
-                    APIError::InternalServerError("Failed to create user".to_string())
-                })?
-        }
-    };
-
-    // Check if already linked
-    if let Ok(existing_link) = workspace_links_repo
-        .get_by_person_and_workspace(person_model.id.clone(), workspace_name.clone())
-        .await
-    {
-        if existing_link.is_linked {
-            return Ok(Json(InviteUserResponse {
-                success: false,
-                message: format!(
-                    "User '{}' is already a member of this workspace",
-                    payload.email
-                ),
-                user: Some(WorkspaceUserInfo {
-                    id: person_model.id,
-                    name: person_model.name,
-                    email: person_model.email,
-                    slack_member_id: existing_link.slack_member_id,
-                    is_active: existing_link.is_active,
-                    linked_at: existing_link.created_at.to_string(),
-                }),
-            }));
+    if let Ok(person_model) = persons_repo.get_by_email(payload.email.clone()).await {
+        if let Ok(existing_link) = workspace_links_repo
+            .get_by_person_and_workspace(person_model.id.clone(), workspace.id.clone())
+            .await
+        {
+            if existing_link.is_linked {
+                return Ok(Json(InviteUserResponse {
+                    success: false,
+                    message: format!(
+                        "User '{}' is already a member of this workspace",
+                        payload.email
+                    ),
+                    user: Some(WorkspaceUserInfo {
+                        id: person_model.id,
+                        name: person_model.name,
+                        email: person_model.email,
+                        slack_member_id: existing_link.slack_member_id,
+                        is_active: existing_link.is_active,
+                        role: existing_link.role,
+                        linked_at: existing_link.created_at.to_string(),
+                    }),
+                    invitation: None,
+                }));
+            }
         }
     }
 
-    // Create workspace link
-    let link = workspace_links_repo
-        .link_workspace(
-            person_model.id.clone(),
-            workspace_name.clone(),
+    // Replace any earlier invitation so only the newest link works
+    let invitations_repo = InvitationsRepo::new(state.database.clone());
+    invitations_repo
+        .expire_pending(&payload.email, &workspace.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to expire earlier invitations: {}", e);
+            APIError::InternalServerError("Failed to create invitation".to_string())
+        })?;
+    let invitation = invitations_repo
+        .create(
+            payload.email.clone(),
+            workspace.id.clone(),
             slack_member_id.clone(),
+            person.email.clone(),
+            payload.role,
+            state.config.invitation_expiry_hours,
         )
         .await
         .map_err(|e| {
-            error!("Failed to link user to workspace: {}", e);
-            APIError::InternalServerError("Failed to add user to workspace".to_string())
+            error!("Failed to create invitation: {}", e);
+            APIError::InternalServerError("Failed to create invitation".to_string())
         })?;
 
+    let accept_url = format!(
+        "{}/api/invitations/{}/accept",
+        state.config.frontend_url.trim_end_matches('/'),
+        invitation.token
+    );
+    let email = Email::invitation(
+        &slack_name,
+        &person.email,
+        &workspace_name,
+        &accept_url,
+        state.config.invitation_expiry_hours,
+    );
+
+    if let Err(e) = state.mailer.send(&payload.email, &email).await {
+        error!("Failed to send invitation email: {}", e);
+        return Err(APIError::InternalServerError(
+            "Invitation created but the email could not be sent".to_string(),
+        ));
+    }
+
     info!(
-        "Successfully invited {} to workspace {}",
-        payload.email, workspace_name
+        "Sent invitation for workspace {} to {}",
+        workspace_name, payload.email
     );
 
     Ok(Json(InviteUserResponse {
         success: true,
-        message: format!("Successfully added '{}' to the workspace", payload.email),
-        user: Some(WorkspaceUserInfo {
-            id: person_model.id,
-            name: person_model.name,
-            email: person_model.email,
-            slack_member_id: link.slack_member_id,
-            is_active: link.is_active,
-            linked_at: link.created_at.to_string(),
-        }),
+        message: format!("Invitation sent to '{}'", payload.email),
+        user: None,
+        invitation: Some(invitation),
     }))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct RemoveUserRequest {
     pub user_id: String,
 }
 
 /// Remove a user from a workspace - REQUIRES ADMIN PERMISSION
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/{workspace_name}/users/remove",
+    tag = "workspaces",
+    params(("workspace_name" = String, Path, description = "Workspace name")),
+    request_body = RemoveUserRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "User removed", body = InviteUserResponse),
+        (status = 400, description = "User not found in this workspace"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Requires workspace admin permission"),
+        (status = 404, description = "Workspace not found"),
+    )
+)]
 pub async fn remove_user_from_workspace(
     State(state): State<Arc<AppState>>,
     person: Person,
@@ -826,10 +1923,11 @@ pub async fn remove_user_from_workspace(
         person.email, payload.user_id, workspace_name
     );
 
+    let workspace = find_workspace(&state, &workspace_name).await?;
     let workspace_links_repo = WorkspaceLinksRepo::new(state.database.clone());
 
     workspace_links_repo
-        .unlink_workspace(payload.user_id.clone(), workspace_name.clone())
+        .unlink_workspace(payload.user_id.clone(), workspace.id)
         .await
         .map_err(|e| {
             error!("Failed to remove user from workspace: {}", e);
@@ -840,5 +1938,6 @@ pub async fn remove_user_from_workspace(
         success: true,
         message: "User removed from workspace".to_string(),
         user: None,
+        invitation: None,
     }))
 }
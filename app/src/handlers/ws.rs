@@ -0,0 +1,348 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::Response,
+};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::{sync::broadcast::error::RecvError, time::interval};
+use tracing::{error, info, warn};
+
+use crate::{
+    core::{
+        board_events::{BoardChange, BoardEvent},
+        state::AppState,
+    },
+    handlers::{admins::can_configure_workspaces, workspaces::find_workspace},
+    models::{person::Model as Person, workspace_link::WorkspaceRole},
+    repos::{
+        sessions::SessionsRepo, workspace_links::WorkspaceLinksRepo, workspaces::WorkspacesRepo,
+    },
+    utils::{jwt::verify_jwt, response::APIError},
+};
+
+/// How often the server pings; a client that misses a full interval is disconnected
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize)]
+pub struct WsAuthQuery {
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ClientMessage {
+    Subscribe {
+        workspace: Option<String>,
+        person_id: Option<String>,
+    },
+    Unsubscribe {
+        workspace: Option<String>,
+        person_id: Option<String>,
+    },
+    Ping,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Subscribed {
+        workspaces: Vec<String>,
+        person_ids: Vec<String>,
+    },
+    Patch {
+        workspace: String,
+        patch: Vec<serde_json::Value>,
+    },
+    Pong,
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Debug, Default)]
+struct Subscriptions {
+    workspaces: HashSet<String>,
+    /// Followed people, with the names of the workspaces their tasks stream from;
+    /// None for the caller, whose own tasks stream from every workspace
+    person_ids: HashMap<String, Option<HashSet<String>>>,
+}
+
+impl Subscriptions {
+    fn matches(&self, event: &BoardEvent) -> bool {
+        self.workspaces.contains(&event.workspace_name)
+            || self.follows(&event.task.assigned_to, &event.workspace_name)
+            || event
+                .task
+                .assigned_by
+                .as_ref()
+                .is_some_and(|id| self.follows(id, &event.workspace_name))
+    }
+
+    fn follows(&self, person_id: &str, workspace_name: &str) -> bool {
+        match self.person_ids.get(person_id) {
+            Some(None) => true,
+            Some(Some(workspaces)) => workspaces.contains(workspace_name),
+            None => false,
+        }
+    }
+
+    fn summary(&self) -> ServerMessage {
+        ServerMessage::Subscribed {
+            workspaces: self.workspaces.iter().cloned().collect(),
+            person_ids: self.person_ids.keys().cloned().collect(),
+        }
+    }
+}
+
+/// Names of the workspaces both `admin` (not as a viewer) and `person_id` are
+/// linked to, the only ones whose tasks of that person an admin may follow
+async fn shared_workspaces(state: &AppState, admin: &Person, person_id: &str) -> HashSet<String> {
+    let links_repo = WorkspaceLinksRepo::new(state.database.clone());
+    let links = match links_repo.get_by_person(admin.id.clone()).await {
+        Ok(admin_links) => links_repo
+            .get_by_person(person_id.to_string())
+            .await
+            .map(|person_links| (admin_links, person_links)),
+        Err(e) => Err(e),
+    };
+    let (admin_links, person_links) = match links {
+        Ok(links) => links,
+        Err(e) => {
+            error!("Failed to load workspace links: {}", e);
+            return HashSet::new();
+        }
+    };
+    let administered: HashSet<String> = admin_links
+        .into_iter()
+        .filter(|link| link.is_linked && link.role == WorkspaceRole::Member)
+        .map(|link| link.workspace_id)
+        .collect();
+
+    let workspaces_repo = WorkspacesRepo::new(state.database.clone());
+    let mut shared = HashSet::new();
+    for link in person_links {
+        if !link.is_linked || !administered.contains(&link.workspace_id) {
+            continue;
+        }
+        match workspaces_repo.get(&link.workspace_id).await {
+            Ok(workspace) => {
+                shared.insert(workspace.name);
+            }
+            Err(e) => error!("Failed to load workspace {}: {}", link.workspace_id, e),
+        }
+    }
+    shared
+}
+
+/// Upgrade to a WebSocket streaming live board changes.
+/// Browsers can't set headers on WebSocket requests, so the JWT comes in `?token=`.
+pub async fn board_socket(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<WsAuthQuery>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, APIError> {
+    let claims = verify_jwt(&query.token, &state.config.jwt.secret).map_err(|e| {
+        error!("WebSocket auth failed, invalid token: {}", e);
+        APIError::UnAuthorized
+    })?;
+
+    let person = state
+        .stores
+        .persons
+        .get_by_email(claims.sub)
+        .await
+        .map_err(|e| {
+            error!("WebSocket auth failed, user not found: {}", e);
+            APIError::UnAuthorized
+        })?;
+    if let Some(session_id) = &claims.sid {
+        let session = SessionsRepo::new(state.database.clone())
+            .get_active(&person.id, session_id)
+            .await;
+        if !matches!(session, Ok(Some(_))) {
+            error!("WebSocket auth failed, session {} was revoked", session_id);
+            return Err(APIError::UnAuthorized);
+        }
+    } else if claims.impersonated_by.is_none() {
+        error!("WebSocket auth failed, token has no session");
+        return Err(APIError::UnAuthorized);
+    }
+
+    Ok(ws.on_upgrade(move |socket| handle_socket(socket, state, person)))
+}
+
+async fn handle_socket(socket: WebSocket, state: Arc<AppState>, person: Person) {
+    info!("[WS API] {} connected", person.email);
+
+    let (mut sender, mut receiver) = socket.split();
+    let mut events = state.board_events.subscribe();
+    let mut subscriptions = Subscriptions::default();
+    let mut heartbeat = interval(HEARTBEAT_INTERVAL);
+    let mut awaiting_pong = false;
+
+    loop {
+        let outgoing = tokio::select! {
+            _ = state.shutdown_token.cancelled() => {
+                let _ = sender.send(Message::Close(None)).await;
+                break;
+            }
+            _ = heartbeat.tick() => {
+                if awaiting_pong {
+                    info!("[WS API] {} missed heartbeat, disconnecting", person.email);
+                    break;
+                }
+                awaiting_pong = true;
+                Some(Message::Ping(Vec::new()))
+            }
+            event = events.recv() => match event {
+                Ok(event) if subscriptions.matches(&event) => to_message(&patch_for(event)),
+                Ok(_) => None,
+                Err(RecvError::Lagged(missed)) => {
+                    warn!("[WS API] {} lagged behind by {} events", person.email, missed);
+                    to_message(&ServerMessage::Error {
+                        message: format!("Missed {} updates, refetch the board", missed),
+                    })
+                }
+                Err(RecvError::Closed) => break,
+            },
+            msg = receiver.next() => {
+                awaiting_pong = false;
+                match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        let reply = match serde_json::from_str::<ClientMessage>(&text) {
+                            Ok(client_message) => {
+                                handle_client_message(&state, &person, &mut subscriptions, client_message)
+                                    .await
+                            }
+                            Err(e) => ServerMessage::Error {
+                                message: format!("Invalid message: {}", e),
+                            },
+                        };
+                        to_message(&reply)
+                    }
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Err(e)) => {
+                        warn!("[WS API] Connection error for {}: {}", person.email, e);
+                        break;
+                    }
+                    // Pings are answered by axum; pongs only reset the heartbeat
+                    Some(Ok(_)) => None,
+                }
+            }
+        };
+
+        if let Some(message) = outgoing {
+            if sender.send(message).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    info!("[WS API] {} disconnected", person.email);
+}
+
+async fn handle_client_message(
+    state: &AppState,
+    person: &Person,
+    subscriptions: &mut Subscriptions,
+    message: ClientMessage,
+) -> ServerMessage {
+    match message {
+        ClientMessage::Subscribe {
+            workspace,
+            person_id,
+        } => {
+            if let Some(workspace) = workspace {
+                let links_repo = WorkspaceLinksRepo::new(state.database.clone());
+                let workspace_id = match find_workspace(state, &workspace).await {
+                    Ok(found) => found.id,
+                    Err(_) => {
+                        return ServerMessage::Error {
+                            message: format!("Workspace '{}' not found", workspace),
+                        }
+                    }
+                };
+                match links_repo
+                    .get_by_person_and_workspace(person.id.clone(), workspace_id)
+                    .await
+                {
+                    Ok(link) if link.is_linked => {
+                        subscriptions.workspaces.insert(workspace);
+                    }
+                    _ => {
+                        return ServerMessage::Error {
+                            message: format!("Not linked to workspace '{}'", workspace),
+                        }
+                    }
+                }
+            }
+
+            if let Some(person_id) = person_id {
+                // Anyone may follow their own tasks; admins may follow others' in the
+                // workspaces they share with them
+                let workspaces = if person_id == person.id {
+                    None
+                } else {
+                    let workspaces = if can_configure_workspaces(state, &person.email).await {
+                        shared_workspaces(state, person, &person_id).await
+                    } else {
+                        HashSet::new()
+                    };
+                    if workspaces.is_empty() {
+                        return ServerMessage::Error {
+                            message: "Not allowed to subscribe to another person's tasks"
+                                .to_string(),
+                        };
+                    }
+                    Some(workspaces)
+                };
+                subscriptions.person_ids.insert(person_id, workspaces);
+            }
+
+            subscriptions.summary()
+        }
+        ClientMessage::Unsubscribe {
+            workspace,
+            person_id,
+        } => {
+            if let Some(workspace) = workspace {
+                subscriptions.workspaces.remove(&workspace);
+            }
+            if let Some(person_id) = person_id {
+                subscriptions.person_ids.remove(&person_id);
+            }
+
+            subscriptions.summary()
+        }
+        ClientMessage::Ping => ServerMessage::Pong,
+    }
+}
+
+/// Express a board event as a JSON Patch (RFC 6902) against `{ "tasks": { <id>: task } }`
+fn patch_for(event: BoardEvent) -> ServerMessage {
+    let path = format!("/tasks/{}", event.task.id);
+    let operation = match event.change {
+        BoardChange::Created => json!({ "op": "add", "path": path, "value": event.task }),
+        BoardChange::Updated => json!({ "op": "replace", "path": path, "value": event.task }),
+        BoardChange::Deleted => json!({ "op": "remove", "path": path }),
+    };
+
+    ServerMessage::Patch {
+        workspace: event.workspace_name,
+        patch: vec![operation],
+    }
+}
+
+fn to_message(message: &ServerMessage) -> Option<Message> {
+    serde_json::to_string(message).ok().map(Message::Text)
+}
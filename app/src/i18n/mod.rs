@@ -0,0 +1,248 @@
+//! Message catalogs of what the bot says: DMs, digests, button labels and modals.
+//! Each locale is a YAML file embedded at build time, its nested keys read as
+//! `section.name`. Texts missing from a locale fall back to English. People pick a
+//! locale in their notification settings, otherwise their workspace's applies.
+
+use std::{collections::HashMap, sync::LazyLock};
+
+use chrono::NaiveDate;
+use sea_orm::{entity::prelude::*, DatabaseConnection};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::{
+    models::task::TaskStatus,
+    repos::{
+        notification_settings::NotificationSettingsRepo, workspace_links::WorkspaceLinksRepo,
+        workspace_settings::WorkspaceSettingsRepo,
+    },
+};
+
+/// Language of the bot's messages
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Hash,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum Locale {
+    #[default]
+    #[sea_orm(string_value = "en")]
+    En,
+    #[sea_orm(string_value = "es")]
+    Es,
+}
+
+type Catalog = HashMap<String, String>;
+
+static CATALOGS: LazyLock<HashMap<Locale, Catalog>> = LazyLock::new(|| {
+    [
+        (Locale::En, include_str!("en.yaml")),
+        (Locale::Es, include_str!("es.yaml")),
+    ]
+    .into_iter()
+    .map(|(locale, source)| (locale, parse(source)))
+    .collect()
+});
+
+fn parse(source: &str) -> Catalog {
+    let value: Value = serde_yaml::from_str(source).expect("valid message catalog");
+    let mut catalog = Catalog::new();
+    flatten("", &value, &mut catalog);
+    catalog
+}
+
+fn flatten(prefix: &str, value: &Value, catalog: &mut Catalog) {
+    match value {
+        Value::Mapping(mapping) => {
+            for (key, value) in mapping {
+                let Some(key) = key.as_str() else {
+                    continue;
+                };
+                let key = if prefix.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&key, value, catalog);
+            }
+        }
+        Value::String(text) => {
+            catalog.insert(prefix.to_string(), text.clone());
+        }
+        Value::Number(number) => {
+            catalog.insert(prefix.to_string(), number.to_string());
+        }
+        _ => {}
+    }
+}
+
+/// Text of `key` in `locale` with its `{name}` placeholders filled from `args`.
+/// Falls back to English, then to the key itself.
+pub fn text(locale: Locale, key: &str, args: &[(&str, &str)]) -> String {
+    let template = CATALOGS
+        .get(&locale)
+        .and_then(|catalog| catalog.get(key))
+        .or_else(|| CATALOGS.get(&Locale::En)?.get(key));
+    match template {
+        Some(template) => fill(template, args),
+        None => key.to_string(),
+    }
+}
+
+/// Placeholders without an argument are left as they are
+fn fill(template: &str, args: &[(&str, &str)]) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let arg = after.find('}').and_then(|end| {
+            args.iter()
+                .find(|(name, _)| *name == &after[..end])
+                .map(|(_, value)| (end, *value))
+        });
+        match arg {
+            Some((end, value)) => {
+                filled.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                filled.push('{');
+                rest = after;
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+/// Name of a status, as on the board
+pub fn status(locale: Locale, status: &TaskStatus) -> String {
+    let key = match status {
+        TaskStatus::Blank => "status.blank",
+        TaskStatus::InProgress => "status.in_progress",
+        TaskStatus::Blocked => "status.blocked",
+        TaskStatus::Completed => "status.completed",
+    };
+    text(locale, key, &[])
+}
+
+/// Date in the locale's short format, e.g. `Mon, 23 Mar`
+pub fn short_date(locale: Locale, date: NaiveDate) -> String {
+    date.format(&text(locale, "date.short", &[])).to_string()
+}
+
+/// Date in the locale's long format, e.g. `Monday, 23 March`
+pub fn long_date(locale: Locale, date: NaiveDate) -> String {
+    date.format(&text(locale, "date.long", &[])).to_string()
+}
+
+/// Locale of a workspace, English when it never picked one
+pub async fn workspace_locale(db: &DatabaseConnection, workspace_id: &str) -> Locale {
+    WorkspaceSettingsRepo::new(db.clone())
+        .get_locale(workspace_id)
+        .await
+        .unwrap_or_else(|e| {
+            warn!(
+                "[i18n] Failed to load the locale of workspace {}: {}",
+                workspace_id, e
+            );
+            Locale::default()
+        })
+}
+
+/// Locale of texts meant for one person: their own choice, else that of
+/// `workspace_id` or, without one, of their active workspace
+pub async fn person_locale(
+    db: &DatabaseConnection,
+    person_id: &str,
+    workspace_id: Option<&str>,
+) -> Locale {
+    match NotificationSettingsRepo::new(db.clone())
+        .get_or_default(person_id)
+        .await
+    {
+        Ok(settings) => {
+            if let Some(locale) = settings.locale {
+                return locale;
+            }
+        }
+        Err(e) => warn!("[i18n] Failed to load the locale of {}: {}", person_id, e),
+    }
+
+    let workspace_id = match workspace_id {
+        Some(workspace_id) => workspace_id.to_string(),
+        None => match WorkspaceLinksRepo::new(db.clone())
+            .get_active_workspace(person_id.to_string())
+            .await
+        {
+            Ok(link) => link.workspace_id,
+            Err(_) => return Locale::default(),
+        },
+    };
+    workspace_locale(db, &workspace_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translations_only_use_english_keys() {
+        let english = &CATALOGS[&Locale::En];
+        for (locale, catalog) in CATALOGS.iter() {
+            for key in catalog.keys() {
+                assert!(
+                    english.contains_key(key),
+                    "{:?} has {} which English lacks",
+                    locale,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fills_placeholders() {
+        assert_eq!(
+            text(Locale::En, "notifications.assignment", &[("name", "Ada")]),
+            "New task from Ada"
+        );
+        assert_eq!(
+            text(
+                Locale::Es,
+                "notifications.task_moved",
+                &[("task", "ACME-4"), ("from", "{to}"), ("to", "Hecho")]
+            ),
+            "ACME-4 pasó de {to} a Hecho"
+        );
+        assert_eq!(fill("{missing} {", &[]), "{missing} {");
+    }
+
+    #[test]
+    fn falls_back_to_the_key() {
+        assert_eq!(text(Locale::Es, "no.such.key", &[]), "no.such.key");
+    }
+
+    #[test]
+    fn names_statuses_and_dates() {
+        assert_eq!(status(Locale::Es, &TaskStatus::InProgress), "En curso");
+        assert_eq!(
+            short_date(Locale::En, NaiveDate::from_ymd_opt(2026, 3, 23).unwrap()),
+            "Mon, 23 Mar"
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Discord: a channel of a mixed Slack/Discord team follows the workspace's task
+//! activity through one of its incoming webhooks. Posting goes through the
+//! `DiscordNotifier`; this module only talks to Discord.
+
+use anyhow::{anyhow, Result};
+use reqwest::{Client, Url};
+use serde::Deserialize;
+
+/// Discord refuses messages over 2000 characters
+const MAX_CONTENT_CHARS: usize = 2000;
+
+const WEBHOOK_HOSTS: &[&str] = &[
+    "discord.com",
+    "discordapp.com",
+    "canary.discord.com",
+    "ptb.discord.com",
+];
+
+#[derive(Debug, Deserialize)]
+struct Webhook {
+    name: Option<String>,
+}
+
+/// Whether `url` looks like a Discord incoming webhook URL
+pub fn is_webhook_url(url: &str) -> bool {
+    Url::parse(url.trim()).is_ok_and(|url| {
+        url.scheme() == "https"
+            && url
+                .host_str()
+                .is_some_and(|host| WEBHOOK_HOSTS.contains(&host))
+            && url.path().starts_with("/api/webhooks/")
+    })
+}
+
+/// Name of the webhook, which also proves the URL works
+pub async fn webhook_name(client: &Client, url: &str) -> Result<String> {
+    let response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        return Err(anyhow!("Discord answered {}", response.status()));
+    }
+    let webhook: Webhook = response.json().await?;
+    Ok(webhook
+        .name
+        .unwrap_or_else(|| "Discord webhook".to_string()))
+}
+
+/// Post a message with a bold title. Mentions in it never ping anyone.
+pub async fn post(client: &Client, url: &str, title: &str, text: &str) -> Result<()> {
+    let response = client
+        .post(url)
+        .json(&serde_json::json!({
+            "content": content(title, text),
+            "allowed_mentions": { "parse": [] },
+        }))
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Discord webhook answered {}: {}",
+            response.status(),
+            response.text().await.unwrap_or_default()
+        ));
+    }
+    Ok(())
+}
+
+fn content(title: &str, text: &str) -> String {
+    let content = format!("**{}**\n{}", title, text);
+    if content.chars().count() <= MAX_CONTENT_CHARS {
+        return content;
+    }
+    let mut cut: String = content.chars().take(MAX_CONTENT_CHARS - 1).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_only_discord_webhook_urls() {
+        assert!(is_webhook_url("https://discord.com/api/webhooks/1/abc"));
+        assert!(is_webhook_url("https://discordapp.com/api/webhooks/1/abc"));
+        assert!(!is_webhook_url("http://discord.com/api/webhooks/1/abc"));
+        assert!(!is_webhook_url("https://discord.com/channels/1/2"));
+        assert!(!is_webhook_url(
+            "https://discord.com.evil.test/api/webhooks/1/abc"
+        ));
+    }
+
+    #[test]
+    fn cuts_long_messages() {
+        let text = "x".repeat(3000);
+        let content = content("Title", &text);
+
+        assert_eq!(content.chars().count(), MAX_CONTENT_CHARS);
+        assert!(content.starts_with("**Title**\nxxx"));
+        assert!(content.ends_with('…'));
+    }
+}
@@ -0,0 +1,180 @@
+//! Email-in: a Mailgun route forwards mail sent to a workspace's inbound address,
+//! `<token>@<inbound_email_domain>`, and each email becomes a task assigned to the
+//! sender, who must be a linked member of that workspace.
+
+use anyhow::Result;
+use hmac::{Hmac, Mac};
+use sea_orm::DbErr;
+use serde::Deserialize;
+use sha2::Sha256;
+use tracing::info;
+
+use crate::{
+    core::state::AppState,
+    models::{
+        message::EMAIL_ID_PREFIX,
+        task::{Model as Task, TaskStatus},
+    },
+    repos::{
+        messages::MessagesRepo, persons::PersonsRepo, tasks::TasksRepo,
+        workspace_links::WorkspaceLinksRepo,
+    },
+    services::task_service::{NewTask, Outcome, TaskService, TaskSource},
+};
+
+/// `channel` of messages created from email
+const EMAIL_CHANNEL: &str = "email";
+
+/// The fields of a Mailgun inbound route delivery Slacker reads
+#[derive(Debug, Deserialize)]
+pub struct InboundEmail {
+    /// Envelope recipients, comma separated
+    pub recipient: String,
+    /// Envelope sender address
+    pub sender: String,
+    #[serde(default)]
+    pub subject: String,
+    #[serde(rename = "body-plain", default)]
+    pub body_plain: String,
+    /// The body without quoted replies and signature
+    #[serde(rename = "stripped-text")]
+    pub stripped_text: Option<String>,
+    #[serde(rename = "Message-Id")]
+    pub message_id: Option<String>,
+    pub timestamp: String,
+    pub token: String,
+    pub signature: String,
+}
+
+impl InboundEmail {
+    /// Message content: the subject as first line, as integrations use it as title
+    fn content(&self) -> String {
+        let body = self
+            .stripped_text
+            .as_deref()
+            .filter(|text| !text.trim().is_empty())
+            .unwrap_or(&self.body_plain)
+            .trim();
+        match self.subject.trim() {
+            "" => body.to_string(),
+            subject => format!("{}\n\n{}", subject, body),
+        }
+    }
+}
+
+/// Check a delivery's signature: hex HMAC-SHA256 of timestamp and token under the
+/// Mailgun webhook signing key
+pub fn verify_signature(signing_key: &str, email: &InboundEmail) -> bool {
+    let Ok(signature) = hex::decode(&email.signature) else {
+        return false;
+    };
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(signing_key.as_bytes()) else {
+        return false;
+    };
+    mac.update(email.timestamp.as_bytes());
+    mac.update(email.token.as_bytes());
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Inbound address token among the recipients, i.e. the local part of the first
+/// recipient on `domain`
+pub fn recipient_token(recipients: &str, domain: &str) -> Option<String> {
+    recipients.split(',').find_map(|recipient| {
+        let address = recipient
+            .trim()
+            .trim_start_matches('<')
+            .trim_end_matches('>');
+        let (local, recipient_domain) = address.rsplit_once('@')?;
+        (recipient_domain.eq_ignore_ascii_case(domain) && !local.is_empty())
+            .then(|| local.to_string())
+    })
+}
+
+/// Create a task from an email sent to the workspace's address. Returns None when
+/// the sender is not a linked member of the workspace; a repeated delivery returns
+/// the task created the first time.
+pub async fn create_task(
+    state: &AppState,
+    workspace_id: &str,
+    email: InboundEmail,
+) -> Result<Option<Task>> {
+    let sender = email.sender.trim().to_lowercase();
+    let person = match PersonsRepo::new(state.database.clone())
+        .get_by_email(sender.clone())
+        .await
+    {
+        Ok(person) => person,
+        Err(DbErr::RecordNotFound(_)) => {
+            info!("[Email] Ignoring email from unknown sender {}", sender);
+            return Ok(None);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let linked = WorkspaceLinksRepo::new(state.database.clone())
+        .get_by_person_and_workspace(person.id.clone(), workspace_id.to_string())
+        .await
+        .is_ok_and(|link| link.is_linked);
+    if !linked {
+        info!(
+            "[Email] Ignoring email from {}, not linked to workspace {}",
+            sender, workspace_id
+        );
+        return Ok(None);
+    }
+
+    let messages_repo = MessagesRepo::new(state.database.clone(), state.message_encryption.clone());
+    let tasks_repo = TasksRepo::new(state.database.clone());
+
+    // Mailgun retries deliveries it considers failed; the Message-Id recognizes them
+    let external_id = format!(
+        "{}{}",
+        EMAIL_ID_PREFIX,
+        email
+            .message_id
+            .clone()
+            .unwrap_or_else(|| format!("{}:{}", email.timestamp, email.token))
+    );
+    match messages_repo
+        .get_message_by_external_id(external_id.clone())
+        .await
+    {
+        Ok(message) => {
+            return Ok(tasks_repo.get_task_by_message_id(message.id).await.ok());
+        }
+        Err(DbErr::RecordNotFound(_)) => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    let message = messages_repo
+        .create(
+            email.content(),
+            external_id,
+            EMAIL_CHANNEL.to_string(),
+            email.timestamp.clone(),
+            &person,
+            workspace_id,
+        )
+        .await?;
+    let new_task = NewTask {
+        workspace_id,
+        status: TaskStatus::InProgress,
+        assignee: person.clone(),
+        assigner: Some(person),
+        message,
+        rule: None,
+        template: None,
+        source: TaskSource::Email,
+        created_at: None,
+    };
+    let Outcome::Done(task) = TaskService::from_state(state)
+        .create_from_message(new_task)
+        .await?
+    else {
+        return Ok(None);
+    };
+    info!("[Email] Created task {} from email by {}", task.id, sender);
+
+    Ok(Some(task))
+}
@@ -0,0 +1,192 @@
+//! GitHub: issues and pull requests that mention `SLK-<task id>` in their title,
+//! description or branch are linked to the task, and merging such a pull request
+//! completes the task.
+
+use std::{collections::BTreeSet, sync::LazyLock};
+
+use anyhow::Result;
+use hmac::{Hmac, Mac};
+use regex::Regex;
+use serde::Deserialize;
+use sha2::Sha256;
+use tracing::info;
+
+use crate::{
+    core::state::AppState,
+    integrations::complete_task,
+    models::{
+        github_link::GithubLinkKind, github_repository::Model as GithubRepository,
+        task::Model as Task,
+    },
+    repos::{github_links::GithubLinksRepo, tasks::TasksRepo},
+};
+
+/// Task ids are 16 character nanoids
+static TASK_REFERENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"SLK-([A-Za-z0-9_-]{16})").expect("valid regex"));
+
+#[derive(Debug, Deserialize)]
+pub struct GithubWebhook {
+    pub action: Option<String>,
+    pub repository: WebhookRepository,
+    pub pull_request: Option<PullRequest>,
+    pub issue: Option<Issue>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookRepository {
+    pub full_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PullRequest {
+    pub html_url: String,
+    pub title: String,
+    pub body: Option<String>,
+    /// "open" or "closed"
+    pub state: String,
+    #[serde(default)]
+    pub merged: bool,
+    pub head: BranchRef,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BranchRef {
+    #[serde(rename = "ref")]
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Issue {
+    pub html_url: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub state: String,
+}
+
+/// Ids of every task mentioned in the given texts, each once
+pub fn task_references<'a>(texts: impl IntoIterator<Item = &'a str>) -> BTreeSet<String> {
+    texts
+        .into_iter()
+        .flat_map(|text| TASK_REFERENCE.captures_iter(text))
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+/// Check the `X-Hub-Signature-256` header ("sha256=<hex hmac of the body>")
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(signature) = signature
+        .strip_prefix("sha256=")
+        .and_then(|hex| hex::decode(hex).ok())
+    else {
+        return false;
+    };
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// An issue or pull request, as far as linking tasks goes
+struct Mention {
+    kind: GithubLinkKind,
+    url: String,
+    title: String,
+    state: String,
+    task_ids: BTreeSet<String>,
+    /// The event is a pull request being merged
+    merged: bool,
+}
+
+impl Mention {
+    fn from_event(event: &str, payload: GithubWebhook) -> Option<Self> {
+        match event {
+            "pull_request" => {
+                let pr = payload.pull_request?;
+                Some(Self {
+                    kind: GithubLinkKind::PullRequest,
+                    task_ids: task_references([
+                        pr.title.as_str(),
+                        pr.body.as_deref().unwrap_or_default(),
+                        pr.head.name.as_str(),
+                    ]),
+                    state: if pr.merged {
+                        "merged".to_string()
+                    } else {
+                        pr.state
+                    },
+                    merged: pr.merged && payload.action.as_deref() == Some("closed"),
+                    url: pr.html_url,
+                    title: pr.title,
+                })
+            }
+            "issues" => {
+                let issue = payload.issue?;
+                Some(Self {
+                    kind: GithubLinkKind::Issue,
+                    task_ids: task_references([
+                        issue.title.as_str(),
+                        issue.body.as_deref().unwrap_or_default(),
+                    ]),
+                    state: issue.state,
+                    merged: false,
+                    url: issue.html_url,
+                    title: issue.title,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Link mentioned tasks and complete them when a pull request mentioning them merged.
+/// Tasks of other workspaces are ignored. Returns the completed tasks.
+pub async fn handle_event(
+    state: &AppState,
+    repository: &GithubRepository,
+    event: &str,
+    payload: GithubWebhook,
+) -> Result<Vec<Task>> {
+    let Some(mention) = Mention::from_event(event, payload) else {
+        return Ok(Vec::new());
+    };
+
+    let tasks_repo = TasksRepo::new(state.database.clone());
+    let links_repo = GithubLinksRepo::new(state.database.clone());
+    let mut completed = Vec::new();
+
+    for task_id in &mention.task_ids {
+        let task = match tasks_repo.get(task_id.clone()).await {
+            Ok(task) if task.workspace_id.as_deref() == Some(repository.workspace_id.as_str()) => {
+                task
+            }
+            _ => continue,
+        };
+
+        links_repo
+            .upsert(
+                &task.id,
+                mention.kind.clone(),
+                &mention.url,
+                &mention.title,
+                &mention.state,
+            )
+            .await?;
+
+        if mention.merged {
+            info!(
+                "[GitHub] {} merged in {}, completing task {}",
+                mention.url, repository.full_name, task.id
+            );
+            let reply = format!(
+                "Completed by merged pull request <{}|{}>",
+                mention.url, mention.title
+            );
+            completed.push(complete_task(state, task, Some(reply)).await?);
+        }
+    }
+
+    Ok(completed)
+}
@@ -0,0 +1,276 @@
+//! Jira Cloud: create an issue from a task, complete the task when the issue is
+//! done and move the issue to done when the task is completed in Slack.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use reqwest::Client;
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use tracing::info;
+
+use crate::{
+    core::{
+        jobs::{self, Job},
+        state::AppState,
+    },
+    integrations::complete_task,
+    models::{
+        jira_connection::Model as JiraConnection,
+        task::{Model as Task, TaskStatus},
+    },
+    repos::{jira_connections::JiraConnectionsRepo, messages::MessagesRepo, tasks::TasksRepo},
+    utils::encryption::decrypt,
+};
+
+/// Jira limits summaries to 255 characters
+const MAX_SUMMARY_CHARS: usize = 250;
+
+/// Status category Jira gives every "done"-like status, whatever its name
+const DONE_CATEGORY: &str = "done";
+
+#[derive(Debug, Deserialize)]
+struct CreatedIssue {
+    key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransitionList {
+    transitions: Vec<Transition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Transition {
+    id: String,
+    to: TransitionTarget,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransitionTarget {
+    #[serde(rename = "statusCategory")]
+    status_category: StatusCategory,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatusCategory {
+    pub key: String,
+}
+
+/// The parts of a Jira `jira:issue_updated` webhook Slacker reads
+#[derive(Debug, Deserialize)]
+pub struct JiraWebhook {
+    #[serde(rename = "webhookEvent")]
+    pub webhook_event: String,
+    pub issue: Option<WebhookIssue>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookIssue {
+    pub key: String,
+    pub fields: WebhookIssueFields,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookIssueFields {
+    pub status: WebhookStatus,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookStatus {
+    #[serde(rename = "statusCategory")]
+    pub status_category: StatusCategory,
+}
+
+/// REST API v2 client authenticated with an account email and API token
+struct JiraClient {
+    http_client: Client,
+    base_url: String,
+    email: String,
+    api_token: String,
+}
+
+impl JiraClient {
+    fn new(connection: &JiraConnection, encryption_key: &str) -> Result<Self> {
+        Ok(Self {
+            http_client: Client::new(),
+            base_url: connection.base_url.trim_end_matches('/').to_string(),
+            email: connection.email.clone(),
+            api_token: decrypt(&connection.api_token, encryption_key)?,
+        })
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.http_client
+            .request(method, format!("{}/rest/api/2/{}", self.base_url, path))
+            .basic_auth(&self.email, Some(&self.api_token))
+            .timeout(Duration::from_secs(10))
+    }
+
+    async fn create_issue(
+        &self,
+        project_key: &str,
+        issue_type: &str,
+        summary: &str,
+        description: &str,
+    ) -> Result<CreatedIssue> {
+        let body = serde_json::json!({
+            "fields": {
+                "project": { "key": project_key },
+                "issuetype": { "name": issue_type },
+                "summary": summary,
+                "description": description,
+            }
+        });
+
+        Ok(self
+            .request(reqwest::Method::POST, "issue")
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    /// Move an issue to the first status in the done category it can reach.
+    /// Returns false when the workflow has no such transition from its status.
+    async fn transition_to_done(&self, issue_key: &str) -> Result<bool> {
+        let path = format!("issue/{}/transitions", issue_key);
+        let list: TransitionList = self
+            .request(reqwest::Method::GET, &path)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let Some(transition) = list
+            .transitions
+            .into_iter()
+            .find(|t| t.to.status_category.key == DONE_CATEGORY)
+        else {
+            return Ok(false);
+        };
+
+        self.request(reqwest::Method::POST, &path)
+            .json(&serde_json::json!({ "transition": { "id": transition.id } }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(true)
+    }
+}
+
+/// Browser link to an issue
+pub fn issue_url(connection: &JiraConnection, issue_key: &str) -> String {
+    format!(
+        "{}/browse/{}",
+        connection.base_url.trim_end_matches('/'),
+        issue_key
+    )
+}
+
+/// Create an issue from the task's message and remember its key on the task
+pub async fn create_issue(
+    state: &AppState,
+    connection: &JiraConnection,
+    task: Task,
+) -> Result<Task> {
+    let message = MessagesRepo::new(state.database.clone(), state.message_encryption.clone())
+        .get_by_id(task.message_id.clone())
+        .await?;
+
+    let first_line = message.content.lines().next().unwrap_or_default().trim();
+    let summary: String = if first_line.is_empty() {
+        format!("Slack task {}", task.id)
+    } else {
+        first_line.chars().take(MAX_SUMMARY_CHARS).collect()
+    };
+    let description = format!(
+        "{}\n\nFrom Slack: {}",
+        message.content,
+        message.slack_link()
+    );
+
+    let client = JiraClient::new(connection, &state.config.encryption_key)?;
+    let issue = client
+        .create_issue(
+            &connection.project_key,
+            &connection.issue_type,
+            &summary,
+            &description,
+        )
+        .await?;
+    info!("[Jira] Created {} for task {}", issue.key, task.id);
+
+    Ok(TasksRepo::new(state.database.clone())
+        .set_jira_issue_key(task, issue.key)
+        .await?)
+}
+
+/// Queue moving the task's issue to done when the task was just completed
+pub async fn queue_transition(db: &DatabaseConnection, task: &Task) -> Result<()> {
+    if task.status != TaskStatus::Completed || task.jira_issue_key.is_none() {
+        return Ok(());
+    }
+
+    jobs::enqueue(
+        db,
+        Job::JiraTransition {
+            task_id: task.id.clone(),
+        },
+    )
+    .await
+}
+
+/// Job body of `Job::JiraTransition`
+pub async fn transition_task_issue(state: &AppState, task_id: &str) -> Result<()> {
+    let task = TasksRepo::new(state.database.clone())
+        .get(task_id.to_string())
+        .await?;
+    let (Some(issue_key), Some(workspace_id)) = (&task.jira_issue_key, &task.workspace_id) else {
+        return Ok(());
+    };
+    // Disconnected since the job was queued
+    let Some(connection) = JiraConnectionsRepo::new(state.database.clone())
+        .get_by_workspace(workspace_id)
+        .await?
+    else {
+        return Ok(());
+    };
+
+    let client = JiraClient::new(&connection, &state.config.encryption_key)?;
+    if client.transition_to_done(issue_key).await? {
+        info!("[Jira] Moved {} to done for task {}", issue_key, task.id);
+    } else {
+        info!("[Jira] {} has no transition to done, leaving it", issue_key);
+    }
+    Ok(())
+}
+
+/// Complete the task of an issue that moved to a done status. Returns the task, if
+/// the issue belongs to one that was not completed yet.
+pub async fn handle_webhook(
+    state: &AppState,
+    connection: &JiraConnection,
+    payload: JiraWebhook,
+) -> Result<Option<Task>> {
+    let Some(issue) = payload.issue else {
+        return Ok(None);
+    };
+    if payload.webhook_event != "jira:issue_updated"
+        || issue.fields.status.status_category.key != DONE_CATEGORY
+    {
+        return Ok(None);
+    }
+
+    let task = TasksRepo::new(state.database.clone())
+        .get_by_jira_issue_key(&connection.workspace_id, &issue.key)
+        .await?;
+    match task {
+        Some(task) if task.status != TaskStatus::Completed => {
+            Ok(Some(complete_task(state, task, None).await?))
+        }
+        _ => Ok(None),
+    }
+}
@@ -0,0 +1,302 @@
+//! Linear: tasks created in a connected workspace are pushed as issues to its Linear
+//! team, and an issue reaching a completed state completes its task.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use sea_orm::DatabaseConnection;
+use serde::{de::DeserializeOwned, Deserialize};
+use sha2::Sha256;
+use tracing::info;
+
+use crate::{
+    core::{
+        jobs::{self, Job},
+        state::AppState,
+    },
+    integrations::complete_task,
+    models::{
+        task::{Model as Task, TaskStatus},
+        workspace_settings::LinearSettings,
+    },
+    repos::{messages::MessagesRepo, tasks::TasksRepo, workspace_settings::WorkspaceSettingsRepo},
+    utils::encryption::decrypt,
+};
+
+const API_URL: &str = "https://api.linear.app/graphql";
+
+/// Linear truncates longer titles in most views
+const MAX_TITLE_CHARS: usize = 250;
+
+/// Workflow state type Linear gives every "done"-like state, whatever its name
+const COMPLETED_STATE: &str = "completed";
+
+#[derive(Debug, Deserialize)]
+struct GraphqlResponse<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<GraphqlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphqlError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TeamData {
+    team: Team,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Team {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IssueCreateData {
+    issue_create: IssueCreatePayload,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueCreatePayload {
+    success: bool,
+    issue: Option<CreatedIssue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreatedIssue {
+    id: String,
+    identifier: String,
+}
+
+/// Envelope of every Linear webhook; `data` is an issue when `kind` is "Issue"
+#[derive(Debug, Deserialize)]
+pub struct LinearWebhook {
+    /// "create", "update" or "remove"
+    pub action: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookIssue {
+    pub id: String,
+    pub identifier: String,
+    pub team_id: String,
+    pub state: Option<WebhookState>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookState {
+    /// "triage", "backlog", "unstarted", "started", "completed" or "canceled"
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+impl LinearWebhook {
+    /// The issue a create or update event is about
+    pub fn issue(&self) -> Option<WebhookIssue> {
+        if self.kind != "Issue" || self.action == "remove" {
+            return None;
+        }
+        serde_json::from_value(self.data.clone()).ok()
+    }
+}
+
+/// GraphQL client authenticated with a personal API key
+pub struct LinearClient {
+    http_client: Client,
+    api_key: String,
+}
+
+impl LinearClient {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            http_client: Client::new(),
+            api_key,
+        }
+    }
+
+    fn from_settings(settings: &LinearSettings, encryption_key: &str) -> Result<Self> {
+        Ok(Self::new(decrypt(&settings.api_key, encryption_key)?))
+    }
+
+    async fn query<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<T> {
+        let response: GraphqlResponse<T> = self
+            .http_client
+            .post(API_URL)
+            // Personal API keys are sent as is, without "Bearer"
+            .header(reqwest::header::AUTHORIZATION, &self.api_key)
+            .json(&serde_json::json!({ "query": query, "variables": variables }))
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let Some(error) = response.errors.into_iter().next() {
+            return Err(anyhow!("Linear API error: {}", error.message));
+        }
+        response
+            .data
+            .ok_or_else(|| anyhow!("Linear API returned no data"))
+    }
+
+    /// Look up a team, which checks the key can reach it
+    pub async fn team(&self, team_id: &str) -> Result<Team> {
+        let data: TeamData = self
+            .query(
+                "query Team($id: String!) { team(id: $id) { name } }",
+                serde_json::json!({ "id": team_id }),
+            )
+            .await?;
+        Ok(data.team)
+    }
+
+    async fn create_issue(
+        &self,
+        team_id: &str,
+        title: &str,
+        description: &str,
+    ) -> Result<CreatedIssue> {
+        let data: IssueCreateData = self
+            .query(
+                "mutation IssueCreate($input: IssueCreateInput!) { \
+                 issueCreate(input: $input) { success issue { id identifier } } }",
+                serde_json::json!({
+                    "input": {
+                        "teamId": team_id,
+                        "title": title,
+                        "description": description,
+                    }
+                }),
+            )
+            .await?;
+
+        match data.issue_create.issue {
+            Some(issue) if data.issue_create.success => Ok(issue),
+            _ => Err(anyhow!("Linear did not create the issue")),
+        }
+    }
+}
+
+/// Check the `Linear-Signature` header (hex HMAC-SHA256 of the body)
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Queue pushing a just created task to Linear when its workspace is connected
+pub async fn queue_issue(db: &DatabaseConnection, task: &Task) -> Result<()> {
+    let Some(workspace_id) = &task.workspace_id else {
+        return Ok(());
+    };
+    let connected = WorkspaceSettingsRepo::new(db.clone())
+        .get_linear_settings(workspace_id)
+        .await?
+        .is_some();
+    if !connected {
+        return Ok(());
+    }
+
+    jobs::enqueue(
+        db,
+        Job::LinearIssue {
+            task_id: task.id.clone(),
+        },
+    )
+    .await
+}
+
+/// Job body of `Job::LinearIssue`: create an issue from the task's message in the
+/// workspace's team and remember its id on the task
+pub async fn push_task(state: &AppState, task_id: &str) -> Result<()> {
+    let task = TasksRepo::new(state.database.clone())
+        .get(task_id.to_string())
+        .await?;
+    let Some(workspace_id) = task.workspace_id.clone() else {
+        return Ok(());
+    };
+    // Already pushed by an earlier attempt
+    if task.linear_issue_id.is_some() {
+        return Ok(());
+    }
+    // Disconnected since the job was queued
+    let Some(settings) = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_linear_settings(&workspace_id)
+        .await?
+    else {
+        return Ok(());
+    };
+
+    let message = MessagesRepo::new(state.database.clone(), state.message_encryption.clone())
+        .get_by_id(task.message_id.clone())
+        .await?;
+    let first_line = message.content.lines().next().unwrap_or_default().trim();
+    let title: String = if first_line.is_empty() {
+        format!("Slack task {}", task.id)
+    } else {
+        first_line.chars().take(MAX_TITLE_CHARS).collect()
+    };
+    let description = format!(
+        "{}\n\n[From Slack]({})",
+        message.content,
+        message.slack_link()
+    );
+
+    let client = LinearClient::from_settings(&settings, &state.config.encryption_key)?;
+    let issue = client
+        .create_issue(&settings.team_id, &title, &description)
+        .await?;
+    info!("[Linear] Created {} for task {}", issue.identifier, task.id);
+
+    TasksRepo::new(state.database.clone())
+        .set_linear_issue_id(task, issue.id)
+        .await?;
+    Ok(())
+}
+
+/// Mirror an issue's state onto its task. Slack reactions decide a task's status and
+/// a completed reaction outranks the others, so only completion is carried over.
+/// Returns the task, if the issue belongs to one that was not completed yet.
+pub async fn handle_webhook(
+    state: &AppState,
+    workspace_id: &str,
+    issue: WebhookIssue,
+) -> Result<Option<Task>> {
+    if issue.state.as_ref().map(|s| s.kind.as_str()) != Some(COMPLETED_STATE) {
+        return Ok(None);
+    }
+
+    let task = TasksRepo::new(state.database.clone())
+        .get_by_linear_issue_id(workspace_id, &issue.id)
+        .await?;
+    match task {
+        Some(task) if task.status != TaskStatus::Completed => {
+            info!(
+                "[Linear] {} completed, completing task {}",
+                issue.identifier, task.id
+            );
+            Ok(Some(complete_task(state, task, None).await?))
+        }
+        _ => Ok(None),
+    }
+}
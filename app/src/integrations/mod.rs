@@ -0,0 +1,189 @@
+//! Third-party trackers linked to Slacker tasks, chat apps following a workspace,
+//! email-in, and S3 for backups
+
+pub mod discord;
+pub mod email;
+pub mod github;
+pub mod jira;
+pub mod linear;
+pub mod notion;
+pub mod s3;
+pub mod teams;
+
+use anyhow::Result;
+use reqwest::Client;
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::{
+    core::state::AppState,
+    models::task::{Model as Task, TaskStatus},
+    repos::{messages::MessagesRepo, workspace_settings::WorkspaceSettingsRepo},
+    services::{
+        task_service::{Outcome, TaskService},
+        workspace::get_cached_workspace_tokens,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+struct SlackResponse {
+    ok: bool,
+    error: Option<String>,
+}
+
+/// Let integrations react to a task the Slack bot just created
+pub async fn task_created(db: &DatabaseConnection, task: &Task) {
+    if let Err(e) = linear::queue_issue(db, task).await {
+        warn!("[Linear] Failed to queue issue for task {}: {}", task.id, e);
+    }
+}
+
+/// Let integrations react to a status change the Slack bot picked up
+pub async fn task_status_changed(db: &DatabaseConnection, task: &Task) {
+    if let Err(e) = jira::queue_transition(db, task).await {
+        warn!(
+            "[Jira] Failed to queue transition of task {}: {}",
+            task.id, e
+        );
+    }
+}
+
+/// Complete a task because it was finished elsewhere. The bot adds the workspace's
+/// completed reaction to the task's message, so the status derived from reactions
+/// agrees, and posts `reply` in the message's thread.
+pub async fn complete_task(state: &AppState, task: Task, reply: Option<String>) -> Result<Task> {
+    if task.status == TaskStatus::Completed {
+        return Ok(task);
+    }
+
+    let task = TaskService::from_state(state)
+        .sync_status(task, TaskStatus::Completed)
+        .await?;
+    info!("[Integrations] Completed task {}", task.id);
+
+    if let Some(workspace_id) = task.workspace_id.as_deref() {
+        if let Err(e) = post_status(state, &task, workspace_id, reply).await {
+            warn!(
+                "[Integrations] Could not mark task {} completed in Slack: {}",
+                task.id, e
+            );
+        }
+    }
+
+    Ok(task)
+}
+
+/// Move a task to another status from outside Slack, e.g. the API, unless a hard
+/// WIP limit refuses it. Like `complete_task`, the bot reacts with the status's
+/// first emoji so the status derived from reactions agrees; a reaction of a status
+/// that outranks it still wins. With `expected`, only while the task is at that
+/// version, see `TaskService::transition_from`.
+pub async fn set_task_status(
+    state: &AppState,
+    task: Task,
+    status: TaskStatus,
+    expected: Option<i32>,
+) -> Result<Outcome> {
+    if task.status == status {
+        return Ok(Outcome::Done(task));
+    }
+
+    let outcome = TaskService::from_state(state)
+        .transition_from(task, status, expected)
+        .await?;
+    if let Outcome::Done(task) = &outcome {
+        if let Some(workspace_id) = task.workspace_id.as_deref() {
+            if let Err(e) = post_status(state, task, workspace_id, None).await {
+                warn!(
+                    "[Integrations] Could not mark task {} {:?} in Slack: {}",
+                    task.id, task.status, e
+                );
+            }
+        }
+    }
+
+    Ok(outcome)
+}
+
+async fn post_status(
+    state: &AppState,
+    task: &Task,
+    workspace_id: &str,
+    reply: Option<String>,
+) -> Result<()> {
+    let message = MessagesRepo::new(state.database.clone(), state.message_encryption.clone())
+        .get_by_id(task.message_id.clone())
+        .await?;
+    if !message.is_from_slack() {
+        return Ok(());
+    }
+    let tokens = get_cached_workspace_tokens(
+        &state.cache,
+        &state.database,
+        workspace_id,
+        &state.config.encryption_key,
+    )
+    .await?;
+    let mappings = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_emoji_mappings(workspace_id)
+        .await?;
+    let emoji = match task.status {
+        TaskStatus::InProgress => mappings.in_progress,
+        TaskStatus::Blocked => mappings.blocked,
+        TaskStatus::Completed => mappings.completed,
+        TaskStatus::Blank => vec![],
+    }
+    .into_iter()
+    .next();
+
+    let client = Client::new();
+    if let Some(emoji) = emoji {
+        let response = client
+            .post("https://slack.com/api/reactions.add")
+            .bearer_auth(&tokens.bot_token)
+            .json(&serde_json::json!({
+                "channel": message.channel,
+                "timestamp": message.timestamp,
+                "name": emoji,
+            }))
+            .send()
+            .await?
+            .json::<SlackResponse>()
+            .await?;
+        check_slack_response("reactions.add", response)?;
+    }
+
+    if let Some(text) = reply {
+        let response = client
+            .post("https://slack.com/api/chat.postMessage")
+            .bearer_auth(&tokens.bot_token)
+            .json(&serde_json::json!({
+                "channel": message.channel,
+                "thread_ts": message.timestamp,
+                "text": text,
+            }))
+            .send()
+            .await?
+            .json::<SlackResponse>()
+            .await?;
+        check_slack_response("chat.postMessage", response)?;
+    }
+
+    Ok(())
+}
+
+fn check_slack_response(method: &str, response: SlackResponse) -> Result<()> {
+    // "already_reacted": someone marked the message done by hand
+    if response.ok || response.error.as_deref() == Some("already_reacted") {
+        return Ok(());
+    }
+
+    Err(anyhow::anyhow!(
+        "Slack {} failed: {}",
+        method,
+        response
+            .error
+            .unwrap_or_else(|| "unknown error".to_string())
+    ))
+}
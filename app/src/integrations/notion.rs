@@ -0,0 +1,240 @@
+//! Notion: completed tasks are appended to a workspace's Notion database, one page
+//! per task, by the `notion_export` scheduled job or on demand.
+
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::{anyhow, Result};
+use reqwest::{Client, Method, RequestBuilder};
+use serde::Deserialize;
+use tracing::{error, info};
+
+use crate::{
+    core::state::AppState,
+    models::notion_connection::Model as NotionConnection,
+    repos::{
+        messages::MessagesRepo, notion_connections::NotionConnectionsRepo, persons::PersonsRepo,
+        tasks::TasksRepo, workspace_settings::WorkspaceSettingsRepo,
+    },
+    utils::{
+        encryption::decrypt,
+        redaction::{redact, HASH_PREFIX},
+    },
+};
+
+const API_URL: &str = "https://api.notion.com/v1";
+const API_VERSION: &str = "2022-06-28";
+
+/// First line of the message, cut well below Notion's 2000 character limit
+const MAX_TITLE_CHARS: usize = 250;
+
+/// Properties Slacker fills besides the title; created on connect when missing
+const ASSIGNEE_PROPERTY: &str = "Assignee";
+const COMPLETED_PROPERTY: &str = "Completed";
+const SLACK_PROPERTY: &str = "Slack";
+
+#[derive(Debug, Deserialize)]
+struct Database {
+    properties: HashMap<String, DatabaseProperty>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DatabaseProperty {
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreatedPage {
+    id: String,
+}
+
+struct NotionClient {
+    http_client: Client,
+    token: String,
+}
+
+impl NotionClient {
+    fn new(token: String) -> Self {
+        Self {
+            http_client: Client::new(),
+            token,
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http_client
+            .request(method, format!("{}/{}", API_URL, path))
+            .bearer_auth(&self.token)
+            .header("Notion-Version", API_VERSION)
+            .timeout(Duration::from_secs(10))
+    }
+
+    async fn database(&self, database_id: &str) -> Result<Database> {
+        Ok(self
+            .request(Method::GET, &format!("databases/{}", database_id))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    async fn add_properties(&self, database_id: &str, properties: serde_json::Value) -> Result<()> {
+        self.request(Method::PATCH, &format!("databases/{}", database_id))
+            .json(&serde_json::json!({ "properties": properties }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    async fn create_page(&self, body: serde_json::Value) -> Result<CreatedPage> {
+        Ok(self
+            .request(Method::POST, "pages")
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+}
+
+/// Check the token can reach the database and add the properties Slacker fills.
+/// Returns the name of the database's title property.
+pub async fn prepare_database(token: &str, database_id: &str) -> Result<String> {
+    let client = NotionClient::new(token.to_string());
+    let database = client.database(database_id).await?;
+
+    let title_property = database
+        .properties
+        .iter()
+        .find(|(_, property)| property.kind == "title")
+        .map(|(name, _)| name.clone())
+        .ok_or_else(|| anyhow!("The database has no title property"))?;
+
+    let mut missing = serde_json::Map::new();
+    for (name, kind) in [
+        (ASSIGNEE_PROPERTY, "rich_text"),
+        (COMPLETED_PROPERTY, "date"),
+        (SLACK_PROPERTY, "url"),
+    ] {
+        match database.properties.get(name) {
+            Some(property) if property.kind == kind => {}
+            Some(property) => {
+                return Err(anyhow!(
+                    "Property \"{}\" must be of type {}, not {}",
+                    name,
+                    kind,
+                    property.kind
+                ))
+            }
+            None => {
+                missing.insert(name.to_string(), serde_json::json!({ kind: {} }));
+            }
+        }
+    }
+    if !missing.is_empty() {
+        client
+            .add_properties(database_id, serde_json::Value::Object(missing))
+            .await?;
+    }
+
+    Ok(title_property)
+}
+
+/// Append every completed task of the connection's workspace that was not exported
+/// yet. Returns how many pages were created.
+pub async fn export_completed_tasks(
+    state: &AppState,
+    connection: &NotionConnection,
+) -> Result<usize> {
+    let tasks_repo = TasksRepo::new(state.database.clone());
+    let tasks = tasks_repo
+        .get_completed_not_exported(&connection.workspace_id)
+        .await?;
+    if tasks.is_empty() {
+        return Ok(0);
+    }
+
+    let message_ids = tasks.iter().map(|t| t.message_id.clone()).collect();
+    let messages: HashMap<_, _> =
+        MessagesRepo::new(state.database.clone(), state.message_encryption.clone())
+            .get_by_ids(message_ids)
+            .await?
+            .into_iter()
+            .map(|m| (m.id.clone(), m))
+            .collect();
+    let redaction = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_message_redaction(&connection.workspace_id)
+        .await?;
+    let persons_repo = PersonsRepo::new(state.database.clone());
+    let client = NotionClient::new(decrypt(&connection.token, &state.config.encryption_key)?);
+
+    let mut exported = 0;
+    for task in tasks {
+        let Some(message) = messages.get(&task.message_id) else {
+            continue;
+        };
+        let content = redact(&message.content, redaction);
+        let first_line = content.lines().next().unwrap_or_default().trim();
+        // A hash makes a poor title
+        let title: String = if first_line.is_empty() || first_line.starts_with(HASH_PREFIX) {
+            format!("Slack task {}", task.id)
+        } else {
+            first_line.chars().take(MAX_TITLE_CHARS).collect()
+        };
+        let assignee = persons_repo
+            .get_by_id(task.assigned_to.clone())
+            .await
+            .map(|person| person.name)
+            .unwrap_or_default();
+        let completed = task
+            .completed_at
+            .map(|at| serde_json::json!({ "start": at.and_utc().to_rfc3339() }));
+
+        let mut properties = serde_json::json!({
+            connection.title_property.as_str(): { "title": [{ "text": { "content": title } }] },
+            ASSIGNEE_PROPERTY: { "rich_text": [{ "text": { "content": assignee } }] },
+            COMPLETED_PROPERTY: { "date": completed },
+        });
+        if message.is_from_slack() {
+            properties[SLACK_PROPERTY] = serde_json::json!({ "url": message.slack_link() });
+        }
+
+        let page = client
+            .create_page(serde_json::json!({
+                "parent": { "database_id": connection.database_id },
+                "properties": properties,
+            }))
+            .await?;
+        tasks_repo.set_notion_page_id(task, page.id).await?;
+        exported += 1;
+    }
+
+    info!(
+        "[Notion] Exported {} completed task(s) of workspace {}",
+        exported, connection.workspace_id
+    );
+    Ok(exported)
+}
+
+/// Export for every connected workspace; body of the `notion_export` scheduled job
+pub async fn export_all(state: &AppState) -> Result<usize> {
+    let connections = NotionConnectionsRepo::new(state.database.clone())
+        .get_all()
+        .await?;
+
+    let mut exported = 0;
+    for connection in connections {
+        match export_completed_tasks(state, &connection).await {
+            Ok(count) => exported += count,
+            Err(e) => error!(
+                "[Notion] Failed to export tasks of workspace {}: {}",
+                connection.workspace_id, e
+            ),
+        }
+    }
+
+    Ok(exported)
+}
@@ -0,0 +1,184 @@
+//! Objects in S3 or an S3-compatible store such as MinIO, for database backups.
+//! Requests are signed with AWS Signature Version 4 and use path-style URLs, so
+//! any endpoint works.
+
+use std::sync::LazyLock;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use regex::Regex;
+use reqwest::{Client, Method, Response};
+use sha2::{Digest, Sha256};
+
+use crate::config::config::S3Config;
+
+const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+static OBJECT_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<Contents>.*?<Key>(.*?)</Key>.*?<Size>(\d+)</Size>.*?</Contents>").unwrap()
+});
+
+static CONTINUATION_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<NextContinuationToken>(.*?)</NextContinuationToken>").unwrap());
+
+#[derive(Debug, Clone)]
+pub struct S3Object {
+    pub key: String,
+    pub size: u64,
+}
+
+pub async fn put_object(
+    client: &Client,
+    config: &S3Config,
+    key: &str,
+    body: Vec<u8>,
+) -> Result<()> {
+    let response = send(client, config, Method::PUT, key, &[], body).await?;
+    check(response, "PutObject").await?;
+    Ok(())
+}
+
+pub async fn get_object(client: &Client, config: &S3Config, key: &str) -> Result<Vec<u8>> {
+    let response = send(client, config, Method::GET, key, &[], vec![]).await?;
+    Ok(check(response, "GetObject").await?.bytes().await?.to_vec())
+}
+
+/// Every object whose key starts with `prefix`
+pub async fn list_objects(
+    client: &Client,
+    config: &S3Config,
+    prefix: &str,
+) -> Result<Vec<S3Object>> {
+    let mut objects = Vec::new();
+    let mut continuation: Option<String> = None;
+    loop {
+        let mut query = vec![
+            ("list-type", "2".to_string()),
+            ("prefix", prefix.to_string()),
+        ];
+        if let Some(token) = continuation.take() {
+            query.push(("continuation-token", token));
+        }
+        let response = send(client, config, Method::GET, "", &query, vec![]).await?;
+        let body = check(response, "ListObjectsV2").await?.text().await?;
+
+        for found in OBJECT_PATTERN.captures_iter(&body) {
+            objects.push(S3Object {
+                key: found[1].to_string(),
+                size: found[2].parse().unwrap_or_default(),
+            });
+        }
+        match CONTINUATION_PATTERN.captures(&body) {
+            Some(found) => continuation = Some(found[1].to_string()),
+            None => return Ok(objects),
+        }
+    }
+}
+
+async fn send(
+    client: &Client,
+    config: &S3Config,
+    method: Method,
+    key: &str,
+    query: &[(&str, String)],
+    body: Vec<u8>,
+) -> Result<Response> {
+    let endpoint = config
+        .endpoint
+        .clone()
+        .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", config.region));
+    let path = format!("/{}/{}", config.bucket, key);
+    let mut query: Vec<String> = query
+        .iter()
+        .map(|(name, value)| {
+            format!(
+                "{}={}",
+                urlencoding::encode(name),
+                urlencoding::encode(value)
+            )
+        })
+        .collect();
+    query.sort();
+    let query = query.join("&");
+
+    let url = reqwest::Url::parse(&format!("{}{}", endpoint.trim_end_matches('/'), path))?;
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => return Err(anyhow!("S3 endpoint {} has no host", endpoint)),
+    };
+
+    let now = Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = hex::encode(Sha256::digest(&body));
+    let canonical_request = format!(
+        "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method,
+        url.path(),
+        query,
+        host,
+        payload_hash,
+        amz_date,
+        SIGNED_HEADERS,
+        payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, config.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let signature = hex::encode(hmac(
+        &signing_key(&config.secret_access_key, &date, &config.region),
+        string_to_sign.as_bytes(),
+    ));
+
+    let url = if query.is_empty() {
+        url.to_string()
+    } else {
+        format!("{}?{}", url, query)
+    };
+    let response = client
+        .request(method, url)
+        .header("x-amz-content-sha256", payload_hash)
+        .header("x-amz-date", amz_date)
+        .header(
+            "authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                config.access_key_id, scope, SIGNED_HEADERS, signature
+            ),
+        )
+        .body(body)
+        .send()
+        .await?;
+    Ok(response)
+}
+
+async fn check(response: Response, operation: &str) -> Result<Response> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    Err(anyhow!(
+        "S3 {} answered {}: {}",
+        operation,
+        response.status(),
+        response.text().await.unwrap_or_default()
+    ))
+}
+
+fn signing_key(secret: &str, date: &str, region: &str) -> Vec<u8> {
+    let key = hmac(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let key = hmac(&key, region.as_bytes());
+    let key = hmac(&key, b"s3");
+    hmac(&key, b"aws4_request")
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
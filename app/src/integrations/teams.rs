@@ -0,0 +1,130 @@
+//! Microsoft Teams: a stakeholder channel follows the workspace's task activity
+//! through an incoming webhook, either a Workflows one or a legacy connector.
+//! Messages are Adaptive Cards. Posting goes through the `TeamsNotifier`; this
+//! module only talks to Teams.
+
+use anyhow::{anyhow, Result};
+use reqwest::{Client, Url};
+use serde_json::{json, Value};
+
+/// Hosts of the "Post to a channel when a webhook request is received" workflow
+/// and of the older Office 365 connectors
+const WEBHOOK_HOST_SUFFIXES: &[&str] = &[
+    ".logic.azure.com",
+    ".powerplatform.com",
+    ".webhook.office.com",
+];
+
+/// Host of `url` when it looks like a Teams incoming webhook
+pub fn webhook_host(url: &str) -> Option<String> {
+    let url = Url::parse(url.trim()).ok()?;
+    let host = url.host_str()?;
+    if url.scheme() != "https"
+        || !WEBHOOK_HOST_SUFFIXES
+            .iter()
+            .any(|suffix| host.ends_with(suffix))
+    {
+        return None;
+    }
+    Some(host.to_string())
+}
+
+/// Post a card with a title. Lines of `text` that are only a URL become buttons.
+pub async fn post(client: &Client, url: &str, title: &str, text: &str) -> Result<()> {
+    let response = client.post(url).json(&message(title, text)).send().await?;
+
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Teams webhook answered {}: {}",
+            response.status(),
+            response.text().await.unwrap_or_default()
+        ));
+    }
+    Ok(())
+}
+
+fn message(title: &str, text: &str) -> Value {
+    let (links, lines): (Vec<&str>, Vec<&str>) = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .partition(|line| {
+            (line.starts_with("https://") || line.starts_with("http://")) && !line.contains(' ')
+        });
+
+    let mut body = vec![json!({
+        "type": "TextBlock",
+        "text": title,
+        "weight": "Bolder",
+        "size": "Medium",
+        "wrap": true,
+    })];
+    if !lines.is_empty() {
+        body.push(json!({
+            "type": "TextBlock",
+            "text": lines.join("\n\n"),
+            "wrap": true,
+        }));
+    }
+    let actions: Vec<Value> = links
+        .into_iter()
+        .map(|link| {
+            json!({
+                "type": "Action.OpenUrl",
+                "title": if link.contains("slack.com") { "Open in Slack" } else { "Open" },
+                "url": link,
+            })
+        })
+        .collect();
+
+    json!({
+        "type": "message",
+        "attachments": [{
+            "contentType": "application/vnd.microsoft.card.adaptive",
+            "contentUrl": null,
+            "content": {
+                "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                "type": "AdaptiveCard",
+                "version": "1.4",
+                "body": body,
+                "actions": actions,
+            },
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_only_teams_webhook_urls() {
+        assert_eq!(
+            webhook_host("https://acme.webhook.office.com/webhookb2/1/IncomingWebhook/2/3")
+                .as_deref(),
+            Some("acme.webhook.office.com")
+        );
+        assert!(webhook_host(
+            "https://prod-01.westus.logic.azure.com:443/workflows/1/triggers/manual/paths/invoke"
+        )
+        .is_some());
+        assert!(webhook_host("http://acme.webhook.office.com/webhookb2/1").is_none());
+        assert!(webhook_host("https://webhook.office.com.evil.test/webhookb2/1").is_none());
+    }
+
+    #[test]
+    fn turns_link_lines_into_buttons() {
+        let message = message(
+            "ACME-42 moved from Blank to InProgress",
+            "Fix the login page\nhttps://acme.slack.com/archives/C1/p1",
+        );
+        let card = &message["attachments"][0]["content"];
+
+        assert_eq!(card["body"][1]["text"], "Fix the login page");
+        assert_eq!(card["actions"][0]["title"], "Open in Slack");
+        assert_eq!(
+            card["actions"][0]["url"],
+            "https://acme.slack.com/archives/C1/p1"
+        );
+    }
+}
@@ -1,11 +1,14 @@
+pub mod cli;
 pub mod config;
 pub mod core;
 mod database;
 mod handlers;
+mod i18n;
+mod integrations;
 mod middlewares;
-mod models;
-mod repos;
+pub mod models;
+pub mod repos;
 mod routes;
 mod services;
 pub mod sockets;
-mod utils;
+pub mod utils;
@@ -1,90 +1,98 @@
-use std::net::{IpAddr, SocketAddr};
+use std::{
+    io::Write,
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use dotenvy::dotenv;
 use slacker::{
-    config::{config::Config, workspaces::WorkspacesConfig},
-    core::server::create_server,
-    sockets::slack_bot::SlackBot,
+    cli::doctor,
+    config::config::Config,
+    core::{file_log::FileLogLayer, server::create_server, shutdown::shutdown_signal},
+    services::doctor::{check_config, Severity},
 };
-use tokio::signal;
-use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv().ok();
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
-        )
-        .init();
+    let config = Config::load().expect("Failed to load config");
 
-    let config = Config::load_envs().expect("Failed to load envs");
+    tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .with(tracing_subscriber::fmt::layer())
+        .with(FileLogLayer::new(&config.log).context("Failed to open log directory")?)
+        .init();
 
-    // Check if using default encryption key
-    if config.encryption_key == "change-this-default-encryption-key-in-production" {
-        warn!("⚠️  Using default encryption key! Set ENCRYPTION_KEY in production!");
+    // `--check`: report what is misconfigured and exit without serving
+    if std::env::args().any(|arg| arg == "--check") {
+        return doctor(&config).await;
+    }
+    for finding in check_config(&config) {
+        match finding.severity {
+            Severity::Ok => {}
+            Severity::Warning => warn!("{}", finding),
+            Severity::Error => error!("{}", finding),
+        }
     }
 
     let port: u16 = config.port.clone();
     let server_ip_str: String = config.server_ip.clone();
     let server_ip: IpAddr = server_ip_str.parse().unwrap_or(IpAddr::from([0, 0, 0, 0]));
     let addr = SocketAddr::new(server_ip, port);
-    let (server, db_conn, bot_status) = create_server(config.clone()).await?;
+    let shutdown_timeout = Duration::from_secs(config.shutdown_timeout_secs);
+    let (server, state) = create_server(config.clone()).await?;
 
-    let shutdown_token = CancellationToken::new();
+    // Import any workspaces still only in workspaces.yaml, spawn a bot for each
+    state.spawn_configured_bots().await;
+    state.spawn_scheduler();
+    state.spawn_job_workers();
+    state.spawn_hook_dispatcher();
+    state.spawn_snapshot_invalidator();
+    state.spawn_event_consumers();
+    state.spawn_config_watcher();
 
-    // Load and decrypt workspaces, spawn a bot for each
-    match WorkspacesConfig::load_and_decrypt("workspaces.yaml", &config.encryption_key) {
-        Ok(workspaces_config) => {
-            info!(
-                "Loaded {} workspaces from config",
-                workspaces_config.workspaces.len()
-            );
+    // On SIGINT/SIGTERM: stop accepting connections, let in-flight requests drain
+    // and signal every bot to close its WebSocket after the current event.
+    let handle = axum_server::Handle::new();
+    let signal_handle = handle.clone();
+    let signal_state = state.clone();
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        signal_handle.graceful_shutdown(Some(shutdown_timeout));
+        signal_state.shutdown_token.cancel();
+    });
 
-            for (workspace_name, workspace_config) in workspaces_config.workspaces {
-                let bot = SlackBot::new(
-                    workspace_name.clone(),
-                    workspace_config.app_token,
-                    workspace_config.bot_token,
-                    db_conn.clone(),
-                    bot_status.clone(),
-                );
+    info!("Server starting on {}", addr);
+    if let Err(e) = axum_server::bind(addr)
+        .handle(handle)
+        .serve(server.into_make_service())
+        .await
+    {
+        error!("Server failed: {}", e);
+        state.shutdown_token.cancel();
+    }
 
-                let token = shutdown_token.clone();
-                tokio::spawn(async move {
-                    info!("Starting SlackBot for workspace: {}", workspace_name);
-                    if let Err(e) = bot.start(token).await {
-                        error!("SlackBot for workspace {} failed: {}", workspace_name, e);
-                    }
-                });
-            }
-        }
-        Err(e) => {
-            error!("Failed to load workspaces.yaml: {}", e);
-            error!("SlackBots will not start. Please create workspaces.yaml");
-        }
+    // Wait for bots to finish the event they are processing
+    state.bot_tasks.close();
+    if tokio::time::timeout(shutdown_timeout, state.bot_tasks.wait())
+        .await
+        .is_err()
+    {
+        warn!(
+            "Timed out after {:?} waiting for {} bot task(s) to stop",
+            shutdown_timeout,
+            state.bot_tasks.len()
+        );
     }
 
-    let server = axum_server::bind(addr).serve(server.into_make_service());
-    info!("Server starting on {}", addr);
+    // Let other instances pick up our bots and the scheduler right away
+    state.release_leases().await;
 
-    // Run server until Ctrl+C, then signal bots to shut down gracefully
-    tokio::select! {
-        result = server => {
-            if let Err(e) = result {
-                error!("Server failed: {}", e);
-            }
-        }
-        _ = signal::ctrl_c() => {
-            info!("Received Ctrl+C, shutting down gracefully...");
-            shutdown_token.cancel();
-            // Give bots a moment to close their WebSocket connections
-            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-        }
-    }
+    info!("Shutdown complete");
+    let _ = std::io::stdout().flush();
 
     Ok(())
 }
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use axum::{
     body::Body,
     extract::{Request, State},
+    http::HeaderValue,
     middleware::Next,
     response::{IntoResponse, Response},
 };
@@ -10,10 +11,25 @@ use tracing::error;
 
 use crate::{
     core::state::AppState,
-    repos::persons::PersonsRepo,
+    repos::{audit_logs::AuditLogsRepo, sessions::SessionsRepo},
     utils::{jwt::verify_jwt, response::APIError},
 };
 
+/// Set on every response to a request made with an impersonation token
+pub const IMPERSONATED_BY_HEADER: &str = "x-impersonated-by";
+
+/// In the request extensions while a super admin is acting as the authenticated person
+#[derive(Debug, Clone)]
+pub struct Impersonation {
+    pub admin_id: String,
+}
+
+/// In the request extensions when the token belongs to a session, see `SessionsRepo`
+#[derive(Debug, Clone)]
+pub struct CurrentSession {
+    pub id: String,
+}
+
 pub async fn require_auth(
     State(state): State<Arc<AppState>>,
     mut request: Request<Body>,
@@ -32,7 +48,7 @@ pub async fn require_auth(
         }
     };
 
-    let claims = match verify_jwt(token, &state.config.jwt_secret) {
+    let claims = match verify_jwt(token, &state.config.jwt.secret) {
         Ok(c) => c,
         Err(e) => {
             error!("Auth Failed, Invalid token: {}", e);
@@ -40,8 +56,7 @@ pub async fn require_auth(
         }
     };
 
-    let persons_repo = PersonsRepo::new(state.database.clone());
-    let person = match persons_repo.get_by_email(claims.sub).await {
+    let person = match state.stores.persons.get_by_email(claims.sub).await {
         Ok(p) => p,
         Err(e) => {
             error!("User not found: {}", e);
@@ -49,6 +64,63 @@ pub async fn require_auth(
         }
     };
 
+    // Tokens of revoked or expired sessions are refused, see `DELETE /api/me/sessions/{id}`.
+    // Every sign-in has a session; only impersonation tokens are issued without one.
+    if let Some(session_id) = claims.sid {
+        let sessions_repo = SessionsRepo::new(state.database.clone());
+        match sessions_repo.get_active(&person.id, &session_id).await {
+            Ok(Some(session)) => {
+                if let Err(e) = sessions_repo.touch(&session).await {
+                    error!("Failed to record use of session {}: {}", session.id, e);
+                }
+            }
+            Ok(None) => {
+                error!("Auth Failed, session {} was revoked", session_id);
+                return APIError::UnAuthorized.into_response();
+            }
+            Err(e) => {
+                error!("Failed to load session {}: {}", session_id, e);
+                return APIError::UnAuthorized.into_response();
+            }
+        }
+        request
+            .extensions_mut()
+            .insert(CurrentSession { id: session_id });
+    } else if claims.impersonated_by.is_none() {
+        error!("Auth Failed, token has no session");
+        return APIError::UnAuthorized.into_response();
+    }
+
+    let Some(admin_id) = claims.impersonated_by else {
+        request.extensions_mut().insert(person);
+        return next.run(request).await;
+    };
+
+    // Every request made while impersonating ends up in the audit log
+    let person_id = person.id.clone();
+    let action = format!("{} {}", request.method(), request.uri().path());
     request.extensions_mut().insert(person);
-    next.run(request).await
+    request.extensions_mut().insert(Impersonation {
+        admin_id: admin_id.clone(),
+    });
+
+    let mut response = next.run(request).await;
+
+    let details = format!("{} -> {}", action, response.status().as_u16());
+    if let Err(e) = AuditLogsRepo::new(state.database.clone())
+        .record_impersonated(
+            &admin_id,
+            &person_id,
+            "impersonation.request",
+            Some(details),
+        )
+        .await
+    {
+        error!("Failed to audit impersonated request {}: {}", action, e);
+    }
+    if let Ok(value) = HeaderValue::from_str(&admin_id) {
+        response.headers_mut().insert(IMPERSONATED_BY_HEADER, value);
+    }
+
+    response
 }
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::core::state::AppState;
+
+/// Successful writes through the API can change tasks without a board event (a
+/// status update, a merge, an integration webhook), so each one moves the board
+/// version and with it the board ETags
+pub async fn touch_boards_after_writes(
+    State(state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let write = !matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    let response = next.run(request).await;
+    if write && response.status().is_success() {
+        state.board_events.touch();
+    }
+    response
+}
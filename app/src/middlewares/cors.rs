@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use reqwest::Url;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::{info, warn};
+
+use crate::{
+    config::config::Config,
+    middlewares::{
+        auth::IMPERSONATED_BY_HEADER,
+        idempotency::{IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_REPLAYED_HEADER},
+    },
+};
+
+/// `scheme://host[:port]` of a URL, the form browsers send in `Origin`
+fn origin_of(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .map(|url| url.origin().ascii_serialization())
+        .filter(|origin| origin != "null")
+}
+
+fn is_local(url: &Url) -> bool {
+    matches!(url.scheme(), "http" | "https")
+        && matches!(
+            url.host_str(),
+            Some("localhost") | Some("127.0.0.1") | Some("[::1]")
+        )
+}
+
+/// CORS for browser frontends on other origins. Only listed origins and
+/// `frontend_url` are allowed, plus local dev servers while developing locally
+pub fn cors_layer(config: &Config) -> CorsLayer {
+    let cors = &config.cors;
+    let allow_localhost = cors
+        .allow_localhost
+        .unwrap_or_else(|| Url::parse(&config.frontend_url).is_ok_and(|url| is_local(&url)));
+
+    let wildcard = cors.allowed_origins.iter().any(|origin| origin == "*");
+    if wildcard && cors.allow_credentials {
+        warn!("[CORS] Ignoring \"*\" in allowed origins, it cannot be combined with credentials");
+    }
+
+    let mut origins: Vec<String> = cors
+        .allowed_origins
+        .iter()
+        .filter(|origin| *origin != "*")
+        .filter_map(|origin| {
+            let normalized = origin_of(origin);
+            if normalized.is_none() {
+                warn!("[CORS] Ignoring invalid allowed origin {:?}", origin);
+            }
+            normalized
+        })
+        .collect();
+    if let Some(frontend) = origin_of(&config.frontend_url) {
+        if !origins.contains(&frontend) {
+            origins.push(frontend);
+        }
+    }
+    info!(
+        "[CORS] Allowing origins {:?}{}",
+        origins,
+        if allow_localhost {
+            " and localhost"
+        } else {
+            ""
+        }
+    );
+
+    let allow_origin = if wildcard && !cors.allow_credentials {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::predicate(move |origin: &HeaderValue, _| {
+            let Ok(origin) = origin.to_str() else {
+                return false;
+            };
+            origins.iter().any(|allowed| allowed == origin)
+                || (allow_localhost && Url::parse(origin).is_ok_and(|url| is_local(&url)))
+        })
+    };
+
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers([
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            header::ACCEPT,
+            // Task versions for PATCH, and conditional board requests
+            header::IF_MATCH,
+            header::IF_NONE_MATCH,
+            HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
+        ])
+        .expose_headers([
+            HeaderName::from_static(IMPERSONATED_BY_HEADER),
+            header::ETAG,
+            HeaderName::from_static(IDEMPOTENT_REPLAYED_HEADER),
+        ])
+        .allow_credentials(cors.allow_credentials)
+        .max_age(Duration::from_secs(cors.max_age_secs))
+}
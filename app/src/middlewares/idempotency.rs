@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Duration;
+use sha2::{Digest, Sha256};
+use tracing::error;
+
+use crate::{
+    core::state::AppState,
+    models::{idempotency_key::Model as IdempotencyKey, person::Model as Person},
+    repos::idempotency_keys::{Claim, IdempotencyKeysRepo},
+    utils::response::APIError,
+};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Set on responses that were replayed instead of handled again
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Longest key accepted; UUIDs and similar fit easily
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Bodies buffered to hash a request or keep a response, like axum's own body limit
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Run an authenticated POST with an `Idempotency-Key` header once per key and
+/// person. Retries get the stored response back, marked `Idempotent-Replayed`,
+/// until `idempotency_key_ttl_hours` passed. Server errors are not kept, so the
+/// request can be retried for real.
+pub async fn replay_idempotent_requests(
+    State(state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+    let key = match key.to_str().map(str::trim) {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => key.to_string(),
+        _ => {
+            return APIError::BadRequest(format!(
+                "Idempotency-Key must be 1 to {} visible characters",
+                MAX_KEY_LENGTH
+            ))
+            .into_response()
+        }
+    };
+    let Some(person) = request.extensions().get::<Person>().cloned() else {
+        return next.run(request).await;
+    };
+
+    let (parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, MAX_BODY_BYTES).await else {
+        return APIError::BadRequest("Request body is too large".to_string()).into_response();
+    };
+    let request_hash = request_hash(&parts.method, &parts.uri.to_string(), &body);
+
+    let repo = IdempotencyKeysRepo::new(state.database.clone());
+    let expired_before =
+        chrono::Utc::now().naive_utc() - Duration::hours(state.config.idempotency_key_ttl_hours);
+    let claim = match repo
+        .claim(&person.id, &key, &request_hash, expired_before)
+        .await
+    {
+        Ok(claim) => claim,
+        Err(e) => {
+            error!("Failed to claim idempotency key: {}", e);
+            return APIError::InternalServerError("Failed to check Idempotency-Key".to_string())
+                .into_response();
+        }
+    };
+    let id = match claim {
+        Claim::New(id) => id,
+        Claim::Existing(stored) if stored.request_hash != request_hash => {
+            return APIError::BadRequest(
+                "Idempotency-Key was already used for a different request".to_string(),
+            )
+            .into_response()
+        }
+        Claim::Existing(stored) => return replay(stored),
+    };
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    if response.status().is_server_error() {
+        release(&repo, &id).await;
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            error!("Failed to read response for idempotency key: {}", e);
+            release(&repo, &id).await;
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    // Only text responses are kept; anything else runs again when retried
+    match String::from_utf8(body.to_vec()) {
+        Ok(text) => {
+            let content_type = parts
+                .headers
+                .get(header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            if let Err(e) = repo
+                .complete(&id, parts.status.as_u16(), content_type, text)
+                .await
+            {
+                error!("Failed to store response for idempotency key: {}", e);
+                release(&repo, &id).await;
+            }
+        }
+        Err(_) => release(&repo, &id).await,
+    }
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn request_hash(method: &Method, uri: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str().as_bytes());
+    hasher.update([0]);
+    hasher.update(uri.as_bytes());
+    hasher.update([0]);
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+/// The stored response, or 409 while the first request is still running
+fn replay(stored: IdempotencyKey) -> Response {
+    let (Some(status), Some(body)) = (stored.status_code, stored.response_body) else {
+        return APIError::Conflict(
+            "A request with this Idempotency-Key is still in progress".to_string(),
+        )
+        .into_response();
+    };
+
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() =
+        StatusCode::from_u16(status as u16).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    if let Some(content_type) = stored
+        .content_type
+        .and_then(|value| HeaderValue::from_str(&value).ok())
+    {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, content_type);
+    }
+    response
+        .headers_mut()
+        .insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+async fn release(repo: &IdempotencyKeysRepo, id: &str) {
+    if let Err(e) = repo.release(id).await {
+        error!("Failed to release idempotency key: {}", e);
+    }
+}
@@ -0,0 +1,94 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::Body,
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use tracing::{error, warn};
+
+use crate::{
+    core::state::AppState, models::notification_setting::NotificationKind,
+    repos::persons::PersonsRepo,
+};
+
+/// Time every request against `latency_budget_ms` per route. Slow requests are
+/// logged with their route, and a route whose p99 goes over budget raises an alert.
+pub async fn track_latency(
+    State(state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    // Unmatched requests would fill the metrics with every path tried
+    let Some(path) = request.extensions().get::<MatchedPath>() else {
+        return next.run(request).await;
+    };
+    let route = format!("{} {}", request.method(), path.as_str());
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    let elapsed = started.elapsed();
+
+    let budget = Duration::from_millis(state.config.latency_budget_ms);
+    if elapsed > budget {
+        warn!(
+            route = %route,
+            elapsed_ms = elapsed.as_millis() as u64,
+            budget_ms = state.config.latency_budget_ms,
+            "Request over latency budget"
+        );
+    }
+
+    let window = Duration::from_secs(state.config.latency_window_secs);
+    if let Some(p99) = state.metrics.record(&route, elapsed, budget, window) {
+        warn!(
+            route = %route,
+            p99_ms = p99.as_millis() as u64,
+            budget_ms = state.config.latency_budget_ms,
+            "Route p99 latency over budget"
+        );
+        if state.config.latency_alerts {
+            tokio::spawn(alert_admin(state.clone(), route, p99));
+        }
+    }
+
+    response
+}
+
+async fn alert_admin(state: Arc<AppState>, route: String, p99: Duration) {
+    let admin = match PersonsRepo::new(state.database.clone())
+        .get_by_email(state.config.admin_email.clone())
+        .await
+    {
+        Ok(admin) => admin,
+        Err(e) => {
+            error!("Failed to load the admin for a latency alert: {}", e);
+            return;
+        }
+    };
+
+    let body = format!(
+        "The 99th percentile latency of {} was {} ms over the last {} seconds, above the {} ms budget. Slow database queries are the usual cause.",
+        route,
+        p99.as_millis(),
+        state.config.latency_window_secs,
+        state.config.latency_budget_ms
+    );
+    if let Err(e) = state
+        .notifier
+        .notify(
+            &admin,
+            NotificationKind::LatencyAlert,
+            None,
+            &format!("{} is slow", route),
+            body,
+        )
+        .await
+    {
+        error!("Failed to send latency alert for {}: {}", route, e);
+    }
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{core::state::AppState, utils::response::APIError};
+
+/// Still accepted during maintenance, so admins can turn it off again and back
+/// up or restore the database meanwhile
+const MAINTENANCE_PATHS: &[&str] = &[
+    "/admin/maintenance",
+    "/admin/backups",
+    "/admin/backups/restore",
+];
+
+/// Refuse mutating requests with 503 while the instance is in maintenance mode
+pub async fn reject_writes_during_maintenance(
+    State(state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let read_only = matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    if read_only || MAINTENANCE_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    match state.maintenance.current() {
+        Some(maintenance) => APIError::ServiceUnavailable(maintenance.message).into_response(),
+        None => next.run(request).await,
+    }
+}
@@ -1 +1,7 @@
 pub mod auth;
+pub mod board_version;
+pub mod cors;
+pub mod idempotency;
+pub mod latency;
+pub mod maintenance;
+pub mod viewers;
@@ -0,0 +1,98 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    body::Body,
+    extract::{Path, Request, State},
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tracing::warn;
+
+use crate::{
+    core::state::AppState,
+    handlers::admins::is_read_only,
+    models::person::Model as Person,
+    repos::{
+        hook_subscriptions::HookSubscriptionsRepo, task_templates::TaskTemplatesRepo,
+        workspaces::WorkspacesRepo,
+    },
+    utils::response::APIError,
+};
+
+/// Refuse mutating requests with 403 from viewers of the workspace they change,
+/// who may look at its tasks but not change them. The workspace comes from the
+/// path, see `target_workspace`; requests naming none are left to the handler.
+/// Goes inside `require_auth` as a `route_layer`, so the path is matched.
+pub async fn reject_viewer_writes(
+    State(state): State<Arc<AppState>>,
+    params: Option<Path<HashMap<String, String>>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let read_only = matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    if read_only {
+        return next.run(request).await;
+    }
+    let Some(person) = request.extensions().get::<Person>() else {
+        return next.run(request).await;
+    };
+    let Some(Path(params)) = params else {
+        return next.run(request).await;
+    };
+    let Some(workspace_id) = target_workspace(&state, &params).await else {
+        return next.run(request).await;
+    };
+
+    if is_read_only(&state, person, &workspace_id).await {
+        warn!(
+            "Refused {} {} of viewer {}",
+            request.method(),
+            request.uri().path(),
+            person.email
+        );
+        return APIError::Forbidden.into_response();
+    }
+    next.run(request).await
+}
+
+/// Id of the workspace a path points at: its `workspace_name`, or the workspace of
+/// its task, task template or hook. None when it names none that exists.
+async fn target_workspace(state: &AppState, params: &HashMap<String, String>) -> Option<String> {
+    if let Some(name) = params.get("workspace_name") {
+        return WorkspacesRepo::new(state.database.clone())
+            .get_by_name(name)
+            .await
+            .ok()
+            .map(|workspace| workspace.id);
+    }
+    if let Some(task_id) = params.get("task_id") {
+        return state
+            .stores
+            .tasks
+            .get(task_id.clone())
+            .await
+            .ok()
+            .and_then(|task| task.workspace_id);
+    }
+    if let Some(template_id) = params.get("template_id") {
+        return TaskTemplatesRepo::new(state.database.clone())
+            .get_by_id(template_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|template| template.workspace_id);
+    }
+    if let Some(hook_id) = params.get("hook_id") {
+        return HookSubscriptionsRepo::new(state.database.clone())
+            .get(hook_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|subscription| subscription.workspace_id);
+    }
+    None
+}
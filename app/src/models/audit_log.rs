@@ -0,0 +1,26 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Record of a sensitive action, kept after the actor or target is gone
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = AuditLog)]
+#[sea_orm(table_name = "audit_logs")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    /// Person who acted, `None` for the system
+    pub actor_id: Option<String>,
+    /// Dotted action name, e.g. `person.erased`
+    pub action: String,
+    pub target_id: Option<String>,
+    pub details: Option<String>,
+    /// Set when an admin was impersonating the actor
+    pub impersonated_by: Option<String>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,30 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Days a member is out of office, both ends included, as dates in their own
+/// timezone. Auto-assignment passes them over meanwhile.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = Availability)]
+#[sea_orm(table_name = "availability")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    #[serde(skip_serializing)]
+    pub person_id: String,
+    pub starts_on: Date,
+    pub ends_on: Date,
+    pub note: Option<String>,
+    pub created_at: DateTime,
+}
+
+impl Model {
+    pub fn covers(&self, day: Date) -> bool {
+        self.starts_on <= day && day <= self.ends_on
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,68 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{
+    message::Model as Message,
+    task::{Model as Task, TaskStatus},
+};
+
+/// Which tasks a board view shows; empty fields don't filter
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BoardViewFilter {
+    /// Person id of the assignee
+    #[serde(default)]
+    pub assignee: Option<String>,
+    /// Tasks with all of these labels
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Slack channel id of the task's message
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Tasks in any of these statuses
+    #[serde(default)]
+    pub statuses: Vec<TaskStatus>,
+}
+
+impl BoardViewFilter {
+    pub fn matches(&self, task: &Task, message: &Message) -> bool {
+        let labels = task.labels();
+        self.assignee
+            .as_ref()
+            .is_none_or(|assignee| *assignee == task.assigned_to)
+            && self.labels.iter().all(|label| labels.contains(label))
+            && self
+                .channel
+                .as_ref()
+                .is_none_or(|channel| *channel == message.channel)
+            && (self.statuses.is_empty() || self.statuses.contains(&task.status))
+    }
+}
+
+/// Named filter a person saved for their board
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = BoardView)]
+#[sea_orm(table_name = "board_views")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    #[serde(skip_serializing)]
+    pub person_id: String,
+    /// Unique among the person's views
+    pub name: String,
+    /// JSON `BoardViewFilter`, see `filter()`
+    #[schema(value_type = BoardViewFilter)]
+    pub filter: Json,
+    pub created_at: DateTime,
+}
+
+impl Model {
+    pub fn filter(&self) -> BoardViewFilter {
+        serde_json::from_value(self.filter.clone()).unwrap_or_default()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
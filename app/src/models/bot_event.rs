@@ -0,0 +1,67 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(
+    Clone, Debug, Eq, PartialEq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum BotEventOutcome {
+    /// The bot acted on the event
+    #[sea_orm(string_value = "handled")]
+    Handled,
+    /// The bot looked at the event and chose not to act, see `error` for why
+    #[sea_orm(string_value = "skipped")]
+    Skipped,
+    /// A kind of event the bot does not handle
+    #[sea_orm(string_value = "ignored")]
+    Ignored,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+/// A Slack event or interaction a workspace's bot processed. Message text is not
+/// kept; the event can be found in Slack by its channel and `ts`.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = BotEvent)]
+#[sea_orm(table_name = "bot_events")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub workspace_id: String,
+    /// `events_api` or `interactive`
+    pub envelope_type: String,
+    /// Event type, e.g. `reaction_added`, or the interaction type, e.g. `shortcut`
+    pub event_type: String,
+    pub subtype: Option<String>,
+    pub channel: Option<String>,
+    /// Timestamp of the message the event is about
+    pub ts: Option<String>,
+    /// Slack member who caused the event
+    pub user_id: Option<String>,
+    pub reaction: Option<String>,
+    pub outcome: BotEventOutcome,
+    /// Why the event failed or was skipped
+    pub error: Option<String>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::workspace::Entity",
+        from = "Column::WorkspaceId",
+        to = "super::workspace::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Workspace,
+}
+
+impl Related<super::workspace::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Workspace.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,54 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(
+    Clone, Debug, Eq, PartialEq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum BotIncidentKind {
+    #[sea_orm(string_value = "connected")]
+    Connected,
+    #[sea_orm(string_value = "disconnected")]
+    Disconnected,
+    /// The bot failed to start or crashed
+    #[sea_orm(string_value = "error")]
+    Error,
+    #[sea_orm(string_value = "reconnect_requested")]
+    ReconnectRequested,
+    /// Stopped on shutdown or when the workspace was removed
+    #[sea_orm(string_value = "stopped")]
+    Stopped,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = BotIncident)]
+#[sea_orm(table_name = "bot_incidents")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub workspace_id: String,
+    pub kind: BotIncidentKind,
+    pub message: Option<String>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::workspace::Entity",
+        from = "Column::WorkspaceId",
+        to = "super::workspace::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Workspace,
+}
+
+impl Related<super::workspace::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Workspace.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,63 @@
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+
+use super::{change::Model as Change, task::TaskStatus};
+
+/// Status change of a task moved out of `changes`, see `services::archive`
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "changes_archive")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub old: TaskStatus,
+    pub new: TaskStatus,
+    pub index: i16,
+    pub task_id: String,
+    pub created_at: Option<DateTime>,
+    pub archived_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::task::Entity",
+        from = "Column::TaskId",
+        to = "super::task::Column::Id"
+    )]
+    Task,
+}
+
+impl Related<super::task::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Archived copy of a change, archived now
+    pub fn of(change: Change) -> Self {
+        Self {
+            id: Set(change.id),
+            old: Set(change.old),
+            new: Set(change.new),
+            index: Set(change.index),
+            task_id: Set(change.task_id),
+            created_at: Set(change.created_at),
+            archived_at: Set(chrono::Utc::now().naive_utc()),
+        }
+    }
+}
+
+impl From<Model> for Change {
+    fn from(archived: Model) -> Self {
+        Self {
+            id: archived.id,
+            old: archived.old,
+            new: archived.new,
+            index: archived.index,
+            task_id: archived.task_id,
+            created_at: archived.created_at,
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+
+use super::task::TaskPriority;
+
+/// Defaults for tasks created from messages of a Slack channel
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "channel_rules")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub workspace_id: String,
+    /// Slack channel id
+    pub channel: String,
+    /// Person new tasks are assigned to instead of the message's author
+    pub assignee_id: Option<String>,
+    /// JSON array of label names given to new tasks, see `labels()`
+    pub labels: Json,
+    pub priority: Option<TaskPriority>,
+    /// Project new tasks are grouped under
+    pub project_id: Option<String>,
+    /// No tasks are created or updated from the channel's messages
+    pub ignored: bool,
+    pub updated_at: DateTime,
+}
+
+impl Model {
+    pub fn labels(&self) -> Vec<String> {
+        serde_json::from_value(self.labels.clone()).unwrap_or_default()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
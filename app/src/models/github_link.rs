@@ -0,0 +1,38 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(
+    Clone, Debug, Eq, PartialEq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum GithubLinkKind {
+    #[sea_orm(string_value = "issue")]
+    Issue,
+    #[sea_orm(string_value = "pull_request")]
+    PullRequest,
+}
+
+/// Issue or pull request that mentions a task
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = GithubLink)]
+#[sea_orm(table_name = "github_links")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[serde(skip_serializing)]
+    pub id: String,
+    #[serde(skip_serializing)]
+    pub task_id: String,
+    pub kind: GithubLinkKind,
+    pub url: String,
+    pub title: String,
+    /// "open", "closed" or "merged"
+    pub state: String,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
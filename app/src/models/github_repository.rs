@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+
+/// GitHub repository whose webhooks may complete tasks of a workspace
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "github_repositories")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub workspace_id: String,
+    /// "owner/repo"
+    #[sea_orm(unique)]
+    pub full_name: String,
+    /// Encrypted with the master key; GitHub signs deliveries with it
+    pub webhook_secret: String,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
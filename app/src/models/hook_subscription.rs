@@ -0,0 +1,44 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Task events a REST hook can subscribe to
+#[derive(
+    Clone, Debug, Eq, PartialEq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    #[sea_orm(string_value = "task_created")]
+    TaskCreated,
+    /// Status or assigner changed
+    #[sea_orm(string_value = "task_updated")]
+    TaskUpdated,
+    /// An update that left the task completed
+    #[sea_orm(string_value = "task_completed")]
+    TaskCompleted,
+}
+
+/// REST hook: task events of a workspace are POSTed to `target_url`
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = HookSubscription)]
+#[sea_orm(table_name = "hook_subscriptions")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    #[serde(skip_serializing)]
+    pub person_id: String,
+    pub workspace_id: String,
+    pub event: HookEvent,
+    pub target_url: String,
+    /// Signs deliveries, encrypted with the master key; None for hooks subscribed
+    /// before deliveries were signed, until the secret is rotated
+    #[serde(skip_serializing)]
+    pub secret: Option<String>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
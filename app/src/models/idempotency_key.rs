@@ -0,0 +1,39 @@
+use sea_orm::entity::prelude::*;
+
+/// A POST request sent with an `Idempotency-Key` header, and once it finished the
+/// response to replay when the request is retried with the same key
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "idempotency_keys")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub person_id: String,
+    pub key: String,
+    /// SHA-256 of the method, path and body, so a key is not reused for another
+    /// request
+    pub request_hash: String,
+    /// None while the first request is still running
+    pub status_code: Option<i32>,
+    pub content_type: Option<String>,
+    pub response_body: Option<String>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::person::Entity",
+        from = "Column::PersonId",
+        to = "super::person::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Person,
+}
+
+impl Related<super::person::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Person.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
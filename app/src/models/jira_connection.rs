@@ -0,0 +1,28 @@
+use sea_orm::entity::prelude::*;
+
+/// Jira Cloud site a workspace creates issues in
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "jira_connections")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    #[sea_orm(unique)]
+    pub workspace_id: String,
+    /// e.g. "https://example.atlassian.net"
+    pub base_url: String,
+    /// Account the API token belongs to
+    pub email: String,
+    /// Encrypted with the master key
+    pub api_token: String,
+    pub project_key: String,
+    pub issue_type: String,
+    /// Identifies the workspace on incoming Jira webhooks
+    #[sea_orm(unique)]
+    pub webhook_secret: String,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
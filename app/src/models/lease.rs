@@ -0,0 +1,18 @@
+use sea_orm::entity::prelude::*;
+
+/// Exclusive right of one app instance to run something, e.g. a workspace's bot.
+/// Holders renew it well before `expires_at`; an expired lease may be taken by anyone.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "leases")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub name: String,
+    /// Instance id of the holder
+    pub holder: String,
+    pub expires_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,16 +1,74 @@
 use sea_orm::entity::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+/// `external_id` prefix of messages created from inbound email
+pub const EMAIL_ID_PREFIX: &str = "email:";
+
+/// `external_id` prefix of messages written for tasks made from a template
+pub const TEMPLATE_ID_PREFIX: &str = "template:";
+
+/// `external_id` prefix of messages written for imported tasks
+pub const IMPORT_ID_PREFIX: &str = "import:";
+
+/// Stands in for the channel of messages whose channel is hidden
+pub const HIDDEN_CHANNEL: &str = "private";
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[schema(as = Message)]
 #[sea_orm(table_name = "messages")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: String,
     pub content: String,
+    /// `content` is stored encrypted, see `Config::encrypt_messages`. Messages
+    /// read through `MessagesRepo` come decrypted
+    #[serde(skip)]
+    pub content_encrypted: bool,
     pub external_id: String,
     pub person_id: String,
     pub channel: String,
     pub timestamp: String,
+    /// Task this message's own task was merged into as a duplicate; no task is created
+    /// from the message again
+    pub merged_into_task_id: Option<String>,
+    /// Posted in a private channel or DM of a workspace that anonymizes those, see
+    /// `PrivateChannelPolicy`; API responses leave the channel out
+    #[serde(default)]
+    pub channel_hidden: bool,
+    /// Slack team the message was posted in, which tells the workspaces of an
+    /// Enterprise Grid org apart
+    #[serde(default)]
+    pub slack_team_id: Option<String>,
+    pub created_at: DateTime,
+}
+
+impl Model {
+    /// Messages also arrive by email, see `integrations::email`, and are written for
+    /// tasks made from a template, see `services::task_templates`, or imported, see
+    /// `services::task_import`
+    pub fn is_from_slack(&self) -> bool {
+        !self.external_id.starts_with(EMAIL_ID_PREFIX)
+            && !self.external_id.starts_with(TEMPLATE_ID_PREFIX)
+            && !self.external_id.starts_with(IMPORT_ID_PREFIX)
+    }
+
+    /// `external_id` as API responses show it, without the channel when it is hidden
+    pub fn visible_external_id(&self) -> String {
+        if !self.channel_hidden {
+            return self.external_id.clone();
+        }
+        format!("slack:{}:{}", HIDDEN_CHANNEL, self.timestamp)
+    }
+
+    /// Link to the message in Slack: https://slack.com/archives/{channel}/p{timestamp_without_dot}
+    pub fn slack_link(&self) -> String {
+        format!(
+            "https://slack.com/archives/{}/p{}",
+            self.channel,
+            self.timestamp.replace('.', "")
+        )
+    }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,66 @@
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+
+use super::message::Model as Message;
+
+/// Message moved out of `messages` once no task pointed to it any more, see
+/// `services::archive`. The text stays as stored, encrypted or not.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "messages_archive")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub content: String,
+    pub content_encrypted: bool,
+    pub external_id: String,
+    pub person_id: String,
+    pub channel: String,
+    pub timestamp: String,
+    pub merged_into_task_id: Option<String>,
+    pub channel_hidden: bool,
+    pub slack_team_id: Option<String>,
+    pub created_at: DateTime,
+    pub archived_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Archived copy of a message as stored, archived now
+    pub fn of(message: Message) -> Self {
+        Self {
+            id: Set(message.id),
+            content: Set(message.content),
+            content_encrypted: Set(message.content_encrypted),
+            external_id: Set(message.external_id),
+            person_id: Set(message.person_id),
+            channel: Set(message.channel),
+            timestamp: Set(message.timestamp),
+            merged_into_task_id: Set(message.merged_into_task_id),
+            channel_hidden: Set(message.channel_hidden),
+            slack_team_id: Set(message.slack_team_id),
+            created_at: Set(message.created_at),
+            archived_at: Set(chrono::Utc::now().naive_utc()),
+        }
+    }
+}
+
+impl From<Model> for Message {
+    fn from(archived: Model) -> Self {
+        Self {
+            id: archived.id,
+            content: archived.content,
+            content_encrypted: archived.content_encrypted,
+            external_id: archived.external_id,
+            person_id: archived.person_id,
+            channel: archived.channel,
+            timestamp: archived.timestamp,
+            merged_into_task_id: archived.merged_into_task_id,
+            channel_hidden: archived.channel_hidden,
+            slack_team_id: archived.slack_team_id,
+            created_at: archived.created_at,
+        }
+    }
+}
@@ -1,7 +1,40 @@
+pub mod audit_log;
+pub mod availability;
+pub mod board_view;
+pub mod bot_event;
+pub mod bot_incident;
+pub mod calendar_feed;
 pub mod change;
+pub mod change_archive;
+pub mod channel_rule;
+pub mod github_link;
+pub mod github_repository;
+pub mod hook_subscription;
+pub mod idempotency_key;
+pub mod invitation;
+pub mod jira_connection;
+pub mod job;
+pub mod lease;
 pub mod message;
+pub mod message_archive;
+pub mod notification_setting;
+pub mod notion_connection;
 pub mod person;
+pub mod project;
+pub mod reminder;
+pub mod retention_policy;
+pub mod scheduled_job;
+pub mod session;
+pub mod sla_breach;
+pub mod sla_policy;
+pub mod slack_status_setting;
+pub mod standup_entry;
 pub mod task;
+pub mod task_sequence;
+pub mod task_template;
+pub mod task_tombstone;
+pub mod workspace;
 pub mod workspace_admin;
+pub mod workspace_credential;
 pub mod workspace_link;
 pub mod workspace_settings;
@@ -0,0 +1,136 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::i18n::Locale;
+
+/// Where a person's notifications are delivered
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Hash,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationChannel {
+    /// Direct message from the workspace bot
+    #[default]
+    #[sea_orm(string_value = "slack_dm")]
+    SlackDm,
+    #[sea_orm(string_value = "email")]
+    Email,
+    /// Slack DM and email
+    #[sea_orm(string_value = "both")]
+    Both,
+    /// JSON POST to the person's webhook URL
+    #[sea_orm(string_value = "webhook")]
+    Webhook,
+    /// Post in the Discord channel following the workspace; for workspace routes
+    #[sea_orm(string_value = "discord")]
+    Discord,
+    /// Post in the Microsoft Teams channel following the workspace; for workspace routes
+    #[sea_orm(string_value = "teams")]
+    Teams,
+}
+
+impl NotificationChannel {
+    /// Channels that post where the whole team reads rather than to one person
+    pub fn is_workspace_channel(&self) -> bool {
+        matches!(self, Self::Discord | Self::Teams)
+    }
+}
+
+/// Kinds of notification a person can opt in to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    Assignment,
+    DailyDigest,
+    WeeklySummary,
+    /// A task went over a soft WIP limit; always sent
+    WipLimit,
+    /// A task stayed in a status longer than an SLA policy allows; always sent
+    SlaBreach,
+    /// A task of the workspace was created or moved; only sent to workspace channels
+    TaskActivity,
+    /// An API route went over its latency budget; sent to the super admin, always
+    LatencyAlert,
+}
+
+impl NotificationKind {
+    pub fn is_enabled(&self, settings: &Model) -> bool {
+        match self {
+            Self::Assignment => settings.dm_on_assignment,
+            Self::DailyDigest => settings.daily_digest,
+            Self::WeeklySummary => settings.weekly_summary,
+            Self::WipLimit | Self::SlaBreach | Self::TaskActivity | Self::LatencyAlert => true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = NotificationSettings)]
+#[sea_orm(table_name = "notification_settings")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[serde(skip_serializing)]
+    pub id: String,
+    #[sea_orm(unique)]
+    #[serde(skip_serializing)]
+    pub person_id: String,
+    /// Message the person when a task is assigned to them
+    pub dm_on_assignment: bool,
+    pub daily_digest: bool,
+    pub weekly_summary: bool,
+    pub channel: NotificationChannel,
+    /// Required when `channel` is `webhook`
+    pub webhook_url: Option<String>,
+    /// Language of the person's bot messages and digests; None follows the workspace
+    pub locale: Option<Locale>,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::person::Entity",
+        from = "Column::PersonId",
+        to = "super::person::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Person,
+}
+
+impl Related<super::person::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Person.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Settings for a person who never saved any: everything off, Slack DM channel
+    pub fn default_for(person_id: &str) -> Self {
+        Self {
+            id: String::new(),
+            person_id: person_id.to_string(),
+            dm_on_assignment: false,
+            daily_digest: false,
+            weekly_summary: false,
+            channel: NotificationChannel::default(),
+            webhook_url: None,
+            locale: None,
+            updated_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use sea_orm::entity::prelude::*;
+
+/// Notion database a workspace's completed tasks are appended to
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "notion_connections")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    #[sea_orm(unique)]
+    pub workspace_id: String,
+    /// Internal integration token, encrypted with the master key
+    pub token: String,
+    pub database_id: String,
+    /// Name of the database's title property, which holds the task's title
+    pub title_property: String,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
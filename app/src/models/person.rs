@@ -4,9 +4,11 @@ use axum::{
     http::{request::Parts, StatusCode},
 };
 use sea_orm::entity::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, DeriveEntityModel, PartialEq, Serialize)]
+#[derive(Debug, Clone, DeriveEntityModel, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = Person)]
 #[sea_orm(table_name = "persons")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -16,6 +18,9 @@ pub struct Model {
     pub is_me: bool,
     // slack member id
     pub external_id: String,
+    /// IANA timezone name, used for digest scheduling and rendering dates
+    pub timezone: String,
+    pub avatar_url: Option<String>,
 }
 
 #[async_trait]
@@ -55,3 +60,10 @@ impl Related<super::task::Entity> for Entity {
 }
 
 impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// The person's timezone, falling back to UTC for unknown names
+    pub fn tz(&self) -> chrono_tz::Tz {
+        crate::utils::time::parse_timezone(&self.timezone).unwrap_or(chrono_tz::UTC)
+    }
+}
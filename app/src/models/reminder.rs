@@ -0,0 +1,28 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// A DM about a task a person asked for at a later time. Sent once `remind_at` has
+/// passed; snoozing moves `remind_at` and sends it again.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = Reminder)]
+#[sea_orm(table_name = "reminders")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub task_id: String,
+    /// Who is reminded
+    pub person_id: String,
+    /// UTC
+    pub remind_at: DateTime,
+    /// UTC; None until the DM went out for the current `remind_at`
+    pub sent_at: Option<DateTime>,
+    /// UTC; set by the DM's Done button, after which nothing is sent
+    pub done_at: Option<DateTime>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
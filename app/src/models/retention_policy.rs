@@ -0,0 +1,84 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// What happens to expired messages
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Hash,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionMode {
+    /// Delete the messages along with their tasks and status history
+    #[default]
+    #[sea_orm(string_value = "delete")]
+    Delete,
+    /// Replace message text but keep tasks, so board history and summaries stay intact
+    #[sea_orm(string_value = "anonymize")]
+    Anonymize,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = RetentionPolicy)]
+#[sea_orm(table_name = "retention_policies")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[serde(skip_serializing)]
+    pub id: String,
+    #[sea_orm(unique)]
+    #[serde(skip_serializing)]
+    pub workspace_id: String,
+    /// Days to keep completed tasks, `null` keeps them forever
+    pub completed_task_days: Option<i32>,
+    /// Days to keep task messages of any status, `null` keeps them forever
+    pub message_days: Option<i32>,
+    /// Days to keep bot incidents, `null` keeps them forever
+    pub incident_days: Option<i32>,
+    pub mode: RetentionMode,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::workspace::Entity",
+        from = "Column::WorkspaceId",
+        to = "super::workspace::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Workspace,
+}
+
+impl Related<super::workspace::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Workspace.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Policy of a workspace that never saved one: keep everything
+    pub fn default_for(workspace_id: &str) -> Self {
+        Self {
+            id: String::new(),
+            workspace_id: workspace_id.to_string(),
+            completed_task_days: None,
+            message_days: None,
+            incident_days: None,
+            mode: RetentionMode::default(),
+            updated_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
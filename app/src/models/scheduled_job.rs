@@ -0,0 +1,85 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// What a scheduled job does when it fires
+#[derive(
+    Clone, Debug, Eq, PartialEq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduledJobKind {
+    /// Open tasks for everyone who enabled the daily digest
+    #[sea_orm(string_value = "daily_digest")]
+    DailyDigest,
+    /// Last week's task counts for everyone who enabled the weekly summary
+    #[sea_orm(string_value = "weekly_summary")]
+    WeeklySummary,
+    /// Apply every workspace's data retention policy and trim the bot event log
+    #[sea_orm(string_value = "retention_purge")]
+    RetentionPurge,
+    /// Append completed tasks to every connected Notion database
+    #[sea_orm(string_value = "notion_export")]
+    NotionExport,
+    /// Unlink members whose Slack accounts were deactivated
+    #[sea_orm(string_value = "deactivated_members")]
+    DeactivatedMembers,
+    /// DM reminders whose time has come
+    #[sea_orm(string_value = "reminders")]
+    Reminders,
+    /// Move old status changes and merged messages to the archive tables
+    #[sea_orm(string_value = "archive_history")]
+    ArchiveHistory,
+    /// DM the standup questions to the members of the job's workspace
+    #[sea_orm(string_value = "standup")]
+    Standup,
+    /// Post the standup answers of the job's workspace to its channel
+    #[sea_orm(string_value = "standup_summary")]
+    StandupSummary,
+    /// Record tasks over an SLA policy and alert their assignees
+    #[sea_orm(string_value = "sla_breaches")]
+    SlaBreaches,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = ScheduledJob)]
+#[sea_orm(table_name = "scheduled_jobs")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    #[sea_orm(unique)]
+    pub name: String,
+    pub kind: ScheduledJobKind,
+    /// Cron expression with a seconds field, e.g. `0 0 9 * * Mon-Fri`
+    pub cron: String,
+    /// IANA timezone the cron expression is evaluated in
+    pub timezone: String,
+    /// Set for jobs that only concern one workspace
+    pub workspace_id: Option<String>,
+    pub enabled: bool,
+    /// UTC; None until the scheduler computed the first run
+    pub next_run_at: Option<DateTime>,
+    pub last_run_at: Option<DateTime>,
+    /// Error of the last run, cleared on success
+    pub last_error: Option<String>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::workspace::Entity",
+        from = "Column::WorkspaceId",
+        to = "super::workspace::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Workspace,
+}
+
+impl Related<super::workspace::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Workspace.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,39 @@
+use sea_orm::entity::prelude::*;
+
+/// A sign-in on one device. Its id is the `sid` of the token handed out at login,
+/// so deleting the session signs that device out.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "sessions")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub person_id: String,
+    /// User-Agent of the browser that signed in
+    pub user_agent: Option<String>,
+    /// Address the sign-in came from, as forwarded by the proxy
+    pub ip_address: Option<String>,
+    pub created_at: DateTime,
+    /// Updated at most every few minutes
+    pub last_used_at: DateTime,
+    /// When the session's token expires
+    pub expires_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::person::Entity",
+        from = "Column::PersonId",
+        to = "super::person::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Person,
+}
+
+impl Related<super::person::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Person.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
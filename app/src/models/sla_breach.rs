@@ -0,0 +1,51 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+use super::task::TaskStatus;
+
+/// A task that stayed in a status longer than an SLA policy allows. Recorded once
+/// per stay, so the assignee is alerted once; moving the task ends the breach.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "sla_breaches")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub policy_id: String,
+    pub task_id: String,
+    pub status: TaskStatus,
+    /// When the task entered `status`, see `task_service::status_since`
+    pub entered_at: DateTime,
+    pub breached_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::sla_policy::Entity",
+        from = "Column::PolicyId",
+        to = "super::sla_policy::Column::Id",
+        on_delete = "Cascade"
+    )]
+    SlaPolicy,
+    #[sea_orm(
+        belongs_to = "super::task::Entity",
+        from = "Column::TaskId",
+        to = "super::task::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Task,
+}
+
+impl Related<super::sla_policy::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SlaPolicy.def()
+    }
+}
+
+impl Related<super::task::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,43 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::task::TaskStatus;
+
+/// Longest a task of the workspace may stay in a status, e.g. blocked tasks must be
+/// unblocked within 8 business hours. Counted in the workspace's business hours
+/// when it has them, otherwise around the clock.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = SlaPolicy)]
+#[sea_orm(table_name = "sla_policies")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub workspace_id: String,
+    /// Unique within the workspace
+    pub name: String,
+    /// In progress or blocked
+    pub status: TaskStatus,
+    /// Hours a task may stay in `status` before the policy is breached
+    pub max_hours: i32,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::workspace::Entity",
+        from = "Column::WorkspaceId",
+        to = "super::workspace::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Workspace,
+}
+
+impl Related<super::workspace::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Workspace.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
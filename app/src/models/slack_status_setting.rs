@@ -0,0 +1,20 @@
+use sea_orm::entity::prelude::*;
+
+/// A member's opt-in to having their Slack status show the task they are working on
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "slack_status_settings")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub person_id: String,
+    pub workspace_id: String,
+    /// The member's own user token, encrypted with the master key
+    pub user_token: String,
+    pub enabled: bool,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
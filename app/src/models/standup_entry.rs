@@ -0,0 +1,27 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// A member's answers to a workspace's standup questions for one day, in the
+/// workspace's standup timezone
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = StandupEntry)]
+#[sea_orm(table_name = "standup_entries")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub workspace_id: String,
+    pub person_id: String,
+    pub day: Date,
+    pub yesterday: String,
+    pub today: String,
+    /// None when nothing is in the way
+    pub blockers: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,7 +1,10 @@
 use sea_orm::entity::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, Eq, PartialEq, Hash, EnumIter, DeriveActiveEnum, Serialize)]
+#[derive(
+    Clone, Debug, Eq, PartialEq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
 pub enum TaskStatus {
     #[sea_orm(string_value = "Blank")]
@@ -14,7 +17,24 @@ pub enum TaskStatus {
     Completed,
 }
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[derive(
+    Clone, Debug, Eq, PartialEq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum TaskPriority {
+    #[sea_orm(string_value = "low")]
+    Low,
+    #[sea_orm(string_value = "medium")]
+    Medium,
+    #[sea_orm(string_value = "high")]
+    High,
+    #[sea_orm(string_value = "urgent")]
+    Urgent,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[schema(as = Task)]
 #[sea_orm(table_name = "tasks")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -24,6 +44,47 @@ pub struct Model {
     pub assigned_by: Option<String>,
     pub created_at: DateTime,
     pub message_id: String,
+    /// Workspace the task was created in (None for tasks predating workspace tracking)
+    pub workspace_id: Option<String>,
+    /// Key of the Jira issue created from the task, e.g. "OPS-42"
+    pub jira_issue_key: Option<String>,
+    /// Id of the Linear issue pushed for the task
+    pub linear_issue_id: Option<String>,
+    /// When the task should be done, in UTC
+    pub due_at: Option<DateTime>,
+    /// When the task last became completed; None for tasks completed before this was
+    /// tracked
+    pub completed_at: Option<DateTime>,
+    /// Notion page the completed task was exported to
+    pub notion_page_id: Option<String>,
+    /// Set from the channel rule of the channel the task was created in
+    pub priority: Option<TaskPriority>,
+    /// JSON array of label names, see `labels()`
+    #[schema(value_type = Option<Vec<String>>)]
+    pub labels: Option<Json>,
+    /// Project the task is grouped under, see `models::project`
+    pub project_id: Option<String>,
+    /// When the assignee left Slack and no one took the task over, see
+    /// `services::deprovision`
+    pub orphaned_at: Option<DateTime>,
+    /// Human-readable id such as `ACME-42`, see `services::task_keys`. None for
+    /// tasks without a workspace.
+    #[sea_orm(unique)]
+    pub key: Option<String>,
+    /// Bumped on every write; `PATCH /api/tasks/:id` only applies when the client
+    /// sends back the version it saw
+    pub version: i32,
+    /// Last write to the task, see `handlers::tasks::get_board_changes`
+    pub updated_at: DateTime,
+}
+
+impl Model {
+    pub fn labels(&self) -> Vec<String> {
+        self.labels
+            .clone()
+            .and_then(|labels| serde_json::from_value(labels).ok())
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -43,6 +104,13 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Message,
+    #[sea_orm(
+        belongs_to = "super::workspace::Entity",
+        from = "Column::WorkspaceId",
+        to = "super::workspace::Column::Id",
+        on_delete = "SetNull"
+    )]
+    Workspace,
 }
 
 impl Related<super::person::Entity> for Entity {
@@ -0,0 +1,19 @@
+use sea_orm::entity::prelude::*;
+
+/// Counter behind a workspace's task keys, see `services::task_keys`
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "task_sequences")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub workspace_id: String,
+    /// Fixed when the workspace's first key is handed out, so renaming the
+    /// workspace does not change its keys
+    pub prefix: String,
+    /// Number of the latest key
+    pub last_number: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
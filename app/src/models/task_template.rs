@@ -0,0 +1,54 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::task::TaskPriority;
+
+/// Blueprint for tasks that don't start from a Slack message, e.g. a recurring
+/// release checklist
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = TaskTemplate)]
+#[sea_orm(table_name = "task_templates")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub workspace_id: String,
+    /// Unique within the workspace
+    pub name: String,
+    /// Text of new tasks, see `render_title()`
+    pub title_pattern: String,
+    /// JSON array of label names given to new tasks, see `labels()`
+    #[schema(value_type = Vec<String>)]
+    pub labels: Json,
+    pub priority: Option<TaskPriority>,
+    /// JSON array of checklist items appended to new tasks, see `checklist()`
+    #[schema(value_type = Vec<String>)]
+    pub checklist: Json,
+    pub created_at: DateTime,
+}
+
+impl Model {
+    pub fn labels(&self) -> Vec<String> {
+        serde_json::from_value(self.labels.clone()).unwrap_or_default()
+    }
+
+    pub fn checklist(&self) -> Vec<String> {
+        serde_json::from_value(self.checklist.clone()).unwrap_or_default()
+    }
+
+    /// The title pattern with `{date}` (YYYY-MM-DD), `{assignee}` and `{details}`
+    /// filled in
+    pub fn render_title(&self, date: chrono::NaiveDate, assignee: &str, details: &str) -> String {
+        self.title_pattern
+            .replace("{date}", &date.format("%Y-%m-%d").to_string())
+            .replace("{assignee}", assignee)
+            .replace("{details}", details)
+            .trim()
+            .to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
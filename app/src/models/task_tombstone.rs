@@ -0,0 +1,33 @@
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+
+use super::task::Model as Task;
+
+/// What is left of a deleted task, so boards polling for changes can drop it
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "task_tombstones")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub task_id: String,
+    pub workspace_id: Option<String>,
+    pub assigned_to: String,
+    pub assigned_by: Option<String>,
+    pub deleted_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Tombstone of a task deleted now
+    pub fn of(task: &Task) -> Self {
+        Self {
+            task_id: Set(task.id.clone()),
+            workspace_id: Set(task.workspace_id.clone()),
+            assigned_to: Set(task.assigned_to.clone()),
+            assigned_by: Set(task.assigned_by.clone()),
+            deleted_at: Set(chrono::Utc::now().naive_utc()),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(
+    Clone, Debug, Eq, PartialEq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceStatus {
+    #[sea_orm(string_value = "active")]
+    Active,
+    /// Offboarded: bot stopped and credentials removed, history kept
+    #[sea_orm(string_value = "archived")]
+    Archived,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, ToSchema)]
+#[schema(as = Workspace)]
+#[sea_orm(table_name = "workspaces")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    /// Display name, unique; may be renamed without orphaning links or settings
+    #[sea_orm(unique)]
+    pub name: String,
+    /// Slack team ID resolved via `auth.test` when the workspace is set up
+    pub slack_team_id: Option<String>,
+    /// Email of the admin who set up the workspace
+    pub created_by: Option<String>,
+    pub status: WorkspaceStatus,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_one = "super::workspace_credential::Entity")]
+    Credential,
+    #[sea_orm(has_many = "super::workspace_link::Entity")]
+    Link,
+}
+
+impl Related<super::workspace_credential::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Credential.def()
+    }
+}
+
+impl Related<super::workspace_link::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Link.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
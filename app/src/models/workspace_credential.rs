@@ -0,0 +1,49 @@
+use sea_orm::entity::prelude::*;
+
+use crate::config::workspaces::WorkspaceConfig;
+
+/// Slack tokens for a workspace, encrypted at rest. Never serialized.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "workspace_credentials")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    #[sea_orm(unique)]
+    pub workspace_id: String,
+    pub app_token: String,
+    pub bot_token: String,
+    /// Enterprise Grid org of org-wide tokens, see `WorkspaceConfig::enterprise_id`
+    pub enterprise_id: Option<String>,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::workspace::Entity",
+        from = "Column::WorkspaceId",
+        to = "super::workspace::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Workspace,
+}
+
+impl Related<super::workspace::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Workspace.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Decrypt the stored tokens
+    pub fn decrypt(&self, encryption_key: &str) -> anyhow::Result<WorkspaceConfig> {
+        WorkspaceConfig {
+            app_token: self.app_token.clone(),
+            bot_token: self.bot_token.clone(),
+            enterprise_id: self.enterprise_id.clone(),
+        }
+        .decrypt(encryption_key)
+    }
+}
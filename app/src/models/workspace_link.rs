@@ -1,16 +1,49 @@
 use sea_orm::entity::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, DeriveEntityModel, PartialEq, Serialize)]
+/// What a linked person may do in a workspace
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Hash,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceRole {
+    /// Owns tasks through their reactions and moves them on the board
+    #[default]
+    #[sea_orm(string_value = "member")]
+    Member,
+    /// Sees the workspace's boards and analytics without changing tasks
+    #[sea_orm(string_value = "viewer")]
+    Viewer,
+}
+
+#[derive(Debug, Clone, DeriveEntityModel, PartialEq, Serialize, ToSchema)]
+#[schema(as = WorkspaceLink)]
 #[sea_orm(table_name = "workspace_links")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: String,
     pub person_id: String,
-    pub workspace_name: String,
+    pub workspace_id: String,
     pub slack_member_id: Option<String>,
+    /// Slack team of the workspace when linked. Members of an Enterprise Grid org
+    /// keep one member id across its workspaces, the team tells the links apart.
+    pub slack_team_id: Option<String>,
     pub is_linked: bool,
     pub is_active: bool,
+    pub role: WorkspaceRole,
     pub created_at: DateTime,
     pub updated_at: Option<DateTime>,
 }
@@ -24,6 +57,13 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Person,
+    #[sea_orm(
+        belongs_to = "super::workspace::Entity",
+        from = "Column::WorkspaceId",
+        to = "super::workspace::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Workspace,
 }
 
 impl Related<super::person::Entity> for Entity {
@@ -32,4 +72,10 @@ impl Related<super::person::Entity> for Entity {
     }
 }
 
+impl Related<super::workspace::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Workspace.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
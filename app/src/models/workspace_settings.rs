@@ -1,10 +1,21 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::i18n::Locale;
+
+use super::{
+    notification_setting::{NotificationChannel, NotificationKind},
+    task::TaskStatus,
+};
 
 /// Represents emoji to status mappings
 /// Key: emoji name (e.g., "eyes", "white_check_mark")
 /// Value: status string (e.g., "InProgress", "Completed")
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default, ToSchema)]
 pub struct EmojiMappings {
     #[serde(default)]
     pub in_progress: Vec<String>,
@@ -29,6 +40,323 @@ impl EmojiMappings {
             ],
         }
     }
+
+    /// Emoji mapped to a status
+    pub fn emojis(&self, status: &TaskStatus) -> &[String] {
+        match status {
+            TaskStatus::InProgress => &self.in_progress,
+            TaskStatus::Blocked => &self.blocked,
+            TaskStatus::Completed => &self.completed,
+            TaskStatus::Blank => &[],
+        }
+    }
+
+    /// Emoji mapped to more than one status, with those statuses
+    pub fn overlaps(&self) -> Vec<(&str, Vec<TaskStatus>)> {
+        let mut statuses: BTreeMap<&str, Vec<TaskStatus>> = BTreeMap::new();
+        for status in [
+            TaskStatus::InProgress,
+            TaskStatus::Blocked,
+            TaskStatus::Completed,
+        ] {
+            for emoji in self.emojis(&status) {
+                let mapped = statuses.entry(emoji.as_str()).or_default();
+                if !mapped.contains(&status) {
+                    mapped.push(status.clone());
+                }
+            }
+        }
+        statuses
+            .into_iter()
+            .filter(|(_, statuses)| statuses.len() > 1)
+            .collect()
+    }
+}
+
+/// Linear connection of a workspace, stored in `workspace_settings.linear`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LinearSettings {
+    /// Team new tasks are pushed to as issues
+    pub team_id: String,
+    /// Encrypted with the master key, like workspace tokens
+    pub api_key: String,
+    /// Signing secret of the team's Linear webhook, encrypted
+    pub webhook_secret: String,
+}
+
+/// Discord channel following the workspace, stored in `workspace_settings.discord`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiscordSettings {
+    /// Incoming webhook of the channel, encrypted with the master key
+    pub webhook_url: String,
+    /// Name Discord shows for the webhook
+    pub webhook_name: String,
+}
+
+/// Daily standup of a workspace, stored in `workspace_settings.standup`. The bot DMs
+/// the questions to linked members at `ask_at` and posts their answers to `channel`
+/// at `summary_at`, both local times of `timezone` on `days`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct StandupSettings {
+    /// Slack channel id the summary is posted to; the bot must be a member
+    pub channel: String,
+    /// `HH:MM`, e.g. `09:30`
+    pub ask_at: String,
+    /// `HH:MM`, later in the day than `ask_at`
+    pub summary_at: String,
+    /// IANA timezone, e.g. `Europe/Berlin`
+    pub timezone: String,
+    /// Days of the week in cron syntax, e.g. `Mon-Fri` or `Mon,Wed,Fri`
+    #[serde(default = "StandupSettings::default_days")]
+    pub days: String,
+}
+
+impl StandupSettings {
+    pub fn default_days() -> String {
+        "Mon-Fri".to_string()
+    }
+}
+
+/// Working hours of a workspace, stored in `workspace_settings.business_hours`.
+/// Ages, SLAs and escalation timers count only the time from `start` to `end`,
+/// local times of `timezone`, on `days` that aren't `holidays`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BusinessHours {
+    /// `HH:MM`, e.g. `09:00`
+    pub start: String,
+    /// `HH:MM`, later in the day than `start`
+    pub end: String,
+    /// IANA timezone, e.g. `Europe/Berlin`
+    pub timezone: String,
+    /// Days of the week in cron syntax, e.g. `Mon-Fri` or `Sun-Thu`
+    #[serde(default = "StandupSettings::default_days")]
+    pub days: String,
+    /// Dates off on top of the days of the week not worked
+    #[serde(default)]
+    pub holidays: Vec<NaiveDate>,
+}
+
+/// Teams channel following the workspace, stored in `workspace_settings.teams`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TeamsSettings {
+    /// Incoming webhook of the channel, encrypted with the master key
+    pub webhook_url: String,
+    /// Host of the webhook, to tell admins which one is set up
+    pub webhook_host: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerMatch {
+    /// The message starts with the pattern
+    Prefix,
+    /// The pattern appears anywhere in the message
+    Contains,
+    /// The message mentions the bot first, followed by the pattern, e.g. "@slacker task"
+    Mention,
+}
+
+/// Message text that turns a message into a task without a reaction
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct KeywordTrigger {
+    pub pattern: String,
+    #[serde(rename = "match")]
+    pub match_type: TriggerMatch,
+}
+
+impl KeywordTrigger {
+    /// Whether a message fires the trigger, ignoring case. `mentioned` is set for
+    /// `app_mention` events, whose text starts with the bot's `<@U...>` mention;
+    /// only mention triggers apply to those.
+    pub fn matches(&self, text: &str, mentioned: bool) -> bool {
+        let pattern = self.pattern.trim().to_lowercase();
+        if pattern.is_empty() {
+            return false;
+        }
+        let text = text.trim().to_lowercase();
+
+        match (&self.match_type, mentioned) {
+            (TriggerMatch::Prefix, false) => text.starts_with(&pattern),
+            (TriggerMatch::Contains, false) => text.contains(&pattern),
+            (TriggerMatch::Mention, true) => text
+                .strip_prefix("<@")
+                .and_then(|rest| rest.split_once('>'))
+                .is_some_and(|(_, rest)| rest.trim_start().starts_with(&pattern)),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WipScope {
+    /// Counts each assignee's tasks in the column
+    Person,
+    /// Counts every task in the column
+    Column,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WipMode {
+    /// The move happens and the assignee gets a DM
+    Soft,
+    /// The move is refused
+    Hard,
+}
+
+/// Most tasks a board column may hold
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WipLimit {
+    pub status: TaskStatus,
+    pub scope: WipScope,
+    pub max_tasks: u64,
+    pub mode: WipMode,
+}
+
+/// Channels a kind of notification about the workspace goes out on, in place of the
+/// channel each member chose. Members still only get the kinds they opted in to.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct NotificationRoute {
+    pub kind: NotificationKind,
+    pub channels: Vec<NotificationChannel>,
+}
+
+/// Text of a kind of notification about the workspace in place of the built-in
+/// one. `{{variable}}`s are filled in when it is sent; each kind allows its own.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct NotificationTemplate {
+    pub kind: NotificationKind,
+    /// None keeps the built-in subject
+    #[serde(default)]
+    pub subject: Option<String>,
+    /// None keeps the built-in body
+    #[serde(default)]
+    pub body: Option<String>,
+}
+
+/// How message text is stored, for teams that can't keep raw Slack messages.
+/// Applied when a message is saved and again when it is exported. Ordered from the
+/// least to the most strict
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum MessageRedaction {
+    /// The text as written
+    #[default]
+    #[sea_orm(string_value = "none")]
+    None,
+    /// Email addresses and phone numbers replaced with placeholders
+    #[sea_orm(string_value = "scrub")]
+    Scrub,
+    /// Only a hash of the text, enough to spot duplicates
+    #[sea_orm(string_value = "hash")]
+    Hash,
+}
+
+/// How a message's task status is picked when its reactions map to several statuses
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum StatusResolution {
+    /// The strongest status wins: completed, then blocked, then in progress
+    #[default]
+    #[sea_orm(string_value = "completed_wins")]
+    CompletedWins,
+    /// The status of the most recently added reaction
+    #[sea_orm(string_value = "latest_reaction")]
+    LatestReaction,
+    /// The status most reactors voted for, each reactor counted once
+    #[sea_orm(string_value = "majority")]
+    Majority,
+}
+
+/// Whose reactions move a task between statuses
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum ReactionPolicy {
+    /// Anyone in the channel
+    #[default]
+    #[sea_orm(string_value = "anyone")]
+    Anyone,
+    /// Members who linked the workspace in Slacker
+    #[sea_orm(string_value = "linked_members")]
+    LinkedMembers,
+    /// Linked members who are workspace admins
+    #[sea_orm(string_value = "admins")]
+    Admins,
+    /// Only the task's assignee
+    #[sea_orm(string_value = "assignee")]
+    Assignee,
+}
+
+/// What happens to messages in private channels, group DMs and DMs that would
+/// become tasks
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum PrivateChannelPolicy {
+    /// They become tasks like any other message
+    #[default]
+    #[sea_orm(string_value = "allow")]
+    Allow,
+    /// They become tasks, but API responses leave out their channel
+    #[sea_orm(string_value = "anonymize")]
+    Anonymize,
+    /// They never become tasks, and tasks made from them earlier stop changing
+    #[sea_orm(string_value = "forbid")]
+    Forbid,
 }
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
@@ -36,14 +364,58 @@ impl EmojiMappings {
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: String,
-    pub workspace_name: String,
+    #[sea_orm(unique)]
+    pub workspace_id: String,
     pub emoji_mappings: Json,
+    /// `LinearSettings`, when the workspace is connected to Linear
+    pub linear: Option<Json>,
+    /// `DiscordSettings`, when a Discord channel follows the workspace
+    pub discord: Option<Json>,
+    /// `TeamsSettings`, when a Microsoft Teams channel follows the workspace
+    pub teams: Option<Json>,
+    /// Local part of the workspace's inbound email address, see `Config::inbound_email_domain`
+    #[sea_orm(unique)]
+    pub inbound_email_token: Option<String>,
+    /// `KeywordTrigger`s of the workspace, none when unset
+    pub keyword_triggers: Option<Json>,
+    /// `WipLimit`s of the workspace, none when unset
+    pub wip_limits: Option<Json>,
+    /// `NotificationRoute`s of the workspace, none when unset
+    pub notification_routes: Option<Json>,
+    /// `NotificationTemplate`s of the workspace, none when unset
+    pub notification_templates: Option<Json>,
+    /// `StandupSettings`, when the workspace runs a daily standup
+    pub standup: Option<Json>,
+    /// `BusinessHours`, when the workspace counts time in working hours only
+    pub business_hours: Option<Json>,
+    /// Ids of the public Slack channels the bot joins, none when unset
+    pub auto_join_channels: Option<Json>,
+    pub message_redaction: MessageRedaction,
+    pub status_resolution: StatusResolution,
+    pub reaction_policy: ReactionPolicy,
+    pub private_channel_policy: PrivateChannelPolicy,
+    /// Language of bot messages for members who did not pick their own
+    pub locale: Locale,
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::workspace::Entity",
+        from = "Column::WorkspaceId",
+        to = "super::workspace::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Workspace,
+}
+
+impl Related<super::workspace::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Workspace.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
 
@@ -52,4 +424,71 @@ impl Model {
         serde_json::from_value(self.emoji_mappings.clone())
             .unwrap_or_else(|_| EmojiMappings::default_mappings())
     }
+
+    pub fn get_linear_settings(&self) -> Option<LinearSettings> {
+        serde_json::from_value(self.linear.clone()?).ok()
+    }
+
+    pub fn get_discord_settings(&self) -> Option<DiscordSettings> {
+        serde_json::from_value(self.discord.clone()?).ok()
+    }
+
+    pub fn get_teams_settings(&self) -> Option<TeamsSettings> {
+        serde_json::from_value(self.teams.clone()?).ok()
+    }
+
+    pub fn get_standup_settings(&self) -> Option<StandupSettings> {
+        serde_json::from_value(self.standup.clone()?).ok()
+    }
+
+    pub fn get_business_hours(&self) -> Option<BusinessHours> {
+        serde_json::from_value(self.business_hours.clone()?).ok()
+    }
+
+    /// Workspace channels set up to receive notifications
+    pub fn connected_channels(&self) -> Vec<NotificationChannel> {
+        let mut channels = Vec::new();
+        if self.get_discord_settings().is_some() {
+            channels.push(NotificationChannel::Discord);
+        }
+        if self.get_teams_settings().is_some() {
+            channels.push(NotificationChannel::Teams);
+        }
+        channels
+    }
+
+    pub fn get_wip_limits(&self) -> Vec<WipLimit> {
+        self.wip_limits
+            .clone()
+            .and_then(|limits| serde_json::from_value(limits).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn get_notification_routes(&self) -> Vec<NotificationRoute> {
+        self.notification_routes
+            .clone()
+            .and_then(|routes| serde_json::from_value(routes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn get_notification_templates(&self) -> Vec<NotificationTemplate> {
+        self.notification_templates
+            .clone()
+            .and_then(|templates| serde_json::from_value(templates).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn get_auto_join_channels(&self) -> Vec<String> {
+        self.auto_join_channels
+            .clone()
+            .and_then(|channels| serde_json::from_value(channels).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn get_keyword_triggers(&self) -> Vec<KeywordTrigger> {
+        self.keyword_triggers
+            .clone()
+            .and_then(|triggers| serde_json::from_value(triggers).ok())
+            .unwrap_or_default()
+    }
 }
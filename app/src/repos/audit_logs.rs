@@ -0,0 +1,74 @@
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, DatabaseConnection, DbErr, EntityTrait};
+
+use crate::{
+    models::audit_log::{self, ActiveModel, Entity as AuditLogEntity, Model as AuditLog},
+    utils::{
+        crypto::generate_uuid,
+        pagination::{Cursor, Page, Pagination},
+    },
+};
+
+pub struct AuditLogsRepo {
+    db: DatabaseConnection,
+}
+
+impl AuditLogsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn record(
+        &self,
+        actor_id: Option<&str>,
+        action: &str,
+        target_id: Option<&str>,
+        details: Option<String>,
+    ) -> Result<AuditLog, DbErr> {
+        ActiveModel {
+            id: Set(generate_uuid()),
+            actor_id: Set(actor_id.map(str::to_string)),
+            action: Set(action.to_string()),
+            target_id: Set(target_id.map(str::to_string)),
+            details: Set(details),
+            impersonated_by: Set(None),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    /// Record something done by `admin_id` while impersonating `person_id`
+    pub async fn record_impersonated(
+        &self,
+        admin_id: &str,
+        person_id: &str,
+        action: &str,
+        details: Option<String>,
+    ) -> Result<AuditLog, DbErr> {
+        ActiveModel {
+            id: Set(generate_uuid()),
+            actor_id: Set(Some(person_id.to_string())),
+            action: Set(action.to_string()),
+            target_id: Set(None),
+            details: Set(details),
+            impersonated_by: Set(Some(admin_id.to_string())),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    /// Newest entries first
+    pub async fn get_page(&self, pagination: &Pagination) -> Result<Page<AuditLog>, DbErr> {
+        let logs = pagination
+            .apply(
+                AuditLogEntity::find(),
+                audit_log::Column::CreatedAt,
+                audit_log::Column::Id,
+            )
+            .all(&self.db)
+            .await?;
+
+        Ok(pagination.page(logs, |l| Cursor::new(l.created_at, &l.id)))
+    }
+}
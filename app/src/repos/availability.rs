@@ -0,0 +1,109 @@
+use chrono::NaiveDate;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder,
+};
+
+use crate::{
+    models::availability::{
+        self, ActiveModel, Entity as AvailabilityEntity, Model as Availability,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct AvailabilityRepo {
+    db: DatabaseConnection,
+}
+
+impl AvailabilityRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        person_id: &str,
+        starts_on: NaiveDate,
+        ends_on: NaiveDate,
+        note: Option<String>,
+    ) -> Result<Availability, DbErr> {
+        ActiveModel {
+            id: Set(generate_uuid()),
+            person_id: Set(person_id.to_string()),
+            starts_on: Set(starts_on),
+            ends_on: Set(ends_on),
+            note: Set(note),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    pub async fn get(
+        &self,
+        person_id: &str,
+        availability_id: &str,
+    ) -> Result<Option<Availability>, DbErr> {
+        AvailabilityEntity::find_by_id(availability_id)
+            .filter(availability::Column::PersonId.eq(person_id))
+            .one(&self.db)
+            .await
+    }
+
+    /// Periods of the person ending on `from` or later, earliest first
+    pub async fn get_by_person(
+        &self,
+        person_id: &str,
+        from: NaiveDate,
+    ) -> Result<Vec<Availability>, DbErr> {
+        AvailabilityEntity::find()
+            .filter(availability::Column::PersonId.eq(person_id))
+            .filter(availability::Column::EndsOn.gte(from))
+            .order_by_asc(availability::Column::StartsOn)
+            .all(&self.db)
+            .await
+    }
+
+    /// Periods of any of these people that overlap `from..=to`
+    pub async fn get_overlapping(
+        &self,
+        person_ids: Vec<String>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<Availability>, DbErr> {
+        if person_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        AvailabilityEntity::find()
+            .filter(availability::Column::PersonId.is_in(person_ids))
+            .filter(availability::Column::StartsOn.lte(to))
+            .filter(availability::Column::EndsOn.gte(from))
+            .all(&self.db)
+            .await
+    }
+
+    /// Every period of the person, e.g. for a data export
+    pub async fn get_all_by_person(&self, person_id: &str) -> Result<Vec<Availability>, DbErr> {
+        AvailabilityEntity::find()
+            .filter(availability::Column::PersonId.eq(person_id))
+            .order_by_asc(availability::Column::StartsOn)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn delete(&self, availability: Availability) -> Result<(), DbErr> {
+        AvailabilityEntity::delete_by_id(availability.id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_by_person(&self, person_id: &str) -> Result<u64, DbErr> {
+        let result = AvailabilityEntity::delete_many()
+            .filter(availability::Column::PersonId.eq(person_id))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,93 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder,
+};
+
+use crate::{
+    models::board_view::{
+        self, ActiveModel, BoardViewFilter, Entity as BoardViewEntity, Model as BoardView,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct BoardViewsRepo {
+    db: DatabaseConnection,
+}
+
+impl BoardViewsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        person_id: &str,
+        name: String,
+        filter: &BoardViewFilter,
+    ) -> Result<BoardView, DbErr> {
+        ActiveModel {
+            id: Set(generate_uuid()),
+            person_id: Set(person_id.to_string()),
+            name: Set(name),
+            filter: Set(serde_json::json!(filter)),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    /// A view of the person; None for unknown ids and views of other people
+    pub async fn get(&self, person_id: &str, view_id: &str) -> Result<Option<BoardView>, DbErr> {
+        BoardViewEntity::find_by_id(view_id)
+            .filter(board_view::Column::PersonId.eq(person_id))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn get_by_name(
+        &self,
+        person_id: &str,
+        name: &str,
+    ) -> Result<Option<BoardView>, DbErr> {
+        BoardViewEntity::find()
+            .filter(board_view::Column::PersonId.eq(person_id))
+            .filter(board_view::Column::Name.eq(name))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn get_by_person(&self, person_id: &str) -> Result<Vec<BoardView>, DbErr> {
+        BoardViewEntity::find()
+            .filter(board_view::Column::PersonId.eq(person_id))
+            .order_by_asc(board_view::Column::Name)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn update(
+        &self,
+        view: BoardView,
+        name: String,
+        filter: &BoardViewFilter,
+    ) -> Result<BoardView, DbErr> {
+        let mut view: ActiveModel = view.into();
+        view.name = Set(name);
+        view.filter = Set(serde_json::json!(filter));
+        view.update(&self.db).await
+    }
+
+    pub async fn delete(&self, view: BoardView) -> Result<(), DbErr> {
+        BoardViewEntity::delete_by_id(view.id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_by_person(&self, person_id: &str) -> Result<u64, DbErr> {
+        let result = BoardViewEntity::delete_many()
+            .filter(board_view::Column::PersonId.eq(person_id))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
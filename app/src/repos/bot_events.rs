@@ -0,0 +1,134 @@
+use sea_orm::{
+    prelude::DateTime, ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+
+use crate::{
+    models::bot_event::{
+        self, ActiveModel, BotEventOutcome, Entity as BotEventEntity, Model as BotEvent,
+    },
+    utils::{
+        crypto::generate_uuid,
+        pagination::{Cursor, Page, Pagination},
+    },
+};
+
+/// What a bot saw of an event, before it is stored
+#[derive(Debug, Clone, Default)]
+pub struct NewBotEvent {
+    pub envelope_type: String,
+    pub event_type: String,
+    pub subtype: Option<String>,
+    pub channel: Option<String>,
+    pub ts: Option<String>,
+    pub user_id: Option<String>,
+    pub reaction: Option<String>,
+}
+
+/// Narrows `get_page`; unset fields match every event
+#[derive(Debug, Clone, Default)]
+pub struct BotEventFilter {
+    pub workspace_id: Option<String>,
+    pub outcome: Option<BotEventOutcome>,
+    pub event_type: Option<String>,
+    pub channel: Option<String>,
+}
+
+pub struct BotEventsRepo {
+    db: DatabaseConnection,
+}
+
+impl BotEventsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        workspace_id: &str,
+        event: NewBotEvent,
+        outcome: BotEventOutcome,
+        error: Option<String>,
+    ) -> Result<BotEvent, DbErr> {
+        ActiveModel {
+            id: Set(generate_uuid()),
+            workspace_id: Set(workspace_id.to_string()),
+            envelope_type: Set(event.envelope_type),
+            event_type: Set(event.event_type),
+            subtype: Set(event.subtype),
+            channel: Set(event.channel),
+            ts: Set(event.ts),
+            user_id: Set(event.user_id),
+            reaction: Set(event.reaction),
+            outcome: Set(outcome),
+            error: Set(error),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    pub async fn get(&self, id: &str) -> Result<BotEvent, DbErr> {
+        BotEventEntity::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .ok_or(DbErr::RecordNotFound("Bot event not found".to_string()))
+    }
+
+    /// Newest events first
+    pub async fn get_page(
+        &self,
+        filter: &BotEventFilter,
+        pagination: &Pagination,
+    ) -> Result<Page<BotEvent>, DbErr> {
+        let mut query = BotEventEntity::find();
+        if let Some(workspace_id) = &filter.workspace_id {
+            query = query.filter(bot_event::Column::WorkspaceId.eq(workspace_id));
+        }
+        if let Some(outcome) = &filter.outcome {
+            query = query.filter(bot_event::Column::Outcome.eq(outcome.clone()));
+        }
+        if let Some(event_type) = &filter.event_type {
+            query = query.filter(bot_event::Column::EventType.eq(event_type));
+        }
+        if let Some(channel) = &filter.channel {
+            query = query.filter(bot_event::Column::Channel.eq(channel));
+        }
+
+        let events = pagination
+            .apply(query, bot_event::Column::CreatedAt, bot_event::Column::Id)
+            .all(&self.db)
+            .await?;
+        Ok(pagination.page(events, |e| Cursor::new(e.created_at, &e.id)))
+    }
+
+    /// Delete the events of every workspace stored before `cutoff`
+    pub async fn delete_before(&self, cutoff: DateTime) -> Result<u64, DbErr> {
+        let result = BotEventEntity::delete_many()
+            .filter(bot_event::Column::CreatedAt.lt(cutoff))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// Delete all but the newest `keep` events of a workspace
+    pub async fn trim(&self, workspace_id: &str, keep: u64) -> Result<u64, DbErr> {
+        let Some(oldest_kept) = BotEventEntity::find()
+            .filter(bot_event::Column::WorkspaceId.eq(workspace_id))
+            .order_by_desc(bot_event::Column::CreatedAt)
+            .offset(keep.saturating_sub(1))
+            .limit(1)
+            .one(&self.db)
+            .await?
+        else {
+            return Ok(0);
+        };
+
+        let result = BotEventEntity::delete_many()
+            .filter(bot_event::Column::WorkspaceId.eq(workspace_id))
+            .filter(bot_event::Column::CreatedAt.lt(oldest_kept.created_at))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
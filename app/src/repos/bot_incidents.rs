@@ -0,0 +1,62 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect,
+};
+
+use crate::{
+    models::bot_incident::{
+        self, ActiveModel, BotIncidentKind, Entity as BotIncidentEntity, Model as BotIncident,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct BotIncidentsRepo {
+    db: DatabaseConnection,
+}
+
+impl BotIncidentsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        workspace_id: &str,
+        kind: BotIncidentKind,
+        message: Option<String>,
+    ) -> Result<BotIncident, DbErr> {
+        let incident = ActiveModel {
+            id: Set(generate_uuid()),
+            workspace_id: Set(workspace_id.to_string()),
+            kind: Set(kind),
+            message: Set(message),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        };
+
+        incident.insert(&self.db).await
+    }
+
+    /// Most recent incidents of a workspace, newest first
+    pub async fn get_recent(
+        &self,
+        workspace_id: &str,
+        limit: u64,
+    ) -> Result<Vec<BotIncident>, DbErr> {
+        BotIncidentEntity::find()
+            .filter(bot_incident::Column::WorkspaceId.eq(workspace_id))
+            .order_by_desc(bot_incident::Column::CreatedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+    }
+
+    /// Most recent bot errors of every workspace, newest first
+    pub async fn get_recent_errors(&self, limit: u64) -> Result<Vec<BotIncident>, DbErr> {
+        BotIncidentEntity::find()
+            .filter(bot_incident::Column::Kind.eq(BotIncidentKind::Error))
+            .order_by_desc(bot_incident::Column::CreatedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+    }
+}
@@ -0,0 +1,66 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, TransactionTrait,
+};
+
+use crate::{
+    models::calendar_feed::{
+        self, ActiveModel, Entity as CalendarFeedEntity, Model as CalendarFeed,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct CalendarFeedsRepo {
+    db: DatabaseConnection,
+}
+
+impl CalendarFeedsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn get_by_token(&self, token: &str) -> Result<Option<CalendarFeed>, DbErr> {
+        CalendarFeedEntity::find()
+            .filter(calendar_feed::Column::Token.eq(token))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn get_by_person(&self, person_id: &str) -> Result<Option<CalendarFeed>, DbErr> {
+        CalendarFeedEntity::find()
+            .filter(calendar_feed::Column::PersonId.eq(person_id))
+            .one(&self.db)
+            .await
+    }
+
+    /// Give the person a new feed token, revoking the previous one
+    pub async fn rotate(&self, person_id: &str) -> Result<CalendarFeed, DbErr> {
+        let txn = self.db.begin().await?;
+
+        CalendarFeedEntity::delete_many()
+            .filter(calendar_feed::Column::PersonId.eq(person_id))
+            .exec(&txn)
+            .await?;
+        let feed = ActiveModel {
+            id: Set(generate_uuid()),
+            person_id: Set(person_id.to_string()),
+            token: Set(nanoid::nanoid!(32)),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(&txn)
+        .await?;
+
+        txn.commit().await?;
+        Ok(feed)
+    }
+
+    /// Returns whether the person had a feed
+    pub async fn delete_by_person(&self, person_id: &str) -> Result<bool, DbErr> {
+        let result = CalendarFeedEntity::delete_many()
+            .filter(calendar_feed::Column::PersonId.eq(person_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
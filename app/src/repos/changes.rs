@@ -1,11 +1,16 @@
+use std::collections::HashSet;
+
+use sea_orm::prelude::DateTime;
+use sea_orm::sea_query::{Expr, OnConflict};
 use sea_orm::ActiveValue::Set;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
-    QueryFilter,
+    QueryFilter, QuerySelect, QueryTrait, TransactionTrait,
 };
 
 use crate::models::change::{self, ActiveModel, Entity as ChangeEntity, Model as Change};
-use crate::models::task::{Model as Task, TaskStatus};
+use crate::models::change_archive::{self, Entity as ChangeArchiveEntity};
+use crate::models::task::{self, Entity as TaskEntity, Model as Task, TaskStatus};
 use crate::utils::crypto::generate_uuid;
 
 pub struct ChangesRepo {
@@ -18,10 +23,7 @@ impl ChangesRepo {
     }
 
     pub async fn create(&self, old: TaskStatus, task: &Task) -> Result<Change, DbErr> {
-        let changes_count = ChangeEntity::find()
-            .filter(change::Column::TaskId.eq(&task.id))
-            .count(&self.db)
-            .await? as i16;
+        let changes_count = self.count_for_task(&task.id).await? as i16;
 
         let change_model = ActiveModel {
             id: Set(generate_uuid()),
@@ -29,19 +31,143 @@ impl ChangesRepo {
             new: Set(task.status.clone()),
             index: Set(changes_count),
             task_id: Set(task.id.clone()),
-            ..Default::default()
+            created_at: Set(Some(chrono::Utc::now().naive_utc())),
         };
         let change = change_model.insert(&self.db).await?;
 
         Ok(change)
     }
 
+    /// Changes of the tasks, archived ones included, ordered by task and index
+    pub async fn get_for_tasks(&self, task_ids: Vec<String>) -> Result<Vec<Change>, DbErr> {
+        let mut changes = ChangeEntity::find()
+            .filter(change::Column::TaskId.is_in(task_ids.clone()))
+            .all(&self.db)
+            .await?;
+        changes.extend(
+            ChangeArchiveEntity::find()
+                .filter(change_archive::Column::TaskId.is_in(task_ids))
+                .all(&self.db)
+                .await?
+                .into_iter()
+                .map(Change::from),
+        );
+
+        changes.sort_by(|a, b| a.task_id.cmp(&b.task_id).then(a.index.cmp(&b.index)));
+        Ok(changes)
+    }
+
+    /// Store changes read from a backup as they are. Changes already stored are
+    /// skipped; returns how many were inserted.
+    pub async fn restore_many(&self, changes: Vec<Change>) -> Result<u64, DbErr> {
+        if changes.is_empty() {
+            return Ok(0);
+        }
+
+        let models = changes
+            .into_iter()
+            .map(|change| ActiveModel::from(change).reset_all());
+        ChangeEntity::insert_many(models)
+            .on_conflict(OnConflict::new().do_nothing().to_owned())
+            .exec_without_returning(&self.db)
+            .await
+    }
+
+    /// Append the history of one task to another's, e.g. when merging a duplicate
+    pub async fn move_to_task(&self, from_task_id: &str, to_task_id: &str) -> Result<(), DbErr> {
+        let offset = self.count_for_task(to_task_id).await? as i16;
+
+        let archived: HashSet<String> = ChangeArchiveEntity::find()
+            .filter(change_archive::Column::TaskId.eq(from_task_id))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|change| change.id)
+            .collect();
+        let moved = self.get_all_for_task(from_task_id.to_string()).await?;
+        for (position, change) in moved.into_iter().enumerate() {
+            let index = offset + position as i16;
+            if archived.contains(&change.id) {
+                ChangeArchiveEntity::update_many()
+                    .col_expr(change_archive::Column::TaskId, Expr::value(to_task_id))
+                    .col_expr(change_archive::Column::Index, Expr::value(index))
+                    .filter(change_archive::Column::Id.eq(change.id))
+                    .exec(&self.db)
+                    .await?;
+                continue;
+            }
+            let mut change: ActiveModel = change.into();
+            change.task_id = Set(to_task_id.to_string());
+            change.index = Set(index);
+            change.update(&self.db).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Status changes of a task in the order they happened, archived ones included
     pub async fn get_all_for_task(&self, task_id: String) -> Result<Vec<Change>, DbErr> {
+        self.get_for_tasks(vec![task_id]).await
+    }
+
+    async fn count_for_task(&self, task_id: &str) -> Result<u64, DbErr> {
+        let live = ChangeEntity::find()
+            .filter(change::Column::TaskId.eq(task_id))
+            .count(&self.db)
+            .await?;
+        let archived = ChangeArchiveEntity::find()
+            .filter(change_archive::Column::TaskId.eq(task_id))
+            .count(&self.db)
+            .await?;
+        Ok(live + archived)
+    }
+
+    /// Move up to `limit` changes of tasks completed and left alone since before
+    /// `cutoff` to the archive. Returns how many moved; call again until none do.
+    pub async fn archive_completed_before(
+        &self,
+        cutoff: DateTime,
+        limit: u64,
+    ) -> Result<u64, DbErr> {
+        let settled_tasks = TaskEntity::find()
+            .select_only()
+            .column(task::Column::Id)
+            .filter(task::Column::Status.eq(TaskStatus::Completed))
+            .filter(task::Column::UpdatedAt.lt(cutoff))
+            .into_query();
         let changes = ChangeEntity::find()
-            .filter(change::Column::TaskId.eq(&task_id))
+            .filter(change::Column::TaskId.in_subquery(settled_tasks))
+            .limit(limit)
             .all(&self.db)
             .await?;
+        if changes.is_empty() {
+            return Ok(0);
+        }
 
-        Ok(changes)
+        let ids: Vec<String> = changes.iter().map(|change| change.id.clone()).collect();
+        let txn = self.db.begin().await?;
+        ChangeArchiveEntity::insert_many(changes.into_iter().map(change_archive::ActiveModel::of))
+            .on_conflict(
+                OnConflict::column(change_archive::Column::Id)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(&txn)
+            .await?;
+        let result = ChangeEntity::delete_many()
+            .filter(change::Column::Id.is_in(ids))
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// Status changes of every workspace since a time
+    pub async fn count_since(&self, since: DateTime) -> Result<u64, DbErr> {
+        ChangeEntity::find()
+            .filter(change::Column::CreatedAt.gte(since))
+            .count(&self.db)
+            .await
     }
 }
@@ -0,0 +1,103 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder,
+};
+
+use crate::{
+    models::{
+        channel_rule::{self, ActiveModel, Entity as ChannelRuleEntity, Model as ChannelRule},
+        task::TaskPriority,
+    },
+    utils::crypto::generate_uuid,
+};
+
+/// What a channel rule does to tasks created from the channel's messages
+pub struct ChannelRuleDefaults {
+    pub assignee_id: Option<String>,
+    pub labels: Vec<String>,
+    pub priority: Option<TaskPriority>,
+    pub project_id: Option<String>,
+    pub ignored: bool,
+}
+
+pub struct ChannelRulesRepo {
+    db: DatabaseConnection,
+}
+
+impl ChannelRulesRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn get_by_workspace(&self, workspace_id: &str) -> Result<Vec<ChannelRule>, DbErr> {
+        ChannelRuleEntity::find()
+            .filter(channel_rule::Column::WorkspaceId.eq(workspace_id))
+            .order_by_asc(channel_rule::Column::Channel)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn get_by_channel(
+        &self,
+        workspace_id: &str,
+        channel: &str,
+    ) -> Result<Option<ChannelRule>, DbErr> {
+        ChannelRuleEntity::find()
+            .filter(channel_rule::Column::WorkspaceId.eq(workspace_id))
+            .filter(channel_rule::Column::Channel.eq(channel))
+            .one(&self.db)
+            .await
+    }
+
+    /// Replace the rule of a channel, creating it on first save
+    pub async fn upsert(
+        &self,
+        workspace_id: &str,
+        channel: &str,
+        defaults: ChannelRuleDefaults,
+    ) -> Result<ChannelRule, DbErr> {
+        let existing = self.get_by_channel(workspace_id, channel).await?;
+
+        let model = ActiveModel {
+            id: Set(String::new()),
+            workspace_id: Set(workspace_id.to_string()),
+            channel: Set(channel.to_string()),
+            assignee_id: Set(defaults.assignee_id),
+            labels: Set(serde_json::json!(defaults.labels)),
+            priority: Set(defaults.priority),
+            project_id: Set(defaults.project_id),
+            ignored: Set(defaults.ignored),
+            updated_at: Set(chrono::Utc::now().naive_utc()),
+        };
+
+        match existing {
+            Some(existing) => {
+                ActiveModel {
+                    id: Set(existing.id),
+                    ..model
+                }
+                .update(&self.db)
+                .await
+            }
+            None => {
+                ActiveModel {
+                    id: Set(generate_uuid()),
+                    ..model
+                }
+                .insert(&self.db)
+                .await
+            }
+        }
+    }
+
+    /// Returns whether a rule was removed
+    pub async fn delete(&self, workspace_id: &str, channel: &str) -> Result<bool, DbErr> {
+        let result = ChannelRuleEntity::delete_many()
+            .filter(channel_rule::Column::WorkspaceId.eq(workspace_id))
+            .filter(channel_rule::Column::Channel.eq(channel))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
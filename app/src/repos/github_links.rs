@@ -0,0 +1,93 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder,
+};
+
+use crate::{
+    models::github_link::{
+        self, ActiveModel, Entity as GithubLinkEntity, GithubLinkKind, Model as GithubLink,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct GithubLinksRepo {
+    db: DatabaseConnection,
+}
+
+impl GithubLinksRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Record that an issue or pull request mentions a task, or refresh its title and state
+    pub async fn upsert(
+        &self,
+        task_id: &str,
+        kind: GithubLinkKind,
+        url: &str,
+        title: &str,
+        state: &str,
+    ) -> Result<GithubLink, DbErr> {
+        let existing = GithubLinkEntity::find()
+            .filter(github_link::Column::TaskId.eq(task_id))
+            .filter(github_link::Column::Url.eq(url))
+            .one(&self.db)
+            .await?;
+
+        let now = chrono::Utc::now().naive_utc();
+        match existing {
+            Some(existing) => {
+                let mut link: ActiveModel = existing.into();
+                link.title = Set(title.to_string());
+                link.state = Set(state.to_string());
+                link.updated_at = Set(now);
+                link.update(&self.db).await
+            }
+            None => {
+                ActiveModel {
+                    id: Set(generate_uuid()),
+                    task_id: Set(task_id.to_string()),
+                    kind: Set(kind),
+                    url: Set(url.to_string()),
+                    title: Set(title.to_string()),
+                    state: Set(state.to_string()),
+                    updated_at: Set(now),
+                }
+                .insert(&self.db)
+                .await
+            }
+        }
+    }
+
+    pub async fn get_for_task(&self, task_id: &str) -> Result<Vec<GithubLink>, DbErr> {
+        GithubLinkEntity::find()
+            .filter(github_link::Column::TaskId.eq(task_id))
+            .order_by_asc(github_link::Column::UpdatedAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// Move the links of one task to another, dropping those it already has
+    pub async fn move_to_task(&self, from_task_id: &str, to_task_id: &str) -> Result<(), DbErr> {
+        let existing: Vec<String> = self
+            .get_for_task(to_task_id)
+            .await?
+            .into_iter()
+            .map(|link| link.url)
+            .collect();
+
+        for link in self.get_for_task(from_task_id).await? {
+            if existing.contains(&link.url) {
+                GithubLinkEntity::delete_by_id(link.id)
+                    .exec(&self.db)
+                    .await?;
+                continue;
+            }
+            let mut link: ActiveModel = link.into();
+            link.task_id = Set(to_task_id.to_string());
+            link.update(&self.db).await?;
+        }
+
+        Ok(())
+    }
+}
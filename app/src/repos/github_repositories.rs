@@ -0,0 +1,71 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder,
+};
+
+use crate::{
+    models::github_repository::{
+        self, ActiveModel, Entity as GithubRepositoryEntity, Model as GithubRepository,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct GithubRepositoriesRepo {
+    db: DatabaseConnection,
+}
+
+impl GithubRepositoriesRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        workspace_id: &str,
+        full_name: &str,
+        webhook_secret: String,
+    ) -> Result<GithubRepository, DbErr> {
+        ActiveModel {
+            id: Set(generate_uuid()),
+            workspace_id: Set(workspace_id.to_string()),
+            full_name: Set(full_name.to_string()),
+            webhook_secret: Set(webhook_secret),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    pub async fn get_by_workspace(
+        &self,
+        workspace_id: &str,
+    ) -> Result<Vec<GithubRepository>, DbErr> {
+        GithubRepositoryEntity::find()
+            .filter(github_repository::Column::WorkspaceId.eq(workspace_id))
+            .order_by_asc(github_repository::Column::FullName)
+            .all(&self.db)
+            .await
+    }
+
+    /// Repository names are matched case-insensitively, like GitHub does
+    pub async fn get_by_full_name(
+        &self,
+        full_name: &str,
+    ) -> Result<Option<GithubRepository>, DbErr> {
+        GithubRepositoryEntity::find()
+            .filter(github_repository::Column::FullName.eq(full_name.to_lowercase()))
+            .one(&self.db)
+            .await
+    }
+
+    /// Returns whether the repository was connected to the workspace
+    pub async fn delete(&self, workspace_id: &str, id: &str) -> Result<bool, DbErr> {
+        let result = GithubRepositoryEntity::delete_many()
+            .filter(github_repository::Column::WorkspaceId.eq(workspace_id))
+            .filter(github_repository::Column::Id.eq(id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -0,0 +1,106 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder,
+};
+
+use crate::{
+    models::hook_subscription::{
+        self, ActiveModel, Entity as HookSubscriptionEntity, HookEvent, Model as HookSubscription,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct HookSubscriptionsRepo {
+    db: DatabaseConnection,
+}
+
+impl HookSubscriptionsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        person_id: &str,
+        workspace_id: &str,
+        event: HookEvent,
+        target_url: &str,
+        secret: String,
+    ) -> Result<HookSubscription, DbErr> {
+        ActiveModel {
+            id: Set(generate_uuid()),
+            person_id: Set(person_id.to_string()),
+            workspace_id: Set(workspace_id.to_string()),
+            event: Set(event),
+            target_url: Set(target_url.to_string()),
+            secret: Set(Some(secret)),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<HookSubscription>, DbErr> {
+        HookSubscriptionEntity::find_by_id(id).one(&self.db).await
+    }
+
+    pub async fn get_by_person(&self, person_id: &str) -> Result<Vec<HookSubscription>, DbErr> {
+        HookSubscriptionEntity::find()
+            .filter(hook_subscription::Column::PersonId.eq(person_id))
+            .order_by_asc(hook_subscription::Column::CreatedAt)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn get_for_event(
+        &self,
+        workspace_id: &str,
+        event: HookEvent,
+    ) -> Result<Vec<HookSubscription>, DbErr> {
+        HookSubscriptionEntity::find()
+            .filter(hook_subscription::Column::WorkspaceId.eq(workspace_id))
+            .filter(hook_subscription::Column::Event.eq(event))
+            .all(&self.db)
+            .await
+    }
+
+    /// Replace the (encrypted) signing secret of a subscription
+    pub async fn set_secret(
+        &self,
+        subscription: HookSubscription,
+        secret: String,
+    ) -> Result<HookSubscription, DbErr> {
+        let mut active: ActiveModel = subscription.into();
+        active.secret = Set(Some(secret));
+        active.update(&self.db).await
+    }
+
+    /// Only removes the subscription when it belongs to `person_id`. Returns whether
+    /// one was removed.
+    pub async fn delete(&self, person_id: &str, id: &str) -> Result<bool, DbErr> {
+        let result = HookSubscriptionEntity::delete_many()
+            .filter(hook_subscription::Column::PersonId.eq(person_id))
+            .filter(hook_subscription::Column::Id.eq(id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Unsubscribe a hook whose target answered 410 Gone
+    pub async fn delete_by_id(&self, id: &str) -> Result<(), DbErr> {
+        HookSubscriptionEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_by_person(&self, person_id: &str) -> Result<u64, DbErr> {
+        let result = HookSubscriptionEntity::delete_many()
+            .filter(hook_subscription::Column::PersonId.eq(person_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+}
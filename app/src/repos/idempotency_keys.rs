@@ -0,0 +1,122 @@
+use sea_orm::{
+    prelude::DateTime,
+    sea_query::{Expr, OnConflict},
+    ActiveValue::Set,
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+};
+
+use crate::{
+    models::idempotency_key::{self, ActiveModel, Entity as IdempotencyKeyEntity, Model},
+    utils::crypto::generate_uuid,
+};
+
+/// Outcome of claiming a key for a request
+pub enum Claim {
+    /// First use of the key; the request runs and its response is stored here
+    New(String),
+    /// The key was used before, maybe by a request that is still running
+    Existing(Model),
+}
+
+pub struct IdempotencyKeysRepo {
+    db: DatabaseConnection,
+}
+
+impl IdempotencyKeysRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Claim a person's key for a request. Keys claimed before `expired_before` are
+    /// free again. Of concurrent requests with one key only one gets `Claim::New`.
+    pub async fn claim(
+        &self,
+        person_id: &str,
+        key: &str,
+        request_hash: &str,
+        expired_before: DateTime,
+    ) -> Result<Claim, DbErr> {
+        IdempotencyKeyEntity::delete_many()
+            .filter(idempotency_key::Column::PersonId.eq(person_id))
+            .filter(idempotency_key::Column::Key.eq(key))
+            .filter(idempotency_key::Column::CreatedAt.lt(expired_before))
+            .exec(&self.db)
+            .await?;
+
+        let id = generate_uuid();
+        let inserted = IdempotencyKeyEntity::insert(ActiveModel {
+            id: Set(id.clone()),
+            person_id: Set(person_id.to_string()),
+            key: Set(key.to_string()),
+            request_hash: Set(request_hash.to_string()),
+            status_code: Set(None),
+            content_type: Set(None),
+            response_body: Set(None),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        })
+        .on_conflict(
+            OnConflict::columns([
+                idempotency_key::Column::PersonId,
+                idempotency_key::Column::Key,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(&self.db)
+        .await?;
+        if inserted > 0 {
+            return Ok(Claim::New(id));
+        }
+
+        IdempotencyKeyEntity::find()
+            .filter(idempotency_key::Column::PersonId.eq(person_id))
+            .filter(idempotency_key::Column::Key.eq(key))
+            .one(&self.db)
+            .await?
+            .map(Claim::Existing)
+            .ok_or(DbErr::RecordNotFound(
+                "Idempotency key not found".to_string(),
+            ))
+    }
+
+    /// Store the response of a claimed key's request
+    pub async fn complete(
+        &self,
+        id: &str,
+        status_code: u16,
+        content_type: Option<String>,
+        body: String,
+    ) -> Result<(), DbErr> {
+        IdempotencyKeyEntity::update_many()
+            .col_expr(
+                idempotency_key::Column::StatusCode,
+                Expr::value(status_code as i32),
+            )
+            .col_expr(
+                idempotency_key::Column::ContentType,
+                Expr::value(content_type),
+            )
+            .col_expr(idempotency_key::Column::ResponseBody, Expr::value(body))
+            .filter(idempotency_key::Column::Id.eq(id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Free a claimed key, so a retry runs the request again
+    pub async fn release(&self, id: &str) -> Result<(), DbErr> {
+        IdempotencyKeyEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete the keys of every person claimed before `cutoff`
+    pub async fn delete_before(&self, cutoff: DateTime) -> Result<u64, DbErr> {
+        let result = IdempotencyKeyEntity::delete_many()
+            .filter(idempotency_key::Column::CreatedAt.lt(cutoff))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,106 @@
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter,
+};
+
+use crate::{
+    models::invitation::{
+        self, ActiveModel, Entity as InvitationEntity, InvitationStatus, Model as Invitation,
+    },
+    models::workspace_link::WorkspaceRole,
+    utils::crypto::generate_uuid,
+};
+
+pub struct InvitationsRepo {
+    db: DatabaseConnection,
+}
+
+impl InvitationsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        email: String,
+        workspace_id: String,
+        slack_member_id: String,
+        invited_by: String,
+        role: WorkspaceRole,
+        expiry_hours: i64,
+    ) -> Result<Invitation, DbErr> {
+        let now = chrono::Utc::now().naive_utc();
+        let invitation = ActiveModel {
+            id: Set(generate_uuid()),
+            token: Set(nanoid::nanoid!(32)),
+            email: Set(email),
+            workspace_id: Set(workspace_id),
+            slack_member_id: Set(slack_member_id),
+            invited_by: Set(invited_by),
+            role: Set(role),
+            status: Set(InvitationStatus::Pending),
+            expires_at: Set(now + chrono::Duration::hours(expiry_hours)),
+            accepted_at: Set(None),
+            created_at: Set(now),
+        };
+
+        invitation.insert(&self.db).await
+    }
+
+    /// Delete every invitation sent to an email address, whatever its status
+    pub async fn delete_by_email(&self, email: &str) -> Result<u64, DbErr> {
+        let result = InvitationEntity::delete_many()
+            .filter(invitation::Column::Email.eq(email))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+
+    pub async fn get_by_token(&self, token: &str) -> Result<Invitation, DbErr> {
+        InvitationEntity::find()
+            .filter(invitation::Column::Token.eq(token))
+            .one(&self.db)
+            .await?
+            .ok_or(DbErr::RecordNotFound("Invitation not found".to_string()))
+    }
+
+    /// Pending invitations for an email, including ones past their expiry
+    pub async fn get_pending_by_email(&self, email: &str) -> Result<Vec<Invitation>, DbErr> {
+        InvitationEntity::find()
+            .filter(invitation::Column::Email.eq(email))
+            .filter(invitation::Column::Status.eq(InvitationStatus::Pending))
+            .all(&self.db)
+            .await
+    }
+
+    /// Expire earlier pending invitations, e.g. before sending a fresh one
+    pub async fn expire_pending(&self, email: &str, workspace_id: &str) -> Result<u64, DbErr> {
+        let result = InvitationEntity::update_many()
+            .col_expr(
+                invitation::Column::Status,
+                Expr::value(InvitationStatus::Expired),
+            )
+            .filter(invitation::Column::Email.eq(email))
+            .filter(invitation::Column::WorkspaceId.eq(workspace_id))
+            .filter(invitation::Column::Status.eq(InvitationStatus::Pending))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+
+    pub async fn set_status(
+        &self,
+        invitation: Invitation,
+        status: InvitationStatus,
+    ) -> Result<Invitation, DbErr> {
+        let accepted_at =
+            (status == InvitationStatus::Accepted).then(|| chrono::Utc::now().naive_utc());
+
+        let mut invitation: ActiveModel = invitation.into();
+        invitation.status = Set(status);
+        invitation.accepted_at = Set(accepted_at);
+        invitation.update(&self.db).await
+    }
+}
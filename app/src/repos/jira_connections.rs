@@ -0,0 +1,89 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter,
+};
+
+use crate::{
+    models::jira_connection::{
+        self, ActiveModel, Entity as JiraConnectionEntity, Model as JiraConnection,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct JiraConnectionsRepo {
+    db: DatabaseConnection,
+}
+
+impl JiraConnectionsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn get_by_workspace(
+        &self,
+        workspace_id: &str,
+    ) -> Result<Option<JiraConnection>, DbErr> {
+        JiraConnectionEntity::find()
+            .filter(jira_connection::Column::WorkspaceId.eq(workspace_id))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn get_by_webhook_secret(
+        &self,
+        webhook_secret: &str,
+    ) -> Result<Option<JiraConnection>, DbErr> {
+        JiraConnectionEntity::find()
+            .filter(jira_connection::Column::WebhookSecret.eq(webhook_secret))
+            .one(&self.db)
+            .await
+    }
+
+    /// Replace a workspace's connection, creating the row on first save. The webhook
+    /// secret of an existing connection is kept so the Jira webhook keeps working.
+    pub async fn upsert(&self, connection: JiraConnection) -> Result<JiraConnection, DbErr> {
+        let existing = self.get_by_workspace(&connection.workspace_id).await?;
+
+        let model = ActiveModel {
+            id: Set(String::new()),
+            workspace_id: Set(connection.workspace_id),
+            base_url: Set(connection.base_url),
+            email: Set(connection.email),
+            api_token: Set(connection.api_token),
+            project_key: Set(connection.project_key),
+            issue_type: Set(connection.issue_type),
+            webhook_secret: Set(connection.webhook_secret),
+            updated_at: Set(chrono::Utc::now().naive_utc()),
+        };
+
+        match existing {
+            Some(existing) => {
+                ActiveModel {
+                    id: Set(existing.id),
+                    webhook_secret: Set(existing.webhook_secret),
+                    ..model
+                }
+                .update(&self.db)
+                .await
+            }
+            None => {
+                ActiveModel {
+                    id: Set(generate_uuid()),
+                    ..model
+                }
+                .insert(&self.db)
+                .await
+            }
+        }
+    }
+
+    /// Returns whether a connection was removed
+    pub async fn delete_by_workspace(&self, workspace_id: &str) -> Result<bool, DbErr> {
+        let result = JiraConnectionEntity::delete_many()
+            .filter(jira_connection::Column::WorkspaceId.eq(workspace_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -0,0 +1,139 @@
+use chrono::NaiveDateTime;
+use sea_orm::{
+    sea_query::{LockBehavior, LockType},
+    ActiveModelTrait,
+    ActiveValue::Set,
+    ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait,
+};
+
+use crate::{
+    models::job::{self, ActiveModel, Entity as JobEntity, JobStatus, Model as Job},
+    utils::crypto::generate_uuid,
+};
+
+pub struct JobsRepo {
+    db: DatabaseConnection,
+}
+
+impl JobsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        kind: String,
+        payload: serde_json::Value,
+        max_attempts: i32,
+    ) -> Result<Job, DbErr> {
+        let now = chrono::Utc::now().naive_utc();
+        let job = ActiveModel {
+            id: Set(generate_uuid()),
+            kind: Set(kind),
+            payload: Set(payload),
+            status: Set(JobStatus::Pending),
+            attempts: Set(0),
+            max_attempts: Set(max_attempts),
+            run_at: Set(now),
+            locked_at: Set(None),
+            last_error: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        job.insert(&self.db).await
+    }
+
+    /// Claim the oldest runnable job for this worker. Rows claimed by other workers
+    /// are skipped, and running jobs locked before `stale_before` are taken over.
+    pub async fn claim_next(&self, stale_before: NaiveDateTime) -> Result<Option<Job>, DbErr> {
+        let now = chrono::Utc::now().naive_utc();
+        let txn = self.db.begin().await?;
+
+        let job = JobEntity::find()
+            .filter(
+                Condition::any()
+                    .add(
+                        Condition::all()
+                            .add(job::Column::Status.eq(JobStatus::Pending))
+                            .add(job::Column::RunAt.lte(now)),
+                    )
+                    .add(
+                        Condition::all()
+                            .add(job::Column::Status.eq(JobStatus::Running))
+                            .add(job::Column::LockedAt.lt(stale_before)),
+                    ),
+            )
+            .order_by_asc(job::Column::RunAt)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+            .one(&txn)
+            .await?;
+
+        let Some(job) = job else {
+            txn.commit().await?;
+            return Ok(None);
+        };
+
+        let attempts = job.attempts + 1;
+        let mut job: ActiveModel = job.into();
+        job.status = Set(JobStatus::Running);
+        job.attempts = Set(attempts);
+        job.locked_at = Set(Some(now));
+        job.updated_at = Set(now);
+        let job = job.update(&txn).await?;
+
+        txn.commit().await?;
+        Ok(Some(job))
+    }
+
+    pub async fn complete(&self, job: Job) -> Result<Job, DbErr> {
+        let mut job: ActiveModel = job.into();
+        job.status = Set(JobStatus::Completed);
+        job.locked_at = Set(None);
+        job.last_error = Set(None);
+        job.updated_at = Set(chrono::Utc::now().naive_utc());
+        job.update(&self.db).await
+    }
+
+    /// Schedule a retry at `retry_at`, or dead-letter the job once it used all attempts
+    pub async fn fail(
+        &self,
+        job: Job,
+        error: String,
+        retry_at: NaiveDateTime,
+    ) -> Result<Job, DbErr> {
+        let status = if job.attempts >= job.max_attempts {
+            JobStatus::Dead
+        } else {
+            JobStatus::Pending
+        };
+
+        let mut job: ActiveModel = job.into();
+        job.status = Set(status);
+        job.run_at = Set(retry_at);
+        job.locked_at = Set(None);
+        job.last_error = Set(Some(error));
+        job.updated_at = Set(chrono::Utc::now().naive_utc());
+        job.update(&self.db).await
+    }
+
+    pub async fn count_by_status(&self, status: JobStatus) -> Result<u64, DbErr> {
+        JobEntity::find()
+            .filter(job::Column::Status.eq(status))
+            .count(&self.db)
+            .await
+    }
+
+    /// Jobs whose last attempt failed, whether they will be retried or not, most
+    /// recently failed first
+    pub async fn get_recently_failed(&self, limit: u64) -> Result<Vec<Job>, DbErr> {
+        JobEntity::find()
+            .filter(job::Column::LastError.is_not_null())
+            .filter(job::Column::Status.ne(JobStatus::Completed))
+            .order_by_desc(job::Column::UpdatedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+    }
+}
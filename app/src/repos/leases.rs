@@ -0,0 +1,98 @@
+use chrono::Duration;
+use sea_orm::{
+    sea_query::{Expr, OnConflict},
+    ActiveValue::Set,
+    ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+};
+
+use crate::models::lease::{self, ActiveModel, Entity as LeaseEntity};
+
+pub struct LeasesRepo {
+    db: DatabaseConnection,
+}
+
+impl LeasesRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Take or renew a lease for `ttl`. Returns false while another holder's lease
+    /// has not expired.
+    pub async fn acquire(&self, name: &str, holder: &str, ttl: Duration) -> Result<bool, DbErr> {
+        let now = chrono::Utc::now().naive_utc();
+
+        // Renew our own lease or take over an expired one
+        let renewed = LeaseEntity::update_many()
+            .col_expr(lease::Column::Holder, Expr::value(holder))
+            .col_expr(lease::Column::ExpiresAt, Expr::value(now + ttl))
+            .filter(lease::Column::Name.eq(name))
+            .filter(
+                Condition::any()
+                    .add(lease::Column::Holder.eq(holder))
+                    .add(lease::Column::ExpiresAt.lt(now)),
+            )
+            .exec(&self.db)
+            .await?;
+        if renewed.rows_affected > 0 {
+            return Ok(true);
+        }
+
+        // Nobody held it yet; of several instances racing here only one inserts
+        let inserted = LeaseEntity::insert(ActiveModel {
+            name: Set(name.to_string()),
+            holder: Set(holder.to_string()),
+            expires_at: Set(now + ttl),
+        })
+        .on_conflict(
+            OnConflict::column(lease::Column::Name)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(&self.db)
+        .await?;
+
+        Ok(inserted > 0)
+    }
+
+    /// Take a lease even if another holder has it. That holder notices on its next
+    /// renewal and stops.
+    pub async fn take_over(&self, name: &str, holder: &str, ttl: Duration) -> Result<(), DbErr> {
+        let now = chrono::Utc::now().naive_utc();
+
+        LeaseEntity::insert(ActiveModel {
+            name: Set(name.to_string()),
+            holder: Set(holder.to_string()),
+            expires_at: Set(now + ttl),
+        })
+        .on_conflict(
+            OnConflict::column(lease::Column::Name)
+                .update_columns([lease::Column::Holder, lease::Column::ExpiresAt])
+                .to_owned(),
+        )
+        .exec_without_returning(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Give up a lease, if still held by `holder`
+    pub async fn release(&self, name: &str, holder: &str) -> Result<(), DbErr> {
+        LeaseEntity::delete_many()
+            .filter(lease::Column::Name.eq(name))
+            .filter(lease::Column::Holder.eq(holder))
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Give up every lease of `holder`, so other instances take over right away
+    pub async fn release_all(&self, holder: &str) -> Result<u64, DbErr> {
+        let result = LeaseEntity::delete_many()
+            .filter(lease::Column::Holder.eq(holder))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+}
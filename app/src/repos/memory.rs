@@ -0,0 +1,243 @@
+//! In-memory fakes of the `repos::stores` traits. They keep rows in a map and
+//! answer like the SeaORM repositories, including `RecordNotFound` for missing rows.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use axum::async_trait;
+use sea_orm::{prelude::DateTime, DbErr};
+
+use crate::{
+    models::{
+        person::Model as Person,
+        task::{Model as Task, TaskStatus},
+    },
+    repos::stores::{PersonsStore, TasksStore},
+    utils::pagination::{Cursor, Page, Pagination},
+};
+
+#[derive(Debug, Default)]
+pub struct InMemoryTasks {
+    tasks: Mutex<HashMap<String, Task>>,
+}
+
+impl InMemoryTasks {
+    pub fn new(tasks: impl IntoIterator<Item = Task>) -> Self {
+        Self {
+            tasks: Mutex::new(tasks.into_iter().map(|t| (t.id.clone(), t)).collect()),
+        }
+    }
+
+    pub fn insert(&self, task: Task) {
+        self.tasks.lock().unwrap().insert(task.id.clone(), task);
+    }
+
+    /// Matching tasks, newest first
+    fn find(&self, matches: impl Fn(&Task) -> bool) -> Vec<Task> {
+        let mut tasks: Vec<Task> = self
+            .tasks
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|task| matches(task))
+            .cloned()
+            .collect();
+        tasks.sort_by(|a, b| (b.created_at, &b.id).cmp(&(a.created_at, &a.id)));
+        tasks
+    }
+
+    fn update(&self, mut task: Task) -> Result<Task, DbErr> {
+        match self.tasks.lock().unwrap().get_mut(&task.id) {
+            Some(stored) => {
+                task.version = stored.version + 1;
+                task.updated_at = chrono::Utc::now().naive_utc();
+                *stored = task.clone();
+                Ok(task)
+            }
+            None => Err(DbErr::RecordNotFound("Task was not found".to_string())),
+        }
+    }
+}
+
+#[async_trait]
+impl TasksStore for InMemoryTasks {
+    async fn get(&self, task_id: String) -> Result<Task, DbErr> {
+        self.tasks
+            .lock()
+            .unwrap()
+            .get(&task_id)
+            .cloned()
+            .ok_or_else(|| DbErr::RecordNotFound("Task was not found".to_string()))
+    }
+
+    async fn get_assigned_page(
+        &self,
+        person_id: String,
+        status: Option<TaskStatus>,
+        workspace_id: Option<String>,
+        pagination: &Pagination,
+    ) -> Result<(Page<Task>, u64), DbErr> {
+        let assigned = self.find(|task| {
+            task.assigned_to == person_id
+                && status.as_ref().is_none_or(|status| task.status == *status)
+                && workspace_id
+                    .as_ref()
+                    .is_none_or(|id| task.workspace_id.as_ref() == Some(id))
+        });
+        let total = assigned.len() as u64;
+
+        let rows = assigned
+            .into_iter()
+            .filter(|task| match &pagination.after {
+                Some(after) => (task.created_at, &task.id) < (after.created_at, &after.id),
+                None => true,
+            })
+            .take(pagination.limit as usize + 1)
+            .collect();
+
+        Ok((
+            pagination.page(rows, |t| Cursor::new(t.created_at, &t.id)),
+            total,
+        ))
+    }
+
+    async fn get_initiated_by(&self, person_id: String) -> Result<Vec<Task>, DbErr> {
+        Ok(self.find(|task| task.assigned_by.as_ref() == Some(&person_id)))
+    }
+
+    async fn get_assigned_by_others(&self, person_id: String) -> Result<Vec<Task>, DbErr> {
+        Ok(self.find(|task| {
+            task.assigned_to == person_id
+                && task
+                    .assigned_by
+                    .as_ref()
+                    .is_some_and(|assigned_by| *assigned_by != person_id)
+        }))
+    }
+
+    async fn set_due_at(&self, mut task: Task, due_at: Option<DateTime>) -> Result<Task, DbErr> {
+        task.due_at = due_at;
+        self.update(task)
+    }
+
+    async fn set_project(&self, mut task: Task, project_id: Option<String>) -> Result<Task, DbErr> {
+        task.project_id = project_id;
+        self.update(task)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryPersons {
+    persons: Mutex<Vec<Person>>,
+}
+
+impl InMemoryPersons {
+    pub fn new(persons: impl IntoIterator<Item = Person>) -> Self {
+        Self {
+            persons: Mutex::new(persons.into_iter().collect()),
+        }
+    }
+
+    pub fn insert(&self, person: Person) {
+        self.persons.lock().unwrap().push(person);
+    }
+
+    fn find(&self, matches: impl Fn(&Person) -> bool) -> Result<Person, DbErr> {
+        self.persons
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|person| matches(person))
+            .cloned()
+            .ok_or_else(|| DbErr::RecordNotFound("Person not found".to_string()))
+    }
+}
+
+#[async_trait]
+impl PersonsStore for InMemoryPersons {
+    async fn get_by_id(&self, id: String) -> Result<Person, DbErr> {
+        self.find(|person| person.id == id)
+    }
+
+    async fn get_by_email(&self, email: String) -> Result<Person, DbErr> {
+        self.find(|person| person.email == email)
+    }
+
+    async fn get_by_external_id(&self, external_id: String) -> Result<Person, DbErr> {
+        self.find(|person| person.external_id == external_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime as ChronoDateTime;
+
+    use super::*;
+
+    fn task(id: &str, assigned_to: &str, assigned_by: Option<&str>, secs: i64) -> Task {
+        Task {
+            id: id.to_string(),
+            status: TaskStatus::InProgress,
+            assigned_to: assigned_to.to_string(),
+            assigned_by: assigned_by.map(str::to_string),
+            created_at: ChronoDateTime::from_timestamp(secs, 0).unwrap().naive_utc(),
+            message_id: format!("message-{}", id),
+            workspace_id: Some("acme".to_string()),
+            jira_issue_key: None,
+            linear_issue_id: None,
+            due_at: None,
+            completed_at: None,
+            notion_page_id: None,
+            priority: None,
+            labels: None,
+            project_id: None,
+            orphaned_at: None,
+            key: None,
+            version: 0,
+            updated_at: ChronoDateTime::from_timestamp(secs, 0).unwrap().naive_utc(),
+        }
+    }
+
+    #[tokio::test]
+    async fn pages_follow_the_cursor_newest_first() {
+        let store = InMemoryTasks::new([
+            task("a", "ana", None, 1),
+            task("b", "ana", None, 2),
+            task("c", "ana", None, 3),
+            task("d", "bo", None, 4),
+        ]);
+        let ids = |page: &Page<Task>| -> Vec<String> {
+            page.items.iter().map(|t| t.id.clone()).collect()
+        };
+
+        let first = Pagination::first(2);
+        let (page, total) = store
+            .get_assigned_page("ana".to_string(), None, None, &first)
+            .await
+            .unwrap();
+        assert_eq!((ids(&page), total), (vec!["c".into(), "b".into()], 3));
+
+        let next = first.next(&page).unwrap();
+        let (page, _) = store
+            .get_assigned_page("ana".to_string(), None, None, &next)
+            .await
+            .unwrap();
+        assert_eq!(ids(&page), vec!["a".to_string()]);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn self_assigned_tasks_are_not_assigned_by_others() {
+        let store = InMemoryTasks::new([
+            task("mine", "ana", Some("ana"), 1),
+            task("given", "ana", Some("bo"), 2),
+            task("unknown", "ana", None, 3),
+        ]);
+
+        let tasks = store
+            .get_assigned_by_others("ana".to_string())
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, "given");
+    }
+}
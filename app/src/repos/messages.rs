@@ -1,22 +1,115 @@
 use crate::models::person::Model as Person;
 use crate::models::{
     message::{self, ActiveModel, Entity as MessageEntity, Model as Message},
-    task,
+    message_archive::{self, Entity as MessageArchiveEntity},
+    task::{self, Entity as TaskEntity},
 };
+use crate::repos::{tasks::BULK_INSERT_BATCH, workspace_settings::WorkspaceSettingsRepo};
 use crate::utils::crypto::generate_uuid;
+use crate::utils::encryption::{decrypt_message, encrypt_message};
+use crate::utils::pagination::{Cursor, Page, Pagination};
+use crate::utils::redaction::redact;
 use migration::query;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use sea_orm::{
+    prelude::DateTime,
+    sea_query::{Expr, OnConflict},
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QueryTrait, TransactionTrait,
+};
 use sea_orm::{ActiveValue::Set, QuerySelect, RelationTrait};
+use tracing::warn;
+
+/// Shown instead of message text that can't be decrypted
+pub const UNREADABLE_CONTENT: &str = "[encrypted]";
+
+/// Master key for message text, kept in `AppState` and handed to every
+/// `MessagesRepo`
+#[derive(Debug, Clone)]
+pub struct MessageEncryption {
+    master_key: String,
+    /// Encrypt new messages; stored ones are decrypted either way
+    enabled: bool,
+}
+
+impl MessageEncryption {
+    pub fn new(master_key: &str, enabled: bool) -> Self {
+        Self {
+            master_key: master_key.to_string(),
+            enabled,
+        }
+    }
+
+    /// Whether new message text is encrypted at rest
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Stored form of message text of `workspace_id`, encrypted when `encrypt` is
+    /// set, and whether it is
+    fn seal(
+        &self,
+        content: String,
+        workspace_id: &str,
+        encrypt: bool,
+    ) -> Result<(String, bool), DbErr> {
+        if !encrypt {
+            return Ok((content, false));
+        }
+        encrypt_message(&content, &self.master_key, workspace_id)
+            .map(|sealed| (sealed, true))
+            .map_err(|e| DbErr::Custom(format!("Failed to encrypt message: {}", e)))
+    }
+
+    /// The message with its text readable. Text that can't be decrypted stays
+    /// marked as encrypted
+    fn open(&self, mut message: Message) -> Message {
+        if !message.content_encrypted {
+            return message;
+        }
+        match decrypt_message(&message.content, &self.master_key) {
+            Ok(content) => {
+                message.content = content;
+                message.content_encrypted = false;
+            }
+            Err(e) => {
+                warn!("Failed to decrypt message {}: {}", message.id, e);
+                message.content = UNREADABLE_CONTENT.to_string();
+            }
+        }
+        message
+    }
+}
+
+/// A message to store with `create_many`
+#[derive(Debug, Clone)]
+pub struct NewMessage {
+    pub content: String,
+    pub external_id: String,
+    pub channel: String,
+    pub timestamp: String,
+    pub person_id: String,
+    /// See `Message::channel_hidden`
+    pub channel_hidden: bool,
+    /// See `Message::slack_team_id`
+    pub slack_team_id: Option<String>,
+}
 
 pub struct MessagesRepo {
     db: DatabaseConnection,
+    encryption: MessageEncryption,
 }
 
 impl MessagesRepo {
-    pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+    pub fn new(db: DatabaseConnection, encryption: MessageEncryption) -> Self {
+        Self { db, encryption }
+    }
+
+    fn open(&self, message: Message) -> Message {
+        self.encryption.open(message)
     }
 
+    /// Store a message of `workspace_id`, its text redacted as the workspace's
+    /// settings ask and encrypted when `Config::encrypt_messages` is on
     pub async fn create(
         &self,
         content: String,
@@ -24,27 +117,179 @@ impl MessagesRepo {
         channel: String,
         timestamp: String,
         person: &Person,
+        workspace_id: &str,
     ) -> Result<Message, DbErr> {
+        let redaction = WorkspaceSettingsRepo::new(self.db.clone())
+            .get_message_redaction(workspace_id)
+            .await?;
+
+        let (content, content_encrypted) = self.encryption.seal(
+            redact(&content, redaction),
+            workspace_id,
+            self.encryption.enabled(),
+        )?;
         let message_model = ActiveModel {
             id: Set(generate_uuid()),
             person_id: Set(person.id.clone()),
             content: Set(content),
+            content_encrypted: Set(content_encrypted),
             external_id: Set(external_id),
             channel: Set(channel),
             timestamp: Set(timestamp),
+            merged_into_task_id: Set(None),
+            channel_hidden: Set(false),
+            slack_team_id: Set(None),
+            created_at: Set(chrono::Utc::now().naive_utc()),
         };
         let message = message_model.insert(&self.db).await?;
 
-        Ok(message)
+        Ok(self.open(message))
+    }
+
+    /// Store many messages of `workspace_id` like `create`, with multi-row inserts
+    /// of `BULK_INSERT_BATCH` rows. Messages already stored under the same external
+    /// id are kept as they are. Returns the stored message of every external id.
+    pub async fn create_many(
+        &self,
+        messages: Vec<NewMessage>,
+        workspace_id: &str,
+    ) -> Result<Vec<Message>, DbErr> {
+        let redaction = WorkspaceSettingsRepo::new(self.db.clone())
+            .get_message_redaction(workspace_id)
+            .await?;
+        let encrypt = self.encryption.enabled();
+
+        let mut stored = Vec::with_capacity(messages.len());
+        for batch in messages.chunks(BULK_INSERT_BATCH) {
+            let external_ids: Vec<String> = batch
+                .iter()
+                .map(|message| message.external_id.clone())
+                .collect();
+            let existing: Vec<String> = self
+                .get_by_external_ids(external_ids.clone())
+                .await?
+                .into_iter()
+                .map(|message| message.external_id)
+                .collect();
+
+            let now = chrono::Utc::now().naive_utc();
+            let mut models = Vec::new();
+            for message in batch {
+                if existing.contains(&message.external_id) {
+                    continue;
+                }
+                let (content, content_encrypted) = self.encryption.seal(
+                    redact(&message.content, redaction),
+                    workspace_id,
+                    encrypt,
+                )?;
+                models.push(ActiveModel {
+                    id: Set(generate_uuid()),
+                    person_id: Set(message.person_id.clone()),
+                    content: Set(content),
+                    content_encrypted: Set(content_encrypted),
+                    external_id: Set(message.external_id.clone()),
+                    channel: Set(message.channel.clone()),
+                    timestamp: Set(message.timestamp.clone()),
+                    merged_into_task_id: Set(None),
+                    channel_hidden: Set(message.channel_hidden),
+                    slack_team_id: Set(message.slack_team_id.clone()),
+                    created_at: Set(now),
+                });
+            }
+            if !models.is_empty() {
+                MessageEntity::insert_many(models)
+                    .on_conflict(OnConflict::new().do_nothing().to_owned())
+                    .exec_without_returning(&self.db)
+                    .await?;
+            }
+
+            stored.extend(self.get_by_external_ids(external_ids).await?);
+        }
+
+        Ok(stored)
     }
 
+    /// Store messages read from a backup under their ids, their text encrypted for
+    /// `workspace_id` when `Config::encrypt_messages` is on. Messages conflicting
+    /// with stored ones are skipped; returns how many were inserted.
+    pub async fn restore_many(
+        &self,
+        messages: Vec<Message>,
+        workspace_id: &str,
+    ) -> Result<u64, DbErr> {
+        let encrypt = self.encryption.enabled();
+
+        let mut inserted = 0;
+        for batch in messages.chunks(BULK_INSERT_BATCH) {
+            let models = batch
+                .iter()
+                .map(|message| {
+                    let mut model = ActiveModel::from(message.clone()).reset_all();
+                    let (content, content_encrypted) =
+                        self.encryption
+                            .seal(message.content.clone(), workspace_id, encrypt)?;
+                    model.content = Set(content);
+                    model.content_encrypted = Set(content_encrypted);
+                    Ok(model)
+                })
+                .collect::<Result<Vec<_>, DbErr>>()?;
+            inserted += MessageEntity::insert_many(models)
+                .on_conflict(OnConflict::new().do_nothing().to_owned())
+                .exec_without_returning(&self.db)
+                .await?;
+        }
+
+        Ok(inserted)
+    }
+
+    /// Messages with any of the external ids, archived ones included
+    pub async fn get_by_external_ids(
+        &self,
+        external_ids: Vec<String>,
+    ) -> Result<Vec<Message>, DbErr> {
+        if external_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut messages = MessageEntity::find()
+            .filter(message::Column::ExternalId.is_in(external_ids.clone()))
+            .all(&self.db)
+            .await?;
+        messages.extend(
+            MessageArchiveEntity::find()
+                .filter(message_archive::Column::ExternalId.is_in(external_ids))
+                .all(&self.db)
+                .await?
+                .into_iter()
+                .map(Message::from),
+        );
+
+        Ok(messages
+            .into_iter()
+            .map(|message| self.open(message))
+            .collect())
+    }
+
+    /// Every message a person wrote, archived ones included
     pub async fn get_all_by_person(&self, person_id: String) -> Result<Vec<Message>, DbErr> {
-        let messages = MessageEntity::find()
+        let mut messages = MessageEntity::find()
             .filter(message::Column::PersonId.eq(&person_id))
             .all(&self.db)
             .await?;
+        messages.extend(
+            MessageArchiveEntity::find()
+                .filter(message_archive::Column::PersonId.eq(&person_id))
+                .all(&self.db)
+                .await?
+                .into_iter()
+                .map(Message::from),
+        );
 
-        Ok(messages)
+        Ok(messages
+            .into_iter()
+            .map(|message| self.open(message))
+            .collect())
     }
 
     pub async fn get_task_message(&self, task_id: String) -> Result<Message, DbErr> {
@@ -58,21 +303,29 @@ impl MessagesRepo {
             .await?;
 
         match message {
-            Some(mesg) => Ok(mesg),
+            Some(mesg) => Ok(self.open(mesg)),
             None => Err(DbErr::RecordNotFound(
                 "Associated task not found for the message".to_string(),
             )),
         }
     }
 
+    /// The message with the external id, looked up in the archive too
     pub async fn get_message_by_external_id(&self, external_id: String) -> Result<Message, DbErr> {
-        let message = MessageEntity::find()
+        let mut message = MessageEntity::find()
             .filter(message::Column::ExternalId.eq(external_id.clone()))
             .one(&self.db)
             .await?;
+        if message.is_none() {
+            message = MessageArchiveEntity::find()
+                .filter(message_archive::Column::ExternalId.eq(external_id.clone()))
+                .one(&self.db)
+                .await?
+                .map(Message::from);
+        }
 
         match message {
-            Some(msg) => Ok(msg),
+            Some(msg) => Ok(self.open(msg)),
             None => Err(DbErr::RecordNotFound(format!(
                 "Message with external_id: {} not found",
                 external_id
@@ -84,7 +337,7 @@ impl MessagesRepo {
         let message = MessageEntity::find_by_id(&message_id).one(&self.db).await?;
 
         match message {
-            Some(msg) => Ok(msg),
+            Some(msg) => Ok(self.open(msg)),
             None => Err(DbErr::RecordNotFound(format!(
                 "Message with id: {} not found",
                 message_id
@@ -92,9 +345,241 @@ impl MessagesRepo {
         }
     }
 
-    pub async fn get_all(&self) -> Result<Vec<Message>, DbErr> {
-        let messages = MessageEntity::find().all(&self.db).await?;
+    /// Fetch several messages in one query (order is not preserved)
+    pub async fn get_by_ids(&self, message_ids: Vec<String>) -> Result<Vec<Message>, DbErr> {
+        if message_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let messages = MessageEntity::find()
+            .filter(message::Column::Id.is_in(message_ids))
+            .all(&self.db)
+            .await?;
+
+        Ok(messages
+            .into_iter()
+            .map(|message| self.open(message))
+            .collect())
+    }
+
+    /// Delete messages by id; their tasks and changes cascade. Messages of
+    /// duplicates merged into those tasks go with them, archived ones included.
+    pub async fn delete_by_ids(&self, message_ids: Vec<String>) -> Result<u64, DbErr> {
+        if message_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let task_ids = TaskEntity::find()
+            .select_only()
+            .column(task::Column::Id)
+            .filter(task::Column::MessageId.is_in(message_ids.clone()))
+            .into_query();
+        let txn = self.db.begin().await?;
+        let archived = MessageArchiveEntity::delete_many()
+            .filter(message_archive::Column::MergedIntoTaskId.in_subquery(task_ids.clone()))
+            .exec(&txn)
+            .await?;
+        let merged = MessageEntity::delete_many()
+            .filter(message::Column::MergedIntoTaskId.in_subquery(task_ids))
+            .exec(&txn)
+            .await?;
+        let result = MessageEntity::delete_many()
+            .filter(message::Column::Id.is_in(message_ids))
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+
+        Ok(archived.rows_affected + merged.rows_affected + result.rows_affected)
+    }
+
+    /// Replace the text of every message a person wrote, archived ones included,
+    /// keeping the rows and their tasks
+    pub async fn anonymize_by_person(&self, person_id: &str, content: &str) -> Result<u64, DbErr> {
+        let result = MessageEntity::update_many()
+            .col_expr(message::Column::Content, Expr::value(content))
+            .col_expr(message::Column::ContentEncrypted, Expr::value(false))
+            .filter(message::Column::PersonId.eq(person_id))
+            .exec(&self.db)
+            .await?;
+        let archived = MessageArchiveEntity::update_many()
+            .col_expr(message_archive::Column::Content, Expr::value(content))
+            .col_expr(
+                message_archive::Column::ContentEncrypted,
+                Expr::value(false),
+            )
+            .filter(message_archive::Column::PersonId.eq(person_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected + archived.rows_affected)
+    }
+
+    /// Record where in Slack a message was posted, see `Message::slack_team_id` and
+    /// `Message::channel_hidden`
+    pub async fn set_slack_origin(
+        &self,
+        message_id: &str,
+        slack_team_id: Option<&str>,
+        channel_hidden: bool,
+    ) -> Result<(), DbErr> {
+        MessageEntity::update_many()
+            .col_expr(message::Column::SlackTeamId, Expr::value(slack_team_id))
+            .col_expr(message::Column::ChannelHidden, Expr::value(channel_hidden))
+            .filter(message::Column::Id.eq(message_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Remember that the task of a message was merged into `task_id`
+    pub async fn set_merged_into(&self, message_id: &str, task_id: &str) -> Result<(), DbErr> {
+        MessageEntity::update_many()
+            .col_expr(message::Column::MergedIntoTaskId, Expr::value(task_id))
+            .filter(message::Column::Id.eq(message_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Messages whose tasks were merged into `task_id` as duplicates, archived ones
+    /// included, oldest first
+    pub async fn get_merged_into(&self, task_id: &str) -> Result<Vec<Message>, DbErr> {
+        self.get_merged_into_any(vec![task_id.to_string()]).await
+    }
+
+    /// Messages of duplicates merged into any of the tasks, oldest first
+    pub async fn get_merged_into_any(&self, task_ids: Vec<String>) -> Result<Vec<Message>, DbErr> {
+        if task_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut messages = MessageArchiveEntity::find()
+            .filter(message_archive::Column::MergedIntoTaskId.is_in(task_ids.clone()))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(Message::from)
+            .collect::<Vec<_>>();
+        messages.extend(
+            MessageEntity::find()
+                .filter(message::Column::MergedIntoTaskId.is_in(task_ids))
+                .all(&self.db)
+                .await?,
+        );
+
+        messages.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(messages
+            .into_iter()
+            .map(|message| self.open(message))
+            .collect())
+    }
+
+    /// Move up to `limit` messages of merged duplicates stored before `cutoff` to the
+    /// archive. No task points to them any more, they are only history. Returns how
+    /// many moved; call again until none do.
+    pub async fn archive_merged_before(&self, cutoff: DateTime, limit: u64) -> Result<u64, DbErr> {
+        let task_messages = TaskEntity::find()
+            .select_only()
+            .column(task::Column::MessageId)
+            .into_query();
+        let messages = MessageEntity::find()
+            .filter(message::Column::MergedIntoTaskId.is_not_null())
+            .filter(message::Column::CreatedAt.lt(cutoff))
+            .filter(message::Column::Id.not_in_subquery(task_messages))
+            .limit(limit)
+            .all(&self.db)
+            .await?;
+        if messages.is_empty() {
+            return Ok(0);
+        }
 
-        Ok(messages)
+        let ids: Vec<String> = messages.iter().map(|message| message.id.clone()).collect();
+        let txn = self.db.begin().await?;
+        MessageArchiveEntity::insert_many(
+            messages.into_iter().map(message_archive::ActiveModel::of),
+        )
+        .on_conflict(
+            OnConflict::column(message_archive::Column::Id)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(&txn)
+        .await?;
+        let result = MessageEntity::delete_many()
+            .filter(message::Column::Id.is_in(ids))
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// The most recent messages of a channel, newest first
+    pub async fn get_recent_in_channel(
+        &self,
+        channel: &str,
+        limit: u64,
+    ) -> Result<Vec<Message>, DbErr> {
+        let messages = MessageEntity::find()
+            .filter(message::Column::Channel.eq(channel))
+            .order_by_desc(message::Column::Timestamp)
+            .limit(limit)
+            .all(&self.db)
+            .await?;
+
+        Ok(messages
+            .into_iter()
+            .map(|message| self.open(message))
+            .collect())
+    }
+
+    /// Newest messages first
+    pub async fn get_page(&self, pagination: &Pagination) -> Result<Page<Message>, DbErr> {
+        let messages = pagination
+            .apply(
+                MessageEntity::find(),
+                message::Column::CreatedAt,
+                message::Column::Id,
+            )
+            .all(&self.db)
+            .await?;
+
+        let messages = messages
+            .into_iter()
+            .map(|message| self.open(message))
+            .collect();
+        Ok(pagination.page(messages, |m| Cursor::new(m.created_at, &m.id)))
+    }
+
+    /// Messages of every workspace stored since a time
+    pub async fn count_since(&self, since: DateTime) -> Result<u64, DbErr> {
+        MessageEntity::find()
+            .filter(message::Column::CreatedAt.gte(since))
+            .count(&self.db)
+            .await
+    }
+
+    /// Store the text of a message again, encrypted with the key of `workspace_id` or
+    /// in plain text. Used to convert messages stored before encryption was toggled
+    pub async fn reseal(
+        &self,
+        message: Message,
+        workspace_id: &str,
+        encrypt: bool,
+    ) -> Result<Message, DbErr> {
+        if message.content_encrypted {
+            return Err(DbErr::Custom(format!(
+                "Message {} can't be decrypted with the configured key",
+                message.id
+            )));
+        }
+        let mut model: ActiveModel = message.into();
+        let content = model.content.take().unwrap_or_default();
+        let (content, content_encrypted) = self.encryption.seal(content, workspace_id, encrypt)?;
+        model.content = Set(content);
+        model.content_encrypted = Set(content_encrypted);
+        Ok(self.open(model.update(&self.db).await?))
     }
 }
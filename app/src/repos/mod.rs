@@ -1,7 +1,40 @@
+pub mod audit_logs;
+pub mod availability;
+pub mod board_views;
+pub mod bot_events;
+pub mod bot_incidents;
+pub mod calendar_feeds;
 pub mod changes;
+pub mod channel_rules;
+pub mod github_links;
+pub mod github_repositories;
+pub mod hook_subscriptions;
+pub mod idempotency_keys;
+pub mod invitations;
+pub mod jira_connections;
+pub mod jobs;
+pub mod leases;
+pub mod memory;
 pub mod messages;
+pub mod notification_settings;
+pub mod notion_connections;
 pub mod persons;
+pub mod projects;
+pub mod reminders;
+pub mod retention_policies;
+pub mod scheduled_jobs;
+pub mod sessions;
+pub mod sla_breaches;
+pub mod sla_policies;
+pub mod slack_status_settings;
+pub mod standup_entries;
+pub mod stores;
+pub mod task_sequences;
+pub mod task_templates;
+pub mod task_tombstones;
 pub mod tasks;
 pub mod workspace_admins;
+pub mod workspace_credentials;
 pub mod workspace_links;
 pub mod workspace_settings;
+pub mod workspaces;
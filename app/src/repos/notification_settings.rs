@@ -0,0 +1,99 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter,
+};
+
+use crate::{
+    models::{
+        notification_setting::{
+            self, ActiveModel, Entity as NotificationSettingEntity, Model as NotificationSetting,
+        },
+        person::{Entity as PersonEntity, Model as Person},
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct NotificationSettingsRepo {
+    db: DatabaseConnection,
+}
+
+impl NotificationSettingsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Saved settings of a person, or the defaults when they never saved any
+    pub async fn get_or_default(&self, person_id: &str) -> Result<NotificationSetting, DbErr> {
+        let settings = NotificationSettingEntity::find()
+            .filter(notification_setting::Column::PersonId.eq(person_id))
+            .one(&self.db)
+            .await?;
+
+        Ok(settings.unwrap_or_else(|| NotificationSetting::default_for(person_id)))
+    }
+
+    pub async fn delete_by_person(&self, person_id: &str) -> Result<u64, DbErr> {
+        let result = NotificationSettingEntity::delete_many()
+            .filter(notification_setting::Column::PersonId.eq(person_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// People who turned on a boolean setting, e.g. `Column::DailyDigest`
+    pub async fn get_subscribers(
+        &self,
+        setting: notification_setting::Column,
+    ) -> Result<Vec<Person>, DbErr> {
+        let rows = NotificationSettingEntity::find()
+            .filter(setting.eq(true))
+            .find_also_related(PersonEntity)
+            .all(&self.db)
+            .await?;
+
+        Ok(rows.into_iter().filter_map(|(_, person)| person).collect())
+    }
+
+    /// Replace a person's settings, creating the row on first save
+    pub async fn upsert(
+        &self,
+        settings: NotificationSetting,
+    ) -> Result<NotificationSetting, DbErr> {
+        let existing = NotificationSettingEntity::find()
+            .filter(notification_setting::Column::PersonId.eq(&settings.person_id))
+            .one(&self.db)
+            .await?;
+
+        let model = ActiveModel {
+            id: Set(String::new()),
+            person_id: Set(settings.person_id),
+            dm_on_assignment: Set(settings.dm_on_assignment),
+            daily_digest: Set(settings.daily_digest),
+            weekly_summary: Set(settings.weekly_summary),
+            channel: Set(settings.channel),
+            webhook_url: Set(settings.webhook_url),
+            locale: Set(settings.locale),
+            updated_at: Set(chrono::Utc::now().naive_utc()),
+        };
+
+        match existing {
+            Some(existing) => {
+                ActiveModel {
+                    id: Set(existing.id),
+                    ..model
+                }
+                .update(&self.db)
+                .await
+            }
+            None => {
+                ActiveModel {
+                    id: Set(generate_uuid()),
+                    ..model
+                }
+                .insert(&self.db)
+                .await
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter,
+};
+
+use crate::{
+    models::notion_connection::{
+        self, ActiveModel, Entity as NotionConnectionEntity, Model as NotionConnection,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct NotionConnectionsRepo {
+    db: DatabaseConnection,
+}
+
+impl NotionConnectionsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn get_by_workspace(
+        &self,
+        workspace_id: &str,
+    ) -> Result<Option<NotionConnection>, DbErr> {
+        NotionConnectionEntity::find()
+            .filter(notion_connection::Column::WorkspaceId.eq(workspace_id))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn get_all(&self) -> Result<Vec<NotionConnection>, DbErr> {
+        NotionConnectionEntity::find().all(&self.db).await
+    }
+
+    /// Replace a workspace's connection, creating the row on first save
+    pub async fn upsert(&self, connection: NotionConnection) -> Result<NotionConnection, DbErr> {
+        let existing = self.get_by_workspace(&connection.workspace_id).await?;
+
+        let model = ActiveModel {
+            id: Set(String::new()),
+            workspace_id: Set(connection.workspace_id),
+            token: Set(connection.token),
+            database_id: Set(connection.database_id),
+            title_property: Set(connection.title_property),
+            updated_at: Set(chrono::Utc::now().naive_utc()),
+        };
+
+        match existing {
+            Some(existing) => {
+                ActiveModel {
+                    id: Set(existing.id),
+                    ..model
+                }
+                .update(&self.db)
+                .await
+            }
+            None => {
+                ActiveModel {
+                    id: Set(generate_uuid()),
+                    ..model
+                }
+                .insert(&self.db)
+                .await
+            }
+        }
+    }
+
+    /// Returns whether a connection was removed
+    pub async fn delete_by_workspace(&self, workspace_id: &str) -> Result<bool, DbErr> {
+        let result = NotionConnectionEntity::delete_many()
+            .filter(notion_connection::Column::WorkspaceId.eq(workspace_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
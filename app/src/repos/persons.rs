@@ -30,6 +30,8 @@ impl PersonsRepo {
             is_me: Set(is_me),
             external_id: Set(external_id),
             email: Set(email),
+            timezone: Set("UTC".to_string()),
+            avatar_url: Set(None),
         };
 
         let person = person_model.insert(&self.db).await?;
@@ -37,6 +39,11 @@ impl PersonsRepo {
         Ok(person)
     }
 
+    /// Store a person read from a backup under its id
+    pub async fn restore(&self, person: Person) -> Result<Person, DbErr> {
+        ActiveModel::from(person).reset_all().insert(&self.db).await
+    }
+
     pub async fn get_by_external_id(&self, external_id: String) -> Result<Person, DbErr> {
         let person = PersonEntity::find()
             .filter(person::Column::ExternalId.eq(&external_id))
@@ -91,6 +98,18 @@ impl PersonsRepo {
         }
     }
 
+    /// Fetch several people in one query (order is not preserved)
+    pub async fn get_by_ids(&self, ids: Vec<String>) -> Result<Vec<Person>, DbErr> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        PersonEntity::find()
+            .filter(person::Column::Id.is_in(ids))
+            .all(&self.db)
+            .await
+    }
+
     /// Update a person's external_id (Slack member ID)
     pub async fn update_external_id(
         &self,
@@ -104,4 +123,38 @@ impl PersonsRepo {
         person_active.external_id = Set(external_id);
         person_active.update(&self.db).await
     }
+
+    /// Strip everything identifying from a person erased at their request. The row
+    /// stays so tasks assigned to or by them keep their history.
+    pub async fn anonymize(&self, person: Person) -> Result<Person, DbErr> {
+        let email = format!("deleted-{}@invalid", person.id);
+        let mut person_active: ActiveModel = person.into();
+        person_active.name = Set("Deleted user".to_string());
+        person_active.email = Set(email);
+        person_active.external_id = Set(String::new());
+        person_active.avatar_url = Set(None);
+        person_active.update(&self.db).await
+    }
+
+    /// Update the self-service profile fields; `None` leaves a field unchanged
+    /// and an empty avatar URL clears it
+    pub async fn update_profile(
+        &self,
+        person: Person,
+        name: Option<String>,
+        timezone: Option<String>,
+        avatar_url: Option<String>,
+    ) -> Result<Person, DbErr> {
+        let mut person_active: ActiveModel = person.into();
+        if let Some(name) = name {
+            person_active.name = Set(name);
+        }
+        if let Some(timezone) = timezone {
+            person_active.timezone = Set(timezone);
+        }
+        if let Some(avatar_url) = avatar_url {
+            person_active.avatar_url = Set(Some(avatar_url).filter(|url| !url.is_empty()));
+        }
+        person_active.update(&self.db).await
+    }
 }
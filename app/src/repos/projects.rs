@@ -0,0 +1,114 @@
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter, QueryOrder,
+};
+
+use crate::{
+    models::{
+        channel_rule::{self, Entity as ChannelRuleEntity},
+        project::{self, ActiveModel, Entity as ProjectEntity, Model as Project},
+        task::{self, Entity as TaskEntity},
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct ProjectsRepo {
+    db: DatabaseConnection,
+}
+
+impl ProjectsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        workspace_id: &str,
+        name: &str,
+        description: Option<String>,
+    ) -> Result<Project, DbErr> {
+        ActiveModel {
+            id: Set(generate_uuid()),
+            workspace_id: Set(workspace_id.to_string()),
+            name: Set(name.to_string()),
+            description: Set(description),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    /// A project of the workspace; None for unknown ids and projects of other workspaces
+    pub async fn get(
+        &self,
+        workspace_id: &str,
+        project_id: &str,
+    ) -> Result<Option<Project>, DbErr> {
+        ProjectEntity::find_by_id(project_id)
+            .filter(project::Column::WorkspaceId.eq(workspace_id))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn get_by_name(
+        &self,
+        workspace_id: &str,
+        name: &str,
+    ) -> Result<Option<Project>, DbErr> {
+        ProjectEntity::find()
+            .filter(project::Column::WorkspaceId.eq(workspace_id))
+            .filter(project::Column::Name.eq(name))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn get_by_workspace(&self, workspace_id: &str) -> Result<Vec<Project>, DbErr> {
+        ProjectEntity::find()
+            .filter(project::Column::WorkspaceId.eq(workspace_id))
+            .order_by_asc(project::Column::Name)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn update(
+        &self,
+        project: Project,
+        name: &str,
+        description: Option<String>,
+    ) -> Result<Project, DbErr> {
+        let mut project: ActiveModel = project.into();
+        project.name = Set(name.to_string());
+        project.description = Set(description);
+        project.update(&self.db).await
+    }
+
+    /// Delete a project; its tasks and channel rules are left without one
+    pub async fn delete(&self, project: Project) -> Result<(), DbErr> {
+        TaskEntity::update_many()
+            .col_expr(task::Column::ProjectId, Expr::value(Option::<String>::None))
+            .col_expr(
+                task::Column::Version,
+                Expr::col((TaskEntity, task::Column::Version)).add(1),
+            )
+            .col_expr(
+                task::Column::UpdatedAt,
+                Expr::value(chrono::Utc::now().naive_utc()),
+            )
+            .filter(task::Column::ProjectId.eq(&project.id))
+            .exec(&self.db)
+            .await?;
+        ChannelRuleEntity::update_many()
+            .col_expr(
+                channel_rule::Column::ProjectId,
+                Expr::value(Option::<String>::None),
+            )
+            .filter(channel_rule::Column::ProjectId.eq(&project.id))
+            .exec(&self.db)
+            .await?;
+
+        ProjectEntity::delete_by_id(project.id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder,
+};
+
+use crate::{
+    models::reminder::{self, ActiveModel, Entity as ReminderEntity, Model as Reminder},
+    utils::crypto::generate_uuid,
+};
+
+pub struct RemindersRepo {
+    db: DatabaseConnection,
+}
+
+impl RemindersRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        task_id: &str,
+        person_id: &str,
+        remind_at: NaiveDateTime,
+    ) -> Result<Reminder, DbErr> {
+        ActiveModel {
+            id: Set(generate_uuid()),
+            task_id: Set(task_id.to_string()),
+            person_id: Set(person_id.to_string()),
+            remind_at: Set(remind_at),
+            sent_at: Set(None),
+            done_at: Set(None),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    pub async fn get_by_id(&self, reminder_id: &str) -> Result<Option<Reminder>, DbErr> {
+        ReminderEntity::find_by_id(reminder_id).one(&self.db).await
+    }
+
+    /// Reminders of the person that are not done yet, soonest first
+    pub async fn get_open_by_person(&self, person_id: &str) -> Result<Vec<Reminder>, DbErr> {
+        ReminderEntity::find()
+            .filter(reminder::Column::PersonId.eq(person_id))
+            .filter(reminder::Column::DoneAt.is_null())
+            .order_by_asc(reminder::Column::RemindAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// Reminders whose time has come and that were not sent for it yet
+    pub async fn get_due(&self, now: NaiveDateTime) -> Result<Vec<Reminder>, DbErr> {
+        ReminderEntity::find()
+            .filter(reminder::Column::RemindAt.lte(now))
+            .filter(reminder::Column::SentAt.is_null())
+            .filter(reminder::Column::DoneAt.is_null())
+            .order_by_asc(reminder::Column::RemindAt)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn mark_sent(&self, reminder: Reminder) -> Result<Reminder, DbErr> {
+        let mut reminder: ActiveModel = reminder.into();
+        reminder.sent_at = Set(Some(chrono::Utc::now().naive_utc()));
+        reminder.update(&self.db).await
+    }
+
+    pub async fn mark_done(&self, reminder: Reminder) -> Result<Reminder, DbErr> {
+        let mut reminder: ActiveModel = reminder.into();
+        reminder.done_at = Set(Some(chrono::Utc::now().naive_utc()));
+        reminder.update(&self.db).await
+    }
+
+    /// Send the reminder again at `remind_at`
+    pub async fn snooze(
+        &self,
+        reminder: Reminder,
+        remind_at: NaiveDateTime,
+    ) -> Result<Reminder, DbErr> {
+        let mut reminder: ActiveModel = reminder.into();
+        reminder.remind_at = Set(remind_at);
+        reminder.sent_at = Set(None);
+        reminder.update(&self.db).await
+    }
+
+    pub async fn delete(&self, reminder: Reminder) -> Result<(), DbErr> {
+        ReminderEntity::delete_by_id(reminder.id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_by_person(&self, person_id: &str) -> Result<u64, DbErr> {
+        let result = ReminderEntity::delete_many()
+            .filter(reminder::Column::PersonId.eq(person_id))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
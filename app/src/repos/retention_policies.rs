@@ -0,0 +1,73 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter,
+};
+
+use crate::{
+    models::retention_policy::{
+        self, ActiveModel, Entity as RetentionPolicyEntity, Model as RetentionPolicy,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct RetentionPoliciesRepo {
+    db: DatabaseConnection,
+}
+
+impl RetentionPoliciesRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Every saved policy; workspaces without one keep everything
+    pub async fn get_all(&self) -> Result<Vec<RetentionPolicy>, DbErr> {
+        RetentionPolicyEntity::find().all(&self.db).await
+    }
+
+    /// Saved policy of a workspace, or the keep-everything default
+    pub async fn get_or_default(&self, workspace_id: &str) -> Result<RetentionPolicy, DbErr> {
+        let policy = RetentionPolicyEntity::find()
+            .filter(retention_policy::Column::WorkspaceId.eq(workspace_id))
+            .one(&self.db)
+            .await?;
+
+        Ok(policy.unwrap_or_else(|| RetentionPolicy::default_for(workspace_id)))
+    }
+
+    /// Replace a workspace's policy, creating the row on first save
+    pub async fn upsert(&self, policy: RetentionPolicy) -> Result<RetentionPolicy, DbErr> {
+        let existing = RetentionPolicyEntity::find()
+            .filter(retention_policy::Column::WorkspaceId.eq(&policy.workspace_id))
+            .one(&self.db)
+            .await?;
+
+        let model = ActiveModel {
+            id: Set(String::new()),
+            workspace_id: Set(policy.workspace_id),
+            completed_task_days: Set(policy.completed_task_days),
+            message_days: Set(policy.message_days),
+            incident_days: Set(policy.incident_days),
+            mode: Set(policy.mode),
+            updated_at: Set(chrono::Utc::now().naive_utc()),
+        };
+
+        match existing {
+            Some(existing) => {
+                ActiveModel {
+                    id: Set(existing.id),
+                    ..model
+                }
+                .update(&self.db)
+                .await
+            }
+            None => {
+                ActiveModel {
+                    id: Set(generate_uuid()),
+                    ..model
+                }
+                .insert(&self.db)
+                .await
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter, QueryOrder,
+};
+
+use crate::{
+    models::scheduled_job::{
+        self, ActiveModel, Entity as ScheduledJobEntity, Model as ScheduledJob, ScheduledJobKind,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct ScheduledJobsRepo {
+    db: DatabaseConnection,
+}
+
+impl ScheduledJobsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn get_all(&self) -> Result<Vec<ScheduledJob>, DbErr> {
+        ScheduledJobEntity::find()
+            .order_by_asc(scheduled_job::Column::Name)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn get_by_name(&self, name: &str) -> Result<ScheduledJob, DbErr> {
+        ScheduledJobEntity::find()
+            .filter(scheduled_job::Column::Name.eq(name))
+            .one(&self.db)
+            .await?
+            .ok_or(DbErr::RecordNotFound(format!(
+                "Scheduled job {} not found",
+                name
+            )))
+    }
+
+    /// Enabled jobs whose next run is due, or was never computed
+    pub async fn get_due(&self, now: NaiveDateTime) -> Result<Vec<ScheduledJob>, DbErr> {
+        ScheduledJobEntity::find()
+            .filter(scheduled_job::Column::Enabled.eq(true))
+            .filter(
+                Condition::any()
+                    .add(scheduled_job::Column::NextRunAt.is_null())
+                    .add(scheduled_job::Column::NextRunAt.lte(now)),
+            )
+            .all(&self.db)
+            .await
+    }
+
+    /// Create a job unless one with the same name exists, leaving admin edits intact
+    pub async fn create_if_missing(
+        &self,
+        name: &str,
+        kind: ScheduledJobKind,
+        cron: &str,
+        timezone: &str,
+    ) -> Result<ScheduledJob, DbErr> {
+        if let Ok(job) = self.get_by_name(name).await {
+            return Ok(job);
+        }
+
+        let job = ActiveModel {
+            id: Set(generate_uuid()),
+            name: Set(name.to_string()),
+            kind: Set(kind),
+            cron: Set(cron.to_string()),
+            timezone: Set(timezone.to_string()),
+            workspace_id: Set(None),
+            enabled: Set(true),
+            next_run_at: Set(None),
+            last_run_at: Set(None),
+            last_error: Set(None),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        };
+
+        job.insert(&self.db).await
+    }
+
+    /// Create or replace the schedule of a job that concerns one workspace. The next
+    /// run is computed again by the scheduler.
+    pub async fn save_workspace_job(
+        &self,
+        name: &str,
+        kind: ScheduledJobKind,
+        cron: &str,
+        timezone: &str,
+        workspace_id: &str,
+    ) -> Result<ScheduledJob, DbErr> {
+        if let Ok(job) = self.get_by_name(name).await {
+            let mut job: ActiveModel = job.into();
+            job.kind = Set(kind);
+            job.cron = Set(cron.to_string());
+            job.timezone = Set(timezone.to_string());
+            job.workspace_id = Set(Some(workspace_id.to_string()));
+            job.next_run_at = Set(None);
+            return job.update(&self.db).await;
+        }
+
+        let job = ActiveModel {
+            id: Set(generate_uuid()),
+            name: Set(name.to_string()),
+            kind: Set(kind),
+            cron: Set(cron.to_string()),
+            timezone: Set(timezone.to_string()),
+            workspace_id: Set(Some(workspace_id.to_string())),
+            enabled: Set(true),
+            next_run_at: Set(None),
+            last_run_at: Set(None),
+            last_error: Set(None),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        };
+
+        job.insert(&self.db).await
+    }
+
+    pub async fn delete_by_name(&self, name: &str) -> Result<(), DbErr> {
+        ScheduledJobEntity::delete_many()
+            .filter(scheduled_job::Column::Name.eq(name))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_next_run(
+        &self,
+        job: ScheduledJob,
+        next_run_at: Option<NaiveDateTime>,
+    ) -> Result<ScheduledJob, DbErr> {
+        let mut job: ActiveModel = job.into();
+        job.next_run_at = Set(next_run_at);
+        job.update(&self.db).await
+    }
+
+    /// Record a finished run and when the job fires next
+    pub async fn record_run(
+        &self,
+        job: ScheduledJob,
+        ran_at: NaiveDateTime,
+        next_run_at: Option<NaiveDateTime>,
+        error: Option<String>,
+    ) -> Result<ScheduledJob, DbErr> {
+        let mut job: ActiveModel = job.into();
+        job.last_run_at = Set(Some(ran_at));
+        job.last_error = Set(error);
+        job.next_run_at = Set(next_run_at);
+        job.update(&self.db).await
+    }
+}
@@ -0,0 +1,113 @@
+use chrono::Duration;
+use sea_orm::{
+    prelude::DateTime, sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait,
+    DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+};
+
+use crate::{
+    models::session::{self, ActiveModel, Entity as SessionEntity, Model as Session},
+    utils::crypto::generate_uuid,
+};
+
+/// How stale `last_used_at` may get before a request updates it, so not every
+/// request writes
+const LAST_USED_PRECISION_MINUTES: i64 = 5;
+
+pub struct SessionsRepo {
+    db: DatabaseConnection,
+}
+
+impl SessionsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        person_id: &str,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+        expiry_hours: i64,
+    ) -> Result<Session, DbErr> {
+        let now = chrono::Utc::now().naive_utc();
+        ActiveModel {
+            id: Set(generate_uuid()),
+            person_id: Set(person_id.to_string()),
+            user_agent: Set(user_agent),
+            ip_address: Set(ip_address),
+            created_at: Set(now),
+            last_used_at: Set(now),
+            expires_at: Set(now + Duration::hours(expiry_hours)),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    /// The person's session unless it was revoked or expired
+    pub async fn get_active(
+        &self,
+        person_id: &str,
+        session_id: &str,
+    ) -> Result<Option<Session>, DbErr> {
+        SessionEntity::find_by_id(session_id)
+            .filter(session::Column::PersonId.eq(person_id))
+            .filter(session::Column::ExpiresAt.gt(chrono::Utc::now().naive_utc()))
+            .one(&self.db)
+            .await
+    }
+
+    /// Sessions of a person that have not expired, most recently used first
+    pub async fn get_active_by_person(&self, person_id: &str) -> Result<Vec<Session>, DbErr> {
+        SessionEntity::find()
+            .filter(session::Column::PersonId.eq(person_id))
+            .filter(session::Column::ExpiresAt.gt(chrono::Utc::now().naive_utc()))
+            .order_by_desc(session::Column::LastUsedAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// Record that the session was just used, unless that was recorded lately
+    pub async fn touch(&self, session: &Session) -> Result<(), DbErr> {
+        let now = chrono::Utc::now().naive_utc();
+        if now - session.last_used_at < Duration::minutes(LAST_USED_PRECISION_MINUTES) {
+            return Ok(());
+        }
+
+        SessionEntity::update_many()
+            .col_expr(session::Column::LastUsedAt, Expr::value(now))
+            .filter(session::Column::Id.eq(&session.id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Returns whether the person had the session
+    pub async fn delete(&self, person_id: &str, session_id: &str) -> Result<bool, DbErr> {
+        let result = SessionEntity::delete_many()
+            .filter(session::Column::Id.eq(session_id))
+            .filter(session::Column::PersonId.eq(person_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    pub async fn delete_by_person(&self, person_id: &str) -> Result<u64, DbErr> {
+        let result = SessionEntity::delete_many()
+            .filter(session::Column::PersonId.eq(person_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// Delete sessions whose tokens expired before `cutoff`
+    pub async fn delete_expired(&self, cutoff: DateTime) -> Result<u64, DbErr> {
+        let result = SessionEntity::delete_many()
+            .filter(session::Column::ExpiresAt.lt(cutoff))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+}
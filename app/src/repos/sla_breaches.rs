@@ -0,0 +1,53 @@
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter,
+};
+
+use crate::{
+    models::{
+        sla_breach::{self, ActiveModel, Entity as SlaBreachEntity, Model as SlaBreach},
+        sla_policy::Model as SlaPolicy,
+        task::Model as Task,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct SlaBreachesRepo {
+    db: DatabaseConnection,
+}
+
+impl SlaBreachesRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Record that the task stayed in its status, entered at `entered_at`, past the policy
+    pub async fn create(
+        &self,
+        policy: &SlaPolicy,
+        task: &Task,
+        entered_at: NaiveDateTime,
+    ) -> Result<SlaBreach, DbErr> {
+        ActiveModel {
+            id: Set(generate_uuid()),
+            policy_id: Set(policy.id.clone()),
+            task_id: Set(task.id.clone()),
+            status: Set(task.status.clone()),
+            entered_at: Set(entered_at),
+            breached_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    pub async fn get_for_policies(&self, policy_ids: Vec<String>) -> Result<Vec<SlaBreach>, DbErr> {
+        if policy_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        SlaBreachEntity::find()
+            .filter(sla_breach::Column::PolicyId.is_in(policy_ids))
+            .all(&self.db)
+            .await
+    }
+}
@@ -0,0 +1,107 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder,
+};
+
+use crate::{
+    models::{
+        sla_policy::{self, ActiveModel, Entity as SlaPolicyEntity, Model as SlaPolicy},
+        task::TaskStatus,
+    },
+    utils::crypto::generate_uuid,
+};
+
+/// What a policy allows
+#[derive(Debug, Clone)]
+pub struct PolicyFields {
+    pub name: String,
+    pub status: TaskStatus,
+    pub max_hours: i32,
+}
+
+pub struct SlaPoliciesRepo {
+    db: DatabaseConnection,
+}
+
+impl SlaPoliciesRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        workspace_id: &str,
+        fields: PolicyFields,
+    ) -> Result<SlaPolicy, DbErr> {
+        ActiveModel {
+            id: Set(generate_uuid()),
+            workspace_id: Set(workspace_id.to_string()),
+            name: Set(fields.name),
+            status: Set(fields.status),
+            max_hours: Set(fields.max_hours),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    /// A policy of the workspace; None for unknown ids and policies of other workspaces
+    pub async fn get(
+        &self,
+        workspace_id: &str,
+        policy_id: &str,
+    ) -> Result<Option<SlaPolicy>, DbErr> {
+        SlaPolicyEntity::find_by_id(policy_id)
+            .filter(sla_policy::Column::WorkspaceId.eq(workspace_id))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn get_by_name(
+        &self,
+        workspace_id: &str,
+        name: &str,
+    ) -> Result<Option<SlaPolicy>, DbErr> {
+        SlaPolicyEntity::find()
+            .filter(sla_policy::Column::WorkspaceId.eq(workspace_id))
+            .filter(sla_policy::Column::Name.eq(name))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn get_by_workspace(&self, workspace_id: &str) -> Result<Vec<SlaPolicy>, DbErr> {
+        SlaPolicyEntity::find()
+            .filter(sla_policy::Column::WorkspaceId.eq(workspace_id))
+            .order_by_asc(sla_policy::Column::Name)
+            .all(&self.db)
+            .await
+    }
+
+    /// Policies of every workspace, for the breach check
+    pub async fn get_all(&self) -> Result<Vec<SlaPolicy>, DbErr> {
+        SlaPolicyEntity::find()
+            .order_by_asc(sla_policy::Column::WorkspaceId)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn update(
+        &self,
+        policy: SlaPolicy,
+        fields: PolicyFields,
+    ) -> Result<SlaPolicy, DbErr> {
+        let mut policy: ActiveModel = policy.into();
+        policy.name = Set(fields.name);
+        policy.status = Set(fields.status);
+        policy.max_hours = Set(fields.max_hours);
+        policy.update(&self.db).await
+    }
+
+    /// Delete a policy with its breaches
+    pub async fn delete(&self, policy: SlaPolicy) -> Result<(), DbErr> {
+        SlaPolicyEntity::delete_by_id(policy.id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
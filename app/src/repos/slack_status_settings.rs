@@ -0,0 +1,99 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter,
+};
+
+use crate::{
+    models::slack_status_setting::{
+        self, ActiveModel, Entity as SlackStatusSettingEntity, Model as SlackStatusSetting,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct SlackStatusSettingsRepo {
+    db: DatabaseConnection,
+}
+
+impl SlackStatusSettingsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn get(
+        &self,
+        person_id: &str,
+        workspace_id: &str,
+    ) -> Result<Option<SlackStatusSetting>, DbErr> {
+        SlackStatusSettingEntity::find()
+            .filter(slack_status_setting::Column::PersonId.eq(person_id))
+            .filter(slack_status_setting::Column::WorkspaceId.eq(workspace_id))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn get_by_person(&self, person_id: &str) -> Result<Vec<SlackStatusSetting>, DbErr> {
+        SlackStatusSettingEntity::find()
+            .filter(slack_status_setting::Column::PersonId.eq(person_id))
+            .all(&self.db)
+            .await
+    }
+
+    /// Replace the person's setting for a workspace, creating the row on first save
+    pub async fn upsert(
+        &self,
+        person_id: &str,
+        workspace_id: &str,
+        user_token: String,
+        enabled: bool,
+    ) -> Result<SlackStatusSetting, DbErr> {
+        let existing = self.get(person_id, workspace_id).await?;
+
+        let model = ActiveModel {
+            id: Set(String::new()),
+            person_id: Set(person_id.to_string()),
+            workspace_id: Set(workspace_id.to_string()),
+            user_token: Set(user_token),
+            enabled: Set(enabled),
+            updated_at: Set(chrono::Utc::now().naive_utc()),
+        };
+
+        match existing {
+            Some(existing) => {
+                ActiveModel {
+                    id: Set(existing.id),
+                    ..model
+                }
+                .update(&self.db)
+                .await
+            }
+            None => {
+                ActiveModel {
+                    id: Set(generate_uuid()),
+                    ..model
+                }
+                .insert(&self.db)
+                .await
+            }
+        }
+    }
+
+    /// Returns whether a setting was removed
+    pub async fn delete(&self, person_id: &str, workspace_id: &str) -> Result<bool, DbErr> {
+        let result = SlackStatusSettingEntity::delete_many()
+            .filter(slack_status_setting::Column::PersonId.eq(person_id))
+            .filter(slack_status_setting::Column::WorkspaceId.eq(workspace_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    pub async fn delete_by_person(&self, person_id: &str) -> Result<u64, DbErr> {
+        let result = SlackStatusSettingEntity::delete_many()
+            .filter(slack_status_setting::Column::PersonId.eq(person_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,105 @@
+use chrono::NaiveDate;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder,
+};
+
+use crate::{
+    models::standup_entry::{
+        self, ActiveModel, Entity as StandupEntryEntity, Model as StandupEntry,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct StandupEntriesRepo {
+    db: DatabaseConnection,
+}
+
+impl StandupEntriesRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn get(
+        &self,
+        workspace_id: &str,
+        person_id: &str,
+        day: NaiveDate,
+    ) -> Result<Option<StandupEntry>, DbErr> {
+        StandupEntryEntity::find()
+            .filter(standup_entry::Column::WorkspaceId.eq(workspace_id))
+            .filter(standup_entry::Column::PersonId.eq(person_id))
+            .filter(standup_entry::Column::Day.eq(day))
+            .one(&self.db)
+            .await
+    }
+
+    /// Answers of a workspace's standup, in the order they came in
+    pub async fn get_for_day(
+        &self,
+        workspace_id: &str,
+        day: NaiveDate,
+    ) -> Result<Vec<StandupEntry>, DbErr> {
+        StandupEntryEntity::find()
+            .filter(standup_entry::Column::WorkspaceId.eq(workspace_id))
+            .filter(standup_entry::Column::Day.eq(day))
+            .order_by_asc(standup_entry::Column::CreatedAt)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn get_by_person(&self, person_id: &str) -> Result<Vec<StandupEntry>, DbErr> {
+        StandupEntryEntity::find()
+            .filter(standup_entry::Column::PersonId.eq(person_id))
+            .order_by_asc(standup_entry::Column::Day)
+            .all(&self.db)
+            .await
+    }
+
+    /// Store a member's answers for a day, replacing earlier ones
+    pub async fn save(
+        &self,
+        workspace_id: &str,
+        person_id: &str,
+        day: NaiveDate,
+        yesterday: String,
+        today: String,
+        blockers: Option<String>,
+    ) -> Result<StandupEntry, DbErr> {
+        let now = chrono::Utc::now().naive_utc();
+        match self.get(workspace_id, person_id, day).await? {
+            Some(existing) => {
+                let mut entry: ActiveModel = existing.into();
+                entry.yesterday = Set(yesterday);
+                entry.today = Set(today);
+                entry.blockers = Set(blockers);
+                entry.updated_at = Set(now);
+                entry.update(&self.db).await
+            }
+            None => {
+                ActiveModel {
+                    id: Set(generate_uuid()),
+                    workspace_id: Set(workspace_id.to_string()),
+                    person_id: Set(person_id.to_string()),
+                    day: Set(day),
+                    yesterday: Set(yesterday),
+                    today: Set(today),
+                    blockers: Set(blockers),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                }
+                .insert(&self.db)
+                .await
+            }
+        }
+    }
+
+    pub async fn delete_by_person(&self, person_id: &str) -> Result<u64, DbErr> {
+        let result = StandupEntryEntity::delete_many()
+            .filter(standup_entry::Column::PersonId.eq(person_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+}
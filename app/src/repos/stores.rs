@@ -0,0 +1,131 @@
+//! Repositories behind traits, so handlers can be exercised against the in-memory
+//! fakes of `repos::memory` instead of a database. `AppState` holds them as
+//! `Stores`; more repos move behind a trait as their handlers need tests.
+
+use std::sync::Arc;
+
+use axum::async_trait;
+use sea_orm::{prelude::DateTime, DatabaseConnection, DbErr};
+
+use crate::{
+    models::{
+        person::Model as Person,
+        task::{Model as Task, TaskStatus},
+    },
+    repos::{
+        memory::{InMemoryPersons, InMemoryTasks},
+        persons::PersonsRepo,
+        tasks::TasksRepo,
+    },
+    utils::pagination::{Page, Pagination},
+};
+
+#[async_trait]
+pub trait TasksStore: Send + Sync {
+    async fn get(&self, task_id: String) -> Result<Task, DbErr>;
+
+    /// Tasks assigned to a person, newest first, and how many there are in all
+    async fn get_assigned_page(
+        &self,
+        person_id: String,
+        status: Option<TaskStatus>,
+        workspace_id: Option<String>,
+        pagination: &Pagination,
+    ) -> Result<(Page<Task>, u64), DbErr>;
+
+    /// Tasks the person assigned, newest first
+    async fn get_initiated_by(&self, person_id: String) -> Result<Vec<Task>, DbErr>;
+
+    /// Tasks assigned to the person by someone else, newest first
+    async fn get_assigned_by_others(&self, person_id: String) -> Result<Vec<Task>, DbErr>;
+
+    async fn set_due_at(&self, task: Task, due_at: Option<DateTime>) -> Result<Task, DbErr>;
+
+    async fn set_project(&self, task: Task, project_id: Option<String>) -> Result<Task, DbErr>;
+}
+
+#[async_trait]
+pub trait PersonsStore: Send + Sync {
+    async fn get_by_id(&self, id: String) -> Result<Person, DbErr>;
+
+    async fn get_by_email(&self, email: String) -> Result<Person, DbErr>;
+
+    /// Look a person up by Slack member id
+    async fn get_by_external_id(&self, external_id: String) -> Result<Person, DbErr>;
+}
+
+#[async_trait]
+impl TasksStore for TasksRepo {
+    async fn get(&self, task_id: String) -> Result<Task, DbErr> {
+        TasksRepo::get(self, task_id).await
+    }
+
+    async fn get_assigned_page(
+        &self,
+        person_id: String,
+        status: Option<TaskStatus>,
+        workspace_id: Option<String>,
+        pagination: &Pagination,
+    ) -> Result<(Page<Task>, u64), DbErr> {
+        TasksRepo::get_assigned_page(self, person_id, status, workspace_id, pagination).await
+    }
+
+    async fn get_initiated_by(&self, person_id: String) -> Result<Vec<Task>, DbErr> {
+        TasksRepo::get_initiated_by(self, person_id).await
+    }
+
+    async fn get_assigned_by_others(&self, person_id: String) -> Result<Vec<Task>, DbErr> {
+        TasksRepo::get_assigned_by_others(self, person_id).await
+    }
+
+    async fn set_due_at(&self, task: Task, due_at: Option<DateTime>) -> Result<Task, DbErr> {
+        TasksRepo::set_due_at(self, task, due_at).await
+    }
+
+    async fn set_project(&self, task: Task, project_id: Option<String>) -> Result<Task, DbErr> {
+        TasksRepo::set_project(self, task, project_id).await
+    }
+}
+
+#[async_trait]
+impl PersonsStore for PersonsRepo {
+    async fn get_by_id(&self, id: String) -> Result<Person, DbErr> {
+        PersonsRepo::get_by_id(self, id).await
+    }
+
+    async fn get_by_email(&self, email: String) -> Result<Person, DbErr> {
+        PersonsRepo::get_by_email(self, email).await
+    }
+
+    async fn get_by_external_id(&self, external_id: String) -> Result<Person, DbErr> {
+        PersonsRepo::get_by_external_id(self, external_id).await
+    }
+}
+
+/// The repositories handlers reach through `AppState`
+#[derive(Clone)]
+pub struct Stores {
+    pub tasks: Arc<dyn TasksStore>,
+    pub persons: Arc<dyn PersonsStore>,
+}
+
+impl Stores {
+    /// Stores backed by a database
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            tasks: Arc::new(TasksRepo::new(db.clone())),
+            persons: Arc::new(PersonsRepo::new(db)),
+        }
+    }
+
+    /// Stores that keep everything in memory, for tests
+    pub fn in_memory(tasks: Arc<InMemoryTasks>, persons: Arc<InMemoryPersons>) -> Self {
+        Self { tasks, persons }
+    }
+}
+
+impl std::fmt::Debug for Stores {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Stores").finish_non_exhaustive()
+    }
+}
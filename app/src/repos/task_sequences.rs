@@ -0,0 +1,86 @@
+use sea_orm::{
+    sea_query::{Expr, OnConflict},
+    ActiveValue::Set,
+    DatabaseConnection, DbErr, EntityTrait,
+};
+
+use crate::models::task_sequence::{self, ActiveModel, Entity as TaskSequenceEntity};
+
+pub struct TaskSequencesRepo {
+    db: DatabaseConnection,
+}
+
+impl TaskSequencesRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Next key of the workspace, e.g. `ACME-42`. One statement bumps the counter,
+    /// so concurrent tasks never share a number. `prefix` only applies to the
+    /// workspace's first key.
+    pub async fn next_key(&self, workspace_id: &str, prefix: &str) -> Result<String, DbErr> {
+        let mut keys = self.next_keys(workspace_id, prefix, 1).await?;
+        Ok(keys.remove(0))
+    }
+
+    /// Make sure keys handed out later come after `number`, e.g. once tasks were
+    /// restored with their keys
+    pub async fn raise_to(
+        &self,
+        workspace_id: &str,
+        prefix: &str,
+        number: i32,
+    ) -> Result<(), DbErr> {
+        let last_number = Expr::col((TaskSequenceEntity, task_sequence::Column::LastNumber));
+        TaskSequenceEntity::insert(ActiveModel {
+            workspace_id: Set(workspace_id.to_string()),
+            prefix: Set(prefix.to_string()),
+            last_number: Set(number),
+        })
+        .on_conflict(
+            OnConflict::column(task_sequence::Column::WorkspaceId)
+                .value(
+                    task_sequence::Column::LastNumber,
+                    Expr::case(last_number.clone().lt(number), number).finally(last_number),
+                )
+                .to_owned(),
+        )
+        .exec_without_returning(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The next `count` keys of the workspace, taken in one statement like `next_key`
+    pub async fn next_keys(
+        &self,
+        workspace_id: &str,
+        prefix: &str,
+        count: usize,
+    ) -> Result<Vec<String>, DbErr> {
+        if count == 0 {
+            return Ok(vec![]);
+        }
+
+        let count = count as i32;
+        let sequence = TaskSequenceEntity::insert(ActiveModel {
+            workspace_id: Set(workspace_id.to_string()),
+            prefix: Set(prefix.to_string()),
+            last_number: Set(count),
+        })
+        .on_conflict(
+            OnConflict::column(task_sequence::Column::WorkspaceId)
+                .value(
+                    task_sequence::Column::LastNumber,
+                    Expr::col((TaskSequenceEntity, task_sequence::Column::LastNumber)).add(count),
+                )
+                .to_owned(),
+        )
+        .exec_with_returning(&self.db)
+        .await?;
+
+        Ok((sequence.last_number - count + 1..=sequence.last_number)
+            .map(|number| format!("{}-{}", sequence.prefix, number))
+            .collect())
+    }
+}
@@ -0,0 +1,111 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder,
+};
+
+use crate::{
+    models::{
+        task::TaskPriority,
+        task_template::{self, ActiveModel, Entity as TaskTemplateEntity, Model as TaskTemplate},
+    },
+    utils::crypto::generate_uuid,
+};
+
+/// What a template gives the tasks made from it
+#[derive(Debug, Clone)]
+pub struct TemplateFields {
+    pub name: String,
+    pub title_pattern: String,
+    pub labels: Vec<String>,
+    pub priority: Option<TaskPriority>,
+    pub checklist: Vec<String>,
+}
+
+pub struct TaskTemplatesRepo {
+    db: DatabaseConnection,
+}
+
+impl TaskTemplatesRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        workspace_id: &str,
+        fields: TemplateFields,
+    ) -> Result<TaskTemplate, DbErr> {
+        ActiveModel {
+            id: Set(generate_uuid()),
+            workspace_id: Set(workspace_id.to_string()),
+            name: Set(fields.name),
+            title_pattern: Set(fields.title_pattern),
+            labels: Set(serde_json::json!(fields.labels)),
+            priority: Set(fields.priority),
+            checklist: Set(serde_json::json!(fields.checklist)),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    pub async fn get_by_id(&self, template_id: &str) -> Result<Option<TaskTemplate>, DbErr> {
+        TaskTemplateEntity::find_by_id(template_id)
+            .one(&self.db)
+            .await
+    }
+
+    /// A template of the workspace; None for unknown ids and templates of other workspaces
+    pub async fn get(
+        &self,
+        workspace_id: &str,
+        template_id: &str,
+    ) -> Result<Option<TaskTemplate>, DbErr> {
+        TaskTemplateEntity::find_by_id(template_id)
+            .filter(task_template::Column::WorkspaceId.eq(workspace_id))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn get_by_name(
+        &self,
+        workspace_id: &str,
+        name: &str,
+    ) -> Result<Option<TaskTemplate>, DbErr> {
+        TaskTemplateEntity::find()
+            .filter(task_template::Column::WorkspaceId.eq(workspace_id))
+            .filter(task_template::Column::Name.eq(name))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn get_by_workspace(&self, workspace_id: &str) -> Result<Vec<TaskTemplate>, DbErr> {
+        TaskTemplateEntity::find()
+            .filter(task_template::Column::WorkspaceId.eq(workspace_id))
+            .order_by_asc(task_template::Column::Name)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn update(
+        &self,
+        template: TaskTemplate,
+        fields: TemplateFields,
+    ) -> Result<TaskTemplate, DbErr> {
+        let mut template: ActiveModel = template.into();
+        template.name = Set(fields.name);
+        template.title_pattern = Set(fields.title_pattern);
+        template.labels = Set(serde_json::json!(fields.labels));
+        template.priority = Set(fields.priority);
+        template.checklist = Set(serde_json::json!(fields.checklist));
+        template.update(&self.db).await
+    }
+
+    /// Delete a template; tasks made from it stay as they are
+    pub async fn delete(&self, template: TaskTemplate) -> Result<(), DbErr> {
+        TaskTemplateEntity::delete_by_id(template.id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use sea_orm::{
+    prelude::DateTime, sea_query::OnConflict, ColumnTrait, Condition, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter, QueryOrder,
+};
+
+use crate::models::{
+    task::Model as Task,
+    task_tombstone::{self, ActiveModel, Entity as TaskTombstoneEntity, Model as TaskTombstone},
+};
+
+pub struct TaskTombstonesRepo {
+    db: DatabaseConnection,
+}
+
+impl TaskTombstonesRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Leave tombstones for tasks about to be deleted
+    pub async fn bury(&self, tasks: &[Task]) -> Result<(), DbErr> {
+        if tasks.is_empty() {
+            return Ok(());
+        }
+
+        TaskTombstoneEntity::insert_many(tasks.iter().map(ActiveModel::of))
+            .on_conflict(
+                OnConflict::column(task_tombstone::Column::TaskId)
+                    .update_column(task_tombstone::Column::DeletedAt)
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Tasks a person was assigned or had assigned to someone that were deleted
+    /// after `since`, oldest first
+    pub async fn get_for_person_since(
+        &self,
+        person_id: &str,
+        since: DateTime,
+    ) -> Result<Vec<TaskTombstone>, DbErr> {
+        TaskTombstoneEntity::find()
+            .filter(task_tombstone::Column::DeletedAt.gt(since))
+            .filter(
+                Condition::any()
+                    .add(task_tombstone::Column::AssignedTo.eq(person_id))
+                    .add(task_tombstone::Column::AssignedBy.eq(person_id)),
+            )
+            .order_by_asc(task_tombstone::Column::DeletedAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// Tasks of a workspace deleted after `since`, oldest first
+    pub async fn get_for_workspace_since(
+        &self,
+        workspace_id: &str,
+        since: DateTime,
+    ) -> Result<Vec<TaskTombstone>, DbErr> {
+        TaskTombstoneEntity::find()
+            .filter(task_tombstone::Column::DeletedAt.gt(since))
+            .filter(task_tombstone::Column::WorkspaceId.eq(workspace_id))
+            .order_by_asc(task_tombstone::Column::DeletedAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// Delete the tombstones of every workspace left before `cutoff`
+    pub async fn delete_before(&self, cutoff: DateTime) -> Result<u64, DbErr> {
+        let result = TaskTombstoneEntity::delete_many()
+            .filter(task_tombstone::Column::DeletedAt.lt(cutoff))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
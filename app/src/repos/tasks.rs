@@ -2,15 +2,27 @@ use crate::{
     models::{
         message::Model as Message,
         person::Model as Person,
-        task::{self, ActiveModel, Entity as TaskEntity, Model as Task, TaskStatus},
+        task::{self, ActiveModel, Entity as TaskEntity, Model as Task, TaskPriority, TaskStatus},
+        task_tombstone::{self, Entity as TaskTombstoneEntity},
+    },
+    utils::{
+        crypto::generate_uuid,
+        pagination::{Cursor, Page, Pagination},
     },
-    utils::crypto::generate_uuid,
 };
 use sea_orm::{
-    prelude::DateTime, ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr,
-    EntityTrait, QueryFilter,
+    prelude::DateTime,
+    sea_query::{Expr, OnConflict},
+    ActiveModelTrait,
+    ActiveValue::Set,
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    TransactionTrait,
 };
 
+/// Rows per multi-row insert; keeps statements well under the bind parameter
+/// limits of SQLite and Postgres
+pub const BULK_INSERT_BATCH: usize = 500;
+
 pub struct TasksRepo {
     pub db: DatabaseConnection,
 }
@@ -27,18 +39,133 @@ impl TasksRepo {
         assigned_by: Option<Person>,
         created_at: DateTime,
         message: Message,
+        workspace_id: &str,
+        key: String,
     ) -> Result<Task, DbErr> {
-        let task_model = ActiveModel {
+        let task_model = Self::new_model(
+            status,
+            &assigned_to,
+            assigned_by.as_ref(),
+            created_at,
+            &message,
+            workspace_id,
+            key,
+        );
+        let task = task_model.insert(&self.db).await?;
+
+        Ok(task)
+    }
+
+    /// Row of a new task, for `create_many`
+    pub fn new_model(
+        status: TaskStatus,
+        assigned_to: &Person,
+        assigned_by: Option<&Person>,
+        created_at: DateTime,
+        message: &Message,
+        workspace_id: &str,
+        key: String,
+    ) -> ActiveModel {
+        ActiveModel {
             id: Set(generate_uuid()),
+            completed_at: Set((status == TaskStatus::Completed).then_some(created_at)),
             status: Set(status),
             assigned_to: Set(assigned_to.id.clone()),
-            assigned_by: Set(assigned_by.map(|p| p.id)),
+            assigned_by: Set(assigned_by.map(|p| p.id.clone())),
             created_at: Set(created_at),
             message_id: Set(message.id.clone()),
-        };
-        let task = task_model.insert(&self.db).await?;
+            workspace_id: Set(Some(workspace_id.to_string())),
+            jira_issue_key: Set(None),
+            linear_issue_id: Set(None),
+            due_at: Set(None),
+            notion_page_id: Set(None),
+            priority: Set(None),
+            labels: Set(None),
+            project_id: Set(None),
+            orphaned_at: Set(None),
+            key: Set(Some(key)),
+            version: Set(0),
+            updated_at: Set(chrono::Utc::now().naive_utc()),
+        }
+    }
 
-        Ok(task)
+    /// Insert many tasks with multi-row inserts of `BULK_INSERT_BATCH` rows. Rows
+    /// that conflict with stored ones are skipped; the tasks inserted are returned.
+    pub async fn create_many(&self, tasks: Vec<ActiveModel>) -> Result<Vec<Task>, DbErr> {
+        let mut created = Vec::with_capacity(tasks.len());
+        for batch in tasks.chunks(BULK_INSERT_BATCH) {
+            let ids: Vec<String> = batch.iter().map(|task| task.id.clone().unwrap()).collect();
+            TaskEntity::insert_many(batch.to_vec())
+                .on_conflict(OnConflict::new().do_nothing().to_owned())
+                .exec_without_returning(&self.db)
+                .await?;
+            created.extend(
+                TaskEntity::find()
+                    .filter(task::Column::Id.is_in(ids))
+                    .all(&self.db)
+                    .await?,
+            );
+        }
+
+        Ok(created)
+    }
+
+    /// Store tasks read from a backup as they are, with multi-row inserts of
+    /// `BULK_INSERT_BATCH` rows. Tasks conflicting with stored ones are skipped;
+    /// returns how many were inserted.
+    pub async fn restore_many(&self, tasks: Vec<Task>) -> Result<u64, DbErr> {
+        let mut inserted = 0;
+        for batch in tasks.chunks(BULK_INSERT_BATCH) {
+            let models = batch
+                .iter()
+                .map(|task| ActiveModel::from(task.clone()).reset_all());
+            inserted += TaskEntity::insert_many(models)
+                .on_conflict(OnConflict::new().do_nothing().to_owned())
+                .exec_without_returning(&self.db)
+                .await?;
+        }
+
+        Ok(inserted)
+    }
+
+    /// Tasks made from any of the messages
+    pub async fn get_by_message_ids(&self, message_ids: Vec<String>) -> Result<Vec<Task>, DbErr> {
+        if message_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        TaskEntity::find()
+            .filter(task::Column::MessageId.is_in(message_ids))
+            .all(&self.db)
+            .await
+    }
+
+    /// Write the set fields of a task and bump its version in the same statement,
+    /// so a write based on a stale copy still moves the version forward. With
+    /// `expected`, the write only happens while the task is at that version, and
+    /// fails with `DbErr::RecordNotUpdated` once it moved on.
+    async fn update(&self, task: ActiveModel, expected: Option<i32>) -> Result<Task, DbErr> {
+        let task_id = task.id.clone().unwrap();
+        let mut query = TaskEntity::update_many()
+            .set(task)
+            .col_expr(
+                task::Column::Version,
+                Expr::col((TaskEntity, task::Column::Version)).add(1),
+            )
+            .col_expr(
+                task::Column::UpdatedAt,
+                Expr::value(chrono::Utc::now().naive_utc()),
+            )
+            .filter(task::Column::Id.eq(task_id));
+        if let Some(version) = expected {
+            query = query.filter(task::Column::Version.eq(version));
+        }
+
+        match query.exec_with_returning(&self.db).await?.pop() {
+            Some(task) => Ok(task),
+            None if expected.is_some() => Err(DbErr::RecordNotUpdated),
+            None => Err(DbErr::RecordNotFound("Task was not found.".to_string())),
+        }
     }
 
     pub async fn get(&self, task_id: String) -> Result<Task, DbErr> {
@@ -53,21 +180,87 @@ impl TasksRepo {
     pub async fn get_assigned(&self, person_id: String) -> Result<Vec<Task>, DbErr> {
         let tasks = TaskEntity::find()
             .filter(task::Column::AssignedTo.eq(&person_id))
+            .order_by_desc(task::Column::CreatedAt)
             .all(&self.db)
             .await?;
 
         Ok(tasks)
     }
 
-    pub async fn change_status(&self, task_id: String, status: TaskStatus) -> Result<Task, DbErr> {
+    /// Tasks assigned to a person in one workspace, newest first
+    pub async fn get_assigned_in_workspace(
+        &self,
+        person_id: &str,
+        workspace_id: &str,
+        status: Option<TaskStatus>,
+    ) -> Result<Vec<Task>, DbErr> {
+        let mut query = TaskEntity::find()
+            .filter(task::Column::AssignedTo.eq(person_id))
+            .filter(task::Column::WorkspaceId.eq(workspace_id));
+        if let Some(status) = status {
+            query = query.filter(task::Column::Status.eq(status));
+        }
+
+        query
+            .order_by_desc(task::Column::CreatedAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// A page of tasks assigned to a person, newest first, with the total match count
+    pub async fn get_assigned_page(
+        &self,
+        person_id: String,
+        status: Option<TaskStatus>,
+        workspace_id: Option<String>,
+        pagination: &Pagination,
+    ) -> Result<(Page<Task>, u64), DbErr> {
+        let mut query = TaskEntity::find().filter(task::Column::AssignedTo.eq(&person_id));
+        if let Some(status) = status {
+            query = query.filter(task::Column::Status.eq(status));
+        }
+        if let Some(workspace_id) = workspace_id {
+            query = query.filter(task::Column::WorkspaceId.eq(workspace_id));
+        }
+
+        let total = query.clone().count(&self.db).await?;
+        let tasks = pagination
+            .apply(query, task::Column::CreatedAt, task::Column::Id)
+            .all(&self.db)
+            .await?;
+
+        Ok((
+            pagination.page(tasks, |t| Cursor::new(t.created_at, &t.id)),
+            total,
+        ))
+    }
+
+    /// Move a task to `status`. With `expected`, only while the task is at that
+    /// version, see `update`
+    pub async fn change_status(
+        &self,
+        task_id: String,
+        status: TaskStatus,
+        expected: Option<i32>,
+    ) -> Result<Task, DbErr> {
         let task = TaskEntity::find_by_id(&task_id)
             .one(&self.db)
             .await?
             .ok_or(DbErr::RecordNotFound("Task was not found.".to_string()))?;
+        if expected.is_some_and(|version| version != task.version) {
+            return Err(DbErr::RecordNotUpdated);
+        }
+
+        let completed_at = match status {
+            TaskStatus::Completed if task.status == TaskStatus::Completed => task.completed_at,
+            TaskStatus::Completed => Some(chrono::Utc::now().naive_utc()),
+            _ => None,
+        };
 
         let mut task: ActiveModel = task.into();
         task.status = Set(status);
-        let updated_task = task.update(&self.db).await?;
+        task.completed_at = Set(completed_at);
+        let updated_task = self.update(task, expected).await?;
 
         Ok(updated_task)
     }
@@ -84,11 +277,253 @@ impl TasksRepo {
 
         let mut task: ActiveModel = task.into();
         task.assigned_by = Set(assigned_by);
-        let updated_task = task.update(&self.db).await?;
+        let updated_task = self.update(task, None).await?;
 
         Ok(updated_task)
     }
 
+    pub async fn set_jira_issue_key(&self, task: Task, issue_key: String) -> Result<Task, DbErr> {
+        let mut task: ActiveModel = task.into();
+        task.jira_issue_key = Set(Some(issue_key));
+        self.update(task, None).await
+    }
+
+    pub async fn set_linear_issue_id(&self, task: Task, issue_id: String) -> Result<Task, DbErr> {
+        let mut task: ActiveModel = task.into();
+        task.linear_issue_id = Set(Some(issue_id));
+        self.update(task, None).await
+    }
+
+    pub async fn set_notion_page_id(&self, task: Task, page_id: String) -> Result<Task, DbErr> {
+        let mut task: ActiveModel = task.into();
+        task.notion_page_id = Set(Some(page_id));
+        self.update(task, None).await
+    }
+
+    /// Give a new task the defaults of its channel's rule
+    pub async fn set_rule_defaults(
+        &self,
+        task: Task,
+        priority: Option<TaskPriority>,
+        labels: Vec<String>,
+        project_id: Option<String>,
+    ) -> Result<Task, DbErr> {
+        let mut task: ActiveModel = task.into();
+        task.priority = Set(priority);
+        task.labels = Set((!labels.is_empty()).then(|| serde_json::json!(labels)));
+        task.project_id = Set(project_id);
+        self.update(task, None).await
+    }
+
+    pub async fn set_project(&self, task: Task, project_id: Option<String>) -> Result<Task, DbErr> {
+        let mut task: ActiveModel = task.into();
+        task.project_id = Set(project_id);
+        self.update(task, None).await
+    }
+
+    /// Completed tasks of a workspace not exported to Notion yet, in completion order
+    pub async fn get_completed_not_exported(&self, workspace_id: &str) -> Result<Vec<Task>, DbErr> {
+        TaskEntity::find()
+            .filter(task::Column::WorkspaceId.eq(workspace_id))
+            .filter(task::Column::Status.eq(TaskStatus::Completed))
+            .filter(task::Column::NotionPageId.is_null())
+            .order_by_asc(task::Column::CompletedAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// Tasks of a workspace in a status, optionally only those of one assignee and
+    /// leaving one task out
+    pub async fn count_in_status(
+        &self,
+        workspace_id: &str,
+        status: TaskStatus,
+        assigned_to: Option<&str>,
+        excluding: Option<&str>,
+    ) -> Result<u64, DbErr> {
+        let mut query = TaskEntity::find()
+            .filter(task::Column::WorkspaceId.eq(workspace_id))
+            .filter(task::Column::Status.eq(status));
+        if let Some(assigned_to) = assigned_to {
+            query = query.filter(task::Column::AssignedTo.eq(assigned_to));
+        }
+        if let Some(excluding) = excluding {
+            query = query.filter(task::Column::Id.ne(excluding));
+        }
+        query.count(&self.db).await
+    }
+
+    /// Open tasks of a workspace created from any of the given messages
+    pub async fn get_open_by_messages(
+        &self,
+        workspace_id: &str,
+        message_ids: Vec<String>,
+    ) -> Result<Vec<Task>, DbErr> {
+        if message_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        TaskEntity::find()
+            .filter(task::Column::WorkspaceId.eq(workspace_id))
+            .filter(task::Column::MessageId.is_in(message_ids))
+            .filter(task::Column::Status.ne(TaskStatus::Completed))
+            .order_by_desc(task::Column::CreatedAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// Copy what a merged duplicate knew that the task it is merged into does not:
+    /// issue links, the due date, priority, project and labels
+    pub async fn absorb(&self, task: Task, duplicate: &Task) -> Result<Task, DbErr> {
+        let mut labels = task.labels();
+        for label in duplicate.labels() {
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+
+        let mut merged: ActiveModel = task.clone().into();
+        merged.jira_issue_key = Set(task.jira_issue_key.or(duplicate.jira_issue_key.clone()));
+        merged.linear_issue_id = Set(task.linear_issue_id.or(duplicate.linear_issue_id.clone()));
+        merged.notion_page_id = Set(task.notion_page_id.or(duplicate.notion_page_id.clone()));
+        merged.due_at = Set(task.due_at.or(duplicate.due_at));
+        merged.priority = Set(task.priority.or(duplicate.priority.clone()));
+        merged.project_id = Set(task.project_id.or(duplicate.project_id.clone()));
+        merged.labels = Set((!labels.is_empty()).then(|| serde_json::json!(labels)));
+        self.update(merged, None).await
+    }
+
+    /// Open tasks of a person in a workspace
+    pub async fn get_open_assigned_in_workspace(
+        &self,
+        person_id: &str,
+        workspace_id: &str,
+    ) -> Result<Vec<Task>, DbErr> {
+        TaskEntity::find()
+            .filter(task::Column::AssignedTo.eq(person_id))
+            .filter(task::Column::WorkspaceId.eq(workspace_id))
+            .filter(task::Column::Status.is_in([TaskStatus::InProgress, TaskStatus::Blocked]))
+            .order_by_asc(task::Column::CreatedAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// Open tasks of a workspace whose assignee left, oldest first
+    pub async fn get_orphaned(&self, workspace_id: &str) -> Result<Vec<Task>, DbErr> {
+        TaskEntity::find()
+            .filter(task::Column::WorkspaceId.eq(workspace_id))
+            .filter(task::Column::OrphanedAt.is_not_null())
+            .filter(task::Column::Status.ne(TaskStatus::Completed))
+            .order_by_asc(task::Column::OrphanedAt)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn set_orphaned(&self, task: Task) -> Result<Task, DbErr> {
+        let mut task: ActiveModel = task.into();
+        task.orphaned_at = Set(Some(chrono::Utc::now().naive_utc()));
+        self.update(task, None).await
+    }
+
+    /// Give a task to someone else; it is no longer orphaned
+    pub async fn reassign(&self, task: Task, assigned_to: &str) -> Result<Task, DbErr> {
+        let mut task: ActiveModel = task.into();
+        task.assigned_to = Set(assigned_to.to_string());
+        task.orphaned_at = Set(None);
+        self.update(task, None).await
+    }
+
+    /// Delete a task, leaving a tombstone for boards polling for changes
+    pub async fn delete(&self, task: &Task) -> Result<(), DbErr> {
+        let txn = self.db.begin().await?;
+        TaskTombstoneEntity::insert(task_tombstone::ActiveModel::of(task))
+            .on_conflict(
+                OnConflict::column(task_tombstone::Column::TaskId)
+                    .update_column(task_tombstone::Column::DeletedAt)
+                    .to_owned(),
+            )
+            .exec_without_returning(&txn)
+            .await?;
+        TaskEntity::delete_by_id(&task.id).exec(&txn).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    pub async fn set_due_at(&self, task: Task, due_at: Option<DateTime>) -> Result<Task, DbErr> {
+        let mut task: ActiveModel = task.into();
+        task.due_at = Set(due_at);
+        self.update(task, None).await
+    }
+
+    /// Tasks assigned to a person that have a due date, soonest first
+    pub async fn get_assigned_with_due_date(&self, person_id: &str) -> Result<Vec<Task>, DbErr> {
+        TaskEntity::find()
+            .filter(task::Column::AssignedTo.eq(person_id))
+            .filter(task::Column::DueAt.is_not_null())
+            .order_by_asc(task::Column::DueAt)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn get_by_jira_issue_key(
+        &self,
+        workspace_id: &str,
+        issue_key: &str,
+    ) -> Result<Option<Task>, DbErr> {
+        TaskEntity::find()
+            .filter(task::Column::WorkspaceId.eq(workspace_id))
+            .filter(task::Column::JiraIssueKey.eq(issue_key))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn get_by_linear_issue_id(
+        &self,
+        workspace_id: &str,
+        issue_id: &str,
+    ) -> Result<Option<Task>, DbErr> {
+        TaskEntity::find()
+            .filter(task::Column::WorkspaceId.eq(workspace_id))
+            .filter(task::Column::LinearIssueId.eq(issue_id))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn get_by_key(&self, key: &str) -> Result<Option<Task>, DbErr> {
+        TaskEntity::find()
+            .filter(task::Column::Key.eq(key))
+            .one(&self.db)
+            .await
+    }
+
+    /// Tasks holding any of the keys
+    pub async fn get_by_keys(&self, keys: Vec<String>) -> Result<Vec<Task>, DbErr> {
+        if keys.is_empty() {
+            return Ok(vec![]);
+        }
+
+        TaskEntity::find()
+            .filter(task::Column::Key.is_in(keys))
+            .all(&self.db)
+            .await
+    }
+
+    /// Tasks of a workspace that have no key yet, oldest first
+    pub async fn get_without_key(&self) -> Result<Vec<Task>, DbErr> {
+        TaskEntity::find()
+            .filter(task::Column::Key.is_null())
+            .filter(task::Column::WorkspaceId.is_not_null())
+            .order_by_asc(task::Column::CreatedAt)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn set_key(&self, task: Task, key: String) -> Result<Task, DbErr> {
+        let mut task: ActiveModel = task.into();
+        task.key = Set(Some(key));
+        self.update(task, None).await
+    }
+
     pub async fn get_task_by_message_id(&self, message_id: String) -> Result<Task, DbErr> {
         let task = TaskEntity::find()
             .filter(task::Column::MessageId.eq(&message_id))
@@ -101,6 +536,31 @@ impl TasksRepo {
         }
     }
 
+    pub async fn get_by_workspace(&self, workspace_id: String) -> Result<Vec<Task>, DbErr> {
+        TaskEntity::find()
+            .filter(task::Column::WorkspaceId.eq(&workspace_id))
+            .all(&self.db)
+            .await
+    }
+
+    /// A page of a workspace's tasks, newest first
+    pub async fn get_workspace_page(
+        &self,
+        workspace_id: &str,
+        pagination: &Pagination,
+    ) -> Result<Page<Task>, DbErr> {
+        let tasks = pagination
+            .apply(
+                TaskEntity::find().filter(task::Column::WorkspaceId.eq(workspace_id)),
+                task::Column::CreatedAt,
+                task::Column::Id,
+            )
+            .all(&self.db)
+            .await?;
+
+        Ok(pagination.page(tasks, |t| Cursor::new(t.created_at, &t.id)))
+    }
+
     pub async fn get_all_tasks(&self) -> Result<Vec<Task>, DbErr> {
         let tasks = TaskEntity::find().all(&self.db).await?;
 
@@ -119,6 +579,7 @@ impl TasksRepo {
     pub async fn get_initiated_by(&self, person_id: String) -> Result<Vec<Task>, DbErr> {
         let tasks = TaskEntity::find()
             .filter(task::Column::AssignedBy.eq(person_id))
+            .order_by_desc(task::Column::CreatedAt)
             .all(&self.db)
             .await?;
 
@@ -132,9 +593,32 @@ impl TasksRepo {
             .filter(task::Column::AssignedTo.eq(&person_id))
             .filter(task::Column::AssignedBy.is_not_null())
             .filter(task::Column::AssignedBy.ne(&person_id))
+            .order_by_desc(task::Column::CreatedAt)
             .all(&self.db)
             .await?;
 
         Ok(tasks)
     }
+
+    /// Tasks of every workspace in a status
+    pub async fn count_in_workspace(&self, workspace_id: &str) -> Result<u64, DbErr> {
+        TaskEntity::find()
+            .filter(task::Column::WorkspaceId.eq(workspace_id))
+            .count(&self.db)
+            .await
+    }
+
+    pub async fn count_by_status(&self, status: TaskStatus) -> Result<u64, DbErr> {
+        TaskEntity::find()
+            .filter(task::Column::Status.eq(status))
+            .count(&self.db)
+            .await
+    }
+
+    pub async fn count_created_since(&self, since: DateTime) -> Result<u64, DbErr> {
+        TaskEntity::find()
+            .filter(task::Column::CreatedAt.gte(since))
+            .count(&self.db)
+            .await
+    }
 }
@@ -0,0 +1,76 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter,
+};
+
+use crate::{
+    models::workspace_credential::{
+        self, ActiveModel, Entity as WorkspaceCredentialEntity, Model as WorkspaceCredential,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct WorkspaceCredentialsRepo {
+    db: DatabaseConnection,
+}
+
+impl WorkspaceCredentialsRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn get_by_workspace(&self, workspace_id: &str) -> Result<WorkspaceCredential, DbErr> {
+        WorkspaceCredentialEntity::find()
+            .filter(workspace_credential::Column::WorkspaceId.eq(workspace_id))
+            .one(&self.db)
+            .await?
+            .ok_or(DbErr::RecordNotFound(
+                "Workspace credentials not found".to_string(),
+            ))
+    }
+
+    /// Store already-encrypted tokens, replacing any existing ones. `enterprise_id`
+    /// is set for org-wide tokens of an Enterprise Grid install.
+    pub async fn upsert(
+        &self,
+        workspace_id: &str,
+        app_token: String,
+        bot_token: String,
+        enterprise_id: Option<String>,
+    ) -> Result<WorkspaceCredential, DbErr> {
+        let now = chrono::Utc::now().naive_utc();
+
+        match self.get_by_workspace(workspace_id).await {
+            Ok(existing) => {
+                let mut credential: ActiveModel = existing.into();
+                credential.app_token = Set(app_token);
+                credential.bot_token = Set(bot_token);
+                credential.enterprise_id = Set(enterprise_id);
+                credential.updated_at = Set(now);
+                credential.update(&self.db).await
+            }
+            Err(DbErr::RecordNotFound(_)) => {
+                let credential = ActiveModel {
+                    id: Set(generate_uuid()),
+                    workspace_id: Set(workspace_id.to_string()),
+                    app_token: Set(app_token),
+                    bot_token: Set(bot_token),
+                    enterprise_id: Set(enterprise_id),
+                    updated_at: Set(now),
+                };
+                credential.insert(&self.db).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Remove stored tokens, returns whether any existed
+    pub async fn delete_by_workspace(&self, workspace_id: &str) -> Result<bool, DbErr> {
+        let result = WorkspaceCredentialEntity::delete_many()
+            .filter(workspace_credential::Column::WorkspaceId.eq(workspace_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -1,14 +1,18 @@
 use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
-    PaginatorTrait, QueryFilter, QueryOrder,
+    sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr,
+    EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
 };
 
 use crate::{
     models::person::{Entity as PersonEntity, Model as Person},
+    models::workspace::Entity as WorkspaceEntity,
     models::workspace_link::{
-        self, ActiveModel, Entity as WorkspaceLinkEntity, Model as WorkspaceLink,
+        self, ActiveModel, Entity as WorkspaceLinkEntity, Model as WorkspaceLink, WorkspaceRole,
+    },
+    utils::{
+        crypto::generate_uuid,
+        pagination::{Cursor, Page, Pagination},
     },
-    utils::crypto::generate_uuid,
 };
 
 pub struct WorkspaceLinksRepo {
@@ -23,15 +27,17 @@ impl WorkspaceLinksRepo {
     pub async fn create(
         &self,
         person_id: String,
-        workspace_name: String,
+        workspace_id: String,
     ) -> Result<WorkspaceLink, DbErr> {
         let link_model = ActiveModel {
             id: Set(generate_uuid()),
             person_id: Set(person_id),
-            workspace_name: Set(workspace_name),
+            workspace_id: Set(workspace_id),
             slack_member_id: Set(None),
+            slack_team_id: Set(None),
             is_linked: Set(false),
             is_active: Set(false),
+            role: Set(WorkspaceRole::Member),
             created_at: Set(chrono::Utc::now().naive_utc()),
             updated_at: Set(None),
         };
@@ -52,11 +58,11 @@ impl WorkspaceLinksRepo {
     pub async fn get_by_person_and_workspace(
         &self,
         person_id: String,
-        workspace_name: String,
+        workspace_id: String,
     ) -> Result<WorkspaceLink, DbErr> {
         let link = WorkspaceLinkEntity::find()
             .filter(workspace_link::Column::PersonId.eq(&person_id))
-            .filter(workspace_link::Column::WorkspaceName.eq(&workspace_name))
+            .filter(workspace_link::Column::WorkspaceId.eq(&workspace_id))
             .one(&self.db)
             .await?;
 
@@ -68,21 +74,29 @@ impl WorkspaceLinksRepo {
         }
     }
 
+    /// Link a person to a workspace as `slack_member_id`, keeping the workspace's
+    /// Slack team on the link
     pub async fn link_workspace(
         &self,
         person_id: String,
-        workspace_name: String,
+        workspace_id: String,
         slack_member_id: String,
     ) -> Result<WorkspaceLink, DbErr> {
+        let slack_team_id = WorkspaceEntity::find_by_id(&workspace_id)
+            .one(&self.db)
+            .await?
+            .and_then(|workspace| workspace.slack_team_id);
+
         // Try to get existing link
         match self
-            .get_by_person_and_workspace(person_id.clone(), workspace_name.clone())
+            .get_by_person_and_workspace(person_id.clone(), workspace_id.clone())
             .await
         {
             Ok(link) => {
                 // Update existing
                 let mut link: ActiveModel = link.into();
                 link.slack_member_id = Set(Some(slack_member_id));
+                link.slack_team_id = Set(slack_team_id);
                 link.is_linked = Set(true);
                 link.updated_at = Set(Some(chrono::Utc::now().naive_utc()));
                 link.update(&self.db).await
@@ -95,10 +109,12 @@ impl WorkspaceLinksRepo {
                 let link_model = ActiveModel {
                     id: Set(generate_uuid()),
                     person_id: Set(person_id),
-                    workspace_name: Set(workspace_name),
+                    workspace_id: Set(workspace_id),
                     slack_member_id: Set(Some(slack_member_id)),
+                    slack_team_id: Set(slack_team_id),
                     is_linked: Set(true),
                     is_active: Set(is_first), // Auto-activate if first workspace
+                    role: Set(WorkspaceRole::Member),
                     created_at: Set(chrono::Utc::now().naive_utc()),
                     updated_at: Set(None),
                 };
@@ -107,13 +123,22 @@ impl WorkspaceLinksRepo {
         }
     }
 
+    pub async fn delete_by_person(&self, person_id: &str) -> Result<u64, DbErr> {
+        let result = WorkspaceLinkEntity::delete_many()
+            .filter(workspace_link::Column::PersonId.eq(person_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+
     pub async fn unlink_workspace(
         &self,
         person_id: String,
-        workspace_name: String,
+        workspace_id: String,
     ) -> Result<WorkspaceLink, DbErr> {
         let link = self
-            .get_by_person_and_workspace(person_id, workspace_name)
+            .get_by_person_and_workspace(person_id, workspace_id)
             .await?;
 
         let mut link: ActiveModel = link.into();
@@ -123,6 +148,23 @@ impl WorkspaceLinksRepo {
         link.update(&self.db).await
     }
 
+    /// Change what a linked person may do in the workspace
+    pub async fn set_role(
+        &self,
+        person_id: String,
+        workspace_id: String,
+        role: WorkspaceRole,
+    ) -> Result<WorkspaceLink, DbErr> {
+        let link = self
+            .get_by_person_and_workspace(person_id, workspace_id)
+            .await?;
+
+        let mut link: ActiveModel = link.into();
+        link.role = Set(role);
+        link.updated_at = Set(Some(chrono::Utc::now().naive_utc()));
+        link.update(&self.db).await
+    }
+
     pub async fn delete(&self, link_id: String) -> Result<(), DbErr> {
         WorkspaceLinkEntity::delete_by_id(link_id)
             .exec(&self.db)
@@ -148,7 +190,7 @@ impl WorkspaceLinksRepo {
     pub async fn set_active_workspace(
         &self,
         person_id: String,
-        workspace_name: String,
+        workspace_id: String,
     ) -> Result<WorkspaceLink, DbErr> {
         // Deactivate all workspaces for this user
         let all_links = self.get_by_person(person_id.clone()).await?;
@@ -160,7 +202,7 @@ impl WorkspaceLinksRepo {
 
         // Activate the selected workspace
         let link = self
-            .get_by_person_and_workspace(person_id, workspace_name)
+            .get_by_person_and_workspace(person_id, workspace_id)
             .await?;
 
         let mut link: ActiveModel = link.into();
@@ -169,15 +211,15 @@ impl WorkspaceLinksRepo {
         link.update(&self.db).await
     }
 
-    /// Get a workspace link by Slack member ID and workspace name
+    /// Get a workspace link by Slack member ID and workspace
     pub async fn get_by_slack_member_id_and_workspace(
         &self,
         slack_member_id: String,
-        workspace_name: String,
+        workspace_id: String,
     ) -> Result<WorkspaceLink, DbErr> {
         let link = WorkspaceLinkEntity::find()
             .filter(workspace_link::Column::SlackMemberId.eq(Some(slack_member_id)))
-            .filter(workspace_link::Column::WorkspaceName.eq(&workspace_name))
+            .filter(workspace_link::Column::WorkspaceId.eq(&workspace_id))
             .filter(workspace_link::Column::IsLinked.eq(true))
             .one(&self.db)
             .await?;
@@ -193,10 +235,10 @@ impl WorkspaceLinksRepo {
     /// Get all links for a workspace (used to find all users in a workspace)
     pub async fn get_by_workspace(
         &self,
-        workspace_name: String,
+        workspace_id: String,
     ) -> Result<Vec<WorkspaceLink>, DbErr> {
         let links = WorkspaceLinkEntity::find()
-            .filter(workspace_link::Column::WorkspaceName.eq(&workspace_name))
+            .filter(workspace_link::Column::WorkspaceId.eq(&workspace_id))
             .filter(workspace_link::Column::IsLinked.eq(true))
             .order_by_desc(workspace_link::Column::CreatedAt)
             .all(&self.db)
@@ -205,40 +247,195 @@ impl WorkspaceLinksRepo {
         Ok(links)
     }
 
-    /// Get paginated users for a workspace with their person details
-    pub async fn get_workspace_users_paginated(
+    /// Count all links of a workspace, linked or not
+    pub async fn count_by_workspace(&self, workspace_id: String) -> Result<u64, DbErr> {
+        WorkspaceLinkEntity::find()
+            .filter(workspace_link::Column::WorkspaceId.eq(&workspace_id))
+            .count(&self.db)
+            .await
+    }
+
+    /// Unlink and deactivate every link of a workspace, returns the number of links changed
+    pub async fn unlink_all(&self, workspace_id: String) -> Result<u64, DbErr> {
+        let result = WorkspaceLinkEntity::update_many()
+            .col_expr(workspace_link::Column::IsLinked, Expr::value(false))
+            .col_expr(workspace_link::Column::IsActive, Expr::value(false))
+            .col_expr(
+                workspace_link::Column::UpdatedAt,
+                Expr::value(Some(chrono::Utc::now().naive_utc())),
+            )
+            .filter(workspace_link::Column::WorkspaceId.eq(&workspace_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// Every linked member of a workspace with their person details; viewers are
+    /// left out as they own no tasks
+    pub async fn get_workspace_users(
         &self,
-        workspace_name: String,
-        page: u64,
-        per_page: u64,
-    ) -> Result<(Vec<(WorkspaceLink, Person)>, u64), DbErr> {
-        // Get total count
-        let total = WorkspaceLinkEntity::find()
-            .filter(workspace_link::Column::WorkspaceName.eq(&workspace_name))
+        workspace_id: &str,
+    ) -> Result<Vec<(WorkspaceLink, Person)>, DbErr> {
+        let rows = WorkspaceLinkEntity::find()
+            .find_also_related(PersonEntity)
+            .filter(workspace_link::Column::WorkspaceId.eq(workspace_id))
             .filter(workspace_link::Column::IsLinked.eq(true))
-            .count(&self.db)
+            .filter(workspace_link::Column::Role.eq(WorkspaceRole::Member))
+            .order_by_asc(workspace_link::Column::CreatedAt)
+            .all(&self.db)
             .await?;
 
-        // Get paginated links
+        Ok(rows
+            .into_iter()
+            .filter_map(|(link, person)| Some((link, person?)))
+            .collect())
+    }
+
+    /// A page of linked users of a workspace with their person details, most recently
+    /// linked first, and how many there are in all
+    pub async fn get_workspace_users_page(
+        &self,
+        workspace_id: String,
+        pagination: &Pagination,
+    ) -> Result<(Page<(WorkspaceLink, Person)>, u64), DbErr> {
+        let linked = WorkspaceLinkEntity::find()
+            .filter(workspace_link::Column::WorkspaceId.eq(&workspace_id))
+            .filter(workspace_link::Column::IsLinked.eq(true));
+        let total = linked.clone().count(&self.db).await?;
+
+        // Links joined with their persons in one query
+        let rows = pagination
+            .apply(
+                linked.find_also_related(PersonEntity),
+                workspace_link::Column::CreatedAt,
+                workspace_link::Column::Id,
+            )
+            .all(&self.db)
+            .await?;
+        let page = pagination.page(rows, |(link, _)| Cursor::new(link.created_at, &link.id));
+
+        Ok((
+            Page {
+                items: page
+                    .items
+                    .into_iter()
+                    .filter_map(|(link, person)| Some((link, person?)))
+                    .collect(),
+                next_cursor: page.next_cursor,
+            },
+            total,
+        ))
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::Database;
+
+    use super::*;
+    use crate::repos::{persons::PersonsRepo, workspaces::WorkspacesRepo};
+
+    #[tokio::test]
+    async fn workspace_users_page_matches_per_link_lookup() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+
+        let workspaces_repo = WorkspacesRepo::new(db.clone());
+        let workspace = workspaces_repo
+            .create("acme".to_string(), None, None)
+            .await
+            .unwrap();
+        let other = workspaces_repo
+            .create("other".to_string(), None, None)
+            .await
+            .unwrap();
+
+        let persons_repo = PersonsRepo::new(db.clone());
+        let links_repo = WorkspaceLinksRepo::new(db.clone());
+        for i in 0..5 {
+            let person = persons_repo
+                .create(
+                    format!("user{i}"),
+                    false,
+                    format!("U{i}"),
+                    format!("user{i}@example.com"),
+                )
+                .await
+                .unwrap();
+            links_repo
+                .link_workspace(person.id.clone(), workspace.id.clone(), format!("U{i}"))
+                .await
+                .unwrap();
+            links_repo
+                .link_workspace(person.id, other.id.clone(), format!("X{i}"))
+                .await
+                .unwrap();
+        }
+
+        // Members who never linked their Slack account are left out
+        let pending = persons_repo
+            .create(
+                "pending".to_string(),
+                false,
+                "UP".to_string(),
+                "pending@example.com".to_string(),
+            )
+            .await
+            .unwrap();
+        links_repo
+            .create(pending.id, workspace.id.clone())
+            .await
+            .unwrap();
+
+        // Walk the pages by cursor
+        let mut users = Vec::new();
+        let mut pagination = Pagination::first(2);
+        loop {
+            let (page, total) = links_repo
+                .get_workspace_users_page(workspace.id.clone(), &pagination)
+                .await
+                .unwrap();
+            assert_eq!(total, 5);
+            assert!(page.items.len() <= 2);
+            users.extend(page.items);
+            match page.next_cursor {
+                Some(cursor) => pagination.after = Cursor::decode(&cursor),
+                None => break,
+            }
+        }
+
+        // One person lookup per link, in cursor order
         let links = WorkspaceLinkEntity::find()
-            .filter(workspace_link::Column::WorkspaceName.eq(&workspace_name))
+            .filter(workspace_link::Column::WorkspaceId.eq(&workspace.id))
             .filter(workspace_link::Column::IsLinked.eq(true))
             .order_by_desc(workspace_link::Column::CreatedAt)
-            .paginate(&self.db, per_page)
-            .fetch_page(page)
-            .await?;
-
-        // Fetch persons for each link
-        let mut results: Vec<(WorkspaceLink, Person)> = Vec::new();
+            .order_by_desc(workspace_link::Column::Id)
+            .all(&db)
+            .await
+            .unwrap();
+        let mut expected = Vec::new();
         for link in links {
-            if let Ok(Some(person)) = PersonEntity::find_by_id(&link.person_id)
-                .one(&self.db)
+            let person = PersonEntity::find_by_id(&link.person_id)
+                .one(&db)
                 .await
-            {
-                results.push((link, person));
-            }
+                .unwrap()
+                .unwrap();
+            expected.push((link, person));
         }
 
-        Ok((results, total))
+        assert_eq!(users, expected);
+    }
+
+    /// People linked to at least one workspace
+    pub async fn count_linked_people(&self) -> Result<u64, DbErr> {
+        WorkspaceLinkEntity::find()
+            .filter(workspace_link::Column::IsLinked.eq(true))
+            .select_only()
+            .column(workspace_link::Column::PersonId)
+            .distinct()
+            .count(&self.db)
+            .await
     }
 }
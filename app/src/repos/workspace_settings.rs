@@ -3,9 +3,14 @@ use sea_orm::{
 };
 use serde_json::json;
 
-use crate::models::workspace_settings::{
-    ActiveModel, Column, EmojiMappings, Entity as WorkspaceSettingsEntity,
-    Model as WorkspaceSettings,
+use crate::{
+    i18n::Locale,
+    models::workspace_settings::{
+        ActiveModel, BusinessHours, Column, DiscordSettings, EmojiMappings,
+        Entity as WorkspaceSettingsEntity, KeywordTrigger, LinearSettings, MessageRedaction,
+        Model as WorkspaceSettings, NotificationRoute, NotificationTemplate, PrivateChannelPolicy,
+        ReactionPolicy, StandupSettings, StatusResolution, TeamsSettings, WipLimit,
+    },
 };
 
 pub struct WorkspaceSettingsRepo {
@@ -19,16 +24,16 @@ impl WorkspaceSettingsRepo {
 
     pub async fn get_by_workspace(
         &self,
-        workspace_name: &str,
+        workspace_id: &str,
     ) -> Result<Option<WorkspaceSettings>, DbErr> {
         WorkspaceSettingsEntity::find()
-            .filter(Column::WorkspaceName.eq(workspace_name))
+            .filter(Column::WorkspaceId.eq(workspace_id))
             .one(&self.db)
             .await
     }
 
-    pub async fn get_or_create(&self, workspace_name: &str) -> Result<WorkspaceSettings, DbErr> {
-        if let Some(settings) = self.get_by_workspace(workspace_name).await? {
+    pub async fn get_or_create(&self, workspace_id: &str) -> Result<WorkspaceSettings, DbErr> {
+        if let Some(settings) = self.get_by_workspace(workspace_id).await? {
             return Ok(settings);
         }
 
@@ -39,8 +44,24 @@ impl WorkspaceSettingsRepo {
 
         let model = ActiveModel {
             id: Set(id),
-            workspace_name: Set(workspace_name.to_string()),
+            workspace_id: Set(workspace_id.to_string()),
             emoji_mappings: Set(json!(default_mappings)),
+            linear: Set(None),
+            discord: Set(None),
+            teams: Set(None),
+            inbound_email_token: Set(None),
+            keyword_triggers: Set(None),
+            wip_limits: Set(None),
+            notification_routes: Set(None),
+            notification_templates: Set(None),
+            standup: Set(None),
+            business_hours: Set(None),
+            auto_join_channels: Set(None),
+            message_redaction: Set(MessageRedaction::None),
+            status_resolution: Set(StatusResolution::CompletedWins),
+            reaction_policy: Set(ReactionPolicy::Anyone),
+            private_channel_policy: Set(PrivateChannelPolicy::Allow),
+            locale: Set(Locale::En),
             created_at: Set(now),
             updated_at: Set(now),
         };
@@ -50,25 +71,408 @@ impl WorkspaceSettingsRepo {
 
     pub async fn update_emoji_mappings(
         &self,
-        workspace_name: &str,
+        workspace_id: &str,
         mappings: EmojiMappings,
     ) -> Result<WorkspaceSettings, DbErr> {
-        let settings = self.get_or_create(workspace_name).await?;
-        let now = chrono::Utc::now().naive_utc();
+        // Each emoji sets one status; callers report overlaps to users before this
+        if let Some((emoji, statuses)) = mappings.overlaps().first() {
+            return Err(DbErr::Custom(format!(
+                "Emoji '{}' is mapped to several statuses: {:?}",
+                emoji, statuses
+            )));
+        }
+        let settings = self.get_or_create(workspace_id).await?;
 
-        let model = ActiveModel {
-            id: Set(settings.id),
-            workspace_name: Set(workspace_name.to_string()),
-            emoji_mappings: Set(json!(mappings)),
-            created_at: Set(settings.created_at),
-            updated_at: Set(now),
-        };
+        let mut model: ActiveModel = settings.into();
+        model.emoji_mappings = Set(json!(mappings));
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    /// Connect the workspace to Linear, or disconnect it with `None`
+    pub async fn update_linear(
+        &self,
+        workspace_id: &str,
+        linear: Option<LinearSettings>,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.linear = Set(linear.map(|linear| json!(linear)));
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    pub async fn get_linear_settings(
+        &self,
+        workspace_id: &str,
+    ) -> Result<Option<LinearSettings>, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .and_then(|settings| settings.get_linear_settings()))
+    }
+
+    /// Have a Discord channel follow the workspace, or stop with `None`
+    pub async fn update_discord(
+        &self,
+        workspace_id: &str,
+        discord: Option<DiscordSettings>,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.discord = Set(discord.map(|discord| json!(discord)));
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    pub async fn get_discord_settings(
+        &self,
+        workspace_id: &str,
+    ) -> Result<Option<DiscordSettings>, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .and_then(|settings| settings.get_discord_settings()))
+    }
+
+    /// Have a Teams channel follow the workspace, or stop with `None`
+    pub async fn update_teams(
+        &self,
+        workspace_id: &str,
+        teams: Option<TeamsSettings>,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.teams = Set(teams.map(|teams| json!(teams)));
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    pub async fn get_teams_settings(
+        &self,
+        workspace_id: &str,
+    ) -> Result<Option<TeamsSettings>, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .and_then(|settings| settings.get_teams_settings()))
+    }
+
+    /// Run a daily standup in the workspace, or stop with `None`
+    pub async fn update_standup(
+        &self,
+        workspace_id: &str,
+        standup: Option<StandupSettings>,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.standup = Set(standup.map(|standup| json!(standup)));
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    pub async fn get_standup_settings(
+        &self,
+        workspace_id: &str,
+    ) -> Result<Option<StandupSettings>, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .and_then(|settings| settings.get_standup_settings()))
+    }
+
+    /// Count time in the workspace in working hours only, or in wall-clock time
+    /// again with `None`
+    pub async fn update_business_hours(
+        &self,
+        workspace_id: &str,
+        business_hours: Option<BusinessHours>,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.business_hours = Set(business_hours.map(|hours| json!(hours)));
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    pub async fn get_business_hours(
+        &self,
+        workspace_id: &str,
+    ) -> Result<Option<BusinessHours>, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .and_then(|settings| settings.get_business_hours()))
+    }
+
+    /// Give the workspace a new inbound email address, or turn email-in off with `None`
+    pub async fn set_inbound_email_token(
+        &self,
+        workspace_id: &str,
+        token: Option<String>,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.inbound_email_token = Set(token);
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    pub async fn update_keyword_triggers(
+        &self,
+        workspace_id: &str,
+        triggers: Vec<KeywordTrigger>,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.keyword_triggers = Set((!triggers.is_empty()).then(|| json!(triggers)));
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    pub async fn get_keyword_triggers(
+        &self,
+        workspace_id: &str,
+    ) -> Result<Vec<KeywordTrigger>, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .map(|settings| settings.get_keyword_triggers())
+            .unwrap_or_default())
+    }
+
+    /// Public channels the bot joins on setup and whenever it connects
+    pub async fn update_auto_join_channels(
+        &self,
+        workspace_id: &str,
+        channels: Vec<String>,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.auto_join_channels = Set((!channels.is_empty()).then(|| json!(channels)));
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    pub async fn get_auto_join_channels(&self, workspace_id: &str) -> Result<Vec<String>, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .map(|settings| settings.get_auto_join_channels())
+            .unwrap_or_default())
+    }
+
+    pub async fn update_wip_limits(
+        &self,
+        workspace_id: &str,
+        limits: Vec<WipLimit>,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.wip_limits = Set((!limits.is_empty()).then(|| json!(limits)));
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    pub async fn get_wip_limits(&self, workspace_id: &str) -> Result<Vec<WipLimit>, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .map(|settings| settings.get_wip_limits())
+            .unwrap_or_default())
+    }
+
+    pub async fn update_notification_routes(
+        &self,
+        workspace_id: &str,
+        routes: Vec<NotificationRoute>,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.notification_routes = Set((!routes.is_empty()).then(|| json!(routes)));
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    pub async fn get_notification_routes(
+        &self,
+        workspace_id: &str,
+    ) -> Result<Vec<NotificationRoute>, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .map(|settings| settings.get_notification_routes())
+            .unwrap_or_default())
+    }
+
+    pub async fn update_notification_templates(
+        &self,
+        workspace_id: &str,
+        templates: Vec<NotificationTemplate>,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.notification_templates = Set((!templates.is_empty()).then(|| json!(templates)));
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    pub async fn get_notification_templates(
+        &self,
+        workspace_id: &str,
+    ) -> Result<Vec<NotificationTemplate>, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .map(|settings| settings.get_notification_templates())
+            .unwrap_or_default())
+    }
+
+    pub async fn update_message_redaction(
+        &self,
+        workspace_id: &str,
+        redaction: MessageRedaction,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.message_redaction = Set(redaction);
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    pub async fn get_message_redaction(
+        &self,
+        workspace_id: &str,
+    ) -> Result<MessageRedaction, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .map(|settings| settings.message_redaction)
+            .unwrap_or_default())
+    }
+
+    pub async fn update_status_resolution(
+        &self,
+        workspace_id: &str,
+        resolution: StatusResolution,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.status_resolution = Set(resolution);
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    pub async fn get_status_resolution(
+        &self,
+        workspace_id: &str,
+    ) -> Result<StatusResolution, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .map(|settings| settings.status_resolution)
+            .unwrap_or_default())
+    }
+
+    pub async fn update_reaction_policy(
+        &self,
+        workspace_id: &str,
+        policy: ReactionPolicy,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.reaction_policy = Set(policy);
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
+
+    pub async fn get_reaction_policy(&self, workspace_id: &str) -> Result<ReactionPolicy, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .map(|settings| settings.reaction_policy)
+            .unwrap_or_default())
+    }
+
+    pub async fn update_private_channel_policy(
+        &self,
+        workspace_id: &str,
+        policy: PrivateChannelPolicy,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.private_channel_policy = Set(policy);
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
+        model.update(&self.db).await
+    }
 
+    pub async fn get_private_channel_policy(
+        &self,
+        workspace_id: &str,
+    ) -> Result<PrivateChannelPolicy, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .map(|settings| settings.private_channel_policy)
+            .unwrap_or_default())
+    }
+
+    pub async fn update_locale(
+        &self,
+        workspace_id: &str,
+        locale: Locale,
+    ) -> Result<WorkspaceSettings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
+
+        let mut model: ActiveModel = settings.into();
+        model.locale = Set(locale);
+        model.updated_at = Set(chrono::Utc::now().naive_utc());
         model.update(&self.db).await
     }
 
-    pub async fn get_emoji_mappings(&self, workspace_name: &str) -> Result<EmojiMappings, DbErr> {
-        let settings = self.get_or_create(workspace_name).await?;
+    pub async fn get_locale(&self, workspace_id: &str) -> Result<Locale, DbErr> {
+        Ok(self
+            .get_by_workspace(workspace_id)
+            .await?
+            .map(|settings| settings.locale)
+            .unwrap_or_default())
+    }
+
+    pub async fn get_by_inbound_email_token(
+        &self,
+        token: &str,
+    ) -> Result<Option<WorkspaceSettings>, DbErr> {
+        WorkspaceSettingsEntity::find()
+            .filter(Column::InboundEmailToken.eq(token))
+            .one(&self.db)
+            .await
+    }
+
+    /// Settings of every workspace connected to Linear
+    pub async fn get_all_with_linear(&self) -> Result<Vec<WorkspaceSettings>, DbErr> {
+        WorkspaceSettingsEntity::find()
+            .filter(Column::Linear.is_not_null())
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn get_emoji_mappings(&self, workspace_id: &str) -> Result<EmojiMappings, DbErr> {
+        let settings = self.get_or_create(workspace_id).await?;
         Ok(settings.get_emoji_mappings())
     }
 }
@@ -0,0 +1,120 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder,
+};
+
+use crate::{
+    models::workspace::{
+        self, ActiveModel, Entity as WorkspaceEntity, Model as Workspace, WorkspaceStatus,
+    },
+    utils::crypto::generate_uuid,
+};
+
+pub struct WorkspacesRepo {
+    db: DatabaseConnection,
+}
+
+impl WorkspacesRepo {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        name: String,
+        slack_team_id: Option<String>,
+        created_by: Option<String>,
+    ) -> Result<Workspace, DbErr> {
+        let workspace = ActiveModel {
+            id: Set(generate_uuid()),
+            name: Set(name),
+            slack_team_id: Set(slack_team_id),
+            created_by: Set(created_by),
+            status: Set(WorkspaceStatus::Active),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        };
+
+        workspace.insert(&self.db).await
+    }
+
+    pub async fn get(&self, workspace_id: &str) -> Result<Workspace, DbErr> {
+        WorkspaceEntity::find_by_id(workspace_id)
+            .one(&self.db)
+            .await?
+            .ok_or(DbErr::RecordNotFound("Workspace not found".to_string()))
+    }
+
+    pub async fn get_by_name(&self, name: &str) -> Result<Workspace, DbErr> {
+        WorkspaceEntity::find()
+            .filter(workspace::Column::Name.eq(name))
+            .one(&self.db)
+            .await?
+            .ok_or(DbErr::RecordNotFound(format!(
+                "Workspace '{}' not found",
+                name
+            )))
+    }
+
+    /// The active workspace set up for a Slack team
+    pub async fn get_active_by_slack_team_id(&self, team_id: &str) -> Result<Workspace, DbErr> {
+        WorkspaceEntity::find()
+            .filter(workspace::Column::SlackTeamId.eq(team_id))
+            .filter(workspace::Column::Status.eq(WorkspaceStatus::Active))
+            .one(&self.db)
+            .await?
+            .ok_or(DbErr::RecordNotFound(format!(
+                "No workspace for Slack team {}",
+                team_id
+            )))
+    }
+
+    /// Every workspace, archived ones included, by name
+    pub async fn get_all(&self) -> Result<Vec<Workspace>, DbErr> {
+        WorkspaceEntity::find()
+            .order_by_asc(workspace::Column::Name)
+            .all(&self.db)
+            .await
+    }
+
+    /// Get all active workspaces, oldest first
+    pub async fn get_active(&self) -> Result<Vec<Workspace>, DbErr> {
+        WorkspaceEntity::find()
+            .filter(workspace::Column::Status.eq(WorkspaceStatus::Active))
+            .order_by_asc(workspace::Column::CreatedAt)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn set_status(
+        &self,
+        workspace_id: &str,
+        status: WorkspaceStatus,
+    ) -> Result<Workspace, DbErr> {
+        let workspace = self.get(workspace_id).await?;
+
+        let mut workspace: ActiveModel = workspace.into();
+        workspace.status = Set(status);
+        workspace.update(&self.db).await
+    }
+
+    /// Delete a workspace; credentials, links and settings cascade
+    pub async fn delete(&self, workspace_id: &str) -> Result<(), DbErr> {
+        WorkspaceEntity::delete_by_id(workspace_id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Record the Slack team ID once it has been resolved
+    pub async fn set_slack_team_id(
+        &self,
+        workspace_id: &str,
+        slack_team_id: String,
+    ) -> Result<Workspace, DbErr> {
+        let workspace = self.get(workspace_id).await?;
+
+        let mut workspace: ActiveModel = workspace.into();
+        workspace.slack_team_id = Set(Some(slack_team_id));
+        workspace.update(&self.db).await
+    }
+}
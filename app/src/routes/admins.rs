@@ -7,7 +7,9 @@ use axum::{
 
 use crate::{
     core::state::AppState,
-    handlers::admins::{check_permissions, invite_admin, list_admins, revoke_admin},
+    handlers::admins::{
+        check_permissions, invite_admin, list_admins, list_audit_logs, revoke_admin,
+    },
 };
 
 pub fn admin_routes() -> Router<Arc<AppState>> {
@@ -16,4 +18,5 @@ pub fn admin_routes() -> Router<Arc<AppState>> {
         .route("/", get(list_admins))
         .route("/invite", post(invite_admin))
         .route("/revoke", post(revoke_admin))
+        .route("/audit-logs", get(list_audit_logs))
 }
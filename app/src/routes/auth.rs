@@ -4,11 +4,14 @@ use axum::{routing::get, Router};
 
 use crate::{
     core::state::AppState,
-    handlers::auth::{google_callback, google_login},
+    handlers::auth::{auth_providers, google_callback, google_login, slack_callback, slack_login},
 };
 
 pub fn auth_routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/google", get(google_login))
         .route("/google/callback", get(google_callback))
+        .route("/slack", get(slack_login))
+        .route("/slack/callback", get(slack_callback))
+        .route("/providers", get(auth_providers))
 }
@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{
+    core::state::AppState,
+    handlers::bots::{list_bots, reconnect_bot},
+};
+
+pub fn bot_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_bots))
+        .route("/:workspace_name/reconnect", post(reconnect_bot))
+}
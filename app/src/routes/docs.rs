@@ -0,0 +1,475 @@
+use std::sync::Arc;
+
+use axum::Router;
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::{
+    core::{
+        bot_status::BotStatus, maintenance::Maintenance, metrics::RouteMetrics,
+        reload::ReloadReport, state::AppState,
+    },
+    database::connect::PoolMetrics,
+    handlers::{
+        admins, auth, availability, backup, board_views, bots, business_hours, channel_rules,
+        channels, database_backups, discord, email, github, hooks, invitations, jira, linear,
+        maintenance, me, notification_templates, notion, orphaned_tasks, projects, reminders,
+        retention, schedules, sessions, sla_policies, slack_status, standups, task_import,
+        task_templates, tasks, teams, workload, workspaces,
+    },
+    i18n::Locale,
+    models::{
+        audit_log::Model as AuditLog,
+        availability::Model as Availability,
+        board_view::{BoardViewFilter, Model as BoardView},
+        bot_event::{BotEventOutcome, Model as BotEvent},
+        bot_incident::{BotIncidentKind, Model as BotIncident},
+        change::Model as Change,
+        github_link::{GithubLinkKind, Model as GithubLink},
+        hook_subscription::{HookEvent, Model as HookSubscription},
+        invitation::{InvitationStatus, Model as Invitation},
+        message::Model as Message,
+        notification_setting::{
+            Model as NotificationSettings, NotificationChannel, NotificationKind,
+        },
+        person::Model as Person,
+        project::Model as Project,
+        reminder::Model as Reminder,
+        retention_policy::{Model as RetentionPolicy, RetentionMode},
+        scheduled_job::{Model as ScheduledJob, ScheduledJobKind},
+        sla_policy::Model as SlaPolicy,
+        standup_entry::Model as StandupEntry,
+        task::Model as Task,
+        task::{TaskPriority, TaskStatus},
+        task_template::Model as TaskTemplate,
+        workspace_link::{Model as WorkspaceLink, WorkspaceRole},
+        workspace_settings::{
+            BusinessHours, EmojiMappings, KeywordTrigger, MessageRedaction, NotificationRoute,
+            NotificationTemplate, PrivateChannelPolicy, ReactionPolicy, StandupSettings,
+            StatusResolution, TriggerMatch, WipLimit, WipMode, WipScope,
+        },
+    },
+    services::{
+        backup::BackupImportReport,
+        database_backups::{BackupFile, RestoreReport},
+        diagnostics::DiagnosticCheck,
+        hooks::HookPayload,
+        privacy::{DataExport, ErasureReport},
+        retention::RetentionReport,
+        task_import::{ImportRow, ImportedRow},
+        workload::MemberWorkload,
+        workspace::{BotIdentity, OffboardMode, OffboardReport},
+    },
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Slacker API",
+        description = "Slack reaction-driven task board. Authenticated POST requests take an \
+            `Idempotency-Key` header: retries with the same key get the first response back \
+            instead of running again."
+    ),
+    paths(
+        tasks::get_my_tasks,
+        tasks::get_tasks_board,
+        tasks::get_board_changes,
+        tasks::get_task_detail,
+        tasks::get_task_full,
+        tasks::get_all_my_tasks,
+        tasks::get_task_by_key,
+        tasks::update_task,
+        tasks::update_task_due_date,
+        tasks::merge_task,
+        tasks::update_task_project,
+        task_templates::create_task_from_template,
+        reminders::create_reminder,
+        workspaces::list_workspaces,
+        workspaces::link_workspace,
+        workspaces::unlink_workspace,
+        workspaces::switch_workspace,
+        workspaces::get_active_workspace,
+        workspaces::setup_workspace,
+        workspaces::delete_workspace,
+        workspaces::get_workspace_settings,
+        workspaces::update_workspace_tokens,
+        workspaces::get_workspace_diagnostics,
+        workspaces::get_workspace_emojis,
+        workspaces::update_emoji_mappings,
+        workspaces::reset_emoji_mappings,
+        workspaces::get_keyword_triggers,
+        workspaces::update_keyword_triggers,
+        workspaces::get_wip_limits,
+        workspaces::update_wip_limits,
+        workspaces::get_notification_routes,
+        workspaces::update_notification_routes,
+        workspaces::update_message_redaction,
+        workspaces::update_status_resolution,
+        workspaces::update_reaction_policy,
+        workspaces::update_private_channel_policy,
+        workspaces::update_workspace_locale,
+        workspaces::get_workspace_users,
+        workspaces::invite_user_to_workspace,
+        workspaces::remove_user_from_workspace,
+        invitations::accept_invitation,
+        admins::check_permissions,
+        admins::list_admins,
+        admins::invite_admin,
+        admins::revoke_admin,
+        admins::list_audit_logs,
+        bots::list_bots,
+        bots::reconnect_bot,
+        bots::reload_config,
+        bots::list_bot_events,
+        bots::replay_bot_event,
+        maintenance::get_maintenance,
+        maintenance::update_maintenance,
+        maintenance::readiness,
+        admins::impersonate,
+        admins::send_test_email,
+        admins::get_overview,
+        admins::get_metrics,
+        backup::export_messages,
+        backup::import_backup,
+        database_backups::list_backups,
+        database_backups::create_backup,
+        database_backups::restore_backup,
+        schedules::list_schedules,
+        schedules::run_schedule,
+        retention::get_retention_policy,
+        retention::update_retention_policy,
+        retention::preview_retention_policy,
+        jira::get_jira_connection,
+        jira::update_jira_connection,
+        jira::delete_jira_connection,
+        jira::create_jira_issue,
+        jira::jira_webhook,
+        email::get_inbound_email_address,
+        email::rotate_inbound_email_address,
+        email::delete_inbound_email_address,
+        email::inbound_email,
+        task_import::import_tasks,
+        linear::get_linear_connection,
+        linear::update_linear_connection,
+        linear::delete_linear_connection,
+        linear::linear_webhook,
+        channels::list_workspace_channels,
+        channels::join_workspace_channels,
+        notification_templates::get_notification_templates,
+        notification_templates::update_notification_templates,
+        notification_templates::preview_notification_template,
+        channel_rules::list_channel_rules,
+        channel_rules::update_channel_rule,
+        channel_rules::delete_channel_rule,
+        projects::list_projects,
+        projects::create_project,
+        projects::update_project,
+        projects::delete_project,
+        task_templates::list_task_templates,
+        task_templates::create_task_template,
+        task_templates::update_task_template,
+        task_templates::delete_task_template,
+        sla_policies::list_sla_policies,
+        sla_policies::create_sla_policy,
+        sla_policies::update_sla_policy,
+        sla_policies::delete_sla_policy,
+        orphaned_tasks::list_orphaned_tasks,
+        orphaned_tasks::assign_orphaned_task,
+        workload::get_workload,
+        workload::suggest_assignee,
+        notion::get_notion_connection,
+        notion::update_notion_connection,
+        notion::delete_notion_connection,
+        notion::export_to_notion,
+        discord::get_discord_connection,
+        discord::update_discord_connection,
+        discord::delete_discord_connection,
+        teams::get_teams_connection,
+        teams::update_teams_connection,
+        teams::delete_teams_connection,
+        standups::get_standup,
+        standups::update_standup,
+        standups::delete_standup,
+        business_hours::get_business_hours,
+        business_hours::update_business_hours,
+        business_hours::delete_business_hours,
+        github::list_github_repositories,
+        github::connect_github_repository,
+        github::disconnect_github_repository,
+        github::github_webhook,
+        hooks::subscribe_hook,
+        hooks::list_hooks,
+        hooks::unsubscribe_hook,
+        hooks::sample_hook,
+        hooks::test_hook,
+        hooks::rotate_hook_secret,
+        auth::google_login,
+        auth::google_callback,
+        auth::slack_login,
+        auth::slack_callback,
+        auth::auth_providers,
+        auth::get_me,
+        me::get_profile,
+        me::update_profile,
+        me::get_notification_settings,
+        me::update_notification_settings,
+        me::export_data,
+        me::delete_account,
+        me::create_calendar_feed,
+        me::delete_calendar_feed,
+        me::calendar_feed,
+        board_views::list_board_views,
+        board_views::create_board_view,
+        board_views::update_board_view,
+        board_views::delete_board_view,
+        reminders::list_reminders,
+        reminders::delete_reminder,
+        availability::list_availability,
+        availability::create_availability,
+        availability::delete_availability,
+        sessions::list_sessions,
+        sessions::revoke_session,
+        slack_status::list_slack_status_settings,
+        slack_status::update_slack_status_setting,
+        slack_status::delete_slack_status_setting,
+    ),
+    components(schemas(
+        Task,
+        TaskStatus,
+        TaskPriority,
+        Change,
+        Person,
+        WorkspaceLink,
+        WorkspaceRole,
+        EmojiMappings,
+        tasks::MessageSummary,
+        tasks::TaskResponse,
+        tasks::TaskListResponse,
+        tasks::TaskBoard,
+        tasks::BoardChanges,
+        tasks::StaleTasks,
+        tasks::MessageDetail,
+        tasks::TaskDetailResponse,
+        tasks::TaskFullResponse,
+        tasks::WorkspaceTaskResponse,
+        tasks::AllMyTasksResponse,
+        tasks::UpdateTaskRequest,
+        tasks::MergeTaskRequest,
+        tasks::UpdateTaskProjectRequest,
+        tasks::UpdateDueDateRequest,
+        workspaces::WorkspaceInfo,
+        workspaces::WorkspaceListResponse,
+        workspaces::LinkWorkspaceRequest,
+        workspaces::LinkWorkspaceResponse,
+        workspaces::SetupWorkspaceRequest,
+        workspaces::SetupWorkspaceResponse,
+        workspaces::DeleteWorkspaceResponse,
+        OffboardMode,
+        OffboardReport,
+        workspaces::WorkspaceSettingsResponse,
+        workspaces::UpdateTokenRequest,
+        workspaces::UpdateTokenResponse,
+        BotIdentity,
+        workspaces::DiagnosticsResponse,
+        DiagnosticCheck,
+        workspaces::EmojiListResponse,
+        workspaces::UpdateEmojiMappingsRequest,
+        workspaces::KeywordTriggersPayload,
+        KeywordTrigger,
+        TriggerMatch,
+        workspaces::WipLimitsPayload,
+        WipLimit,
+        WipScope,
+        WipMode,
+        workspaces::NotificationRoutesPayload,
+        NotificationRoute,
+        NotificationKind,
+        workspaces::MessageRedactionPayload,
+        MessageRedaction,
+        workspaces::StatusResolutionPayload,
+        StatusResolution,
+        workspaces::ReactionPolicyPayload,
+        ReactionPolicy,
+        workspaces::PrivateChannelPolicyPayload,
+        PrivateChannelPolicy,
+        workspaces::LocalePayload,
+        Locale,
+        workspaces::WorkspaceUserInfo,
+        workspaces::WorkspaceUsersResponse,
+        workspaces::InviteUserRequest,
+        workspaces::InviteUserResponse,
+        workspaces::RemoveUserRequest,
+        Invitation,
+        InvitationStatus,
+        admins::AdminInfo,
+        admins::AdminListResponse,
+        admins::PermissionCheckResponse,
+        admins::InviteAdminRequest,
+        admins::InviteAdminResponse,
+        admins::RevokeAdminRequest,
+        admins::ImpersonateRequest,
+        admins::ImpersonateResponse,
+        admins::TestEmailResponse,
+        admins::TaskStatusCounts,
+        admins::ActivityCounts,
+        admins::JobQueueCounts,
+        admins::RecentError,
+        admins::OverviewResponse,
+        admins::MetricsResponse,
+        PoolMetrics,
+        RouteMetrics,
+        admins::AuditLogListResponse,
+        BotStatus,
+        BotIncident,
+        BotIncidentKind,
+        BotEvent,
+        BotEventOutcome,
+        bots::BotEventListResponse,
+        bots::BotInfo,
+        bots::BotListResponse,
+        bots::ReconnectBotResponse,
+        ReloadReport,
+        Maintenance,
+        maintenance::UpdateMaintenanceRequest,
+        maintenance::MaintenanceResponse,
+        ScheduledJob,
+        ScheduledJobKind,
+        schedules::ScheduleListResponse,
+        schedules::RunScheduleResponse,
+        RetentionPolicy,
+        RetentionMode,
+        RetentionReport,
+        retention::UpdateRetentionPolicyRequest,
+        jira::UpdateJiraConnectionRequest,
+        jira::JiraConnectionResponse,
+        jira::JiraIssueResponse,
+        channels::WorkspaceChannel,
+        channels::WorkspaceChannelListResponse,
+        channels::JoinChannelsRequest,
+        channels::JoinChannelsResponse,
+        channels::ChannelJoinFailure,
+        NotificationTemplate,
+        notification_templates::TemplateVariables,
+        notification_templates::NotificationTemplatesRequest,
+        notification_templates::NotificationTemplatesResponse,
+        notification_templates::NotificationTemplatePreview,
+        channel_rules::UpdateChannelRuleRequest,
+        channel_rules::ChannelRuleResponse,
+        channel_rules::ChannelRuleListResponse,
+        projects::ProjectRequest,
+        projects::ProjectListResponse,
+        Project,
+        task_templates::TaskTemplateRequest,
+        task_templates::TaskTemplateListResponse,
+        task_templates::TaskFromTemplateRequest,
+        TaskTemplate,
+        SlaPolicy,
+        sla_policies::SlaPolicyRequest,
+        sla_policies::SlaPolicyListResponse,
+        orphaned_tasks::OrphanedTaskListResponse,
+        orphaned_tasks::AssignOrphanedTaskRequest,
+        workload::WorkloadResponse,
+        workload::SuggestedAssigneeResponse,
+        MemberWorkload,
+        email::InboundEmailAddressResponse,
+        email::InboundEmailResult,
+        task_import::ImportReport,
+        ImportRow,
+        BackupImportReport,
+        BackupFile,
+        RestoreReport,
+        database_backups::BackupListResponse,
+        database_backups::RestoreBackupRequest,
+        ImportedRow,
+        linear::UpdateLinearConnectionRequest,
+        linear::LinearConnectionResponse,
+        notion::UpdateNotionConnectionRequest,
+        notion::NotionConnectionResponse,
+        notion::NotionExportResponse,
+        discord::UpdateDiscordConnectionRequest,
+        discord::DiscordConnectionResponse,
+        teams::UpdateTeamsConnectionRequest,
+        teams::TeamsConnectionResponse,
+        StandupSettings,
+        BusinessHours,
+        StandupEntry,
+        github::ConnectGithubRepositoryRequest,
+        github::GithubRepositoryInfo,
+        github::GithubRepositoryListResponse,
+        github::ConnectGithubRepositoryResponse,
+        github::GithubWebhookResponse,
+        GithubLink,
+        GithubLinkKind,
+        HookSubscription,
+        HookEvent,
+        HookPayload,
+        hooks::SubscribeHookRequest,
+        hooks::HookListResponse,
+        hooks::SubscribeHookResponse,
+        hooks::HookSecretResponse,
+        hooks::HookTestResponse,
+        NotificationSettings,
+        NotificationChannel,
+        me::UpdateProfileRequest,
+        me::UpdateNotificationSettingsRequest,
+        me::CalendarFeedResponse,
+        BoardView,
+        BoardViewFilter,
+        board_views::BoardViewRequest,
+        board_views::BoardViewListResponse,
+        Reminder,
+        reminders::CreateReminderRequest,
+        reminders::ReminderListResponse,
+        Availability,
+        availability::AvailabilityRequest,
+        availability::AvailabilityListResponse,
+        sessions::SessionInfo,
+        sessions::SessionListResponse,
+        auth::AuthProvidersResponse,
+        slack_status::UpdateSlackStatusRequest,
+        slack_status::SlackStatusSettingResponse,
+        slack_status::SlackStatusSettingListResponse,
+        DataExport,
+        ErasureReport,
+        Message,
+        AuditLog,
+    )),
+    modifiers(&BearerAuth),
+    tags(
+        (name = "tasks", description = "Task board and task details"),
+        (name = "workspaces", description = "Workspace linking, settings and users"),
+        (name = "admins", description = "Workspace admin management"),
+        (name = "auth", description = "Google OAuth login and session"),
+        (name = "me", description = "Current user preferences"),
+        (name = "integrations", description = "Webhooks from Jira, Linear, GitHub and inbound email"),
+        (name = "hooks", description = "REST hook subscriptions for Zapier, Make and similar tools"),
+    )
+)]
+pub struct ApiDoc;
+
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// Serves the OpenAPI document at `/api/openapi.json` and Swagger UI at `/api/docs`
+pub fn docs_routes() -> Router<Arc<AppState>> {
+    SwaggerUi::new("/api/docs")
+        .url("/api/openapi.json", ApiDoc::openapi())
+        .into()
+}
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use axum::{
+    routing::{delete, get, post},
+    Router,
+};
+
+use crate::{
+    core::state::AppState,
+    handlers::hooks::{
+        list_hooks, rotate_hook_secret, sample_hook, subscribe_hook, test_hook, unsubscribe_hook,
+    },
+};
+
+pub fn hook_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_hooks))
+        .route("/subscribe", post(subscribe_hook))
+        .route("/sample", get(sample_hook))
+        .route("/:hook_id", delete(unsubscribe_hook))
+        .route("/:hook_id/test", post(test_hook))
+        .route("/:hook_id/secret/rotate", post(rotate_hook_secret))
+}
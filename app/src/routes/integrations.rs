@@ -0,0 +1,20 @@
+use std::sync::Arc;
+
+use axum::{routing::post, Router};
+
+use crate::{
+    core::state::AppState,
+    handlers::{
+        email::inbound_email, github::github_webhook, jira::jira_webhook, linear::linear_webhook,
+    },
+};
+
+/// Webhooks from third-party trackers and the inbound mail provider; each
+/// authenticates with its own secret
+pub fn integration_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/jira/webhook", post(jira_webhook))
+        .route("/github/webhook", post(github_webhook))
+        .route("/linear/webhook", post(linear_webhook))
+        .route("/email/inbound", post(inbound_email))
+}
@@ -0,0 +1,9 @@
+use std::sync::Arc;
+
+use axum::{routing::get, Router};
+
+use crate::{core::state::AppState, handlers::invitations::accept_invitation};
+
+pub fn invitation_routes() -> Router<Arc<AppState>> {
+    Router::new().route("/:token/accept", get(accept_invitation))
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use axum::{
+    routing::{delete, get, post, put},
+    Router,
+};
+
+use crate::{
+    core::state::AppState,
+    handlers::{
+        availability::{create_availability, delete_availability, list_availability},
+        board_views::{create_board_view, delete_board_view, list_board_views, update_board_view},
+        me::{
+            create_calendar_feed, delete_account, delete_calendar_feed, export_data,
+            get_notification_settings, get_profile, update_notification_settings, update_profile,
+        },
+        reminders::{delete_reminder, list_reminders},
+        sessions::{list_sessions, revoke_session},
+        slack_status::{
+            delete_slack_status_setting, list_slack_status_settings, update_slack_status_setting,
+        },
+        tasks::get_all_my_tasks,
+    },
+};
+
+pub fn me_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/",
+            get(get_profile)
+                .patch(update_profile)
+                .delete(delete_account),
+        )
+        .route("/export", get(export_data))
+        .route("/tasks/all", get(get_all_my_tasks))
+        .route(
+            "/calendar-feed",
+            post(create_calendar_feed).delete(delete_calendar_feed),
+        )
+        .route(
+            "/notifications",
+            get(get_notification_settings).put(update_notification_settings),
+        )
+        .route("/views", get(list_board_views).post(create_board_view))
+        .route(
+            "/views/:view_id",
+            put(update_board_view).delete(delete_board_view),
+        )
+        .route("/reminders", get(list_reminders))
+        .route("/reminders/:reminder_id", delete(delete_reminder))
+        .route(
+            "/availability",
+            get(list_availability).post(create_availability),
+        )
+        .route(
+            "/availability/:availability_id",
+            delete(delete_availability),
+        )
+        .route("/sessions", get(list_sessions))
+        .route("/sessions/:session_id", delete(revoke_session))
+        .route("/slack-status", get(list_slack_status_settings))
+        .route(
+            "/slack-status/:workspace_name",
+            put(update_slack_status_setting).delete(delete_slack_status_setting),
+        )
+}
@@ -1,19 +1,52 @@
 pub mod admins;
 pub mod auth;
+pub mod bots;
+pub mod docs;
+pub mod hooks;
+pub mod integrations;
+pub mod invitations;
+pub mod me;
+pub mod schedules;
+#[cfg(feature = "embed-frontend")]
+pub mod spa;
 pub mod tasks;
 pub mod workspaces;
+pub mod ws;
 
 use std::sync::Arc;
 
-use axum::{http::StatusCode, middleware, routing::get, Router};
+use axum::{
+    http::StatusCode,
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use tower_http::compression::CompressionLayer;
+#[cfg(not(feature = "embed-frontend"))]
 use tower_http::services::{ServeDir, ServeFile};
 
 use crate::{
     core::state::AppState,
-    middlewares::auth::require_auth,
+    handlers::{
+        admins::{get_metrics, get_overview, impersonate, send_test_email},
+        backup::{export_messages, import_backup},
+        bots::{list_bot_events, reload_config, replay_bot_event},
+        database_backups::{create_backup, list_backups, restore_backup},
+        maintenance::{get_maintenance, readiness, update_maintenance},
+        me::calendar_feed,
+    },
+    middlewares::{
+        auth::require_auth, board_version::touch_boards_after_writes, cors::cors_layer,
+        idempotency::replay_idempotent_requests, latency::track_latency,
+        maintenance::reject_writes_during_maintenance, viewers::reject_viewer_writes,
+    },
     routes::{
-        admins::admin_routes, auth::auth_routes, tasks::task_routes, workspaces::workspace_routes,
+        admins::admin_routes, auth::auth_routes, bots::bot_routes, docs::docs_routes,
+        hooks::hook_routes, integrations::integration_routes, invitations::invitation_routes,
+        me::me_routes, schedules::schedule_routes, tasks::task_routes,
+        workspaces::workspace_routes, ws::ws_routes,
     },
+    utils::global_error_handler::{global_error_handler, method_not_allowed_handler},
 };
 
 async fn health_check() -> StatusCode {
@@ -23,26 +56,92 @@ async fn health_check() -> StatusCode {
 pub fn create_routers(state: Arc<AppState>) -> Router<()> {
     let public_routes = Router::new()
         .nest("/auth", auth_routes())
-        .route("/health", get(health_check));
+        .nest("/ws", ws_routes())
+        .nest("/invitations", invitation_routes())
+        .nest("/integrations", integration_routes())
+        // Authenticated by its feed token, see `calendar_feed`
+        .route("/me/tasks.ics", get(calendar_feed))
+        .route("/health", get(health_check))
+        .route("/readyz", get(readiness));
 
+    // Viewers see boards but don't change their workspace's tasks or settings
+    let viewer_writes = || middleware::from_fn_with_state(state.clone(), reject_viewer_writes);
     let protected_routes = Router::new()
-        .nest("/tasks", task_routes())
-        .nest("/workspaces", workspace_routes())
+        .nest("/tasks", task_routes().route_layer(viewer_writes()))
+        .nest(
+            "/workspaces",
+            workspace_routes().route_layer(viewer_writes()),
+        )
         .nest("/admins", admin_routes())
+        .nest("/admin/bots", bot_routes())
+        .nest("/admin/schedules", schedule_routes())
+        .route("/admin/impersonate", post(impersonate))
+        .route("/admin/reload-config", post(reload_config))
+        .route("/admin/test-email", post(send_test_email))
+        .route("/admin/overview", get(get_overview))
+        .route("/admin/metrics", get(get_metrics))
+        .route("/admin/export/messages", get(export_messages))
+        .route("/admin/import", post(import_backup))
+        .route("/admin/backups", get(list_backups).post(create_backup))
+        .route("/admin/backups/restore", post(restore_backup))
+        .route("/admin/bot-events", get(list_bot_events))
+        .route("/admin/bot-events/:id/replay", post(replay_bot_event))
+        .route(
+            "/admin/maintenance",
+            get(get_maintenance).post(update_maintenance),
+        )
+        .nest("/me", me_routes())
+        .nest("/hooks", hook_routes().route_layer(viewer_writes()))
         .nest("/auth", protected_auth_routes())
+        // Inside `require_auth`, keys belong to the person sending them
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            replay_idempotent_requests,
+        ))
         .layer(middleware::from_fn_with_state(state.clone(), require_auth));
 
-    // Serve static files from the frontend build directory
-    // Falls back to index.html for SPA routing
+    let cors = cors_layer(&state.config);
+
+    Router::new()
+        .nest(
+            "/api",
+            public_routes
+                .merge(protected_routes)
+                .fallback(global_error_handler)
+                .method_not_allowed_fallback(method_not_allowed_handler)
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    touch_boards_after_writes,
+                ))
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    reject_writes_during_maintenance,
+                ))
+                .layer(middleware::from_fn_with_state(state.clone(), track_latency))
+                // Boards and workload reports of large workspaces get big
+                .layer(CompressionLayer::new()),
+        )
+        .merge(docs_routes())
+        .merge(frontend_routes())
+        .layer(cors)
+        .with_state(state)
+}
+
+/// The frontend compiled into the binary, with index.html for client-side routes
+#[cfg(feature = "embed-frontend")]
+fn frontend_routes() -> Router<Arc<AppState>> {
+    Router::new().fallback(spa::serve_embedded)
+}
+
+/// Serve static files from the frontend build directory
+/// Falls back to index.html for SPA routing
+#[cfg(not(feature = "embed-frontend"))]
+fn frontend_routes() -> Router<Arc<AppState>> {
     let static_dir = std::env::var("STATIC_DIR").unwrap_or_else(|_| "./static".to_string());
     let index_file = format!("{}/index.html", static_dir);
 
-    let serve_dir = ServeDir::new(&static_dir).not_found_service(ServeFile::new(&index_file));
-
     Router::new()
-        .nest("/api", public_routes.merge(protected_routes))
-        .fallback_service(serve_dir)
-        .with_state(state)
+        .fallback_service(ServeDir::new(&static_dir).not_found_service(ServeFile::new(&index_file)))
 }
 
 fn protected_auth_routes() -> Router<Arc<AppState>> {
@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{
+    core::state::AppState,
+    handlers::schedules::{list_schedules, run_schedule},
+};
+
+pub fn schedule_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_schedules))
+        .route("/:name/run", post(run_schedule))
+}
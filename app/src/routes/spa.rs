@@ -0,0 +1,85 @@
+//! Frontend compiled into the binary, for installs that run a single server. Built
+//! with the `embed-frontend` feature after `npm run build` in `frontend/`.
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, HeaderValue, StatusCode, Uri},
+    response::{IntoResponse, Response},
+};
+use rust_embed::{EmbeddedFile, RustEmbed};
+
+#[derive(RustEmbed)]
+#[folder = "../frontend/dist"]
+// Keeps `cargo check --features embed-frontend` working before a frontend build;
+// the server then answers every page with 404
+#[allow_missing = true]
+struct Assets;
+
+const INDEX_FILE: &str = "index.html";
+
+/// Vite fingerprints everything under `assets/`, so those never change
+const IMMUTABLE_PREFIX: &str = "assets/";
+
+fn file_response(path: &str, file: EmbeddedFile, headers: &HeaderMap) -> Response {
+    let etag = format!("\"{}\"", hex::encode(file.metadata.sha256_hash()));
+    let cache_control = if path.starts_with(IMMUTABLE_PREFIX) {
+        "public, max-age=31536000, immutable"
+    } else {
+        // index.html points at the current asset names, always revalidate it
+        "no-cache"
+    };
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag));
+    if not_modified {
+        return (
+            StatusCode::NOT_MODIFIED,
+            [
+                (header::ETAG, etag),
+                (header::CACHE_CONTROL, cache_control.to_string()),
+            ],
+        )
+            .into_response();
+    }
+
+    let content_type = HeaderValue::from_str(file.metadata.mimetype())
+        .unwrap_or(HeaderValue::from_static("application/octet-stream"));
+    (
+        [
+            (header::CONTENT_TYPE, content_type),
+            (
+                header::ETAG,
+                HeaderValue::from_str(&etag).expect("hex etag"),
+            ),
+            (
+                header::CACHE_CONTROL,
+                HeaderValue::from_static(cache_control),
+            ),
+        ],
+        Body::from(file.data.into_owned()),
+    )
+        .into_response()
+}
+
+/// Serve an embedded file, or index.html for client-side routes such as
+/// `/workspaces/acme` so the SPA router can take over
+pub async fn serve_embedded(uri: Uri, headers: HeaderMap) -> Response {
+    let path = uri.path().trim_start_matches('/');
+    let path = if path.is_empty() { INDEX_FILE } else { path };
+
+    if let Some(file) = Assets::get(path) {
+        return file_response(path, file, &headers);
+    }
+
+    // A missing script or image is a real 404, not a page of the app
+    let is_file = path
+        .rsplit('/')
+        .next()
+        .is_some_and(|name| name.contains('.'));
+    match Assets::get(INDEX_FILE) {
+        Some(index) if !is_file => file_response(INDEX_FILE, index, &headers),
+        _ => StatusCode::NOT_FOUND.into_response(),
+    }
+}
@@ -1,15 +1,39 @@
 use std::sync::Arc;
 
-use axum::{routing::get, Router};
+use axum::{
+    routing::{get, post, put},
+    Router,
+};
 
 use crate::{
     core::state::AppState,
-    handlers::tasks::{get_my_tasks, get_task_detail, get_tasks_board},
+    handlers::{
+        jira::create_jira_issue,
+        reminders::create_reminder,
+        task_templates::create_task_from_template,
+        tasks::{
+            get_board_changes, get_my_tasks, get_task_by_key, get_task_detail, get_task_full,
+            get_tasks_board, merge_task, update_task, update_task_due_date, update_task_project,
+        },
+    },
 };
 
 pub fn task_routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(get_my_tasks))
+        .route("/mine", get(get_my_tasks))
         .route("/board", get(get_tasks_board))
-        .route("/:task_id", get(get_task_detail))
+        .route("/board/changes", get(get_board_changes))
+        .route("/key/:key", get(get_task_by_key))
+        .route(
+            "/from-template/:template_id",
+            post(create_task_from_template),
+        )
+        .route("/:task_id", get(get_task_detail).patch(update_task))
+        .route("/:task_id/full", get(get_task_full))
+        .route("/:task_id/jira", post(create_jira_issue))
+        .route("/:task_id/due-date", put(update_task_due_date))
+        .route("/:task_id/project", put(update_task_project))
+        .route("/:task_id/merge", post(merge_task))
+        .route("/:task_id/reminders", post(create_reminder))
 }
@@ -6,11 +6,50 @@ use std::sync::Arc;
 
 use crate::{
     core::state::AppState,
+    handlers::business_hours::{delete_business_hours, get_business_hours, update_business_hours},
+    handlers::channel_rules::{delete_channel_rule, list_channel_rules, update_channel_rule},
+    handlers::channels::{join_workspace_channels, list_workspace_channels},
+    handlers::discord::{
+        delete_discord_connection, get_discord_connection, update_discord_connection,
+    },
+    handlers::email::{
+        delete_inbound_email_address, get_inbound_email_address, rotate_inbound_email_address,
+    },
+    handlers::github::{
+        connect_github_repository, disconnect_github_repository, list_github_repositories,
+    },
+    handlers::jira::{delete_jira_connection, get_jira_connection, update_jira_connection},
+    handlers::linear::{delete_linear_connection, get_linear_connection, update_linear_connection},
+    handlers::notification_templates::{
+        get_notification_templates, preview_notification_template, update_notification_templates,
+    },
+    handlers::notion::{
+        delete_notion_connection, export_to_notion, get_notion_connection, update_notion_connection,
+    },
+    handlers::orphaned_tasks::{assign_orphaned_task, list_orphaned_tasks},
+    handlers::projects::{create_project, delete_project, list_projects, update_project},
+    handlers::retention::{
+        get_retention_policy, preview_retention_policy, update_retention_policy,
+    },
+    handlers::sla_policies::{
+        create_sla_policy, delete_sla_policy, list_sla_policies, update_sla_policy,
+    },
+    handlers::standups::{delete_standup, get_standup, update_standup},
+    handlers::task_import::import_tasks,
+    handlers::task_templates::{
+        create_task_template, delete_task_template, list_task_templates, update_task_template,
+    },
+    handlers::teams::{delete_teams_connection, get_teams_connection, update_teams_connection},
+    handlers::workload::{get_workload, suggest_assignee},
     handlers::workspaces::{
-        get_active_workspace, get_workspace_settings, get_workspace_users,
-        invite_user_to_workspace, link_workspace, list_workspaces, remove_user_from_workspace,
-        reset_emoji_mappings, setup_workspace, switch_workspace, unlink_workspace,
-        update_emoji_mappings, update_workspace_tokens,
+        delete_workspace, get_active_workspace, get_keyword_triggers, get_notification_routes,
+        get_wip_limits, get_workspace_diagnostics, get_workspace_emojis, get_workspace_settings,
+        get_workspace_users, invite_user_to_workspace, link_workspace, list_workspaces,
+        remove_user_from_workspace, reset_emoji_mappings, setup_workspace, switch_workspace,
+        unlink_workspace, update_emoji_mappings, update_keyword_triggers, update_message_redaction,
+        update_notification_routes, update_private_channel_policy, update_reaction_policy,
+        update_status_resolution, update_wip_limits, update_workspace_locale,
+        update_workspace_tokens,
     },
 };
 
@@ -22,9 +61,15 @@ pub fn workspace_routes() -> Router<Arc<AppState>> {
         .route("/switch", post(switch_workspace))
         .route("/active", get(get_active_workspace))
         .route("/setup", post(setup_workspace))
+        .route("/:workspace_name", delete(delete_workspace))
         // Settings routes
         .route("/:workspace_name/settings", get(get_workspace_settings))
         .route("/:workspace_name/tokens", put(update_workspace_tokens))
+        .route(
+            "/:workspace_name/diagnostics",
+            get(get_workspace_diagnostics),
+        )
+        .route("/:workspace_name/emojis", get(get_workspace_emojis))
         .route(
             "/:workspace_name/emoji-mappings",
             put(update_emoji_mappings),
@@ -33,6 +78,148 @@ pub fn workspace_routes() -> Router<Arc<AppState>> {
             "/:workspace_name/emoji-mappings/reset",
             post(reset_emoji_mappings),
         )
+        .route(
+            "/:workspace_name/keyword-triggers",
+            get(get_keyword_triggers).put(update_keyword_triggers),
+        )
+        .route(
+            "/:workspace_name/wip-limits",
+            get(get_wip_limits).put(update_wip_limits),
+        )
+        .route(
+            "/:workspace_name/notification-routes",
+            get(get_notification_routes).put(update_notification_routes),
+        )
+        .route(
+            "/:workspace_name/message-redaction",
+            put(update_message_redaction),
+        )
+        .route(
+            "/:workspace_name/status-resolution",
+            put(update_status_resolution),
+        )
+        .route(
+            "/:workspace_name/reaction-policy",
+            put(update_reaction_policy),
+        )
+        .route(
+            "/:workspace_name/private-channel-policy",
+            put(update_private_channel_policy),
+        )
+        .route("/:workspace_name/locale", put(update_workspace_locale))
+        .route(
+            "/:workspace_name/retention",
+            get(get_retention_policy).put(update_retention_policy),
+        )
+        .route(
+            "/:workspace_name/retention/preview",
+            post(preview_retention_policy),
+        )
+        .route("/:workspace_name/orphaned-tasks", get(list_orphaned_tasks))
+        .route(
+            "/:workspace_name/orphaned-tasks/:task_id",
+            put(assign_orphaned_task),
+        )
+        .route("/:workspace_name/workload", get(get_workload))
+        .route("/:workspace_name/workload/suggest", get(suggest_assignee))
+        .route(
+            "/:workspace_name/projects",
+            get(list_projects).post(create_project),
+        )
+        .route(
+            "/:workspace_name/projects/:project_id",
+            put(update_project).delete(delete_project),
+        )
+        .route(
+            "/:workspace_name/task-templates",
+            get(list_task_templates).post(create_task_template),
+        )
+        .route(
+            "/:workspace_name/task-templates/:template_id",
+            put(update_task_template).delete(delete_task_template),
+        )
+        .route(
+            "/:workspace_name/sla-policies",
+            get(list_sla_policies).post(create_sla_policy),
+        )
+        .route(
+            "/:workspace_name/sla-policies/:policy_id",
+            put(update_sla_policy).delete(delete_sla_policy),
+        )
+        .route("/:workspace_name/channels", get(list_workspace_channels))
+        .route(
+            "/:workspace_name/channels/join",
+            post(join_workspace_channels),
+        )
+        .route(
+            "/:workspace_name/notification-templates",
+            get(get_notification_templates).put(update_notification_templates),
+        )
+        .route(
+            "/:workspace_name/notification-templates/preview",
+            post(preview_notification_template),
+        )
+        .route("/:workspace_name/channel-rules", get(list_channel_rules))
+        .route(
+            "/:workspace_name/channel-rules/:channel",
+            put(update_channel_rule).delete(delete_channel_rule),
+        )
+        .route(
+            "/:workspace_name/jira",
+            get(get_jira_connection)
+                .put(update_jira_connection)
+                .delete(delete_jira_connection),
+        )
+        .route(
+            "/:workspace_name/inbound-email",
+            get(get_inbound_email_address)
+                .post(rotate_inbound_email_address)
+                .delete(delete_inbound_email_address),
+        )
+        .route(
+            "/:workspace_name/linear",
+            get(get_linear_connection)
+                .put(update_linear_connection)
+                .delete(delete_linear_connection),
+        )
+        .route(
+            "/:workspace_name/notion",
+            get(get_notion_connection)
+                .put(update_notion_connection)
+                .delete(delete_notion_connection),
+        )
+        .route("/:workspace_name/export/notion", post(export_to_notion))
+        .route(
+            "/:workspace_name/discord",
+            get(get_discord_connection)
+                .put(update_discord_connection)
+                .delete(delete_discord_connection),
+        )
+        .route(
+            "/:workspace_name/teams",
+            get(get_teams_connection)
+                .put(update_teams_connection)
+                .delete(delete_teams_connection),
+        )
+        .route(
+            "/:workspace_name/standup",
+            get(get_standup).put(update_standup).delete(delete_standup),
+        )
+        .route(
+            "/:workspace_name/business-hours",
+            get(get_business_hours)
+                .put(update_business_hours)
+                .delete(delete_business_hours),
+        )
+        .route("/:workspace_name/import", post(import_tasks))
+        .route(
+            "/:workspace_name/github",
+            get(list_github_repositories).post(connect_github_repository),
+        )
+        .route(
+            "/:workspace_name/github/:repository_id",
+            delete(disconnect_github_repository),
+        )
         // User management routes
         .route("/:workspace_name/users", get(get_workspace_users))
         .route(
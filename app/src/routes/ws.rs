@@ -0,0 +1,10 @@
+use std::sync::Arc;
+
+use axum::{routing::get, Router};
+
+use crate::{core::state::AppState, handlers::ws::board_socket};
+
+/// WebSocket routes authenticate via `?token=` rather than the auth middleware
+pub fn ws_routes() -> Router<Arc<AppState>> {
+    Router::new().route("/", get(board_socket))
+}
@@ -0,0 +1,62 @@
+//! Keeps `changes` and `messages` to the rows day-to-day work needs. History older
+//! than `archive_after_days` moves to `changes_archive` and `messages_archive`,
+//! tables with the same columns, and the repos read both wherever history is asked
+//! for: task detail, status ages, merged duplicates, privacy exports and erasure.
+//!
+//! Archive tables rather than partitions, since SQLite has none and Postgres can't
+//! partition the existing tables in place.
+
+use anyhow::Result;
+use chrono::Duration;
+use tracing::info;
+
+use crate::{
+    core::state::AppState,
+    repos::{changes::ChangesRepo, messages::MessagesRepo},
+};
+
+/// Rows moved per transaction, so the live tables are never locked for long
+const BATCH_SIZE: u64 = 500;
+
+/// Move old history to the archive tables. Returns how many rows moved; nothing
+/// moves unless `archive_after_days` is set.
+pub async fn archive_old_history(state: &AppState) -> Result<usize> {
+    let Some(days) = state.config.archive_after_days else {
+        return Ok(0);
+    };
+    let cutoff = chrono::Utc::now().naive_utc() - Duration::days(days);
+
+    // Changes of completed tasks nobody touched since the cutoff
+    let changes_repo = ChangesRepo::new(state.database.clone());
+    let mut changes = 0;
+    loop {
+        let moved = changes_repo
+            .archive_completed_before(cutoff, BATCH_SIZE)
+            .await?;
+        changes += moved;
+        if moved < BATCH_SIZE {
+            break;
+        }
+    }
+
+    // Messages of duplicates merged into other tasks
+    let messages_repo = MessagesRepo::new(state.database.clone(), state.message_encryption.clone());
+    let mut messages = 0;
+    loop {
+        let moved = messages_repo
+            .archive_merged_before(cutoff, BATCH_SIZE)
+            .await?;
+        messages += moved;
+        if moved < BATCH_SIZE {
+            break;
+        }
+    }
+
+    if changes + messages > 0 {
+        info!(
+            "[Archive] {} change(s) and {} message(s) archived",
+            changes, messages
+        );
+    }
+    Ok((changes + messages) as usize)
+}
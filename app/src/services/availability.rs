@@ -0,0 +1,48 @@
+//! Out-of-office periods members mark for themselves. Auto-assignment passes over
+//! whoever is away, and digests point out tasks waiting on them.
+
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate, Utc};
+use sea_orm::{DatabaseConnection, DbErr};
+
+use crate::{models::person::Model as Person, repos::availability::AvailabilityRepo};
+
+/// Which of these people are out of office today in their own timezone, with the
+/// last day they are away
+pub async fn away_today(
+    db: &DatabaseConnection,
+    people: &[&Person],
+) -> Result<HashMap<String, NaiveDate>, DbErr> {
+    // Local dates are at most a day off UTC
+    let today = Utc::now().date_naive();
+    let periods = AvailabilityRepo::new(db.clone())
+        .get_overlapping(
+            people.iter().map(|person| person.id.clone()).collect(),
+            today - Duration::days(1),
+            today + Duration::days(1),
+        )
+        .await?;
+
+    let mut away = HashMap::new();
+    for person in people {
+        let local_today = Utc::now().with_timezone(&person.tz()).date_naive();
+        let back_after = periods
+            .iter()
+            .filter(|period| period.person_id == person.id && period.covers(local_today))
+            .map(|period| period.ends_on)
+            .max();
+        if let Some(back_after) = back_after {
+            away.insert(person.id.clone(), back_after);
+        }
+    }
+    Ok(away)
+}
+
+/// Last day of the person's current time out of office, None when they are in
+pub async fn away_until(
+    db: &DatabaseConnection,
+    person: &Person,
+) -> Result<Option<NaiveDate>, DbErr> {
+    Ok(away_today(db, &[person]).await?.remove(&person.id))
+}
@@ -0,0 +1,372 @@
+//! Logical backup of one workspace as newline-delimited JSON, e.g. to move it to
+//! another instance. Every line is one record, written after the records it refers
+//! to, so a backup is exported and imported in one pass without holding it in
+//! memory.
+
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+};
+
+use axum::body::Bytes;
+use futures_util::{stream, Stream};
+use sea_orm::{DatabaseConnection, DbErr};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    models::{
+        change::Model as Change, message::Model as Message, person::Model as Person,
+        task::Model as Task, workspace::Model as Workspace,
+    },
+    repos::{
+        changes::ChangesRepo,
+        messages::{MessageEncryption, MessagesRepo},
+        persons::PersonsRepo,
+        task_sequences::TaskSequencesRepo,
+        tasks::TasksRepo,
+    },
+    services::task_keys,
+    utils::pagination::{Pagination, MAX_LIMIT},
+};
+
+pub const CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Records of one kind written to the database at once by an import
+const IMPORT_BATCH: usize = 100;
+
+/// One line of a backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BackupRecord {
+    Person(Person),
+    /// Message text is exported readable and encrypted again on import
+    Message(Message),
+    Task(Task),
+    Change(Change),
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct BackupImportReport {
+    pub persons: u64,
+    pub messages: u64,
+    pub tasks: u64,
+    pub changes: u64,
+    /// Records this instance already had, e.g. from an earlier import
+    pub skipped: u64,
+}
+
+struct ExportCursor {
+    db: DatabaseConnection,
+    encryption: MessageEncryption,
+    workspace_id: String,
+    /// None once the last page was written
+    pagination: Option<Pagination>,
+    /// People already written, each is exported once
+    persons: HashSet<String>,
+}
+
+/// Stream the tasks of a workspace with their messages, history and the people
+/// involved, a page of tasks per chunk. The next page is only read once the
+/// client took the previous one.
+pub fn export(
+    db: DatabaseConnection,
+    encryption: MessageEncryption,
+    workspace_id: String,
+) -> impl Stream<Item = Result<Bytes, DbErr>> + Send {
+    let cursor = ExportCursor {
+        db,
+        encryption,
+        workspace_id,
+        pagination: Some(Pagination::first(MAX_LIMIT)),
+        persons: HashSet::new(),
+    };
+    stream::try_unfold(cursor, |mut cursor| async move {
+        let Some(pagination) = cursor.pagination.take() else {
+            return Ok(None);
+        };
+        let page = TasksRepo::new(cursor.db.clone())
+            .get_workspace_page(&cursor.workspace_id, &pagination)
+            .await?;
+        cursor.pagination = pagination.next(&page);
+        let chunk = cursor.export_tasks(page.items).await?;
+        Ok(Some((chunk, cursor)))
+    })
+}
+
+impl ExportCursor {
+    async fn export_tasks(&mut self, tasks: Vec<Task>) -> Result<Bytes, DbErr> {
+        let messages_repo = MessagesRepo::new(self.db.clone(), self.encryption.clone());
+        let task_ids: Vec<String> = tasks.iter().map(|task| task.id.clone()).collect();
+        let messages = messages_repo
+            .get_by_ids(tasks.iter().map(|task| task.message_id.clone()).collect())
+            .await?;
+        let merged = messages_repo.get_merged_into_any(task_ids.clone()).await?;
+        let changes = ChangesRepo::new(self.db.clone())
+            .get_for_tasks(task_ids)
+            .await?;
+
+        let mut person_ids: Vec<String> = Vec::new();
+        let involved = tasks
+            .iter()
+            .flat_map(|task| [Some(&task.assigned_to), task.assigned_by.as_ref()])
+            .flatten()
+            .chain(messages.iter().chain(&merged).map(|m| &m.person_id));
+        for person_id in involved {
+            if self.persons.insert(person_id.clone()) {
+                person_ids.push(person_id.clone());
+            }
+        }
+        let persons = PersonsRepo::new(self.db.clone())
+            .get_by_ids(person_ids)
+            .await?;
+
+        // Merged messages point to their task, so they come after it
+        let records = persons
+            .into_iter()
+            .map(BackupRecord::Person)
+            .chain(messages.into_iter().map(BackupRecord::Message))
+            .chain(tasks.into_iter().map(BackupRecord::Task))
+            .chain(merged.into_iter().map(BackupRecord::Message))
+            .chain(changes.into_iter().map(BackupRecord::Change));
+
+        let mut chunk = Vec::new();
+        for record in records {
+            serde_json::to_writer(&mut chunk, &record)
+                .map_err(|e| DbErr::Custom(format!("Failed to serialize backup: {}", e)))?;
+            chunk.push(b'\n');
+        }
+        Ok(Bytes::from(chunk))
+    }
+}
+
+/// Reads the records of a backup into a workspace, in the order they were
+/// exported. Records this instance already has are skipped, so an import that
+/// failed half-way can be run again. People are matched by their Slack member id.
+pub struct Importer {
+    db: DatabaseConnection,
+    encryption: MessageEncryption,
+    workspace_id: String,
+    key_prefix: String,
+    /// Consecutive records of one kind, not written yet
+    pending: Vec<BackupRecord>,
+    /// Ids of the backup to the ids of the people and messages stored here
+    person_ids: HashMap<String, String>,
+    message_ids: HashMap<String, String>,
+    /// Highest number of the imported keys with this workspace's prefix
+    highest_key: i32,
+    /// Tasks that came without a key or whose key is taken here; they get new
+    /// ones at the end
+    unkeyed: Vec<String>,
+    report: BackupImportReport,
+}
+
+impl Importer {
+    pub fn new(
+        db: DatabaseConnection,
+        encryption: MessageEncryption,
+        workspace: &Workspace,
+    ) -> Self {
+        Self {
+            db,
+            encryption,
+            workspace_id: workspace.id.clone(),
+            key_prefix: task_keys::prefix_for(&workspace.name),
+            pending: Vec::new(),
+            person_ids: HashMap::new(),
+            message_ids: HashMap::new(),
+            highest_key: 0,
+            unkeyed: Vec::new(),
+            report: BackupImportReport::default(),
+        }
+    }
+
+    pub async fn add(&mut self, record: BackupRecord) -> Result<(), DbErr> {
+        let same_kind = self
+            .pending
+            .first()
+            .is_none_or(|first| mem::discriminant(first) == mem::discriminant(&record));
+        if !same_kind || self.pending.len() >= IMPORT_BATCH {
+            self.flush().await?;
+        }
+        self.pending.push(record);
+        Ok(())
+    }
+
+    /// Write what is left and give keys to the tasks without one
+    pub async fn finish(mut self) -> Result<BackupImportReport, DbErr> {
+        self.flush().await?;
+
+        if self.highest_key > 0 {
+            TaskSequencesRepo::new(self.db.clone())
+                .raise_to(&self.workspace_id, &self.key_prefix, self.highest_key)
+                .await?;
+        }
+        // Skipped tasks were in the list too, and keep what they have
+        let tasks_repo = TasksRepo::new(self.db.clone());
+        let mut unkeyed = Vec::new();
+        for task_id in self.unkeyed {
+            let task = tasks_repo.get(task_id).await?;
+            if task.key.is_none() {
+                unkeyed.push(task);
+            }
+        }
+        let keys = task_keys::next_keys(&self.db, &self.workspace_id, unkeyed.len()).await?;
+        for (task, key) in unkeyed.into_iter().zip(keys) {
+            tasks_repo.set_key(task, key).await?;
+        }
+
+        Ok(self.report)
+    }
+
+    async fn flush(&mut self) -> Result<(), DbErr> {
+        let (mut persons, mut messages, mut tasks, mut changes) = (vec![], vec![], vec![], vec![]);
+        for record in mem::take(&mut self.pending) {
+            match record {
+                BackupRecord::Person(person) => persons.push(person),
+                BackupRecord::Message(message) => messages.push(message),
+                BackupRecord::Task(task) => tasks.push(task),
+                BackupRecord::Change(change) => changes.push(change),
+            }
+        }
+
+        // Records are pending in runs of one kind, so only one of these has any
+        self.import_persons(persons).await?;
+        self.import_messages(messages).await?;
+        self.import_tasks(tasks).await?;
+        self.import_changes(changes).await
+    }
+
+    async fn import_persons(&mut self, persons: Vec<Person>) -> Result<(), DbErr> {
+        let repo = PersonsRepo::new(self.db.clone());
+        for person in persons {
+            match repo.get_by_external_id(person.external_id.clone()).await {
+                Ok(stored) => {
+                    self.person_ids.insert(person.id, stored.id);
+                    self.report.skipped += 1;
+                }
+                Err(DbErr::RecordNotFound(_)) => {
+                    // Whoever runs this instance is not the person of the old one
+                    repo.restore(Person {
+                        is_me: false,
+                        ..person
+                    })
+                    .await?;
+                    self.report.persons += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    async fn import_messages(&mut self, messages: Vec<Message>) -> Result<(), DbErr> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+        let repo = MessagesRepo::new(self.db.clone(), self.encryption.clone());
+        let stored: HashMap<String, String> = repo
+            .get_by_external_ids(messages.iter().map(|m| m.external_id.clone()).collect())
+            .await?
+            .into_iter()
+            .map(|message| (message.external_id, message.id))
+            .collect();
+
+        let mut new = Vec::new();
+        for mut message in messages {
+            if let Some(stored_id) = stored.get(&message.external_id) {
+                self.message_ids.insert(message.id, stored_id.clone());
+                self.report.skipped += 1;
+                continue;
+            }
+            message.person_id = self.person_id(message.person_id);
+            new.push(message);
+        }
+
+        let count = new.len() as u64;
+        let inserted = repo.restore_many(new, &self.workspace_id).await?;
+        self.report.messages += inserted;
+        self.report.skipped += count - inserted;
+        Ok(())
+    }
+
+    async fn import_tasks(&mut self, tasks: Vec<Task>) -> Result<(), DbErr> {
+        if tasks.is_empty() {
+            return Ok(());
+        }
+        let repo = TasksRepo::new(self.db.clone());
+        let taken: HashSet<String> = repo
+            .get_by_keys(tasks.iter().filter_map(|task| task.key.clone()).collect())
+            .await?
+            .into_iter()
+            .filter_map(|task| task.key)
+            .collect();
+
+        let count = tasks.len() as u64;
+        let mut restored = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            let mut task = Task {
+                assigned_to: self.person_id(task.assigned_to),
+                assigned_by: task.assigned_by.map(|id| self.person_id(id)),
+                message_id: self
+                    .message_ids
+                    .get(&task.message_id)
+                    .cloned()
+                    .unwrap_or(task.message_id),
+                workspace_id: Some(self.workspace_id.clone()),
+                // Projects belong to the old workspace
+                project_id: None,
+                ..task
+            };
+            match task.key.as_deref() {
+                Some(key) if taken.contains(key) => {
+                    task.key = None;
+                    self.unkeyed.push(task.id.clone());
+                }
+                Some(key) => {
+                    if let Some(number) = key_number(key, &self.key_prefix) {
+                        self.highest_key = self.highest_key.max(number);
+                    }
+                }
+                None => self.unkeyed.push(task.id.clone()),
+            }
+            restored.push(task);
+        }
+
+        let inserted = repo.restore_many(restored).await?;
+        self.report.tasks += inserted;
+        self.report.skipped += count - inserted;
+        Ok(())
+    }
+
+    async fn import_changes(&mut self, changes: Vec<Change>) -> Result<(), DbErr> {
+        let count = changes.len() as u64;
+        let inserted = ChangesRepo::new(self.db.clone())
+            .restore_many(changes)
+            .await?;
+        self.report.changes += inserted;
+        self.report.skipped += count - inserted;
+        Ok(())
+    }
+
+    fn person_id(&self, id: String) -> String {
+        self.person_ids.get(&id).cloned().unwrap_or(id)
+    }
+}
+
+/// Number of a key with the given prefix, e.g. 42 for `ACME-42`
+fn key_number(key: &str, prefix: &str) -> Option<i32> {
+    key.strip_prefix(prefix)?.strip_prefix('-')?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_only_keys_of_the_prefix() {
+        assert_eq!(key_number("ACME-42", "ACME"), Some(42));
+        assert_eq!(key_number("ACMECO-42", "ACME"), None);
+        assert_eq!(key_number("OTHER-7", "ACME"), None);
+    }
+}
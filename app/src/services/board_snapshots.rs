@@ -0,0 +1,85 @@
+//! Boards of large workspaces are served from a snapshot of the workspace's tasks
+//! with their messages, instead of reading every task, message and link on each
+//! page load. A snapshot is dropped when one of its tasks changes on this instance
+//! and otherwise lives for `cache_ttl_secs`.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    core::{cache::keys, state::AppState},
+    models::{message::Model as Message, task::Model as Task},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardSnapshot {
+    pub built_at: DateTime<Utc>,
+    pub entries: Vec<SnapshotEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub task: Task,
+    pub message: Message,
+    /// When the task entered its status, see `task_service::status_since`
+    pub status_since: Option<NaiveDateTime>,
+}
+
+pub async fn get(state: &AppState, workspace_id: &str) -> Option<BoardSnapshot> {
+    let key = keys::board_snapshot(workspace_id);
+    if state.message_encryption.enabled() {
+        state.cache.get_local(&key).await
+    } else {
+        state.cache.get(&key).await
+    }
+}
+
+/// Snapshots hold message text, so it stays in process memory when the text is
+/// encrypted at rest
+pub async fn store(state: &AppState, workspace_id: &str, snapshot: &BoardSnapshot) {
+    let key = keys::board_snapshot(workspace_id);
+    if state.message_encryption.enabled() {
+        state.cache.set_local(&key, snapshot).await
+    } else {
+        state.cache.set(&key, snapshot).await
+    }
+}
+
+/// Drop the snapshot of every workspace a task changes in until shutdown. Board
+/// events are followed rather than domain events, since only they cover every
+/// task write, edits and deletes included.
+pub async fn run_snapshot_invalidator(state: Arc<AppState>, shutdown_token: CancellationToken) {
+    let mut events = state.board_events.subscribe();
+
+    info!("[Snapshots] Invalidator started");
+    loop {
+        let event = tokio::select! {
+            _ = shutdown_token.cancelled() => break,
+            event = events.recv() => event,
+        };
+
+        match event {
+            Ok(event) => {
+                if let Some(workspace_id) = event.task.workspace_id.as_deref() {
+                    state
+                        .cache
+                        .delete(&keys::board_snapshot(workspace_id))
+                        .await;
+                }
+            }
+            Err(RecvError::Lagged(missed)) => {
+                warn!(
+                    "[Snapshots] Fell behind, boards may be stale for up to the cache TTL after {} task change(s)",
+                    missed
+                )
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+    info!("[Snapshots] Invalidator stopped");
+}
@@ -0,0 +1,117 @@
+//! Business hours of workspaces: the calendar their ages, SLAs and escalation timers
+//! count working time with. Workspaces without business hours count wall-clock time.
+
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use sea_orm::DatabaseConnection;
+use tracing::warn;
+
+use crate::{
+    models::workspace_settings::BusinessHours,
+    repos::workspace_settings::WorkspaceSettingsRepo,
+    utils::{
+        business_time::{parse_weekdays, BusinessCalendar},
+        response::FieldError,
+        time::parse_timezone,
+    },
+};
+
+/// Most holidays a workspace may list
+const MAX_HOLIDAYS: usize = 500;
+
+/// Calendar of the business hours, or why they are invalid
+pub fn calendar(settings: &BusinessHours) -> Result<BusinessCalendar, Vec<FieldError>> {
+    let mut errors = Vec::new();
+    let mut error = |field: &str, message: &str| {
+        errors.push(FieldError {
+            field: field.to_string(),
+            message: message.to_string(),
+        })
+    };
+
+    let start = NaiveTime::parse_from_str(settings.start.trim(), "%H:%M").ok();
+    if start.is_none() {
+        error("start", "Use HH:MM, e.g. 09:00");
+    }
+    let end = NaiveTime::parse_from_str(settings.end.trim(), "%H:%M").ok();
+    match (start, end) {
+        (_, None) => error("end", "Use HH:MM, e.g. 17:00"),
+        (Some(start), Some(end)) if end <= start => {
+            error("end", "Must be later in the day than start")
+        }
+        _ => {}
+    }
+    let timezone = parse_timezone(&settings.timezone);
+    if timezone.is_none() {
+        error("timezone", "Unknown timezone");
+    }
+    let days = parse_weekdays(&settings.days);
+    if days.is_none() {
+        error(
+            "days",
+            "Use cron days of the week, e.g. Mon-Fri or Mon,Wed,Fri",
+        );
+    }
+    if settings.holidays.len() > MAX_HOLIDAYS {
+        error(
+            "holidays",
+            &format!("List at most {} holidays", MAX_HOLIDAYS),
+        );
+    }
+
+    match (start, end, timezone, days) {
+        (Some(start), Some(end), Some(timezone), Some(days)) if errors.is_empty() => {
+            Ok(BusinessCalendar::new(
+                timezone,
+                start,
+                end,
+                days,
+                settings.holidays.iter().copied(),
+            ))
+        }
+        _ => Err(errors),
+    }
+}
+
+/// Calendar of the workspace's business hours, None when it counts wall-clock time.
+/// Settings that fail to load count wall-clock time too.
+pub async fn workspace_calendar(
+    db: &DatabaseConnection,
+    workspace_id: &str,
+) -> Option<BusinessCalendar> {
+    let settings = WorkspaceSettingsRepo::new(db.clone())
+        .get_business_hours(workspace_id)
+        .await
+        .unwrap_or_else(|e| {
+            warn!(
+                "Failed to load the business hours of workspace {}: {}",
+                workspace_id, e
+            );
+            None
+        })?;
+    calendar(&settings).ok()
+}
+
+/// Time between two instants, in working hours only when there is a calendar
+pub fn elapsed(
+    calendar: Option<&BusinessCalendar>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Duration {
+    match calendar {
+        Some(calendar) => calendar.working_time(from, to),
+        None => (to - from).max(Duration::zero()),
+    }
+}
+
+/// When `duration` has passed since `from`, in working hours only when there is a
+/// calendar
+pub fn deadline(
+    calendar: Option<&BusinessCalendar>,
+    from: DateTime<Utc>,
+    duration: Duration,
+) -> Option<DateTime<Utc>> {
+    match calendar {
+        Some(calendar) => calendar.add(from, duration),
+        None => from.checked_add_signed(duration),
+    }
+}
@@ -0,0 +1,99 @@
+//! iCalendar (RFC 5545) feed of a person's tasks with due dates
+
+use chrono::{Duration, NaiveDateTime};
+
+use crate::models::{
+    message::Model as Message,
+    task::{Model as Task, TaskStatus},
+};
+
+/// Due dates are points in time; calendars get a short event ending there
+const EVENT_MINUTES: i64 = 30;
+
+/// Longest line allowed before folding, in octets
+const MAX_LINE_OCTETS: usize = 75;
+
+fn format_utc(at: NaiveDateTime) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT value
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line into CRLF-terminated lines of at most 75 octets, continued
+/// by a leading space, without splitting a UTF-8 character
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn status_label(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Blank => "Open",
+        TaskStatus::InProgress => "In progress",
+        TaskStatus::Blocked => "Blocked",
+        TaskStatus::Completed => "Completed",
+    }
+}
+
+/// Render the feed. Tasks without a due date or message are skipped.
+pub fn render_feed(tasks: &[(Task, Option<Message>)], now: NaiveDateTime) -> String {
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//Slacker//Tasks//EN");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(&mut out, "X-WR-CALNAME:Slacker tasks");
+
+    for (task, message) in tasks {
+        let (Some(due_at), Some(message)) = (task.due_at, message) else {
+            continue;
+        };
+
+        let title = message.content.lines().next().unwrap_or_default().trim();
+        let summary = match task.status {
+            TaskStatus::Completed => format!("✓ {}", title),
+            _ => title.to_string(),
+        };
+        let description = format!(
+            "Status: {}\n\n{}",
+            status_label(&task.status),
+            message.content
+        );
+
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}@slacker", task.id));
+        push_line(&mut out, &format!("DTSTAMP:{}", format_utc(now)));
+        push_line(
+            &mut out,
+            &format!(
+                "DTSTART:{}",
+                format_utc(due_at - Duration::minutes(EVENT_MINUTES))
+            ),
+        );
+        push_line(&mut out, &format!("DTEND:{}", format_utc(due_at)));
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&summary)));
+        push_line(&mut out, &format!("DESCRIPTION:{}", escape(&description)));
+        if !message.channel_hidden {
+            push_line(&mut out, &format!("URL:{}", message.slack_link()));
+        }
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
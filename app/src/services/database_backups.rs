@@ -0,0 +1,461 @@
+//! Logical backups of every table Slacker owns, written by Slacker itself rather
+//! than pg_dump: a header line, then one JSON line per row, tables ordered so rows
+//! come after the rows they reference. Taken in one read-only transaction, so the
+//! tables agree with each other. Kept in `[backup] dir` or an S3 bucket.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
+use reqwest::Client;
+use sea_orm::{
+    AccessMode, ConnectionTrait, DatabaseBackend, DatabaseConnection, IsolationLevel, Statement,
+    StreamTrait, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{
+    fs::{self, File},
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter},
+};
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::{
+    config::config::{BackupConfig, S3Config},
+    integrations::s3,
+};
+
+const FILE_PREFIX: &str = "slacker-backup-";
+const FILE_SUFFIX: &str = ".ndjson";
+
+const FORMAT: &str = "slacker-backup";
+const VERSION: u32 = 1;
+
+/// Rows of one table written per statement by a restore
+const RESTORE_BATCH: usize = 500;
+
+/// Where sea-orm keeps track of applied migrations
+const MIGRATIONS_TABLE: &str = "seaql_migrations";
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BackupFile {
+    /// e.g. `slacker-backup-20260316T040000Z.ndjson`
+    pub name: String,
+    pub size_bytes: u64,
+    /// Path of the file, or `s3://bucket/key`
+    pub location: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RestoreReport {
+    pub name: String,
+    pub rows: u64,
+}
+
+/// First line of a backup
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    format: String,
+    version: u32,
+    created_at: DateTime<Utc>,
+    /// Last migration applied to the database the backup was taken from
+    migration: String,
+}
+
+/// Every other line
+#[derive(Debug, Deserialize)]
+struct BackupRow {
+    table: String,
+    row: Value,
+}
+
+/// Why backups can't be taken or restored here, None when they can
+pub fn unavailable_reason(db: &DatabaseConnection, config: &BackupConfig) -> Option<String> {
+    if db.get_database_backend() != DatabaseBackend::Postgres {
+        return Some("Backups need Postgres; back up a SQLite database by copying its file".into());
+    }
+    if config.dir.is_none() && config.s3.is_none() {
+        return Some("No backup storage is configured; set [backup] dir or [backup.s3]".into());
+    }
+    None
+}
+
+/// Whether `name` is the name of a backup, and nothing like a path
+pub fn is_backup_name(name: &str) -> bool {
+    name.strip_prefix(FILE_PREFIX)
+        .and_then(|rest| rest.strip_suffix(FILE_SUFFIX))
+        .is_some_and(|stamp| !stamp.is_empty() && stamp.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Backups in the configured storage, newest first
+pub async fn list(config: &BackupConfig) -> Result<Vec<BackupFile>> {
+    let mut files = Storage::new(config)?.list().await?;
+    files.sort_by(|a, b| b.name.cmp(&a.name));
+    Ok(files)
+}
+
+/// Write a backup of every table to the configured storage
+pub async fn create(db: &DatabaseConnection, config: &BackupConfig) -> Result<BackupFile> {
+    if let Some(reason) = unavailable_reason(db, config) {
+        bail!(reason);
+    }
+    let storage = Storage::new(config)?;
+    let name = format!(
+        "{}{}{}",
+        FILE_PREFIX,
+        Utc::now().format("%Y%m%dT%H%M%SZ"),
+        FILE_SUFFIX
+    );
+    let path = match &storage {
+        Storage::Dir(dir) => {
+            fs::create_dir_all(dir).await?;
+            dir.join(format!("{}.partial", name))
+        }
+        Storage::S3(_) => std::env::temp_dir().join(&name),
+    };
+
+    let rows = match write_backup(db, &path).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            let _ = fs::remove_file(&path).await;
+            return Err(e);
+        }
+    };
+    let file = storage.save(&name, &path).await?;
+    info!("[Backup] Wrote {} row(s) to {}", rows, file.location);
+    Ok(file)
+}
+
+/// Replace the data of every table with a backup, in one transaction. The
+/// database must be at the migration the backup was taken at. Callers make sure
+/// nothing writes meanwhile, i.e. maintenance mode is on.
+pub async fn restore(
+    db: &DatabaseConnection,
+    config: &BackupConfig,
+    name: &str,
+) -> Result<RestoreReport> {
+    if let Some(reason) = unavailable_reason(db, config) {
+        bail!(reason);
+    }
+    if !is_backup_name(name) {
+        bail!("{} is not the name of a backup", name);
+    }
+    let mut lines = Storage::new(config)?.open(name).await?.lines();
+
+    let header: Header = serde_json::from_str(
+        &lines
+            .next_line()
+            .await?
+            .ok_or_else(|| anyhow!("{} is empty", name))?,
+    )?;
+    if header.format != FORMAT || header.version != VERSION {
+        bail!("{} is not a backup this version of Slacker can read", name);
+    }
+
+    let txn = db.begin().await?;
+    let migration = applied_migration(&txn).await?;
+    if header.migration != migration {
+        bail!(
+            "{} was taken at migration {}, but the database is at {}; restore it with the Slacker version that took it",
+            name,
+            header.migration,
+            migration
+        );
+    }
+    let tables = table_names(&txn).await?;
+    let all: Vec<String> = tables.iter().map(|table| quote(table)).collect();
+    txn.execute_unprepared(&format!("TRUNCATE {} CASCADE", all.join(", ")))
+        .await?;
+
+    let mut report = RestoreReport {
+        name: name.to_string(),
+        rows: 0,
+    };
+    let mut pending: Vec<Value> = Vec::new();
+    let mut pending_table = String::new();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row: BackupRow = serde_json::from_str(&line)?;
+        if !tables.contains(&row.table) {
+            bail!("{} has rows of an unknown table {}", name, row.table);
+        }
+        if row.table != pending_table || pending.len() >= RESTORE_BATCH {
+            report.rows += insert_rows(&txn, &pending_table, &mut pending).await?;
+            pending_table = row.table;
+        }
+        pending.push(row.row);
+    }
+    report.rows += insert_rows(&txn, &pending_table, &mut pending).await?;
+    txn.commit().await?;
+
+    info!("[Backup] Restored {} row(s) from {}", report.rows, name);
+    Ok(report)
+}
+
+async fn write_backup(db: &DatabaseConnection, path: &Path) -> Result<u64> {
+    let txn = db
+        .begin_with_config(
+            Some(IsolationLevel::RepeatableRead),
+            Some(AccessMode::ReadOnly),
+        )
+        .await?;
+    let tables = dependency_order(table_names(&txn).await?, &foreign_keys(&txn).await?);
+
+    let mut file = BufWriter::new(File::create(path).await?);
+    let header = Header {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        created_at: Utc::now(),
+        migration: applied_migration(&txn).await?,
+    };
+    file.write_all(format!("{}\n", serde_json::to_string(&header)?).as_bytes())
+        .await?;
+
+    let mut written = 0;
+    for table in tables {
+        let sql = format!(
+            r#"SELECT row_to_json(t)::text AS "row" FROM {} t"#,
+            quote(&table)
+        );
+        let rows = txn
+            .stream(Statement::from_string(DatabaseBackend::Postgres, sql))
+            .await?;
+        let mut rows = std::pin::pin!(rows);
+        while let Some(row) = rows.try_next().await? {
+            let row: String = row.try_get("", "row")?;
+            // Table names are plain identifiers and rows JSON already
+            file.write_all(format!("{{\"table\":\"{}\",\"row\":{}}}\n", table, row).as_bytes())
+                .await?;
+            written += 1;
+        }
+    }
+    file.flush().await?;
+    txn.commit().await?;
+    Ok(written)
+}
+
+async fn insert_rows(
+    txn: &impl ConnectionTrait,
+    table: &str,
+    rows: &mut Vec<Value>,
+) -> Result<u64> {
+    if rows.is_empty() {
+        return Ok(0);
+    }
+    let sql = format!(
+        "INSERT INTO {0} SELECT * FROM json_populate_recordset(NULL::{0}, $1::json)",
+        quote(table)
+    );
+    let values = serde_json::to_string(&std::mem::take(rows))?;
+    let result = txn
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            sql,
+            [values.into()],
+        ))
+        .await?;
+    Ok(result.rows_affected())
+}
+
+/// Tables of the current schema, except the one of migrations
+async fn table_names(db: &impl ConnectionTrait) -> Result<Vec<String>> {
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT tablename FROM pg_tables WHERE schemaname = current_schema() AND tablename <> $1 ORDER BY tablename",
+            [MIGRATIONS_TABLE.into()],
+        ))
+        .await?;
+    rows.iter()
+        .map(|row| Ok(row.try_get("", "tablename")?))
+        .collect()
+}
+
+/// Pairs of a table and a table it references
+async fn foreign_keys(db: &impl ConnectionTrait) -> Result<Vec<(String, String)>> {
+    let rows = db
+        .query_all(Statement::from_string(
+            DatabaseBackend::Postgres,
+            "SELECT c.relname AS from_table, f.relname AS to_table FROM pg_constraint k \
+             JOIN pg_class c ON c.oid = k.conrelid \
+             JOIN pg_class f ON f.oid = k.confrelid \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE k.contype = 'f' AND n.nspname = current_schema()",
+        ))
+        .await?;
+    rows.iter()
+        .map(|row| Ok((row.try_get("", "from_table")?, row.try_get("", "to_table")?)))
+        .collect()
+}
+
+async fn applied_migration(db: &impl ConnectionTrait) -> Result<String> {
+    let row = db
+        .query_one(Statement::from_string(
+            DatabaseBackend::Postgres,
+            format!(
+                "SELECT version FROM {} ORDER BY version DESC LIMIT 1",
+                MIGRATIONS_TABLE
+            ),
+        ))
+        .await?
+        .ok_or_else(|| anyhow!("No migrations were applied to the database"))?;
+    Ok(row.try_get("", "version")?)
+}
+
+/// Tables ordered so each comes after the tables it references. Tables of a
+/// reference cycle keep alphabetical order.
+fn dependency_order(mut remaining: Vec<String>, references: &[(String, String)]) -> Vec<String> {
+    remaining.sort();
+    let mut ordered: Vec<String> = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        let ready: Vec<String> = remaining
+            .iter()
+            .filter(|table| {
+                references
+                    .iter()
+                    .all(|(from, to)| from != *table || to == *table || !remaining.contains(to))
+            })
+            .cloned()
+            .collect();
+        if ready.is_empty() {
+            ordered.append(&mut remaining);
+            break;
+        }
+        remaining.retain(|table| !ready.contains(table));
+        ordered.extend(ready);
+    }
+    ordered
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+enum Storage {
+    Dir(PathBuf),
+    S3(S3Config),
+}
+
+impl Storage {
+    fn new(config: &BackupConfig) -> Result<Self> {
+        match (&config.s3, &config.dir) {
+            (Some(s3), _) => Ok(Self::S3(s3.clone())),
+            (None, Some(dir)) => Ok(Self::Dir(PathBuf::from(dir))),
+            (None, None) => bail!("No backup storage is configured"),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<BackupFile>> {
+        let mut files = Vec::new();
+        match self {
+            Self::Dir(dir) => {
+                let mut entries = match fs::read_dir(dir).await {
+                    Ok(entries) => entries,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(files),
+                    Err(e) => return Err(e.into()),
+                };
+                while let Some(entry) = entries.next_entry().await? {
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    if is_backup_name(&name) {
+                        files.push(BackupFile {
+                            size_bytes: entry.metadata().await?.len(),
+                            location: entry.path().display().to_string(),
+                            name,
+                        });
+                    }
+                }
+            }
+            Self::S3(config) => {
+                for object in s3::list_objects(&Client::new(), config, &config.prefix).await? {
+                    let Some(name) = object.key.strip_prefix(&config.prefix) else {
+                        continue;
+                    };
+                    if is_backup_name(name) {
+                        files.push(BackupFile {
+                            name: name.to_string(),
+                            size_bytes: object.size,
+                            location: format!("s3://{}/{}", config.bucket, object.key),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    /// Keep the finished backup at `path` under `name`
+    async fn save(&self, name: &str, path: &Path) -> Result<BackupFile> {
+        let size_bytes = fs::metadata(path).await?.len();
+        match self {
+            Self::Dir(dir) => {
+                let target = dir.join(name);
+                fs::rename(path, &target).await?;
+                Ok(BackupFile {
+                    name: name.to_string(),
+                    size_bytes,
+                    location: target.display().to_string(),
+                })
+            }
+            Self::S3(config) => {
+                let key = format!("{}{}", config.prefix, name);
+                let uploaded =
+                    s3::put_object(&Client::new(), config, &key, fs::read(path).await?).await;
+                fs::remove_file(path).await?;
+                uploaded?;
+                Ok(BackupFile {
+                    name: name.to_string(),
+                    size_bytes,
+                    location: format!("s3://{}/{}", config.bucket, key),
+                })
+            }
+        }
+    }
+
+    async fn open(&self, name: &str) -> Result<Box<dyn AsyncBufRead + Send + Unpin>> {
+        match self {
+            Self::Dir(dir) => Ok(Box::new(BufReader::new(File::open(dir.join(name)).await?))),
+            Self::S3(config) => {
+                let key = format!("{}{}", config.prefix, name);
+                let bytes = s3::get_object(&Client::new(), config, &key).await?;
+                Ok(Box::new(std::io::Cursor::new(bytes)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_tables_after_the_ones_they_reference() {
+        let tables = ["changes", "messages", "persons", "tasks"]
+            .map(String::from)
+            .to_vec();
+        let references = [
+            ("changes", "tasks"),
+            ("messages", "persons"),
+            ("tasks", "messages"),
+            ("tasks", "persons"),
+            ("tasks", "tasks"),
+        ]
+        .map(|(from, to)| (from.to_string(), to.to_string()));
+
+        assert_eq!(
+            dependency_order(tables, &references),
+            vec!["persons", "messages", "tasks", "changes"]
+        );
+    }
+
+    #[test]
+    fn accepts_only_backup_names() {
+        assert!(is_backup_name("slacker-backup-20260316T040000Z.ndjson"));
+        assert!(!is_backup_name("slacker-backup-../../etc/passwd.ndjson"));
+        assert!(!is_backup_name("slacker-backup-.ndjson"));
+        assert!(!is_backup_name("notes.txt"));
+    }
+}
@@ -0,0 +1,211 @@
+//! Deprovisioning: a member who leaves Slack, or whose account is deactivated, is
+//! unlinked from the workspace. Their open tasks go to the assignee of the task's
+//! channel rule, or are flagged as orphaned for an admin to hand out.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use reqwest::Client;
+use sea_orm::{DatabaseConnection, DbErr};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::{
+    core::{
+        board_events::{BoardChange, BoardEventsManager},
+        state::AppState,
+    },
+    models::workspace::Model as Workspace,
+    repos::{
+        audit_logs::AuditLogsRepo,
+        channel_rules::ChannelRulesRepo,
+        messages::{MessageEncryption, MessagesRepo},
+        tasks::TasksRepo,
+        workspace_links::WorkspaceLinksRepo,
+    },
+    services::workspace::get_active_workspaces_with_tokens,
+};
+
+/// What deprovisioning a member did to their open tasks
+#[derive(Debug, Default, Serialize)]
+pub struct DeprovisionReport {
+    pub person_id: String,
+    pub reassigned: u64,
+    pub orphaned: u64,
+}
+
+/// Unlink the member with this Slack id from the workspace and hand over their open
+/// tasks. Returns None when no linked member has the id.
+pub async fn deprovision_member(
+    db: &DatabaseConnection,
+    encryption: &MessageEncryption,
+    board_events: &BoardEventsManager,
+    workspace: &Workspace,
+    slack_member_id: &str,
+    reason: &str,
+) -> Result<Option<DeprovisionReport>> {
+    let links_repo = WorkspaceLinksRepo::new(db.clone());
+    let link = match links_repo
+        .get_by_slack_member_id_and_workspace(slack_member_id.to_string(), workspace.id.clone())
+        .await
+    {
+        Ok(link) => link,
+        Err(DbErr::RecordNotFound(_)) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let person_id = link.person_id.clone();
+    links_repo
+        .unlink_workspace(person_id.clone(), workspace.id.clone())
+        .await?;
+
+    let tasks_repo = TasksRepo::new(db.clone());
+    let messages_repo = MessagesRepo::new(db.clone(), encryption.clone());
+    let rules_repo = ChannelRulesRepo::new(db.clone());
+    let mut report = DeprovisionReport {
+        person_id: person_id.clone(),
+        ..Default::default()
+    };
+
+    for task in tasks_repo
+        .get_open_assigned_in_workspace(&person_id, &workspace.id)
+        .await?
+    {
+        let channel = messages_repo
+            .get_by_id(task.message_id.clone())
+            .await?
+            .channel;
+        let rule_assignee = rules_repo
+            .get_by_channel(&workspace.id, &channel)
+            .await?
+            .and_then(|rule| rule.assignee_id)
+            .filter(|assignee_id| *assignee_id != person_id);
+        let takeover = match rule_assignee {
+            Some(assignee_id) => links_repo
+                .get_by_person_and_workspace(assignee_id.clone(), workspace.id.clone())
+                .await
+                .is_ok_and(|link| link.is_linked)
+                .then_some(assignee_id),
+            None => None,
+        };
+
+        let task = match takeover {
+            Some(assignee_id) => {
+                report.reassigned += 1;
+                tasks_repo.reassign(task, &assignee_id).await?
+            }
+            None => {
+                report.orphaned += 1;
+                tasks_repo.set_orphaned(task).await?
+            }
+        };
+        board_events.publish(&workspace.name, BoardChange::Updated, task);
+    }
+
+    AuditLogsRepo::new(db.clone())
+        .record(
+            None,
+            "member.deprovisioned",
+            Some(&person_id),
+            Some(
+                serde_json::json!({
+                    "workspace_id": workspace.id,
+                    "reason": reason,
+                    "reassigned": report.reassigned,
+                    "orphaned": report.orphaned,
+                })
+                .to_string(),
+            ),
+        )
+        .await?;
+    info!(
+        "Deprovisioned member {} of workspace {} ({}): {} task(s) reassigned, {} orphaned",
+        person_id, workspace.name, reason, report.reassigned, report.orphaned
+    );
+
+    Ok(Some(report))
+}
+
+/// Whether Slack reports the member's account as deactivated
+async fn is_deactivated(client: &Client, bot_token: &str, slack_member_id: &str) -> Result<bool> {
+    #[derive(Debug, Deserialize)]
+    struct User {
+        #[serde(default)]
+        deleted: bool,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct UserInfoResponse {
+        ok: bool,
+        user: Option<User>,
+        error: Option<String>,
+    }
+
+    let response = client
+        .get("https://slack.com/api/users.info")
+        .bearer_auth(bot_token)
+        .query(&[("user", slack_member_id)])
+        .send()
+        .await?
+        .json::<UserInfoResponse>()
+        .await?;
+
+    match response.user {
+        Some(user) if response.ok => Ok(user.deleted),
+        // Members removed from the team entirely are not found any more
+        _ if response.error.as_deref() == Some("user_not_found") => Ok(true),
+        _ => Err(anyhow::anyhow!(
+            "Slack users.info failed: {}",
+            response
+                .error
+                .unwrap_or_else(|| "unknown error".to_string())
+        )),
+    }
+}
+
+/// Deprovision linked members whose Slack accounts were deactivated while no bot
+/// was listening; body of the `deactivated_members` scheduled job
+pub async fn deprovision_deactivated_members(state: &AppState) -> Result<usize> {
+    let workspaces =
+        get_active_workspaces_with_tokens(&state.database, &state.config.encryption_key).await?;
+    let links_repo = WorkspaceLinksRepo::new(state.database.clone());
+    let client = Client::new();
+
+    let mut deprovisioned = 0;
+    for (workspace, tokens) in workspaces {
+        for link in links_repo.get_by_workspace(workspace.id.clone()).await? {
+            let Some(slack_member_id) = link.slack_member_id else {
+                continue;
+            };
+            let deactivated = is_deactivated(&client, &tokens.bot_token, &slack_member_id).await;
+            // users.info allows about 100 calls a minute
+            tokio::time::sleep(Duration::from_millis(600)).await;
+            match deactivated {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    warn!(
+                        "[Deprovision] Could not check Slack member {} of {}: {}",
+                        slack_member_id, workspace.name, e
+                    );
+                    continue;
+                }
+            }
+
+            if deprovision_member(
+                &state.database,
+                &state.message_encryption,
+                &state.board_events,
+                &workspace,
+                &slack_member_id,
+                "deactivated",
+            )
+            .await?
+            .is_some()
+            {
+                deprovisioned += 1;
+            }
+        }
+    }
+
+    Ok(deprovisioned)
+}
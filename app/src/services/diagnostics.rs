@@ -0,0 +1,221 @@
+//! Self-check of one workspace's Slack app: whether its tokens work and the bot
+//! token has the scopes Slacker uses, so a misconfigured app shows up as a failed
+//! check instead of opaque Slack errors at runtime.
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{config::workspaces::WorkspaceConfig, models::workspace::Model as Workspace};
+
+/// Bot scopes of `slack-app-manifest.yaml`
+pub const REQUIRED_BOT_SCOPES: &[&str] = &[
+    "app_mentions:read",
+    "channels:history",
+    "channels:join",
+    "channels:read",
+    "chat:write",
+    "commands",
+    "emoji:read",
+    "groups:history",
+    "groups:read",
+    "im:history",
+    "im:read",
+    "links:read",
+    "links:write",
+    "mpim:history",
+    "mpim:read",
+    "reactions:read",
+    "reactions:write",
+    "users:read",
+    "users:read.email",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct DiagnosticCheck {
+    /// `credentials`, `bot_token`, `team`, `scope:<scope>` or `app_token`
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl DiagnosticCheck {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            passed: true,
+            detail: detail.into(),
+        }
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            passed: false,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackResponse {
+    ok: bool,
+    error: Option<String>,
+    team_id: Option<String>,
+    /// Org-wide tokens of an Enterprise Grid install name the org as their team
+    #[serde(default)]
+    is_enterprise_install: bool,
+}
+
+/// Run every check against Slack. Nothing is changed; the Socket Mode URL that
+/// `apps.connections.open` hands out is never connected to.
+pub async fn run(workspace: &Workspace, tokens: &WorkspaceConfig) -> Vec<DiagnosticCheck> {
+    let client = Client::new();
+    let mut checks = check_bot_token(&client, workspace, &tokens.bot_token).await;
+    checks.push(check_app_token(&client, &tokens.app_token).await);
+    checks
+}
+
+/// `auth.test` with the bot token, the team it belongs to and its scopes, which
+/// Slack lists in the `x-oauth-scopes` header
+async fn check_bot_token(
+    client: &Client,
+    workspace: &Workspace,
+    bot_token: &str,
+) -> Vec<DiagnosticCheck> {
+    let response = match client
+        .post("https://slack.com/api/auth.test")
+        .bearer_auth(bot_token)
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            return vec![DiagnosticCheck::fail(
+                "bot_token",
+                format!("Cannot reach Slack: {}", e),
+            )]
+        }
+    };
+    let scopes = response
+        .headers()
+        .get("x-oauth-scopes")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = match response.json::<SlackResponse>().await {
+        Ok(body) => body,
+        Err(e) => {
+            return vec![DiagnosticCheck::fail(
+                "bot_token",
+                format!("Unexpected answer from auth.test: {}", e),
+            )]
+        }
+    };
+    if !body.ok {
+        return vec![DiagnosticCheck::fail(
+            "bot_token",
+            format!(
+                "auth.test failed: {}",
+                body.error.as_deref().unwrap_or("unknown error")
+            ),
+        )];
+    }
+
+    let mut checks = vec![DiagnosticCheck::pass("bot_token", "auth.test succeeded")];
+    checks.push(match (&workspace.slack_team_id, &body.team_id) {
+        (_, Some(actual)) if body.is_enterprise_install => DiagnosticCheck::pass(
+            "team",
+            format!("The bot token is installed org-wide on {}", actual),
+        ),
+        (Some(expected), Some(actual)) if expected != actual => DiagnosticCheck::fail(
+            "team",
+            format!(
+                "The bot token belongs to Slack team {}, not {}",
+                actual, expected
+            ),
+        ),
+        (_, Some(actual)) => {
+            DiagnosticCheck::pass("team", format!("The bot token belongs to {}", actual))
+        }
+        (_, None) => DiagnosticCheck::fail("team", "auth.test named no team"),
+    });
+    match scopes {
+        Some(scopes) => checks.extend(scope_checks(&scopes)),
+        None => checks.push(DiagnosticCheck::fail(
+            "scopes",
+            "Slack did not list the bot token's scopes",
+        )),
+    }
+    checks
+}
+
+/// One check per required scope, given the comma-separated scopes of a token
+pub fn scope_checks(granted: &str) -> Vec<DiagnosticCheck> {
+    let granted: Vec<&str> = granted.split(',').map(str::trim).collect();
+    REQUIRED_BOT_SCOPES
+        .iter()
+        .map(|scope| {
+            let name = format!("scope:{}", scope);
+            if granted.contains(scope) {
+                DiagnosticCheck::pass(name, "Granted")
+            } else {
+                DiagnosticCheck::fail(
+                    name,
+                    "Missing; add it to the app's bot scopes and reinstall the app",
+                )
+            }
+        })
+        .collect()
+}
+
+/// `apps.connections.open` with the app-level token, which Socket Mode needs
+async fn check_app_token(client: &Client, app_token: &str) -> DiagnosticCheck {
+    let response = client
+        .post("https://slack.com/api/apps.connections.open")
+        .bearer_auth(app_token)
+        .send()
+        .await;
+    let body = match response {
+        Ok(response) => response.json::<SlackResponse>().await,
+        Err(e) => return DiagnosticCheck::fail("app_token", format!("Cannot reach Slack: {}", e)),
+    };
+    match body {
+        Ok(body) if body.ok => {
+            DiagnosticCheck::pass("app_token", "Socket Mode connections can be opened")
+        }
+        Ok(body) => DiagnosticCheck::fail(
+            "app_token",
+            format!(
+                "apps.connections.open failed: {}; the app token needs connections:write and Socket Mode turned on",
+                body.error.as_deref().unwrap_or("unknown error")
+            ),
+        ),
+        Err(e) => DiagnosticCheck::fail(
+            "app_token",
+            format!("Unexpected answer from apps.connections.open: {}", e),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fails_missing_scopes() {
+        let granted = REQUIRED_BOT_SCOPES
+            .iter()
+            .filter(|scope| **scope != "users:read.email")
+            .copied()
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let failed: Vec<String> = scope_checks(&granted)
+            .into_iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.name)
+            .collect();
+
+        assert_eq!(failed, vec!["scope:users:read.email"]);
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use chrono::{Datelike, NaiveDate, Timelike, Utc, Weekday};
+use tracing::warn;
+
+use crate::{
+    core::state::AppState,
+    i18n,
+    models::{
+        notification_setting::{self, NotificationKind},
+        person::Model as Person,
+        task::{Model as Task, TaskStatus},
+    },
+    repos::{
+        messages::MessagesRepo, notification_settings::NotificationSettingsRepo,
+        persons::PersonsRepo, tasks::TasksRepo,
+    },
+    services::{availability, notification_templates::NotificationText},
+};
+
+/// Local hour at which digests and summaries reach people
+pub const DELIVERY_HOUR: u32 = 9;
+
+/// How much of a message is quoted per task in a digest
+const PREVIEW_CHARS: usize = 80;
+
+/// Send the daily digest of open tasks to everyone who enabled it.
+/// With `due_only`, only people for whom it is currently the delivery hour are included.
+pub async fn send_daily_digests(state: &AppState, due_only: bool) -> Result<usize> {
+    let subscribers = NotificationSettingsRepo::new(state.database.clone())
+        .get_subscribers(notification_setting::Column::DailyDigest)
+        .await?;
+
+    let mut sent = 0;
+    for person in subscribers {
+        if due_only && !is_delivery_time(&person, None) {
+            continue;
+        }
+
+        let text = match daily_digest(state, &person).await {
+            Ok(Some(text)) => text,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!(
+                    "[Digest] Failed to build digest for {}: {}",
+                    person.email, e
+                );
+                continue;
+            }
+        };
+
+        match state
+            .notifier
+            .notify_text(&person, NotificationKind::DailyDigest, None, text)
+            .await
+        {
+            Ok(true) => sent += 1,
+            Ok(false) => {}
+            Err(e) => warn!("[Digest] Failed to send digest to {}: {}", person.email, e),
+        }
+    }
+
+    Ok(sent)
+}
+
+/// Send last week's task summary to everyone who enabled it, on Mondays in their timezone.
+/// With `due_only`, only people for whom it is currently the delivery hour are included.
+pub async fn send_weekly_summaries(state: &AppState, due_only: bool) -> Result<usize> {
+    let subscribers = NotificationSettingsRepo::new(state.database.clone())
+        .get_subscribers(notification_setting::Column::WeeklySummary)
+        .await?;
+
+    let mut sent = 0;
+    for person in subscribers {
+        if due_only && !is_delivery_time(&person, Some(Weekday::Mon)) {
+            continue;
+        }
+
+        let text = match weekly_summary(state, &person).await {
+            Ok(text) => text,
+            Err(e) => {
+                warn!(
+                    "[Digest] Failed to build summary for {}: {}",
+                    person.email, e
+                );
+                continue;
+            }
+        };
+
+        match state
+            .notifier
+            .notify_text(&person, NotificationKind::WeeklySummary, None, text)
+            .await
+        {
+            Ok(true) => sent += 1,
+            Ok(false) => {}
+            Err(e) => warn!("[Digest] Failed to send summary to {}: {}", person.email, e),
+        }
+    }
+
+    Ok(sent)
+}
+
+fn is_delivery_time(person: &Person, weekday: Option<Weekday>) -> bool {
+    let local = Utc::now().with_timezone(&person.tz());
+    local.hour() == DELIVERY_HOUR && weekday.is_none_or(|day| local.weekday() == day)
+}
+
+/// Open tasks the person handed to someone who is out of office today, with that
+/// someone and the last day they are away
+async fn waiting_on_away(
+    state: &AppState,
+    person: &Person,
+) -> Result<Vec<(Task, Person, NaiveDate)>> {
+    let handed_out: Vec<Task> = TasksRepo::new(state.read_database.clone())
+        .get_initiated_by(person.id.clone())
+        .await?
+        .into_iter()
+        .filter(|t| t.assigned_to != person.id)
+        .filter(|t| matches!(t.status, TaskStatus::InProgress | TaskStatus::Blocked))
+        .collect();
+    if handed_out.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let assignee_ids: HashSet<String> = handed_out.iter().map(|t| t.assigned_to.clone()).collect();
+    let assignees = PersonsRepo::new(state.read_database.clone())
+        .get_by_ids(assignee_ids.into_iter().collect())
+        .await?;
+    let away =
+        availability::away_today(&state.read_database, &assignees.iter().collect::<Vec<_>>())
+            .await?;
+
+    Ok(handed_out
+        .into_iter()
+        .filter_map(|task| {
+            let until = *away.get(&task.assigned_to)?;
+            let assignee = assignees.iter().find(|p| p.id == task.assigned_to)?.clone();
+            Some((task, assignee, until))
+        })
+        .collect())
+}
+
+/// None when the person has no open tasks and none waits on someone out of office,
+/// so no empty digest is sent
+async fn daily_digest(state: &AppState, person: &Person) -> Result<Option<NotificationText>> {
+    let tasks: Vec<Task> = TasksRepo::new(state.read_database.clone())
+        .get_assigned(person.id.clone())
+        .await?
+        .into_iter()
+        .filter(|t| matches!(t.status, TaskStatus::InProgress | TaskStatus::Blocked))
+        .collect();
+    let waiting = waiting_on_away(state, person).await?;
+
+    if tasks.is_empty() && waiting.is_empty() {
+        return Ok(None);
+    }
+    let locale = i18n::person_locale(&state.database, &person.id, None).await;
+
+    let message_ids = tasks
+        .iter()
+        .chain(waiting.iter().map(|(task, _, _)| task))
+        .map(|t| t.message_id.clone())
+        .collect();
+    let messages: HashMap<String, String> = MessagesRepo::new(
+        state.read_database.clone(),
+        state.message_encryption.clone(),
+    )
+    .get_by_ids(message_ids)
+    .await?
+    .into_iter()
+    .map(|m| (m.id, m.content))
+    .collect();
+
+    let mut lines = Vec::with_capacity(tasks.len());
+    for status in [TaskStatus::Blocked, TaskStatus::InProgress] {
+        for task in tasks.iter().filter(|t| t.status == status) {
+            let content = messages
+                .get(&task.message_id)
+                .map(|c| preview(c))
+                .unwrap_or_default();
+            lines.push(format!("- [{}] {}", i18n::status(locale, &status), content));
+        }
+    }
+
+    let task_list = lines.join("\n");
+    let waiting_list = waiting
+        .iter()
+        .map(|(task, assignee, until)| {
+            let content = messages
+                .get(&task.message_id)
+                .map(|c| preview(c))
+                .unwrap_or_default();
+            i18n::text(
+                locale,
+                "digest.away",
+                &[
+                    ("task", &content),
+                    ("name", &assignee.name),
+                    ("date", &i18n::short_date(locale, *until)),
+                ],
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let count = tasks.len().to_string();
+    let mut sections = Vec::new();
+    if !tasks.is_empty() {
+        sections.push(format!(
+            "{}\n{}",
+            i18n::text(locale, "digest.open_tasks", &[("count", &count)]),
+            task_list
+        ));
+    }
+    if !waiting.is_empty() {
+        sections.push(format!(
+            "{}\n{}",
+            i18n::text(locale, "digest.waiting", &[]),
+            waiting_list
+        ));
+    }
+
+    let today = Utc::now().with_timezone(&person.tz()).date_naive();
+    let date = i18n::short_date(locale, today);
+    let url = format!("{}/", state.config.frontend_url);
+    Ok(Some(NotificationText {
+        subject: i18n::text(locale, "digest.daily_subject", &[("date", &date)]),
+        body: format!("{}\n\n{}", sections.join("\n\n"), url),
+        variables: vec![
+            ("name", person.name.clone()),
+            ("date", date),
+            ("count", count),
+            ("tasks", task_list),
+            ("waiting", waiting_list),
+            ("url", url),
+        ],
+    }))
+}
+
+async fn weekly_summary(state: &AppState, person: &Person) -> Result<NotificationText> {
+    let week_ago = Utc::now().naive_utc() - chrono::Duration::days(7);
+    let tasks = TasksRepo::new(state.read_database.clone())
+        .get_assigned(person.id.clone())
+        .await?;
+
+    let count = |status: TaskStatus| {
+        tasks
+            .iter()
+            .filter(|t| t.status == status)
+            .count()
+            .to_string()
+    };
+    let variables = vec![
+        ("name", person.name.clone()),
+        (
+            "new",
+            tasks
+                .iter()
+                .filter(|t| t.created_at >= week_ago)
+                .count()
+                .to_string(),
+        ),
+        ("in_progress", count(TaskStatus::InProgress)),
+        ("blocked", count(TaskStatus::Blocked)),
+        ("completed", count(TaskStatus::Completed)),
+        ("url", format!("{}/", state.config.frontend_url)),
+    ];
+
+    let locale = i18n::person_locale(&state.database, &person.id, None).await;
+    let counts: Vec<(&str, &str)> = variables
+        .iter()
+        .map(|(name, value)| (*name, value.as_str()))
+        .collect();
+    Ok(NotificationText {
+        subject: i18n::text(locale, "digest.weekly_subject", &[]),
+        body: format!(
+            "{}\n\n{}/",
+            i18n::text(locale, "digest.weekly_body", &counts),
+            state.config.frontend_url
+        ),
+        variables,
+    })
+}
+
+/// First line of a message, shortened for lists
+pub fn preview(content: &str) -> String {
+    let line = content.lines().next().unwrap_or_default();
+    if line.chars().count() > PREVIEW_CHARS {
+        format!("{}…", line.chars().take(PREVIEW_CHARS).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
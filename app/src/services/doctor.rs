@@ -0,0 +1,246 @@
+//! Checks of a deployment's configuration that would otherwise only fail on the
+//! first request that needs it: `slacker-cli doctor` and `slacker --check`.
+
+use std::fmt;
+
+use migration::{Migrator, MigratorTrait};
+use reqwest::Url;
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    config::config::Config,
+    database::connect::connect_database,
+    repos::workspaces::WorkspacesRepo,
+    services::workspace::{fetch_bot_identity, get_workspace_tokens},
+};
+
+/// Shortest JWT secret accepted; HS256 keys below 256 bits are guessable
+const MIN_JWT_SECRET_LEN: usize = 32;
+
+const DEFAULT_ENCRYPTION_KEY: &str = "change-this-default-encryption-key-in-production";
+
+/// Path of the OAuth callback under the API, which `google.redirect_uri` must end in
+const GOOGLE_CALLBACK_PATH: &str = "/api/auth/google/callback";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Ok,
+    /// Works, but is likely to cause trouble
+    Warning,
+    /// Something will fail
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub check: &'static str,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Finding {
+    fn ok(check: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            check,
+            severity: Severity::Ok,
+            message: message.into(),
+        }
+    }
+
+    fn warning(check: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            check,
+            severity: Severity::Warning,
+            message: message.into(),
+        }
+    }
+
+    fn error(check: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            check,
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.severity {
+            Severity::Ok => "ok",
+            Severity::Warning => "warn",
+            Severity::Error => "error",
+        };
+        write!(f, "[{}] {}: {}", label, self.check, self.message)
+    }
+}
+
+/// Checks that only read the config, cheap enough to run on every startup
+pub fn check_config(config: &Config) -> Vec<Finding> {
+    let mut findings = vec![check_jwt_secret(config), check_encryption_key(config)];
+    findings.extend(check_google(config));
+    findings
+}
+
+/// Every check, including the database and a Slack `auth.test` per active workspace
+pub async fn run_checks(config: &Config) -> Vec<Finding> {
+    let mut findings = check_config(config);
+
+    let db = match connect_database(config.clone()).await {
+        Ok(db) => {
+            findings.push(Finding::ok("database", "Connected"));
+            db
+        }
+        Err(e) => {
+            findings.push(Finding::error(
+                "database",
+                format!("Cannot connect with database_url: {}", e),
+            ));
+            return findings;
+        }
+    };
+
+    findings.push(check_migrations(&db).await);
+    findings.extend(check_workspaces(&db, config).await);
+    findings
+}
+
+fn check_jwt_secret(config: &Config) -> Finding {
+    let length = config.jwt.secret.len();
+    if length < MIN_JWT_SECRET_LEN {
+        Finding::error(
+            "jwt",
+            format!(
+                "jwt.secret is {} bytes, use at least {} random bytes (e.g. `openssl rand -hex 32`)",
+                length, MIN_JWT_SECRET_LEN
+            ),
+        )
+    } else {
+        Finding::ok("jwt", format!("Secret is {} bytes", length))
+    }
+}
+
+fn check_encryption_key(config: &Config) -> Finding {
+    if config.encryption_key == DEFAULT_ENCRYPTION_KEY {
+        Finding::warning(
+            "encryption_key",
+            "Using the default key; set ENCRYPTION_KEY before storing any workspace tokens",
+        )
+    } else {
+        Finding::ok("encryption_key", "Set")
+    }
+}
+
+fn check_google(config: &Config) -> Vec<Finding> {
+    let google = &config.google;
+    let mut findings = vec![];
+
+    if !google.client_id.ends_with(".apps.googleusercontent.com") {
+        findings.push(Finding::error(
+            "google",
+            "google.client_id should be an OAuth client id ending in .apps.googleusercontent.com",
+        ));
+    }
+    if google.client_secret.trim().is_empty() {
+        findings.push(Finding::error("google", "google.client_secret is empty"));
+    }
+    match Url::parse(&google.redirect_uri) {
+        Ok(url) if !matches!(url.scheme(), "http" | "https") => findings.push(Finding::error(
+            "google",
+            "google.redirect_uri must be an http(s) URL",
+        )),
+        Ok(url) if !url.path().ends_with(GOOGLE_CALLBACK_PATH) => findings.push(Finding::warning(
+            "google",
+            format!(
+                "google.redirect_uri should point at {} of this server",
+                GOOGLE_CALLBACK_PATH
+            ),
+        )),
+        Ok(_) => {}
+        Err(e) => findings.push(Finding::error(
+            "google",
+            format!("google.redirect_uri is not a URL: {}", e),
+        )),
+    }
+
+    if findings.is_empty() {
+        findings.push(Finding::ok("google", "OAuth settings look valid"));
+    }
+    findings
+}
+
+async fn check_migrations(db: &DatabaseConnection) -> Finding {
+    match Migrator::get_pending_migrations(db).await {
+        Ok(pending) if pending.is_empty() => Finding::ok("migrations", "Up to date"),
+        Ok(pending) => Finding::warning(
+            "migrations",
+            format!(
+                "{} pending; the server applies them on start, or run `slacker-cli migrate`",
+                pending.len()
+            ),
+        ),
+        Err(e) => Finding::error("migrations", format!("Cannot read migrations: {}", e)),
+    }
+}
+
+/// Decrypt the tokens of every active workspace and try them against Slack
+async fn check_workspaces(db: &DatabaseConnection, config: &Config) -> Vec<Finding> {
+    let workspaces = match WorkspacesRepo::new(db.clone()).get_active().await {
+        Ok(workspaces) => workspaces,
+        Err(e) => {
+            return vec![Finding::error(
+                "workspaces",
+                format!("Cannot load workspaces: {}", e),
+            )]
+        }
+    };
+    if workspaces.is_empty() {
+        return vec![Finding::warning(
+            "workspaces",
+            "No active workspaces; add one with `slacker-cli workspace add`",
+        )];
+    }
+
+    let mut findings = vec![];
+    for workspace in workspaces {
+        let tokens = match get_workspace_tokens(db, &workspace.id, &config.encryption_key).await {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                findings.push(Finding::error(
+                    "workspaces",
+                    format!(
+                        "Cannot decrypt the tokens of '{}' ({}); was encryption_key changed since they were stored?",
+                        workspace.name, e
+                    ),
+                ));
+                continue;
+            }
+        };
+
+        match fetch_bot_identity(&tokens.bot_token).await {
+            Ok(bot) if workspace.slack_team_id.as_ref().is_some_and(|id| !bot.serves(id)) => {
+                findings.push(Finding::warning(
+                    "workspaces",
+                    format!(
+                        "The bot token of '{}' belongs to Slack team {}, not the stored {}",
+                        workspace.name,
+                        bot.team_id,
+                        workspace.slack_team_id.as_deref().unwrap_or_default()
+                    ),
+                ))
+            }
+            Ok(_) => findings.push(Finding::ok(
+                "workspaces",
+                format!("'{}' authenticates with Slack", workspace.name),
+            )),
+            Err(e) => findings.push(Finding::error(
+                "workspaces",
+                format!(
+                    "The bot token of '{}' was rejected: {}; rotate it with `slacker-cli token rotate`",
+                    workspace.name, e
+                ),
+            )),
+        }
+    }
+    findings
+}
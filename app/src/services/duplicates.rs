@@ -0,0 +1,102 @@
+//! Duplicate tasks: the same request tasked twice. A new task whose message reads
+//! like the message of an open task in the same channel is flagged when it is
+//! created, and a duplicate can be merged into the task that is kept.
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use sea_orm::DatabaseConnection;
+use tracing::info;
+
+use crate::{
+    core::{board_events::BoardChange, state::AppState},
+    models::{message::Model as Message, task::Model as Task},
+    repos::{
+        changes::ChangesRepo,
+        github_links::GithubLinksRepo,
+        messages::{MessageEncryption, MessagesRepo},
+        tasks::TasksRepo,
+    },
+    services::task_service::TaskService,
+};
+
+/// Share of distinct words two messages must have in common to count as the same
+const SIMILARITY_THRESHOLD: f64 = 0.85;
+
+/// How far back in a channel to look for the original of a new task
+const CANDIDATE_MESSAGES: u64 = 200;
+
+/// Distinct lowercase words of a message, ignoring punctuation and Slack markup
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Whether two messages say the same thing, give or take a word or punctuation
+pub fn is_near_identical(a: &str, b: &str) -> bool {
+    let (a, b) = (words(a), words(b));
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+
+    let shared = a.intersection(&b).count() as f64;
+    let total = a.union(&b).count() as f64;
+    shared / total >= SIMILARITY_THRESHOLD
+}
+
+/// Open tasks of the task's workspace whose messages, in the same channel, read
+/// like the one it was created from
+pub async fn find_similar(
+    db: &DatabaseConnection,
+    encryption: &MessageEncryption,
+    task: &Task,
+    message: &Message,
+) -> Result<Vec<Task>> {
+    let Some(workspace_id) = task.workspace_id.as_deref() else {
+        return Ok(vec![]);
+    };
+
+    let message_ids: Vec<String> = MessagesRepo::new(db.clone(), encryption.clone())
+        .get_recent_in_channel(&message.channel, CANDIDATE_MESSAGES)
+        .await?
+        .into_iter()
+        .filter(|other| {
+            other.id != message.id && is_near_identical(&other.content, &message.content)
+        })
+        .map(|other| other.id)
+        .collect();
+
+    Ok(TasksRepo::new(db.clone())
+        .get_open_by_messages(workspace_id, message_ids)
+        .await?
+        .into_iter()
+        .filter(|other| other.id != task.id)
+        .collect())
+}
+
+/// Fold `duplicate` into `task`: its status history and GitHub links move over,
+/// issue links, due date, priority, project and labels fill in what `task` lacks, and the
+/// duplicate is deleted. Its message is remembered so the bot does not task it again.
+pub async fn merge(state: &AppState, task: Task, duplicate: Task) -> Result<Task> {
+    let db = &state.database;
+
+    ChangesRepo::new(db.clone())
+        .move_to_task(&duplicate.id, &task.id)
+        .await?;
+    GithubLinksRepo::new(db.clone())
+        .move_to_task(&duplicate.id, &task.id)
+        .await?;
+    let task = TasksRepo::new(db.clone()).absorb(task, &duplicate).await?;
+    MessagesRepo::new(db.clone(), state.message_encryption.clone())
+        .set_merged_into(&duplicate.message_id, &task.id)
+        .await?;
+    let tasks = TaskService::from_state(state);
+    let duplicate_id = duplicate.id.clone();
+    tasks.archive(duplicate).await?;
+    info!("Merged task {} into task {}", duplicate_id, task.id);
+    tasks.publish(BoardChange::Updated, task.clone()).await;
+
+    Ok(task)
+}
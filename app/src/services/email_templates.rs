@@ -0,0 +1,211 @@
+//! Emails Slacker sends, each with an HTML version and the plain text for clients
+//! that don't show HTML
+
+/// A rendered email, ready for `Mailer::send`
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+impl Email {
+    /// A notification whose body is the same text a Slack DM gets: paragraphs are
+    /// separated by blank lines, lines starting with `- ` become lists and URLs links.
+    /// Digests, weekly summaries and WIP limit escalations use it.
+    pub fn notification(subject: &str, body: String) -> Self {
+        let html = layout(
+            subject,
+            &text_to_html(&body),
+            "You get this email because of your Slacker notification settings.",
+        );
+        Self {
+            subject: subject.to_string(),
+            text: body,
+            html,
+        }
+    }
+
+    pub fn invitation(
+        invitee_name: &str,
+        inviter_email: &str,
+        workspace_name: &str,
+        accept_url: &str,
+        expiry_hours: i64,
+    ) -> Self {
+        let text = format!(
+            "Hi {},\n\n{} invited you to track your Slack tasks for the '{}' workspace on Slacker.\n\n\
+             Accept the invitation and sign in with Google:\n{}\n\n\
+             This link expires in {} hours.",
+            invitee_name, inviter_email, workspace_name, accept_url, expiry_hours
+        );
+        let content = format!(
+            "<p>Hi {},</p>\n\
+             <p>{} invited you to track your Slack tasks for the <strong>{}</strong> workspace on Slacker.</p>\n\
+             <p>{}</p>\n\
+             <p style=\"color:#71717a;font-size:13px\">This link expires in {} hours.</p>",
+            escape(invitee_name),
+            escape(inviter_email),
+            escape(workspace_name),
+            button("Accept and sign in with Google", accept_url),
+            expiry_hours
+        );
+        let subject = format!("You're invited to {} on Slacker", workspace_name);
+        let html = layout(
+            &subject,
+            &content,
+            "You get this email because a Slacker admin invited you.",
+        );
+        Self {
+            subject,
+            text,
+            html,
+        }
+    }
+
+    /// Sent from `POST /api/admin/test-email` to check the SMTP settings
+    pub fn test(requested_by: &str) -> Self {
+        let subject = "Slacker test email".to_string();
+        let text = format!(
+            "This is a test email requested by {}. If you can read it, Slacker can send email.",
+            requested_by
+        );
+        let html = layout(
+            &subject,
+            &text_to_html(&text),
+            "You get this email because an admin checked Slacker's email settings.",
+        );
+        Self {
+            subject,
+            text,
+            html,
+        }
+    }
+}
+
+fn layout(title: &str, content: &str, footer: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n\
+         <html>\n\
+         <head><meta charset=\"utf-8\"><title>{title}</title></head>\n\
+         <body style=\"margin:0;padding:24px;background:#f4f4f5;font-family:-apple-system,'Segoe UI',Helvetica,Arial,sans-serif;color:#18181b;font-size:15px;line-height:1.5\">\n\
+         <div style=\"max-width:560px;margin:0 auto;background:#ffffff;border-radius:8px;padding:24px\">\n\
+         <h1 style=\"font-size:18px;margin:0 0 16px\">{title}</h1>\n\
+         {content}\n\
+         </div>\n\
+         <p style=\"max-width:560px;margin:16px auto 0;font-size:12px;color:#71717a\">{footer}</p>\n\
+         </body>\n\
+         </html>\n",
+        title = escape(title),
+        content = content,
+        footer = escape(footer),
+    )
+}
+
+fn button(label: &str, url: &str) -> String {
+    format!(
+        "<a href=\"{}\" style=\"display:inline-block;padding:10px 16px;background:#4f46e5;color:#ffffff;border-radius:6px;text-decoration:none\">{}</a>",
+        escape(url),
+        escape(label)
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Links for the http(s) URLs of an escaped line
+fn linkify(line: &str) -> String {
+    line.split(' ')
+        .map(|word| {
+            let (url, rest) = word.split_at(word.trim_end_matches(['.', ',', ')']).len());
+            if url.starts_with("http://") || url.starts_with("https://") {
+                format!("<a href=\"{0}\">{0}</a>{1}", url, rest)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// HTML for a plain-text notification body
+pub fn text_to_html(text: &str) -> String {
+    let mut html = Vec::new();
+    for block in text.split("\n\n").filter(|block| !block.trim().is_empty()) {
+        let mut paragraph: Vec<String> = Vec::new();
+        let mut items: Vec<String> = Vec::new();
+        for line in block.lines() {
+            match line.strip_prefix("- ") {
+                Some(item) => {
+                    flush_paragraph(&mut html, &mut paragraph);
+                    items.push(format!("<li>{}</li>", linkify(&escape(item))));
+                }
+                None => {
+                    flush_list(&mut html, &mut items);
+                    paragraph.push(linkify(&escape(line)));
+                }
+            }
+        }
+        flush_paragraph(&mut html, &mut paragraph);
+        flush_list(&mut html, &mut items);
+    }
+    html.join("\n")
+}
+
+fn flush_paragraph(html: &mut Vec<String>, lines: &mut Vec<String>) {
+    if !lines.is_empty() {
+        html.push(format!("<p>{}</p>", lines.join("<br>")));
+        lines.clear();
+    }
+}
+
+fn flush_list(html: &mut Vec<String>, items: &mut Vec<String>) {
+    if !items.is_empty() {
+        html.push(format!("<ul>{}</ul>", items.concat()));
+        items.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_paragraphs_lists_and_links() {
+        let text = "You have 2 open task(s):\n- [Blocked] Fix <login>\n- [InProgress] Ship & tell\n\nhttps://slacker.example.com/";
+
+        assert_eq!(
+            text_to_html(text),
+            "<p>You have 2 open task(s):</p>\n\
+             <ul><li>[Blocked] Fix &lt;login&gt;</li><li>[InProgress] Ship &amp; tell</li></ul>\n\
+             <p><a href=\"https://slacker.example.com/\">https://slacker.example.com/</a></p>"
+        );
+    }
+
+    #[test]
+    fn keeps_trailing_punctuation_out_of_links() {
+        assert_eq!(
+            text_to_html("See https://example.com/a?b=1&c=2."),
+            "<p>See <a href=\"https://example.com/a?b=1&amp;c=2\">https://example.com/a?b=1&amp;c=2</a>.</p>"
+        );
+    }
+
+    #[test]
+    fn escapes_invitation_fields() {
+        let email = Email::invitation(
+            "Ana <3",
+            "bo@example.com",
+            "acme",
+            "https://slacker.example.com/api/invitations/t/accept",
+            48,
+        );
+
+        assert!(email.html.contains("Hi Ana &lt;3,"));
+        assert!(email.text.starts_with("Hi Ana <3,"));
+        assert_eq!(email.subject, "You're invited to acme on Slacker");
+    }
+}
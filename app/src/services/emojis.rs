@@ -0,0 +1,208 @@
+//! Emoji names a workspace can react with: the standard set plus the workspace's
+//! custom emoji. Emoji mappings are checked against them so a typo doesn't leave a
+//! status that no reaction ever reaches.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    sync::LazyLock,
+};
+
+use anyhow::Result;
+
+use crate::{
+    core::{cache::keys as cache_keys, state::AppState},
+    models::{task::TaskStatus, workspace::Model as Workspace, workspace_settings::EmojiMappings},
+    services::workspace::get_cached_workspace_tokens,
+    sockets::slack_api::SlackClient,
+    utils::response::FieldError,
+};
+
+/// Suggestions further than this many edits from the typed name are left out
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Shortcodes of the standard emoji, as Slack names them
+pub static STANDARD_EMOJI: LazyLock<BTreeSet<&'static str>> = LazyLock::new(|| {
+    emojis::iter()
+        .flat_map(|emoji| emoji.shortcodes())
+        .collect()
+});
+
+/// Names of a workspace's custom emoji, through the cache
+pub async fn custom_emoji(state: &AppState, workspace: &Workspace) -> Result<Vec<String>> {
+    let key = cache_keys::custom_emoji(&workspace.id);
+    if let Some(names) = state.cache.get(&key).await {
+        return Ok(names);
+    }
+
+    let tokens = get_cached_workspace_tokens(
+        &state.cache,
+        &state.database,
+        &workspace.id,
+        &state.config.encryption_key,
+    )
+    .await?;
+    let names = SlackClient::http(tokens.bot_token).list_emoji().await?;
+    state.cache.set(&key, &names).await;
+    Ok(names)
+}
+
+/// `eyes` for `:eyes:` and for a skin tone variant such as `+1::skin-tone-2`
+fn base_name(name: &str) -> &str {
+    let name = name.trim().trim_matches(':');
+    name.split_once("::").map_or(name, |(base, _)| base)
+}
+
+/// Mapped emoji that are neither standard nor custom, with the closest known names
+pub fn unknown_emojis(mappings: &EmojiMappings, custom: &[String]) -> Vec<FieldError> {
+    let custom: HashSet<&str> = custom.iter().map(String::as_str).collect();
+    // Defaults such as `loading` stay allowed in workspaces without that custom emoji
+    let defaults = EmojiMappings::default_mappings();
+    let default_names: HashSet<&str> = [
+        &defaults.in_progress,
+        &defaults.blocked,
+        &defaults.completed,
+    ]
+    .into_iter()
+    .flatten()
+    .map(String::as_str)
+    .collect();
+
+    let mut errors = vec![];
+    for (status, names) in [
+        ("in_progress", &mappings.in_progress),
+        ("blocked", &mappings.blocked),
+        ("completed", &mappings.completed),
+    ] {
+        for (index, name) in names.iter().enumerate() {
+            let base = base_name(name);
+            if STANDARD_EMOJI.contains(base)
+                || custom.contains(base)
+                || default_names.contains(base)
+            {
+                continue;
+            }
+
+            let candidates = STANDARD_EMOJI.iter().copied().chain(custom.iter().copied());
+            let message = match suggestions(base, candidates).as_slice() {
+                [] => format!("Unknown emoji :{}:", base),
+                names => format!(
+                    "Unknown emoji :{}:, did you mean {}?",
+                    base,
+                    names
+                        .iter()
+                        .map(|name| format!(":{}:", name))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            };
+            errors.push(FieldError {
+                field: format!("emoji_mappings.{}[{}]", status, index),
+                message,
+            });
+        }
+    }
+    errors
+}
+
+/// Emoji mapped to more than one status. Reactions pick one status per emoji, so
+/// such mappings are rejected rather than resolved by precedence.
+pub fn overlapping_emojis(mappings: &EmojiMappings) -> Vec<FieldError> {
+    mappings
+        .overlaps()
+        .into_iter()
+        .map(|(emoji, statuses)| FieldError {
+            field: "emoji_mappings".to_string(),
+            message: format!(
+                ":{}: is mapped to {}; an emoji can set only one status",
+                emoji,
+                statuses
+                    .iter()
+                    .map(list_name)
+                    .collect::<Vec<_>>()
+                    .join(" and ")
+            ),
+        })
+        .collect()
+}
+
+/// Name of a status's list in `EmojiMappings`
+fn list_name(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::InProgress => "in_progress",
+        TaskStatus::Blocked => "blocked",
+        TaskStatus::Completed => "completed",
+        TaskStatus::Blank => "blank",
+    }
+}
+
+/// Known names closest to `name`, nearest first
+fn suggestions<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut scored: Vec<(usize, &str)> = candidates
+        .filter(|candidate| candidate.len().abs_diff(name.len()) <= MAX_SUGGESTION_DISTANCE)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .collect();
+    scored.sort();
+    scored.dedup_by_key(|(_, candidate)| *candidate);
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+/// Levenshtein distance
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mappings(completed: &[&str]) -> EmojiMappings {
+        EmojiMappings {
+            completed: completed.iter().map(|name| name.to_string()).collect(),
+            ..EmojiMappings::default()
+        }
+    }
+
+    #[test]
+    fn standard_custom_and_default_names_are_known() {
+        let custom = vec!["shipit".to_string()];
+        let mappings = mappings(&["white_check_mark", ":shipit:", "loading", "+1::skin-tone-3"]);
+        assert!(unknown_emojis(&mappings, &custom).is_empty());
+    }
+
+    #[test]
+    fn typos_get_suggestions() {
+        let errors = unknown_emojis(&mappings(&["eyes", "white_check_mrk"]), &[]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "emoji_mappings.completed[1]");
+        assert!(errors[0].message.contains(":white_check_mark:"));
+    }
+
+    #[test]
+    fn emoji_mapped_to_two_statuses_is_rejected() {
+        let mut mappings = mappings(&["eyes", "white_check_mark"]);
+        mappings.in_progress = vec!["eyes".to_string()];
+        let errors = overlapping_emojis(&mappings);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            ":eyes: is mapped to in_progress and completed; an emoji can set only one status"
+        );
+        assert!(overlapping_emojis(&EmojiMappings::default_mappings()).is_empty());
+    }
+}
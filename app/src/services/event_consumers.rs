@@ -0,0 +1,226 @@
+//! Consumers of the domain event bus: features that react to what happened elsewhere
+//! without the code that made it happen knowing about them.
+
+use std::{future::Future, sync::Arc};
+
+use anyhow::Result;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::{
+    core::{events::DomainEvent, state::AppState},
+    i18n,
+    repos::{audit_logs::AuditLogsRepo, messages::MessagesRepo, persons::PersonsRepo},
+    services::{digest::preview, notification_templates::NotificationText, slack_status},
+};
+
+/// Feed every event to `handle` until shutdown. Events are published only on the
+/// instance they happened on, so every instance runs the consumers.
+async fn consume<F, Fut>(
+    name: &str,
+    state: Arc<AppState>,
+    shutdown_token: CancellationToken,
+    handle: F,
+) where
+    F: Fn(Arc<AppState>, DomainEvent) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let mut events = state.events.subscribe();
+
+    info!("[{}] Consumer started", name);
+    loop {
+        let event = tokio::select! {
+            _ = shutdown_token.cancelled() => break,
+            event = events.recv() => event,
+        };
+
+        match event {
+            Ok(event) => {
+                if let Err(e) = handle(state.clone(), event).await {
+                    error!("[{}] Failed to handle event: {}", name, e);
+                }
+            }
+            Err(RecvError::Lagged(missed)) => {
+                warn!("[{}] Fell behind, {} event(s) missed", name, missed)
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+    info!("[{}] Consumer stopped", name);
+}
+
+/// DM assignees about tasks someone else gave them
+pub async fn run_notification_consumer(state: Arc<AppState>, shutdown_token: CancellationToken) {
+    consume("Notify", state, shutdown_token, notify).await
+}
+
+async fn notify(state: Arc<AppState>, event: DomainEvent) -> Result<()> {
+    let DomainEvent::TaskCreated {
+        task,
+        from_history: false,
+    } = event
+    else {
+        return Ok(());
+    };
+    let (Some(assigned_by), Some(workspace_id)) = (&task.assigned_by, &task.workspace_id) else {
+        return Ok(());
+    };
+    if *assigned_by == task.assigned_to {
+        return Ok(());
+    }
+
+    let persons_repo = PersonsRepo::new(state.database.clone());
+    let assignee = persons_repo.get_by_id(task.assigned_to.clone()).await?;
+    let assigner = persons_repo.get_by_id(assigned_by.clone()).await?;
+    let message = MessagesRepo::new(state.database.clone(), state.message_encryption.clone())
+        .get_by_id(task.message_id.clone())
+        .await?;
+    state
+        .notifier
+        .notify_assignment(workspace_id, &assignee, Some(&assigner), message.content)
+        .await;
+
+    Ok(())
+}
+
+/// Show assignees' task in progress as their Slack status, for those who opted in
+pub async fn run_slack_status_consumer(state: Arc<AppState>, shutdown_token: CancellationToken) {
+    consume("SlackStatus", state, shutdown_token, sync_slack_status).await
+}
+
+async fn sync_slack_status(state: Arc<AppState>, event: DomainEvent) -> Result<()> {
+    let task = match event {
+        DomainEvent::TaskCreated {
+            task,
+            from_history: false,
+        }
+        | DomainEvent::TaskTransitioned { task, .. } => task,
+        _ => return Ok(()),
+    };
+    let Some(workspace_id) = &task.workspace_id else {
+        return Ok(());
+    };
+
+    slack_status::sync(&state, &task.assigned_to, workspace_id).await
+}
+
+/// Post created and moved tasks in the channels following their workspace, e.g.
+/// Discord or Teams
+pub async fn run_activity_consumer(state: Arc<AppState>, shutdown_token: CancellationToken) {
+    consume("Activity", state, shutdown_token, announce_activity).await
+}
+
+async fn announce_activity(state: Arc<AppState>, event: DomainEvent) -> Result<()> {
+    let (task, from) = match event {
+        DomainEvent::TaskCreated {
+            task,
+            from_history: false,
+        } => (task, None),
+        DomainEvent::TaskTransitioned { from, task } => (task, Some(from)),
+        _ => return Ok(()),
+    };
+    let Some(workspace_id) = &task.workspace_id else {
+        return Ok(());
+    };
+
+    let assignee = PersonsRepo::new(state.database.clone())
+        .get_by_id(task.assigned_to.clone())
+        .await?;
+    let message = MessagesRepo::new(state.database.clone(), state.message_encryption.clone())
+        .get_by_id(task.message_id.clone())
+        .await?;
+    let locale = i18n::workspace_locale(&state.database, workspace_id).await;
+    let name = task
+        .key
+        .clone()
+        .unwrap_or_else(|| i18n::text(locale, "notifications.a_task", &[]));
+    let status = i18n::status(locale, &task.status);
+    let from = from.map(|from| i18n::status(locale, &from));
+    let subject = match &from {
+        None => i18n::text(
+            locale,
+            "notifications.task_created",
+            &[("task", &name), ("name", &assignee.name)],
+        ),
+        Some(from) => i18n::text(
+            locale,
+            "notifications.task_moved",
+            &[("task", &name), ("from", from), ("to", &status)],
+        ),
+    };
+    let content = preview(&message.content);
+    let link = if message.is_from_slack() {
+        message.slack_link()
+    } else {
+        String::new()
+    };
+    let body = if link.is_empty() {
+        content.clone()
+    } else {
+        format!("{}\n{}", content, link)
+    };
+
+    let text = NotificationText {
+        subject,
+        body,
+        variables: vec![
+            ("task", name),
+            ("assignee", assignee.name.clone()),
+            ("status", status),
+            ("from", from.unwrap_or_default()),
+            ("message", content),
+            ("link", link),
+        ],
+    };
+    state
+        .notifier
+        .announce(workspace_id, &assignee, text)
+        .await?;
+    Ok(())
+}
+
+/// Keep links to workspaces and bot disconnects in the audit log
+pub async fn run_audit_consumer(state: Arc<AppState>, shutdown_token: CancellationToken) {
+    consume("Audit", state, shutdown_token, audit).await
+}
+
+async fn audit(state: Arc<AppState>, event: DomainEvent) -> Result<()> {
+    let repo = AuditLogsRepo::new(state.database.clone());
+    match event {
+        DomainEvent::WorkspaceLinked {
+            person_id,
+            workspace_id,
+        } => {
+            repo.record(
+                Some(&person_id),
+                "workspace.linked",
+                Some(&workspace_id),
+                None,
+            )
+            .await?;
+        }
+        DomainEvent::BotDisconnected {
+            workspace_id,
+            workspace_name,
+            reason,
+        } => {
+            repo.record(
+                None,
+                "bot.disconnected",
+                Some(&workspace_id),
+                Some(
+                    serde_json::json!({
+                        "workspace": workspace_name,
+                        "reason": reason,
+                    })
+                    .to_string(),
+                ),
+            )
+            .await?;
+        }
+        DomainEvent::TaskCreated { .. } | DomainEvent::TaskTransitioned { .. } => {}
+    }
+
+    Ok(())
+}
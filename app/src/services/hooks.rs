@@ -0,0 +1,286 @@
+//! REST hooks: no-code tools such as Zapier or Make subscribe a URL to a workspace's
+//! task events, and every event is POSTed there as a `HookPayload`.
+//!
+//! Deliveries are signed so receivers can tell them from forgeries:
+//! `X-Slacker-Timestamp` holds the Unix time of sending and `X-Slacker-Signature`
+//! is `sha256=` followed by the hex HMAC-SHA256, keyed with the hook's secret, of
+//! the timestamp, a dot and the raw body. `verify_signature` is the check a
+//! receiver does.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use hmac::{Hmac, Mac};
+use reqwest::{header, Client, Response};
+use serde::Serialize;
+use sha2::Sha256;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use crate::{
+    core::{
+        board_events::{BoardChange, BoardEvent},
+        jobs::{self, Job},
+        state::AppState,
+    },
+    models::{
+        hook_subscription::{HookEvent, Model as HookSubscription},
+        task::{Model as Task, TaskStatus},
+    },
+    repos::hook_subscriptions::HookSubscriptionsRepo,
+    utils::encryption::decrypt,
+};
+
+pub const SIGNATURE_HEADER: &str = "X-Slacker-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Slacker-Timestamp";
+
+/// Deliveries older or newer than this are rejected by `verify_signature`, so a
+/// captured one can't be replayed later
+const SIGNATURE_TOLERANCE_SECS: i64 = 5 * 60;
+
+/// Body POSTed to a hook's target URL
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HookPayload {
+    pub event: HookEvent,
+    pub workspace: String,
+    pub occurred_at: String,
+    pub task: Task,
+}
+
+/// Hook events a board change raises
+fn hook_events(change: BoardChange, task: &Task) -> Vec<HookEvent> {
+    match change {
+        BoardChange::Created => vec![HookEvent::TaskCreated],
+        BoardChange::Updated if task.status == TaskStatus::Completed => {
+            vec![HookEvent::TaskUpdated, HookEvent::TaskCompleted]
+        }
+        BoardChange::Updated => vec![HookEvent::TaskUpdated],
+        BoardChange::Deleted => vec![],
+    }
+}
+
+/// Queue deliveries for task changes until shutdown. Each change is published only
+/// on the instance that made it, so every instance runs a dispatcher.
+pub async fn run_hook_dispatcher(state: Arc<AppState>, shutdown_token: CancellationToken) {
+    let mut events = state.board_events.subscribe();
+
+    info!("[Hooks] Dispatcher started");
+    loop {
+        let event = tokio::select! {
+            _ = shutdown_token.cancelled() => break,
+            event = events.recv() => event,
+        };
+
+        match event {
+            Ok(event) => {
+                if let Err(e) = dispatch(&state, event).await {
+                    error!("[Hooks] Failed to queue deliveries: {}", e);
+                }
+            }
+            Err(RecvError::Lagged(missed)) => {
+                warn!(
+                    "[Hooks] Fell behind, {} task change(s) not delivered",
+                    missed
+                )
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+    info!("[Hooks] Dispatcher stopped");
+}
+
+async fn dispatch(state: &AppState, event: BoardEvent) -> Result<()> {
+    let Some(workspace_id) = event.task.workspace_id.clone() else {
+        return Ok(());
+    };
+    let repo = HookSubscriptionsRepo::new(state.database.clone());
+
+    for hook_event in hook_events(event.change, &event.task) {
+        let subscriptions = repo
+            .get_for_event(&workspace_id, hook_event.clone())
+            .await?;
+        if subscriptions.is_empty() {
+            continue;
+        }
+
+        let body = serde_json::to_value(HookPayload {
+            event: hook_event,
+            workspace: event.workspace_name.clone(),
+            occurred_at: chrono::Utc::now().to_rfc3339(),
+            task: event.task.clone(),
+        })?;
+        for subscription in subscriptions {
+            jobs::enqueue(
+                &state.database,
+                Job::HookDelivery {
+                    subscription_id: subscription.id,
+                    url: subscription.target_url,
+                    body: body.clone(),
+                },
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A fresh signing secret, shown to the subscriber once
+pub fn new_secret() -> String {
+    nanoid::nanoid!(32)
+}
+
+/// The readable signing secret of a subscription, if it has one
+pub fn signing_secret(
+    subscription: &HookSubscription,
+    encryption_key: &str,
+) -> Result<Option<String>> {
+    subscription
+        .secret
+        .as_deref()
+        .map(|secret| decrypt(secret, encryption_key))
+        .transpose()
+}
+
+/// `X-Slacker-Signature` of a body sent at `timestamp`
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// What a receiver checks: the signature matches the timestamp and raw body, and
+/// the timestamp is recent
+pub fn verify_signature(secret: &str, timestamp: &str, body: &[u8], signature: &str) -> bool {
+    let Ok(timestamp) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    if (chrono::Utc::now().timestamp() - timestamp).abs() > SIGNATURE_TOLERANCE_SECS {
+        return false;
+    }
+    let Some(signature) = signature
+        .strip_prefix("sha256=")
+        .and_then(|hex| hex::decode(hex).ok())
+    else {
+        return false;
+    };
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// POST a payload to a hook's target, signed when the hook has a secret
+pub async fn deliver(
+    client: &Client,
+    url: &str,
+    body: &serde_json::Value,
+    secret: Option<&str>,
+) -> Result<Response> {
+    let body = serde_json::to_vec(body)?;
+    let mut request = client
+        .post(url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .timeout(std::time::Duration::from_secs(10));
+    if let Some(secret) = secret {
+        let timestamp = chrono::Utc::now().timestamp();
+        request = request
+            .header(TIMESTAMP_HEADER, timestamp)
+            .header(SIGNATURE_HEADER, sign(secret, timestamp, &body));
+    }
+    Ok(request.body(body).send().await?)
+}
+
+/// Example of what a hook receives, for tools that show fields before the first event
+pub fn sample_payload(event: HookEvent, workspace: &str) -> HookPayload {
+    let now = chrono::Utc::now().naive_utc();
+    let status = match event {
+        HookEvent::TaskCompleted => TaskStatus::Completed,
+        _ => TaskStatus::InProgress,
+    };
+
+    HookPayload {
+        event,
+        workspace: workspace.to_string(),
+        occurred_at: now.and_utc().to_rfc3339(),
+        task: Task {
+            id: "sample-task-id".to_string(),
+            completed_at: (status == TaskStatus::Completed).then_some(now),
+            status,
+            assigned_to: "sample-assignee-id".to_string(),
+            assigned_by: Some("sample-assigner-id".to_string()),
+            created_at: now,
+            message_id: "sample-message-id".to_string(),
+            workspace_id: Some("sample-workspace-id".to_string()),
+            jira_issue_key: None,
+            linear_issue_id: None,
+            due_at: None,
+            notion_page_id: None,
+            priority: None,
+            labels: None,
+            project_id: None,
+            orphaned_at: None,
+            key: Some("SAMPLE-1".to_string()),
+            version: 0,
+            updated_at: now,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_signed_deliveries() {
+        let body = br#"{"event":"task_created"}"#;
+        let now = chrono::Utc::now().timestamp();
+        let signature = sign("secret", now, body);
+
+        assert!(verify_signature(
+            "secret",
+            &now.to_string(),
+            body,
+            &signature
+        ));
+        assert!(!verify_signature(
+            "other",
+            &now.to_string(),
+            body,
+            &signature
+        ));
+        assert!(!verify_signature(
+            "secret",
+            &now.to_string(),
+            b"{}",
+            &signature
+        ));
+        assert!(!verify_signature(
+            "secret",
+            &(now + 1).to_string(),
+            body,
+            &signature
+        ));
+    }
+
+    #[test]
+    fn rejects_old_deliveries() {
+        let body = b"{}";
+        let then = chrono::Utc::now().timestamp() - SIGNATURE_TOLERANCE_SECS - 60;
+        let signature = sign("secret", then, body);
+
+        assert!(!verify_signature(
+            "secret",
+            &then.to_string(),
+            body,
+            &signature
+        ));
+    }
+}
@@ -0,0 +1,72 @@
+use anyhow::Result;
+use sea_orm::DatabaseConnection;
+use tracing::info;
+
+use crate::{
+    core::events::{DomainEvent, EventBus},
+    models::{
+        invitation::{InvitationStatus, Model as Invitation},
+        person::Model as Person,
+    },
+    repos::{
+        invitations::InvitationsRepo, persons::PersonsRepo, workspace_links::WorkspaceLinksRepo,
+    },
+};
+
+/// Link a person to every workspace they have a valid pending invitation for.
+/// Expired invitations are marked as such along the way.
+pub async fn accept_pending_invitations(
+    db: &DatabaseConnection,
+    events: &EventBus,
+    person: &Person,
+) -> Result<Vec<Invitation>> {
+    let invitations_repo = InvitationsRepo::new(db.clone());
+    let workspace_links_repo = WorkspaceLinksRepo::new(db.clone());
+
+    let mut accepted = Vec::new();
+    for invitation in invitations_repo.get_pending_by_email(&person.email).await? {
+        if invitation.is_expired() {
+            invitations_repo
+                .set_status(invitation, InvitationStatus::Expired)
+                .await?;
+            continue;
+        }
+
+        workspace_links_repo
+            .link_workspace(
+                person.id.clone(),
+                invitation.workspace_id.clone(),
+                invitation.slack_member_id.clone(),
+            )
+            .await?;
+        workspace_links_repo
+            .set_role(
+                person.id.clone(),
+                invitation.workspace_id.clone(),
+                invitation.role,
+            )
+            .await?;
+        events.publish(DomainEvent::WorkspaceLinked {
+            person_id: person.id.clone(),
+            workspace_id: invitation.workspace_id.clone(),
+        });
+
+        if person.external_id.is_empty() {
+            PersonsRepo::new(db.clone())
+                .update_external_id(person.id.clone(), invitation.slack_member_id.clone())
+                .await?;
+        }
+
+        info!(
+            "{} accepted invitation to workspace {} as {:?}",
+            person.email, invitation.workspace_id, invitation.role
+        );
+        accepted.push(
+            invitations_repo
+                .set_status(invitation, InvitationStatus::Accepted)
+                .await?,
+        );
+    }
+
+    Ok(accepted)
+}
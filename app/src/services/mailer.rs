@@ -0,0 +1,80 @@
+use anyhow::Result;
+use lettre::{
+    message::{Mailbox, MultiPart},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use tracing::{info, warn};
+
+use crate::{config::config::Config, services::email_templates::Email};
+
+/// Sends email over SMTP, or only logs it when SMTP is not configured
+#[derive(Clone)]
+pub struct Mailer {
+    transport: Option<AsyncSmtpTransport<Tokio1Executor>>,
+    from: Mailbox,
+}
+
+impl std::fmt::Debug for Mailer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Mailer")
+            .field("enabled", &self.transport.is_some())
+            .field("from", &self.from.to_string())
+            .finish()
+    }
+}
+
+impl Mailer {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let from = config.smtp_from.parse::<Mailbox>()?;
+
+        let transport = match config.smtp_host.as_deref().filter(|h| !h.is_empty()) {
+            Some(host) => {
+                let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?
+                    .port(config.smtp_port);
+                if let (Some(username), Some(password)) =
+                    (&config.smtp_username, &config.smtp_password)
+                {
+                    builder =
+                        builder.credentials(Credentials::new(username.clone(), password.clone()));
+                }
+                Some(builder.build())
+            }
+            None => {
+                warn!("SMTP_HOST not set, emails will be logged instead of sent");
+                None
+            }
+        };
+
+        Ok(Self { transport, from })
+    }
+
+    /// Whether emails are sent rather than logged
+    pub fn is_enabled(&self) -> bool {
+        self.transport.is_some()
+    }
+
+    /// Send an email with its HTML and plain-text versions
+    pub async fn send(&self, to: &str, email: &Email) -> Result<()> {
+        let Some(transport) = &self.transport else {
+            info!(
+                "[Mailer] To: {} | Subject: {}\n{}",
+                to, email.subject, email.text
+            );
+            return Ok(());
+        };
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to.parse()?)
+            .subject(email.subject.as_str())
+            .multipart(MultiPart::alternative_plain_html(
+                email.text.clone(),
+                email.html.clone(),
+            ))?;
+
+        transport.send(message).await?;
+        info!("[Mailer] Sent '{}' to {}", email.subject, to);
+        Ok(())
+    }
+}
@@ -1,2 +1,36 @@
+pub mod archive;
+pub mod availability;
+pub mod backup;
+pub mod board_snapshots;
+pub mod business_hours;
+pub mod calendar;
+pub mod database_backups;
+pub mod deprovision;
+pub mod diagnostics;
+pub mod digest;
+pub mod doctor;
+pub mod duplicates;
+pub mod email_templates;
+pub mod emojis;
+pub mod event_consumers;
+pub mod hooks;
+pub mod invitation;
+pub mod mailer;
+pub mod notification_templates;
+pub mod notifications;
+pub mod privacy;
+pub mod reminders;
+pub mod retention;
+pub mod sla;
 pub mod slack_service;
+pub mod slack_status;
+pub mod standups;
+pub mod task_import;
+pub mod task_keys;
+pub mod task_service;
+pub mod task_templates;
+pub mod unfurls;
 pub mod user;
+pub mod wip_limits;
+pub mod workload;
+pub mod workspace;
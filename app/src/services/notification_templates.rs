@@ -0,0 +1,280 @@
+//! Workspace templates that replace the built-in text of notifications. Templates
+//! use `{{variable}}` placeholders; each kind of notification offers a fixed set of
+//! variables, and saving a template with any other is refused, so templates only
+//! ever see the values meant for them.
+
+use sea_orm::DatabaseConnection;
+use tracing::warn;
+
+use crate::{
+    models::{notification_setting::NotificationKind, workspace_settings::NotificationTemplate},
+    repos::workspace_settings::WorkspaceSettingsRepo,
+    utils::response::FieldError,
+};
+
+/// Longest subject or body a template may have, in characters
+const MAX_TEMPLATE_CHARS: usize = 4000;
+
+/// Kinds of notification a workspace may have templates for
+pub const TEMPLATE_KINDS: [NotificationKind; 6] = [
+    NotificationKind::Assignment,
+    NotificationKind::DailyDigest,
+    NotificationKind::WeeklySummary,
+    NotificationKind::WipLimit,
+    NotificationKind::SlaBreach,
+    NotificationKind::TaskActivity,
+];
+
+/// Text of a notification as built in, with the values a template may use instead
+#[derive(Debug, Clone)]
+pub struct NotificationText {
+    pub subject: String,
+    pub body: String,
+    pub variables: Vec<(&'static str, String)>,
+}
+
+impl NotificationText {
+    /// The text with the template's subject and body, where it has them
+    pub fn with_template(self, template: &NotificationTemplate) -> Self {
+        let subject = match &template.subject {
+            // Subjects stay on one line, even with a multi-line variable
+            Some(subject) => render(subject, &self.variables)
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join(" "),
+            None => self.subject,
+        };
+        let body = match &template.body {
+            Some(body) => render(body, &self.variables),
+            None => self.body,
+        };
+        Self {
+            subject,
+            body,
+            variables: self.variables,
+        }
+    }
+}
+
+/// Variables templates of a kind may use
+pub fn variables(kind: NotificationKind) -> &'static [&'static str] {
+    match kind {
+        NotificationKind::Assignment => &["assignee", "assigner", "message"],
+        NotificationKind::DailyDigest => &["name", "date", "count", "tasks", "waiting", "url"],
+        NotificationKind::WeeklySummary => {
+            &["name", "new", "in_progress", "blocked", "completed", "url"]
+        }
+        NotificationKind::WipLimit => &["assignee", "reasons"],
+        NotificationKind::SlaBreach => &["task", "assignee", "policy", "status", "hours", "link"],
+        NotificationKind::TaskActivity => {
+            &["task", "assignee", "status", "from", "message", "link"]
+        }
+        // Sent to the super admin about the whole server, not about a workspace
+        NotificationKind::LatencyAlert => &[],
+    }
+}
+
+/// Values of a kind's variables for previews
+pub fn sample_variables(kind: NotificationKind, frontend_url: &str) -> Vec<(&'static str, String)> {
+    variables(kind)
+        .iter()
+        .map(|&name| {
+            let value = match name {
+                "assignee" | "name" => "Ada Lovelace".to_string(),
+                "assigner" => "Grace Hopper".to_string(),
+                "message" => "Can you review the release notes before Friday?".to_string(),
+                "date" => "Mon, 23 Mar".to_string(),
+                "count" => "2".to_string(),
+                "tasks" => {
+                    "- [Blocked] Fix the login page\n- [In Progress] Review the release notes"
+                        .to_string()
+                }
+                "waiting" => {
+                    "- Update the roadmap (Grace Hopper is out of office until Fri, 27 Mar)"
+                        .to_string()
+                }
+                "url" => format!("{}/", frontend_url),
+                "new" => "3".to_string(),
+                "in_progress" => "2".to_string(),
+                "blocked" => "1".to_string(),
+                "completed" => "5".to_string(),
+                "reasons" => "WIP limit reached: the In Progress column already holds 5 task(s), \
+                              its limit is 5."
+                    .to_string(),
+                "task" => "ACME-42".to_string(),
+                "status" => "Blocked".to_string(),
+                "policy" => "Unblock within a day".to_string(),
+                "hours" => "8".to_string(),
+                "from" => "In Progress".to_string(),
+                "link" => "https://acme.slack.com/archives/C0123/p1774252800000000".to_string(),
+                _ => String::new(),
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+/// Names of the `{{variable}}`s in a template, or the first placeholder left open
+fn placeholders(template: &str) -> Result<Vec<&str>, String> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| "has a {{ without a closing }}".to_string())?;
+        names.push(after[..end].trim());
+        rest = &after[end + 2..];
+    }
+    Ok(names)
+}
+
+/// Fill a template's `{{variable}}`s. Variables it has no value for become empty.
+pub fn render(template: &str, variables: &[(&str, String)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rendered.push_str(&rest[start..]);
+            return rendered;
+        };
+        let name = after[..end].trim();
+        if let Some((_, value)) = variables.iter().find(|(variable, _)| *variable == name) {
+            rendered.push_str(value);
+        }
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Why templates can't be saved, with `field` prefixed to each error's path, e.g.
+/// `templates[0]`
+pub fn validate(template: &NotificationTemplate, field: &str) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+    let allowed = variables(template.kind);
+    if allowed.is_empty() {
+        errors.push(FieldError {
+            field: format!("{}.kind", field),
+            message: "is not about a workspace and can't have a template".to_string(),
+        });
+        return errors;
+    }
+    if template.subject.is_none() && template.body.is_none() {
+        errors.push(FieldError {
+            field: field.to_string(),
+            message: "needs a subject or a body".to_string(),
+        });
+    }
+
+    for (name, text) in [("subject", &template.subject), ("body", &template.body)] {
+        let Some(text) = text else {
+            continue;
+        };
+        let field = format!("{}.{}", field, name);
+        if text.trim().is_empty() {
+            errors.push(FieldError {
+                field,
+                message: "can't be empty; leave it out to keep the built-in text".to_string(),
+            });
+            continue;
+        }
+        if text.chars().count() > MAX_TEMPLATE_CHARS {
+            errors.push(FieldError {
+                field,
+                message: format!("is longer than {} characters", MAX_TEMPLATE_CHARS),
+            });
+            continue;
+        }
+        match placeholders(text) {
+            Err(message) => errors.push(FieldError { field, message }),
+            Ok(names) => {
+                if let Some(unknown) = names.iter().find(|name| !allowed.contains(*name)) {
+                    errors.push(FieldError {
+                        field,
+                        message: format!(
+                            "uses {{{{{}}}}}, which is not one of {}",
+                            unknown,
+                            allowed.join(", ")
+                        ),
+                    });
+                }
+            }
+        }
+    }
+    errors
+}
+
+/// The text with the workspace's template for the kind, when it has one. Templates
+/// that fail to load leave the built-in text.
+pub async fn apply(
+    db: &DatabaseConnection,
+    workspace_id: &str,
+    kind: NotificationKind,
+    text: NotificationText,
+) -> NotificationText {
+    let template = match WorkspaceSettingsRepo::new(db.clone())
+        .get_notification_templates(workspace_id)
+        .await
+    {
+        Ok(templates) => templates.into_iter().find(|t| t.kind == kind),
+        Err(e) => {
+            warn!(
+                "[Notify] Failed to load notification templates of workspace {}: {}",
+                workspace_id, e
+            );
+            None
+        }
+    };
+    match template {
+        Some(template) => text.with_template(&template),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(subject: Option<&str>, body: Option<&str>) -> NotificationTemplate {
+        NotificationTemplate {
+            kind: NotificationKind::Assignment,
+            subject: subject.map(str::to_string),
+            body: body.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn renders_allowed_variables() {
+        let text = NotificationText {
+            subject: "New task from Grace".to_string(),
+            body: "Review it".to_string(),
+            variables: vec![
+                ("assigner", "Grace".to_string()),
+                ("message", "Review it\nplease".to_string()),
+            ],
+        }
+        .with_template(&template(Some("{{ assigner }}: {{message}}"), None));
+        assert_eq!(text.subject, "Grace: Review it please");
+        assert_eq!(text.body, "Review it");
+        assert_eq!(render("{{missing}}!{{", &[]), "!{{");
+    }
+
+    #[test]
+    fn refuses_unknown_variables() {
+        let errors = validate(
+            &template(Some("{{assigner}}"), Some("{{ person.email }}")),
+            "templates[0]",
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "templates[0].body");
+
+        let errors = validate(&template(None, Some("{{assigner")), "templates[0]");
+        assert_eq!(errors[0].message, "has a {{ without a closing }}");
+        assert_eq!(validate(&template(None, None), "templates[0]").len(), 1);
+    }
+}
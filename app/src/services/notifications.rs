@@ -0,0 +1,588 @@
+//! Delivery of notifications. Each channel is a `Notifier`; `Notifications` picks
+//! the channels of a notification from the workspace's routes or the person's
+//! settings and hands it to their notifiers, so a new channel only needs a
+//! `NotificationChannel` variant and a `Notifier` registered for it.
+
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Result;
+use axum::async_trait;
+use reqwest::Client;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::{
+    core::{
+        cache::Cache,
+        jobs::{self, Job},
+    },
+    i18n,
+    integrations::{discord, teams},
+    models::{
+        notification_setting::{
+            Model as NotificationSetting, NotificationChannel, NotificationKind,
+        },
+        person::Model as Person,
+    },
+    repos::{
+        notification_settings::NotificationSettingsRepo, workspace_links::WorkspaceLinksRepo,
+        workspace_settings::WorkspaceSettingsRepo,
+    },
+    services::{
+        email_templates::Email,
+        mailer::Mailer,
+        notification_templates::{self, NotificationText},
+        workspace::get_cached_workspace_tokens,
+    },
+    utils::encryption::decrypt,
+};
+
+/// A notification on its way to one person
+#[derive(Debug, Clone, Copy)]
+pub struct Delivery<'a> {
+    pub person: &'a Person,
+    pub settings: &'a NotificationSetting,
+    pub kind: NotificationKind,
+    /// Workspace the notification is about, if any
+    pub workspace_id: Option<&'a str>,
+    pub subject: &'a str,
+    pub body: &'a str,
+}
+
+/// Sends notifications over one channel
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn send(&self, delivery: Delivery<'_>) -> Result<()>;
+}
+
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    kind: NotificationKind,
+    email: &'a str,
+    subject: &'a str,
+    body: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct PostMessageResponse {
+    ok: bool,
+    error: Option<String>,
+}
+
+/// DMs from the bot of the notification's workspace, or the person's active one
+#[derive(Clone, Debug)]
+pub struct SlackDmNotifier {
+    db: DatabaseConnection,
+    cache: Cache,
+    encryption_key: String,
+    http_client: Client,
+}
+
+impl SlackDmNotifier {
+    pub fn new(db: DatabaseConnection, cache: Cache, encryption_key: String) -> Self {
+        Self {
+            db,
+            cache,
+            encryption_key,
+            http_client: Client::new(),
+        }
+    }
+
+    async fn send_dm(
+        &self,
+        person: &Person,
+        workspace_id: Option<&str>,
+        text: &str,
+        blocks: Option<&Value>,
+    ) -> Result<()> {
+        let links_repo = WorkspaceLinksRepo::new(self.db.clone());
+        let link = match workspace_id {
+            Some(workspace_id) => {
+                links_repo
+                    .get_by_person_and_workspace(person.id.clone(), workspace_id.to_string())
+                    .await?
+            }
+            None => links_repo.get_active_workspace(person.id.clone()).await?,
+        };
+
+        let member_id = link
+            .slack_member_id
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| person.external_id.clone());
+        let tokens = get_cached_workspace_tokens(
+            &self.cache,
+            &self.db,
+            &link.workspace_id,
+            &self.encryption_key,
+        )
+        .await?;
+
+        let mut body = serde_json::json!({ "channel": member_id, "text": text });
+        if let Some(blocks) = blocks {
+            body["blocks"] = blocks.clone();
+        }
+
+        // Posting to a member ID opens the bot's DM with them
+        let response = self
+            .http_client
+            .post("https://slack.com/api/chat.postMessage")
+            .header("Authorization", format!("Bearer {}", tokens.bot_token))
+            .json(&body)
+            .send()
+            .await?
+            .json::<PostMessageResponse>()
+            .await?;
+
+        if !response.ok {
+            return Err(anyhow::anyhow!(
+                "Slack chat.postMessage failed: {}",
+                response
+                    .error
+                    .unwrap_or_else(|| "unknown error".to_string())
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackDmNotifier {
+    async fn send(&self, delivery: Delivery<'_>) -> Result<()> {
+        self.send_dm(
+            delivery.person,
+            delivery.workspace_id,
+            &format!("*{}*\n{}", delivery.subject, delivery.body),
+            None,
+        )
+        .await
+    }
+}
+
+/// HTML email to the person's address
+#[derive(Clone, Debug)]
+pub struct EmailNotifier {
+    mailer: Mailer,
+}
+
+impl EmailNotifier {
+    pub fn new(mailer: Mailer) -> Self {
+        Self { mailer }
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    async fn send(&self, delivery: Delivery<'_>) -> Result<()> {
+        let email = Email::notification(delivery.subject, delivery.body.to_string());
+        self.mailer.send(&delivery.person.email, &email).await
+    }
+}
+
+/// JSON POST to the person's webhook URL, delivered by the job queue
+#[derive(Clone, Debug)]
+pub struct WebhookNotifier {
+    db: DatabaseConnection,
+}
+
+impl WebhookNotifier {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn send(&self, delivery: Delivery<'_>) -> Result<()> {
+        let url = delivery
+            .settings
+            .webhook_url
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("No webhook URL configured"))?;
+        let body = serde_json::to_value(WebhookPayload {
+            kind: delivery.kind,
+            email: &delivery.person.email,
+            subject: delivery.subject,
+            body: delivery.body,
+        })?;
+        // The job queue retries while the endpoint is down
+        jobs::enqueue(&self.db, Job::WebhookDelivery { url, body }).await?;
+        Ok(())
+    }
+}
+
+/// Message in the Discord channel following the notification's workspace
+#[derive(Clone, Debug)]
+pub struct DiscordNotifier {
+    db: DatabaseConnection,
+    encryption_key: String,
+    http_client: Client,
+}
+
+impl DiscordNotifier {
+    pub fn new(db: DatabaseConnection, encryption_key: String) -> Self {
+        Self {
+            db,
+            encryption_key,
+            http_client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    async fn send(&self, delivery: Delivery<'_>) -> Result<()> {
+        let workspace_id = delivery
+            .workspace_id
+            .ok_or_else(|| anyhow::anyhow!("Discord posts need a workspace"))?;
+        let settings = WorkspaceSettingsRepo::new(self.db.clone())
+            .get_discord_settings(workspace_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Discord is not connected"))?;
+        let url = decrypt(&settings.webhook_url, &self.encryption_key)?;
+
+        // Everyone in the channel reads it, so say who it is for
+        let title = match delivery.kind {
+            NotificationKind::TaskActivity => delivery.subject.to_string(),
+            _ => format!("{} ({})", delivery.subject, delivery.person.name),
+        };
+        discord::post(&self.http_client, &url, &title, delivery.body).await
+    }
+}
+
+/// Adaptive Card in the Microsoft Teams channel following the notification's
+/// workspace
+#[derive(Clone, Debug)]
+pub struct TeamsNotifier {
+    db: DatabaseConnection,
+    encryption_key: String,
+    http_client: Client,
+}
+
+impl TeamsNotifier {
+    pub fn new(db: DatabaseConnection, encryption_key: String) -> Self {
+        Self {
+            db,
+            encryption_key,
+            http_client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for TeamsNotifier {
+    async fn send(&self, delivery: Delivery<'_>) -> Result<()> {
+        let workspace_id = delivery
+            .workspace_id
+            .ok_or_else(|| anyhow::anyhow!("Teams posts need a workspace"))?;
+        let settings = WorkspaceSettingsRepo::new(self.db.clone())
+            .get_teams_settings(workspace_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Teams is not connected"))?;
+        let url = decrypt(&settings.webhook_url, &self.encryption_key)?;
+
+        let title = match delivery.kind {
+            NotificationKind::TaskActivity => delivery.subject.to_string(),
+            _ => format!("{} ({})", delivery.subject, delivery.person.name),
+        };
+        teams::post(&self.http_client, &url, &title, delivery.body).await
+    }
+}
+
+/// Delivers notifications over the channels the workspace routes them to, or else
+/// the channel each person chose in their settings
+#[derive(Clone)]
+pub struct Notifications {
+    db: DatabaseConnection,
+    slack: Arc<SlackDmNotifier>,
+    notifiers: HashMap<NotificationChannel, Arc<dyn Notifier>>,
+}
+
+impl std::fmt::Debug for Notifications {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Notifications")
+            .field("channels", &self.notifiers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Notifications {
+    /// With the Slack DM, email, webhook, Discord and Teams notifiers registered
+    pub fn new(
+        db: DatabaseConnection,
+        mailer: Mailer,
+        cache: Cache,
+        encryption_key: String,
+    ) -> Self {
+        let slack = Arc::new(SlackDmNotifier::new(
+            db.clone(),
+            cache,
+            encryption_key.clone(),
+        ));
+        let mut notifications = Self {
+            db: db.clone(),
+            slack: slack.clone(),
+            notifiers: HashMap::new(),
+        };
+        notifications.register(NotificationChannel::SlackDm, slack);
+        notifications.register(
+            NotificationChannel::Email,
+            Arc::new(EmailNotifier::new(mailer)),
+        );
+        notifications.register(
+            NotificationChannel::Webhook,
+            Arc::new(WebhookNotifier::new(db.clone())),
+        );
+        notifications.register(
+            NotificationChannel::Discord,
+            Arc::new(DiscordNotifier::new(db.clone(), encryption_key.clone())),
+        );
+        notifications.register(
+            NotificationChannel::Teams,
+            Arc::new(TeamsNotifier::new(db, encryption_key)),
+        );
+        notifications
+    }
+
+    /// Send notifications of `channel` through `notifier`, replacing any notifier
+    /// registered for it before
+    pub fn register(&mut self, channel: NotificationChannel, notifier: Arc<dyn Notifier>) {
+        self.notifiers.insert(channel, notifier);
+    }
+
+    /// Send a notification unless the person has not opted in to this kind.
+    /// `workspace_id` picks the workspace's routes and the bot for Slack DMs,
+    /// defaulting to the person's channel and active workspace.
+    /// Returns whether anything was sent; one channel reaching the person is enough.
+    pub async fn notify(
+        &self,
+        person: &Person,
+        kind: NotificationKind,
+        workspace_id: Option<&str>,
+        subject: &str,
+        body: String,
+    ) -> Result<bool> {
+        let settings = NotificationSettingsRepo::new(self.db.clone())
+            .get_or_default(&person.id)
+            .await?;
+
+        if !kind.is_enabled(&settings) {
+            return Ok(false);
+        }
+
+        let channels = self.channels(&settings, kind, workspace_id).await?;
+        let delivery = Delivery {
+            person,
+            settings: &settings,
+            kind,
+            workspace_id,
+            subject,
+            body: &body,
+        };
+        self.deliver(delivery, channels).await?;
+        Ok(true)
+    }
+
+    /// `notify` with the workspace's template for the kind, when it has one, in
+    /// place of the built-in text. Without `workspace_id` the templates of the
+    /// person's active workspace apply.
+    pub async fn notify_text(
+        &self,
+        person: &Person,
+        kind: NotificationKind,
+        workspace_id: Option<&str>,
+        text: NotificationText,
+    ) -> Result<bool> {
+        let template_workspace = match workspace_id {
+            Some(workspace_id) => Some(workspace_id.to_string()),
+            None => WorkspaceLinksRepo::new(self.db.clone())
+                .get_active_workspace(person.id.clone())
+                .await
+                .ok()
+                .map(|link| link.workspace_id),
+        };
+        let text = match template_workspace {
+            Some(template_workspace) => {
+                notification_templates::apply(&self.db, &template_workspace, kind, text).await
+            }
+            None => text,
+        };
+        self.notify(person, kind, workspace_id, &text.subject, text.body)
+            .await
+    }
+
+    /// Post task activity in the workspace's channels, e.g. Discord or Teams: those
+    /// its `TaskActivity` route names, else every connected one. `person` is who the
+    /// task is assigned to. Returns whether anything was sent.
+    pub async fn announce(
+        &self,
+        workspace_id: &str,
+        person: &Person,
+        text: NotificationText,
+    ) -> Result<bool> {
+        let Some(workspace_settings) = WorkspaceSettingsRepo::new(self.db.clone())
+            .get_by_workspace(workspace_id)
+            .await?
+        else {
+            return Ok(false);
+        };
+        let channels = workspace_settings
+            .get_notification_routes()
+            .into_iter()
+            .find(|route| route.kind == NotificationKind::TaskActivity)
+            .map(|route| route.channels)
+            .unwrap_or_else(|| workspace_settings.connected_channels());
+        if channels.is_empty() {
+            return Ok(false);
+        }
+
+        let text = notification_templates::apply(
+            &self.db,
+            workspace_id,
+            NotificationKind::TaskActivity,
+            text,
+        )
+        .await;
+        let settings = NotificationSetting::default_for(&person.id);
+        let delivery = Delivery {
+            person,
+            settings: &settings,
+            kind: NotificationKind::TaskActivity,
+            workspace_id: Some(workspace_id),
+            subject: &text.subject,
+            body: &text.body,
+        };
+        self.deliver(delivery, channels).await?;
+        Ok(true)
+    }
+
+    /// Hand the delivery to each channel's notifier. Fails only when none got it
+    /// through.
+    async fn deliver(
+        &self,
+        delivery: Delivery<'_>,
+        channels: Vec<NotificationChannel>,
+    ) -> Result<()> {
+        let mut sent = Vec::new();
+        let mut error = None;
+        for channel in channels {
+            let result = match self.notifiers.get(&channel) {
+                Some(notifier) => notifier.send(delivery).await,
+                None => Err(anyhow::anyhow!("No notifier for the {:?} channel", channel)),
+            };
+            match result {
+                Ok(()) => sent.push(channel),
+                Err(e) => {
+                    warn!(
+                        "[Notify] Failed to send {:?} notification to {} via {:?}: {}",
+                        delivery.kind, delivery.person.email, channel, e
+                    );
+                    error.get_or_insert(e);
+                }
+            }
+        }
+
+        if sent.is_empty() {
+            return Err(error.unwrap_or_else(|| anyhow::anyhow!("No channel to notify on")));
+        }
+        info!(
+            "[Notify] Sent {:?} notification to {} via {:?}",
+            delivery.kind, delivery.person.email, sent
+        );
+        Ok(())
+    }
+
+    /// The workspace's route for the kind, else the person's own choice
+    async fn channels(
+        &self,
+        settings: &NotificationSetting,
+        kind: NotificationKind,
+        workspace_id: Option<&str>,
+    ) -> Result<Vec<NotificationChannel>> {
+        let mut channels = vec![settings.channel];
+        if let Some(workspace_id) = workspace_id {
+            let route = WorkspaceSettingsRepo::new(self.db.clone())
+                .get_notification_routes(workspace_id)
+                .await?
+                .into_iter()
+                .find(|route| route.kind == kind);
+            if let Some(route) = route {
+                channels = route.channels;
+            }
+        }
+
+        let mut expanded = Vec::with_capacity(channels.len());
+        for channel in channels {
+            let parts = match channel {
+                NotificationChannel::Both => {
+                    vec![NotificationChannel::SlackDm, NotificationChannel::Email]
+                }
+                channel => vec![channel],
+            };
+            for channel in parts {
+                if !expanded.contains(&channel) {
+                    expanded.push(channel);
+                }
+            }
+        }
+        Ok(expanded)
+    }
+
+    /// Let the assignee know someone else gave them a task. Failures are only logged.
+    pub async fn notify_assignment(
+        &self,
+        workspace_id: &str,
+        assignee: &Person,
+        assigner: Option<&Person>,
+        content: String,
+    ) {
+        let Some(assigner) = assigner.filter(|a| a.id != assignee.id) else {
+            return;
+        };
+
+        let locale = i18n::person_locale(&self.db, &assignee.id, Some(workspace_id)).await;
+        let text = NotificationText {
+            subject: i18n::text(
+                locale,
+                "notifications.assignment",
+                &[("name", &assigner.name)],
+            ),
+            body: content.clone(),
+            variables: vec![
+                ("assignee", assignee.name.clone()),
+                ("assigner", assigner.name.clone()),
+                ("message", content),
+            ],
+        };
+        if let Err(e) = self
+            .notify_text(
+                assignee,
+                NotificationKind::Assignment,
+                Some(workspace_id),
+                text,
+            )
+            .await
+        {
+            warn!(
+                "[Notify] Failed to notify {} about a new task: {}",
+                assignee.email, e
+            );
+        }
+    }
+
+    /// DM a message with interactive blocks, whatever channel the person chose for
+    /// notifications; `text` is the fallback shown in notifications
+    pub async fn send_blocks(
+        &self,
+        person: &Person,
+        workspace_id: Option<&str>,
+        text: &str,
+        blocks: &Value,
+    ) -> Result<()> {
+        self.slack
+            .send_dm(person, workspace_id, text, Some(blocks))
+            .await
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::{
+    models::{
+        availability::Model as Availability, board_view::Model as BoardView,
+        change::Model as Change, message::Model as Message,
+        notification_setting::Model as NotificationSettings, person::Model as Person,
+        reminder::Model as Reminder, standup_entry::Model as StandupEntry, task::Model as Task,
+        workspace_link::Model as WorkspaceLink, workspace_settings::MessageRedaction,
+    },
+    repos::{
+        audit_logs::AuditLogsRepo,
+        availability::AvailabilityRepo,
+        board_views::BoardViewsRepo,
+        calendar_feeds::CalendarFeedsRepo,
+        changes::ChangesRepo,
+        hook_subscriptions::HookSubscriptionsRepo,
+        invitations::InvitationsRepo,
+        messages::{MessageEncryption, MessagesRepo},
+        notification_settings::NotificationSettingsRepo,
+        persons::PersonsRepo,
+        reminders::RemindersRepo,
+        sessions::SessionsRepo,
+        slack_status_settings::SlackStatusSettingsRepo,
+        standup_entries::StandupEntriesRepo,
+        tasks::TasksRepo,
+        workspace_admins::WorkspaceAdminsRepo,
+        workspace_links::WorkspaceLinksRepo,
+        workspace_settings::WorkspaceSettingsRepo,
+    },
+    utils::redaction::redact,
+};
+
+/// Message text left behind when its author erases their account
+pub const ERASED_CONTENT: &str = "[removed at the author's request]";
+
+/// Everything stored about a person
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DataExport {
+    pub exported_at: String,
+    pub person: Person,
+    pub workspace_links: Vec<WorkspaceLink>,
+    /// Tasks assigned to or by the person
+    pub tasks: Vec<Task>,
+    /// Messages the person wrote
+    pub messages: Vec<Message>,
+    /// Status changes of `tasks`
+    pub changes: Vec<Change>,
+    pub notification_settings: NotificationSettings,
+    pub board_views: Vec<BoardView>,
+    /// Reminders that are not done
+    pub reminders: Vec<Reminder>,
+    /// Out-of-office periods
+    pub availability: Vec<Availability>,
+    /// Answers to standups
+    pub standup_entries: Vec<StandupEntry>,
+}
+
+/// What erasing a person removed or anonymized
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ErasureReport {
+    pub messages_anonymized: u64,
+    pub links_removed: u64,
+    pub settings_removed: u64,
+    pub invitations_removed: u64,
+    pub admin_removed: bool,
+}
+
+pub async fn export_person_data(
+    db: &DatabaseConnection,
+    encryption: &MessageEncryption,
+    person: Person,
+) -> Result<DataExport> {
+    let tasks_repo = TasksRepo::new(db.clone());
+
+    // Tasks the person initiated and tasks they own, each once
+    let mut tasks: HashMap<String, Task> = HashMap::new();
+    for task in tasks_repo
+        .get_tasks_by_person_id(person.id.clone())
+        .await?
+        .into_iter()
+        .chain(tasks_repo.get_initiated_by(person.id.clone()).await?)
+    {
+        tasks.insert(task.id.clone(), task);
+    }
+    let mut tasks: Vec<Task> = tasks.into_values().collect();
+    tasks.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    let task_ids = tasks.iter().map(|t| t.id.clone()).collect();
+    let changes = ChangesRepo::new(db.clone()).get_for_tasks(task_ids).await?;
+    let messages = MessagesRepo::new(db.clone(), encryption.clone())
+        .get_all_by_person(person.id.clone())
+        .await?;
+    let workspace_links = WorkspaceLinksRepo::new(db.clone())
+        .get_by_person(person.id.clone())
+        .await?;
+    let notification_settings = NotificationSettingsRepo::new(db.clone())
+        .get_or_default(&person.id)
+        .await?;
+    let board_views = BoardViewsRepo::new(db.clone())
+        .get_by_person(&person.id)
+        .await?;
+    let reminders = RemindersRepo::new(db.clone())
+        .get_open_by_person(&person.id)
+        .await?;
+    let availability = AvailabilityRepo::new(db.clone())
+        .get_all_by_person(&person.id)
+        .await?;
+    let standup_entries = StandupEntriesRepo::new(db.clone())
+        .get_by_person(&person.id)
+        .await?;
+
+    // Messages are redacted as their task's workspace asks today; those without a
+    // task get the strictest mode of the person's workspaces
+    let settings_repo = WorkspaceSettingsRepo::new(db.clone());
+    let mut redactions: HashMap<String, MessageRedaction> = HashMap::new();
+    for workspace_id in workspace_links
+        .iter()
+        .map(|l| &l.workspace_id)
+        .chain(tasks.iter().filter_map(|t| t.workspace_id.as_ref()))
+    {
+        if !redactions.contains_key(workspace_id) {
+            let redaction = settings_repo.get_message_redaction(workspace_id).await?;
+            redactions.insert(workspace_id.clone(), redaction);
+        }
+    }
+    let strictest = redactions.values().copied().max().unwrap_or_default();
+    let message_workspaces: HashMap<&str, &str> = tasks
+        .iter()
+        .filter_map(|t| Some((t.message_id.as_str(), t.workspace_id.as_deref()?)))
+        .collect();
+    let messages = messages
+        .into_iter()
+        .map(|mut message| {
+            let redaction = message_workspaces
+                .get(message.id.as_str())
+                .and_then(|workspace_id| redactions.get(*workspace_id))
+                .copied()
+                .unwrap_or(strictest);
+            message.content = redact(&message.content, redaction);
+            message
+        })
+        .collect();
+
+    Ok(DataExport {
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        person,
+        workspace_links,
+        tasks,
+        messages,
+        changes,
+        notification_settings,
+        board_views,
+        reminders,
+        availability,
+        standup_entries,
+    })
+}
+
+/// Erase a person at their request: their messages are anonymized, their links,
+/// settings, invitations, sessions, calendar feed, REST hooks, board views,
+/// reminders, out-of-office periods, standup answers, Slack status tokens and admin
+/// grant are deleted, and the person row keeps only its id so tasks other people created
+/// with them stay consistent.
+pub async fn erase_person(
+    db: &DatabaseConnection,
+    encryption: &MessageEncryption,
+    person: Person,
+) -> Result<ErasureReport> {
+    let person_id = person.id.clone();
+    let email = person.email.clone();
+
+    let mut report = ErasureReport {
+        messages_anonymized: MessagesRepo::new(db.clone(), encryption.clone())
+            .anonymize_by_person(&person_id, ERASED_CONTENT)
+            .await?,
+        links_removed: WorkspaceLinksRepo::new(db.clone())
+            .delete_by_person(&person_id)
+            .await?,
+        settings_removed: NotificationSettingsRepo::new(db.clone())
+            .delete_by_person(&person_id)
+            .await?,
+        invitations_removed: InvitationsRepo::new(db.clone())
+            .delete_by_email(&email)
+            .await?,
+        admin_removed: false,
+    };
+
+    SessionsRepo::new(db.clone())
+        .delete_by_person(&person_id)
+        .await?;
+    CalendarFeedsRepo::new(db.clone())
+        .delete_by_person(&person_id)
+        .await?;
+    HookSubscriptionsRepo::new(db.clone())
+        .delete_by_person(&person_id)
+        .await?;
+    BoardViewsRepo::new(db.clone())
+        .delete_by_person(&person_id)
+        .await?;
+    RemindersRepo::new(db.clone())
+        .delete_by_person(&person_id)
+        .await?;
+    AvailabilityRepo::new(db.clone())
+        .delete_by_person(&person_id)
+        .await?;
+    SlackStatusSettingsRepo::new(db.clone())
+        .delete_by_person(&person_id)
+        .await?;
+    StandupEntriesRepo::new(db.clone())
+        .delete_by_person(&person_id)
+        .await?;
+
+    let admins_repo = WorkspaceAdminsRepo::new(db.clone());
+    if admins_repo.get_by_email(&email).await.is_ok() {
+        admins_repo.delete_admin(&email).await?;
+        report.admin_removed = true;
+    }
+
+    // Last, as the email is what the session token resolves to
+    PersonsRepo::new(db.clone()).anonymize(person).await?;
+
+    AuditLogsRepo::new(db.clone())
+        .record(
+            Some(&person_id),
+            "person.erased",
+            Some(&person_id),
+            serde_json::to_string(&report).ok(),
+        )
+        .await?;
+    info!("Erased person {}", person_id);
+
+    Ok(report)
+}
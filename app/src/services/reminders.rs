@@ -0,0 +1,206 @@
+//! Reminders people set on tasks with a delay such as `2d` or `next monday`, from
+//! the API or the "Remind me" message action. The scheduler DMs them once due, with
+//! buttons to mark them done or snooze them again.
+
+use anyhow::Result;
+use chrono::{NaiveDateTime, Utc};
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use crate::{
+    core::state::AppState,
+    i18n::{self, Locale},
+    models::{
+        person::Model as Person,
+        reminder::Model as Reminder,
+        task::{Model as Task, TaskStatus},
+    },
+    repos::{
+        messages::MessagesRepo, persons::PersonsRepo, reminders::RemindersRepo, tasks::TasksRepo,
+    },
+    services::digest::preview,
+    utils::time::parse_delay,
+};
+
+/// `callback_id` of the message action and of the modal it opens
+pub const MESSAGE_ACTION_CALLBACK_ID: &str = "remind_me";
+pub const DONE_ACTION_ID: &str = "reminder_done";
+pub const SNOOZE_ACTION_ID: &str = "reminder_snooze";
+
+/// Choices of the snooze menu in reminder DMs, as catalog key of the label and delay
+const SNOOZE_OPTIONS: [(&str, &str); 5] = [
+    ("reminders.snooze_1h", "1h"),
+    ("reminders.snooze_4h", "4h"),
+    ("reminders.snooze_tomorrow", "tomorrow"),
+    ("reminders.snooze_2d", "2d"),
+    ("reminders.snooze_next_monday", "next monday"),
+];
+
+/// When a reminder set by `person` with `delay` is due, in UTC. Day-based delays
+/// follow the person's timezone.
+pub fn remind_at(person: &Person, delay: &str) -> Option<NaiveDateTime> {
+    parse_delay(delay, Utc::now().with_timezone(&person.tz())).map(|at| at.naive_utc())
+}
+
+/// Slack's date formatting, shown in the reader's timezone
+pub fn slack_date(at: NaiveDateTime) -> String {
+    format!(
+        "<!date^{}^{{date_short_pretty}} at {{time}}|{} UTC>",
+        at.and_utc().timestamp(),
+        at.format("%Y-%m-%d %H:%M")
+    )
+}
+
+/// Modal of the message action, asking when to remind. `private_metadata` carries
+/// the task and the channel to confirm in.
+pub fn reminder_modal(locale: Locale, task_id: &str, channel: &str) -> Value {
+    let text = |key: &str| i18n::text(locale, key, &[]);
+    json!({
+        "type": "modal",
+        "callback_id": MESSAGE_ACTION_CALLBACK_ID,
+        "private_metadata": json!({ "task_id": task_id, "channel": channel }).to_string(),
+        "title": { "type": "plain_text", "text": text("reminders.modal_title") },
+        "submit": { "type": "plain_text", "text": text("reminders.modal_title") },
+        "close": { "type": "plain_text", "text": text("reminders.cancel") },
+        "blocks": [{
+            "type": "input",
+            "block_id": "delay",
+            "label": { "type": "plain_text", "text": text("reminders.when") },
+            "hint": { "type": "plain_text", "text": text("reminders.hint") },
+            "element": {
+                "type": "plain_text_input",
+                "action_id": "delay",
+                "initial_value": "tomorrow",
+            },
+        }],
+    })
+}
+
+/// Task id, channel and delay of a submitted `reminder_modal`
+pub fn submitted_reminder(view: &Value) -> Option<(String, String, String)> {
+    let metadata: Value = serde_json::from_str(view["private_metadata"].as_str()?).ok()?;
+    let delay = view["state"]["values"]["delay"]["delay"]["value"].as_str()?;
+    Some((
+        metadata["task_id"].as_str()?.to_string(),
+        metadata["channel"].as_str()?.to_string(),
+        delay.to_string(),
+    ))
+}
+
+/// Reminder DM with a Done button and a snooze menu. Snooze options carry the
+/// reminder id and the delay as `id|delay`.
+fn reminder_blocks(locale: Locale, reminder: &Reminder, text: &str) -> Value {
+    let label = |key: &str| i18n::text(locale, key, &[]);
+    let options: Vec<Value> = SNOOZE_OPTIONS
+        .iter()
+        .map(|(key, delay)| {
+            json!({
+                "text": { "type": "plain_text", "text": label(key) },
+                "value": format!("{}|{}", reminder.id, delay),
+            })
+        })
+        .collect();
+    json!([
+        { "type": "section", "text": { "type": "mrkdwn", "text": text } },
+        {
+            "type": "actions",
+            "elements": [
+                {
+                    "type": "button",
+                    "action_id": DONE_ACTION_ID,
+                    "text": { "type": "plain_text", "text": label("reminders.done") },
+                    "style": "primary",
+                    "value": reminder.id,
+                },
+                {
+                    "type": "static_select",
+                    "action_id": SNOOZE_ACTION_ID,
+                    "placeholder": {
+                        "type": "plain_text",
+                        "text": label("reminders.snooze_again"),
+                    },
+                    "options": options,
+                },
+            ],
+        },
+    ])
+}
+
+/// Blocks that replace a reminder DM once it was handled, without the buttons
+pub fn handled_blocks(text: &str) -> Value {
+    json!([{ "type": "section", "text": { "type": "mrkdwn", "text": text } }])
+}
+
+/// DM every due reminder to its person. Reminders of completed tasks are marked
+/// done without a DM. Returns how many were sent.
+pub async fn send_due_reminders(state: &AppState) -> Result<usize> {
+    let reminders_repo = RemindersRepo::new(state.database.clone());
+
+    let mut sent = 0;
+    for reminder in reminders_repo.get_due(Utc::now().naive_utc()).await? {
+        let task = TasksRepo::new(state.database.clone())
+            .get(reminder.task_id.clone())
+            .await?;
+        if task.status == TaskStatus::Completed {
+            reminders_repo.mark_done(reminder).await?;
+            continue;
+        }
+
+        let person = PersonsRepo::new(state.database.clone())
+            .get_by_id(reminder.person_id.clone())
+            .await?;
+        match send_reminder(state, &reminder, &task, &person).await {
+            Ok(()) => {
+                reminders_repo.mark_sent(reminder).await?;
+                sent += 1;
+            }
+            // Left unsent, so the next run tries again
+            Err(e) => warn!(
+                "[Reminders] Failed to send reminder {} to {}: {}",
+                reminder.id, person.email, e
+            ),
+        }
+    }
+
+    Ok(sent)
+}
+
+async fn send_reminder(
+    state: &AppState,
+    reminder: &Reminder,
+    task: &Task,
+    person: &Person,
+) -> Result<()> {
+    let message = MessagesRepo::new(state.database.clone(), state.message_encryption.clone())
+        .get_by_id(task.message_id.clone())
+        .await?;
+    let locale =
+        i18n::person_locale(&state.database, &person.id, task.workspace_id.as_deref()).await;
+    let mut text = i18n::text(
+        locale,
+        "reminders.reminder",
+        &[("task", &preview(&message.content))],
+    );
+    if message.is_from_slack() {
+        text.push_str(&format!(
+            "\n<{}|{}>",
+            message.slack_link(),
+            i18n::text(locale, "reminders.open_in_slack", &[])
+        ));
+    }
+
+    state
+        .notifier
+        .send_blocks(
+            person,
+            task.workspace_id.as_deref(),
+            &text,
+            &reminder_blocks(locale, reminder, &text),
+        )
+        .await?;
+    info!(
+        "[Reminders] Sent reminder {} for task {} to {}",
+        reminder.id, task.id, person.email
+    );
+    Ok(())
+}
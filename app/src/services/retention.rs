@@ -0,0 +1,269 @@
+use anyhow::Result;
+use chrono::{Duration, NaiveDateTime};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait,
+    PaginatorTrait, QueryFilter, QuerySelect, QueryTrait, Select,
+};
+use serde::Serialize;
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::{
+    core::state::AppState,
+    models::{
+        bot_incident::{self, Entity as BotIncidentEntity},
+        message::{self, Entity as MessageEntity},
+        message_archive::{self, Entity as MessageArchiveEntity},
+        retention_policy::{Model as RetentionPolicy, RetentionMode},
+        task::{self, Entity as TaskEntity, TaskStatus},
+    },
+    repos::{
+        bot_events::BotEventsRepo, idempotency_keys::IdempotencyKeysRepo,
+        retention_policies::RetentionPoliciesRepo, sessions::SessionsRepo,
+        task_tombstones::TaskTombstonesRepo, workspaces::WorkspacesRepo,
+    },
+};
+
+/// Message text left behind by `RetentionMode::Anonymize`
+pub const ANONYMIZED_CONTENT: &str = "[removed by retention policy]";
+
+/// Rows a retention policy removes or anonymizes. A message can be counted under
+/// both `completed_tasks` and `messages` when both limits apply to it.
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct RetentionReport {
+    pub completed_tasks: u64,
+    pub messages: u64,
+    pub incidents: u64,
+}
+
+impl RetentionReport {
+    pub fn total(&self) -> u64 {
+        self.completed_tasks + self.messages + self.incidents
+    }
+}
+
+/// Apply a workspace's policy as of `now`. With `dry_run`, only count what would go.
+///
+/// Messages have no timestamp of their own, so a message is as old as its task.
+pub async fn apply_policy(
+    db: &DatabaseConnection,
+    policy: &RetentionPolicy,
+    now: NaiveDateTime,
+    dry_run: bool,
+) -> Result<RetentionReport, DbErr> {
+    let cutoff = |days: i32| now - Duration::days(days.into());
+    let workspace_tasks =
+        || TaskEntity::find().filter(task::Column::WorkspaceId.eq(&policy.workspace_id));
+    let mut report = RetentionReport::default();
+
+    if let Some(days) = policy.completed_task_days {
+        let tasks = workspace_tasks()
+            .filter(task::Column::Status.eq(TaskStatus::Completed))
+            .filter(task::Column::CreatedAt.lt(cutoff(days)));
+        report.completed_tasks = purge_task_messages(db, tasks, policy.mode, dry_run).await?;
+    }
+
+    if let Some(days) = policy.message_days {
+        let tasks = workspace_tasks().filter(task::Column::CreatedAt.lt(cutoff(days)));
+        report.messages = purge_task_messages(db, tasks, policy.mode, dry_run).await?;
+    }
+
+    // Incidents hold no personal data, so they are always deleted
+    if let Some(days) = policy.incident_days {
+        let condition = Condition::all()
+            .add(bot_incident::Column::WorkspaceId.eq(&policy.workspace_id))
+            .add(bot_incident::Column::CreatedAt.lt(cutoff(days)));
+        report.incidents = if dry_run {
+            BotIncidentEntity::find()
+                .filter(condition)
+                .count(db)
+                .await?
+        } else {
+            BotIncidentEntity::delete_many()
+                .filter(condition)
+                .exec(db)
+                .await?
+                .rows_affected
+        };
+    }
+
+    Ok(report)
+}
+
+/// Delete or anonymize the messages of the given tasks, and those of duplicates
+/// merged into them, archived ones included. Deleting a message cascades to its
+/// tasks and their changes, so the tasks are buried first.
+async fn purge_task_messages(
+    db: &DatabaseConnection,
+    tasks: Select<TaskEntity>,
+    mode: RetentionMode,
+    dry_run: bool,
+) -> Result<u64, DbErr> {
+    let task_ids = tasks
+        .clone()
+        .select_only()
+        .column(task::Column::Id)
+        .into_query();
+    let message_ids = tasks
+        .select_only()
+        .column(task::Column::MessageId)
+        .into_query();
+    let mut condition = Condition::all().add(
+        Condition::any()
+            .add(message::Column::Id.in_subquery(message_ids.clone()))
+            .add(message::Column::MergedIntoTaskId.in_subquery(task_ids.clone())),
+    );
+    let mut archived =
+        Condition::all().add(message_archive::Column::MergedIntoTaskId.in_subquery(task_ids));
+    if mode == RetentionMode::Anonymize {
+        condition = condition.add(message::Column::Content.ne(ANONYMIZED_CONTENT));
+        archived = archived.add(message_archive::Column::Content.ne(ANONYMIZED_CONTENT));
+    }
+
+    if dry_run {
+        let messages = MessageEntity::find().filter(condition).count(db).await?;
+        let archived = MessageArchiveEntity::find()
+            .filter(archived)
+            .count(db)
+            .await?;
+        return Ok(messages + archived);
+    }
+
+    let rows_affected = match mode {
+        RetentionMode::Delete => {
+            let doomed = TaskEntity::find()
+                .filter(task::Column::MessageId.in_subquery(message_ids))
+                .all(db)
+                .await?;
+            TaskTombstonesRepo::new(db.clone()).bury(&doomed).await?;
+            // Archived duplicates first, they are found through the tasks
+            let archived = MessageArchiveEntity::delete_many()
+                .filter(archived)
+                .exec(db)
+                .await?
+                .rows_affected;
+            let messages = MessageEntity::delete_many()
+                .filter(condition)
+                .exec(db)
+                .await?
+                .rows_affected;
+            archived + messages
+        }
+        RetentionMode::Anonymize => {
+            let archived = MessageArchiveEntity::update_many()
+                .col_expr(
+                    message_archive::Column::Content,
+                    Expr::value(ANONYMIZED_CONTENT),
+                )
+                .col_expr(
+                    message_archive::Column::ContentEncrypted,
+                    Expr::value(false),
+                )
+                .filter(archived)
+                .exec(db)
+                .await?
+                .rows_affected;
+            let messages = MessageEntity::update_many()
+                .col_expr(message::Column::Content, Expr::value(ANONYMIZED_CONTENT))
+                .col_expr(message::Column::ContentEncrypted, Expr::value(false))
+                .filter(condition)
+                .exec(db)
+                .await?
+                .rows_affected;
+            archived + messages
+        }
+    };
+
+    Ok(rows_affected)
+}
+
+/// Apply every saved retention policy. Returns how many rows were removed or anonymized.
+pub async fn purge_expired_data(state: &AppState) -> Result<usize> {
+    let policies = RetentionPoliciesRepo::new(state.database.clone())
+        .get_all()
+        .await?;
+    let now = chrono::Utc::now().naive_utc();
+
+    let mut purged = 0;
+    for policy in policies {
+        match apply_policy(&state.database, &policy, now, false).await {
+            Ok(report) if report.total() > 0 => {
+                info!(
+                    "[Retention] Workspace {}: {} completed task(s), {} message(s), {} incident(s) purged",
+                    policy.workspace_id, report.completed_tasks, report.messages, report.incidents
+                );
+                purged += report.total() as usize;
+            }
+            Ok(_) => {}
+            Err(e) => error!(
+                "[Retention] Failed to apply policy of workspace {}: {}",
+                policy.workspace_id, e
+            ),
+        }
+    }
+
+    match purge_bot_events(state, now).await {
+        Ok(0) => {}
+        Ok(events) => {
+            info!("[Retention] {} bot event(s) purged", events);
+            purged += events as usize;
+        }
+        Err(e) => error!("[Retention] Failed to purge bot events: {}", e),
+    }
+
+    match IdempotencyKeysRepo::new(state.database.clone())
+        .delete_before(now - Duration::hours(state.config.idempotency_key_ttl_hours))
+        .await
+    {
+        Ok(0) => {}
+        Ok(keys) => {
+            info!("[Retention] {} idempotency key(s) purged", keys);
+            purged += keys as usize;
+        }
+        Err(e) => error!("[Retention] Failed to purge idempotency keys: {}", e),
+    }
+
+    match TaskTombstonesRepo::new(state.database.clone())
+        .delete_before(now - Duration::days(state.config.task_tombstone_retention_days))
+        .await
+    {
+        Ok(0) => {}
+        Ok(tombstones) => {
+            info!("[Retention] {} task tombstone(s) purged", tombstones);
+            purged += tombstones as usize;
+        }
+        Err(e) => error!("[Retention] Failed to purge task tombstones: {}", e),
+    }
+
+    match SessionsRepo::new(state.database.clone())
+        .delete_expired(now)
+        .await
+    {
+        Ok(0) => {}
+        Ok(sessions) => {
+            info!("[Retention] {} expired session(s) purged", sessions);
+            purged += sessions as usize;
+        }
+        Err(e) => error!("[Retention] Failed to purge expired sessions: {}", e),
+    }
+
+    Ok(purged)
+}
+
+/// Keep the bot event log within `bot_event_retention_days` and `bot_event_limit`,
+/// whatever the workspaces' policies say
+async fn purge_bot_events(state: &AppState, now: NaiveDateTime) -> Result<u64, DbErr> {
+    let repo = BotEventsRepo::new(state.database.clone());
+    let mut purged = repo
+        .delete_before(now - Duration::days(state.config.bot_event_retention_days))
+        .await?;
+    for workspace in WorkspacesRepo::new(state.database.clone())
+        .get_all()
+        .await?
+    {
+        purged += repo
+            .trim(&workspace.id, state.config.bot_event_limit)
+            .await?;
+    }
+    Ok(purged)
+}
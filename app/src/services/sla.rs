@@ -0,0 +1,229 @@
+//! SLA policies: the longest tasks may stay in a status, counted in the workspace's
+//! business hours. The scheduler checks open tasks against them from when each
+//! task entered its status, records each breach once and alerts the assignee.
+//! Boards flag tasks whose current stay has a breach.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::{DatabaseConnection, DbErr};
+use tracing::{info, warn};
+
+use crate::{
+    core::state::AppState,
+    i18n,
+    models::{
+        notification_setting::NotificationKind, sla_breach::Model as SlaBreach,
+        sla_policy::Model as SlaPolicy, task::Model as Task,
+    },
+    repos::{
+        messages::MessagesRepo, persons::PersonsRepo, sla_breaches::SlaBreachesRepo,
+        sla_policies::SlaPoliciesRepo, tasks::TasksRepo,
+    },
+    services::{business_hours, notification_templates::NotificationText, task_service},
+};
+
+/// Breaches of a workspace's tasks, by task
+#[derive(Debug, Default)]
+pub struct Breaches(HashMap<String, Vec<SlaBreach>>);
+
+impl Breaches {
+    /// Whether the task's stay in its current status, entered at `status_since`,
+    /// breached a policy
+    pub fn is_breached(&self, task: &Task, status_since: Option<NaiveDateTime>) -> bool {
+        self.0.get(&task.id).is_some_and(|breaches| {
+            breaches
+                .iter()
+                .any(|b| b.status == task.status && Some(b.entered_at) == status_since)
+        })
+    }
+}
+
+/// Breaches recorded for the workspace's policies
+pub async fn workspace_breaches(
+    db: &DatabaseConnection,
+    workspace_id: &str,
+) -> Result<Breaches, DbErr> {
+    let policy_ids = SlaPoliciesRepo::new(db.clone())
+        .get_by_workspace(workspace_id)
+        .await?
+        .into_iter()
+        .map(|policy| policy.id)
+        .collect();
+    let mut breaches: HashMap<String, Vec<SlaBreach>> = HashMap::new();
+    for breach in SlaBreachesRepo::new(db.clone())
+        .get_for_policies(policy_ids)
+        .await?
+    {
+        breaches
+            .entry(breach.task_id.clone())
+            .or_default()
+            .push(breach);
+    }
+    Ok(Breaches(breaches))
+}
+
+/// Check the open tasks of every workspace with SLA policies. Returns how many new
+/// breaches were found.
+pub async fn check_breaches(state: &AppState) -> Result<usize> {
+    let mut policies: HashMap<String, Vec<SlaPolicy>> = HashMap::new();
+    for policy in SlaPoliciesRepo::new(state.database.clone())
+        .get_all()
+        .await?
+    {
+        policies
+            .entry(policy.workspace_id.clone())
+            .or_default()
+            .push(policy);
+    }
+
+    let mut breached = 0;
+    for (workspace_id, policies) in policies {
+        match check_workspace(state, &workspace_id, &policies).await {
+            Ok(count) => breached += count,
+            Err(e) => warn!(
+                "[SLA] Failed to check the tasks of workspace {}: {}",
+                workspace_id, e
+            ),
+        }
+    }
+    if breached > 0 {
+        // Breaches flag tasks on boards without a task event
+        state.board_events.touch();
+    }
+    Ok(breached)
+}
+
+async fn check_workspace(
+    state: &AppState,
+    workspace_id: &str,
+    policies: &[SlaPolicy],
+) -> Result<usize> {
+    let db = &state.database;
+    let tasks: Vec<Task> = TasksRepo::new(db.clone())
+        .get_by_workspace(workspace_id.to_string())
+        .await?
+        .into_iter()
+        .filter(|task| policies.iter().any(|policy| policy.status == task.status))
+        .collect();
+    if tasks.is_empty() {
+        return Ok(0);
+    }
+
+    let breaches_repo = SlaBreachesRepo::new(db.clone());
+    let recorded: HashSet<(String, String, NaiveDateTime)> = breaches_repo
+        .get_for_policies(policies.iter().map(|policy| policy.id.clone()).collect())
+        .await?
+        .into_iter()
+        .map(|breach| (breach.policy_id, breach.task_id, breach.entered_at))
+        .collect();
+    let calendar = business_hours::workspace_calendar(db, workspace_id).await;
+    let now = Utc::now();
+
+    let mut breached = 0;
+    // Chunked to stay under the bind parameter limits of the databases
+    for chunk in tasks.chunks(1000) {
+        let since = task_service::status_since(db, chunk).await?;
+        for task in chunk {
+            // Tasks that last moved before changes were timed can't be measured
+            let Some(entered_at) = since.get(&task.id).copied().flatten() else {
+                continue;
+            };
+            for policy in policies
+                .iter()
+                .filter(|policy| policy.status == task.status)
+            {
+                let key = (policy.id.clone(), task.id.clone(), entered_at);
+                if recorded.contains(&key) {
+                    continue;
+                }
+                let deadline = business_hours::deadline(
+                    calendar.as_ref(),
+                    entered_at.and_utc(),
+                    Duration::hours(policy.max_hours.into()),
+                );
+                if deadline.is_none_or(|deadline| now < deadline) {
+                    continue;
+                }
+
+                breaches_repo.create(policy, task, entered_at).await?;
+                breached += 1;
+                info!(
+                    "[SLA] Task {} breached policy {} of workspace {}",
+                    task.id, policy.name, workspace_id
+                );
+                alert(state, workspace_id, policy, task).await;
+            }
+        }
+    }
+    Ok(breached)
+}
+
+/// Tell the assignee their task breached the policy
+async fn alert(state: &AppState, workspace_id: &str, policy: &SlaPolicy, task: &Task) {
+    let db = &state.database;
+    let assignee = match PersonsRepo::new(db.clone())
+        .get_by_id(task.assigned_to.clone())
+        .await
+    {
+        Ok(assignee) => assignee,
+        Err(e) => {
+            warn!("[SLA] Failed to load assignee {}: {}", task.assigned_to, e);
+            return;
+        }
+    };
+    let link = match MessagesRepo::new(db.clone(), state.message_encryption.clone())
+        .get_by_id(task.message_id.clone())
+        .await
+    {
+        Ok(message) if message.is_from_slack() => message.slack_link(),
+        _ => String::new(),
+    };
+
+    let locale = i18n::person_locale(db, &assignee.id, Some(workspace_id)).await;
+    let name = task
+        .key
+        .clone()
+        .unwrap_or_else(|| i18n::text(locale, "notifications.a_task", &[]));
+    let status = i18n::status(locale, &policy.status);
+    let hours = policy.max_hours.to_string();
+    let mut body = i18n::text(
+        locale,
+        "sla.body",
+        &[
+            ("task", &name),
+            ("status", &status),
+            ("hours", &hours),
+            ("policy", &policy.name),
+        ],
+    );
+    if !link.is_empty() {
+        body.push_str(&format!("\n{}", link));
+    }
+
+    let text = NotificationText {
+        subject: i18n::text(locale, "sla.subject", &[("task", &name)]),
+        body,
+        variables: vec![
+            ("task", name),
+            ("assignee", assignee.name.clone()),
+            ("policy", policy.name.clone()),
+            ("status", status),
+            ("hours", hours),
+            ("link", link),
+        ],
+    };
+    if let Err(e) = state
+        .notifier
+        .notify_text(
+            &assignee,
+            NotificationKind::SlaBreach,
+            Some(workspace_id),
+            text,
+        )
+        .await
+    {
+        warn!("[SLA] Failed to alert {}: {}", assignee.email, e);
+    }
+}
@@ -1,19 +1,267 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use crate::models::task::TaskStatus;
+use sea_orm::{DatabaseConnection, DbErr};
 
-pub fn eval_status_from_reactions(statuses: HashSet<TaskStatus>) -> TaskStatus {
-    if statuses.contains(&TaskStatus::Completed) {
-        return TaskStatus::Completed;
+use crate::{
+    models::{
+        person::Model as Person,
+        task::TaskStatus,
+        workspace_settings::{ReactionPolicy, StatusResolution},
+    },
+    repos::{workspace_admins::WorkspaceAdminsRepo, workspace_links::WorkspaceLinksRepo},
+};
+
+/// Which status wins when a message has reactions for several, strongest first.
+/// A task marked done stays done even if someone also reacted with `eyes`. The
+/// other strategies fall back to it to break ties.
+pub const STATUS_PRECEDENCE: [TaskStatus; 3] = [
+    TaskStatus::Completed,
+    TaskStatus::Blocked,
+    TaskStatus::InProgress,
+];
+
+/// A reaction on a message that maps to a status, with the people who added it
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusReaction {
+    pub status: TaskStatus,
+    pub users: Vec<String>,
+}
+
+/// Slack members whose reactions count toward a task's status
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reactors {
+    Anyone,
+    Only(HashSet<String>),
+}
+
+impl Reactors {
+    pub fn counts(&self, slack_id: &str) -> bool {
+        match self {
+            Reactors::Anyone => true,
+            Reactors::Only(members) => members.contains(slack_id),
+        }
     }
+}
+
+/// Whose reactions count under the workspace's policy. `assignee` is the person
+/// the task is, or would be, assigned to.
+pub async fn counted_reactors(
+    db: &DatabaseConnection,
+    admin_email: &str,
+    workspace_id: &str,
+    policy: ReactionPolicy,
+    assignee: &Person,
+) -> Result<Reactors, DbErr> {
+    let members = match policy {
+        ReactionPolicy::Anyone => return Ok(Reactors::Anyone),
+        ReactionPolicy::Assignee => {
+            let assignee = HashSet::from([assignee.external_id.clone()]);
+            return Ok(Reactors::Only(assignee));
+        }
+        ReactionPolicy::LinkedMembers | ReactionPolicy::Admins => {
+            WorkspaceLinksRepo::new(db.clone())
+                .get_workspace_users(workspace_id)
+                .await?
+        }
+    };
 
-    if statuses.contains(&TaskStatus::Blocked) {
-        return TaskStatus::Blocked;
+    let mut admins: HashSet<String> = HashSet::from([admin_email.to_string()]);
+    if policy == ReactionPolicy::Admins {
+        admins.extend(
+            WorkspaceAdminsRepo::new(db.clone())
+                .get_active_admins()
+                .await?
+                .into_iter()
+                .map(|admin| admin.email),
+        );
     }
 
-    if statuses.contains(&TaskStatus::InProgress) {
-        return TaskStatus::InProgress;
+    Ok(Reactors::Only(
+        members
+            .into_iter()
+            .filter(|(_, person)| {
+                policy != ReactionPolicy::Admins || admins.contains(&person.email)
+            })
+            .map(|(link, person)| link.slack_member_id.unwrap_or(person.external_id))
+            .collect(),
+    ))
+}
+
+/// Picks a message's status from its reactions, given oldest first
+pub trait StatusResolver {
+    fn resolve(&self, reactions: &[StatusReaction]) -> TaskStatus;
+}
+
+/// `StatusResolution::CompletedWins`
+pub struct PrecedenceResolver;
+
+impl StatusResolver for PrecedenceResolver {
+    fn resolve(&self, reactions: &[StatusReaction]) -> TaskStatus {
+        STATUS_PRECEDENCE
+            .into_iter()
+            .find(|status| reactions.iter().any(|reaction| reaction.status == *status))
+            .unwrap_or(TaskStatus::Blank)
+    }
+}
+
+/// `StatusResolution::LatestReaction`
+pub struct LatestReactionResolver;
+
+impl StatusResolver for LatestReactionResolver {
+    fn resolve(&self, reactions: &[StatusReaction]) -> TaskStatus {
+        reactions
+            .last()
+            .map(|reaction| reaction.status.clone())
+            .unwrap_or(TaskStatus::Blank)
     }
+}
+
+/// `StatusResolution::Majority`. Someone who reacted for several statuses votes
+/// for the latest one; ties go by precedence.
+pub struct MajorityResolver;
+
+impl StatusResolver for MajorityResolver {
+    fn resolve(&self, reactions: &[StatusReaction]) -> TaskStatus {
+        let mut votes: HashMap<&str, &TaskStatus> = HashMap::new();
+        for reaction in reactions {
+            for user in &reaction.users {
+                votes.insert(user, &reaction.status);
+            }
+        }
+        // Reactions fetched without their users have no voters to count
+        if votes.is_empty() {
+            return PrecedenceResolver.resolve(reactions);
+        }
+
+        let tally = |status: &TaskStatus| votes.values().filter(|vote| *vote == status).count();
+        let most = STATUS_PRECEDENCE
+            .iter()
+            .map(tally)
+            .max()
+            .unwrap_or_default();
+        STATUS_PRECEDENCE
+            .into_iter()
+            .find(|status| tally(status) == most)
+            .unwrap_or(TaskStatus::Blank)
+    }
+}
 
-    TaskStatus::Blank
+pub fn resolver(strategy: StatusResolution) -> &'static dyn StatusResolver {
+    match strategy {
+        StatusResolution::CompletedWins => &PrecedenceResolver,
+        StatusResolution::LatestReaction => &LatestReactionResolver,
+        StatusResolution::Majority => &MajorityResolver,
+    }
+}
+
+/// Status of a message under the workspace's strategy, `Blank` without status reactions
+pub fn resolve_status(strategy: StatusResolution, reactions: &[StatusReaction]) -> TaskStatus {
+    resolver(strategy).resolve(reactions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reaction(status: TaskStatus, users: &[&str]) -> StatusReaction {
+        StatusReaction {
+            status,
+            users: users.iter().map(|user| user.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn no_reactions_leave_the_task_blank() {
+        for strategy in [
+            StatusResolution::CompletedWins,
+            StatusResolution::LatestReaction,
+            StatusResolution::Majority,
+        ] {
+            assert_eq!(resolve_status(strategy, &[]), TaskStatus::Blank);
+        }
+    }
+
+    #[test]
+    fn completed_wins_over_later_reactions() {
+        let reactions = [
+            reaction(TaskStatus::Completed, &["U1"]),
+            reaction(TaskStatus::InProgress, &["U2", "U3"]),
+            reaction(TaskStatus::Blocked, &["U4"]),
+        ];
+        assert_eq!(
+            resolve_status(StatusResolution::CompletedWins, &reactions),
+            TaskStatus::Completed
+        );
+    }
+
+    #[test]
+    fn blocked_wins_over_in_progress() {
+        let reactions = [
+            reaction(TaskStatus::Blocked, &["U1"]),
+            reaction(TaskStatus::InProgress, &["U1"]),
+        ];
+        assert_eq!(
+            resolve_status(StatusResolution::CompletedWins, &reactions),
+            TaskStatus::Blocked
+        );
+    }
+
+    #[test]
+    fn latest_reaction_wins() {
+        let reactions = [
+            reaction(TaskStatus::Completed, &["U1"]),
+            reaction(TaskStatus::InProgress, &["U2"]),
+        ];
+        assert_eq!(
+            resolve_status(StatusResolution::LatestReaction, &reactions),
+            TaskStatus::InProgress
+        );
+    }
+
+    #[test]
+    fn majority_counts_each_reactor_once() {
+        let reactions = [
+            reaction(TaskStatus::Completed, &["U1"]),
+            reaction(TaskStatus::InProgress, &["U2", "U3"]),
+        ];
+        assert_eq!(
+            resolve_status(StatusResolution::Majority, &reactions),
+            TaskStatus::InProgress
+        );
+
+        // U2 and U3 moved on to blocked, so their in progress votes no longer count
+        let reactions = [
+            reaction(TaskStatus::InProgress, &["U2", "U3"]),
+            reaction(TaskStatus::Completed, &["U1"]),
+            reaction(TaskStatus::Blocked, &["U2", "U3"]),
+        ];
+        assert_eq!(
+            resolve_status(StatusResolution::Majority, &reactions),
+            TaskStatus::Blocked
+        );
+    }
+
+    #[test]
+    fn majority_ties_go_by_precedence() {
+        let reactions = [
+            reaction(TaskStatus::InProgress, &["U1"]),
+            reaction(TaskStatus::Completed, &["U2"]),
+        ];
+        assert_eq!(
+            resolve_status(StatusResolution::Majority, &reactions),
+            TaskStatus::Completed
+        );
+    }
+
+    #[test]
+    fn majority_without_reactors_falls_back_to_precedence() {
+        let reactions = [
+            reaction(TaskStatus::Completed, &[]),
+            reaction(TaskStatus::InProgress, &[]),
+        ];
+        assert_eq!(
+            resolve_status(StatusResolution::Majority, &reactions),
+            TaskStatus::Completed
+        );
+    }
 }
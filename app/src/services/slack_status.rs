@@ -0,0 +1,65 @@
+//! Slack statuses of members who opted in, kept showing the task they are working on
+
+use anyhow::Result;
+use tracing::info;
+
+use crate::{
+    core::state::AppState,
+    models::{slack_status_setting::Model as SlackStatusSetting, task::TaskStatus},
+    repos::{slack_status_settings::SlackStatusSettingsRepo, tasks::TasksRepo},
+    sockets::slack_api::SlackClient,
+    utils::encryption::decrypt,
+};
+
+const STATUS_EMOJI: &str = ":hammer:";
+
+/// Point the person's status in the workspace at their newest task in progress, or
+/// clear it when they have none. Does nothing unless they opted in there.
+pub async fn sync(state: &AppState, person_id: &str, workspace_id: &str) -> Result<()> {
+    let Some(setting) = SlackStatusSettingsRepo::new(state.database.clone())
+        .get(person_id, workspace_id)
+        .await?
+        .filter(|setting| setting.enabled)
+    else {
+        return Ok(());
+    };
+
+    let current = TasksRepo::new(state.database.clone())
+        .get_assigned_in_workspace(person_id, workspace_id, Some(TaskStatus::InProgress))
+        .await?
+        .into_iter()
+        .next();
+    let text = current.map(|task| {
+        format!(
+            "Working on {}",
+            task.key.unwrap_or_else(|| "a task".to_string())
+        )
+    });
+
+    match text {
+        Some(text) => set(state, &setting, &text, STATUS_EMOJI).await?,
+        None => clear(state, &setting).await?,
+    }
+    info!(
+        "[SlackStatus] Synced status of {} in workspace {}",
+        person_id, workspace_id
+    );
+    Ok(())
+}
+
+/// Remove the status, e.g. when the person opts out
+pub async fn clear(state: &AppState, setting: &SlackStatusSetting) -> Result<()> {
+    set(state, setting, "", "").await
+}
+
+async fn set(
+    state: &AppState,
+    setting: &SlackStatusSetting,
+    text: &str,
+    emoji: &str,
+) -> Result<()> {
+    let token = decrypt(&setting.user_token, &state.config.encryption_key)?;
+    SlackClient::http(token)
+        .set_profile_status(text, emoji)
+        .await
+}
@@ -0,0 +1,490 @@
+//! Daily standups over bot DMs. At `ask_at` the bot DMs every linked member of the
+//! workspace a button that opens the three questions in a modal; at `summary_at`
+//! it posts the day's answers to the team channel. Both run as scheduled jobs of
+//! the workspace, named after it and kept in step with its `StandupSettings`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use anyhow::Result;
+use chrono::{NaiveDate, NaiveTime, Timelike, Utc};
+use cron::Schedule;
+use sea_orm::DatabaseConnection;
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use crate::{
+    core::state::AppState,
+    i18n::{self, Locale},
+    models::{
+        scheduled_job::ScheduledJobKind, standup_entry::Model as StandupEntry, task::TaskStatus,
+        workspace_settings::StandupSettings,
+    },
+    repos::{
+        scheduled_jobs::ScheduledJobsRepo, standup_entries::StandupEntriesRepo, tasks::TasksRepo,
+        workspace_links::WorkspaceLinksRepo, workspace_settings::WorkspaceSettingsRepo,
+    },
+    services::{task_keys, unfurls, workspace::get_cached_workspace_tokens},
+    sockets::slack_api::SlackClient,
+    utils::{response::FieldError, time::parse_timezone},
+};
+
+/// Button of the standup DM; its value is the day asked about
+pub const ANSWER_ACTION_ID: &str = "standup_answer";
+/// `callback_id` of the modal with the questions
+pub const MODAL_CALLBACK_ID: &str = "standup";
+
+/// Answers of a submitted `standup_modal`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StandupAnswers {
+    pub day: NaiveDate,
+    pub yesterday: String,
+    pub today: String,
+    pub blockers: Option<String>,
+}
+
+/// One linked member in a standup summary
+#[derive(Debug, Clone)]
+pub struct SummaryMember {
+    /// Slack member id, for the mention
+    pub member_id: String,
+    pub entry: Option<StandupEntry>,
+    /// Slack links to the member's tasks in progress
+    pub tasks: Vec<String>,
+}
+
+/// Names of the question and summary jobs of a workspace
+pub fn job_names(workspace_id: &str) -> (String, String) {
+    (
+        format!("standup:{}", workspace_id),
+        format!("standup_summary:{}", workspace_id),
+    )
+}
+
+/// Cron expressions of the question and summary jobs, or why the settings are
+/// rejected
+pub fn crons(settings: &StandupSettings) -> Result<(String, String), Vec<FieldError>> {
+    let mut errors = Vec::new();
+    let mut error = |field: &str, message: &str| {
+        errors.push(FieldError {
+            field: field.to_string(),
+            message: message.to_string(),
+        })
+    };
+
+    if settings.channel.trim().is_empty() {
+        error("channel", "Pick the channel the summary is posted to");
+    }
+    let ask_at = NaiveTime::parse_from_str(settings.ask_at.trim(), "%H:%M").ok();
+    if ask_at.is_none() {
+        error("ask_at", "Use HH:MM, e.g. 09:30");
+    }
+    let summary_at = NaiveTime::parse_from_str(settings.summary_at.trim(), "%H:%M").ok();
+    match (ask_at, summary_at) {
+        (_, None) => error("summary_at", "Use HH:MM, e.g. 11:00"),
+        (Some(ask_at), Some(summary_at)) if summary_at <= ask_at => {
+            error("summary_at", "Must be later in the day than ask_at")
+        }
+        _ => {}
+    }
+    if parse_timezone(&settings.timezone).is_none() {
+        error("timezone", "Unknown timezone");
+    }
+    let days = settings.days.trim();
+    if days.is_empty() || Schedule::from_str(&format!("0 0 0 * * {}", days)).is_err() {
+        error(
+            "days",
+            "Use cron days of the week, e.g. Mon-Fri or Mon,Wed,Fri",
+        );
+    }
+
+    match (ask_at, summary_at) {
+        (Some(ask_at), Some(summary_at)) if errors.is_empty() => {
+            let cron = |at: NaiveTime| format!("0 {} {} * * {}", at.minute(), at.hour(), days);
+            Ok((cron(ask_at), cron(summary_at)))
+        }
+        _ => Err(errors),
+    }
+}
+
+/// Create or move the jobs of a workspace's standup, or remove them with `None`
+pub async fn schedule(
+    db: &DatabaseConnection,
+    workspace_id: &str,
+    settings: Option<&StandupSettings>,
+) -> Result<()> {
+    let repo = ScheduledJobsRepo::new(db.clone());
+    let (questions, summary) = job_names(workspace_id);
+
+    let Some(settings) = settings else {
+        repo.delete_by_name(&questions).await?;
+        repo.delete_by_name(&summary).await?;
+        return Ok(());
+    };
+    let (ask_cron, summary_cron) = crons(settings).map_err(|errors| {
+        anyhow::anyhow!(
+            "Invalid standup settings: {}",
+            errors
+                .iter()
+                .map(|e| format!("{}: {}", e.field, e.message))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    })?;
+    repo.save_workspace_job(
+        &questions,
+        ScheduledJobKind::Standup,
+        &ask_cron,
+        &settings.timezone,
+        workspace_id,
+    )
+    .await?;
+    repo.save_workspace_job(
+        &summary,
+        ScheduledJobKind::StandupSummary,
+        &summary_cron,
+        &settings.timezone,
+        workspace_id,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Today in the standup's timezone
+fn local_day(settings: &StandupSettings) -> NaiveDate {
+    match parse_timezone(&settings.timezone) {
+        Some(tz) => Utc::now().with_timezone(&tz).date_naive(),
+        None => Utc::now().date_naive(),
+    }
+}
+
+/// DM with the button that opens the questions
+fn question_blocks(locale: Locale, day: NaiveDate, text: &str) -> Value {
+    json!([
+        { "type": "section", "text": { "type": "mrkdwn", "text": text } },
+        {
+            "type": "actions",
+            "elements": [{
+                "type": "button",
+                "action_id": ANSWER_ACTION_ID,
+                "text": {
+                    "type": "plain_text",
+                    "text": i18n::text(locale, "standup.answer", &[]),
+                },
+                "style": "primary",
+                "value": day.to_string(),
+            }],
+        },
+    ])
+}
+
+/// Modal with the three questions, filled with earlier answers of the day.
+/// `private_metadata` carries the day.
+pub fn standup_modal(locale: Locale, day: NaiveDate, entry: Option<&StandupEntry>) -> Value {
+    let text = |key: &str| i18n::text(locale, key, &[]);
+    let input = |block_id: &str, label: String, initial: Option<&str>, optional: bool| {
+        let mut element = json!({
+            "type": "plain_text_input",
+            "action_id": block_id,
+            "multiline": true,
+        });
+        if let Some(initial) = initial {
+            element["initial_value"] = json!(initial);
+        }
+        json!({
+            "type": "input",
+            "block_id": block_id,
+            "optional": optional,
+            "label": { "type": "plain_text", "text": label },
+            "element": element,
+        })
+    };
+
+    json!({
+        "type": "modal",
+        "callback_id": MODAL_CALLBACK_ID,
+        "private_metadata": day.to_string(),
+        "title": { "type": "plain_text", "text": text("standup.title") },
+        "submit": { "type": "plain_text", "text": text("standup.send") },
+        "close": { "type": "plain_text", "text": text("standup.cancel") },
+        "blocks": [
+            input(
+                "yesterday",
+                text("standup.ask_yesterday"),
+                entry.map(|e| e.yesterday.as_str()),
+                false,
+            ),
+            input(
+                "today",
+                text("standup.ask_today"),
+                entry.map(|e| e.today.as_str()),
+                false,
+            ),
+            input(
+                "blockers",
+                text("standup.ask_blockers"),
+                entry.and_then(|e| e.blockers.as_deref()),
+                true,
+            ),
+        ],
+    })
+}
+
+/// Answers of a submitted `standup_modal`
+pub fn submitted_standup(view: &Value) -> Option<StandupAnswers> {
+    let values = &view["state"]["values"];
+    let answer = |block_id: &str| {
+        values[block_id][block_id]["value"]
+            .as_str()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    Some(StandupAnswers {
+        day: view["private_metadata"].as_str()?.parse().ok()?,
+        yesterday: answer("yesterday")?,
+        today: answer("today")?,
+        blockers: answer("blockers"),
+    })
+}
+
+/// Summary of a day's standup: each member's answers and tasks in progress, then
+/// who did not answer
+pub fn summary_text(locale: Locale, day: NaiveDate, members: &[SummaryMember]) -> String {
+    let label = |key: &str| i18n::text(locale, key, &[]);
+    let mut text = i18n::text(
+        locale,
+        "standup.summary_title",
+        &[("date", &i18n::long_date(locale, day))],
+    );
+    let mut missing = Vec::new();
+    for member in members {
+        let Some(entry) = &member.entry else {
+            missing.push(format!("<@{}>", member.member_id));
+            continue;
+        };
+        text.push_str(&format!(
+            "\n\n<@{}>\n*{}:* {}\n*{}:* {}",
+            member.member_id,
+            label("standup.yesterday"),
+            entry.yesterday,
+            label("standup.today"),
+            entry.today
+        ));
+        if let Some(blockers) = &entry.blockers {
+            text.push_str(&format!("\n*{}:* {}", label("standup.blockers"), blockers));
+        }
+        if !member.tasks.is_empty() {
+            text.push_str(&format!(
+                "\n*{}:* {}",
+                label("standup.in_progress"),
+                member.tasks.join(", ")
+            ));
+        }
+    }
+
+    if missing.len() == members.len() {
+        text.push_str(&format!("\n\n{}", label("standup.nobody_answered")));
+    } else if !missing.is_empty() {
+        text.push_str(&format!(
+            "\n\n*{}:* {}",
+            label("standup.no_answer"),
+            missing.join(", ")
+        ));
+    }
+    text
+}
+
+/// DM the questions to every linked member of a workspace. Returns how many were
+/// sent.
+pub async fn send_questions(state: &AppState, workspace_id: &str) -> Result<usize> {
+    let Some(settings) = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_standup_settings(workspace_id)
+        .await?
+    else {
+        return Ok(0);
+    };
+    let day = local_day(&settings);
+
+    let mut sent = 0;
+    for (_, person) in WorkspaceLinksRepo::new(state.database.clone())
+        .get_workspace_users(workspace_id)
+        .await?
+    {
+        let locale = i18n::person_locale(&state.database, &person.id, Some(workspace_id)).await;
+        let text = i18n::text(
+            locale,
+            "standup.questions",
+            &[("day", &i18n::short_date(locale, day))],
+        );
+        let blocks = question_blocks(locale, day, &text);
+        match state
+            .notifier
+            .send_blocks(&person, Some(workspace_id), &text, &blocks)
+            .await
+        {
+            Ok(()) => sent += 1,
+            Err(e) => warn!(
+                "[Standups] Failed to ask {} in workspace {}: {}",
+                person.email, workspace_id, e
+            ),
+        }
+    }
+    Ok(sent)
+}
+
+/// Post the day's answers of a workspace to its standup channel. Task keys in the
+/// answers link to the tasks. Returns how many members answered.
+pub async fn post_summary(state: &AppState, workspace_id: &str) -> Result<usize> {
+    let Some(settings) = WorkspaceSettingsRepo::new(state.database.clone())
+        .get_standup_settings(workspace_id)
+        .await?
+    else {
+        return Ok(0);
+    };
+    let day = local_day(&settings);
+    let frontend_url = &state.config.frontend_url;
+    let tasks_repo = TasksRepo::new(state.database.clone());
+
+    let mut entries: HashMap<String, StandupEntry> =
+        StandupEntriesRepo::new(state.database.clone())
+            .get_for_day(workspace_id, day)
+            .await?
+            .into_iter()
+            .map(|entry| (entry.person_id.clone(), entry))
+            .collect();
+
+    // Only keys of this workspace's tasks become links
+    let mentioned = entries
+        .values()
+        .flat_map(|e| [&e.yesterday, &e.today].into_iter().chain(&e.blockers))
+        .flat_map(|text| task_keys::find_keys(text))
+        .collect::<HashSet<_>>();
+    let known: HashSet<String> = tasks_repo
+        .get_by_keys(mentioned.into_iter().collect())
+        .await?
+        .into_iter()
+        .filter(|task| task.workspace_id.as_deref() == Some(workspace_id))
+        .filter_map(|task| task.key)
+        .collect();
+    let linkify = |text: &str| {
+        task_keys::link_keys(text, |key| {
+            known
+                .contains(key)
+                .then(|| unfurls::task_url(frontend_url, key))
+        })
+    };
+
+    let mut members = Vec::new();
+    for (link, person) in WorkspaceLinksRepo::new(state.database.clone())
+        .get_workspace_users(workspace_id)
+        .await?
+    {
+        let entry = entries.remove(&person.id).map(|entry| StandupEntry {
+            yesterday: linkify(&entry.yesterday),
+            today: linkify(&entry.today),
+            blockers: entry.blockers.as_deref().map(linkify),
+            ..entry
+        });
+        let tasks = tasks_repo
+            .get_assigned_in_workspace(&person.id, workspace_id, Some(TaskStatus::InProgress))
+            .await?
+            .into_iter()
+            .filter_map(|task| task.key)
+            .map(|key| format!("<{}|{}>", unfurls::task_url(frontend_url, &key), key))
+            .collect();
+        members.push(SummaryMember {
+            member_id: link
+                .slack_member_id
+                .filter(|id| !id.is_empty())
+                .unwrap_or(person.external_id),
+            entry,
+            tasks,
+        });
+    }
+
+    let locale = i18n::workspace_locale(&state.database, workspace_id).await;
+    let tokens = get_cached_workspace_tokens(
+        &state.cache,
+        &state.database,
+        workspace_id,
+        &state.config.encryption_key,
+    )
+    .await?;
+    SlackClient::http(tokens.bot_token)
+        .post_message(&settings.channel, &summary_text(locale, day, &members))
+        .await?;
+
+    let answered = members.iter().filter(|m| m.entry.is_some()).count();
+    info!(
+        "[Standups] Posted the standup of workspace {} with {} answer(s)",
+        workspace_id, answered
+    );
+    Ok(answered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(ask_at: &str, summary_at: &str, days: &str) -> StandupSettings {
+        StandupSettings {
+            channel: "C123".to_string(),
+            ask_at: ask_at.to_string(),
+            summary_at: summary_at.to_string(),
+            timezone: "Europe/Berlin".to_string(),
+            days: days.to_string(),
+        }
+    }
+
+    #[test]
+    fn schedules_both_jobs_on_the_days() {
+        assert_eq!(
+            crons(&settings("09:30", "11:00", "Mon-Fri")).ok(),
+            Some((
+                "0 30 9 * * Mon-Fri".to_string(),
+                "0 0 11 * * Mon-Fri".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_summaries_before_the_questions() {
+        let fields = |settings: StandupSettings| -> Vec<String> {
+            crons(&settings)
+                .unwrap_err()
+                .into_iter()
+                .map(|e| e.field)
+                .collect()
+        };
+        assert_eq!(
+            fields(settings("11:00", "09:30", "Mon-Fri")),
+            ["summary_at"]
+        );
+        assert_eq!(fields(settings("9.30", "11:00", "Mon-Fri")), ["ask_at"]);
+        assert_eq!(fields(settings("09:30", "11:00", "Someday")), ["days"]);
+    }
+
+    #[test]
+    fn reads_submitted_answers() {
+        let view = json!({
+            "private_metadata": "2026-03-23",
+            "state": { "values": {
+                "yesterday": { "yesterday": { "value": "ACME-4 " } },
+                "today": { "today": { "value": "ACME-7" } },
+                "blockers": { "blockers": { "value": "" } },
+            } },
+        });
+        assert_eq!(
+            submitted_standup(&view),
+            Some(StandupAnswers {
+                day: NaiveDate::from_ymd_opt(2026, 3, 23).unwrap(),
+                yesterday: "ACME-4".to_string(),
+                today: "ACME-7".to_string(),
+                blockers: None,
+            })
+        );
+    }
+}
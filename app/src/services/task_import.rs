@@ -0,0 +1,366 @@
+//! Import of tasks kept elsewhere, e.g. in a spreadsheet, for teams moving to
+//! Slacker. Each row gets a message written for it, like email-in and templates,
+//! and becomes a task of the person with the row's email.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use sea_orm::DbErr;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::{
+    core::state::AppState,
+    models::{
+        message::IMPORT_ID_PREFIX,
+        person::Model as Person,
+        task::{Model as Task, TaskStatus},
+    },
+    repos::{messages::MessagesRepo, persons::PersonsRepo, workspace_links::WorkspaceLinksRepo},
+    services::task_service::{NewTask, Outcome, TaskService, TaskSource},
+    utils::crypto::generate_uuid,
+};
+
+/// Channel recorded on the messages written for imported tasks
+const IMPORT_CHANNEL: &str = "import";
+
+/// Most rows one import takes
+pub const MAX_ROWS: usize = 5000;
+
+/// One task to import. CSV files name these columns in their header row.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, ToSchema)]
+pub struct ImportRow {
+    pub title: String,
+    /// Email of the member the task is assigned to
+    pub assignee_email: String,
+    /// Blank, in progress, blocked or completed (done); in progress when empty
+    #[serde(default)]
+    pub status: Option<String>,
+    /// RFC 3339 timestamp or `YYYY-MM-DD[ HH:MM:SS]` in UTC; the time of the import
+    /// when empty
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// What became of one row
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportedRow {
+    /// Position of the row, from 1, not counting a CSV header
+    pub row: usize,
+    pub task_id: Option<String>,
+    pub key: Option<String>,
+    /// Why the row was not imported
+    pub error: Option<String>,
+}
+
+/// Rows of a CSV file whose header names the columns of `ImportRow`, in any order.
+/// Fields may be quoted, with `""` for a quote inside them.
+pub fn parse_csv(text: &str) -> Result<Vec<ImportRow>, String> {
+    let mut records = csv_records(text.trim_start_matches('\u{feff}'))?.into_iter();
+    let header = records.next().ok_or("The file is empty")?;
+
+    let column = |names: &[&str]| {
+        header.iter().position(|name| {
+            let name = name.trim().to_ascii_lowercase().replace([' ', '-'], "_");
+            names.contains(&name.as_str())
+        })
+    };
+    let title = column(&["title"]).ok_or("Missing the title column")?;
+    let assignee = column(&["assignee_email", "assignee", "email"])
+        .ok_or("Missing the assignee_email column")?;
+    let status = column(&["status"]);
+    let created_at = column(&["created_at", "created"]);
+
+    let field = |record: &[String], index: usize| {
+        record
+            .get(index)
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    };
+    let optional = |record: &[String], index: Option<usize>| {
+        index
+            .map(|index| field(record, index))
+            .filter(|value| !value.is_empty())
+    };
+
+    Ok(records
+        // Spreadsheets often end with empty lines
+        .filter(|record| record.iter().any(|value| !value.trim().is_empty()))
+        .map(|record| ImportRow {
+            title: field(&record, title),
+            assignee_email: field(&record, assignee),
+            status: optional(&record, status),
+            created_at: optional(&record, created_at),
+        })
+        .collect())
+}
+
+fn csv_records(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!(
+            "Unclosed quote in row {} of the file",
+            records.len() + 1
+        ));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Status named by an import, in any case and spacing
+pub fn parse_status(value: Option<&str>) -> Result<TaskStatus, String> {
+    let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(TaskStatus::InProgress);
+    };
+    let normalized = value.to_ascii_lowercase().replace([' ', '_', '-'], "");
+    match normalized.as_str() {
+        "blank" | "todo" => Ok(TaskStatus::Blank),
+        "inprogress" => Ok(TaskStatus::InProgress),
+        "blocked" => Ok(TaskStatus::Blocked),
+        "completed" | "done" => Ok(TaskStatus::Completed),
+        _ => Err(format!("Unknown status '{}'", value)),
+    }
+}
+
+/// Creation date of an imported task, in UTC
+pub fn parse_created_at(value: &str) -> Result<NaiveDateTime, String> {
+    let value = value.trim();
+    DateTime::parse_from_rfc3339(value)
+        .map(|at| at.naive_utc())
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S"))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S"))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|date| date.and_time(Default::default()))
+        })
+        .map_err(|_| format!("Invalid created date '{}'", value))
+}
+
+/// Create a task per row in the workspace. A row that can't be imported is
+/// reported and the others still are.
+pub async fn import_rows(
+    state: &AppState,
+    workspace_id: &str,
+    rows: Vec<ImportRow>,
+) -> Vec<ImportedRow> {
+    let mut members: HashMap<String, Option<Person>> = HashMap::new();
+    let mut report = Vec::with_capacity(rows.len());
+
+    for (index, row) in rows.into_iter().enumerate() {
+        let result = match import_row(state, workspace_id, row, &mut members).await {
+            Ok(task) => ImportedRow {
+                row: index + 1,
+                task_id: Some(task.id),
+                key: task.key,
+                error: None,
+            },
+            Err(error) => ImportedRow {
+                row: index + 1,
+                task_id: None,
+                key: None,
+                error: Some(error),
+            },
+        };
+        report.push(result);
+    }
+
+    info!(
+        "[Import] Imported {} of {} row(s) into workspace {}",
+        report.iter().filter(|row| row.error.is_none()).count(),
+        report.len(),
+        workspace_id
+    );
+    report
+}
+
+async fn import_row(
+    state: &AppState,
+    workspace_id: &str,
+    row: ImportRow,
+    members: &mut HashMap<String, Option<Person>>,
+) -> Result<Task, String> {
+    let title = row.title.trim();
+    if title.is_empty() {
+        return Err("The title is empty".to_string());
+    }
+    let status = parse_status(row.status.as_deref())?;
+    let created_at = match row.created_at.as_deref().filter(|at| !at.trim().is_empty()) {
+        Some(at) => parse_created_at(at)?,
+        None => Utc::now().naive_utc(),
+    };
+    if created_at > Utc::now().naive_utc() {
+        return Err("The created date is in the future".to_string());
+    }
+
+    let email = row.assignee_email.trim().to_lowercase();
+    if !members.contains_key(&email) {
+        let member = find_member(state, workspace_id, &email)
+            .await
+            .map_err(|e| {
+                error!("[Import] Failed to look up {}: {}", email, e);
+                "Failed to look up the assignee".to_string()
+            })?;
+        members.insert(email.clone(), member);
+    }
+    let Some(assignee) = members.get(&email).cloned().flatten() else {
+        return Err(format!(
+            "No member of the workspace has the email '{}'",
+            email
+        ));
+    };
+
+    let created = created_at.and_utc();
+    let message = MessagesRepo::new(state.database.clone(), state.message_encryption.clone())
+        .create(
+            title.to_string(),
+            format!("{}{}", IMPORT_ID_PREFIX, generate_uuid()),
+            IMPORT_CHANNEL.to_string(),
+            format!(
+                "{}.{:06}",
+                created.timestamp(),
+                created.timestamp_subsec_micros()
+            ),
+            &assignee,
+            workspace_id,
+        )
+        .await
+        .map_err(|e| {
+            error!("[Import] Failed to create message: {}", e);
+            "Failed to create the task".to_string()
+        })?;
+
+    // Imported tasks are history: no WIP limits, integrations or notifications
+    let new_task = NewTask {
+        workspace_id,
+        status,
+        assignee,
+        assigner: None,
+        message,
+        rule: None,
+        template: None,
+        source: TaskSource::History,
+        created_at: Some(created_at),
+    };
+    match TaskService::from_state(state)
+        .create_from_message(new_task)
+        .await
+    {
+        Ok(Outcome::Done(task)) => Ok(task),
+        Ok(Outcome::Blocked(wip)) => Err(wip.message()),
+        Err(e) => {
+            error!("[Import] Failed to create task: {}", e);
+            Err("Failed to create the task".to_string())
+        }
+    }
+}
+
+/// Person with this email linked to the workspace
+async fn find_member(
+    state: &AppState,
+    workspace_id: &str,
+    email: &str,
+) -> Result<Option<Person>, DbErr> {
+    let person = match PersonsRepo::new(state.database.clone())
+        .get_by_email(email.to_string())
+        .await
+    {
+        Ok(person) => person,
+        Err(DbErr::RecordNotFound(_)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    match WorkspaceLinksRepo::new(state.database.clone())
+        .get_by_person_and_workspace(person.id.clone(), workspace_id.to_string())
+        .await
+    {
+        Ok(link) => Ok(link.is_linked.then_some(person)),
+        Err(DbErr::RecordNotFound(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_csv_with_quotes_and_any_column_order() {
+        let csv = "Status,Assignee Email,Title\r\n\
+                   done,ana@example.com,\"Ship it, finally\"\r\n\
+                   ,bo@example.com,\"Say \"\"hi\"\"\nto all\"\r\n\
+                   ,,\r\n";
+        let rows = parse_csv(csv).unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                ImportRow {
+                    title: "Ship it, finally".to_string(),
+                    assignee_email: "ana@example.com".to_string(),
+                    status: Some("done".to_string()),
+                    created_at: None,
+                },
+                ImportRow {
+                    title: "Say \"hi\"\nto all".to_string(),
+                    assignee_email: "bo@example.com".to_string(),
+                    status: None,
+                    created_at: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_csv_without_required_columns_or_with_open_quotes() {
+        assert!(parse_csv("").is_err());
+        assert!(parse_csv("title,status\nA,done").is_err());
+        assert!(parse_csv("title,email\n\"A,a@example.com").is_err());
+    }
+
+    #[test]
+    fn parses_statuses_and_dates() {
+        assert_eq!(parse_status(None), Ok(TaskStatus::InProgress));
+        assert_eq!(
+            parse_status(Some("In Progress")),
+            Ok(TaskStatus::InProgress)
+        );
+        assert_eq!(parse_status(Some("DONE")), Ok(TaskStatus::Completed));
+        assert!(parse_status(Some("later")).is_err());
+
+        let midnight = NaiveDate::from_ymd_opt(2024, 3, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(parse_created_at("2024-03-01"), Ok(midnight));
+        assert_eq!(parse_created_at("2024-03-01T02:00:00+02:00"), Ok(midnight));
+        assert!(parse_created_at("01/03/2024").is_err());
+    }
+}
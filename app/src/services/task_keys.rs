@@ -0,0 +1,133 @@
+//! Human-readable task keys such as `ACME-42`, numbered per workspace in the
+//! order tasks are created
+
+use std::sync::LazyLock;
+
+use anyhow::Result;
+use regex::Regex;
+use sea_orm::{DatabaseConnection, DbErr};
+use tracing::info;
+
+use crate::repos::{
+    task_sequences::TaskSequencesRepo, tasks::TasksRepo, workspaces::WorkspacesRepo,
+};
+
+/// Longest prefix taken from a workspace name
+const MAX_PREFIX_LEN: usize = 6;
+
+static KEY_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b([a-z][a-z0-9]{0,5}-[1-9][0-9]*)\b").unwrap());
+
+/// Key prefix of a workspace: the letters and digits of its name, uppercased and
+/// starting with a letter, e.g. `ACMECO` for "acme-corp"
+pub fn prefix_for(workspace_name: &str) -> String {
+    let prefix: String = workspace_name
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .skip_while(char::is_ascii_digit)
+        .take(MAX_PREFIX_LEN)
+        .collect::<String>()
+        .to_ascii_uppercase();
+    if prefix.is_empty() {
+        "TASK".to_string()
+    } else {
+        prefix
+    }
+}
+
+/// Keys mentioned in a message, uppercased, each once in order of appearance
+pub fn find_keys(text: &str) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for found in KEY_PATTERN.find_iter(text) {
+        let key = found.as_str().to_ascii_uppercase();
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+/// Text with each key `link` has a URL for turned into a Slack link
+pub fn link_keys(text: &str, link: impl Fn(&str) -> Option<String>) -> String {
+    KEY_PATTERN
+        .replace_all(text, |found: &regex::Captures| {
+            let key = found[1].to_ascii_uppercase();
+            match link(&key) {
+                Some(url) => format!("<{}|{}>", url, key),
+                None => found[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// Hand out the next key of a workspace
+pub async fn next_key(db: &DatabaseConnection, workspace_id: &str) -> Result<String, DbErr> {
+    let workspace = WorkspacesRepo::new(db.clone()).get(workspace_id).await?;
+    TaskSequencesRepo::new(db.clone())
+        .next_key(workspace_id, &prefix_for(&workspace.name))
+        .await
+}
+
+/// Hand out the next `count` keys of a workspace at once, e.g. for a bulk insert
+pub async fn next_keys(
+    db: &DatabaseConnection,
+    workspace_id: &str,
+    count: usize,
+) -> Result<Vec<String>, DbErr> {
+    let workspace = WorkspacesRepo::new(db.clone()).get(workspace_id).await?;
+    TaskSequencesRepo::new(db.clone())
+        .next_keys(workspace_id, &prefix_for(&workspace.name), count)
+        .await
+}
+
+/// Give tasks created before keys existed theirs, oldest first. Tasks without a
+/// workspace keep none.
+pub async fn assign_missing_keys(db: &DatabaseConnection) -> Result<usize> {
+    let tasks_repo = TasksRepo::new(db.clone());
+    let tasks = tasks_repo.get_without_key().await?;
+
+    let count = tasks.len();
+    for task in tasks {
+        let Some(workspace_id) = task.workspace_id.clone() else {
+            continue;
+        };
+        let key = next_key(db, &workspace_id).await?;
+        tasks_repo.set_key(task, key).await?;
+    }
+    if count > 0 {
+        info!("[Keys] Assigned keys to {} existing task(s)", count);
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_keep_letters_and_digits() {
+        assert_eq!(prefix_for("acme"), "ACME");
+        assert_eq!(prefix_for("acme-corp"), "ACMECO");
+        assert_eq!(prefix_for("42 labs"), "LABS");
+        assert_eq!(prefix_for("--"), "TASK");
+    }
+
+    #[test]
+    fn finds_each_key_once() {
+        assert_eq!(
+            find_keys("acme-42 is blocked by ACME-7, see ACME-42"),
+            vec!["ACME-42".to_string(), "ACME-7".to_string()]
+        );
+        assert!(find_keys("ACME-0, A- and 42-1").is_empty());
+    }
+
+    #[test]
+    fn links_only_known_keys() {
+        let link = |key: &str| (key == "ACME-42").then(|| format!("https://x/tasks/{}", key));
+        assert_eq!(
+            link_keys("fixed acme-42, still on ACME-7", link),
+            "fixed <https://x/tasks/ACME-42|ACME-42>, still on ACME-7"
+        );
+    }
+}
@@ -0,0 +1,460 @@
+//! Task lifecycle shared by the Slack bot, the API and the integrations. Every
+//! change fans out from here: the status history is recorded, integrations queue
+//! their sync jobs, the board event feeds live boards and outgoing webhooks
+//! (`services::hooks`) and the domain event reaches notifications and the audit log.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, DbErr};
+use tracing::{info, warn};
+
+use crate::{
+    core::{
+        board_events::{BoardChange, BoardEventsManager},
+        events::{DomainEvent, EventBus},
+        state::AppState,
+    },
+    integrations,
+    models::{
+        change::Model as Change,
+        channel_rule::Model as ChannelRule,
+        message::Model as Message,
+        person::Model as Person,
+        task::{Model as Task, TaskPriority, TaskStatus},
+        task_template::Model as TaskTemplate,
+    },
+    repos::{
+        changes::ChangesRepo,
+        messages::{MessageEncryption, MessagesRepo},
+        tasks::TasksRepo,
+        workspaces::WorkspacesRepo,
+    },
+    services::{
+        notifications::Notifications,
+        task_keys,
+        wip_limits::{self, WipCheck},
+    },
+};
+
+/// When each task entered its current status: its last change, or its creation
+/// when it never moved. None when it last moved before changes were timed.
+pub async fn status_since(
+    db: &DatabaseConnection,
+    tasks: &[Task],
+) -> Result<HashMap<String, Option<NaiveDateTime>>, DbErr> {
+    let task_ids = tasks.iter().map(|task| task.id.clone()).collect();
+    // Ordered by task and index, so the last change of a task wins
+    let last_changes: HashMap<String, Change> = ChangesRepo::new(db.clone())
+        .get_for_tasks(task_ids)
+        .await?
+        .into_iter()
+        .map(|change| (change.task_id.clone(), change))
+        .collect();
+
+    Ok(tasks
+        .iter()
+        .map(|task| {
+            let since = match last_changes.get(&task.id) {
+                Some(change) => change.created_at,
+                None => Some(task.created_at),
+            };
+            (task.id.clone(), since)
+        })
+        .collect())
+}
+
+/// Where a new task comes from, which decides what its creation sets off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskSource {
+    /// A reaction or keyword trigger in Slack
+    Slack,
+    /// Email-in
+    Email,
+    /// A task template, from the API or the Slack shortcut
+    Template,
+    /// Channel history picked up by a sync, or tasks imported from elsewhere. The
+    /// task was made long ago, so WIP limits, integrations and notifications stay
+    /// out of it.
+    History,
+}
+
+/// A task to create from a message
+#[derive(Debug)]
+pub struct NewTask<'a> {
+    pub workspace_id: &'a str,
+    pub status: TaskStatus,
+    pub assignee: Person,
+    pub assigner: Option<Person>,
+    pub message: Message,
+    /// Rule of the message's channel, whose priority, labels and project the task takes
+    pub rule: Option<&'a ChannelRule>,
+    /// Template the task is made from, whose priority and labels it takes
+    pub template: Option<&'a TaskTemplate>,
+    pub source: TaskSource,
+    /// When the task was made, if not now, e.g. for an imported task
+    pub created_at: Option<NaiveDateTime>,
+}
+
+/// The task as created or moved, or the WIP limits that refused it
+#[derive(Debug)]
+pub enum Outcome {
+    Done(Task),
+    Blocked(WipCheck),
+}
+
+#[derive(Clone, Debug)]
+pub struct TaskService {
+    db: DatabaseConnection,
+    message_encryption: MessageEncryption,
+    board_events: BoardEventsManager,
+    events: EventBus,
+    notifier: Notifications,
+}
+
+impl TaskService {
+    pub fn new(
+        db: DatabaseConnection,
+        message_encryption: MessageEncryption,
+        board_events: BoardEventsManager,
+        events: EventBus,
+        notifier: Notifications,
+    ) -> Self {
+        Self {
+            db,
+            message_encryption,
+            board_events,
+            events,
+            notifier,
+        }
+    }
+
+    pub fn from_state(state: &AppState) -> Self {
+        Self::new(
+            state.database.clone(),
+            state.message_encryption.clone(),
+            state.board_events.clone(),
+            state.events.clone(),
+            state.notifier.clone(),
+        )
+    }
+
+    /// Task a message. Tasks from Slack respect the workspace's WIP limits.
+    pub async fn create_from_message(&self, new: NewTask<'_>) -> Result<Outcome> {
+        let wip = match new.source {
+            TaskSource::Slack => {
+                wip_limits::check(
+                    &self.db,
+                    new.workspace_id,
+                    &new.assignee.id,
+                    None,
+                    &new.status,
+                )
+                .await?
+            }
+            TaskSource::Email | TaskSource::Template | TaskSource::History => WipCheck::default(),
+        };
+        if wip.is_blocked() {
+            return Ok(Outcome::Blocked(wip));
+        }
+
+        let key = task_keys::next_key(&self.db, new.workspace_id).await?;
+        let tasks_repo = TasksRepo::new(self.db.clone());
+        let task = tasks_repo
+            .create(
+                new.status,
+                new.assignee.clone(),
+                new.assigner.clone(),
+                new.created_at
+                    .unwrap_or_else(|| chrono::Utc::now().naive_utc()),
+                new.message.clone(),
+                new.workspace_id,
+                key,
+            )
+            .await?;
+        let task = match new.template {
+            Some(template) => {
+                tasks_repo
+                    .set_rule_defaults(task, template.priority.clone(), template.labels(), None)
+                    .await?
+            }
+            None => apply_rule_defaults(&tasks_repo, task, new.rule).await?,
+        };
+        info!("[TASK] Created task {} in {:?}", task.id, task.status);
+
+        if new.source != TaskSource::History {
+            integrations::task_created(&self.db, &task).await;
+            wip_limits::warn_assignee(
+                &self.db,
+                &self.notifier,
+                new.workspace_id,
+                &task.assigned_to,
+                &wip,
+            )
+            .await;
+        }
+        self.publish(BoardChange::Created, task.clone()).await;
+        self.events.publish(DomainEvent::TaskCreated {
+            task: task.clone(),
+            from_history: new.source == TaskSource::History,
+        });
+
+        Ok(Outcome::Done(task))
+    }
+
+    /// Create many tasks of `workspace_id` found in channel history at once: their
+    /// keys are taken together and the rows go in with multi-row inserts. Otherwise
+    /// like `create_from_message` with `TaskSource::History`.
+    pub async fn create_many_from_history(
+        &self,
+        workspace_id: &str,
+        tasks: Vec<NewTask<'_>>,
+    ) -> Result<Vec<Task>> {
+        if tasks.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let keys = task_keys::next_keys(&self.db, workspace_id, tasks.len()).await?;
+        let now = chrono::Utc::now().naive_utc();
+        let models = tasks
+            .iter()
+            .zip(keys)
+            .map(|(new, key)| {
+                let mut model = TasksRepo::new_model(
+                    new.status.clone(),
+                    &new.assignee,
+                    new.assigner.as_ref(),
+                    new.created_at.unwrap_or(now),
+                    &new.message,
+                    workspace_id,
+                    key,
+                );
+                if let Some((priority, labels, project_id)) = defaults(new) {
+                    model.priority = Set(priority);
+                    model.labels = Set((!labels.is_empty()).then(|| serde_json::json!(labels)));
+                    model.project_id = Set(project_id);
+                }
+                model
+            })
+            .collect();
+        let created = TasksRepo::new(self.db.clone()).create_many(models).await?;
+        info!("[TASK] Created {} task(s) from history", created.len());
+
+        match WorkspacesRepo::new(self.db.clone()).get(workspace_id).await {
+            Ok(workspace) => {
+                for task in &created {
+                    self.board_events
+                        .publish(&workspace.name, BoardChange::Created, task.clone());
+                }
+            }
+            Err(e) => warn!(
+                "[TASK] Failed to load workspace {} to publish new tasks: {}",
+                workspace_id, e
+            ),
+        }
+        for task in &created {
+            self.events.publish(DomainEvent::TaskCreated {
+                task: task.clone(),
+                from_history: true,
+            });
+        }
+
+        Ok(created)
+    }
+
+    /// Move a task to another status, unless a hard WIP limit refuses it. A soft
+    /// limit lets it through and warns the assignee.
+    pub async fn transition(&self, task: Task, status: TaskStatus) -> Result<Outcome> {
+        self.transition_from(task, status, None).await
+    }
+
+    /// Move a task like `transition`, only while it is at version `expected`, e.g.
+    /// the one a client read. Fails with `DbErr::RecordNotUpdated` once the task
+    /// moved on.
+    pub async fn transition_from(
+        &self,
+        task: Task,
+        status: TaskStatus,
+        expected: Option<i32>,
+    ) -> Result<Outcome> {
+        if task.status == status {
+            return Ok(Outcome::Done(task));
+        }
+
+        let wip = match task.workspace_id.as_deref() {
+            Some(workspace_id) => {
+                wip_limits::check(
+                    &self.db,
+                    workspace_id,
+                    &task.assigned_to,
+                    Some(&task.id),
+                    &status,
+                )
+                .await?
+            }
+            None => WipCheck::default(),
+        };
+        if wip.is_blocked() {
+            info!(
+                "[TASK] Kept task {} out of {:?}: {}",
+                task.id,
+                status,
+                wip.message()
+            );
+            return Ok(Outcome::Blocked(wip));
+        }
+
+        let task = self.change_status(task, status, expected).await?;
+        if let Some(workspace_id) = task.workspace_id.as_deref() {
+            wip_limits::warn_assignee(
+                &self.db,
+                &self.notifier,
+                workspace_id,
+                &task.assigned_to,
+                &wip,
+            )
+            .await;
+        }
+
+        Ok(Outcome::Done(task))
+    }
+
+    /// Move a task to the status it already has elsewhere, e.g. in the channel's
+    /// history or a closed issue. The move happened, so WIP limits don't apply.
+    pub async fn sync_status(&self, task: Task, status: TaskStatus) -> Result<Task> {
+        if task.status == status {
+            return Ok(task);
+        }
+        self.change_status(task, status, None).await
+    }
+
+    /// Give a task to someone else and let them know who did
+    pub async fn assign(
+        &self,
+        task: Task,
+        assignee: &Person,
+        assigned_by: &Person,
+    ) -> Result<Task> {
+        let task = TasksRepo::new(self.db.clone())
+            .reassign(task, &assignee.id)
+            .await?;
+        info!("[TASK] Assigned task {} to {}", task.id, assignee.email);
+        self.publish(BoardChange::Updated, task.clone()).await;
+
+        if let Some(workspace_id) = task.workspace_id.as_deref() {
+            match MessagesRepo::new(self.db.clone(), self.message_encryption.clone())
+                .get_by_id(task.message_id.clone())
+                .await
+            {
+                Ok(message) => {
+                    self.notifier
+                        .notify_assignment(
+                            workspace_id,
+                            assignee,
+                            Some(assigned_by),
+                            message.content,
+                        )
+                        .await
+                }
+                Err(e) => warn!(
+                    "[TASK] Failed to load the message of task {}: {}",
+                    task.id, e
+                ),
+            }
+        }
+
+        Ok(task)
+    }
+
+    /// Delete a task and take it off the boards
+    pub async fn archive(&self, task: Task) -> Result<()> {
+        TasksRepo::new(self.db.clone()).delete(&task).await?;
+        info!("[TASK] Deleted task {}", task.id);
+        self.publish(BoardChange::Deleted, task).await;
+        Ok(())
+    }
+
+    /// Tell live boards and webhooks about a change made outside the lifecycle,
+    /// e.g. a new owner or due date
+    pub async fn publish(&self, change: BoardChange, task: Task) {
+        let Some(workspace_id) = task.workspace_id.clone() else {
+            return;
+        };
+        match WorkspacesRepo::new(self.db.clone())
+            .get(&workspace_id)
+            .await
+        {
+            Ok(workspace) => self.board_events.publish(&workspace.name, change, task),
+            Err(e) => warn!(
+                "[TASK] Failed to load workspace {} to publish task {}: {}",
+                workspace_id, task.id, e
+            ),
+        }
+    }
+
+    async fn change_status(
+        &self,
+        task: Task,
+        status: TaskStatus,
+        expected: Option<i32>,
+    ) -> Result<Task> {
+        let old = task.status.clone();
+        let task = TasksRepo::new(self.db.clone())
+            .change_status(task.id, status, expected)
+            .await?;
+        ChangesRepo::new(self.db.clone())
+            .create(old.clone(), &task)
+            .await?;
+        info!("[TASK] Moved task {} to {:?}", task.id, task.status);
+
+        integrations::task_status_changed(&self.db, &task).await;
+        self.publish(BoardChange::Updated, task.clone()).await;
+        self.events.publish(DomainEvent::TaskTransitioned {
+            from: old,
+            task: task.clone(),
+        });
+        Ok(task)
+    }
+}
+
+/// Give a new task the priority, labels and project of its channel's rule
+/// Priority, labels and project a new task takes from its template or else its
+/// channel's rule, None when neither sets any
+fn defaults(new: &NewTask) -> Option<(Option<TaskPriority>, Vec<String>, Option<String>)> {
+    if let Some(template) = new.template {
+        return Some((template.priority.clone(), template.labels(), None));
+    }
+    new.rule
+        .filter(|rule| {
+            rule.priority.is_some() || rule.project_id.is_some() || !rule.labels().is_empty()
+        })
+        .map(|rule| {
+            (
+                rule.priority.clone(),
+                rule.labels(),
+                rule.project_id.clone(),
+            )
+        })
+}
+
+async fn apply_rule_defaults(
+    tasks_repo: &TasksRepo,
+    task: Task,
+    rule: Option<&ChannelRule>,
+) -> Result<Task> {
+    let Some(rule) = rule.filter(|rule| {
+        rule.priority.is_some() || rule.project_id.is_some() || !rule.labels().is_empty()
+    }) else {
+        return Ok(task);
+    };
+
+    Ok(tasks_repo
+        .set_rule_defaults(
+            task,
+            rule.priority.clone(),
+            rule.labels(),
+            rule.project_id.clone(),
+        )
+        .await?)
+}
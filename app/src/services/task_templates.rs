@@ -0,0 +1,172 @@
+//! Tasks made from a template rather than from a Slack message, through
+//! `POST /api/tasks/from-template/:id` or the Slack shortcut.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::DatabaseConnection;
+use serde_json::{json, Value};
+use tracing::info;
+
+use crate::{
+    i18n::{self, Locale},
+    models::{
+        message::TEMPLATE_ID_PREFIX,
+        person::Model as Person,
+        task::{Model as Task, TaskStatus},
+        task_template::Model as TaskTemplate,
+    },
+    repos::messages::{MessageEncryption, MessagesRepo},
+    services::task_service::{NewTask, Outcome, TaskService, TaskSource},
+    utils::{crypto::generate_uuid, time::parse_timezone},
+};
+
+/// `callback_id` of the global shortcut and of the modal it opens
+pub const SHORTCUT_CALLBACK_ID: &str = "task_from_template";
+
+/// Channel stored on the messages of template tasks
+const TEMPLATE_CHANNEL: &str = "template";
+
+/// Slack allows at most this many options in a static select
+const MAX_MODAL_OPTIONS: usize = 100;
+
+/// Modal opened by the shortcut: a template to pick and optional details. Without
+/// templates it only says so.
+pub fn template_modal(locale: Locale, templates: &[TaskTemplate]) -> Value {
+    let text = |key: &str| i18n::text(locale, key, &[]);
+    if templates.is_empty() {
+        return json!({
+            "type": "modal",
+            "callback_id": SHORTCUT_CALLBACK_ID,
+            "title": { "type": "plain_text", "text": text("templates.title") },
+            "close": { "type": "plain_text", "text": text("templates.close") },
+            "blocks": [{
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": text("templates.none") },
+            }],
+        });
+    }
+
+    let options: Vec<Value> = templates
+        .iter()
+        .take(MAX_MODAL_OPTIONS)
+        .map(|template| {
+            json!({
+                "text": { "type": "plain_text", "text": template.name },
+                "value": template.id,
+            })
+        })
+        .collect();
+    json!({
+        "type": "modal",
+        "callback_id": SHORTCUT_CALLBACK_ID,
+        "title": { "type": "plain_text", "text": text("templates.title") },
+        "submit": { "type": "plain_text", "text": text("templates.create") },
+        "close": { "type": "plain_text", "text": text("templates.cancel") },
+        "blocks": [
+            {
+                "type": "input",
+                "block_id": "template",
+                "label": { "type": "plain_text", "text": text("templates.template") },
+                "element": {
+                    "type": "static_select",
+                    "action_id": "template",
+                    "options": options,
+                },
+            },
+            {
+                "type": "input",
+                "block_id": "details",
+                "optional": true,
+                "label": { "type": "plain_text", "text": text("templates.details") },
+                "element": {
+                    "type": "plain_text_input",
+                    "action_id": "details",
+                    "multiline": true,
+                },
+            },
+        ],
+    })
+}
+
+/// Template id and details of a submitted `template_modal`
+pub fn submitted_template(view: &Value) -> Option<(String, String)> {
+    let values = &view["state"]["values"];
+    let template_id = values["template"]["template"]["selected_option"]["value"].as_str()?;
+    let details = values["details"]["details"]["value"]
+        .as_str()
+        .unwrap_or_default();
+    Some((template_id.to_string(), details.to_string()))
+}
+
+/// Text of a task made from `template`: the rendered title, the details when the
+/// title has no place for them, and the checklist
+pub fn task_text(
+    template: &TaskTemplate,
+    date: chrono::NaiveDate,
+    assignee: &str,
+    details: &str,
+) -> String {
+    let details = details.trim();
+    let mut text = template.render_title(date, assignee, details);
+    if !details.is_empty() && !template.title_pattern.contains("{details}") {
+        text.push_str("\n\n");
+        text.push_str(details);
+    }
+
+    let checklist = template.checklist();
+    if !checklist.is_empty() {
+        text.push('\n');
+        for item in checklist {
+            text.push_str(&format!("\n- [ ] {}", item));
+        }
+    }
+    text
+}
+
+/// Make a task in progress for `person` from a template of their workspace
+pub async fn create_task(
+    db: &DatabaseConnection,
+    encryption: &MessageEncryption,
+    tasks: &TaskService,
+    template: &TaskTemplate,
+    person: Person,
+    details: &str,
+) -> Result<Task> {
+    let today = parse_timezone(&person.timezone)
+        .map(|tz| Utc::now().with_timezone(&tz).date_naive())
+        .unwrap_or_else(|| Utc::now().date_naive());
+    let now = Utc::now();
+
+    let message = MessagesRepo::new(db.clone(), encryption.clone())
+        .create(
+            task_text(template, today, &person.name, details),
+            format!("{}{}", TEMPLATE_ID_PREFIX, generate_uuid()),
+            TEMPLATE_CHANNEL.to_string(),
+            format!("{}.{:06}", now.timestamp(), now.timestamp_subsec_micros()),
+            &person,
+            &template.workspace_id,
+        )
+        .await?;
+
+    let new_task = NewTask {
+        workspace_id: &template.workspace_id,
+        status: TaskStatus::InProgress,
+        assignee: person.clone(),
+        assigner: Some(person.clone()),
+        message,
+        rule: None,
+        template: Some(template),
+        source: TaskSource::Template,
+        created_at: None,
+    };
+    // Template tasks skip WIP limits, like email
+    let Outcome::Done(task) = tasks.create_from_message(new_task).await? else {
+        anyhow::bail!("Task from template {} was refused", template.name);
+    };
+    info!(
+        "[Template] Created task {} from template {} for {}",
+        task.id, template.name, person.email
+    );
+
+    Ok(task)
+}
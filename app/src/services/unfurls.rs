@@ -0,0 +1,169 @@
+//! Previews of task links posted in Slack, e.g. `<frontend_url>/tasks/ACME-42`.
+//! Slack sends a `link_shared` event for links on the app's unfurl domains and the
+//! bot answers with `chat.unfurl`: the task's status, assignee and buttons to move
+//! it. The task's message text is left out, as it may come from a private channel.
+
+use reqwest::Url;
+use serde_json::{json, Value};
+
+use crate::{
+    i18n::{self, Locale},
+    models::{
+        person::Model as Person,
+        task::{Model as Task, TaskStatus},
+    },
+    services::{reminders, task_keys},
+};
+
+/// Buttons of an unfurl as action id, catalog key of the label and the status they
+/// move the task to. Each carries the task id as its value.
+const STATUS_BUTTONS: [(&str, &str, TaskStatus); 3] = [
+    (
+        "unfurl_in_progress",
+        "unfurls.start",
+        TaskStatus::InProgress,
+    ),
+    ("unfurl_blocked", "unfurls.block", TaskStatus::Blocked),
+    (
+        "unfurl_completed",
+        "unfurls.complete",
+        TaskStatus::Completed,
+    ),
+];
+
+/// Key of the task a URL on the frontend points to
+pub fn task_key(url: &str, frontend_url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let frontend = Url::parse(frontend_url).ok()?;
+    if url.host_str() != frontend.host_str() {
+        return None;
+    }
+
+    let path = url
+        .path()
+        .strip_prefix(frontend.path().trim_end_matches('/'))?;
+    let key = path.strip_prefix("/tasks/")?.trim_end_matches('/');
+    match task_keys::find_keys(key).as_slice() {
+        [found] if found.eq_ignore_ascii_case(key) => Some(found.clone()),
+        _ => None,
+    }
+}
+
+/// Link to a task on the frontend, the one `task_key` reads back
+pub fn task_url(frontend_url: &str, key: &str) -> String {
+    format!("{}/tasks/{}", frontend_url.trim_end_matches('/'), key)
+}
+
+/// Status a button of an unfurl moves the task to
+pub fn action_status(action_id: &str) -> Option<TaskStatus> {
+    STATUS_BUTTONS
+        .into_iter()
+        .find(|(id, _, _)| *id == action_id)
+        .map(|(_, _, status)| status)
+}
+
+/// Unfurl of a task link, with buttons for the statuses it is not in
+pub fn task_unfurl(locale: Locale, task: &Task, assignee: Option<&Person>, url: &str) -> Value {
+    let label = |key: &str| i18n::text(locale, key, &[]);
+    let title = match task.key.as_deref() {
+        Some(key) => format!("*<{}|{}>*", url, key),
+        None => format!("*<{}|{}>*", url, label("unfurls.task")),
+    };
+    let assignee = assignee
+        .map(|person| format!("<@{}>", person.external_id))
+        .unwrap_or_else(|| label("unfurls.unknown"));
+
+    let mut fields = vec![
+        format!(
+            "*{}*\n{}",
+            label("unfurls.status"),
+            i18n::status(locale, &task.status)
+        ),
+        format!("*{}*\n{}", label("unfurls.assignee"), assignee),
+    ];
+    if let Some(due_at) = task.due_at {
+        fields.push(format!(
+            "*{}*\n{}",
+            label("unfurls.due"),
+            reminders::slack_date(due_at)
+        ));
+    }
+    if let Some(priority) = &task.priority {
+        fields.push(format!("*{}*\n{:?}", label("unfurls.priority"), priority));
+    }
+    let fields: Vec<Value> = fields
+        .into_iter()
+        .map(|text| json!({ "type": "mrkdwn", "text": text }))
+        .collect();
+
+    let buttons: Vec<Value> = STATUS_BUTTONS
+        .iter()
+        .filter(|(_, _, status)| *status != task.status)
+        .map(|(action_id, key, _)| {
+            json!({
+                "type": "button",
+                "action_id": action_id,
+                "text": { "type": "plain_text", "text": label(key) },
+                "value": task.id,
+            })
+        })
+        .collect();
+
+    json!({
+        "blocks": [
+            {
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": title },
+                "fields": fields,
+            },
+            { "type": "actions", "elements": buttons },
+        ],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_keys_of_task_links() {
+        let frontend = "https://slacker.example";
+        assert_eq!(
+            task_key("https://slacker.example/tasks/acme-42", frontend),
+            Some("ACME-42".to_string())
+        );
+        assert_eq!(
+            task_key("https://slacker.example/tasks/ACME-42/", frontend),
+            Some("ACME-42".to_string())
+        );
+        assert_eq!(
+            task_key("https://other.example/tasks/ACME-42", frontend),
+            None
+        );
+        assert_eq!(
+            task_key("https://slacker.example/tasks/ACME-42x", frontend),
+            None
+        );
+        assert_eq!(
+            task_key("https://slacker.example/boards/ACME-42", frontend),
+            None
+        );
+    }
+
+    #[test]
+    fn follows_the_frontend_path() {
+        let frontend = "https://example.com/slacker/";
+        assert_eq!(
+            task_key("https://example.com/slacker/tasks/ACME-42", frontend),
+            Some("ACME-42".to_string())
+        );
+        assert_eq!(
+            task_key("https://example.com/tasks/ACME-42", frontend),
+            None
+        );
+        assert_eq!(
+            task_key(&task_url(frontend, "ACME-42"), frontend),
+            Some("ACME-42".to_string())
+        );
+    }
+}
@@ -1,6 +1,27 @@
 use reqwest::Client;
 use serde::Deserialize;
 
+use crate::core::{cache::keys, state::AppState};
+
+/// Slack member ID and display name for an email, through the cache.
+/// Only successful lookups are cached so new members are found right away.
+pub async fn lookup_user_by_email(
+    state: &AppState,
+    workspace_id: &str,
+    bot_token: &str,
+    email: &str,
+) -> anyhow::Result<(String, String)> {
+    let key = keys::slack_user(workspace_id, email);
+    if let Some(user) = state.cache.get(&key).await {
+        return Ok(user);
+    }
+
+    let user =
+        fetch_user_by_email_with_config(bot_token, &state.config.google.client_id, email).await?;
+    state.cache.set(&key, &user).await;
+    Ok(user)
+}
+
 pub async fn fetch_user_by_email_with_config(
     bot_token: &str,
     _client_id: &str,
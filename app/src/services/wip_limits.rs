@@ -0,0 +1,154 @@
+//! WIP limits: the most tasks a board column may hold, per assignee or in total.
+//! Soft limits let the move happen and DM the assignee, hard limits refuse it.
+
+use anyhow::Result;
+use sea_orm::DatabaseConnection;
+use tracing::warn;
+
+use crate::{
+    i18n::{self, Locale},
+    models::{
+        notification_setting::NotificationKind,
+        task::TaskStatus,
+        workspace_settings::{WipLimit, WipMode, WipScope},
+    },
+    repos::{persons::PersonsRepo, tasks::TasksRepo, workspace_settings::WorkspaceSettingsRepo},
+    services::{notification_templates::NotificationText, notifications::Notifications},
+};
+
+/// A limit a task would go over, with the tasks already counted against it
+#[derive(Debug, Clone)]
+pub struct WipBreach {
+    pub limit: WipLimit,
+    pub count: u64,
+}
+
+impl WipBreach {
+    fn describe(&self, locale: Locale) -> String {
+        let key = match self.limit.scope {
+            WipScope::Person => "wip.per_person",
+            WipScope::Column => "wip.per_column",
+        };
+        i18n::text(
+            locale,
+            key,
+            &[
+                ("column", &i18n::status(locale, &self.limit.status)),
+                ("count", &self.count.to_string()),
+                ("max", &self.limit.max_tasks.to_string()),
+            ],
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WipCheck {
+    pub breaches: Vec<WipBreach>,
+}
+
+impl WipCheck {
+    /// Whether a hard limit refuses the move
+    pub fn is_blocked(&self) -> bool {
+        self.breaches
+            .iter()
+            .any(|breach| breach.limit.mode == WipMode::Hard)
+    }
+
+    pub fn is_exceeded(&self) -> bool {
+        !self.breaches.is_empty()
+    }
+
+    /// Which limits the move goes over, in English as API errors and logs use it
+    pub fn message(&self) -> String {
+        self.message_in(Locale::default())
+    }
+
+    pub fn message_in(&self, locale: Locale) -> String {
+        let reasons: Vec<String> = self
+            .breaches
+            .iter()
+            .map(|breach| breach.describe(locale))
+            .collect();
+        i18n::text(locale, "wip.reached", &[("reasons", &reasons.join("; "))])
+    }
+}
+
+/// Limits of the workspace a task assigned to `assigned_to` goes over by moving to
+/// `status`. `task_id` is the moving task, None when it is being created.
+pub async fn check(
+    db: &DatabaseConnection,
+    workspace_id: &str,
+    assigned_to: &str,
+    task_id: Option<&str>,
+    status: &TaskStatus,
+) -> Result<WipCheck> {
+    let limits: Vec<WipLimit> = WorkspaceSettingsRepo::new(db.clone())
+        .get_wip_limits(workspace_id)
+        .await?
+        .into_iter()
+        .filter(|limit| &limit.status == status)
+        .collect();
+    if limits.is_empty() {
+        return Ok(WipCheck::default());
+    }
+
+    let tasks_repo = TasksRepo::new(db.clone());
+    let mut breaches = vec![];
+    for limit in limits {
+        let assignee = (limit.scope == WipScope::Person).then_some(assigned_to);
+        let count = tasks_repo
+            .count_in_status(workspace_id, status.clone(), assignee, task_id)
+            .await?;
+        if count >= limit.max_tasks {
+            breaches.push(WipBreach { limit, count });
+        }
+    }
+
+    Ok(WipCheck { breaches })
+}
+
+/// DM the assignee that their task went over a soft limit
+pub async fn warn_assignee(
+    db: &DatabaseConnection,
+    notifier: &Notifications,
+    workspace_id: &str,
+    assigned_to: &str,
+    check: &WipCheck,
+) {
+    if !check.is_exceeded() {
+        return;
+    }
+
+    let assignee = match PersonsRepo::new(db.clone())
+        .get_by_id(assigned_to.to_string())
+        .await
+    {
+        Ok(assignee) => assignee,
+        Err(e) => {
+            warn!("[WIP] Failed to load assignee {}: {}", assigned_to, e);
+            return;
+        }
+    };
+
+    let locale = i18n::person_locale(db, &assignee.id, Some(workspace_id)).await;
+    let reasons = check.message_in(locale);
+    let text = NotificationText {
+        subject: i18n::text(locale, "wip.subject", &[]),
+        body: i18n::text(locale, "wip.advice", &[("message", &reasons)]),
+        variables: vec![("assignee", assignee.name.clone()), ("reasons", reasons)],
+    };
+    if let Err(e) = notifier
+        .notify_text(
+            &assignee,
+            NotificationKind::WipLimit,
+            Some(workspace_id),
+            text,
+        )
+        .await
+    {
+        warn!(
+            "[WIP] Failed to warn {} about a WIP limit: {}",
+            assignee.email, e
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! Workload of the members of a workspace: what they have open, for how long, and
+//! how much of what they were given they finished.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, Utc};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    models::task::{Model as Task, TaskStatus},
+    repos::{tasks::TasksRepo, workspace_links::WorkspaceLinksRepo},
+    services::{availability, business_hours},
+};
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MemberWorkload {
+    pub person_id: String,
+    pub name: String,
+    pub email: String,
+    pub in_progress: u64,
+    pub blocked: u64,
+    /// In progress and blocked tasks together
+    pub open: u64,
+    /// Mean age of the open tasks in hours, None without open tasks. Only working
+    /// hours count when the workspace has business hours.
+    pub average_open_age_hours: Option<f64>,
+    /// Tasks assigned during the window
+    pub assigned_recently: u64,
+    /// Of those, how many are completed
+    pub completed_recently: u64,
+    /// `completed_recently / assigned_recently`, None when nothing was assigned
+    pub completion_rate: Option<f64>,
+    /// Last day of the member's current time out of office, None when they are in
+    pub out_of_office_until: Option<NaiveDate>,
+}
+
+impl MemberWorkload {
+    fn add(&mut self, task: &Task, window_start: chrono::NaiveDateTime) {
+        match task.status {
+            TaskStatus::InProgress => self.in_progress += 1,
+            TaskStatus::Blocked => self.blocked += 1,
+            TaskStatus::Completed | TaskStatus::Blank => {}
+        }
+        if task.created_at >= window_start {
+            self.assigned_recently += 1;
+            if task.status == TaskStatus::Completed {
+                self.completed_recently += 1;
+            }
+        }
+    }
+}
+
+/// Workload of every linked member of a workspace, most loaded first. Completion
+/// rates cover tasks assigned in the last `days` days.
+pub async fn workspace_workload(
+    db: &DatabaseConnection,
+    workspace_id: &str,
+    days: i64,
+) -> Result<Vec<MemberWorkload>> {
+    let members = WorkspaceLinksRepo::new(db.clone())
+        .get_workspace_users(workspace_id)
+        .await?;
+    let tasks = TasksRepo::new(db.clone())
+        .get_by_workspace(workspace_id.to_string())
+        .await?;
+    let people: Vec<_> = members.iter().map(|(_, person)| person).collect();
+    let away = availability::away_today(db, &people).await?;
+    let calendar = business_hours::workspace_calendar(db, workspace_id).await;
+
+    let mut workloads: HashMap<String, MemberWorkload> = members
+        .into_iter()
+        .map(|(_, person)| {
+            let workload = MemberWorkload {
+                person_id: person.id.clone(),
+                name: person.name,
+                email: person.email,
+                in_progress: 0,
+                blocked: 0,
+                open: 0,
+                average_open_age_hours: None,
+                assigned_recently: 0,
+                completed_recently: 0,
+                completion_rate: None,
+                out_of_office_until: away.get(&person.id).copied(),
+            };
+            (person.id, workload)
+        })
+        .collect();
+
+    let now = Utc::now().naive_utc();
+    let window_start = now - Duration::days(days);
+    let mut open_age_hours: HashMap<String, Vec<f64>> = HashMap::new();
+    for task in &tasks {
+        let Some(workload) = workloads.get_mut(&task.assigned_to) else {
+            continue;
+        };
+        workload.add(task, window_start);
+        if matches!(task.status, TaskStatus::InProgress | TaskStatus::Blocked) {
+            let age = business_hours::elapsed(
+                calendar.as_ref(),
+                task.created_at.and_utc(),
+                now.and_utc(),
+            );
+            let age = age.num_minutes() as f64 / 60.0;
+            open_age_hours
+                .entry(task.assigned_to.clone())
+                .or_default()
+                .push(age);
+        }
+    }
+
+    let mut workloads: Vec<MemberWorkload> = workloads
+        .into_values()
+        .map(|mut workload| {
+            workload.open = workload.in_progress + workload.blocked;
+            workload.average_open_age_hours = open_age_hours
+                .get(&workload.person_id)
+                .map(|ages| ages.iter().sum::<f64>() / ages.len() as f64);
+            workload.completion_rate = (workload.assigned_recently > 0)
+                .then(|| workload.completed_recently as f64 / workload.assigned_recently as f64);
+            workload
+        })
+        .collect();
+    workloads.sort_by(|a, b| b.open.cmp(&a.open).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(workloads)
+}
+
+/// Least loaded member for a new task who is not out of office: fewest open tasks,
+/// then fewest in progress, then the best completion rate
+pub fn suggest_assignee(workloads: &[MemberWorkload]) -> Option<&MemberWorkload> {
+    workloads
+        .iter()
+        .filter(|w| w.out_of_office_until.is_none())
+        .min_by(|a, b| {
+            a.open
+                .cmp(&b.open)
+                .then_with(|| a.in_progress.cmp(&b.in_progress))
+                .then_with(|| {
+                    let rate = |w: &MemberWorkload| w.completion_rate.unwrap_or(0.0);
+                    rate(b).total_cmp(&rate(a))
+                })
+                .then_with(|| a.name.cmp(&b.name))
+        })
+}
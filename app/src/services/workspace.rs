@@ -0,0 +1,416 @@
+use anyhow::Result;
+use reqwest::Client;
+use sea_orm::{DatabaseConnection, DbErr};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::{
+    config::workspaces::{WorkspaceConfig, WorkspacesConfig},
+    core::cache::{keys, Cache},
+    models::workspace::{Model as Workspace, WorkspaceStatus},
+    repos::{
+        messages::{MessageEncryption, MessagesRepo},
+        tasks::TasksRepo,
+        workspace_credentials::WorkspaceCredentialsRepo,
+        workspace_links::WorkspaceLinksRepo,
+        workspace_settings::WorkspaceSettingsRepo,
+        workspaces::WorkspacesRepo,
+    },
+};
+
+/// How a workspace's data is handled when it is removed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OffboardMode {
+    /// Remove credentials and unlink everyone, keep settings, tasks and messages
+    #[default]
+    Archive,
+    /// Delete the workspace with its links, settings, tasks and messages
+    Purge,
+}
+
+/// What an offboarding removed, or would remove on a dry run
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct OffboardReport {
+    pub credentials: u64,
+    pub links: u64,
+    pub settings: u64,
+    pub tasks: u64,
+    pub messages: u64,
+}
+
+/// Who a bot token signs in to Slack as
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BotIdentity {
+    /// Slack member id of the bot user
+    pub user_id: String,
+    /// Name of the bot user
+    pub user: String,
+    pub bot_id: Option<String>,
+    pub team_id: String,
+    /// Name of the Slack team
+    pub team: String,
+    /// Enterprise Grid org of the team, if it is in one
+    pub enterprise_id: Option<String>,
+    /// Whether the tokens were installed org-wide on an Enterprise Grid org. Those
+    /// belong to the org rather than one team, so `team_id` names no workspace.
+    pub is_enterprise_install: bool,
+}
+
+impl BotIdentity {
+    /// Enterprise Grid org to keep with the tokens, see `WorkspaceConfig::enterprise_id`
+    pub fn org_wide_enterprise_id(&self) -> Option<String> {
+        self.enterprise_id
+            .clone()
+            .filter(|_| self.is_enterprise_install)
+    }
+
+    /// Whether the tokens can serve a workspace of Slack team `team_id`. Org-wide
+    /// tokens serve every team of their org.
+    pub fn serves(&self, team_id: &str) -> bool {
+        self.is_enterprise_install || self.team_id == team_id
+    }
+
+    /// Slack team a workspace set up with these tokens is for. Org-wide tokens need
+    /// it named in `team_id`; other tokens belong to one team, which `team_id` may
+    /// only repeat. The error says what is wrong.
+    pub fn workspace_team(&self, team_id: Option<&str>) -> Result<String, String> {
+        let team_id = team_id.map(str::trim).filter(|id| !id.is_empty());
+        match team_id {
+            Some(team_id) if self.serves(team_id) => Ok(team_id.to_string()),
+            Some(team_id) => Err(format!(
+                "The bot token belongs to Slack team {} ({}), not {}",
+                self.team, self.team_id, team_id
+            )),
+            None if self.is_enterprise_install => Err(
+                "Org-wide tokens of an Enterprise Grid install need the team id of the workspace"
+                    .to_string(),
+            ),
+            None => Ok(self.team_id.clone()),
+        }
+    }
+}
+
+/// Resolve the Slack team ID a bot token belongs to via `auth.test`
+pub async fn fetch_team_id(bot_token: &str) -> Result<String> {
+    Ok(fetch_bot_identity(bot_token).await?.team_id)
+}
+
+/// Resolve the bot user and team of a bot token via `auth.test`
+pub async fn fetch_bot_identity(bot_token: &str) -> Result<BotIdentity> {
+    #[derive(Debug, Deserialize)]
+    struct AuthTestResponse {
+        ok: bool,
+        user_id: Option<String>,
+        user: Option<String>,
+        bot_id: Option<String>,
+        team_id: Option<String>,
+        team: Option<String>,
+        enterprise_id: Option<String>,
+        #[serde(default)]
+        is_enterprise_install: bool,
+        error: Option<String>,
+    }
+
+    let response = Client::new()
+        .post("https://slack.com/api/auth.test")
+        .header("Authorization", format!("Bearer {}", bot_token))
+        .send()
+        .await?
+        .json::<AuthTestResponse>()
+        .await?;
+
+    match (response.user_id, response.team_id) {
+        (Some(user_id), Some(team_id)) if response.ok => Ok(BotIdentity {
+            user_id,
+            user: response.user.unwrap_or_default(),
+            bot_id: response.bot_id,
+            team_id,
+            team: response.team.unwrap_or_default(),
+            enterprise_id: response.enterprise_id,
+            is_enterprise_install: response.is_enterprise_install,
+        }),
+        _ => Err(anyhow::anyhow!(
+            "Slack auth.test failed: {}",
+            response
+                .error
+                .unwrap_or_else(|| "unknown error".to_string())
+        )),
+    }
+}
+
+/// Check that an app-level token can open Socket Mode connections. The URL Slack
+/// hands out is never connected to.
+pub async fn check_app_token(app_token: &str) -> Result<()> {
+    #[derive(Debug, Deserialize)]
+    struct ConnectionsOpenResponse {
+        ok: bool,
+        error: Option<String>,
+    }
+
+    let response = Client::new()
+        .post("https://slack.com/api/apps.connections.open")
+        .header("Authorization", format!("Bearer {}", app_token))
+        .send()
+        .await?
+        .json::<ConnectionsOpenResponse>()
+        .await?;
+
+    if !response.ok {
+        return Err(anyhow::anyhow!(
+            "Slack apps.connections.open failed: {}",
+            response
+                .error
+                .unwrap_or_else(|| "unknown error".to_string())
+        ));
+    }
+    Ok(())
+}
+
+/// Load and decrypt the Slack tokens of a workspace
+pub async fn get_workspace_tokens(
+    db: &DatabaseConnection,
+    workspace_id: &str,
+    encryption_key: &str,
+) -> Result<WorkspaceConfig> {
+    let credential = WorkspaceCredentialsRepo::new(db.clone())
+        .get_by_workspace(workspace_id)
+        .await?;
+
+    credential.decrypt(encryption_key)
+}
+
+/// `get_workspace_tokens` through the in-process cache, so hot paths don't decrypt on every call
+pub async fn get_cached_workspace_tokens(
+    cache: &Cache,
+    db: &DatabaseConnection,
+    workspace_id: &str,
+    encryption_key: &str,
+) -> Result<WorkspaceConfig> {
+    let key = keys::workspace_tokens(workspace_id);
+    if let Some(tokens) = cache.get_local(&key).await {
+        return Ok(tokens);
+    }
+
+    let tokens = get_workspace_tokens(db, workspace_id, encryption_key).await?;
+    cache.set_local(&key, &tokens).await;
+    Ok(tokens)
+}
+
+/// All active workspaces that have tokens configured, with decrypted tokens
+pub async fn get_active_workspaces_with_tokens(
+    db: &DatabaseConnection,
+    encryption_key: &str,
+) -> Result<Vec<(Workspace, WorkspaceConfig)>> {
+    let workspaces = WorkspacesRepo::new(db.clone()).get_active().await?;
+
+    let mut result = Vec::with_capacity(workspaces.len());
+    for workspace in workspaces {
+        match get_workspace_tokens(db, &workspace.id, encryption_key).await {
+            Ok(tokens) => result.push((workspace, tokens)),
+            Err(e) => warn!("Skipping workspace {}: {}", workspace.name, e),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Create or update a workspace and store its tokens encrypted
+pub async fn save_workspace(
+    db: &DatabaseConnection,
+    name: &str,
+    tokens: &WorkspaceConfig,
+    slack_team_id: Option<String>,
+    created_by: Option<String>,
+    encryption_key: &str,
+) -> Result<Workspace> {
+    let workspaces_repo = WorkspacesRepo::new(db.clone());
+
+    let workspace = match workspaces_repo.get_by_name(name).await {
+        Ok(mut existing) => {
+            // Setting up an archived workspace again brings it back
+            if existing.status != WorkspaceStatus::Active {
+                existing = workspaces_repo
+                    .set_status(&existing.id, WorkspaceStatus::Active)
+                    .await?;
+            }
+            match slack_team_id {
+                Some(team_id) if existing.slack_team_id.as_ref() != Some(&team_id) => {
+                    workspaces_repo
+                        .set_slack_team_id(&existing.id, team_id)
+                        .await?
+                }
+                _ => existing,
+            }
+        }
+        Err(DbErr::RecordNotFound(_)) => {
+            workspaces_repo
+                .create(name.to_string(), slack_team_id, created_by)
+                .await?
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let encrypted = tokens.encrypt(encryption_key)?;
+    WorkspaceCredentialsRepo::new(db.clone())
+        .upsert(
+            &workspace.id,
+            encrypted.app_token,
+            encrypted.bot_token,
+            encrypted.enterprise_id,
+        )
+        .await?;
+
+    Ok(workspace)
+}
+
+/// Import workspaces still only present in the legacy YAML file into the database.
+/// Workspaces that already have credentials are left untouched.
+pub async fn import_workspaces_yaml(
+    db: &DatabaseConnection,
+    path: &str,
+    encryption_key: &str,
+) -> Result<usize> {
+    let workspaces_config = WorkspacesConfig::load_and_decrypt(path, encryption_key)?;
+    let workspaces_repo = WorkspacesRepo::new(db.clone());
+    let credentials_repo = WorkspaceCredentialsRepo::new(db.clone());
+
+    let mut imported = 0;
+    for (name, mut tokens) in workspaces_config.workspaces {
+        if let Ok(workspace) = workspaces_repo.get_by_name(&name).await {
+            if credentials_repo
+                .get_by_workspace(&workspace.id)
+                .await
+                .is_ok()
+            {
+                continue;
+            }
+        }
+
+        let slack_team_id = match fetch_bot_identity(&tokens.bot_token).await {
+            Ok(identity) if identity.is_enterprise_install => {
+                warn!(
+                    "'{}' has org-wide tokens; name its Slack team with `slacker-cli workspace add --team-id`",
+                    name
+                );
+                tokens.enterprise_id = identity.org_wide_enterprise_id();
+                None
+            }
+            Ok(identity) => Some(identity.team_id),
+            Err(e) => {
+                warn!("Could not resolve Slack team for {}: {}", name, e);
+                None
+            }
+        };
+
+        save_workspace(db, &name, &tokens, slack_team_id, None, encryption_key).await?;
+        info!("Imported workspace '{}' from {}", name, path);
+        imported += 1;
+    }
+
+    Ok(imported)
+}
+
+/// Archive or purge a workspace's data. With `dry_run` nothing is changed and the
+/// report lists what would be affected. The caller is responsible for stopping the bot.
+pub async fn offboard_workspace(
+    db: &DatabaseConnection,
+    encryption: &MessageEncryption,
+    workspace: &Workspace,
+    mode: OffboardMode,
+    dry_run: bool,
+) -> Result<OffboardReport> {
+    let workspaces_repo = WorkspacesRepo::new(db.clone());
+    let credentials_repo = WorkspaceCredentialsRepo::new(db.clone());
+    let links_repo = WorkspaceLinksRepo::new(db.clone());
+    let settings_repo = WorkspaceSettingsRepo::new(db.clone());
+    let tasks_repo = TasksRepo::new(db.clone());
+    let messages_repo = MessagesRepo::new(db.clone(), encryption.clone());
+
+    let has_credentials = credentials_repo
+        .get_by_workspace(&workspace.id)
+        .await
+        .is_ok();
+    let links = links_repo.count_by_workspace(workspace.id.clone()).await?;
+
+    let mut report = OffboardReport {
+        credentials: has_credentials as u64,
+        links,
+        ..Default::default()
+    };
+
+    if mode == OffboardMode::Purge {
+        let tasks = tasks_repo.get_by_workspace(workspace.id.clone()).await?;
+        let message_ids: Vec<String> = tasks.iter().map(|t| t.message_id.clone()).collect();
+        let merged = messages_repo
+            .get_merged_into_any(tasks.iter().map(|t| t.id.clone()).collect())
+            .await?;
+        let has_settings = settings_repo
+            .get_by_workspace(&workspace.id)
+            .await?
+            .is_some();
+
+        report.settings = has_settings as u64;
+        report.tasks = tasks.len() as u64;
+        report.messages = (message_ids.len() + merged.len()) as u64;
+
+        if !dry_run {
+            // Tasks and their changes cascade from messages, and messages of
+            // merged duplicates go with them; credentials, links and settings
+            // cascade from the workspace
+            report.messages = messages_repo.delete_by_ids(message_ids).await?;
+            workspaces_repo.delete(&workspace.id).await?;
+            info!("Purged workspace '{}'", workspace.name);
+        }
+    } else if !dry_run {
+        credentials_repo.delete_by_workspace(&workspace.id).await?;
+        report.links = links_repo.unlink_all(workspace.id.clone()).await?;
+        workspaces_repo
+            .set_status(&workspace.id, WorkspaceStatus::Archived)
+            .await?;
+        info!("Archived workspace '{}'", workspace.name);
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(is_enterprise_install: bool) -> BotIdentity {
+        BotIdentity {
+            user_id: "U0BOT".to_string(),
+            user: "slacker".to_string(),
+            bot_id: Some("B0BOT".to_string()),
+            team_id: if is_enterprise_install {
+                "E0ORG"
+            } else {
+                "T0ONE"
+            }
+            .to_string(),
+            team: "Acme".to_string(),
+            enterprise_id: Some("E0ORG".to_string()),
+            is_enterprise_install,
+        }
+    }
+
+    #[test]
+    fn workspace_tokens_belong_to_their_team() {
+        let bot = identity(false);
+        assert_eq!(bot.workspace_team(None), Ok("T0ONE".to_string()));
+        assert_eq!(bot.workspace_team(Some(" T0ONE ")), Ok("T0ONE".to_string()));
+        assert!(bot.workspace_team(Some("T0TWO")).is_err());
+        assert_eq!(bot.org_wide_enterprise_id(), None);
+    }
+
+    #[test]
+    fn org_wide_tokens_need_a_team() {
+        let bot = identity(true);
+        assert!(bot.workspace_team(None).is_err());
+        assert!(bot.workspace_team(Some("  ")).is_err());
+        assert_eq!(bot.workspace_team(Some("T0TWO")), Ok("T0TWO".to_string()));
+        assert_eq!(bot.org_wide_enterprise_id(), Some("E0ORG".to_string()));
+    }
+}
@@ -1 +1,2 @@
+pub mod slack_api;
 pub mod slack_bot;
@@ -0,0 +1,745 @@
+//! Slack Web API calls of the bot and the initial syncer. Requests go through the
+//! `SlackApi` trait, so tests can answer them from recorded responses with
+//! `RecordedSlackApi` instead of reaching Slack.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+use axum::async_trait;
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tracing::{error, info};
+
+const SLACK_API_URL: &str = "https://slack.com/api";
+
+/// Transport for Slack Web API methods. Response bodies are returned whether they
+/// are `ok` or not; `SlackClient` makes sense of them.
+#[async_trait]
+pub trait SlackApi: Send + Sync {
+    /// Call a read method with query parameters
+    async fn get(&self, method: &str, query: &[(&str, &str)]) -> Result<Value>;
+
+    /// Call a write method with a JSON body
+    async fn post(&self, method: &str, body: Value) -> Result<Value>;
+}
+
+/// Calls Slack over HTTP with a bot token
+pub struct HttpSlackApi {
+    client: Client,
+    token: String,
+}
+
+impl HttpSlackApi {
+    pub fn new(token: String) -> Self {
+        Self {
+            client: Client::new(),
+            token,
+        }
+    }
+}
+
+#[async_trait]
+impl SlackApi for HttpSlackApi {
+    async fn get(&self, method: &str, query: &[(&str, &str)]) -> Result<Value> {
+        Ok(self
+            .client
+            .get(format!("{}/{}", SLACK_API_URL, method))
+            .bearer_auth(&self.token)
+            .query(query)
+            .send()
+            .await?
+            .json()
+            .await?)
+    }
+
+    async fn post(&self, method: &str, body: Value) -> Result<Value> {
+        Ok(self
+            .client
+            .post(format!("{}/{}", SLACK_API_URL, method))
+            .bearer_auth(&self.token)
+            .json(&body)
+            .send()
+            .await?
+            .json()
+            .await?)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlackReaction {
+    pub name: String,
+    #[serde(default)]
+    pub users: Vec<String>,
+    #[serde(default)]
+    pub count: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlackMessage {
+    pub text: String,
+    pub user: String,
+    pub ts: String,
+    pub thread_timestamp: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageResponse {
+    messages: Option<Vec<SlackMessage>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageWithReactions {
+    #[serde(default)]
+    reactions: Option<Vec<SlackReaction>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReactionsResponse {
+    ok: bool,
+    #[serde(default)]
+    message: Option<MessageWithReactions>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlackChannel {
+    pub id: String,
+    pub name: String,
+    /// Whether the bot is in the channel
+    #[serde(default)]
+    pub is_member: bool,
+    #[serde(default)]
+    pub is_private: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChannelsResponse {
+    ok: bool,
+    channels: Option<Vec<SlackChannel>>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmojiListResponse {
+    ok: bool,
+    /// Custom emoji name to image URL, or `alias:<name>` for aliases
+    emoji: Option<HashMap<String, String>>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryMessage {
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub text: Option<String>,
+    pub ts: String,
+    #[serde(default)]
+    pub reactions: Option<Vec<HistoryReaction>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryReaction {
+    pub name: String,
+    #[serde(default)]
+    pub users: Option<Vec<String>>,
+    #[serde(default)]
+    pub count: Option<i32>,
+}
+
+impl From<&HistoryReaction> for SlackReaction {
+    fn from(reaction: &HistoryReaction) -> Self {
+        Self {
+            name: reaction.name.clone(),
+            users: reaction.users.clone().unwrap_or_default(),
+            count: reaction.count.unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryResponse {
+    pub ok: bool,
+    pub messages: Option<Vec<HistoryMessage>>,
+    pub response_metadata: Option<ResponseMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResponseMetadata {
+    pub next_cursor: Option<String>,
+}
+
+fn parse<T: DeserializeOwned>(method: &str, body: Value) -> Result<T> {
+    serde_json::from_value(body.clone()).map_err(|e| {
+        error!("Failed to parse Slack {} response: {}", method, e);
+        error!("Raw response was: {}", body);
+        anyhow::anyhow!("Failed to parse Slack {} response: {}", method, e)
+    })
+}
+
+/// Typed Slack Web API methods over a `SlackApi` transport
+#[derive(Clone)]
+pub struct SlackClient {
+    api: Arc<dyn SlackApi>,
+}
+
+impl SlackClient {
+    pub fn new(api: Arc<dyn SlackApi>) -> Self {
+        Self { api }
+    }
+
+    /// Client calling Slack over HTTP with a bot token
+    pub fn http(bot_token: String) -> Self {
+        Self::new(Arc::new(HttpSlackApi::new(bot_token)))
+    }
+
+    /// The message of a channel posted at `timestamp`
+    pub async fn fetch_message(&self, channel: &str, timestamp: &str) -> Result<SlackMessage> {
+        let method = "conversations.history";
+        let body = self
+            .api
+            .get(
+                method,
+                &[
+                    ("channel", channel),
+                    ("latest", timestamp),
+                    ("inclusive", "true"),
+                    ("limit", "1"),
+                ],
+            )
+            .await?;
+        info!("Slack {} response: {}", method, body);
+
+        parse::<MessageResponse>(method, body)?
+            .messages
+            .and_then(|mut m| m.pop())
+            .ok_or_else(|| anyhow::anyhow!("Message not found"))
+    }
+
+    /// Reactions currently on a message
+    pub async fn fetch_reactions(
+        &self,
+        channel: &str,
+        timestamp: &str,
+    ) -> Result<Vec<SlackReaction>> {
+        let method = "reactions.get";
+        let body = self
+            .api
+            .get(method, &[("channel", channel), ("timestamp", timestamp)])
+            .await?;
+        let response: ReactionsResponse = parse(method, body)?;
+
+        if !response.ok {
+            return Err(anyhow::anyhow!(
+                "Slack reactions.get failed for {}:{} ({:?})",
+                channel,
+                timestamp,
+                response.error
+            ));
+        }
+
+        Ok(response
+            .message
+            .and_then(|m| m.reactions)
+            .unwrap_or_default())
+    }
+
+    /// React to a message as the bot; a reaction the bot already added is fine
+    pub async fn add_reaction(&self, channel: &str, timestamp: &str, emoji: &str) -> Result<()> {
+        let response = self
+            .api
+            .post(
+                "reactions.add",
+                serde_json::json!({
+                    "channel": channel,
+                    "timestamp": timestamp,
+                    "name": emoji,
+                }),
+            )
+            .await?;
+
+        let error = response["error"].as_str();
+        if response["ok"].as_bool() == Some(true) || error == Some("already_reacted") {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "Slack reactions.add failed: {}",
+            error.unwrap_or("unknown error")
+        ))
+    }
+
+    /// Post a message in a channel the bot is a member of
+    pub async fn post_message(&self, channel: &str, text: &str) -> Result<()> {
+        let response = self
+            .api
+            .post(
+                "chat.postMessage",
+                serde_json::json!({
+                    "channel": channel,
+                    "text": text,
+                }),
+            )
+            .await?;
+
+        if response["ok"].as_bool() == Some(true) {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "Slack chat.postMessage failed: {}",
+            response["error"].as_str().unwrap_or("unknown error")
+        ))
+    }
+
+    /// Post a message only `user` sees in the channel
+    pub async fn post_ephemeral(&self, channel: &str, user: &str, text: &str) -> Result<()> {
+        let response = self
+            .api
+            .post(
+                "chat.postEphemeral",
+                serde_json::json!({
+                    "channel": channel,
+                    "user": user,
+                    "text": text,
+                }),
+            )
+            .await?;
+
+        if response["ok"].as_bool() == Some(true) {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "Slack chat.postEphemeral failed: {}",
+            response["error"].as_str().unwrap_or("unknown error")
+        ))
+    }
+
+    /// Set the status of the user whose token the client calls with; empty text and
+    /// emoji clear it
+    pub async fn set_profile_status(&self, text: &str, emoji: &str) -> Result<()> {
+        let response = self
+            .api
+            .post(
+                "users.profile.set",
+                serde_json::json!({
+                    "profile": {
+                        "status_text": text,
+                        "status_emoji": emoji,
+                        "status_expiration": 0,
+                    },
+                }),
+            )
+            .await?;
+
+        if response["ok"].as_bool() == Some(true) {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "Slack users.profile.set failed: {}",
+            response["error"].as_str().unwrap_or("unknown error")
+        ))
+    }
+
+    /// Open a modal for the `trigger_id` of a shortcut
+    pub async fn open_view(&self, trigger_id: &str, view: Value) -> Result<()> {
+        let response = self
+            .api
+            .post(
+                "views.open",
+                serde_json::json!({
+                    "trigger_id": trigger_id,
+                    "view": view,
+                }),
+            )
+            .await?;
+
+        if response["ok"].as_bool() == Some(true) {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "Slack views.open failed: {}",
+            response["error"].as_str().unwrap_or("unknown error")
+        ))
+    }
+
+    /// Replace the text and blocks of a message the bot posted
+    pub async fn update_message(
+        &self,
+        channel: &str,
+        timestamp: &str,
+        text: &str,
+        blocks: Value,
+    ) -> Result<()> {
+        let response = self
+            .api
+            .post(
+                "chat.update",
+                serde_json::json!({
+                    "channel": channel,
+                    "ts": timestamp,
+                    "text": text,
+                    "blocks": blocks,
+                }),
+            )
+            .await?;
+
+        if response["ok"].as_bool() == Some(true) {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "Slack chat.update failed: {}",
+            response["error"].as_str().unwrap_or("unknown error")
+        ))
+    }
+
+    /// Previews of links in a message, keyed by URL; calling again replaces them
+    pub async fn unfurl(&self, channel: &str, timestamp: &str, unfurls: Value) -> Result<()> {
+        let response = self
+            .api
+            .post(
+                "chat.unfurl",
+                serde_json::json!({
+                    "channel": channel,
+                    "ts": timestamp,
+                    "unfurls": unfurls,
+                }),
+            )
+            .await?;
+
+        if response["ok"].as_bool() == Some(true) {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "Slack chat.unfurl failed: {}",
+            response["error"].as_str().unwrap_or("unknown error")
+        ))
+    }
+
+    /// Public and private channels the bot can see, archived ones excluded
+    pub async fn list_channels(&self) -> Result<Vec<SlackChannel>> {
+        let method = "conversations.list";
+        let body = self
+            .api
+            .get(
+                method,
+                &[
+                    ("types", "public_channel,private_channel"),
+                    ("exclude_archived", "true"),
+                    ("limit", "1000"),
+                ],
+            )
+            .await?;
+        let response: ChannelsResponse = parse(method, body)?;
+
+        if !response.ok {
+            return Err(anyhow::anyhow!(
+                "Failed to fetch channels: {:?}",
+                response.error
+            ));
+        }
+        Ok(response.channels.unwrap_or_default())
+    }
+
+    /// Whether a conversation is a private channel, a group DM or a DM
+    pub async fn channel_is_private(&self, channel: &str) -> Result<bool> {
+        if channel.starts_with('D') {
+            return Ok(true);
+        }
+
+        let response = self
+            .api
+            .get("conversations.info", &[("channel", channel)])
+            .await?;
+        if response["ok"].as_bool() != Some(true) {
+            return Err(anyhow::anyhow!(
+                "Slack conversations.info failed: {}",
+                response["error"].as_str().unwrap_or("unknown error")
+            ));
+        }
+
+        let info = &response["channel"];
+        Ok(["is_private", "is_im", "is_mpim"]
+            .iter()
+            .any(|flag| info[flag].as_bool() == Some(true)))
+    }
+
+    /// Public channels of the workspace, archived ones excluded
+    pub async fn list_public_channels(&self) -> Result<Vec<SlackChannel>> {
+        let method = "conversations.list";
+        let body = self
+            .api
+            .get(
+                method,
+                &[
+                    ("types", "public_channel"),
+                    ("exclude_archived", "true"),
+                    ("limit", "1000"),
+                ],
+            )
+            .await?;
+        let response: ChannelsResponse = parse(method, body)?;
+
+        if !response.ok {
+            return Err(anyhow::anyhow!(
+                "Failed to fetch channels: {:?}",
+                response.error
+            ));
+        }
+        Ok(response.channels.unwrap_or_default())
+    }
+
+    /// Have the bot join a public channel; joining one it is in already succeeds
+    pub async fn join_channel(&self, channel: &str) -> Result<()> {
+        let response = self
+            .api
+            .post(
+                "conversations.join",
+                serde_json::json!({ "channel": channel }),
+            )
+            .await?;
+
+        if response["ok"].as_bool() == Some(true) {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "Slack conversations.join failed: {}",
+            response["error"].as_str().unwrap_or("unknown error")
+        ))
+    }
+
+    /// Names of the workspace's custom emoji, aliases included
+    pub async fn list_emoji(&self) -> Result<Vec<String>> {
+        let method = "emoji.list";
+        let body = self.api.get(method, &[]).await?;
+        let response: EmojiListResponse = parse(method, body)?;
+
+        if !response.ok {
+            return Err(anyhow::anyhow!(
+                "Failed to fetch emoji: {:?}",
+                response.error
+            ));
+        }
+        let mut names: Vec<String> = response.emoji.unwrap_or_default().into_keys().collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// One page of up to 100 messages of a channel, newest first
+    pub async fn channel_history(
+        &self,
+        channel: &str,
+        cursor: Option<&str>,
+    ) -> Result<HistoryResponse> {
+        let method = "conversations.history";
+        let mut query = vec![("channel", channel), ("limit", "100")];
+        if let Some(cursor) = cursor {
+            query.push(("cursor", cursor));
+        }
+
+        let body = self.api.get(method, &query).await?;
+        parse(method, body)
+    }
+}
+
+/// One recorded Slack call: the method, parameters the call must include, and
+/// the response body Slack gave
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedCall {
+    pub method: String,
+    #[serde(default)]
+    pub params: Map<String, Value>,
+    pub response: Value,
+}
+
+impl RecordedCall {
+    fn matches(&self, method: &str, params: &Map<String, Value>) -> bool {
+        self.method == method
+            && self
+                .params
+                .iter()
+                .all(|(key, value)| params.get(key) == Some(value))
+    }
+}
+
+/// `SlackApi` answering from recorded responses, for tests without network access.
+/// A call gets the response of the first recording of its method whose params it
+/// includes, so list specific recordings (e.g. a later page) before general ones.
+/// Calls without a matching recording fail. Every call is kept for assertions.
+#[derive(Debug, Default)]
+pub struct RecordedSlackApi {
+    recordings: Vec<RecordedCall>,
+    calls: Mutex<Vec<(String, Value)>>,
+}
+
+impl RecordedSlackApi {
+    pub fn new(recordings: Vec<RecordedCall>) -> Self {
+        Self {
+            recordings,
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Recordings from a JSON array of `{"method", "params", "response"}` objects
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    /// Add a recording; `params` is a JSON object
+    pub fn record(mut self, method: &str, params: Value, response: Value) -> Self {
+        self.recordings.push(RecordedCall {
+            method: method.to_string(),
+            params: params.as_object().cloned().unwrap_or_default(),
+            response,
+        });
+        self
+    }
+
+    /// Method and parameters of every call so far, oldest first
+    pub fn calls(&self) -> Vec<(String, Value)> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Parameters of the calls to one method, oldest first
+    pub fn calls_to(&self, method: &str) -> Vec<Value> {
+        self.calls()
+            .into_iter()
+            .filter(|(m, _)| m == method)
+            .map(|(_, params)| params)
+            .collect()
+    }
+
+    fn respond(&self, method: &str, params: Map<String, Value>) -> Result<Value> {
+        let response = self
+            .recordings
+            .iter()
+            .find(|recording| recording.matches(method, &params))
+            .map(|recording| recording.response.clone());
+        self.calls
+            .lock()
+            .unwrap()
+            .push((method.to_string(), Value::Object(params.clone())));
+
+        response.ok_or_else(|| {
+            anyhow::anyhow!(
+                "No recorded Slack response for {} {}",
+                method,
+                Value::Object(params)
+            )
+        })
+    }
+}
+
+#[async_trait]
+impl SlackApi for RecordedSlackApi {
+    async fn get(&self, method: &str, query: &[(&str, &str)]) -> Result<Value> {
+        let params = query
+            .iter()
+            .map(|(key, value)| (key.to_string(), Value::String(value.to_string())))
+            .collect();
+        self.respond(method, params)
+    }
+
+    async fn post(&self, method: &str, body: Value) -> Result<Value> {
+        self.respond(method, body.as_object().cloned().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const REACTION_ADDED: &str = include_str!("../../tests/fixtures/slack/reaction_added.json");
+
+    fn client(api: RecordedSlackApi) -> (SlackClient, Arc<RecordedSlackApi>) {
+        let api = Arc::new(api);
+        (SlackClient::new(api.clone()), api)
+    }
+
+    #[tokio::test]
+    async fn fetches_a_recorded_message_and_its_reactions() {
+        let (slack, _) = client(RecordedSlackApi::from_json(REACTION_ADDED).unwrap());
+
+        let message = slack
+            .fetch_message("C0ENG", "1718000000.000100")
+            .await
+            .unwrap();
+        assert_eq!(message.user, "U0AUTHOR");
+        assert_eq!(message.text, "Ship the release notes");
+
+        let reactions = slack
+            .fetch_reactions("C0ENG", "1718000000.000100")
+            .await
+            .unwrap();
+        assert_eq!(reactions.len(), 1);
+        assert_eq!(reactions[0].name, "eyes");
+        assert_eq!(reactions[0].users, vec!["U0REACTOR".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn failed_reaction_lookups_are_errors() {
+        let (slack, _) = client(RecordedSlackApi::default().record(
+            "reactions.get",
+            json!({}),
+            json!({"ok": false, "error": "message_not_found"}),
+        ));
+
+        assert!(slack.fetch_reactions("C0ENG", "1.0").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn adding_a_reaction_twice_is_fine() {
+        let (slack, api) = client(RecordedSlackApi::default().record(
+            "reactions.add",
+            json!({}),
+            json!({"ok": false, "error": "already_reacted"}),
+        ));
+
+        slack.add_reaction("C0ENG", "1.0", "eyes").await.unwrap();
+        assert_eq!(
+            api.calls_to("reactions.add"),
+            vec![json!({"channel": "C0ENG", "timestamp": "1.0", "name": "eyes"})]
+        );
+    }
+
+    #[tokio::test]
+    async fn history_pages_are_matched_by_cursor() {
+        let (slack, _) = client(
+            RecordedSlackApi::default()
+                .record(
+                    "conversations.history",
+                    json!({"cursor": "page2"}),
+                    json!({"ok": true, "messages": []}),
+                )
+                .record(
+                    "conversations.history",
+                    json!({"channel": "C0ENG"}),
+                    json!({
+                        "ok": true,
+                        "messages": [{"user": "U0AUTHOR", "text": "hi", "ts": "1.0"}],
+                        "response_metadata": {"next_cursor": "page2"},
+                    }),
+                ),
+        );
+
+        let first = slack.channel_history("C0ENG", None).await.unwrap();
+        assert_eq!(first.messages.unwrap().len(), 1);
+        let next = first.response_metadata.and_then(|m| m.next_cursor);
+        assert_eq!(next.as_deref(), Some("page2"));
+
+        let second = slack.channel_history("C0ENG", Some("page2")).await.unwrap();
+        assert!(second.messages.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unrecorded_calls_fail() {
+        let (slack, api) = client(RecordedSlackApi::default());
+
+        assert!(slack.list_channels().await.is_err());
+        assert_eq!(api.calls().len(), 1);
+    }
+}
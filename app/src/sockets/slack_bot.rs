@@ -1,25 +1,79 @@
-use std::{collections::HashSet, time::Duration};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Result;
 use futures_util::{SinkExt, StreamExt};
 use reqwest::Client;
-use sea_orm::{sqlx::types::chrono, DatabaseConnection, DbErr};
+use sea_orm::{DatabaseConnection, DbErr};
 use serde::{Deserialize, Serialize};
 use tokio::time::interval;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_util::task::TaskTracker;
 use tracing::{error, info, warn};
 
 use crate::{
     config::{config::Config, workspaces::WorkspacesConfig},
-    core::bot_status::BotStatusManager,
-    models::{task::TaskStatus, workspace_settings::EmojiMappings},
+    core::{
+        board_events::{BoardChange, BoardEventsManager},
+        bot_status::BotStatusManager,
+        cache::{keys as cache_keys, Cache},
+        events::{DomainEvent, EventBus},
+        jobs::{self, Job},
+        maintenance::MaintenanceMode,
+        state::AppState,
+    },
+    i18n::{self, Locale},
+    models::{
+        bot_event::{BotEventOutcome, Model as BotEvent},
+        bot_incident::BotIncidentKind,
+        channel_rule::Model as ChannelRule,
+        message::Model as MessageModel,
+        person::Model as Person,
+        task::{Model as Task, TaskStatus},
+        workspace::Model as Workspace,
+        workspace_link::WorkspaceRole,
+        workspace_settings::{
+            EmojiMappings, KeywordTrigger, PrivateChannelPolicy, ReactionPolicy, StatusResolution,
+        },
+    },
     repos::{
-        messages::MessagesRepo, persons::PersonsRepo, tasks::TasksRepo,
-        workspace_links::WorkspaceLinksRepo, workspace_settings::WorkspaceSettingsRepo,
+        bot_events::{BotEventsRepo, NewBotEvent},
+        bot_incidents::BotIncidentsRepo,
+        channel_rules::ChannelRulesRepo,
+        messages::{MessageEncryption, MessagesRepo, NewMessage},
+        persons::PersonsRepo,
+        reminders::RemindersRepo,
+        standup_entries::StandupEntriesRepo,
+        task_templates::TaskTemplatesRepo,
+        tasks::TasksRepo,
+        workspace_links::WorkspaceLinksRepo,
+        workspace_settings::WorkspaceSettingsRepo,
+        workspaces::WorkspacesRepo,
+    },
+    services::{
+        availability, deprovision, duplicates, reminders,
+        slack_service::{
+            counted_reactors, resolve_status, Reactors, StatusReaction, STATUS_PRECEDENCE,
+        },
+        standups, task_keys,
+        task_service::{NewTask, Outcome, TaskService, TaskSource},
+        task_templates, unfurls,
+        workspace::get_cached_workspace_tokens,
     },
-    services::slack_service::eval_status_from_reactions,
+    sockets::slack_api::{
+        HistoryMessage, SlackApi, SlackChannel, SlackClient, SlackMessage, SlackReaction,
+    },
+    utils::pagination::{Pagination, MAX_LIMIT},
 };
 
+/// Events held back during maintenance; the oldest are dropped beyond this and left
+/// to the periodic sync
+const MAX_QUEUED_EVENTS: usize = 10_000;
+
 // NOTE: This SlackBot currently uses Config which no longer has bot_token/app_token.
 // TODO: Refactor to use WorkspacesConfig and create one bot instance per workspace.
 // Each workspace should have its own WebSocket connection.
@@ -38,22 +92,13 @@ struct SlackEventItem {
     ts: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct SlackReaction {
-    name: String,
-    #[serde(default)]
-    users: Vec<String>,
-    #[serde(default)]
-    count: i32,
-}
-
 #[derive(Debug, Deserialize)]
 struct SlackEvent {
     #[serde(rename = "type")]
     event_type: String,
     #[serde(default)]
     subtype: Option<String>,
-    user: Option<String>,
+    user: Option<EventUser>,
     reaction: Option<String>,
     item: Option<SlackEventItem>,
     #[serde(default)]
@@ -62,6 +107,51 @@ struct SlackEvent {
     ts: Option<String>,
     #[serde(default)]
     message: Option<SlackEventMessage>,
+    /// Text of new messages and app mentions
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    thread_ts: Option<String>,
+    /// Message of a `link_shared` event
+    #[serde(default)]
+    message_ts: Option<String>,
+    /// Links of a `link_shared` event, only those on the app's unfurl domains
+    #[serde(default)]
+    links: Vec<SharedLink>,
+    /// Where a `link_shared` link was posted: `conversations_history`, or
+    /// `composer` while it is still being written
+    #[serde(default)]
+    source: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SharedLink {
+    url: String,
+}
+
+/// `user` of an event: a member id on most events, the member's profile on
+/// `user_change` and `team_leave`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum EventUser {
+    Id(String),
+    Profile {
+        id: String,
+        #[serde(default)]
+        deleted: bool,
+    },
+}
+
+impl EventUser {
+    fn id(&self) -> &str {
+        match self {
+            EventUser::Id(id) | EventUser::Profile { id, .. } => id,
+        }
+    }
+
+    fn is_deleted(&self) -> bool {
+        matches!(self, EventUser::Profile { deleted: true, .. })
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -79,28 +169,88 @@ struct SlackEventMessage {
 #[derive(Debug, Deserialize)]
 struct EventPayload {
     event: Option<SlackEvent>,
+    /// Slack team of an `events_api` envelope
+    #[serde(default)]
+    team_id: Option<String>,
+    /// Slack team of an `interactive` envelope
+    #[serde(default)]
+    team: Option<InteractionTeam>,
+    /// Kind of interaction on `interactive` envelopes, such as `shortcut` or
+    /// `view_submission`
+    #[serde(default, rename = "type")]
+    interaction_type: Option<String>,
+    #[serde(default)]
+    callback_id: Option<String>,
+    #[serde(default)]
+    trigger_id: Option<String>,
+    #[serde(default)]
+    user: Option<InteractionUser>,
+    /// The submitted modal
+    #[serde(default)]
+    view: Option<serde_json::Value>,
+    /// Message of a message action, or the bot's message whose button was clicked
+    #[serde(default)]
+    message: Option<InteractionMessage>,
+    #[serde(default)]
+    channel: Option<InteractionChannel>,
+    /// Buttons and menus used on `block_actions`
+    #[serde(default)]
+    actions: Vec<BlockAction>,
+    /// Where a clicked button is, e.g. the unfurl of a link
+    #[serde(default)]
+    container: Option<InteractionContainer>,
 }
 
 #[derive(Debug, Deserialize)]
-struct SlackEnvelope {
-    #[serde(rename = "type")]
-    envelope_type: String,
-    envelope_id: Option<String>,
-    payload: Option<EventPayload>,
+struct InteractionUser {
+    id: String,
 }
 
 #[derive(Debug, Deserialize)]
-struct SlackMessage {
-    text: String,
-    user: String,
+struct InteractionTeam {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct InteractionMessage {
     ts: String,
-    thread_timestamp: Option<String>,
+    #[serde(default)]
+    text: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
-struct MessageResponse {
-    ok: bool,
-    messages: Option<Vec<SlackMessage>>,
+struct InteractionChannel {
+    id: String,
+}
+
+/// Container of a button on an unfurl: the message with the link and the link
+#[derive(Debug, Deserialize)]
+struct InteractionContainer {
+    #[serde(default)]
+    channel_id: Option<String>,
+    #[serde(default)]
+    message_ts: Option<String>,
+    #[serde(default)]
+    app_unfurl_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockAction {
+    action_id: String,
+    /// Set on buttons
+    #[serde(default)]
+    value: Option<String>,
+    /// Set on menus
+    #[serde(default)]
+    selected_option: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackEnvelope {
+    #[serde(rename = "type")]
+    envelope_type: String,
+    envelope_id: Option<String>,
+    payload: Option<EventPayload>,
 }
 
 #[derive(Debug, Serialize)]
@@ -108,84 +258,360 @@ struct Acknowledgment {
     envelope_id: String,
 }
 
-fn emoji_to_status(emoji: &str, mappings: &EmojiMappings) -> Option<TaskStatus> {
-    if mappings.in_progress.contains(&emoji.to_string()) {
-        return Some(TaskStatus::InProgress);
+impl SlackEvent {
+    /// What the bot event log keeps of the event
+    fn summary(&self) -> NewBotEvent {
+        NewBotEvent {
+            envelope_type: "events_api".to_string(),
+            event_type: self.event_type.clone(),
+            subtype: self.subtype.clone(),
+            channel: self
+                .item
+                .as_ref()
+                .map(|item| item.channel.clone())
+                .or_else(|| self.channel.clone()),
+            ts: self
+                .item
+                .as_ref()
+                .map(|item| item.ts.clone())
+                .or_else(|| self.message.as_ref().and_then(|m| m.ts.clone()))
+                .or_else(|| self.ts.clone())
+                .or_else(|| self.message_ts.clone()),
+            user_id: self.user.as_ref().map(|user| user.id().to_string()),
+            reaction: self.reaction.clone(),
+        }
     }
-    if mappings.blocked.contains(&emoji.to_string()) {
-        return Some(TaskStatus::Blocked);
+}
+
+impl EventPayload {
+    /// Slack team the event or interaction happened in
+    fn slack_team(&self) -> Option<&str> {
+        self.team_id
+            .as_deref()
+            .or_else(|| self.team.as_ref().map(|team| team.id.as_str()))
     }
-    if mappings.completed.contains(&emoji.to_string()) {
-        return Some(TaskStatus::Completed);
+
+    /// What the bot event log keeps of the interaction
+    fn summary(&self) -> NewBotEvent {
+        NewBotEvent {
+            envelope_type: "interactive".to_string(),
+            event_type: self
+                .interaction_type
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string()),
+            subtype: self.callback_id.clone(),
+            channel: self.channel.as_ref().map(|channel| channel.id.clone()),
+            ts: self.message.as_ref().map(|message| message.ts.clone()),
+            user_id: self.user.as_ref().map(|user| user.id.clone()),
+            reaction: None,
+        }
+    }
+}
+
+/// Whether `replay` can run a logged event again. Interactions can't be replayed,
+/// Slack only accepts answers to them for a few seconds.
+pub fn can_replay(event: &BotEvent) -> bool {
+    event.envelope_type == "events_api"
+        && event.channel.is_some()
+        && event.ts.is_some()
+        && matches!(
+            event.event_type.as_str(),
+            "reaction_added" | "reaction_removed" | "message" | "app_mention"
+        )
+}
+
+/// Why the bot did not act on an event. Handlers return it as an error to stop,
+/// and it is recorded as a skipped bot event rather than a failure.
+#[derive(Debug)]
+struct Skipped(String);
+
+impl std::fmt::Display for Skipped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
     }
-    None
 }
 
+impl std::error::Error for Skipped {}
+
+fn skip(reason: impl Into<String>) -> Result<()> {
+    Err(Skipped(reason.into()).into())
+}
+
+/// Status an emoji sets. Mappings saved before overlaps were rejected may list an
+/// emoji under several statuses; the one with the highest precedence wins.
+fn emoji_to_status(emoji: &str, mappings: &EmojiMappings) -> Option<TaskStatus> {
+    STATUS_PRECEDENCE
+        .into_iter()
+        .find(|status| mappings.emojis(status).iter().any(|mapped| mapped == emoji))
+}
+
+/// Reactions that set a status, in the order Slack lists them (first added first),
+/// with only the reactors who count
 fn map_reactions_to_status(
-    reactions: &Vec<SlackReaction>,
+    reactions: &[SlackReaction],
     mappings: &EmojiMappings,
-) -> HashSet<TaskStatus> {
-    let mut status_set: HashSet<TaskStatus> = HashSet::new();
-
-    for reaction in reactions {
-        match emoji_to_status(&reaction.name, mappings) {
-            Some(status) => {
-                status_set.insert(status);
+    reactors: &Reactors,
+) -> Vec<StatusReaction> {
+    reactions
+        .iter()
+        .filter_map(|reaction| {
+            // Non-mapped emojis are the common case and are ignored
+            let status = emoji_to_status(&reaction.name, mappings)?;
+            let users: Vec<String> = reaction
+                .users
+                .iter()
+                .filter(|user| reactors.counts(user))
+                .cloned()
+                .collect();
+            // A policy can't vouch for reactions Slack listed without their users
+            if users.is_empty() && *reactors != Reactors::Anyone {
+                return None;
             }
-            None => {
-                // Silently ignore non-mapped emojis (common case)
-            }
-        };
+            Some(StatusReaction { status, users })
+        })
+        .collect()
+}
+
+/// How the workspace resolves reactions for several statuses, through the cache
+async fn cached_status_resolution(
+    cache: &Cache,
+    db: &DatabaseConnection,
+    workspace_id: &str,
+) -> StatusResolution {
+    let key = cache_keys::status_resolution(workspace_id);
+    if let Some(resolution) = cache.get(&key).await {
+        return resolution;
+    }
+
+    match WorkspaceSettingsRepo::new(db.clone())
+        .get_status_resolution(workspace_id)
+        .await
+    {
+        Ok(resolution) => {
+            cache.set(&key, &resolution).await;
+            resolution
+        }
+        Err(_) => StatusResolution::default(),
+    }
+}
+
+/// Whose reactions count in the workspace, through the cache
+async fn cached_reaction_policy(
+    cache: &Cache,
+    db: &DatabaseConnection,
+    workspace_id: &str,
+) -> ReactionPolicy {
+    let key = cache_keys::reaction_policy(workspace_id);
+    if let Some(policy) = cache.get(&key).await {
+        return policy;
     }
 
-    status_set
+    match WorkspaceSettingsRepo::new(db.clone())
+        .get_reaction_policy(workspace_id)
+        .await
+    {
+        Ok(policy) => {
+            cache.set(&key, &policy).await;
+            policy
+        }
+        Err(_) => ReactionPolicy::default(),
+    }
 }
 
+/// What becomes of messages in private channels and DMs, through the cache
+async fn cached_private_channel_policy(
+    cache: &Cache,
+    db: &DatabaseConnection,
+    workspace_id: &str,
+) -> PrivateChannelPolicy {
+    let key = cache_keys::private_channel_policy(workspace_id);
+    if let Some(policy) = cache.get(&key).await {
+        return policy;
+    }
+
+    match WorkspaceSettingsRepo::new(db.clone())
+        .get_private_channel_policy(workspace_id)
+        .await
+    {
+        Ok(policy) => {
+            cache.set(&key, &policy).await;
+            policy
+        }
+        Err(_) => PrivateChannelPolicy::default(),
+    }
+}
+
+/// Emoji mappings of a workspace, read through the cache as every reaction needs them
+async fn cached_emoji_mappings(
+    cache: &Cache,
+    db: &DatabaseConnection,
+    workspace_id: &str,
+) -> EmojiMappings {
+    let key = cache_keys::emoji_mappings(workspace_id);
+    if let Some(mappings) = cache.get(&key).await {
+        return mappings;
+    }
+
+    match WorkspaceSettingsRepo::new(db.clone())
+        .get_emoji_mappings(workspace_id)
+        .await
+    {
+        Ok(mappings) => {
+            cache.set(&key, &mappings).await;
+            mappings
+        }
+        Err(_) => EmojiMappings::default_mappings(),
+    }
+}
+
+/// Person a channel rule assigns new tasks to, as long as they are linked to the
+/// workspace and not out of office
+async fn rule_assignee(
+    db: &DatabaseConnection,
+    workspace_id: &str,
+    rule: Option<&ChannelRule>,
+) -> Option<Person> {
+    let assignee_id = rule?.assignee_id.clone()?;
+    let linked = WorkspaceLinksRepo::new(db.clone())
+        .get_by_person_and_workspace(assignee_id.clone(), workspace_id.to_string())
+        .await
+        .is_ok_and(|link| link.is_linked);
+    if !linked {
+        warn!(
+            "Channel rule assignee {} is not linked to workspace {} - using the message author",
+            assignee_id, workspace_id
+        );
+        return None;
+    }
+
+    let assignee = PersonsRepo::new(db.clone())
+        .get_by_id(assignee_id)
+        .await
+        .ok()?;
+    if let Ok(Some(until)) = availability::away_until(db, &assignee).await {
+        info!(
+            "Channel rule assignee {} is out of office until {} - using the message author",
+            assignee.email, until
+        );
+        return None;
+    }
+    Some(assignee)
+}
+
+#[derive(Clone)]
 pub struct SlackBot {
+    workspace_id: String,
     workspace_name: String,
+    /// Events of other Slack teams are handed to their workspace, see `for_team`
+    slack_team_id: Option<String>,
     app_token: String,
-    bot_token: String,
     db: DatabaseConnection,
+    /// Opens the Socket Mode connection with the app token
     http_client: Client,
+    slack: SlackClient,
     status_manager: BotStatusManager,
-}
-
-#[derive(Debug, Deserialize)]
-struct MessageWithReactions {
-    #[serde(rename = "type", default)]
-    msg_type: Option<String>,
-    #[serde(default)]
-    text: Option<String>,
-    #[serde(default)]
-    reactions: Option<Vec<SlackReaction>>,
-    #[serde(flatten, default)]
-    _extra: serde_json::Value,
-}
-
-#[derive(Debug, Deserialize)]
-struct ReactionsResponse {
-    ok: bool,
-    #[serde(default)]
-    message: Option<MessageWithReactions>,
-    #[serde(default)]
-    error: Option<String>,
+    board_events: BoardEventsManager,
+    events: EventBus,
+    tasks: TaskService,
+    cache: Cache,
+    maintenance: MaintenanceMode,
+    /// Super admin, who counts as an admin under `ReactionPolicy::Admins`
+    admin_email: String,
+    /// Links to tasks on it are unfurled
+    frontend_url: String,
+    /// Decrypts the tokens of other workspaces of an Enterprise Grid org
+    encryption_key: String,
+    message_encryption: MessageEncryption,
+    /// Background work of the bot, such as the periodic sync, which shutdown waits for
+    bot_tasks: TaskTracker,
 }
 
 impl SlackBot {
     pub fn new(
-        workspace_name: String,
+        workspace: &Workspace,
         app_token: String,
         bot_token: String,
-        db: DatabaseConnection,
-        status_manager: BotStatusManager,
+        state: &AppState,
     ) -> Self {
         Self {
-            workspace_name,
+            workspace_id: workspace.id.clone(),
+            workspace_name: workspace.name.clone(),
+            slack_team_id: workspace.slack_team_id.clone(),
             app_token,
-            bot_token,
-            db,
+            db: state.database.clone(),
             http_client: Client::new(),
-            status_manager,
+            slack: SlackClient::http(bot_token),
+            status_manager: state.bot_status.clone(),
+            board_events: state.board_events.clone(),
+            events: state.events.clone(),
+            tasks: TaskService::from_state(state),
+            cache: state.cache.clone(),
+            maintenance: state.maintenance.clone(),
+            admin_email: state.config.admin_email.clone(),
+            frontend_url: state.config.frontend_url.clone(),
+            encryption_key: state.config.encryption_key.clone(),
+            message_encryption: state.message_encryption.clone(),
+            bot_tasks: state.bot_tasks.clone(),
+        }
+    }
+
+    /// Make Slack Web API calls through `api`, e.g. recorded responses in tests
+    pub fn with_slack_api(mut self, api: Arc<dyn SlackApi>) -> Self {
+        self.slack = SlackClient::new(api);
+        self
+    }
+
+    /// The bot of the workspace that events of Slack team `team_id` belong to. The
+    /// org-wide app of an Enterprise Grid org connects once per workspace and Slack
+    /// spreads the events of every workspace of the org over those connections, so
+    /// events of another team are handled by a copy of this bot for its workspace.
+    /// None when no active workspace is set up for the team.
+    async fn for_team(&self, team_id: Option<&str>) -> Option<Cow<'_, SlackBot>> {
+        let team_id = match (team_id, self.slack_team_id.as_deref()) {
+            (Some(team_id), Some(own)) if team_id != own => team_id,
+            _ => return Some(Cow::Borrowed(self)),
+        };
+
+        let workspace = match WorkspacesRepo::new(self.db.clone())
+            .get_active_by_slack_team_id(team_id)
+            .await
+        {
+            Ok(workspace) => workspace,
+            Err(e) => {
+                warn!("[WS] Dropping event of Slack team {}: {}", team_id, e);
+                return None;
+            }
+        };
+        let tokens = match get_cached_workspace_tokens(
+            &self.cache,
+            &self.db,
+            &workspace.id,
+            &self.encryption_key,
+        )
+        .await
+        {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                error!(
+                    "[WS] Failed to load tokens of {} for an event of its team: {}",
+                    workspace.name, e
+                );
+                return None;
+            }
+        };
+
+        let mut bot = self.clone();
+        bot.workspace_id = workspace.id;
+        bot.workspace_name = workspace.name;
+        bot.slack_team_id = workspace.slack_team_id;
+        bot.slack = SlackClient::http(tokens.bot_token);
+        Some(Cow::Owned(bot))
+    }
+
+    /// Handle an event as the bot of the workspace of its Slack team
+    async fn dispatch_event(&self, team_id: Option<&str>, event: SlackEvent) {
+        if let Some(bot) = self.for_team(team_id).await {
+            bot.handle_event(event).await;
         }
     }
 
@@ -216,34 +642,36 @@ impl SlackBot {
         self.status_manager
             .set_connected(&self.workspace_name)
             .await;
+        self.record_incident(BotIncidentKind::Connected, None).await;
+
+        // Queue the initial sync so it neither blocks the event loop nor gets lost on a crash
+        if let Err(e) = jobs::enqueue(
+            &self.db,
+            Job::InitialSync {
+                workspace_id: self.workspace_id.clone(),
+                slack_member_id: None,
+            },
+        )
+        .await
+        {
+            error!(
+                "Failed to queue initial sync for workspace {}: {}",
+                self.workspace_name, e
+            );
+        }
 
-        // Spawn initial sync in background so it doesn't block the event loop
-        let workspace_name_clone = self.workspace_name.clone();
-        let bot_token_clone = self.bot_token.clone();
-        let db_clone = self.db.clone();
-        let status_manager_clone = self.status_manager.clone();
-
-        tokio::spawn(async move {
-            let syncer = InitialSyncer {
-                workspace_name: workspace_name_clone,
-                bot_token: bot_token_clone,
-                db: db_clone,
-                http_client: Client::new(),
-                status_manager: status_manager_clone,
-            };
-            syncer.perform_initial_sync_for_all_users().await;
-        });
+        // Back into the channels admins picked, e.g. after someone removed the bot
+        let join_bot = self.clone();
+        self.bot_tasks
+            .spawn(async move { join_bot.join_auto_join_channels().await });
 
         // Start periodic sync as a safety net for cases where reaction events are not delivered.
-        let periodic_sync_bot = SlackBot::new(
-            self.workspace_name.clone(),
-            self.app_token.clone(),
-            self.bot_token.clone(),
-            self.db.clone(),
-            self.status_manager.clone(),
-        );
-        tokio::spawn(async move {
-            periodic_sync_bot.start_periodic_tasks_sync().await;
+        let periodic_sync_bot = self.clone();
+        let periodic_sync_token = shutdown_token.clone();
+        self.bot_tasks.spawn(async move {
+            periodic_sync_bot
+                .start_periodic_tasks_sync(periodic_sync_token)
+                .await;
         });
 
         info!(
@@ -251,18 +679,49 @@ impl SlackBot {
             self.workspace_name
         );
 
+        // Events are acknowledged but not persisted during maintenance; they are
+        // handled once it is turned off
+        let mut maintenance = self.maintenance.subscribe();
+        let mut queued_events: VecDeque<(Option<String>, SlackEvent)> = VecDeque::new();
+
         loop {
             tokio::select! {
                 _ = shutdown_token.cancelled() => {
                     info!("[WS] Shutdown signal received, closing WebSocket for {}", self.workspace_name);
+                    if !queued_events.is_empty() {
+                        warn!(
+                            "[WS] Dropping {} event(s) queued during maintenance for {}",
+                            queued_events.len(),
+                            self.workspace_name
+                        );
+                    }
                     let _ = write.send(Message::Close(None)).await;
+                    self.record_incident(BotIncidentKind::Stopped, None).await;
                     break;
                 }
+                Ok(()) = maintenance.changed() => {
+                    if self.maintenance.is_enabled() || queued_events.is_empty() {
+                        continue;
+                    }
+                    info!(
+                        "[WS] Maintenance over, handling {} queued event(s) for {}",
+                        queued_events.len(),
+                        self.workspace_name
+                    );
+                    while let Some((team_id, event)) = queued_events.pop_front() {
+                        self.dispatch_event(team_id.as_deref(), event).await;
+                    }
+                }
                 msg = read.next() => {
                     let msg = match msg {
                         Some(msg) => msg,
                         None => {
                             info!("[WS] WebSocket stream ended for {}", self.workspace_name);
+                            self.record_incident(
+                                BotIncidentKind::Disconnected,
+                                Some("Stream ended".to_string()),
+                            )
+                            .await;
                             break;
                         }
                     };
@@ -293,15 +752,30 @@ impl SlackBot {
 
                                     if envelope.envelope_type == "events_api" {
                                         if let Some(payload) = envelope.payload {
+                                            let team_id = payload.slack_team().map(str::to_string);
                                             if let Some(event) = payload.event {
+                                                if self.maintenance.is_enabled() {
+                                                    info!("[WS] Queueing event during maintenance: type={}", event.event_type);
+                                                    if queued_events.len() >= MAX_QUEUED_EVENTS {
+                                                        queued_events.pop_front();
+                                                    }
+                                                    queued_events.push_back((team_id, event));
+                                                    continue;
+                                                }
                                                 info!("[WS] Dispatching event: type={}", event.event_type);
-                                                self.handle_event(event).await;
+                                                self.dispatch_event(team_id.as_deref(), event).await;
                                             } else {
                                                 warn!("[WS] events_api payload had no event");
                                             }
                                         } else {
                                             warn!("[WS] events_api envelope had no payload");
                                         }
+                                    } else if envelope.envelope_type == "interactive" {
+                                        if let Some(payload) = envelope.payload {
+                                            if let Some(bot) = self.for_team(payload.slack_team()).await {
+                                                bot.handle_interaction(payload).await;
+                                            }
+                                        }
                                     } else {
                                         info!("[WS] Non-event envelope type: {}", envelope.envelope_type);
                                     }
@@ -323,6 +797,11 @@ impl SlackBot {
                                     Some("Connection closed".to_string()),
                                 )
                                 .await;
+                            self.record_incident(
+                                BotIncidentKind::Disconnected,
+                                Some(format!("Connection closed: {:?}", frame)),
+                            )
+                            .await;
                             break;
                         }
                         Err(e) => {
@@ -330,6 +809,8 @@ impl SlackBot {
                             self.status_manager
                                 .set_disconnected(&self.workspace_name, Some(e.to_string()))
                                 .await;
+                            self.record_incident(BotIncidentKind::Error, Some(e.to_string()))
+                                .await;
                             break;
                         }
                         _ => {}
@@ -351,45 +832,812 @@ impl SlackBot {
         Ok(())
     }
 
-    async fn handle_event(&self, event: SlackEvent) {
+    /// Persist a connection incident so bot history survives restarts. Losing the
+    /// connection is published as `BotDisconnected` too.
+    async fn join_auto_join_channels(&self) {
+        let channels = match WorkspaceSettingsRepo::new(self.db.clone())
+            .get_auto_join_channels(&self.workspace_id)
+            .await
+        {
+            Ok(channels) => channels,
+            Err(e) => {
+                error!(
+                    "[WS] Failed to load auto-join channels of {}: {}",
+                    self.workspace_name, e
+                );
+                return;
+            }
+        };
+        for channel in channels {
+            if let Err(e) = self.slack.join_channel(&channel).await {
+                warn!(
+                    "[WS] Failed to join channel {} in {}: {}",
+                    channel, self.workspace_name, e
+                );
+            }
+        }
+    }
+
+    async fn record_incident(&self, kind: BotIncidentKind, message: Option<String>) {
+        if matches!(kind, BotIncidentKind::Disconnected | BotIncidentKind::Error) {
+            self.events.publish(DomainEvent::BotDisconnected {
+                workspace_id: self.workspace_id.clone(),
+                workspace_name: self.workspace_name.clone(),
+                reason: message.clone(),
+            });
+        }
+        let incidents_repo = BotIncidentsRepo::new(self.db.clone());
+        if let Err(e) = incidents_repo
+            .create(&self.workspace_id, kind, message)
+            .await
+        {
+            error!(
+                "Failed to record bot incident for {}: {}",
+                self.workspace_name, e
+            );
+        }
+    }
+
+    /// Shortcuts, modal submissions and buttons: the task template shortcut, the
+    /// "Remind me" message action, the buttons of reminder DMs and of task unfurls
+    async fn handle_interaction(&self, payload: EventPayload) {
+        let summary = payload.summary();
+        if self.maintenance.is_enabled() {
+            info!("[WS] Ignoring interaction during maintenance");
+            self.record_event(summary, Some(skip("Maintenance mode was on")))
+                .await;
+            return;
+        }
+
+        let view_callback = payload
+            .view
+            .as_ref()
+            .and_then(|view| view["callback_id"].as_str());
+        let result = match payload.interaction_type.as_deref() {
+            Some("shortcut")
+                if payload.callback_id.as_deref() == Some(task_templates::SHORTCUT_CALLBACK_ID) =>
+            {
+                self.open_template_modal(&payload).await
+            }
+            Some("view_submission")
+                if view_callback == Some(task_templates::SHORTCUT_CALLBACK_ID) =>
+            {
+                self.create_task_from_template_modal(&payload).await
+            }
+            Some("message_action")
+                if payload.callback_id.as_deref()
+                    == Some(reminders::MESSAGE_ACTION_CALLBACK_ID) =>
+            {
+                self.open_reminder_modal(&payload).await
+            }
+            Some("view_submission")
+                if view_callback == Some(reminders::MESSAGE_ACTION_CALLBACK_ID) =>
+            {
+                self.create_reminder_from_modal(&payload).await
+            }
+            Some("block_actions")
+                if payload
+                    .actions
+                    .first()
+                    .is_some_and(|action| action.action_id == standups::ANSWER_ACTION_ID) =>
+            {
+                self.open_standup_modal(&payload).await
+            }
+            Some("view_submission") if view_callback == Some(standups::MODAL_CALLBACK_ID) => {
+                self.save_standup_answers(&payload).await
+            }
+            Some("block_actions") => match payload
+                .actions
+                .first()
+                .and_then(|action| unfurls::action_status(&action.action_id))
+            {
+                Some(status) => self.handle_unfurl_action(&payload, status).await,
+                None => self.handle_reminder_action(&payload).await,
+            },
+            other => {
+                info!("[WS] Ignoring interaction: type={:?}", other);
+                self.record_event(summary, None).await;
+                return;
+            }
+        };
+        self.record_event(summary, Some(result)).await;
+    }
+
+    /// Keep the outcome of an event in the bot event log, `None` for events the bot
+    /// doesn't handle. Failures are logged too.
+    async fn record_event(
+        &self,
+        event: NewBotEvent,
+        result: Option<Result<()>>,
+    ) -> Option<BotEvent> {
+        let (outcome, error) = match result {
+            None => (BotEventOutcome::Ignored, None),
+            Some(Ok(())) => (BotEventOutcome::Handled, None),
+            Some(Err(e)) => match e.downcast_ref::<Skipped>() {
+                Some(skipped) => {
+                    info!("Skipped {} event: {}", event.event_type, skipped);
+                    (BotEventOutcome::Skipped, Some(skipped.to_string()))
+                }
+                None => {
+                    error!("Failed to handle {} event: {:?}", event.event_type, e);
+                    (BotEventOutcome::Failed, Some(format!("{:#}", e)))
+                }
+            },
+        };
+
+        BotEventsRepo::new(self.db.clone())
+            .create(&self.workspace_id, event, outcome, error)
+            .await
+            .inspect_err(|e| {
+                error!(
+                    "Failed to record bot event for {}: {}",
+                    self.workspace_name, e
+                )
+            })
+            .ok()
+    }
+
+    async fn open_template_modal(&self, payload: &EventPayload) -> Result<()> {
+        let trigger_id = payload
+            .trigger_id
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("No trigger_id on shortcut"))?;
+        let templates = TaskTemplatesRepo::new(self.db.clone())
+            .get_by_workspace(&self.workspace_id)
+            .await?;
+        let locale = match payload.user.as_ref() {
+            Some(user) => self.locale_for(&user.id).await,
+            None => i18n::workspace_locale(&self.db, &self.workspace_id).await,
+        };
+
+        self.slack
+            .open_view(
+                trigger_id,
+                task_templates::template_modal(locale, &templates),
+            )
+            .await
+    }
+
+    async fn create_task_from_template_modal(&self, payload: &EventPayload) -> Result<()> {
+        let user_id = &payload
+            .user
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No user on view submission"))?
+            .id;
+        let (template_id, details) = payload
+            .view
+            .as_ref()
+            .and_then(task_templates::submitted_template)
+            .ok_or_else(|| anyhow::anyhow!("No template selected in submission"))?;
+
+        let Some(person) = self.linked_person(user_id).await else {
+            info!(
+                "Slack member {} is not linked to workspace {} - skipping task from template",
+                user_id, self.workspace_name
+            );
+            return Ok(());
+        };
+
+        let template = TaskTemplatesRepo::new(self.db.clone())
+            .get(&self.workspace_id, &template_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Template {} not found", template_id))?;
+        task_templates::create_task(
+            &self.db,
+            &self.message_encryption,
+            &self.tasks,
+            &template,
+            person,
+            &details,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// The person of a Slack member, if they are linked to this workspace
+    async fn linked_person(&self, slack_id: &str) -> Option<Person> {
+        let person = PersonsRepo::new(self.db.clone())
+            .get_by_external_id(slack_id.to_string())
+            .await
+            .ok()?;
+        WorkspaceLinksRepo::new(self.db.clone())
+            .get_by_person_and_workspace(person.id.clone(), self.workspace_id.clone())
+            .await
+            .is_ok_and(|link| link.is_linked)
+            .then_some(person)
+    }
+
+    /// Locale of what the bot says to a Slack member: their own when linked, else
+    /// the workspace's
+    async fn locale_for(&self, slack_id: &str) -> Locale {
+        match self.linked_person(slack_id).await {
+            Some(person) => {
+                i18n::person_locale(&self.db, &person.id, Some(self.workspace_id.as_str())).await
+            }
+            None => i18n::workspace_locale(&self.db, &self.workspace_id).await,
+        }
+    }
+
+    async fn open_reminder_modal(&self, payload: &EventPayload) -> Result<()> {
+        let (Some(trigger_id), Some(user), Some(channel), Some(message)) = (
+            payload.trigger_id.as_deref(),
+            payload.user.as_ref(),
+            payload.channel.as_ref(),
+            payload.message.as_ref(),
+        ) else {
+            return Err(anyhow::anyhow!("Incomplete message action"));
+        };
+
+        let task = match MessagesRepo::new(self.db.clone(), self.message_encryption.clone())
+            .get_message_by_external_id(format!("slack:{}:{}", channel.id, message.ts))
+            .await
+        {
+            Ok(message) => TasksRepo::new(self.db.clone())
+                .get_task_by_message_id(message.id)
+                .await
+                .ok(),
+            Err(_) => None,
+        };
+        let locale = self.locale_for(&user.id).await;
+        let Some(task) = task else {
+            self.post_ephemeral(
+                &channel.id,
+                &user.id,
+                &i18n::text(locale, "reminders.not_a_task", &[]),
+            )
+            .await;
+            return Ok(());
+        };
+
+        self.slack
+            .open_view(
+                trigger_id,
+                reminders::reminder_modal(locale, &task.id, &channel.id),
+            )
+            .await
+    }
+
+    async fn create_reminder_from_modal(&self, payload: &EventPayload) -> Result<()> {
+        let user_id = &payload
+            .user
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No user on view submission"))?
+            .id;
+        let (task_id, channel, delay) = payload
+            .view
+            .as_ref()
+            .and_then(reminders::submitted_reminder)
+            .ok_or_else(|| anyhow::anyhow!("No delay in reminder submission"))?;
+
+        let locale = self.locale_for(user_id).await;
+        let Some(person) = self.linked_person(user_id).await else {
+            self.post_ephemeral(
+                &channel,
+                user_id,
+                &i18n::text(locale, "reminders.link_account", &[]),
+            )
+            .await;
+            return Ok(());
+        };
+        let Some(at) = reminders::remind_at(&person, &delay) else {
+            self.post_ephemeral(
+                &channel,
+                user_id,
+                &i18n::text(locale, "reminders.unreadable", &[("delay", delay.trim())]),
+            )
+            .await;
+            return Ok(());
+        };
+
+        RemindersRepo::new(self.db.clone())
+            .create(&task_id, &person.id, at)
+            .await?;
+        self.post_ephemeral(
+            &channel,
+            user_id,
+            &i18n::text(
+                locale,
+                "reminders.set",
+                &[("date", &reminders::slack_date(at))],
+            ),
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Answer button of a standup DM, opening the questions with earlier answers of
+    /// the day
+    async fn open_standup_modal(&self, payload: &EventPayload) -> Result<()> {
+        let (Some(trigger_id), Some(user), Some(action)) = (
+            payload.trigger_id.as_deref(),
+            payload.user.as_ref(),
+            payload.actions.first(),
+        ) else {
+            return Err(anyhow::anyhow!("Incomplete standup action"));
+        };
+        let day = action
+            .value
+            .as_deref()
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| anyhow::anyhow!("No day on standup action"))?;
+
+        let locale = self.locale_for(&user.id).await;
+        let entry = match self.linked_person(&user.id).await {
+            Some(person) => {
+                StandupEntriesRepo::new(self.db.clone())
+                    .get(&self.workspace_id, &person.id, day)
+                    .await?
+            }
+            None => None,
+        };
+        self.slack
+            .open_view(
+                trigger_id,
+                standups::standup_modal(locale, day, entry.as_ref()),
+            )
+            .await
+    }
+
+    async fn save_standup_answers(&self, payload: &EventPayload) -> Result<()> {
+        let user_id = &payload
+            .user
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No user on view submission"))?
+            .id;
+        let answers = payload
+            .view
+            .as_ref()
+            .and_then(standups::submitted_standup)
+            .ok_or_else(|| anyhow::anyhow!("No answers in standup submission"))?;
+        let person = self
+            .linked_person(user_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("{} is not a linked member", user_id))?;
+
+        StandupEntriesRepo::new(self.db.clone())
+            .save(
+                &self.workspace_id,
+                &person.id,
+                answers.day,
+                answers.yesterday,
+                answers.today,
+                answers.blockers,
+            )
+            .await?;
+        info!(
+            "[WS] Saved the standup of {} for {}",
+            person.email, answers.day
+        );
+        Ok(())
+    }
+
+    /// Done and snooze on a reminder DM; the DM then loses its buttons
+    async fn handle_reminder_action(&self, payload: &EventPayload) -> Result<()> {
+        let Some(action) = payload.actions.first() else {
+            return Ok(());
+        };
+        let (reminder_id, delay) = match action.action_id.as_str() {
+            reminders::DONE_ACTION_ID => (action.value.clone().unwrap_or_default(), None),
+            reminders::SNOOZE_ACTION_ID => {
+                let value = action
+                    .selected_option
+                    .as_ref()
+                    .and_then(|option| option["value"].as_str())
+                    .unwrap_or_default();
+                let (reminder_id, delay) = value.split_once('|').unwrap_or((value, ""));
+                (reminder_id.to_string(), Some(delay.to_string()))
+            }
+            other => {
+                info!("[WS] Ignoring block action: {}", other);
+                return Ok(());
+            }
+        };
+
+        let user_id = &payload
+            .user
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No user on block action"))?
+            .id;
+        let person = PersonsRepo::new(self.db.clone())
+            .get_by_external_id(user_id.clone())
+            .await
+            .map_err(|_| anyhow::anyhow!("No person found for Slack member {}", user_id))?;
+        let reminders_repo = RemindersRepo::new(self.db.clone());
+        let reminder = reminders_repo
+            .get_by_id(&reminder_id)
+            .await?
+            .filter(|reminder| reminder.person_id == person.id)
+            .ok_or_else(|| anyhow::anyhow!("Reminder {} not found", reminder_id))?;
+
+        let locale =
+            i18n::person_locale(&self.db, &person.id, Some(self.workspace_id.as_str())).await;
+        let outcome = match delay {
+            None => {
+                reminders_repo.mark_done(reminder).await?;
+                i18n::text(locale, "reminders.handled_done", &[])
+            }
+            Some(delay) => {
+                let at = reminders::remind_at(&person, &delay)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown snooze delay {}", delay))?;
+                reminders_repo.snooze(reminder, at).await?;
+                i18n::text(
+                    locale,
+                    "reminders.snoozed",
+                    &[("date", &reminders::slack_date(at))],
+                )
+            }
+        };
+
+        if let (Some(channel), Some(message)) = (payload.channel.as_ref(), payload.message.as_ref())
+        {
+            let text = match message.text.as_deref() {
+                Some(original) => format!("{}\n{}", original, outcome),
+                None => outcome,
+            };
+            self.slack
+                .update_message(
+                    &channel.id,
+                    &message.ts,
+                    &text,
+                    reminders::handled_blocks(&text),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Unfurl links to tasks of this workspace with their status, assignee and
+    /// buttons to move them
+    async fn handle_link_shared(&self, event: SlackEvent) -> Result<()> {
+        let (Some(channel), Some(ts)) = (event.channel, event.message_ts) else {
+            return Ok(());
+        };
+        if event.source.as_deref() == Some("composer") {
+            return skip("Links are unfurled once the message is posted");
+        }
+
+        let mut previews = serde_json::Map::new();
+        for link in event.links {
+            let Some(key) = unfurls::task_key(&link.url, &self.frontend_url) else {
+                continue;
+            };
+            let Some(task) = self.task_by_key(&key).await? else {
+                continue;
+            };
+            let preview = self.task_unfurl(&task, &link.url).await;
+            previews.insert(link.url, preview);
+        }
+        if previews.is_empty() {
+            return skip("No link to a task of this workspace");
+        }
+
+        self.slack
+            .unfurl(&channel, &ts, serde_json::Value::Object(previews))
+            .await
+    }
+
+    /// Status buttons of a task unfurl. They are for whoever's reactions would
+    /// count on the task; like a move from the API, the bot then reacts with the
+    /// status's first emoji so the status derived from reactions agrees.
+    async fn handle_unfurl_action(&self, payload: &EventPayload, status: TaskStatus) -> Result<()> {
+        let task_id = payload
+            .actions
+            .first()
+            .and_then(|action| action.value.clone())
+            .unwrap_or_default();
+        let user_id = &payload
+            .user
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No user on block action"))?
+            .id;
+        let channel = payload.channel.as_ref().map(|channel| channel.id.as_str());
+
+        let task = match TasksRepo::new(self.db.clone()).get(task_id.clone()).await {
+            Ok(task) if task.workspace_id.as_deref() == Some(self.workspace_id.as_str()) => task,
+            Ok(_) | Err(DbErr::RecordNotFound(_)) => {
+                return skip(format!("Task {} is not in this workspace", task_id))
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let policy = self.get_reaction_policy().await;
+        if !self
+            .task_reactors(policy, &task.assigned_to)
+            .await?
+            .counts(user_id)
+        {
+            if let Some(channel) = channel {
+                let locale = self.locale_for(user_id).await;
+                self.post_ephemeral(
+                    channel,
+                    user_id,
+                    &i18n::text(locale, "bot.reaction_policy", &[]),
+                )
+                .await;
+            }
+            return skip("The member may not set statuses under the workspace's reaction policy");
+        }
+
+        let task = if task.status == status {
+            task
+        } else {
+            match self.tasks.transition(task, status).await? {
+                Outcome::Done(task) => {
+                    self.react_with_status(&task).await;
+                    task
+                }
+                Outcome::Blocked(wip) => {
+                    if let Some(channel) = channel {
+                        let locale = self.locale_for(user_id).await;
+                        self.post_ephemeral(channel, user_id, &wip.message_in(locale))
+                            .await;
+                    }
+                    return skip(wip.message());
+                }
+            }
+        };
+
+        // Show the new status in place of the old preview
+        let Some(container) = payload.container.as_ref() else {
+            return Ok(());
+        };
+        let (Some(channel), Some(ts), Some(url)) = (
+            container.channel_id.as_deref(),
+            container.message_ts.as_deref(),
+            container.app_unfurl_url.as_deref(),
+        ) else {
+            return Ok(());
+        };
+        let preview = self.task_unfurl(&task, url).await;
+        self.slack
+            .unfurl(channel, ts, serde_json::json!({ url: preview }))
+            .await
+    }
+
+    /// A task of this workspace by its key, e.g. `ACME-42`
+    async fn task_by_key(&self, key: &str) -> Result<Option<Task>> {
+        Ok(TasksRepo::new(self.db.clone())
+            .get_by_key(key)
+            .await?
+            .filter(|task| task.workspace_id.as_deref() == Some(self.workspace_id.as_str())))
+    }
+
+    /// Unfurls are seen by the whole channel, so they follow the workspace's locale
+    async fn task_unfurl(&self, task: &Task, url: &str) -> serde_json::Value {
+        let assignee = PersonsRepo::new(self.db.clone())
+            .get_by_id(task.assigned_to.clone())
+            .await
+            .ok();
+        let locale = i18n::workspace_locale(&self.db, &self.workspace_id).await;
+        unfurls::task_unfurl(locale, task, assignee.as_ref(), url)
+    }
+
+    /// React to the task's Slack message with the first emoji of its status
+    async fn react_with_status(&self, task: &Task) {
+        let Ok(message) = MessagesRepo::new(self.db.clone(), self.message_encryption.clone())
+            .get_by_id(task.message_id.clone())
+            .await
+        else {
+            return;
+        };
+        let emoji_mappings = self.get_emoji_mappings().await;
+        let Some(emoji) = emoji_mappings.emojis(&task.status).first() else {
+            return;
+        };
+        if !message.is_from_slack() {
+            return;
+        }
+        if let Err(e) = self
+            .slack
+            .add_reaction(&message.channel, &message.timestamp, emoji)
+            .await
+        {
+            warn!(
+                "Could not mark task {} {:?} in Slack: {}",
+                task.id, task.status, e
+            );
+        }
+    }
+
+    /// Handle an event and log what came of it, returning the logged event
+    async fn handle_event(&self, event: SlackEvent) -> Option<BotEvent> {
         info!(
             "Slack event received: type={} subtype={:?}",
             event.event_type, event.subtype
         );
-        match event.event_type.as_str() {
-            "reaction_added" => {
-                let res = self.handle_reaction_added(event).await;
-                if res.is_err() {
-                    error!("Failed to handle event: {:?}", res.err());
-                }
+        let summary = event.summary();
+        let result = match event.event_type.as_str() {
+            "reaction_added" => Some(self.handle_reaction_added(event).await),
+            "reaction_removed" => Some(self.handle_reaction_removed(event).await),
+            "message" => Some(self.handle_message_event(event).await),
+            "app_mention" => Some(self.handle_new_message(event, true).await),
+            "user_change" | "team_leave" => Some(self.handle_member_left(event).await),
+            "link_shared" => Some(self.handle_link_shared(event).await),
+            _ => None,
+        };
+        self.record_event(summary, result).await
+    }
+
+    /// Run a logged event through the handlers again, with the message and its
+    /// reactions as they are now, e.g. after fixing emoji mappings or linking the
+    /// author. The replay is logged as a new event, which is returned.
+    pub async fn replay(&self, logged: &BotEvent) -> Result<BotEvent> {
+        let (Some(channel), Some(ts)) = (logged.channel.clone(), logged.ts.clone()) else {
+            return Err(anyhow::anyhow!("Bot event {} can't be replayed", logged.id));
+        };
+        let mut event = SlackEvent {
+            event_type: logged.event_type.clone(),
+            subtype: logged.subtype.clone(),
+            user: logged.user_id.clone().map(EventUser::Id),
+            reaction: logged.reaction.clone(),
+            item: None,
+            channel: None,
+            ts: None,
+            message: None,
+            text: None,
+            thread_ts: None,
+            message_ts: None,
+            links: vec![],
+            source: None,
+        };
+        match (logged.event_type.as_str(), logged.subtype.as_deref()) {
+            ("reaction_added" | "reaction_removed", _) => {
+                event.item = Some(SlackEventItem {
+                    item_type: "message".to_string(),
+                    channel,
+                    ts,
+                });
             }
-            "reaction_removed" => {
-                let res = self.handle_reaction_removed(event).await;
-                if res.is_err() {
-                    error!("Failed to handle event: {:?}", res.err());
-                }
+            ("message", Some("message_changed")) => {
+                event.channel = Some(channel);
+                event.message = Some(SlackEventMessage {
+                    user: None,
+                    text: None,
+                    ts: Some(ts),
+                    reactions: None,
+                });
             }
-            "message" => {
-                let res = self.handle_message_event(event).await;
-                if res.is_err() {
-                    error!("Failed to handle message event: {:?}", res.err());
-                }
+            // New messages and mentions need their text, which isn't logged
+            _ => {
+                let message = self
+                    .slack
+                    .fetch_message(&channel, &ts)
+                    .await
+                    .map_err(|e| e.context("Failed to fetch message"))?;
+                event.user = Some(EventUser::Id(message.user));
+                event.text = Some(message.text);
+                event.thread_ts = message.thread_timestamp;
+                event.channel = Some(channel);
+                event.ts = Some(ts);
             }
-            _ => {}
         }
+
+        info!(
+            "Replaying bot event {} ({}) in workspace {}",
+            logged.id, logged.event_type, self.workspace_name
+        );
+        self.handle_event(event)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Failed to log the replayed event"))
+    }
+
+    /// Deprovision a member who left the team or whose account was deactivated.
+    /// Other profile changes are ignored.
+    async fn handle_member_left(&self, event: SlackEvent) -> Result<()> {
+        let Some(user) = event.user else {
+            return Ok(());
+        };
+        let reason = match event.event_type.as_str() {
+            "team_leave" => "left the team",
+            _ if user.is_deleted() => "deactivated",
+            _ => return Ok(()),
+        };
+
+        let workspace = WorkspacesRepo::new(self.db.clone())
+            .get(&self.workspace_id)
+            .await?;
+        deprovision::deprovision_member(
+            &self.db,
+            &self.message_encryption,
+            &self.board_events,
+            &workspace,
+            user.id(),
+            reason,
+        )
+        .await?;
+        Ok(())
     }
 
     async fn get_emoji_mappings(&self) -> EmojiMappings {
-        let settings_repo = WorkspaceSettingsRepo::new(self.db.clone());
-        settings_repo
-            .get_emoji_mappings(&self.workspace_name)
+        cached_emoji_mappings(&self.cache, &self.db, &self.workspace_id).await
+    }
+
+    async fn get_status_resolution(&self) -> StatusResolution {
+        cached_status_resolution(&self.cache, &self.db, &self.workspace_id).await
+    }
+
+    async fn get_reaction_policy(&self) -> ReactionPolicy {
+        cached_reaction_policy(&self.cache, &self.db, &self.workspace_id).await
+    }
+
+    async fn get_private_channel_policy(&self) -> PrivateChannelPolicy {
+        cached_private_channel_policy(&self.cache, &self.db, &self.workspace_id).await
+    }
+
+    /// Whether a channel is private, a group DM or a DM. Taken as private when Slack
+    /// cannot tell, so a restrictive policy never lets one through.
+    async fn is_private_channel(&self, channel: &str) -> bool {
+        let key = cache_keys::channel_privacy(&self.workspace_id, channel);
+        if let Some(private) = self.cache.get(&key).await {
+            return private;
+        }
+
+        match self.slack.channel_is_private(channel).await {
+            Ok(private) => {
+                self.cache.set(&key, &private).await;
+                private
+            }
+            Err(e) => {
+                warn!("Failed to look up channel {}: {}", channel, e);
+                true
+            }
+        }
+    }
+
+    /// Whether a Slack member is linked to the workspace as a viewer
+    async fn is_viewer(&self, slack_member_id: &str) -> bool {
+        WorkspaceLinksRepo::new(self.db.clone())
+            .get_by_slack_member_id_and_workspace(
+                slack_member_id.to_string(),
+                self.workspace_id.clone(),
+            )
             .await
-            .unwrap_or_else(|_| EmojiMappings::default_mappings())
+            .is_ok_and(|link| link.role == WorkspaceRole::Viewer)
+    }
+
+    /// Whose reactions count toward the status of a task assigned to `assignee_id`
+    async fn task_reactors(&self, policy: ReactionPolicy, assignee_id: &str) -> Result<Reactors> {
+        if policy == ReactionPolicy::Anyone {
+            return Ok(Reactors::Anyone);
+        }
+        let assignee = PersonsRepo::new(self.db.clone())
+            .get_by_id(assignee_id.to_string())
+            .await?;
+        Ok(counted_reactors(
+            &self.db,
+            &self.admin_email,
+            &self.workspace_id,
+            policy,
+            &assignee,
+        )
+        .await?)
+    }
+
+    /// Keyword triggers of the workspace, cached as every new message is checked
+    async fn get_keyword_triggers(&self) -> Vec<KeywordTrigger> {
+        let key = cache_keys::keyword_triggers(&self.workspace_id);
+        if let Some(triggers) = self.cache.get(&key).await {
+            return triggers;
+        }
+
+        match WorkspaceSettingsRepo::new(self.db.clone())
+            .get_keyword_triggers(&self.workspace_id)
+            .await
+        {
+            Ok(triggers) => {
+                self.cache.set(&key, &triggers).await;
+                triggers
+            }
+            Err(e) => {
+                warn!("Failed to load keyword triggers: {}", e);
+                vec![]
+            }
+        }
     }
 
     async fn handle_reaction_added(&self, event: SlackEvent) -> Result<()> {
         let reactor_slack_id = match &event.user {
-            Some(u) => u.clone(),
+            Some(u) => u.id().to_string(),
             None => return Ok(()),
         };
 
@@ -402,8 +1650,13 @@ impl SlackBot {
         let emoji_mappings = self.get_emoji_mappings().await;
 
         if emoji_to_status(reaction, &emoji_mappings).is_none() {
-            info!("Ignoring non-task emoji: {}", reaction);
-            return Ok(());
+            return skip(format!(":{}: is not mapped to a status", reaction));
+        }
+        if self.is_viewer(&reactor_slack_id).await {
+            return skip(format!(
+                "{} only views the workspace and takes no tasks",
+                reactor_slack_id
+            ));
         }
 
         let item = match &event.item {
@@ -416,21 +1669,19 @@ impl SlackBot {
             reaction, item.ts, item.channel
         );
 
-        match self.fetch_message(&item.channel, &item.ts).await {
-            Ok(message) => {
-                self.create_or_update_task(
-                    message,
-                    &item.channel,
-                    &item.ts,
-                    Some(&reactor_slack_id),
-                    Some(reaction),
-                )
-                .await?;
-            }
-            Err(e) => error!("Failed to fetch message: {}", e),
-        }
-
-        Ok(())
+        let message = self
+            .slack
+            .fetch_message(&item.channel, &item.ts)
+            .await
+            .map_err(|e| e.context("Failed to fetch message"))?;
+        self.create_or_update_task(
+            message,
+            &item.channel,
+            &item.ts,
+            Some(&reactor_slack_id),
+            Some(reaction),
+        )
+        .await
     }
 
     async fn handle_reaction_removed(&self, event: SlackEvent) -> Result<()> {
@@ -441,7 +1692,7 @@ impl SlackBot {
 
         let emoji_mappings = self.get_emoji_mappings().await;
         if emoji_to_status(reaction, &emoji_mappings).is_none() {
-            return Ok(());
+            return skip(format!(":{}: is not mapped to a status", reaction));
         }
 
         let item = match &event.item {
@@ -449,22 +1700,25 @@ impl SlackBot {
             None => return Ok(()),
         };
 
-        match self.fetch_message(&item.channel, &item.ts).await {
-            Ok(message) => {
-                // Recompute status after removal, but don't reassign ownership on a remove event.
-                self.create_or_update_task(message, &item.channel, &item.ts, None, None)
-                    .await?;
-            }
-            Err(e) => error!("Failed to fetch message: {}", e),
-        }
-
-        Ok(())
+        let message = self
+            .slack
+            .fetch_message(&item.channel, &item.ts)
+            .await
+            .map_err(|e| e.context("Failed to fetch message"))?;
+        // Recompute status after removal, but don't reassign ownership on a remove event.
+        self.create_or_update_task(message, &item.channel, &item.ts, None, None)
+            .await
     }
 
     async fn handle_message_event(&self, event: SlackEvent) -> Result<()> {
-        // Some workspaces deliver reaction updates as message_changed events.
-        if event.subtype.as_deref() != Some("message_changed") {
-            return Ok(());
+        match event.subtype.as_deref() {
+            // New messages, including thread replies also sent to the channel
+            None | Some("thread_broadcast") => return self.handle_new_message(event, false).await,
+            // Some workspaces deliver reaction updates as message_changed events.
+            Some("message_changed") => {}
+            Some(subtype) => {
+                return skip(format!("Messages of subtype {} are not tracked", subtype))
+            }
         }
 
         let channel = match event.channel {
@@ -482,58 +1736,136 @@ impl SlackBot {
             None => return Ok(()),
         };
 
-        // Best-effort owner inference from current reaction users.
-        let inferred_reactor = message
-            .reactions
-            .as_ref()
-            .and_then(|reactions| reactions.iter().find_map(|r| r.users.first().cloned()));
-
-        match self.fetch_message(&channel, &message_ts).await {
-            Ok(slack_message) => {
-                self.create_or_update_task(
-                    slack_message,
-                    &channel,
-                    &message_ts,
-                    inferred_reactor.as_deref(),
-                    None,
-                )
-                .await?;
+        // Best-effort owner inference from current reaction users.
+        let inferred_reactor = message
+            .reactions
+            .as_ref()
+            .and_then(|reactions| reactions.iter().find_map(|r| r.users.first().cloned()));
+
+        let slack_message = self
+            .slack
+            .fetch_message(&channel, &message_ts)
+            .await
+            .map_err(|e| e.context("Failed to fetch message from message_changed event"))?;
+        self.create_or_update_task(
+            slack_message,
+            &channel,
+            &message_ts,
+            inferred_reactor.as_deref(),
+            None,
+        )
+        .await
+    }
+
+    /// Create a task from a new message that fires one of the workspace's keyword
+    /// triggers. The bot then reacts with the first in-progress emoji, so the task
+    /// follows reactions like any other. A triggered message that mentions keys of
+    /// existing tasks, such as `ACME-42`, gets their status instead of a new task.
+    async fn handle_new_message(&self, event: SlackEvent, mentioned: bool) -> Result<()> {
+        let user = event.user.map(|user| user.id().to_string());
+        let (Some(channel), Some(user), Some(ts)) = (event.channel, user, event.ts) else {
+            return Ok(());
+        };
+        let text = event.text.unwrap_or_default();
+
+        let triggers = self.get_keyword_triggers().await;
+        if !triggers
+            .iter()
+            .any(|trigger| trigger.matches(&text, mentioned))
+        {
+            return skip("No keyword trigger matched");
+        }
+
+        let referenced = self
+            .describe_task_keys(&text, self.locale_for(&user).await)
+            .await?;
+        if !referenced.is_empty() {
+            self.post_ephemeral(&channel, &user, &referenced.join("\n"))
+                .await;
+            return Ok(());
+        }
+
+        let emoji_mappings = self.get_emoji_mappings().await;
+        let Some(emoji) = emoji_mappings.in_progress.first().cloned() else {
+            return skip("A keyword trigger matched, but no emoji is mapped to In Progress");
+        };
+        info!(
+            "Message {}:{} fired a keyword trigger in workspace {}",
+            channel, ts, self.workspace_name
+        );
+
+        self.create_or_update_task(
+            SlackMessage {
+                text,
+                user,
+                ts: ts.clone(),
+                thread_timestamp: event.thread_ts,
+            },
+            &channel,
+            &ts,
+            None,
+            Some(&emoji),
+        )
+        .await?;
+
+        // Only mark messages a task was made from, not ignored channels or outsiders
+        let Ok(message) = MessagesRepo::new(self.db.clone(), self.message_encryption.clone())
+            .get_message_by_external_id(format!("slack:{}:{}", channel, ts))
+            .await
+        else {
+            return Ok(());
+        };
+        if TasksRepo::new(self.db.clone())
+            .get_task_by_message_id(message.id)
+            .await
+            .is_ok()
+        {
+            if let Err(e) = self.slack.add_reaction(&channel, &ts, &emoji).await {
+                warn!(
+                    "Failed to react to triggered message {}:{} ({}). Its task falls back to blank on the next sync.",
+                    channel, ts, e
+                );
             }
-            Err(e) => error!("Failed to fetch message from message_changed event: {}", e),
         }
 
         Ok(())
     }
 
-    async fn fetch_message(&self, channel: &str, timestamp: &str) -> Result<SlackMessage> {
-        let response = self
-            .http_client
-            .get("https://slack.com/api/conversations.history")
-            .header("Authorization", format!("Bearer {}", self.bot_token))
-            .query(&[
-                ("channel", channel),
-                ("latest", timestamp),
-                ("inclusive", "true"),
-                ("limit", "1"),
-            ])
-            .send()
-            .await?;
-
-        let status = response.status();
-        let response_text = response.text().await?;
-
-        info!("Slack API Response (status {}): {}", status, response_text);
-
-        let response_json: MessageResponse = serde_json::from_str(&response_text).map_err(|e| {
-            error!("Failed to parse Slack response: {}", e);
-            error!("Raw response text: {}", response_text);
-            anyhow::anyhow!("Failed to parse Slack response: {}", e)
-        })?;
+    /// A line per task of this workspace whose key the text mentions
+    async fn describe_task_keys(&self, text: &str, locale: Locale) -> Result<Vec<String>> {
+        let tasks_repo = TasksRepo::new(self.db.clone());
+        let messages_repo = MessagesRepo::new(self.db.clone(), self.message_encryption.clone());
 
-        response_json
-            .messages
-            .and_then(|mut m| m.pop())
-            .ok_or_else(|| anyhow::anyhow!("Message not found"))
+        let mut lines = Vec::new();
+        for key in task_keys::find_keys(text) {
+            let Some(task) = tasks_repo
+                .get_by_key(&key)
+                .await?
+                .filter(|task| task.workspace_id.as_deref() == Some(self.workspace_id.as_str()))
+            else {
+                continue;
+            };
+            let message = messages_repo.get_by_id(task.message_id.clone()).await?;
+            let link = if message.is_from_slack() {
+                format!(
+                    " <{}|{}>",
+                    message.slack_link(),
+                    i18n::text(locale, "bot.message", &[])
+                )
+            } else {
+                String::new()
+            };
+            lines.push(i18n::text(
+                locale,
+                "bot.task_status",
+                &[
+                    ("key", &key),
+                    ("status", &i18n::status(locale, &task.status)),
+                    ("link", &link),
+                ],
+            ));
+        }
+        Ok(lines)
     }
 
     async fn create_or_update_task(
@@ -545,10 +1877,37 @@ impl SlackBot {
         trigger_reaction: Option<&str>,
     ) -> Result<()> {
         let persons_repo = PersonsRepo::new(self.db.clone());
-        let messages_repo = MessagesRepo::new(self.db.clone());
+        let messages_repo = MessagesRepo::new(self.db.clone(), self.message_encryption.clone());
         let tasks_repo = TasksRepo::new(self.db.clone());
         let workspace_links_repo = WorkspaceLinksRepo::new(self.db.clone());
 
+        let rule = ChannelRulesRepo::new(self.db.clone())
+            .get_by_channel(&self.workspace_id, channel)
+            .await?;
+        if rule.as_ref().is_some_and(|rule| rule.ignored) {
+            return skip(format!("Channel {} is ignored by a channel rule", channel));
+        }
+
+        let hide_channel = match self.get_private_channel_policy().await {
+            PrivateChannelPolicy::Allow => false,
+            policy => {
+                let private = self.is_private_channel(channel).await;
+                if private && policy == PrivateChannelPolicy::Forbid {
+                    return skip(format!(
+                        "Channel {} is private and the workspace forbids tasks from those",
+                        channel
+                    ));
+                }
+                private
+            }
+        };
+
+        // Whoever moved the task hears about a hard WIP limit: the reactor, or the author
+        // for keyword triggers
+        let actor_slack_id = reactor_slack_id
+            .map(str::to_string)
+            .unwrap_or_else(|| slack_message.user.clone());
+
         // Get assignee (person who wrote the message)
         let assignee = match persons_repo
             .get_by_external_id(slack_message.user.clone())
@@ -556,11 +1915,10 @@ impl SlackBot {
         {
             Ok(p) => p,
             Err(_) => {
-                info!(
-                    "No person found for Slack member {} - skipping task creation",
+                return skip(format!(
+                    "The message author {} has not signed in to Slacker",
                     slack_message.user
-                );
-                return Ok(());
+                ));
             }
         };
 
@@ -575,7 +1933,7 @@ impl SlackBot {
 
         // Check if assignee is linked to this workspace
         match workspace_links_repo
-            .get_by_person_and_workspace(assignee.id.clone(), self.workspace_name.clone())
+            .get_by_person_and_workspace(assignee.id.clone(), self.workspace_id.clone())
             .await
         {
             Ok(link) if link.is_linked => {
@@ -584,19 +1942,11 @@ impl SlackBot {
                     assignee.email, self.workspace_name
                 );
             }
-            Ok(_) => {
-                info!(
-                    "User {} is not linked to workspace {} - skipping task creation",
-                    assignee.email, self.workspace_name
-                );
-                return Ok(());
-            }
-            Err(_) => {
-                info!(
-                    "User {} has no link to workspace {} - skipping task creation",
-                    assignee.email, self.workspace_name
-                );
-                return Ok(());
+            _ => {
+                return skip(format!(
+                    "The message author {} is not linked to the workspace",
+                    assignee.email
+                ));
             }
         }
 
@@ -619,8 +1969,14 @@ impl SlackBot {
                         channel.to_string(),
                         message_timestamp.to_string(),
                         &assignee,
+                        &self.workspace_id,
                     )
                     .await?;
+                if hide_channel || self.slack_team_id.is_some() {
+                    messages_repo
+                        .set_slack_origin(&created.id, self.slack_team_id.as_deref(), hide_channel)
+                        .await?;
+                }
                 Some(created)
             }
             _ => {
@@ -635,25 +1991,39 @@ impl SlackBot {
         let message = message.unwrap();
         let task_message = tasks_repo.get_task_by_message_id(message.id.clone()).await;
 
-        let (reactions, reactions_fetch_failed) = match self
-            .fetch_message_reactions(channel, message_timestamp)
-            .await
-        {
-            Ok(r) => (r, false),
-            Err(e) => {
-                warn!(
-                    "Failed to fetch reactions for {}:{} ({}). Falling back to event reaction.",
-                    channel, message_timestamp, e
-                );
-                (vec![], true)
+        let (mut reactions, reactions_fetch_failed) =
+            match self.slack.fetch_reactions(channel, message_timestamp).await {
+                Ok(r) => (r, false),
+                Err(e) => {
+                    warn!(
+                        "Failed to fetch reactions for {}:{} ({}). Falling back to event reaction.",
+                        channel, message_timestamp, e
+                    );
+                    (vec![], true)
+                }
+            };
+
+        // The reaction that triggered this event is the latest one, wherever Slack lists it
+        if let Some(trigger) = trigger_reaction {
+            if let Some(index) = reactions.iter().position(|r| r.name == *trigger) {
+                let reaction = reactions.remove(index);
+                reactions.push(reaction);
             }
-        };
+        }
 
         // Get emoji mappings for this workspace
         let emoji_mappings = self.get_emoji_mappings().await;
-        let status_set = map_reactions_to_status(&reactions, &emoji_mappings);
-        let mut status = eval_status_from_reactions(status_set);
-        if status == TaskStatus::Blank {
+        let status_owner = match &task_message {
+            Ok(task) => task.assigned_to.as_str(),
+            Err(_) => assignee.id.as_str(),
+        };
+        let reactors = self
+            .task_reactors(self.get_reaction_policy().await, status_owner)
+            .await?;
+        let status_reactions = map_reactions_to_status(&reactions, &emoji_mappings, &reactors);
+        let mut status = resolve_status(self.get_status_resolution().await, &status_reactions);
+        let reactor_counts = reactor_slack_id.is_none_or(|reactor| reactors.counts(reactor));
+        if status == TaskStatus::Blank && reactor_counts {
             if let Some(reaction_name) = trigger_reaction {
                 if let Some(fallback_status) = emoji_to_status(reaction_name, &emoji_mappings) {
                     status = fallback_status;
@@ -677,40 +2047,69 @@ impl SlackBot {
                     task.id, task.status, status
                 );
                 if !(reactions_fetch_failed && trigger_reaction.is_none()) {
-                    tasks_repo
-                        .change_status(task.id.clone(), status.clone())
-                        .await?;
-                    info!("[TASK] Updated task {} status to {:?}", task.id, status);
+                    if let Outcome::Blocked(wip) =
+                        self.tasks.transition(task.clone(), status).await?
+                    {
+                        let locale = self.locale_for(&actor_slack_id).await;
+                        self.post_ephemeral(channel, &actor_slack_id, &wip.message_in(locale))
+                            .await;
+                    }
                 } else {
                     info!("[TASK] Skipped status update (reactions fetch failed with no trigger)");
                 }
 
                 // Keep ownership aligned with current reaction state for tab filtering.
                 if task.assigned_by != effective_assigner_id {
-                    tasks_repo
+                    let task = tasks_repo
                         .change_assigned_by(task.id.clone(), effective_assigner_id.clone())
                         .await?;
                     info!(
                         "[TASK] Updated task {} assigned_by to {:?}",
                         task.id, effective_assigner_id
                     );
+                    self.tasks.publish(BoardChange::Updated, task).await;
                 }
             }
             Err(DbErr::RecordNotFound(e)) => {
                 info!("Task not found, creating new task: {}", e);
                 if status == TaskStatus::Blank {
                     // Don't create empty tasks when tracked reactions were removed.
-                    return Ok(());
+                    if !reactor_counts {
+                        return skip(
+                            "The reactor may not set statuses under the workspace's reaction policy",
+                        );
+                    }
+                    return skip("No reaction on the message sets a status");
+                }
+                if let Some(merged_into) = message.merged_into_task_id.as_deref() {
+                    return skip(format!("The message was merged into task {}", merged_into));
+                }
+                let task_assignee = rule_assignee(&self.db, &self.workspace_id, rule.as_ref())
+                    .await
+                    .unwrap_or(assignee);
+                let new_task = NewTask {
+                    workspace_id: &self.workspace_id,
+                    status: status.clone(),
+                    assignee: task_assignee,
+                    assigner: effective_assigner,
+                    message: message.clone(),
+                    rule: rule.as_ref(),
+                    template: None,
+                    source: TaskSource::Slack,
+                    created_at: None,
+                };
+                match self.tasks.create_from_message(new_task).await? {
+                    Outcome::Done(task) => {
+                        self.warn_if_duplicate(&task, &message, &actor_slack_id)
+                            .await
+                    }
+                    Outcome::Blocked(wip) => {
+                        let locale = self.locale_for(&actor_slack_id).await;
+                        self.post_ephemeral(channel, &actor_slack_id, &wip.message_in(locale))
+                            .await;
+                        return skip(wip.message());
+                    }
                 }
-                tasks_repo
-                    .create(
-                        status,
-                        assignee,
-                        effective_assigner,
-                        chrono::Utc::now().naive_utc(),
-                        message,
-                    )
-                    .await?;
             }
             Err(e) => {
                 error!("Failed to process task: {}", e);
@@ -721,92 +2120,162 @@ impl SlackBot {
         Ok(())
     }
 
-    async fn fetch_message_reactions(
-        &self,
-        channel: &str,
-        timestamp: &str,
-    ) -> Result<Vec<SlackReaction>> {
-        let response = self
-            .http_client
-            .get("https://slack.com/api/reactions.get")
-            .header("Authorization", format!("Bearer {}", self.bot_token))
-            .query(&[("channel", channel), ("timestamp", timestamp)])
-            .send()
-            .await?
-            .json::<ReactionsResponse>()
-            .await?;
+    /// Tell whoever created a task when it looks like an open task of the same channel
+    async fn warn_if_duplicate(&self, task: &Task, message: &MessageModel, actor_slack_id: &str) {
+        let similar =
+            duplicates::find_similar(&self.db, &self.message_encryption, task, message).await;
+        let similar = match similar {
+            Ok(similar) => similar,
+            Err(e) => {
+                warn!(
+                    "[TASK] Failed to look for duplicates of task {}: {}",
+                    task.id, e
+                );
+                return;
+            }
+        };
+        let Some(original) = similar.first() else {
+            return;
+        };
+        info!(
+            "[TASK] Task {} looks like a duplicate of task {}",
+            task.id, original.id
+        );
 
-        if !response.ok {
-            return Err(anyhow::anyhow!(
-                "Slack reactions.get failed for {}:{} ({:?})",
-                channel,
-                timestamp,
-                response.error
-            ));
-        }
+        let locale = self.locale_for(actor_slack_id).await;
+        let link = MessagesRepo::new(self.db.clone(), self.message_encryption.clone())
+            .get_by_id(original.message_id.clone())
+            .await
+            .map(|original_message| {
+                format!(
+                    " (<{}|{}>)",
+                    original_message.slack_link(),
+                    i18n::text(locale, "bot.message", &[])
+                )
+            })
+            .unwrap_or_default();
+        self.post_ephemeral(
+            &message.channel,
+            actor_slack_id,
+            &i18n::text(locale, "bot.duplicate", &[("link", &link)]),
+        )
+        .await;
+    }
 
-        Ok(response
-            .message
-            .and_then(|m| m.reactions)
-            .unwrap_or_default())
+    /// Show a message only `user` sees in the channel, e.g. why their reaction did not
+    /// move a task
+    async fn post_ephemeral(&self, channel: &str, user: &str, text: &str) {
+        if let Err(e) = self.slack.post_ephemeral(channel, user, text).await {
+            warn!("Failed to post ephemeral message: {}", e);
+        }
     }
 
     pub async fn run_periodic_sync(&self) -> Result<()> {
-        let messages_repo = MessagesRepo::new(self.db.clone());
+        let messages_repo = MessagesRepo::new(self.db.clone(), self.message_encryption.clone());
         let tasks_repo = TasksRepo::new(self.db.clone());
-        let all_messages = messages_repo.get_all().await?;
 
         // Get emoji mappings for this workspace
         let emoji_mappings = self.get_emoji_mappings().await;
+        let resolution = self.get_status_resolution().await;
+        let policy = self.get_reaction_policy().await;
 
-        for message in all_messages {
-            let message_reactions = match self
-                .fetch_message_reactions(&message.channel, &message.timestamp)
-                .await
-            {
-                Ok(reactions) => reactions,
-                Err(e) => {
-                    warn!(
-                        "Periodic sync: failed to fetch reactions for {}:{} ({})",
-                        message.channel, message.timestamp, e
-                    );
+        // Walk the table in batches rather than loading every message at once
+        let mut pagination = Pagination::first(MAX_LIMIT);
+        loop {
+            let page = messages_repo.get_page(&pagination).await?;
+            let next = pagination.next(&page);
+
+            for message in page.items {
+                if !message.is_from_slack() {
                     continue;
                 }
-            };
-            let status_set = map_reactions_to_status(&message_reactions, &emoji_mappings);
-            let correct_status = eval_status_from_reactions(status_set);
-
-            let mapped_task = match tasks_repo.get_task_by_message_id(message.id.clone()).await {
-                Ok(task) => task,
-                Err(DbErr::RecordNotFound(_)) => continue,
-                Err(e) => return Err(anyhow::anyhow!(e)),
-            };
+                let message_reactions = match self
+                    .slack
+                    .fetch_reactions(&message.channel, &message.timestamp)
+                    .await
+                {
+                    Ok(reactions) => reactions,
+                    Err(e) => {
+                        warn!(
+                            "Periodic sync: failed to fetch reactions for {}:{} ({})",
+                            message.channel, message.timestamp, e
+                        );
+                        continue;
+                    }
+                };
+                let mapped_task = match tasks_repo.get_task_by_message_id(message.id.clone()).await
+                {
+                    Ok(task) => task,
+                    Err(DbErr::RecordNotFound(_)) => continue,
+                    Err(e) => return Err(anyhow::anyhow!(e)),
+                };
+
+                let reactors = self.task_reactors(policy, &mapped_task.assigned_to).await?;
+                let status_reactions =
+                    map_reactions_to_status(&message_reactions, &emoji_mappings, &reactors);
+                let correct_status = resolve_status(resolution, &status_reactions);
+
+                self.tasks.transition(mapped_task, correct_status).await?;
+            }
 
-            tasks_repo
-                .change_status(mapped_task.id.clone(), correct_status)
-                .await?;
+            match next {
+                Some(next) => pagination = next,
+                None => break,
+            }
         }
 
         info!("Finished periodically updating tasks");
         Ok(())
     }
 
-    pub async fn start_periodic_tasks_sync(&self) {
+    pub async fn start_periodic_tasks_sync(
+        &self,
+        shutdown_token: tokio_util::sync::CancellationToken,
+    ) {
         let mut interval = interval(Duration::from_secs(300));
         // Skip the immediate tick; we already run initial sync at startup.
         interval.tick().await;
 
         loop {
-            interval.tick().await;
+            tokio::select! {
+                _ = shutdown_token.cancelled() => {
+                    info!("Stopping periodic sync for workspace: {}", self.workspace_name);
+                    break;
+                }
+                _ = interval.tick() => {}
+            }
+
+            if shutdown_token.is_cancelled() {
+                break;
+            }
+            if self.maintenance.is_enabled() {
+                info!(
+                    "Skipping periodic sync for workspace {} during maintenance",
+                    self.workspace_name
+                );
+                continue;
+            }
 
             // Discover new reacted messages as a fallback when reaction events are not delivered.
-            let syncer = InitialSyncer::new(
-                self.workspace_name.clone(),
-                self.bot_token.clone(),
-                self.db.clone(),
-                self.status_manager.clone(),
-            );
-            syncer.perform_initial_sync_for_all_users().await;
+            let syncer = InitialSyncer {
+                workspace_id: self.workspace_id.clone(),
+                workspace_name: self.workspace_name.clone(),
+                slack_team_id: self.slack_team_id.clone(),
+                db: self.db.clone(),
+                message_encryption: self.message_encryption.clone(),
+                slack: self.slack.clone(),
+                status_manager: self.status_manager.clone(),
+                board_events: self.board_events.clone(),
+                tasks: self.tasks.clone(),
+                cache: self.cache.clone(),
+                admin_email: self.admin_email.clone(),
+            };
+            if let Err(e) = syncer.perform_initial_sync_for_all_users().await {
+                error!("Periodic sync of users failed: {}", e);
+            }
+            if shutdown_token.is_cancelled() {
+                break;
+            }
 
             if let Err(e) = self.run_periodic_sync().await {
                 error!("Periodic task failed: {}", e);
@@ -815,90 +2284,67 @@ impl SlackBot {
     }
 }
 
-// Additional structs for channel listing and history
-#[derive(Debug, Deserialize)]
-struct SlackChannel {
-    id: String,
-    name: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct ChannelsResponse {
-    ok: bool,
-    channels: Option<Vec<SlackChannel>>,
-    error: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct HistoryMessage {
-    #[serde(default)]
-    user: Option<String>,
-    #[serde(default)]
-    text: Option<String>,
-    ts: String,
-    #[serde(default)]
-    reactions: Option<Vec<HistoryReaction>>,
-}
-
-#[derive(Debug, Deserialize)]
-struct HistoryReaction {
-    name: String,
-    #[serde(default)]
-    users: Option<Vec<String>>,
-    #[serde(default)]
-    count: Option<i32>,
-}
-
-#[derive(Debug, Deserialize)]
-struct HistoryResponse {
-    ok: bool,
-    messages: Option<Vec<HistoryMessage>>,
-    response_metadata: Option<ResponseMetadata>,
-}
-
-#[derive(Debug, Deserialize)]
-struct ResponseMetadata {
-    next_cursor: Option<String>,
+/// A task found in a channel's history, before anything is stored
+struct HistoryTask {
+    message: NewMessage,
+    person: Person,
+    status: TaskStatus,
+    assigner: Option<Person>,
 }
 
 /// Separate struct for initial sync to run in background without blocking SlackBot
 pub struct InitialSyncer {
+    pub workspace_id: String,
     pub workspace_name: String,
-    pub bot_token: String,
+    pub slack_team_id: Option<String>,
     pub db: DatabaseConnection,
-    pub http_client: Client,
+    pub message_encryption: MessageEncryption,
+    pub slack: SlackClient,
     pub status_manager: BotStatusManager,
+    pub board_events: BoardEventsManager,
+    pub tasks: TaskService,
+    pub cache: Cache,
+    pub admin_email: String,
 }
 
 impl InitialSyncer {
-    pub fn new(
-        workspace_name: String,
-        bot_token: String,
-        db: DatabaseConnection,
-        status_manager: BotStatusManager,
-    ) -> Self {
+    pub fn new(workspace: &Workspace, bot_token: String, state: &AppState) -> Self {
         Self {
-            workspace_name,
-            bot_token,
-            db,
-            http_client: Client::new(),
-            status_manager,
+            workspace_id: workspace.id.clone(),
+            workspace_name: workspace.name.clone(),
+            slack_team_id: workspace.slack_team_id.clone(),
+            db: state.database.clone(),
+            message_encryption: state.message_encryption.clone(),
+            slack: SlackClient::http(bot_token),
+            status_manager: state.bot_status.clone(),
+            board_events: state.board_events.clone(),
+            tasks: TaskService::from_state(state),
+            cache: state.cache.clone(),
+            admin_email: state.config.admin_email.clone(),
         }
     }
+
+    /// Make Slack Web API calls through `api`, e.g. recorded responses in tests
+    pub fn with_slack_api(mut self, api: Arc<dyn SlackApi>) -> Self {
+        self.slack = SlackClient::new(api);
+        self
+    }
 }
 
 impl InitialSyncer {
-    pub async fn perform_initial_sync_for_all_users(&self) {
+    /// Sync every linked user, fails when any of them failed so the job is retried
+    pub async fn perform_initial_sync_for_all_users(&self) -> Result<()> {
         info!(
             "Starting initial sync for all users in workspace: {}",
             self.workspace_name
         );
 
         let workspace_links_repo = WorkspaceLinksRepo::new(self.db.clone());
+        let mut failed = Vec::new();
 
         // Get all linked users for this workspace
         match workspace_links_repo
-            .get_by_workspace(self.workspace_name.clone())
+            .get_by_workspace(self.workspace_id.clone())
             .await
         {
             Ok(links) => {
@@ -916,7 +2362,7 @@ impl InitialSyncer {
                     self.status_manager
                         .set_sync_complete(&self.workspace_name)
                         .await;
-                    return;
+                    return Ok(());
                 }
 
                 for link in links {
@@ -927,6 +2373,7 @@ impl InitialSyncer {
                         );
                         if let Err(e) = self.perform_initial_sync(slack_member_id).await {
                             error!("Failed to sync for user {}: {}", slack_member_id, e);
+                            failed.push(slack_member_id.clone());
                             // Clear syncing status on error
                             self.status_manager
                                 .set_sync_complete(&self.workspace_name)
@@ -944,21 +2391,39 @@ impl InitialSyncer {
                 self.status_manager
                     .set_sync_complete(&self.workspace_name)
                     .await;
+                return Err(e.into());
             }
         }
 
+        self.board_events.touch();
+        if !failed.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Initial sync failed for {} of workspace {}",
+                failed.join(", "),
+                self.workspace_name
+            ));
+        }
         info!(
             "Initial sync completed for workspace: {}",
             self.workspace_name
         );
+        Ok(())
     }
 
     async fn get_emoji_mappings(&self) -> EmojiMappings {
-        let settings_repo = WorkspaceSettingsRepo::new(self.db.clone());
-        settings_repo
-            .get_emoji_mappings(&self.workspace_name)
-            .await
-            .unwrap_or_else(|_| EmojiMappings::default_mappings())
+        cached_emoji_mappings(&self.cache, &self.db, &self.workspace_id).await
+    }
+
+    async fn get_status_resolution(&self) -> StatusResolution {
+        cached_status_resolution(&self.cache, &self.db, &self.workspace_id).await
+    }
+
+    async fn get_reaction_policy(&self) -> ReactionPolicy {
+        cached_reaction_policy(&self.cache, &self.db, &self.workspace_id).await
+    }
+
+    async fn get_private_channel_policy(&self) -> PrivateChannelPolicy {
+        cached_private_channel_policy(&self.cache, &self.db, &self.workspace_id).await
     }
 
     pub async fn perform_initial_sync(&self, user_slack_id: &str) -> Result<()> {
@@ -988,10 +2453,35 @@ impl InitialSyncer {
         info!("Found {} channels to sync", channels.len());
 
         let emoji_mappings = self.get_emoji_mappings().await;
+        let resolution = self.get_status_resolution().await;
+        let policy = self.get_reaction_policy().await;
+        let private_channel_policy = self.get_private_channel_policy().await;
+        let rules: HashMap<String, ChannelRule> = ChannelRulesRepo::new(self.db.clone())
+            .get_by_workspace(&self.workspace_id)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to load channel rules, syncing without them: {}", e);
+                vec![]
+            })
+            .into_iter()
+            .map(|rule| (rule.channel.clone(), rule))
+            .collect();
         let mut processed_messages = 0;
         let mut created_tasks = 0;
 
         for (idx, channel) in channels.iter().enumerate() {
+            let rule = rules.get(&channel.id);
+            if rule.is_some_and(|rule| rule.ignored) {
+                info!("Skipping ignored channel {}", channel.name);
+                continue;
+            }
+            if channel.is_private && private_channel_policy == PrivateChannelPolicy::Forbid {
+                info!("Skipping private channel {}", channel.name);
+                continue;
+            }
+            let hide_channel =
+                channel.is_private && private_channel_policy == PrivateChannelPolicy::Anonymize;
+
             let progress = format!(
                 "Scanning channel {}/{}: {}",
                 idx + 1,
@@ -1008,34 +2498,49 @@ impl InitialSyncer {
                 .await
             {
                 Ok(messages) => {
+                    let mut found = Vec::new();
                     for msg in messages {
                         processed_messages += 1;
 
                         // Check if message has tracked reactions
                         if let Some(reactions) = &msg.reactions {
-                            let slack_reactions: Vec<SlackReaction> = reactions
-                                .iter()
-                                .map(|hr| SlackReaction {
-                                    name: hr.name.clone(),
-                                    users: hr.users.clone().unwrap_or_default(),
-                                    count: hr.count.unwrap_or(0),
-                                })
-                                .collect();
-
-                            let status_set =
-                                map_reactions_to_status(&slack_reactions, &emoji_mappings);
-                            if !status_set.is_empty() {
-                                if let Err(e) = self
-                                    .create_task_from_history(&msg, &channel.id, &emoji_mappings)
+                            let slack_reactions: Vec<SlackReaction> =
+                                reactions.iter().map(SlackReaction::from).collect();
+
+                            // Whose reactions count is checked once the author is known
+                            let status_reactions = map_reactions_to_status(
+                                &slack_reactions,
+                                &emoji_mappings,
+                                &Reactors::Anyone,
+                            );
+                            if !status_reactions.is_empty() {
+                                match self
+                                    .read_history_task(
+                                        &msg,
+                                        &channel.id,
+                                        hide_channel,
+                                        &emoji_mappings,
+                                        resolution,
+                                        policy,
+                                    )
                                     .await
                                 {
-                                    warn!("Failed to create task from history: {}", e);
-                                } else {
-                                    created_tasks += 1;
+                                    Ok(Some(task)) => found.push(task),
+                                    Ok(None) => {}
+                                    Err(e) => warn!("Failed to read task from history: {}", e),
                                 }
                             }
                         }
                     }
+
+                    // Stored in bulk, a large channel takes a few statements
+                    match self.create_tasks_from_history(found, rule).await {
+                        Ok(count) => created_tasks += count,
+                        Err(e) => warn!(
+                            "Failed to create tasks from history of channel {}: {}",
+                            channel.name, e
+                        ),
+                    }
                 }
                 Err(e) => {
                     warn!(
@@ -1059,28 +2564,14 @@ impl InitialSyncer {
     }
 
     async fn fetch_channels(&self) -> Result<Vec<SlackChannel>> {
-        let response = self
-            .http_client
-            .get("https://slack.com/api/conversations.list")
-            .header("Authorization", format!("Bearer {}", self.bot_token))
-            .query(&[
-                ("types", "public_channel,private_channel"),
-                ("exclude_archived", "true"),
-                ("limit", "1000"),
-            ])
-            .send()
-            .await?
-            .json::<ChannelsResponse>()
-            .await?;
-
-        if !response.ok {
-            return Err(anyhow::anyhow!(
-                "Failed to fetch channels: {:?}",
-                response.error
-            ));
+        let key = cache_keys::channels(&self.workspace_id);
+        if let Some(channels) = self.cache.get(&key).await {
+            return Ok(channels);
         }
 
-        Ok(response.channels.unwrap_or_default())
+        let channels = self.slack.list_channels().await?;
+        self.cache.set(&key, &channels).await;
+        Ok(channels)
     }
 
     async fn fetch_channel_messages_with_reactions(
@@ -1094,23 +2585,9 @@ impl InitialSyncer {
         const MAX_PAGES: i32 = 5;
 
         loop {
-            let mut query = vec![
-                ("channel", channel_id.to_string()),
-                ("limit", "100".to_string()),
-            ];
-
-            if let Some(ref c) = cursor {
-                query.push(("cursor", c.clone()));
-            }
-
             let response = self
-                .http_client
-                .get("https://slack.com/api/conversations.history")
-                .header("Authorization", format!("Bearer {}", self.bot_token))
-                .query(&query)
-                .send()
-                .await?
-                .json::<HistoryResponse>()
+                .slack
+                .channel_history(channel_id, cursor.as_deref())
                 .await?;
 
             if !response.ok {
@@ -1152,15 +2629,18 @@ impl InitialSyncer {
         Ok(all_messages)
     }
 
-    async fn create_task_from_history(
+    /// What a message of the channel's history says about its task, None when its
+    /// reactions leave the task blank
+    async fn read_history_task(
         &self,
         msg: &HistoryMessage,
         channel_id: &str,
+        hide_channel: bool,
         emoji_mappings: &EmojiMappings,
-    ) -> Result<()> {
+        resolution: StatusResolution,
+        policy: ReactionPolicy,
+    ) -> Result<Option<HistoryTask>> {
         let persons_repo = PersonsRepo::new(self.db.clone());
-        let messages_repo = MessagesRepo::new(self.db.clone());
-        let tasks_repo = TasksRepo::new(self.db.clone());
         let workspace_links_repo = WorkspaceLinksRepo::new(self.db.clone());
 
         let user_id = msg
@@ -1178,7 +2658,7 @@ impl InitialSyncer {
 
         // Verify person is linked to this workspace
         match workspace_links_repo
-            .get_by_person_and_workspace(person.id.clone(), self.workspace_name.clone())
+            .get_by_person_and_workspace(person.id.clone(), self.workspace_id.clone())
             .await
         {
             Ok(link) if link.is_linked => {}
@@ -1191,50 +2671,24 @@ impl InitialSyncer {
             }
         }
 
-        let message_external_id = format!("slack:{}:{}", channel_id, ts);
-        let message = match messages_repo
-            .get_message_by_external_id(message_external_id.clone())
-            .await
-        {
-            Ok(existing) => existing,
-            Err(DbErr::RecordNotFound(_)) => {
-                messages_repo
-                    .create(
-                        text,
-                        message_external_id,
-                        channel_id.to_string(),
-                        ts.clone(),
-                        &person,
-                    )
-                    .await?
-            }
-            Err(e) => {
-                return Err(anyhow::anyhow!(
-                    "Failed to load or create message {}: {}",
-                    message_external_id,
-                    e
-                ))
-            }
-        };
-
         let reactions: Vec<SlackReaction> = msg
             .reactions
             .as_ref()
-            .map(|r| {
-                r.iter()
-                    .map(|hr| SlackReaction {
-                        name: hr.name.clone(),
-                        users: hr.users.clone().unwrap_or_default(),
-                        count: hr.count.unwrap_or(0),
-                    })
-                    .collect()
-            })
+            .map(|r| r.iter().map(SlackReaction::from).collect())
             .unwrap_or_default();
 
-        let status_set = map_reactions_to_status(&reactions, emoji_mappings);
-        let status = eval_status_from_reactions(status_set);
+        let reactors = counted_reactors(
+            &self.db,
+            &self.admin_email,
+            &self.workspace_id,
+            policy,
+            &person,
+        )
+        .await?;
+        let status_reactions = map_reactions_to_status(&reactions, emoji_mappings, &reactors);
+        let status = resolve_status(resolution, &status_reactions);
         if status == TaskStatus::Blank {
-            return Ok(());
+            return Ok(None);
         }
 
         // Try to get the first reactor as the assigner (if available)
@@ -1242,39 +2696,104 @@ impl InitialSyncer {
             Some(slack_id) => persons_repo.get_by_external_id(slack_id.clone()).await.ok(),
             None => None,
         };
-        let assigner_id = assigner.as_ref().map(|p| p.id.clone());
 
-        match tasks_repo.get_task_by_message_id(message.id.clone()).await {
-            Ok(task) => {
-                if task.status != status {
-                    tasks_repo.change_status(task.id.clone(), status).await?;
-                }
-                if task.assigned_by != assigner_id {
-                    tasks_repo
-                        .change_assigned_by(task.id.clone(), assigner_id)
-                        .await?;
+        Ok(Some(HistoryTask {
+            message: NewMessage {
+                content: text,
+                external_id: format!("slack:{}:{}", channel_id, ts),
+                channel: channel_id.to_string(),
+                timestamp: ts.clone(),
+                person_id: person.id.clone(),
+                channel_hidden: hide_channel,
+                slack_team_id: self.slack_team_id.clone(),
+            },
+            person,
+            status,
+            assigner,
+        }))
+    }
+
+    /// Store the messages of a channel's history tasks and create the tasks they
+    /// lack, both in bulk. Tasks already made from a message take the status from
+    /// history instead. Returns how many tasks were created or synced.
+    async fn create_tasks_from_history(
+        &self,
+        found: Vec<HistoryTask>,
+        rule: Option<&ChannelRule>,
+    ) -> Result<usize> {
+        if found.is_empty() {
+            return Ok(0);
+        }
+        let tasks_repo = TasksRepo::new(self.db.clone());
+
+        let messages: HashMap<String, MessageModel> =
+            MessagesRepo::new(self.db.clone(), self.message_encryption.clone())
+                .create_many(
+                    found.iter().map(|task| task.message.clone()).collect(),
+                    &self.workspace_id,
+                )
+                .await?
+                .into_iter()
+                .map(|message| (message.external_id.clone(), message))
+                .collect();
+        let tasks: HashMap<String, Task> = tasks_repo
+            .get_by_message_ids(
+                messages
+                    .values()
+                    .map(|message| message.id.clone())
+                    .collect(),
+            )
+            .await?
+            .into_iter()
+            .map(|task| (task.message_id.clone(), task))
+            .collect();
+        let rule_assignee = rule_assignee(&self.db, &self.workspace_id, rule).await;
+
+        let mut synced = 0;
+        let mut new_tasks = Vec::new();
+        for found in found {
+            let Some(message) = messages.get(&found.message.external_id).cloned() else {
+                continue;
+            };
+            let assigner_id = found.assigner.as_ref().map(|p| p.id.clone());
+
+            match tasks.get(&message.id) {
+                Some(task) => {
+                    let assigned_by = task.assigned_by.clone();
+                    let task = match self.tasks.sync_status(task.clone(), found.status).await {
+                        Ok(task) => task,
+                        Err(e) => {
+                            warn!("Failed to sync task {} from history: {}", task.id, e);
+                            continue;
+                        }
+                    };
+                    if assigned_by != assigner_id {
+                        let task = tasks_repo
+                            .change_assigned_by(task.id.clone(), assigner_id)
+                            .await?;
+                        self.tasks.publish(BoardChange::Updated, task).await;
+                    }
+                    synced += 1;
                 }
-            }
-            Err(DbErr::RecordNotFound(_)) => {
-                tasks_repo
-                    .create(
-                        status,
-                        person,
-                        assigner,
-                        chrono::Utc::now().naive_utc(),
-                        message,
-                    )
-                    .await?;
-            }
-            Err(e) => {
-                return Err(anyhow::anyhow!(
-                    "Failed to load task for message {}: {}",
-                    message.id,
-                    e
-                ))
+                None if message.merged_into_task_id.is_some() => {}
+                None => new_tasks.push(NewTask {
+                    workspace_id: &self.workspace_id,
+                    status: found.status,
+                    assignee: rule_assignee.clone().unwrap_or(found.person),
+                    assigner: found.assigner,
+                    message,
+                    rule,
+                    template: None,
+                    source: TaskSource::History,
+                    created_at: None,
+                }),
             }
         }
 
-        Ok(())
+        let created = self
+            .tasks
+            .create_many_from_history(&self.workspace_id, new_tasks)
+            .await?;
+        Ok(synced + created.len())
     }
 }
@@ -0,0 +1,223 @@
+//! Time counted only within working hours: a working day runs from `start` to `end`
+//! local time on the weekdays worked, holidays excepted. Ages, SLAs and escalation
+//! timers of workspaces with business hours use it in place of wall-clock time.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+
+/// Days ahead `add` looks for enough working time before giving up
+const MAX_DAYS_AHEAD: u32 = 3660;
+
+#[derive(Clone, Debug)]
+pub struct BusinessCalendar {
+    timezone: Tz,
+    start: NaiveTime,
+    end: NaiveTime,
+    days: Vec<Weekday>,
+    holidays: HashSet<NaiveDate>,
+}
+
+impl BusinessCalendar {
+    /// Working days run from `start` to `end`, which should be later in the day
+    pub fn new(
+        timezone: Tz,
+        start: NaiveTime,
+        end: NaiveTime,
+        days: Vec<Weekday>,
+        holidays: impl IntoIterator<Item = NaiveDate>,
+    ) -> Self {
+        Self {
+            timezone,
+            start,
+            end,
+            days,
+            holidays: holidays.into_iter().collect(),
+        }
+    }
+
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        self.days.contains(&date.weekday()) && !self.holidays.contains(&date)
+    }
+
+    /// Working hours of a local date, None on days off
+    fn window(&self, date: NaiveDate) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        self.is_working_day(date)
+            .then(|| (self.at(date, self.start), self.at(date, self.end)))
+    }
+
+    /// A local time of a date; times skipped by a DST change move an hour on
+    fn at(&self, date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+        let local = date.and_time(time);
+        self.timezone
+            .from_local_datetime(&local)
+            .earliest()
+            .or_else(|| {
+                self.timezone
+                    .from_local_datetime(&(local + Duration::hours(1)))
+                    .earliest()
+            })
+            .map(|at| at.with_timezone(&Utc))
+            .unwrap_or_else(|| local.and_utc())
+    }
+
+    fn local_date(&self, at: DateTime<Utc>) -> NaiveDate {
+        at.with_timezone(&self.timezone).date_naive()
+    }
+
+    /// Working time between two instants, zero when `to` is not after `from`
+    pub fn working_time(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Duration {
+        let mut total = Duration::zero();
+        if to <= from {
+            return total;
+        }
+        let mut date = self.local_date(from);
+        let last = self.local_date(to);
+        while date <= last {
+            if let Some((start, end)) = self.window(date) {
+                let (start, end) = (start.max(from), end.min(to));
+                if end > start {
+                    total += end - start;
+                }
+            }
+            let Some(next) = date.succ_opt() else {
+                break;
+            };
+            date = next;
+        }
+        total
+    }
+
+    /// When `duration` of working time has passed since `from`, e.g. an SLA's
+    /// deadline. None when no working day comes up within ten years.
+    pub fn add(&self, from: DateTime<Utc>, duration: Duration) -> Option<DateTime<Utc>> {
+        if duration <= Duration::zero() {
+            return Some(from);
+        }
+        let mut remaining = duration;
+        let mut date = self.local_date(from);
+        for _ in 0..MAX_DAYS_AHEAD {
+            if let Some((start, end)) = self.window(date) {
+                let start = start.max(from);
+                if end > start {
+                    if remaining <= end - start {
+                        return Some(start + remaining);
+                    }
+                    remaining -= end - start;
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
+
+/// Days of the week in cron syntax, e.g. `Mon-Fri` or `Mon,Wed,Fri`; ranges may
+/// wrap around the weekend, e.g. `Sun-Thu`. None when a part isn't a weekday.
+pub fn parse_weekdays(days: &str) -> Option<Vec<Weekday>> {
+    let mut weekdays = Vec::new();
+    for part in days.split(',') {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (first.trim().parse().ok()?, last.trim().parse().ok()?),
+            None => {
+                let day: Weekday = part.trim().parse().ok()?;
+                (day, day)
+            }
+        };
+        let mut day: Weekday = first;
+        loop {
+            if !weekdays.contains(&day) {
+                weekdays.push(day);
+            }
+            if day == last {
+                break;
+            }
+            day = day.succ();
+        }
+    }
+    Some(weekdays)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 9:00 to 17:00 in Berlin on weekdays, with Friday 2026-04-03 off
+    fn calendar() -> BusinessCalendar {
+        BusinessCalendar::new(
+            chrono_tz::Europe::Berlin,
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            parse_weekdays("Mon-Fri").unwrap(),
+            [NaiveDate::from_ymd_opt(2026, 4, 3).unwrap()],
+        )
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn counts_hours_within_a_day() {
+        // Wednesday 2026-03-04, Berlin is UTC+1
+        assert_eq!(
+            calendar().working_time(utc("2026-03-04T07:00:00Z"), utc("2026-03-04T11:30:00Z")),
+            Duration::minutes(210)
+        );
+        assert_eq!(
+            calendar().working_time(utc("2026-03-04T17:00:00Z"), utc("2026-03-04T20:00:00Z")),
+            Duration::zero()
+        );
+    }
+
+    #[test]
+    fn skips_weekends() {
+        // Friday 15:00 to Monday 11:00 local
+        assert_eq!(
+            calendar().working_time(utc("2026-03-06T14:00:00Z"), utc("2026-03-09T10:00:00Z")),
+            Duration::hours(4)
+        );
+        assert_eq!(
+            calendar().add(utc("2026-03-06T14:00:00Z"), Duration::hours(8)),
+            Some(utc("2026-03-09T14:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn skips_holidays() {
+        // Thursday 16:00 local, Berlin now on summer time, to Monday 10:00 past the
+        // Friday off
+        assert_eq!(
+            calendar().working_time(utc("2026-04-02T14:00:00Z"), utc("2026-04-06T08:00:00Z")),
+            Duration::hours(2)
+        );
+        assert_eq!(
+            calendar().add(utc("2026-04-02T14:00:00Z"), Duration::hours(2)),
+            Some(utc("2026-04-06T08:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn starts_counting_at_the_next_working_hour() {
+        // Saturday: the first working hour is Monday 9:00 local
+        assert_eq!(
+            calendar().add(utc("2026-03-07T12:00:00Z"), Duration::minutes(30)),
+            Some(utc("2026-03-09T08:30:00Z"))
+        );
+        assert_eq!(
+            calendar().add(utc("2026-03-07T12:00:00Z"), Duration::zero()),
+            Some(utc("2026-03-07T12:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn parses_weekdays() {
+        assert_eq!(
+            parse_weekdays("Sun-Tue, fri"),
+            Some(vec![Weekday::Sun, Weekday::Mon, Weekday::Tue, Weekday::Fri])
+        );
+        assert_eq!(parse_weekdays("Mon-Someday"), None);
+        assert_eq!(parse_weekdays(""), None);
+    }
+}
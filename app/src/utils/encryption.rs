@@ -4,7 +4,9 @@ use aes_gcm::{
 };
 use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hmac::{Hmac, Mac};
 use rand::Rng;
+use sha2::Sha256;
 
 const NONCE_SIZE: usize = 12;
 
@@ -91,6 +93,31 @@ pub fn is_encrypted(value: &str) -> bool {
     BASE64.decode(parts[0]).is_ok() && BASE64.decode(parts[1]).is_ok()
 }
 
+/// Key of one workspace's messages, derived from the master key
+fn workspace_message_key(master_key: &str, workspace_id: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(master_key.as_bytes()).expect("HMAC takes any key size");
+    mac.update(b"messages:");
+    mac.update(workspace_id.as_bytes());
+    BASE64.encode(mac.finalize().into_bytes())
+}
+
+/// Encrypt message content with the key of its workspace, as
+/// "{workspace_id}:nonce:ciphertext"
+pub fn encrypt_message(content: &str, master_key: &str, workspace_id: &str) -> Result<String> {
+    let key = workspace_message_key(master_key, workspace_id);
+    Ok(format!("{}:{}", workspace_id, encrypt(content, &key)?))
+}
+
+/// Decrypt message content from `encrypt_message`
+pub fn decrypt_message(content: &str, master_key: &str) -> Result<String> {
+    let (workspace_id, encrypted) = content
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Invalid encrypted message format"))?;
+
+    decrypt(encrypted, &workspace_message_key(master_key, workspace_id))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,4 +145,17 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn messages_are_keyed_per_workspace() {
+        let key = "my-secret-master-key-12345";
+        let encrypted = encrypt_message("ship it: today", key, "acme").unwrap();
+        assert_eq!(decrypt_message(&encrypted, key).unwrap(), "ship it: today");
+
+        // Claiming another workspace picks another key
+        let forged = encrypted.replacen("acme:", "other:", 1);
+        assert!(decrypt_message(&forged, key).is_err());
+
+        assert!(decrypt_message("enc: plain text", key).is_err());
+    }
 }
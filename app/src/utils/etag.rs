@@ -0,0 +1,90 @@
+//! Conditional GETs for responses derived from the board version, so polling
+//! clients get an empty 304 until a task changes, and If-Match on updates of
+//! versioned rows.
+
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+
+/// Clients keep the response but check back every time
+const CACHE_CONTROL: &str = "private, no-cache";
+
+/// ETag of a response built at `version` from the given inputs. Weak, since the
+/// compression layer changes the bytes but not the meaning
+pub fn versioned_etag(version: u64, inputs: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(version.to_be_bytes());
+    for input in inputs {
+        hasher.update(input.as_bytes());
+        hasher.update([0]);
+    }
+    format!("W/\"{}\"", hex::encode(&hasher.finalize()[..16]))
+}
+
+/// Whether the client's If-None-Match already names this ETag
+pub fn is_fresh(headers: &HeaderMap, etag: &str) -> bool {
+    let strip_weak = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || strip_weak(tag) == strip_weak(etag))
+}
+
+fn etag_headers(etag: &str) -> [(header::HeaderName, HeaderValue); 2] {
+    [
+        (
+            header::ETAG,
+            HeaderValue::from_str(etag).expect("hex etag is a valid header"),
+        ),
+        (
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(CACHE_CONTROL),
+        ),
+    ]
+}
+
+/// 304 when the client is up to date, None when the response has to be built
+pub fn not_modified(headers: &HeaderMap, etag: &str) -> Option<Response> {
+    is_fresh(headers, etag).then(|| (StatusCode::NOT_MODIFIED, etag_headers(etag)).into_response())
+}
+
+/// The response with its ETag attached
+pub fn with_etag(response: impl IntoResponse, etag: &str) -> Response {
+    (etag_headers(etag), response).into_response()
+}
+
+/// Strong ETag of a row at `version`, see `if_match`
+pub fn version_tag(version: i32) -> String {
+    format!("\"{}\"", version)
+}
+
+/// What the If-Match of an update asks for
+#[derive(Debug, PartialEq)]
+pub enum IfMatch {
+    Missing,
+    /// `*`: whatever version is current
+    Any,
+    /// A `version_tag`
+    Version(i32),
+    /// A tag that names no version
+    Invalid,
+}
+
+pub fn if_match(headers: &HeaderMap) -> IfMatch {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return IfMatch::Missing;
+    };
+    let tag = value.to_str().unwrap_or_default().trim();
+    if tag == "*" {
+        return IfMatch::Any;
+    }
+    tag.trim_start_matches("W/")
+        .trim_matches('"')
+        .parse()
+        .map(IfMatch::Version)
+        .unwrap_or(IfMatch::Invalid)
+}
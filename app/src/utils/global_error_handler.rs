@@ -1,5 +1,14 @@
-use crate::utils::response::APIResponse;
+use axum::{extract::OriginalUri, http::Method};
 
-pub async fn global_error_handler() -> APIResponse {
-    APIResponse::NotFound("Not Found".to_string())
+use crate::utils::response::APIError;
+
+/// Fallback of the API router for paths no route matches
+pub async fn global_error_handler(OriginalUri(uri): OriginalUri) -> APIError {
+    APIError::NotFound(format!("No API route matches {}", uri.path()))
+}
+
+/// Fallback for known API paths requested with a method they don't support; the
+/// router adds the `Allow` header listing the supported ones
+pub async fn method_not_allowed_handler(method: Method, OriginalUri(uri): OriginalUri) -> APIError {
+    APIError::MethodNotAllowed(format!("{} is not allowed on {}", method, uri.path()))
 }
@@ -0,0 +1,125 @@
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, Request},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use validator::Validate;
+
+use crate::utils::{
+    response::{APIError, FieldError},
+    validation::field_errors,
+};
+
+/// JSON request body and response, like `axum::Json`, but a body that doesn't fit
+/// the payload type is rejected with 422 naming the field at fault
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+fn has_json_content_type(headers: &HeaderMap) -> bool {
+    let Some(content_type) = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    mime == "application/json" || (mime.starts_with("application/") && mime.ends_with("+json"))
+}
+
+/// Field a deserialization error is about: its path in the body, plus the field
+/// name for missing fields, which serde reports on the enclosing object
+fn error_field(path: &str, message: &str) -> String {
+    let missing = message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split('`').next());
+
+    match (path, missing) {
+        (".", Some(field)) => field.to_string(),
+        (path, Some(field)) => format!("{}.{}", path, field),
+        (".", None) => "body".to_string(),
+        (path, None) => path.to_string(),
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if !has_json_content_type(request.headers()) {
+            return Err(APIError::UnsupportedMediaType(
+                "Expected a request with Content-Type: application/json".to_string(),
+            )
+            .into_response());
+        }
+
+        // Keeps axum's answer for bodies that are too large or fail to arrive
+        let bytes = Bytes::from_request(request, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        let deserializer = &mut serde_json::Deserializer::from_slice(&bytes);
+        serde_path_to_error::deserialize(deserializer)
+            .map(Json)
+            .map_err(|e| -> Response {
+                let path = e.path().to_string();
+                let error = e.into_inner();
+                if !error.is_data() {
+                    return APIError::BadRequest(format!("Malformed JSON body: {}", error))
+                        .into_response();
+                }
+
+                // Drop serde's " at line 1 column 42" suffix, the field says where
+                let message = error.to_string();
+                let message = message
+                    .rsplit_once(" at line ")
+                    .map_or(message.as_str(), |(message, _)| message)
+                    .to_string();
+                APIError::UnprocessableEntity(vec![FieldError {
+                    field: error_field(&path, &message),
+                    message,
+                }])
+                .into_response()
+            })
+    }
+}
+
+/// JSON request body that must also pass its `#[derive(Validate)]` rules; every
+/// failed rule is listed in the 422 response
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(payload) = Json::<T>::from_request(request, state).await?;
+        payload.validate().map_err(|errors| {
+            APIError::UnprocessableEntity(field_errors(&errors)).into_response()
+        })?;
+        Ok(ValidatedJson(payload))
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
@@ -8,11 +8,19 @@ pub struct Claims {
     pub person_id: String,
     pub exp: i64,
     pub iat: i64,
+    /// Person ID of the super admin who issued this token to act as `person_id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impersonated_by: Option<String>,
+    /// Session of the sign-in that issued this token; revoking it rejects the token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
 }
 
-pub fn create_jwt(
+/// Token of a sign-in, valid while its session is
+pub fn create_session_jwt(
     email: String,
     person_id: String,
+    session_id: String,
     secret: &str,
     expiry_hours: i64,
 ) -> Result<String, jsonwebtoken::errors::Error> {
@@ -24,6 +32,8 @@ pub fn create_jwt(
         person_id,
         exp: expiry.timestamp(),
         iat: now.timestamp(),
+        impersonated_by: None,
+        sid: Some(session_id),
     };
 
     encode(
@@ -33,6 +43,35 @@ pub fn create_jwt(
     )
 }
 
+/// Short-lived token that lets an admin act as another person
+pub fn create_impersonation_jwt(
+    email: String,
+    person_id: String,
+    admin_id: String,
+    secret: &str,
+    expiry_minutes: i64,
+) -> Result<(String, i64), jsonwebtoken::errors::Error> {
+    let now = Utc::now();
+    let expiry = now + Duration::minutes(expiry_minutes);
+
+    let claims = Claims {
+        sub: email,
+        person_id,
+        exp: expiry.timestamp(),
+        iat: now.timestamp(),
+        impersonated_by: Some(admin_id),
+        sid: None,
+    };
+
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )?;
+
+    Ok((token, claims.exp))
+}
+
 pub fn verify_jwt(token: &str, secret: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
     let validation = Validation::default();
     let token_data = decode::<Claims>(
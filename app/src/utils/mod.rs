@@ -1,5 +1,12 @@
+pub mod business_time;
 pub mod crypto;
 pub mod encryption;
+pub mod etag;
 pub mod global_error_handler;
+pub mod json;
 pub mod jwt;
+pub mod pagination;
+pub mod redaction;
 pub mod response;
+pub mod time;
+pub mod validation;
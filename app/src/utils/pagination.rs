@@ -0,0 +1,179 @@
+//! Keyset pagination, newest first. Pages continue after an opaque cursor naming
+//! the last row's `(created_at, id)`, so a page costs the same however deep it is,
+//! unlike OFFSET which reads and drops every row before it.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, NaiveDateTime};
+use sea_orm::{ColumnTrait, Condition, QueryFilter, QueryOrder, QuerySelect};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::utils::response::APIError;
+
+pub const DEFAULT_LIMIT: u64 = 20;
+pub const MAX_LIMIT: u64 = 100;
+
+/// Position after a row in a listing ordered by `(created_at, id)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    pub created_at: NaiveDateTime,
+    pub id: String,
+}
+
+impl Cursor {
+    pub fn new(created_at: NaiveDateTime, id: &str) -> Self {
+        Self {
+            created_at,
+            id: id.to_string(),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let micros = self.created_at.and_utc().timestamp_micros();
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", micros, self.id))
+    }
+
+    /// None for anything `encode` didn't produce
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+        let decoded = String::from_utf8(bytes).ok()?;
+        let (micros, id) = decoded.split_once(':')?;
+        let created_at = DateTime::from_timestamp_micros(micros.parse().ok()?)?.naive_utc();
+        (!id.is_empty()).then(|| Self::new(created_at, id))
+    }
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct CursorQuery {
+    /// `next_cursor` of the previous page; the first page without one
+    pub cursor: Option<String>,
+    /// Rows per page, 20 by default and at most 100
+    pub limit: Option<u64>,
+}
+
+/// Which page to load: rows after `after`, at most `limit` of them
+#[derive(Debug, Clone)]
+pub struct Pagination {
+    pub after: Option<Cursor>,
+    pub limit: u64,
+}
+
+/// One page of rows and the cursor of the next, None on the last page
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+impl Pagination {
+    pub fn first(limit: u64) -> Self {
+        Self {
+            after: None,
+            limit: limit.clamp(1, MAX_LIMIT),
+        }
+    }
+
+    /// Read `?cursor=&limit=`; a cursor that doesn't decode is a bad request
+    pub fn from_query(query: &CursorQuery) -> Result<Self, APIError> {
+        let after = match query.cursor.as_deref().filter(|c| !c.is_empty()) {
+            Some(cursor) => Some(
+                Cursor::decode(cursor)
+                    .ok_or_else(|| APIError::BadRequest("Invalid cursor".to_string()))?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            after,
+            ..Self::first(query.limit.unwrap_or(DEFAULT_LIMIT))
+        })
+    }
+
+    /// Restrict a query to this page: newest first, rows after the cursor, and one
+    /// row more than the limit to tell whether another page follows
+    pub fn apply<Q, C>(&self, query: Q, created_at: C, id: C) -> Q
+    where
+        Q: QueryFilter + QueryOrder + QuerySelect,
+        C: ColumnTrait,
+    {
+        let query = match &self.after {
+            Some(after) => query.filter(
+                Condition::any().add(created_at.lt(after.created_at)).add(
+                    Condition::all()
+                        .add(created_at.eq(after.created_at))
+                        .add(id.lt(after.id.clone())),
+                ),
+            ),
+            None => query,
+        };
+        query
+            .order_by_desc(created_at)
+            .order_by_desc(id)
+            .limit(self.limit + 1)
+    }
+
+    /// The page following `page`, None when it was the last one
+    pub fn next<T>(&self, page: &Page<T>) -> Option<Self> {
+        let after = Cursor::decode(page.next_cursor.as_deref()?)?;
+        Some(Self {
+            after: Some(after),
+            limit: self.limit,
+        })
+    }
+
+    /// Cut the rows `apply` fetched down to the page
+    pub fn page<T>(&self, mut rows: Vec<T>, cursor_of: impl Fn(&T) -> Cursor) -> Page<T> {
+        let has_more = rows.len() as u64 > self.limit;
+        rows.truncate(self.limit as usize);
+        let next_cursor = has_more
+            .then(|| rows.last().map(|row| cursor_of(row).encode()))
+            .flatten();
+
+        Page {
+            items: rows,
+            next_cursor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursors_round_trip() {
+        let created_at = DateTime::from_timestamp_micros(1_700_000_000_123_456)
+            .unwrap()
+            .naive_utc();
+        let cursor = Cursor::new(created_at, "abc:def");
+
+        assert_eq!(Cursor::decode(&cursor.encode()), Some(cursor));
+    }
+
+    #[test]
+    fn garbage_cursors_are_rejected() {
+        assert_eq!(Cursor::decode("not a cursor"), None);
+        assert_eq!(Cursor::decode(&URL_SAFE_NO_PAD.encode("soon:abc")), None);
+        assert_eq!(Cursor::decode(&URL_SAFE_NO_PAD.encode("123:")), None);
+    }
+
+    #[test]
+    fn pages_know_whether_more_follow() {
+        let pagination = Pagination::first(2);
+        let created_at = DateTime::from_timestamp(0, 0).unwrap().naive_utc();
+        let cursor_of = |id: &&str| Cursor::new(created_at, id);
+
+        let page = pagination.page(vec!["c", "b", "a"], cursor_of);
+        assert_eq!(page.items, ["c", "b"]);
+        assert_eq!(
+            page.next_cursor.as_deref().and_then(Cursor::decode),
+            Some(Cursor::new(created_at, "b"))
+        );
+
+        let next = pagination.next(&page).unwrap();
+        assert_eq!(next.after, Some(Cursor::new(created_at, "b")));
+
+        let page = next.page(vec!["a"], cursor_of);
+        assert_eq!(page.next_cursor, None);
+        assert!(next.next(&page).is_none());
+    }
+}
@@ -0,0 +1,85 @@
+//! Message text as a workspace's `MessageRedaction` allows it to be stored.
+
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+use sha2::{Digest, Sha256};
+
+use crate::models::workspace_settings::MessageRedaction;
+
+pub const EMAIL_PLACEHOLDER: &str = "[email]";
+pub const PHONE_PLACEHOLDER: &str = "[phone]";
+
+/// Prefix of hashed message text, which is never hashed again
+pub const HASH_PREFIX: &str = "[redacted sha256:";
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)[a-z0-9._%+-]+@[a-z0-9-]+(\.[a-z0-9-]+)*\.[a-z]{2,}").expect("valid regex")
+});
+
+/// Candidates only; a match counts as a phone number when it has enough digits
+static PHONE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\+?\(?\d[\d\s().-]{6,}\d").expect("valid regex"));
+
+/// Fewer digits are more likely dates, amounts or ticket numbers
+const MIN_PHONE_DIGITS: usize = 9;
+const MAX_PHONE_DIGITS: usize = 15;
+
+/// Email addresses and phone numbers replaced with placeholders
+pub fn scrub(content: &str) -> String {
+    let content = EMAIL.replace_all(content, EMAIL_PLACEHOLDER);
+    PHONE
+        .replace_all(&content, |caps: &Captures| {
+            let digits = caps[0].chars().filter(char::is_ascii_digit).count();
+            if (MIN_PHONE_DIGITS..=MAX_PHONE_DIGITS).contains(&digits) {
+                PHONE_PLACEHOLDER.to_string()
+            } else {
+                caps[0].to_string()
+            }
+        })
+        .into_owned()
+}
+
+/// `content` as `mode` allows it to be stored. Redacting twice changes nothing, so
+/// exports can apply the current mode to messages stored under an older one
+pub fn redact(content: &str, mode: MessageRedaction) -> String {
+    match mode {
+        MessageRedaction::None => content.to_string(),
+        MessageRedaction::Scrub => scrub(content),
+        MessageRedaction::Hash if content.starts_with(HASH_PREFIX) => content.to_string(),
+        MessageRedaction::Hash => {
+            format!("{}{}]", HASH_PREFIX, hex::encode(Sha256::digest(content)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrubs_emails_and_phone_numbers() {
+        assert_eq!(
+            scrub("Mail jane.doe+ops@example.co.uk or call +1 (415) 555-0132 today"),
+            "Mail [email] or call [phone] today"
+        );
+    }
+
+    #[test]
+    fn leaves_dates_and_short_numbers() {
+        let content = "Release 2024-01-15, ticket 12345, budget 1,500.00";
+        assert_eq!(scrub(content), content);
+    }
+
+    #[test]
+    fn redacting_twice_changes_nothing() {
+        for mode in [
+            MessageRedaction::None,
+            MessageRedaction::Scrub,
+            MessageRedaction::Hash,
+        ] {
+            let once = redact("ping bob@example.com at 020 7946 0958", mode);
+            assert_eq!(redact(&once, mode), once);
+        }
+    }
+}
@@ -6,6 +6,7 @@ use axum::{
 use sea_orm::DbErr;
 use serde::Serialize;
 use serde_json::Value as JsonValue;
+use utoipa::ToSchema;
 
 #[derive(Serialize)]
 struct Message {
@@ -40,12 +41,32 @@ impl IntoResponse for APIResponse {
     }
 }
 
+/// Why one field of a request body was rejected
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FieldError {
+    /// Path of the field in the body, e.g. `members[2].email`
+    pub field: String,
+    pub message: String,
+}
+
 pub enum APIError {
     BadRequest(String),
     NotFound(String),
+    /// The request clashes with the current state, e.g. a full WIP limit
+    Conflict(String),
+    /// The client changed an outdated copy; carries the current one
+    StaleVersion(JsonValue),
     UnAuthorized,
     Forbidden,
-    MethodNotAllowed,
+    /// The path exists but not with this method; the router adds the `Allow` header
+    MethodNotAllowed(String),
+    UnsupportedMediaType(String),
+    /// The body is well-formed JSON but some fields don't fit the payload
+    UnprocessableEntity(Vec<FieldError>),
+    /// Updates of this resource must name the version they are based on
+    PreconditionRequired(String),
+    /// The instance is in maintenance mode and refuses changes for now
+    ServiceUnavailable(String),
     InternalServerError(String),
 }
 
@@ -64,9 +85,51 @@ impl IntoResponse for APIError {
                 Json(serde_json::json!({"status": "error", "detail": msg,})),
             )
                 .into_response(),
+            Self::Conflict(msg) => (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({"status": "error", "detail": msg,})),
+            )
+                .into_response(),
+            Self::StaleVersion(current) => (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({
+                    "status": "error",
+                    "detail": "The resource changed since it was read",
+                    "current": current,
+                })),
+            )
+                .into_response(),
             Self::UnAuthorized => (StatusCode::UNAUTHORIZED).into_response(),
             Self::Forbidden => (StatusCode::FORBIDDEN).into_response(),
-            Self::MethodNotAllowed => (StatusCode::METHOD_NOT_ALLOWED).into_response(),
+            Self::MethodNotAllowed(msg) => (
+                StatusCode::METHOD_NOT_ALLOWED,
+                Json(serde_json::json!({"status": "error", "detail": msg,})),
+            )
+                .into_response(),
+            Self::UnsupportedMediaType(msg) => (
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                Json(serde_json::json!({"status": "error", "detail": msg,})),
+            )
+                .into_response(),
+            Self::UnprocessableEntity(errors) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(serde_json::json!({
+                    "status": "error",
+                    "detail": "Invalid request body",
+                    "errors": errors,
+                })),
+            )
+                .into_response(),
+            Self::PreconditionRequired(msg) => (
+                StatusCode::PRECONDITION_REQUIRED,
+                Json(serde_json::json!({"status": "error", "detail": msg,})),
+            )
+                .into_response(),
+            Self::ServiceUnavailable(msg) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(serde_json::json!({"status": "error", "detail": msg,})),
+            )
+                .into_response(),
             Self::InternalServerError(msg) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(serde_json::json!({
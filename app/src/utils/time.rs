@@ -0,0 +1,105 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+
+/// Local time that day-based delays such as `tomorrow` or `next monday` land on
+const DAY_START: NaiveTime = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+
+/// Parse an IANA timezone name such as `Europe/Berlin`
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.parse::<Tz>().ok()
+}
+
+/// Render a UTC timestamp from the database as RFC 3339 in the given timezone
+pub fn format_in_timezone(timestamp: NaiveDateTime, tz: Tz) -> String {
+    timestamp.and_utc().with_timezone(&tz).to_rfc3339()
+}
+
+/// When a delay such as `30m`, `4h`, `2d`, `in 3 days`, `1w`, `tomorrow` or
+/// `next monday` ends, counted from `now`. Days and weekdays land at 9:00 in
+/// `now`'s timezone. None for anything else, zero and delays over a year.
+pub fn parse_delay(input: &str, now: DateTime<Tz>) -> Option<DateTime<Utc>> {
+    let input = input.trim().to_lowercase();
+    let input = input.strip_prefix("in ").unwrap_or(&input).trim();
+
+    if input == "tomorrow" {
+        return at_day_start(now, 1);
+    }
+    let weekday = input.strip_prefix("next ").unwrap_or(input);
+    if let Ok(weekday) = weekday.parse::<Weekday>() {
+        let days = (weekday.num_days_from_monday() + 7 - now.weekday().num_days_from_monday()) % 7;
+        // The same weekday means a week from today
+        return at_day_start(now, if days == 0 { 7 } else { days.into() });
+    }
+
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let amount: i64 = input[..split].parse().ok().filter(|amount| *amount > 0)?;
+    let delay = match input[split..].trim() {
+        "m" | "min" | "mins" | "minute" | "minutes" => Duration::try_minutes(amount)?,
+        "h" | "hr" | "hrs" | "hour" | "hours" => Duration::try_hours(amount)?,
+        "d" | "day" | "days" if amount <= 365 => return at_day_start(now, amount),
+        "w" | "wk" | "week" | "weeks" if amount <= 52 => return at_day_start(now, amount * 7),
+        _ => return None,
+    };
+    (delay <= Duration::days(365)).then(|| (now + delay).with_timezone(&Utc))
+}
+
+/// 9:00 local time `days` after `now`'s date
+fn at_day_start(now: DateTime<Tz>, days: i64) -> Option<DateTime<Utc>> {
+    let date = now.date_naive().checked_add_signed(Duration::days(days))?;
+    now.timezone()
+        .from_local_datetime(&date.and_time(DAY_START))
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wednesday 2026-03-04 14:30 in Berlin (13:30 UTC)
+    fn now() -> DateTime<Tz> {
+        chrono_tz::Europe::Berlin
+            .with_ymd_and_hms(2026, 3, 4, 14, 30, 0)
+            .unwrap()
+    }
+
+    fn utc(s: &str) -> Option<DateTime<Utc>> {
+        Some(s.parse().unwrap())
+    }
+
+    #[test]
+    fn hours_and_minutes_count_from_now() {
+        assert_eq!(parse_delay("4h", now()), utc("2026-03-04T17:30:00Z"));
+        assert_eq!(
+            parse_delay("in 30 minutes", now()),
+            utc("2026-03-04T14:00:00Z")
+        );
+    }
+
+    #[test]
+    fn days_land_at_nine_local_time() {
+        assert_eq!(parse_delay("2d", now()), utc("2026-03-06T08:00:00Z"));
+        assert_eq!(parse_delay("In 2 days", now()), utc("2026-03-06T08:00:00Z"));
+        assert_eq!(parse_delay("tomorrow", now()), utc("2026-03-05T08:00:00Z"));
+        assert_eq!(parse_delay("1w", now()), utc("2026-03-11T08:00:00Z"));
+    }
+
+    #[test]
+    fn weekdays_are_the_next_one_after_today() {
+        assert_eq!(
+            parse_delay("next monday", now()),
+            utc("2026-03-09T08:00:00Z")
+        );
+        assert_eq!(parse_delay("friday", now()), utc("2026-03-06T08:00:00Z"));
+        assert_eq!(parse_delay("wednesday", now()), utc("2026-03-11T08:00:00Z"));
+    }
+
+    #[test]
+    fn rejects_unknown_zero_and_far_delays() {
+        for input in ["", "soon", "0h", "2 fortnights", "400d", "-1d", "h"] {
+            assert_eq!(parse_delay(input, now()), None, "{}", input);
+        }
+    }
+}
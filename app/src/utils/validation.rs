@@ -0,0 +1,138 @@
+//! Field rules for request payloads, used with `#[derive(Validate)]` and checked by
+//! the `ValidatedJson` extractor before a handler runs.
+
+use std::borrow::Cow;
+
+use validator::{ValidationError, ValidationErrors, ValidationErrorsKind};
+
+use crate::utils::{response::FieldError, time::parse_timezone};
+
+fn invalid(code: &'static str, message: &'static str) -> ValidationError {
+    ValidationError::new(code).with_message(Cow::Borrowed(message))
+}
+
+/// Rejects empty and whitespace-only strings
+pub fn not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(invalid("blank", "must not be empty"));
+    }
+    Ok(())
+}
+
+/// Slack app-level tokens, used to open the Socket Mode connection
+pub fn slack_app_token(value: &str) -> Result<(), ValidationError> {
+    if !value.starts_with("xapp-") {
+        return Err(invalid("slack_app_token", "must start with 'xapp-'"));
+    }
+    Ok(())
+}
+
+/// Slack bot tokens, used for Web API calls
+pub fn slack_bot_token(value: &str) -> Result<(), ValidationError> {
+    if !value.starts_with("xoxb-") {
+        return Err(invalid("slack_bot_token", "must start with 'xoxb-'"));
+    }
+    Ok(())
+}
+
+/// Slack user tokens, used for Web API calls on a member's behalf
+pub fn slack_user_token(value: &str) -> Result<(), ValidationError> {
+    if !value.starts_with("xoxp-") {
+        return Err(invalid("slack_user_token", "must start with 'xoxp-'"));
+    }
+    Ok(())
+}
+
+/// Absolute http or https URL
+pub fn http_url(value: &str) -> Result<(), ValidationError> {
+    let valid =
+        reqwest::Url::parse(value.trim()).is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+    if !valid {
+        return Err(invalid("url", "must be an http(s) URL"));
+    }
+    Ok(())
+}
+
+/// Absolute https URL, for services that refuse plain http
+pub fn https_url(value: &str) -> Result<(), ValidationError> {
+    let valid = reqwest::Url::parse(value.trim()).is_ok_and(|url| url.scheme() == "https");
+    if !valid {
+        return Err(invalid("url", "must be an https URL"));
+    }
+    Ok(())
+}
+
+/// Like `http_url`, for fields where an empty string clears the value
+pub fn http_url_or_empty(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Ok(());
+    }
+    http_url(value)
+}
+
+/// IANA timezone name, e.g. `Europe/Berlin`
+pub fn timezone(value: &str) -> Result<(), ValidationError> {
+    if parse_timezone(value.trim()).is_none() {
+        return Err(invalid("timezone", "must be an IANA timezone name"));
+    }
+    Ok(())
+}
+
+/// Message for rules declared without one, e.g. `#[validate(email)]`
+fn default_message(error: &ValidationError) -> String {
+    let param = |name: &str| error.params.get(name).map(ToString::to_string);
+    match error.code.as_ref() {
+        "email" => "must be a valid email address".to_string(),
+        "url" => "must be a valid URL".to_string(),
+        "length" => match (param("min"), param("max")) {
+            (Some(min), Some(max)) => format!("must be between {} and {} characters", min, max),
+            (Some(min), None) => format!("must be at least {} characters", min),
+            (None, Some(max)) => format!("must be at most {} characters", max),
+            (None, None) => "has an invalid length".to_string(),
+        },
+        "range" => match (param("min"), param("max")) {
+            (Some(min), Some(max)) => format!("must be between {} and {}", min, max),
+            (Some(min), None) => format!("must be at least {}", min),
+            (None, Some(max)) => format!("must be at most {}", max),
+            (None, None) => "is out of range".to_string(),
+        },
+        code => format!("failed the {} check", code),
+    }
+}
+
+fn collect(prefix: Option<&str>, errors: &ValidationErrors, into: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        // Struct level rules report under `__all__`
+        let field = match (prefix, field.as_ref()) {
+            (Some(prefix), "__all__") => prefix.to_string(),
+            (None, "__all__") => "body".to_string(),
+            (Some(prefix), field) => format!("{}.{}", prefix, field),
+            (None, field) => field.to_string(),
+        };
+        match kind {
+            ValidationErrorsKind::Field(errors) => into.extend(errors.iter().map(|error| {
+                FieldError {
+                    field: field.clone(),
+                    message: error
+                        .message
+                        .as_ref()
+                        .map_or_else(|| default_message(error), ToString::to_string),
+                }
+            })),
+            ValidationErrorsKind::Struct(errors) => collect(Some(&field), errors, into),
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    collect(Some(&format!("{}[{}]", field, index)), errors, into);
+                }
+            }
+        }
+    }
+}
+
+/// One entry per failed rule, nested fields named by their path, e.g. `triggers[2].emoji`
+pub fn field_errors(errors: &ValidationErrors) -> Vec<FieldError> {
+    let mut fields = Vec::new();
+    collect(None, errors, &mut fields);
+    fields.sort_by(|a, b| a.field.cmp(&b.field));
+    fields
+}
@@ -0,0 +1,740 @@
+//! Authentication and authorization of every router. When adding a route, add it
+//! to `PROTECTED_ROUTES`, and to `ADMIN_ROUTES` if only admins may use it.
+
+mod common;
+
+use axum::http::{Method, StatusCode};
+use futures_util::{SinkExt, StreamExt};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use sea_orm::DbErr;
+use serde_json::{json, Value};
+use slacker::{
+    models::{hook_subscription::HookEvent, task::TaskStatus, workspace_link::WorkspaceRole},
+    repos::{
+        hook_subscriptions::HookSubscriptionsRepo, sessions::SessionsRepo, tasks::TasksRepo,
+        workspace_links::WorkspaceLinksRepo,
+    },
+    utils::jwt::{create_session_jwt, Claims},
+};
+
+use common::TestApp;
+
+/// Every route behind `require_auth`; `{workspace}` and `{task}` are filled in
+const PROTECTED_ROUTES: &[(Method, &str)] = &[
+    // tasks
+    (Method::GET, "/api/tasks"),
+    (Method::GET, "/api/tasks/mine"),
+    (Method::GET, "/api/tasks/board"),
+    (Method::GET, "/api/tasks/board/changes"),
+    (Method::GET, "/api/tasks/key/ACME-1"),
+    (Method::GET, "/api/tasks/{task}"),
+    (Method::PATCH, "/api/tasks/{task}"),
+    (Method::GET, "/api/tasks/{task}/full"),
+    (Method::POST, "/api/tasks/{task}/jira"),
+    (Method::PUT, "/api/tasks/{task}/due-date"),
+    (Method::PUT, "/api/tasks/{task}/project"),
+    (Method::POST, "/api/tasks/{task}/merge"),
+    (Method::POST, "/api/tasks/{task}/reminders"),
+    (Method::POST, "/api/tasks/from-template/missing"),
+    // workspaces
+    (Method::GET, "/api/workspaces"),
+    (Method::POST, "/api/workspaces/link"),
+    (Method::POST, "/api/workspaces/unlink"),
+    (Method::POST, "/api/workspaces/switch"),
+    (Method::GET, "/api/workspaces/active"),
+    (Method::POST, "/api/workspaces/setup"),
+    (Method::DELETE, "/api/workspaces/{workspace}"),
+    (Method::GET, "/api/workspaces/{workspace}/settings"),
+    (Method::PUT, "/api/workspaces/{workspace}/tokens"),
+    (Method::GET, "/api/workspaces/{workspace}/diagnostics"),
+    (Method::GET, "/api/workspaces/{workspace}/emojis"),
+    (Method::PUT, "/api/workspaces/{workspace}/emoji-mappings"),
+    (
+        Method::POST,
+        "/api/workspaces/{workspace}/emoji-mappings/reset",
+    ),
+    (Method::GET, "/api/workspaces/{workspace}/keyword-triggers"),
+    (Method::PUT, "/api/workspaces/{workspace}/keyword-triggers"),
+    (Method::GET, "/api/workspaces/{workspace}/wip-limits"),
+    (Method::PUT, "/api/workspaces/{workspace}/wip-limits"),
+    (
+        Method::GET,
+        "/api/workspaces/{workspace}/notification-routes",
+    ),
+    (
+        Method::PUT,
+        "/api/workspaces/{workspace}/notification-routes",
+    ),
+    (Method::PUT, "/api/workspaces/{workspace}/message-redaction"),
+    (Method::PUT, "/api/workspaces/{workspace}/status-resolution"),
+    (Method::PUT, "/api/workspaces/{workspace}/reaction-policy"),
+    (
+        Method::PUT,
+        "/api/workspaces/{workspace}/private-channel-policy",
+    ),
+    (Method::PUT, "/api/workspaces/{workspace}/locale"),
+    (Method::GET, "/api/workspaces/{workspace}/retention"),
+    (Method::PUT, "/api/workspaces/{workspace}/retention"),
+    (
+        Method::POST,
+        "/api/workspaces/{workspace}/retention/preview",
+    ),
+    (Method::GET, "/api/workspaces/{workspace}/orphaned-tasks"),
+    (
+        Method::PUT,
+        "/api/workspaces/{workspace}/orphaned-tasks/{task}",
+    ),
+    (Method::GET, "/api/workspaces/{workspace}/workload"),
+    (Method::GET, "/api/workspaces/{workspace}/workload/suggest"),
+    (Method::GET, "/api/workspaces/{workspace}/projects"),
+    (Method::POST, "/api/workspaces/{workspace}/projects"),
+    (Method::PUT, "/api/workspaces/{workspace}/projects/missing"),
+    (
+        Method::DELETE,
+        "/api/workspaces/{workspace}/projects/missing",
+    ),
+    (Method::GET, "/api/workspaces/{workspace}/task-templates"),
+    (Method::POST, "/api/workspaces/{workspace}/task-templates"),
+    (
+        Method::PUT,
+        "/api/workspaces/{workspace}/task-templates/missing",
+    ),
+    (
+        Method::DELETE,
+        "/api/workspaces/{workspace}/task-templates/missing",
+    ),
+    (Method::GET, "/api/workspaces/{workspace}/sla-policies"),
+    (Method::POST, "/api/workspaces/{workspace}/sla-policies"),
+    (
+        Method::PUT,
+        "/api/workspaces/{workspace}/sla-policies/missing",
+    ),
+    (
+        Method::DELETE,
+        "/api/workspaces/{workspace}/sla-policies/missing",
+    ),
+    (Method::GET, "/api/workspaces/{workspace}/channels"),
+    (Method::POST, "/api/workspaces/{workspace}/channels/join"),
+    (
+        Method::GET,
+        "/api/workspaces/{workspace}/notification-templates",
+    ),
+    (
+        Method::PUT,
+        "/api/workspaces/{workspace}/notification-templates",
+    ),
+    (
+        Method::POST,
+        "/api/workspaces/{workspace}/notification-templates/preview",
+    ),
+    (Method::GET, "/api/workspaces/{workspace}/channel-rules"),
+    (
+        Method::PUT,
+        "/api/workspaces/{workspace}/channel-rules/C0TEST",
+    ),
+    (
+        Method::DELETE,
+        "/api/workspaces/{workspace}/channel-rules/C0TEST",
+    ),
+    (Method::GET, "/api/workspaces/{workspace}/jira"),
+    (Method::PUT, "/api/workspaces/{workspace}/jira"),
+    (Method::DELETE, "/api/workspaces/{workspace}/jira"),
+    (Method::GET, "/api/workspaces/{workspace}/inbound-email"),
+    (Method::POST, "/api/workspaces/{workspace}/inbound-email"),
+    (Method::DELETE, "/api/workspaces/{workspace}/inbound-email"),
+    (Method::POST, "/api/workspaces/{workspace}/import"),
+    (Method::GET, "/api/workspaces/{workspace}/linear"),
+    (Method::PUT, "/api/workspaces/{workspace}/linear"),
+    (Method::DELETE, "/api/workspaces/{workspace}/linear"),
+    (Method::GET, "/api/workspaces/{workspace}/notion"),
+    (Method::PUT, "/api/workspaces/{workspace}/notion"),
+    (Method::DELETE, "/api/workspaces/{workspace}/notion"),
+    (Method::POST, "/api/workspaces/{workspace}/export/notion"),
+    (Method::GET, "/api/workspaces/{workspace}/discord"),
+    (Method::PUT, "/api/workspaces/{workspace}/discord"),
+    (Method::DELETE, "/api/workspaces/{workspace}/discord"),
+    (Method::GET, "/api/workspaces/{workspace}/teams"),
+    (Method::PUT, "/api/workspaces/{workspace}/teams"),
+    (Method::DELETE, "/api/workspaces/{workspace}/teams"),
+    (Method::GET, "/api/workspaces/{workspace}/standup"),
+    (Method::PUT, "/api/workspaces/{workspace}/standup"),
+    (Method::DELETE, "/api/workspaces/{workspace}/standup"),
+    (Method::GET, "/api/workspaces/{workspace}/business-hours"),
+    (Method::PUT, "/api/workspaces/{workspace}/business-hours"),
+    (Method::DELETE, "/api/workspaces/{workspace}/business-hours"),
+    (Method::GET, "/api/workspaces/{workspace}/github"),
+    (Method::POST, "/api/workspaces/{workspace}/github"),
+    (Method::DELETE, "/api/workspaces/{workspace}/github/missing"),
+    (Method::GET, "/api/workspaces/{workspace}/users"),
+    (Method::POST, "/api/workspaces/{workspace}/users/invite"),
+    (Method::POST, "/api/workspaces/{workspace}/users/remove"),
+    // admins
+    (Method::GET, "/api/admins"),
+    (Method::GET, "/api/admins/permissions"),
+    (Method::POST, "/api/admins/invite"),
+    (Method::POST, "/api/admins/revoke"),
+    (Method::GET, "/api/admins/audit-logs"),
+    (Method::GET, "/api/admin/bots"),
+    (Method::POST, "/api/admin/bots/{workspace}/reconnect"),
+    (Method::GET, "/api/admin/schedules"),
+    (Method::POST, "/api/admin/schedules/daily_digest/run"),
+    (Method::POST, "/api/admin/impersonate"),
+    (Method::POST, "/api/admin/reload-config"),
+    (Method::POST, "/api/admin/test-email"),
+    (Method::GET, "/api/admin/overview"),
+    (Method::GET, "/api/admin/metrics"),
+    (
+        Method::GET,
+        "/api/admin/export/messages?workspace={workspace}",
+    ),
+    (Method::POST, "/api/admin/import?workspace={workspace}"),
+    (Method::GET, "/api/admin/backups"),
+    (Method::POST, "/api/admin/backups"),
+    (Method::POST, "/api/admin/backups/restore"),
+    (Method::GET, "/api/admin/bot-events"),
+    (Method::POST, "/api/admin/bot-events/missing/replay"),
+    (Method::GET, "/api/admin/maintenance"),
+    (Method::POST, "/api/admin/maintenance"),
+    // me
+    (Method::GET, "/api/me"),
+    (Method::PATCH, "/api/me"),
+    (Method::GET, "/api/me/export"),
+    (Method::POST, "/api/me/calendar-feed"),
+    (Method::DELETE, "/api/me/calendar-feed"),
+    (Method::GET, "/api/me/notifications"),
+    (Method::PUT, "/api/me/notifications"),
+    (Method::GET, "/api/me/views"),
+    (Method::POST, "/api/me/views"),
+    (Method::PUT, "/api/me/views/missing"),
+    (Method::DELETE, "/api/me/views/missing"),
+    (Method::GET, "/api/me/reminders"),
+    (Method::DELETE, "/api/me/reminders/missing"),
+    (Method::GET, "/api/me/tasks/all"),
+    (Method::GET, "/api/me/availability"),
+    (Method::POST, "/api/me/availability"),
+    (Method::DELETE, "/api/me/availability/missing"),
+    (Method::GET, "/api/me/sessions"),
+    (Method::DELETE, "/api/me/sessions/missing"),
+    (Method::GET, "/api/me/slack-status"),
+    (Method::PUT, "/api/me/slack-status/missing"),
+    (Method::DELETE, "/api/me/slack-status/missing"),
+    (Method::GET, "/api/auth/me"),
+    // hooks
+    (Method::GET, "/api/hooks"),
+    (Method::POST, "/api/hooks/subscribe"),
+    (Method::GET, "/api/hooks/sample"),
+    (Method::DELETE, "/api/hooks/missing"),
+    (Method::POST, "/api/hooks/missing/test"),
+    (Method::POST, "/api/hooks/missing/secret/rotate"),
+];
+
+/// Routes that answer 403 to members who are not workspace admins. All of them
+/// check permissions before reading a body, so none is sent.
+const ADMIN_ROUTES: &[(Method, &str)] = &[
+    (Method::GET, "/api/admins"),
+    (Method::GET, "/api/admins/audit-logs"),
+    (Method::GET, "/api/admin/bots"),
+    (Method::POST, "/api/admin/bots/{workspace}/reconnect"),
+    (Method::GET, "/api/admin/schedules"),
+    (Method::POST, "/api/admin/schedules/daily_digest/run"),
+    (Method::POST, "/api/admin/reload-config"),
+    (Method::POST, "/api/admin/test-email"),
+    (Method::GET, "/api/admin/overview"),
+    (Method::GET, "/api/admin/metrics"),
+    (
+        Method::GET,
+        "/api/admin/export/messages?workspace={workspace}",
+    ),
+    (Method::POST, "/api/admin/import?workspace={workspace}"),
+    (Method::GET, "/api/admin/backups"),
+    (Method::POST, "/api/admin/backups"),
+    (Method::GET, "/api/admin/bot-events"),
+    (Method::POST, "/api/admin/bot-events/missing/replay"),
+    (Method::GET, "/api/admin/maintenance"),
+    (Method::GET, "/api/workspaces/{workspace}/diagnostics"),
+    (Method::GET, "/api/workspaces/{workspace}/keyword-triggers"),
+    (Method::GET, "/api/workspaces/{workspace}/wip-limits"),
+    (
+        Method::GET,
+        "/api/workspaces/{workspace}/notification-routes",
+    ),
+    (Method::GET, "/api/workspaces/{workspace}/retention"),
+    (Method::GET, "/api/workspaces/{workspace}/orphaned-tasks"),
+    (Method::GET, "/api/workspaces/{workspace}/workload"),
+    (Method::GET, "/api/workspaces/{workspace}/workload/suggest"),
+    (
+        Method::DELETE,
+        "/api/workspaces/{workspace}/projects/missing",
+    ),
+    (
+        Method::DELETE,
+        "/api/workspaces/{workspace}/task-templates/missing",
+    ),
+    (
+        Method::DELETE,
+        "/api/workspaces/{workspace}/sla-policies/missing",
+    ),
+    (Method::GET, "/api/workspaces/{workspace}/channels"),
+    (
+        Method::GET,
+        "/api/workspaces/{workspace}/notification-templates",
+    ),
+    (Method::GET, "/api/workspaces/{workspace}/channel-rules"),
+    (
+        Method::DELETE,
+        "/api/workspaces/{workspace}/channel-rules/C0TEST",
+    ),
+    (Method::GET, "/api/workspaces/{workspace}/jira"),
+    (Method::DELETE, "/api/workspaces/{workspace}/jira"),
+    (Method::GET, "/api/workspaces/{workspace}/inbound-email"),
+    (Method::DELETE, "/api/workspaces/{workspace}/inbound-email"),
+    (Method::POST, "/api/workspaces/{workspace}/import"),
+    (Method::GET, "/api/workspaces/{workspace}/linear"),
+    (Method::DELETE, "/api/workspaces/{workspace}/linear"),
+    (Method::GET, "/api/workspaces/{workspace}/notion"),
+    (Method::DELETE, "/api/workspaces/{workspace}/notion"),
+    (Method::GET, "/api/workspaces/{workspace}/discord"),
+    (Method::DELETE, "/api/workspaces/{workspace}/discord"),
+    (Method::GET, "/api/workspaces/{workspace}/teams"),
+    (Method::DELETE, "/api/workspaces/{workspace}/teams"),
+    (Method::GET, "/api/workspaces/{workspace}/standup"),
+    (Method::DELETE, "/api/workspaces/{workspace}/standup"),
+    (Method::GET, "/api/workspaces/{workspace}/business-hours"),
+    (Method::DELETE, "/api/workspaces/{workspace}/business-hours"),
+    (Method::GET, "/api/workspaces/{workspace}/github"),
+    (Method::DELETE, "/api/workspaces/{workspace}/github/missing"),
+];
+
+fn fill(path: &str, workspace: &str, task: &str) -> String {
+    path.replace("{workspace}", workspace)
+        .replace("{task}", task)
+}
+
+#[tokio::test]
+async fn protected_routes_require_a_token() {
+    let app = TestApp::spawn().await;
+
+    for (method, path) in PROTECTED_ROUTES {
+        let uri = fill(path, "acme", "missing");
+        let (status, _) = app.request(method.clone(), &uri, None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{} {}", method, uri);
+    }
+}
+
+#[tokio::test]
+async fn protected_routes_reject_invalid_tokens() {
+    let app = TestApp::spawn().await;
+    let person = app.person("mallory").await;
+    let session = SessionsRepo::new(app.state.database.clone())
+        .create(&person.id, None, None, 1)
+        .await
+        .unwrap();
+    let forged = create_session_jwt(
+        person.email.clone(),
+        person.id.clone(),
+        session.id.clone(),
+        "wrong-secret",
+        1,
+    )
+    .unwrap();
+    let expired = create_session_jwt(
+        person.email.clone(),
+        person.id.clone(),
+        session.id,
+        &app.state.config.jwt.secret,
+        -1,
+    )
+    .unwrap();
+    // Signed with the right secret, but not issued by any sign-in
+    let sessionless = encode(
+        &Header::default(),
+        &Claims {
+            sub: person.email.clone(),
+            person_id: person.id.clone(),
+            exp: chrono::Utc::now().timestamp() + 3600,
+            iat: chrono::Utc::now().timestamp(),
+            impersonated_by: None,
+            sid: None,
+        },
+        &EncodingKey::from_secret(app.state.config.jwt.secret.as_bytes()),
+    )
+    .unwrap();
+
+    for token in [
+        "not-a-jwt",
+        forged.as_str(),
+        expired.as_str(),
+        sessionless.as_str(),
+    ] {
+        for (method, path) in PROTECTED_ROUTES {
+            let uri = fill(path, "acme", "missing");
+            let (status, _) = app.request(method.clone(), &uri, Some(token), None).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED, "{} {}", method, uri);
+        }
+    }
+}
+
+#[tokio::test]
+async fn tokens_of_unknown_people_are_rejected() {
+    let app = TestApp::spawn().await;
+    let token = create_session_jwt(
+        "ghost@example.com".to_string(),
+        "ghost".to_string(),
+        "ghost-session".to_string(),
+        &app.state.config.jwt.secret,
+        1,
+    )
+    .unwrap();
+
+    let (status, _) = app
+        .request(Method::GET, "/api/tasks/mine", Some(&token), None)
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn public_routes_need_no_token() {
+    let app = TestApp::spawn().await;
+
+    for uri in ["/api/health", "/api/readyz", "/api/auth/providers"] {
+        let (status, _) = app.request(Method::GET, uri, None, None).await;
+        assert_eq!(status, StatusCode::OK, "GET {}", uri);
+    }
+}
+
+#[tokio::test]
+async fn admin_routes_forbid_members() {
+    let app = TestApp::spawn().await;
+    let workspace = app.workspace("acme").await;
+    let member = app.person("member").await;
+    app.link(&member, &workspace).await;
+    let token = app.token(&member).await;
+
+    for (method, path) in ADMIN_ROUTES {
+        let uri = fill(path, &workspace.name, "missing");
+        let (status, _) = app.request(method.clone(), &uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{} {}", method, uri);
+    }
+}
+
+#[tokio::test]
+async fn admin_routes_allow_the_super_admin() {
+    let app = TestApp::spawn().await;
+    let workspace = app.workspace("acme").await;
+    let token = app.token(&app.admin().await).await;
+
+    for path in [
+        "/api/admins",
+        "/api/admin/bots",
+        "/api/admin/schedules",
+        "/api/admin/maintenance",
+        "/api/workspaces/{workspace}/channel-rules",
+        "/api/workspaces/{workspace}/orphaned-tasks",
+        "/api/workspaces/{workspace}/workload",
+    ] {
+        let uri = fill(path, &workspace.name, "missing");
+        let (status, _) = app.request(Method::GET, &uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::OK, "GET {}", uri);
+    }
+}
+
+#[tokio::test]
+async fn only_people_involved_in_a_task_can_update_it() {
+    let app = TestApp::spawn().await;
+    let workspace = app.workspace("acme").await;
+    let assignee = app.person("assignee").await;
+    let reactor = app.person("reactor").await;
+    let outsider = app.person("outsider").await;
+    for person in [&assignee, &reactor, &outsider] {
+        app.link(person, &workspace).await;
+    }
+    let task = app
+        .task(&assignee, &workspace)
+        .assigned_by(&reactor)
+        .status(TaskStatus::InProgress)
+        .create()
+        .await;
+    let uri = format!("/api/tasks/{}", task.id);
+
+    let (status, _) = app
+        .request(
+            Method::PATCH,
+            &uri,
+            Some(&app.token(&outsider).await),
+            Some(json!({"status": "Blocked"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let mut version = task.version;
+    for (person, status) in [(&assignee, "Blocked"), (&reactor, "InProgress")] {
+        let (code, body) = app
+            .request(
+                Method::PATCH,
+                &uri,
+                Some(&app.token(person).await),
+                Some(json!({ "status": status, "version": version })),
+            )
+            .await;
+        assert_eq!(code, StatusCode::OK);
+        assert_eq!(body["status"], status);
+        version = body["version"].as_i64().unwrap() as i32;
+    }
+
+    let (code, body) = app
+        .request(
+            Method::PATCH,
+            &uri,
+            Some(&app.token(&assignee).await),
+            Some(json!({"status": "Completed", "version": task.version})),
+        )
+        .await;
+    assert_eq!(code, StatusCode::CONFLICT);
+    assert_eq!(body["current"]["status"], "InProgress");
+    assert_eq!(body["current"]["version"], version);
+
+    let (code, _) = app
+        .request(
+            Method::PATCH,
+            &uri,
+            Some(&app.token(&assignee).await),
+            Some(json!({"status": "Completed"})),
+        )
+        .await;
+    assert_eq!(code, StatusCode::PRECONDITION_REQUIRED);
+}
+
+#[tokio::test]
+async fn status_changes_based_on_an_old_version_are_refused() {
+    let app = TestApp::spawn().await;
+    let workspace = app.workspace("acme").await;
+    let assignee = app.person("assignee").await;
+    app.link(&assignee, &workspace).await;
+    let task = app.task(&assignee, &workspace).create().await;
+
+    let tasks_repo = TasksRepo::new(app.state.database.clone());
+    let moved = tasks_repo
+        .change_status(task.id.clone(), TaskStatus::Blocked, Some(task.version))
+        .await
+        .unwrap();
+    assert_eq!(moved.version, task.version + 1);
+
+    let stale = tasks_repo
+        .change_status(task.id.clone(), TaskStatus::Completed, Some(task.version))
+        .await;
+    assert!(matches!(stale, Err(DbErr::RecordNotUpdated)));
+    let current = tasks_repo.get(task.id.clone()).await.unwrap();
+    assert_eq!(current.status, TaskStatus::Blocked);
+    assert_eq!(current.version, moved.version);
+}
+
+#[tokio::test]
+async fn viewers_see_the_board_but_cannot_change_tasks() {
+    let app = TestApp::spawn().await;
+    let workspace = app.workspace("acme").await;
+    let assignee = app.person("assignee").await;
+    let viewer = app.person("viewer").await;
+    app.link(&assignee, &workspace).await;
+    app.link(&viewer, &workspace).await;
+    WorkspaceLinksRepo::new(app.state.database.clone())
+        .set_role(
+            viewer.id.clone(),
+            workspace.id.clone(),
+            WorkspaceRole::Viewer,
+        )
+        .await
+        .unwrap();
+    let task = app.task(&assignee, &workspace).create().await;
+    let token = app.token(&viewer).await;
+
+    let (status, board) = app
+        .request(Method::GET, "/api/tasks/board", Some(&token), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(board["in_progress"][0]["id"], task.id.as_str());
+
+    let (status, _) = app
+        .request(
+            Method::PATCH,
+            &format!("/api/tasks/{}", task.id),
+            Some(&token),
+            Some(json!({"status": "Blocked", "version": task.version})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let uri = fill("/api/workspaces/{workspace}/workload", &workspace.name, "");
+    let (status, _) = app.request(Method::GET, &uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, permissions) = app
+        .request(Method::GET, "/api/admins/permissions", Some(&token), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(permissions["role"], "viewer");
+    assert_eq!(permissions["can_edit_tasks"], false);
+}
+
+#[tokio::test]
+async fn viewers_cannot_change_a_workspace_they_view_from_another_active_one() {
+    let app = TestApp::spawn().await;
+    let home = app.workspace("home").await;
+    let acme = app.workspace("acme").await;
+    let assignee = app.person("assignee").await;
+    let viewer = app.person("viewer").await;
+    app.link(&assignee, &acme).await;
+    // Home is linked first and stays the viewer's active workspace
+    app.link(&viewer, &home).await;
+    app.link(&viewer, &acme).await;
+    WorkspaceLinksRepo::new(app.state.database.clone())
+        .set_role(viewer.id.clone(), acme.id.clone(), WorkspaceRole::Viewer)
+        .await
+        .unwrap();
+    let viewed = app
+        .task(&assignee, &acme)
+        .assigned_by(&viewer)
+        .create()
+        .await;
+    let own = app.task(&viewer, &home).create().await;
+    let hook = HookSubscriptionsRepo::new(app.state.database.clone())
+        .create(
+            &viewer.id,
+            &acme.id,
+            HookEvent::TaskCreated,
+            "https://hooks.example.com/acme",
+            "secret".to_string(),
+        )
+        .await
+        .unwrap();
+    let token = app.token(&viewer).await;
+
+    let subscribe = |workspace: &str| {
+        json!({
+            "target_url": "https://hooks.example.com/new",
+            "event": "task_created",
+            "workspace": workspace,
+        })
+    };
+    let refused = [
+        (
+            Method::PATCH,
+            format!("/api/tasks/{}", viewed.id),
+            Some(json!({"status": "Blocked", "version": viewed.version})),
+        ),
+        (
+            Method::PUT,
+            format!("/api/tasks/{}/due-date", viewed.id),
+            Some(json!({ "due_at": null })),
+        ),
+        (
+            Method::POST,
+            "/api/workspaces/acme/projects".to_string(),
+            Some(json!({"name": "Launch"})),
+        ),
+        (
+            Method::POST,
+            "/api/hooks/subscribe".to_string(),
+            Some(subscribe("acme")),
+        ),
+        (
+            Method::POST,
+            format!("/api/hooks/{}/secret/rotate", hook.id),
+            None,
+        ),
+    ];
+    for (method, uri, body) in refused {
+        let (status, _) = app.request(method.clone(), &uri, Some(&token), body).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{} {}", method, uri);
+    }
+
+    // Their own workspace is theirs to change
+    let (status, _) = app
+        .request(
+            Method::PATCH,
+            &format!("/api/tasks/{}", own.id),
+            Some(&token),
+            Some(json!({"status": "Blocked", "version": own.version})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app
+        .request(
+            Method::POST,
+            "/api/hooks/subscribe",
+            Some(&token),
+            Some(subscribe("home")),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn admins_only_follow_people_of_workspaces_they_share() {
+    let app = TestApp::spawn().await;
+    let admin = app.admin().await;
+    let acme = app.workspace("acme").await;
+    let other = app.workspace("other").await;
+    app.link(&admin, &acme).await;
+    let colleague = app.person("colleague").await;
+    app.link(&colleague, &acme).await;
+    let stranger = app.person("stranger").await;
+    app.link(&stranger, &other).await;
+
+    let address = app.serve().await;
+    let url = format!("ws://{}/api/ws?token={}", address, app.token(&admin).await);
+    let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+    for (person, reply) in [(&colleague, "subscribed"), (&stranger, "error")] {
+        let subscribe = json!({"action": "subscribe", "person_id": person.id});
+        socket
+            .send(tokio_tungstenite::tungstenite::Message::text(
+                subscribe.to_string(),
+            ))
+            .await
+            .unwrap();
+        // Skip the heartbeat pings
+        let answer: Value = loop {
+            let message = socket.next().await.unwrap().unwrap();
+            if message.is_text() {
+                break serde_json::from_str(message.to_text().unwrap()).unwrap();
+            }
+        };
+        assert_eq!(answer["type"], reply, "subscribing to {}", person.name);
+    }
+}
+
+#[tokio::test]
+async fn revoked_sessions_are_signed_out() {
+    let app = TestApp::spawn().await;
+    let person = app.person("laptop").await;
+    let session = SessionsRepo::new(app.state.database.clone())
+        .create(&person.id, Some("Firefox".to_string()), None, 1)
+        .await
+        .unwrap();
+    let token = create_session_jwt(
+        person.email.clone(),
+        person.id.clone(),
+        session.id.clone(),
+        &app.state.config.jwt.secret,
+        1,
+    )
+    .unwrap();
+
+    let (status, body) = app
+        .request(Method::GET, "/api/me/sessions", Some(&token), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["sessions"][0]["id"], session.id.as_str());
+    assert_eq!(body["sessions"][0]["current"], true);
+
+    let uri = format!("/api/me/sessions/{}", session.id);
+    let (status, _) = app
+        .request(Method::DELETE, &uri, Some(&app.token(&person).await), None)
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, _) = app
+        .request(Method::GET, "/api/me/sessions", Some(&token), None)
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
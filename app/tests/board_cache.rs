@@ -0,0 +1,64 @@
+//! Conditional requests for the task board: unchanged boards answer 304 until a
+//! task changes.
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+};
+use serde_json::json;
+use tower::ServiceExt;
+
+use common::TestApp;
+
+async fn get_board(app: &TestApp, token: &str, etag: Option<&str>) -> (StatusCode, String) {
+    let mut builder = Request::builder()
+        .method(Method::GET)
+        .uri("/api/tasks/board")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token));
+    if let Some(etag) = etag {
+        builder = builder.header(header::IF_NONE_MATCH, etag);
+    }
+    let response = app
+        .router
+        .clone()
+        .oneshot(builder.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let etag = response.headers()[header::ETAG]
+        .to_str()
+        .unwrap()
+        .to_string();
+    (response.status(), etag)
+}
+
+#[tokio::test]
+async fn unchanged_boards_are_not_sent_again() {
+    let app = TestApp::spawn().await;
+    let workspace = app.workspace("acme").await;
+    let person = app.person("member").await;
+    app.link(&person, &workspace).await;
+    let task = app.task(&person, &workspace).create().await;
+    let token = app.token(&person).await;
+
+    let (status, etag) = get_board(&app, &token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, same) = get_board(&app, &token, Some(&etag)).await;
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+    assert_eq!(same, etag);
+
+    let (status, _) = app
+        .request(
+            Method::PATCH,
+            &format!("/api/tasks/{}", task.id),
+            Some(&token),
+            Some(json!({"status": "Blocked", "version": task.version})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, changed) = get_board(&app, &token, Some(&etag)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(changed, etag);
+}
@@ -0,0 +1,101 @@
+//! Polling the task board for changes instead of loading it again.
+
+mod common;
+
+use axum::http::{Method, StatusCode};
+use serde_json::{json, Value};
+
+use common::TestApp;
+
+fn ids(list: &Value) -> Vec<&str> {
+    list.as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item.as_str().or(item["id"].as_str()).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn changes_name_updated_and_deleted_tasks() {
+    let app = TestApp::spawn().await;
+    let workspace = app.workspace("acme").await;
+    let person = app.person("member").await;
+    app.link(&person, &workspace).await;
+    let task = app
+        .task(&person, &workspace)
+        .assigned_by(&person)
+        .create()
+        .await;
+    let duplicate = app
+        .task(&person, &workspace)
+        .assigned_by(&person)
+        .create()
+        .await;
+    let token = app.token(&person).await;
+
+    let (status, board) = app
+        .request(Method::GET, "/api/tasks/board", Some(&token), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let changes_since = |cursor: &Value| {
+        format!(
+            "/api/tasks/board/changes?since={}",
+            cursor.as_str().unwrap()
+        )
+    };
+
+    let (status, changes) = app
+        .request(
+            Method::GET,
+            &changes_since(&board["cursor"]),
+            Some(&token),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(ids(&changes["tasks"]).is_empty());
+    assert!(ids(&changes["removed"]).is_empty());
+
+    let uri = format!("/api/tasks/{}", task.id);
+    let (status, _) = app
+        .request(
+            Method::PATCH,
+            &uri,
+            Some(&token),
+            Some(json!({"status": "Blocked", "version": task.version})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app
+        .request(
+            Method::POST,
+            &format!("{}/merge", uri),
+            Some(&token),
+            Some(json!({"duplicate_task_id": duplicate.id})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, changes) = app
+        .request(
+            Method::GET,
+            &changes_since(&changes["cursor"]),
+            Some(&token),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&changes["tasks"]), vec![task.id.as_str()]);
+    assert_eq!(changes["tasks"][0]["status"], "Blocked");
+    assert_eq!(ids(&changes["removed"]), vec![duplicate.id.as_str()]);
+
+    let (status, _) = app
+        .request(
+            Method::GET,
+            "/api/tasks/board/changes?since=garbage",
+            Some(&token),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,292 @@
+//! Shared harness for the integration tests: a fresh app on a throwaway database,
+//! fixture builders and a request helper.
+//!
+//! The database is, in order of preference, `TEST_DATABASE_URL` (it gets
+//! migrated and filled with fixtures, never point it at real data), an in-memory
+//! SQLite database when built with `--features sqlite`, or a Postgres container
+//! started with testcontainers, which needs Docker.
+
+#![allow(dead_code)]
+
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Method, Request, StatusCode},
+    Router,
+};
+use serde_json::{json, Value};
+use slacker::{
+    config::config::Config,
+    core::{server::create_server, state::AppState},
+    models::{
+        person::Model as Person,
+        task::{Model as Task, TaskStatus},
+        workspace::Model as Workspace,
+        workspace_link::Model as WorkspaceLink,
+    },
+    repos::{
+        messages::MessagesRepo, persons::PersonsRepo, sessions::SessionsRepo, tasks::TasksRepo,
+        workspace_links::WorkspaceLinksRepo, workspaces::WorkspacesRepo,
+    },
+    utils::{crypto::generate_uuid, jwt::create_session_jwt},
+};
+use testcontainers_modules::{
+    postgres::Postgres,
+    testcontainers::{runners::AsyncRunner, ContainerAsync},
+};
+use tower::ServiceExt;
+
+pub struct TestApp {
+    pub router: Router,
+    pub state: Arc<AppState>,
+    /// Stopped and removed when the app is dropped
+    _container: Option<ContainerAsync<Postgres>>,
+}
+
+impl TestApp {
+    /// Build the app on a migrated, empty database
+    pub async fn spawn() -> Self {
+        let (database_url, container) = match std::env::var("TEST_DATABASE_URL") {
+            Ok(url) => (url, None),
+            Err(_) if cfg!(feature = "sqlite") => ("sqlite::memory:".to_string(), None),
+            Err(_) => {
+                let container = Postgres::default()
+                    .start()
+                    .await
+                    .expect("Failed to start Postgres, is Docker running?");
+                let host = container.get_host().await.expect("Container host");
+                let port = container
+                    .get_host_port_ipv4(5432)
+                    .await
+                    .expect("Container port");
+                let url = format!("postgres://postgres:postgres@{}:{}/postgres", host, port);
+                (url, Some(container))
+            }
+        };
+
+        let config: Config = serde_json::from_value(json!({
+            "database_url": database_url,
+            "max_connections": 5,
+            "min_connections": 1,
+            "google": {
+                "client_id": "test-client",
+                "client_secret": "test-secret",
+                "redirect_uri": "http://localhost/api/auth/google/callback",
+            },
+            "slack": {
+                "client_id": "test-slack-client",
+                "client_secret": "test-slack-secret",
+                "redirect_uri": "http://localhost/api/auth/slack/callback",
+            },
+            "jwt": { "secret": "test-jwt-secret" },
+            // Unique so apps sharing TEST_DATABASE_URL don't see each other's admin
+            "admin_email": format!("admin-{}@example.com", generate_uuid().to_lowercase()),
+        }))
+        .expect("Invalid test config");
+
+        let (router, state) = create_server(config).await.expect("Failed to build app");
+        Self {
+            router,
+            state,
+            _container: container,
+        }
+    }
+
+    /// Bearer token for a person, as issued on login with a session of its own
+    pub async fn token(&self, person: &Person) -> String {
+        let session = SessionsRepo::new(self.state.database.clone())
+            .create(&person.id, None, None, 1)
+            .await
+            .expect("Failed to create session");
+        create_session_jwt(
+            person.email.clone(),
+            person.id.clone(),
+            session.id,
+            &self.state.config.jwt.secret,
+            1,
+        )
+        .expect("Failed to create token")
+    }
+
+    /// Serve the app on a local port, for clients such as WebSockets that need a
+    /// real connection
+    pub async fn serve(&self) -> SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind a port");
+        let address = listener.local_addr().expect("Listener address");
+        let router = self.router.clone();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        address
+    }
+
+    /// Send a request through the router, returns the status and the JSON body
+    /// (Null when the body is empty or not JSON)
+    pub async fn request(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header("Authorization", format!("Bearer {}", token));
+        }
+        let request = match body {
+            Some(body) => builder
+                .header("Content-Type", "application/json")
+                .body(Body::from(body.to_string())),
+            None => builder.body(Body::empty()),
+        }
+        .expect("Invalid request");
+
+        let response = self
+            .router
+            .clone()
+            .oneshot(request)
+            .await
+            .expect("Router failed");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to read body");
+        let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        (status, json)
+    }
+
+    /// A person with a unique email derived from `name`
+    pub async fn person(&self, name: &str) -> Person {
+        let email = format!("{}-{}@example.com", name, generate_uuid().to_lowercase());
+        self.create_person(name, email).await
+    }
+
+    /// The super admin from the config
+    pub async fn admin(&self) -> Person {
+        let email = self.state.config.admin_email.clone();
+        self.create_person("admin", email).await
+    }
+
+    async fn create_person(&self, name: &str, email: String) -> Person {
+        PersonsRepo::new(self.state.database.clone())
+            .create(name.to_string(), false, generate_uuid(), email)
+            .await
+            .expect("Failed to create person")
+    }
+
+    /// An active workspace with a unique name derived from `name`
+    pub async fn workspace(&self, name: &str) -> Workspace {
+        WorkspacesRepo::new(self.state.database.clone())
+            .create(
+                format!("{}-{}", name, generate_uuid().to_lowercase()),
+                None,
+                None,
+            )
+            .await
+            .expect("Failed to create workspace")
+    }
+
+    /// Link a person to a workspace under a fresh Slack member id
+    pub async fn link(&self, person: &Person, workspace: &Workspace) -> WorkspaceLink {
+        WorkspaceLinksRepo::new(self.state.database.clone())
+            .link_workspace(
+                person.id.clone(),
+                workspace.id.clone(),
+                format!("U{}", generate_uuid().to_uppercase()),
+            )
+            .await
+            .expect("Failed to link workspace")
+    }
+
+    /// A person linked to a workspace under a fresh Slack member id, which is also
+    /// the person's external id as the bot looks authors and reactors up by it
+    pub async fn slack_member(&self, name: &str, workspace: &Workspace) -> Person {
+        let slack_id = format!("U{}", generate_uuid().to_uppercase());
+        let email = format!("{}-{}@example.com", name, generate_uuid().to_lowercase());
+        let person = PersonsRepo::new(self.state.database.clone())
+            .create(name.to_string(), false, slack_id.clone(), email)
+            .await
+            .expect("Failed to create person");
+        WorkspaceLinksRepo::new(self.state.database.clone())
+            .link_workspace(person.id.clone(), workspace.id.clone(), slack_id)
+            .await
+            .expect("Failed to link workspace");
+        person
+    }
+
+    /// Start building a task assigned to `assignee`
+    pub fn task<'a>(&'a self, assignee: &'a Person, workspace: &'a Workspace) -> TaskBuilder<'a> {
+        TaskBuilder {
+            app: self,
+            assignee,
+            workspace,
+            assigned_by: None,
+            status: TaskStatus::InProgress,
+            content: "Test task".to_string(),
+            channel: "C0TEST".to_string(),
+        }
+    }
+}
+
+/// Task fixture with its source message; everything but the assignee and the
+/// workspace has a default
+pub struct TaskBuilder<'a> {
+    app: &'a TestApp,
+    assignee: &'a Person,
+    workspace: &'a Workspace,
+    assigned_by: Option<&'a Person>,
+    status: TaskStatus,
+    content: String,
+    channel: String,
+}
+
+impl<'a> TaskBuilder<'a> {
+    pub fn assigned_by(mut self, person: &'a Person) -> Self {
+        self.assigned_by = Some(person);
+        self
+    }
+
+    pub fn status(mut self, status: TaskStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn content(mut self, content: &str) -> Self {
+        self.content = content.to_string();
+        self
+    }
+
+    pub fn channel(mut self, channel: &str) -> Self {
+        self.channel = channel.to_string();
+        self
+    }
+
+    pub async fn create(self) -> Task {
+        let db = self.app.state.database.clone();
+        let now = chrono::Utc::now();
+        let message = MessagesRepo::new(db.clone(), self.app.state.message_encryption.clone())
+            .create(
+                self.content,
+                generate_uuid(),
+                self.channel,
+                format!("{}.000100", now.timestamp()),
+                self.assignee,
+                &self.workspace.id,
+            )
+            .await
+            .expect("Failed to create message");
+
+        TasksRepo::new(db)
+            .create(
+                self.status,
+                self.assignee.clone(),
+                self.assigned_by.cloned(),
+                now.naive_utc(),
+                message,
+                &self.workspace.id,
+            )
+            .await
+            .expect("Failed to create task")
+    }
+}
@@ -0,0 +1,94 @@
+//! Error responses of the API router itself: unknown paths, wrong methods and
+//! bodies that don't fit the payload.
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+};
+use serde_json::json;
+use tower::ServiceExt;
+
+use common::TestApp;
+
+#[tokio::test]
+async fn unknown_api_paths_are_not_found() {
+    let app = TestApp::spawn().await;
+
+    let (status, body) = app.request(Method::GET, "/api/nope", None, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["status"], "error");
+}
+
+#[tokio::test]
+async fn wrong_methods_list_the_allowed_ones() {
+    let app = TestApp::spawn().await;
+
+    let request = Request::builder()
+        .method(Method::DELETE)
+        .uri("/api/health")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.router.clone().oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    let allow = response.headers()[header::ALLOW].to_str().unwrap();
+    assert!(allow.contains("GET"), "Allow: {}", allow);
+}
+
+#[tokio::test]
+async fn bodies_that_do_not_fit_name_the_field() {
+    let app = TestApp::spawn().await;
+    let workspace = app.workspace("acme").await;
+    let person = app.person("member").await;
+    app.link(&person, &workspace).await;
+    let task = app.task(&person, &workspace).create().await;
+    let uri = format!("/api/tasks/{}", task.id);
+    let token = app.token(&person).await;
+
+    let (status, body) = app
+        .request(
+            Method::PATCH,
+            &uri,
+            Some(&token),
+            Some(json!({"status": "Done"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["errors"][0]["field"], "status");
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            &format!("{}/merge", uri),
+            Some(&token),
+            Some(json!({})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["errors"][0]["field"], "duplicate_task_id");
+}
+
+#[tokio::test]
+async fn invalid_fields_are_all_listed() {
+    let app = TestApp::spawn().await;
+    let token = app.token(&app.admin().await).await;
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/workspaces/setup",
+            Some(&token),
+            Some(json!({"workspace_name": " ", "app_token": "xoxb-1", "bot_token": "xapp-1"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let fields: Vec<&str> = body["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error| error["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, ["app_token", "bot_token", "workspace_name"]);
+}
@@ -0,0 +1,170 @@
+//! The bot and the initial sync against recorded Slack Web API responses.
+
+mod common;
+
+use std::sync::Arc;
+
+use serde_json::json;
+use slacker::{
+    models::{
+        bot_event::{BotEventOutcome, Model as BotEvent},
+        task::TaskStatus,
+    },
+    repos::{messages::MessagesRepo, tasks::TasksRepo},
+    sockets::{
+        slack_api::RecordedSlackApi,
+        slack_bot::{InitialSyncer, SlackBot},
+    },
+    utils::crypto::generate_uuid,
+};
+
+use common::TestApp;
+
+const REACTION_ADDED: &str = include_str!("fixtures/slack/reaction_added.json");
+const TS: &str = "1718000000.000100";
+
+/// A channel id of its own, message external ids are unique across workspaces
+fn channel() -> String {
+    format!("C{}", generate_uuid().to_uppercase())
+}
+
+#[tokio::test]
+async fn reaction_added_creates_a_task_for_the_author() {
+    let app = TestApp::spawn().await;
+    let workspace = app.workspace("acme").await;
+    let author = app.slack_member("author", &workspace).await;
+    let reactor = app.slack_member("reactor", &workspace).await;
+    let channel = channel();
+    let recordings = REACTION_ADDED
+        .replace("U0AUTHOR", &author.external_id)
+        .replace("U0REACTOR", &reactor.external_id)
+        .replace("C0ENG", &channel);
+    let api = Arc::new(RecordedSlackApi::from_json(&recordings).unwrap());
+    let bot = SlackBot::new(
+        &workspace,
+        "xapp-test".to_string(),
+        "xoxb-test".to_string(),
+        &app.state,
+    )
+    .with_slack_api(api.clone());
+
+    let event = bot
+        .replay(&BotEvent {
+            id: generate_uuid(),
+            workspace_id: workspace.id.clone(),
+            envelope_type: "events_api".to_string(),
+            event_type: "reaction_added".to_string(),
+            subtype: None,
+            channel: Some(channel.clone()),
+            ts: Some(TS.to_string()),
+            user_id: Some(reactor.external_id.clone()),
+            reaction: Some("eyes".to_string()),
+            outcome: BotEventOutcome::Handled,
+            error: None,
+            created_at: chrono::Utc::now().naive_utc(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(event.outcome, BotEventOutcome::Handled, "{:?}", event.error);
+
+    let db = app.state.database.clone();
+    let message = MessagesRepo::new(db.clone(), app.state.message_encryption.clone())
+        .get_message_by_external_id(format!("slack:{}:{}", channel, TS))
+        .await
+        .unwrap();
+    assert_eq!(message.content, "Ship the release notes");
+    let task = TasksRepo::new(db)
+        .get_task_by_message_id(message.id)
+        .await
+        .unwrap();
+    assert_eq!(task.status, TaskStatus::InProgress);
+    assert_eq!(task.assigned_to, author.id);
+    assert_eq!(task.assigned_by, Some(reactor.id));
+
+    assert_eq!(api.calls_to("conversations.history").len(), 1);
+    assert_eq!(
+        api.calls_to("reactions.get"),
+        vec![json!({ "channel": channel, "timestamp": TS })]
+    );
+}
+
+#[tokio::test]
+async fn initial_sync_creates_tasks_from_the_members_reacted_messages() {
+    let app = TestApp::spawn().await;
+    let workspace = app.workspace("acme").await;
+    let author = app.slack_member("author", &workspace).await;
+    let reactor = app.slack_member("reactor", &workspace).await;
+    let channel = channel();
+    let reaction =
+        |name: &str| json!([{ "name": name, "users": [reactor.external_id], "count": 1 }]);
+    let api = Arc::new(
+        RecordedSlackApi::default()
+            .record(
+                "conversations.list",
+                json!({}),
+                json!({
+                    "ok": true,
+                    "channels": [{ "id": channel, "name": "eng", "is_member": true }],
+                }),
+            )
+            .record(
+                "conversations.history",
+                json!({ "channel": channel }),
+                json!({
+                    "ok": true,
+                    "messages": [
+                        {
+                            "user": author.external_id,
+                            "text": "Ship the release notes",
+                            "ts": "1718000000.000100",
+                            "reactions": reaction("eyes"),
+                        },
+                        {
+                            "user": author.external_id,
+                            "text": "Lunch?",
+                            "ts": "1718000000.000200",
+                            "reactions": reaction("tada"),
+                        },
+                        {
+                            "user": reactor.external_id,
+                            "text": "Review the budget",
+                            "ts": "1718000000.000300",
+                            "reactions": reaction("eyes"),
+                        },
+                    ],
+                }),
+            ),
+    );
+
+    InitialSyncer::new(&workspace, "xoxb-test".to_string(), &app.state)
+        .with_slack_api(api.clone())
+        .perform_initial_sync(&author.external_id)
+        .await
+        .unwrap();
+
+    let db = app.state.database.clone();
+    let messages = MessagesRepo::new(db.clone(), app.state.message_encryption.clone());
+    let message = messages
+        .get_message_by_external_id(format!("slack:{}:1718000000.000100", channel))
+        .await
+        .unwrap();
+    let task = TasksRepo::new(db)
+        .get_task_by_message_id(message.id)
+        .await
+        .unwrap();
+    assert_eq!(task.status, TaskStatus::InProgress);
+    assert_eq!(task.assigned_to, author.id);
+    assert_eq!(task.assigned_by, Some(reactor.id));
+
+    // An unmapped reaction leaves no task, and only the member's own messages sync
+    for ts in ["1718000000.000200", "1718000000.000300"] {
+        assert!(messages
+            .get_message_by_external_id(format!("slack:{}:{}", channel, ts))
+            .await
+            .is_err());
+    }
+    assert_eq!(
+        api.calls_to("conversations.history"),
+        vec![json!({ "channel": channel, "limit": "100" })]
+    );
+}
@@ -0,0 +1,69 @@
+//! Sign in with Slack is tied to the browser that started it.
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    response::Response,
+};
+use tower::ServiceExt;
+
+use common::TestApp;
+
+async fn get(app: &TestApp, uri: &str, cookie: Option<&str>) -> Response {
+    let mut builder = Request::builder().uri(uri);
+    if let Some(cookie) = cookie {
+        builder = builder.header(header::COOKIE, cookie);
+    }
+    app.router
+        .clone()
+        .oneshot(builder.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+fn query_param<'a>(url: &'a str, name: &str) -> &'a str {
+    url.split_once('?')
+        .unwrap()
+        .1
+        .split('&')
+        .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+        .unwrap_or_else(|| panic!("No {} in {}", name, url))
+}
+
+#[tokio::test]
+async fn login_sends_the_state_and_nonce_it_keeps_in_a_cookie() {
+    let app = TestApp::spawn().await;
+
+    let response = get(&app, "/api/auth/slack", None).await;
+    assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+    let location = response.headers()[header::LOCATION].to_str().unwrap();
+    let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+
+    let state = query_param(location, "state");
+    let nonce = query_param(location, "nonce");
+    assert_ne!(state, nonce);
+    assert!(cookie.starts_with(&format!("slack_oauth={}:{};", state, nonce)));
+    assert!(cookie.contains("HttpOnly"));
+
+    // Every sign-in gets a state of its own
+    let again = get(&app, "/api/auth/slack", None).await;
+    let location = again.headers()[header::LOCATION].to_str().unwrap();
+    assert_ne!(query_param(location, "state"), state);
+}
+
+#[tokio::test]
+async fn callback_refuses_a_sign_in_this_browser_did_not_start() {
+    let app = TestApp::spawn().await;
+    let uri = "/api/auth/slack/callback?code=attacker-code&state=attacker-state";
+
+    for cookie in [
+        None,
+        Some("slack_oauth=victim-state:victim-nonce"),
+        Some("other=attacker-state:nonce"),
+    ] {
+        let response = get(&app, uri, cookie).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{:?}", cookie);
+    }
+}
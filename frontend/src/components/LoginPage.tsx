@@ -1,14 +1,27 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
+import axios from "axios";
 import { useAuth } from "../hooks/useAuth";
+import type { AuthProvider } from "../hooks/useAuth";
 import { Loader2 } from "lucide-react";
 
 export function LoginPage() {
   const { login } = useAuth();
-  const [isLoading, setIsLoading] = useState(false);
+  const [loadingProvider, setLoadingProvider] = useState<AuthProvider | null>(
+    null
+  );
+  const [slackEnabled, setSlackEnabled] = useState(false);
+  const isLoading = loadingProvider !== null;
+
+  useEffect(() => {
+    axios
+      .get<{ google: boolean; slack: boolean }>("/api/auth/providers")
+      .then((response) => setSlackEnabled(response.data.slack))
+      .catch(() => setSlackEnabled(false));
+  }, []);
 
-  const handleLogin = () => {
-    setIsLoading(true);
-    login();
+  const handleLogin = (provider: AuthProvider) => {
+    setLoadingProvider(provider);
+    login(provider);
   };
 
   return (
@@ -69,14 +82,14 @@ export function LoginPage() {
         </p>
 
         <button
-          onClick={handleLogin}
+          onClick={() => handleLogin("google")}
           disabled={isLoading}
           style={{
             ...styles.googleButton,
             ...(isLoading ? styles.googleButtonDisabled : {}),
           }}
         >
-          {isLoading ? (
+          {loadingProvider === "google" ? (
             <Loader2 size={20} style={styles.spinner} />
           ) : (
             <svg
@@ -104,11 +117,61 @@ export function LoginPage() {
               />
             </svg>
           )}
-          <span>{isLoading ? "Redirecting..." : "Continue with Google"}</span>
+          <span>
+            {loadingProvider === "google"
+              ? "Redirecting..."
+              : "Continue with Google"}
+          </span>
         </button>
 
+        {slackEnabled && (
+          <button
+            onClick={() => handleLogin("slack")}
+            disabled={isLoading}
+            style={{
+              ...styles.googleButton,
+              ...(isLoading ? styles.googleButtonDisabled : {}),
+            }}
+          >
+            {loadingProvider === "slack" ? (
+              <Loader2 size={20} style={styles.spinner} />
+            ) : (
+              <svg
+                width="20"
+                height="20"
+                viewBox="0 0 24 24"
+                xmlns="http://www.w3.org/2000/svg"
+              >
+                <path
+                  d="M5.04 15.17a2.53 2.53 0 1 1-2.52-2.53h2.52v2.53zm1.27 0a2.53 2.53 0 0 1 5.05 0v6.31a2.53 2.53 0 1 1-5.05 0v-6.31z"
+                  fill="#E01E5A"
+                />
+                <path
+                  d="M8.83 5.04a2.53 2.53 0 1 1 2.53-2.52v2.52H8.83zm0 1.27a2.53 2.53 0 0 1 0 5.05H2.52a2.53 2.53 0 1 1 0-5.05h6.31z"
+                  fill="#36C5F0"
+                />
+                <path
+                  d="M18.96 8.83a2.53 2.53 0 1 1 2.52 2.53h-2.52V8.83zm-1.27 0a2.53 2.53 0 0 1-5.05 0V2.52a2.53 2.53 0 1 1 5.05 0v6.31z"
+                  fill="#2EB67D"
+                />
+                <path
+                  d="M15.17 18.96a2.53 2.53 0 1 1-2.53 2.52v-2.52h2.53zm0-1.27a2.53 2.53 0 0 1 0-5.05h6.31a2.53 2.53 0 1 1 0 5.05h-6.31z"
+                  fill="#ECB22E"
+                />
+              </svg>
+            )}
+            <span>
+              {loadingProvider === "slack"
+                ? "Redirecting..."
+                : "Continue with Slack"}
+            </span>
+          </button>
+        )}
+
         <p style={styles.note}>
-          Sign in with your Google account linked to Slack
+          {slackEnabled
+            ? "Sign in with Slack, or with your Google account linked to Slack"
+            : "Sign in with your Google account linked to Slack"}
         </p>
       </div>
 
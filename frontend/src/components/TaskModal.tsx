@@ -8,7 +8,7 @@ interface MessageDetail {
   external_id: string;
   channel: string;
   timestamp: string;
-  slack_link: string;
+  slack_link: string | null;
 }
 
 interface Change {
@@ -136,19 +136,21 @@ export function TaskModal({ taskId, isOpen, onClose }: TaskModalProps) {
               </div>
             </div>
 
-            {/* Slack Link */}
-            <div style={styles.section}>
-              <span style={styles.label}>Slack Message</span>
-              <a
-                href={task.message.slack_link}
-                target="_blank"
-                rel="noopener noreferrer"
-                style={styles.slackLink}
-              >
-                <ExternalLink size={16} />
-                <span>Open in Slack</span>
-              </a>
-            </div>
+            {/* Slack Link, left out for anonymized private channels */}
+            {task.message.slack_link && (
+              <div style={styles.section}>
+                <span style={styles.label}>Slack Message</span>
+                <a
+                  href={task.message.slack_link}
+                  target="_blank"
+                  rel="noopener noreferrer"
+                  style={styles.slackLink}
+                >
+                  <ExternalLink size={16} />
+                  <span>Open in Slack</span>
+                </a>
+              </div>
+            )}
 
             {/* Created At */}
             <div style={styles.section}>
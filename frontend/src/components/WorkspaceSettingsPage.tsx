@@ -48,9 +48,7 @@ interface WorkspaceUser {
 interface UsersResponse {
   users: WorkspaceUser[];
   total: number;
-  page: number;
-  per_page: number;
-  total_pages: number;
+  next_cursor: string | null;
 }
 
 interface WorkspaceSettingsPageProps {
@@ -86,10 +84,14 @@ export function WorkspaceSettingsPage({ workspaceName }: WorkspaceSettingsPagePr
   const [usersLoading, setUsersLoading] = useState(false);
   const [usersPagination, setUsersPagination] = useState({
     page: 0,
-    per_page: 10,
+    limit: 10,
     total: 0,
-    total_pages: 0,
+    // Cursor of every page visited so far, the first page has none
+    cursors: [null] as (string | null)[],
+    next_cursor: null as string | null,
   });
+  const usersCursor = usersPagination.cursors[usersPagination.page];
+  const usersTotalPages = Math.ceil(usersPagination.total / usersPagination.limit);
 
   // Invite modal state
   const [showInviteModal, setShowInviteModal] = useState(false);
@@ -120,13 +122,13 @@ export function WorkspaceSettingsPage({ workspaceName }: WorkspaceSettingsPagePr
       setUsersLoading(true);
       const response = await axios.get<UsersResponse>(
         `/api/workspaces/${workspaceName}/users`,
-        { params: { page: usersPagination.page, per_page: usersPagination.per_page } }
+        { params: { cursor: usersCursor ?? undefined, limit: usersPagination.limit } }
       );
       setUsers(response.data.users);
       setUsersPagination((prev) => ({
         ...prev,
         total: response.data.total,
-        total_pages: response.data.total_pages,
+        next_cursor: response.data.next_cursor,
       }));
     } catch (err: unknown) {
       const axiosErr = err as { response?: { data?: { message?: string } } };
@@ -134,7 +136,7 @@ export function WorkspaceSettingsPage({ workspaceName }: WorkspaceSettingsPagePr
     } finally {
       setUsersLoading(false);
     }
-  }, [workspaceName, usersPagination.page, usersPagination.per_page]);
+  }, [workspaceName, usersCursor, usersPagination.limit]);
 
   useEffect(() => {
     fetchSettings();
@@ -161,8 +163,11 @@ export function WorkspaceSettingsPage({ workspaceName }: WorkspaceSettingsPagePr
       if (appToken) payload.app_token = appToken;
       if (botToken) payload.bot_token = botToken;
 
-      await axios.put(`/api/workspaces/${workspaceName}/tokens`, payload);
-      setSuccess("Tokens updated successfully. Restart the server to apply changes.");
+      const { data } = await axios.put<{ message: string }>(
+        `/api/workspaces/${workspaceName}/tokens`,
+        payload,
+      );
+      setSuccess(data.message);
       setAppToken("");
       setBotToken("");
       await fetchSettings();
@@ -672,7 +677,7 @@ export function WorkspaceSettingsPage({ workspaceName }: WorkspaceSettingsPagePr
                     </table>
                   </div>
 
-                  {usersPagination.total_pages > 1 && (
+                  {usersTotalPages > 1 && (
                     <div style={styles.pagination}>
                       <button
                         onClick={() => setUsersPagination({ ...usersPagination, page: usersPagination.page - 1 })}
@@ -682,11 +687,20 @@ export function WorkspaceSettingsPage({ workspaceName }: WorkspaceSettingsPagePr
                         <ChevronLeft size={18} />
                       </button>
                       <span style={styles.paginationInfo}>
-                        Page {usersPagination.page + 1} of {usersPagination.total_pages}
+                        Page {usersPagination.page + 1} of {usersTotalPages}
                       </span>
                       <button
-                        onClick={() => setUsersPagination({ ...usersPagination, page: usersPagination.page + 1 })}
-                        disabled={usersPagination.page >= usersPagination.total_pages - 1}
+                        onClick={() =>
+                          setUsersPagination({
+                            ...usersPagination,
+                            page: usersPagination.page + 1,
+                            cursors: [
+                              ...usersPagination.cursors.slice(0, usersPagination.page + 1),
+                              usersPagination.next_cursor,
+                            ],
+                          })
+                        }
+                        disabled={!usersPagination.next_cursor}
                         style={styles.paginationButton}
                       >
                         <ChevronRight size={18} />
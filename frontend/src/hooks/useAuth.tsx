@@ -15,6 +15,8 @@ export interface Person {
   email: string;
   is_me: boolean;
   external_id: string;
+  timezone?: string;
+  avatar_url?: string | null;
 }
 
 export interface Account {
@@ -27,6 +29,8 @@ export interface MultiAccountStore {
   accounts: Record<string, Account>; // key: email
 }
 
+export type AuthProvider = "google" | "slack";
+
 interface AuthContextType {
   person: Person | null;
   token: string | null;
@@ -34,7 +38,7 @@ interface AuthContextType {
   isLoading: boolean;
   accounts: Account[];
   activeEmail: string | null;
-  login: () => void;
+  login: (provider?: AuthProvider) => void;
   logout: () => void;
   switchAccount: (email: string) => void;
   addAccount: () => void;
@@ -90,9 +94,9 @@ export function AuthProvider({ children }: { children: ReactNode }) {
     setMultiAccountStore(store);
   }, []);
 
-  const login = useCallback(() => {
-    // Redirect to Google OAuth
-    window.location.href = "/api/auth/google";
+  const login = useCallback((provider: AuthProvider = "google") => {
+    // Redirect to the provider's OAuth consent screen
+    window.location.href = `/api/auth/${provider}`;
   }, []);
 
   const addAccount = useCallback(() => {
@@ -6,6 +6,63 @@ mod m20260106_010000_add_active_workspace;
 mod m20260106_020000_workspace_settings;
 mod m20260109_000000_add_assigned_by;
 mod m20260109_010000_workspace_admins;
+mod m20260201_000000_add_task_workspace;
+mod m20260205_000000_workspaces;
+mod m20260206_000000_bot_incidents;
+mod m20260207_000000_invitations;
+mod m20260208_000000_notification_settings;
+mod m20260209_000000_person_profile;
+mod m20260210_000000_scheduled_jobs;
+mod m20260211_000000_jobs;
+mod m20260212_000000_add_indexes;
+mod m20260213_000000_retention_policies;
+mod m20260214_000000_audit_logs;
+mod m20260215_000000_audit_impersonation;
+mod m20260216_000000_leases;
+mod m20260217_000000_jira;
+mod m20260218_000000_github;
+mod m20260219_000000_calendar_feeds;
+mod m20260220_000000_linear;
+mod m20260221_000000_inbound_email;
+mod m20260222_000000_notion;
+mod m20260223_000000_hook_subscriptions;
+mod m20260224_000000_channel_rules;
+mod m20260225_000000_keyword_triggers;
+mod m20260226_000000_wip_limits;
+mod m20260227_000000_task_merges;
+mod m20260228_000000_projects;
+mod m20260301_000000_orphaned_tasks;
+mod m20260302_000000_cursor_pagination;
+mod m20260303_000000_message_redaction;
+mod m20260303_010000_message_encryption;
+mod m20260304_000000_status_resolution;
+mod m20260305_000000_reaction_policy;
+mod m20260306_000000_task_templates;
+mod m20260307_000000_board_views;
+mod m20260308_000000_reminders;
+mod m20260309_000000_task_keys;
+mod m20260310_000000_change_times;
+mod m20260311_000000_availability;
+mod m20260312_000000_slack_status;
+mod m20260313_000000_notification_routes;
+mod m20260314_000000_discord;
+mod m20260315_000000_teams;
+mod m20260316_000000_bot_events;
+mod m20260317_000000_task_version;
+mod m20260318_000000_idempotency_keys;
+mod m20260319_000000_board_changes;
+mod m20260320_000000_archives;
+mod m20260321_000000_hook_secrets;
+mod m20260322_000000_standups;
+mod m20260323_000000_auto_join_channels;
+mod m20260324_000000_private_channels;
+mod m20260325_000000_enterprise_grid;
+mod m20260326_000000_locales;
+mod m20260327_000000_notification_templates;
+mod m20260328_000000_business_hours;
+mod m20260329_000000_sla_policies;
+mod m20260330_000000_viewer_role;
+mod m20260331_000000_sessions;
 
 pub struct Migrator;
 
@@ -19,6 +76,63 @@ impl MigratorTrait for Migrator {
             Box::new(m20260106_020000_workspace_settings::Migration),
             Box::new(m20260109_000000_add_assigned_by::Migration),
             Box::new(m20260109_010000_workspace_admins::Migration),
+            Box::new(m20260201_000000_add_task_workspace::Migration),
+            Box::new(m20260205_000000_workspaces::Migration),
+            Box::new(m20260206_000000_bot_incidents::Migration),
+            Box::new(m20260207_000000_invitations::Migration),
+            Box::new(m20260208_000000_notification_settings::Migration),
+            Box::new(m20260209_000000_person_profile::Migration),
+            Box::new(m20260210_000000_scheduled_jobs::Migration),
+            Box::new(m20260211_000000_jobs::Migration),
+            Box::new(m20260212_000000_add_indexes::Migration),
+            Box::new(m20260213_000000_retention_policies::Migration),
+            Box::new(m20260214_000000_audit_logs::Migration),
+            Box::new(m20260215_000000_audit_impersonation::Migration),
+            Box::new(m20260216_000000_leases::Migration),
+            Box::new(m20260217_000000_jira::Migration),
+            Box::new(m20260218_000000_github::Migration),
+            Box::new(m20260219_000000_calendar_feeds::Migration),
+            Box::new(m20260220_000000_linear::Migration),
+            Box::new(m20260221_000000_inbound_email::Migration),
+            Box::new(m20260222_000000_notion::Migration),
+            Box::new(m20260223_000000_hook_subscriptions::Migration),
+            Box::new(m20260224_000000_channel_rules::Migration),
+            Box::new(m20260225_000000_keyword_triggers::Migration),
+            Box::new(m20260226_000000_wip_limits::Migration),
+            Box::new(m20260227_000000_task_merges::Migration),
+            Box::new(m20260228_000000_projects::Migration),
+            Box::new(m20260301_000000_orphaned_tasks::Migration),
+            Box::new(m20260302_000000_cursor_pagination::Migration),
+            Box::new(m20260303_000000_message_redaction::Migration),
+            Box::new(m20260303_010000_message_encryption::Migration),
+            Box::new(m20260304_000000_status_resolution::Migration),
+            Box::new(m20260305_000000_reaction_policy::Migration),
+            Box::new(m20260306_000000_task_templates::Migration),
+            Box::new(m20260307_000000_board_views::Migration),
+            Box::new(m20260308_000000_reminders::Migration),
+            Box::new(m20260309_000000_task_keys::Migration),
+            Box::new(m20260310_000000_change_times::Migration),
+            Box::new(m20260311_000000_availability::Migration),
+            Box::new(m20260312_000000_slack_status::Migration),
+            Box::new(m20260313_000000_notification_routes::Migration),
+            Box::new(m20260314_000000_discord::Migration),
+            Box::new(m20260315_000000_teams::Migration),
+            Box::new(m20260316_000000_bot_events::Migration),
+            Box::new(m20260317_000000_task_version::Migration),
+            Box::new(m20260318_000000_idempotency_keys::Migration),
+            Box::new(m20260319_000000_board_changes::Migration),
+            Box::new(m20260320_000000_archives::Migration),
+            Box::new(m20260321_000000_hook_secrets::Migration),
+            Box::new(m20260322_000000_standups::Migration),
+            Box::new(m20260323_000000_auto_join_channels::Migration),
+            Box::new(m20260324_000000_private_channels::Migration),
+            Box::new(m20260325_000000_enterprise_grid::Migration),
+            Box::new(m20260326_000000_locales::Migration),
+            Box::new(m20260327_000000_notification_templates::Migration),
+            Box::new(m20260328_000000_business_hours::Migration),
+            Box::new(m20260329_000000_sla_policies::Migration),
+            Box::new(m20260330_000000_viewer_role::Migration),
+            Box::new(m20260331_000000_sessions::Migration),
         ]
     }
 }
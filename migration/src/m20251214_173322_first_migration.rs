@@ -1,6 +1,7 @@
 use sea_orm_migration::{
     prelude::{extension::postgres::Type, *},
     schema::*,
+    sea_orm::DatabaseBackend,
 };
 
 #[derive(DeriveMigrationName)]
@@ -9,19 +10,21 @@ pub struct Migration;
 #[async_trait::async_trait]
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        // TaskStatus data type
-        manager
-            .create_type(
-                Type::create()
-                    .as_enum(TaskStatus::Type)
-                    .values(vec![
-                        TaskStatus::InProgress,
-                        TaskStatus::Blocked,
-                        TaskStatus::Completed,
-                    ])
-                    .to_owned(),
-            )
-            .await?;
+        // TaskStatus data type, Postgres only; tasks.status itself is a plain string
+        if manager.get_database_backend() == DatabaseBackend::Postgres {
+            manager
+                .create_type(
+                    Type::create()
+                        .as_enum(TaskStatus::Type)
+                        .values(vec![
+                            TaskStatus::InProgress,
+                            TaskStatus::Blocked,
+                            TaskStatus::Completed,
+                        ])
+                        .to_owned(),
+                )
+                .await?;
+        }
 
         // persons
         manager
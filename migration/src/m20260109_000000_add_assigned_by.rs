@@ -1,4 +1,4 @@
-use sea_orm_migration::prelude::*;
+use sea_orm_migration::{prelude::*, sea_orm::DatabaseBackend};
 
 #[derive(DeriveMigrationName)]
 pub struct Migration;
@@ -7,22 +7,22 @@ pub struct Migration;
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
         // Add assigned_by column to tasks table (nullable initially for existing records)
-        manager
-            .alter_table(
-                Table::alter()
-                    .table(Alias::new("tasks"))
-                    .add_column(ColumnDef::new(Alias::new("assigned_by")).string().null())
-                    .add_foreign_key(
-                        TableForeignKey::new()
-                            .name("fk_tasks_assigned_by")
-                            .from_col(Alias::new("assigned_by"))
-                            .to_tbl(Alias::new("persons"))
-                            .to_col(Alias::new("id"))
-                            .on_delete(ForeignKeyAction::SetNull),
-                    )
-                    .to_owned(),
-            )
-            .await?;
+        let mut alter = Table::alter()
+            .table(Alias::new("tasks"))
+            .add_column(ColumnDef::new(Alias::new("assigned_by")).string().null())
+            .to_owned();
+        // SQLite cannot add foreign keys to an existing table
+        if manager.get_database_backend() == DatabaseBackend::Postgres {
+            alter.add_foreign_key(
+                TableForeignKey::new()
+                    .name("fk_tasks_assigned_by")
+                    .from_col(Alias::new("assigned_by"))
+                    .to_tbl(Alias::new("persons"))
+                    .to_col(Alias::new("id"))
+                    .on_delete(ForeignKeyAction::SetNull),
+            );
+        }
+        manager.alter_table(alter).await?;
 
         // For existing tasks, set assigned_by to be the same as assigned_to
         manager
@@ -36,11 +36,20 @@ impl MigrationTrait for Migration {
     }
 
     async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() == DatabaseBackend::Postgres {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("tasks"))
+                        .drop_foreign_key(Alias::new("fk_tasks_assigned_by"))
+                        .to_owned(),
+                )
+                .await?;
+        }
         manager
             .alter_table(
                 Table::alter()
                     .table(Alias::new("tasks"))
-                    .drop_foreign_key(Alias::new("fk_tasks_assigned_by"))
                     .drop_column(Alias::new("assigned_by"))
                     .to_owned(),
             )
@@ -0,0 +1,273 @@
+use sea_orm_migration::{prelude::*, schema::*, sea_orm::DatabaseBackend};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Workspaces::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Workspaces::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string_uniq(Workspaces::Name))
+                    .col(string_null(Workspaces::SlackTeamId))
+                    .col(string_null(Workspaces::CreatedBy))
+                    .col(string(Workspaces::Status).default("active"))
+                    .col(
+                        ColumnDef::new(Workspaces::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Encrypted Slack tokens, previously only stored in workspaces.yaml
+        manager
+            .create_table(
+                Table::create()
+                    .table(WorkspaceCredentials::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WorkspaceCredentials::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string_uniq(WorkspaceCredentials::WorkspaceId))
+                    .col(string(WorkspaceCredentials::AppToken))
+                    .col(string(WorkspaceCredentials::BotToken))
+                    .col(
+                        ColumnDef::new(WorkspaceCredentials::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_workspace_credentials_workspace")
+                            .from(
+                                WorkspaceCredentials::Table,
+                                WorkspaceCredentials::WorkspaceId,
+                            )
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Every workspace name referenced so far becomes a workspace row
+        let db = manager.get_connection();
+        let id_expr = match manager.get_database_backend() {
+            DatabaseBackend::Sqlite => "lower(hex(randomblob(16)))",
+            _ => "gen_random_uuid()::text",
+        };
+        db.execute_unprepared(&format!(
+            r#"
+            INSERT INTO workspaces (id, name, status, created_at)
+            SELECT {id_expr}, names.name, 'active', CURRENT_TIMESTAMP
+            FROM (
+                SELECT workspace_name AS name FROM workspace_links
+                UNION SELECT workspace_name FROM workspace_settings
+                UNION SELECT workspace_name FROM tasks WHERE workspace_name IS NOT NULL
+            ) AS names
+            ON CONFLICT (name) DO NOTHING
+            "#
+        ))
+        .await?;
+
+        if manager.get_database_backend() == DatabaseBackend::Sqlite {
+            return sqlite::up(db).await;
+        }
+
+        // Links and settings belong to a workspace; tasks outlive it
+        for (table, on_delete) in [
+            ("workspace_links", ForeignKeyAction::Cascade),
+            ("workspace_settings", ForeignKeyAction::Cascade),
+            ("tasks", ForeignKeyAction::SetNull),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .add_column(string_null(Alias::new("workspace_id")))
+                        .to_owned(),
+                )
+                .await?;
+
+            db.execute_unprepared(&format!(
+                "UPDATE {table} t SET workspace_id = w.id FROM workspaces w WHERE w.name = t.workspace_name"
+            ))
+            .await?;
+
+            let mut column = ColumnDef::new(Alias::new("workspace_id"));
+            column.string();
+            if table != "tasks" {
+                column.not_null();
+            }
+
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .modify_column(&mut column)
+                        .add_foreign_key(
+                            TableForeignKey::new()
+                                .name(format!("fk_{table}_workspace"))
+                                .from_tbl(Alias::new(table))
+                                .from_col(Alias::new("workspace_id"))
+                                .to_tbl(Workspaces::Table)
+                                .to_col(Workspaces::Id)
+                                .on_delete(on_delete),
+                        )
+                        .drop_column(Alias::new("workspace_name"))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // One settings row per workspace, as workspace_name was unique before
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_workspace_settings_workspace_id")
+                    .table(Alias::new("workspace_settings"))
+                    .col(Alias::new("workspace_id"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        if manager.get_database_backend() == DatabaseBackend::Sqlite {
+            sqlite::down(db).await?;
+        } else {
+            postgres_down(manager).await?;
+        }
+
+        manager
+            .drop_table(Table::drop().table(WorkspaceCredentials::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Workspaces::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+async fn postgres_down(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+
+    manager
+        .drop_index(
+            Index::drop()
+                .name("idx_workspace_settings_workspace_id")
+                .table(Alias::new("workspace_settings"))
+                .to_owned(),
+        )
+        .await?;
+
+    for table in ["workspace_links", "workspace_settings", "tasks"] {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new(table))
+                    .add_column(string_null(Alias::new("workspace_name")))
+                    .to_owned(),
+            )
+            .await?;
+
+        db.execute_unprepared(&format!(
+                "UPDATE {table} t SET workspace_name = w.name FROM workspaces w WHERE w.id = t.workspace_id"
+            ))
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new(table))
+                    .drop_foreign_key(Alias::new(format!("fk_{table}_workspace")))
+                    .drop_column(Alias::new("workspace_id"))
+                    .to_owned(),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// SQLite can neither alter a column nor add a foreign key to an existing table, so
+/// workspace_name is renamed in place and rewritten to hold the workspace id. Its
+/// existing unique constraint on workspace_settings carries over.
+mod sqlite {
+    use sea_orm_migration::prelude::*;
+
+    const TABLES: [&str; 3] = ["workspace_links", "workspace_settings", "tasks"];
+
+    pub async fn up(db: &impl ConnectionTrait) -> Result<(), DbErr> {
+        for table in TABLES {
+            db.execute_unprepared(&format!(
+                "ALTER TABLE {table} RENAME COLUMN workspace_name TO workspace_id"
+            ))
+            .await?;
+            db.execute_unprepared(&format!(
+                "UPDATE {table} SET workspace_id = (SELECT w.id FROM workspaces w WHERE w.name = {table}.workspace_id) WHERE workspace_id IS NOT NULL"
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn down(db: &impl ConnectionTrait) -> Result<(), DbErr> {
+        for table in TABLES {
+            db.execute_unprepared(&format!(
+                "UPDATE {table} SET workspace_id = (SELECT w.name FROM workspaces w WHERE w.id = {table}.workspace_id) WHERE workspace_id IS NOT NULL"
+            ))
+            .await?;
+            db.execute_unprepared(&format!(
+                "ALTER TABLE {table} RENAME COLUMN workspace_id TO workspace_name"
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+    Name,
+    SlackTeamId,
+    CreatedBy,
+    Status,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum WorkspaceCredentials {
+    Table,
+    Id,
+    WorkspaceId,
+    AppToken,
+    BotToken,
+    UpdatedAt,
+}
@@ -0,0 +1,73 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BotIncidents::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BotIncidents::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(BotIncidents::WorkspaceId))
+                    .col(string(BotIncidents::Kind))
+                    .col(text_null(BotIncidents::Message))
+                    .col(
+                        ColumnDef::new(BotIncidents::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_bot_incidents_workspace")
+                            .from(BotIncidents::Table, BotIncidents::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_bot_incidents_workspace_created")
+                    .table(BotIncidents::Table)
+                    .col(BotIncidents::WorkspaceId)
+                    .col(BotIncidents::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BotIncidents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BotIncidents {
+    Table,
+    Id,
+    WorkspaceId,
+    Kind,
+    Message,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+}
@@ -0,0 +1,87 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Invitations::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Invitations::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string_uniq(Invitations::Token))
+                    .col(string(Invitations::Email))
+                    .col(string(Invitations::WorkspaceId))
+                    .col(string(Invitations::SlackMemberId))
+                    .col(string(Invitations::InvitedBy))
+                    .col(string(Invitations::Status).default("pending"))
+                    .col(
+                        ColumnDef::new(Invitations::ExpiresAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Invitations::AcceptedAt).timestamp().null())
+                    .col(
+                        ColumnDef::new(Invitations::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_invitations_workspace")
+                            .from(Invitations::Table, Invitations::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_invitations_email_status")
+                    .table(Invitations::Table)
+                    .col(Invitations::Email)
+                    .col(Invitations::Status)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Invitations::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Invitations {
+    Table,
+    Id,
+    Token,
+    Email,
+    WorkspaceId,
+    SlackMemberId,
+    InvitedBy,
+    Status,
+    ExpiresAt,
+    AcceptedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+}
@@ -0,0 +1,68 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(NotificationSettings::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(NotificationSettings::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string_uniq(NotificationSettings::PersonId))
+                    .col(boolean(NotificationSettings::DmOnAssignment).default(false))
+                    .col(boolean(NotificationSettings::DailyDigest).default(false))
+                    .col(boolean(NotificationSettings::WeeklySummary).default(false))
+                    .col(string(NotificationSettings::Channel).default("slack_dm"))
+                    .col(string_null(NotificationSettings::WebhookUrl))
+                    .col(
+                        ColumnDef::new(NotificationSettings::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_notification_settings_person")
+                            .from(NotificationSettings::Table, NotificationSettings::PersonId)
+                            .to(Persons::Table, Persons::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(NotificationSettings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum NotificationSettings {
+    Table,
+    Id,
+    PersonId,
+    DmOnAssignment,
+    DailyDigest,
+    WeeklySummary,
+    Channel,
+    WebhookUrl,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Persons {
+    Table,
+    Id,
+}
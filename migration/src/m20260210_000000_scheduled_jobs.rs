@@ -0,0 +1,84 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ScheduledJobs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ScheduledJobs::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string_uniq(ScheduledJobs::Name))
+                    .col(string(ScheduledJobs::Kind))
+                    .col(string(ScheduledJobs::Cron))
+                    .col(string(ScheduledJobs::Timezone).default("UTC"))
+                    .col(string_null(ScheduledJobs::WorkspaceId))
+                    .col(boolean(ScheduledJobs::Enabled).default(true))
+                    .col(ColumnDef::new(ScheduledJobs::NextRunAt).timestamp().null())
+                    .col(ColumnDef::new(ScheduledJobs::LastRunAt).timestamp().null())
+                    .col(ColumnDef::new(ScheduledJobs::LastError).text().null())
+                    .col(
+                        ColumnDef::new(ScheduledJobs::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_scheduled_jobs_workspace")
+                            .from(ScheduledJobs::Table, ScheduledJobs::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_scheduled_jobs_next_run_at")
+                    .table(ScheduledJobs::Table)
+                    .col(ScheduledJobs::NextRunAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ScheduledJobs::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ScheduledJobs {
+    Table,
+    Id,
+    Name,
+    Kind,
+    Cron,
+    Timezone,
+    WorkspaceId,
+    Enabled,
+    NextRunAt,
+    LastRunAt,
+    LastError,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+}
@@ -0,0 +1,101 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// (index name, table, columns, unique)
+const INDEXES: [(&str, &str, &[&str], bool); 7] = [
+    ("idx_tasks_assigned_to", "tasks", &["assigned_to"], false),
+    ("idx_tasks_message_id", "tasks", &["message_id"], false),
+    (
+        "idx_tasks_status_created_at",
+        "tasks",
+        &["status", "created_at"],
+        false,
+    ),
+    // The bot looks a message up by external_id before saving it
+    (
+        "idx_messages_external_id",
+        "messages",
+        &["external_id"],
+        true,
+    ),
+    ("idx_messages_person_id", "messages", &["person_id"], false),
+    (
+        "idx_workspace_links_slack_member_workspace",
+        "workspace_links",
+        &["slack_member_id", "workspace_id"],
+        false,
+    ),
+    (
+        "idx_changes_task_id_index",
+        "changes",
+        &["task_id", "index"],
+        false,
+    ),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Concurrent syncs could save a message twice; keep the first of each
+        // external_id, with the tasks of the others, before it becomes unique
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            r#"
+            UPDATE tasks SET message_id = (
+                SELECT MIN(keep.id) FROM messages keep
+                WHERE keep.external_id = (
+                    SELECT duplicate.external_id FROM messages duplicate
+                    WHERE duplicate.id = tasks.message_id
+                )
+            )
+            WHERE message_id IN (
+                SELECT duplicate.id FROM messages duplicate
+                WHERE EXISTS (
+                    SELECT 1 FROM messages earlier
+                    WHERE earlier.external_id = duplicate.external_id
+                    AND earlier.id < duplicate.id
+                )
+            )
+            "#,
+        )
+        .await?;
+        db.execute_unprepared(
+            r#"
+            DELETE FROM messages
+            WHERE EXISTS (
+                SELECT 1 FROM messages earlier
+                WHERE earlier.external_id = messages.external_id
+                AND earlier.id < messages.id
+            )
+            "#,
+        )
+        .await?;
+
+        for (name, table, columns, unique) in INDEXES {
+            let mut index = Index::create();
+            index.name(name).table(Alias::new(table)).if_not_exists();
+            for column in columns {
+                index.col(Alias::new(*column));
+            }
+            if unique {
+                index.unique();
+            }
+
+            manager.create_index(index).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (name, table, _, _) in INDEXES {
+            manager
+                .drop_index(Index::drop().name(name).table(Alias::new(table)).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
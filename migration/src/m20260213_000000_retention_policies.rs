@@ -0,0 +1,67 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RetentionPolicies::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RetentionPolicies::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string_uniq(RetentionPolicies::WorkspaceId))
+                    // Days to keep each kind of row, null keeps it forever
+                    .col(integer_null(RetentionPolicies::CompletedTaskDays))
+                    .col(integer_null(RetentionPolicies::MessageDays))
+                    .col(integer_null(RetentionPolicies::IncidentDays))
+                    .col(string(RetentionPolicies::Mode).default("delete"))
+                    .col(
+                        ColumnDef::new(RetentionPolicies::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_retention_policies_workspace")
+                            .from(RetentionPolicies::Table, RetentionPolicies::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RetentionPolicies::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RetentionPolicies {
+    Table,
+    Id,
+    WorkspaceId,
+    CompletedTaskDays,
+    MessageDays,
+    IncidentDays,
+    Mode,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Leases::Table)
+                    .if_not_exists()
+                    // e.g. "scheduler" or "bot:<workspace id>"
+                    .col(
+                        ColumnDef::new(Leases::Name)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(Leases::Holder))
+                    .col(timestamp(Leases::ExpiresAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Leases::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Leases {
+    Table,
+    Name,
+    Holder,
+    ExpiresAt,
+}
@@ -0,0 +1,114 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(JiraConnections::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(JiraConnections::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string_uniq(JiraConnections::WorkspaceId))
+                    .col(string(JiraConnections::BaseUrl))
+                    .col(string(JiraConnections::Email))
+                    // Encrypted with the master key, like workspace tokens
+                    .col(text(JiraConnections::ApiToken))
+                    .col(string(JiraConnections::ProjectKey))
+                    .col(string(JiraConnections::IssueType).default("Task"))
+                    .col(string_uniq(JiraConnections::WebhookSecret))
+                    .col(
+                        ColumnDef::new(JiraConnections::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_jira_connections_workspace")
+                            .from(JiraConnections::Table, JiraConnections::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .add_column(string_null(Tasks::JiraIssueKey))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tasks_jira_issue_key")
+                    .table(Tasks::Table)
+                    .col(Tasks::JiraIssueKey)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_tasks_jira_issue_key")
+                    .table(Tasks::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .drop_column(Tasks::JiraIssueKey)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(JiraConnections::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum JiraConnections {
+    Table,
+    Id,
+    WorkspaceId,
+    BaseUrl,
+    Email,
+    ApiToken,
+    ProjectKey,
+    IssueType,
+    WebhookSecret,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Tasks {
+    Table,
+    JiraIssueKey,
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+}
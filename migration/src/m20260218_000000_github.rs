@@ -0,0 +1,131 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(GithubRepositories::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GithubRepositories::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(GithubRepositories::WorkspaceId))
+                    // "owner/repo", as GitHub sends it in webhooks
+                    .col(string_uniq(GithubRepositories::FullName))
+                    // Encrypted with the master key
+                    .col(text(GithubRepositories::WebhookSecret))
+                    .col(
+                        ColumnDef::new(GithubRepositories::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_github_repositories_workspace")
+                            .from(GithubRepositories::Table, GithubRepositories::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(GithubLinks::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GithubLinks::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(GithubLinks::TaskId))
+                    .col(string(GithubLinks::Kind))
+                    .col(string(GithubLinks::Url))
+                    .col(string(GithubLinks::Title))
+                    .col(string(GithubLinks::State))
+                    .col(
+                        ColumnDef::new(GithubLinks::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_github_links_task")
+                            .from(GithubLinks::Table, GithubLinks::TaskId)
+                            .to(Tasks::Table, Tasks::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_github_links_task_url")
+                    .table(GithubLinks::Table)
+                    .col(GithubLinks::TaskId)
+                    .col(GithubLinks::Url)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GithubLinks::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(GithubRepositories::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GithubRepositories {
+    Table,
+    Id,
+    WorkspaceId,
+    FullName,
+    WebhookSecret,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum GithubLinks {
+    Table,
+    Id,
+    TaskId,
+    Kind,
+    Url,
+    Title,
+    State,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Tasks {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+}
@@ -0,0 +1,85 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .add_column(timestamp_null(Tasks::DueAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        // One feed per person; replacing the token revokes the old feed URL
+        manager
+            .create_table(
+                Table::create()
+                    .table(CalendarFeeds::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CalendarFeeds::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string_uniq(CalendarFeeds::PersonId))
+                    .col(string_uniq(CalendarFeeds::Token))
+                    .col(
+                        ColumnDef::new(CalendarFeeds::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_calendar_feeds_person")
+                            .from(CalendarFeeds::Table, CalendarFeeds::PersonId)
+                            .to(Persons::Table, Persons::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CalendarFeeds::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .drop_column(Tasks::DueAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Tasks {
+    Table,
+    DueAt,
+}
+
+#[derive(DeriveIden)]
+enum CalendarFeeds {
+    Table,
+    Id,
+    PersonId,
+    Token,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Persons {
+    Table,
+    Id,
+}
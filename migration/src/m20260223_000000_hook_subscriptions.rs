@@ -0,0 +1,88 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(HookSubscriptions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(HookSubscriptions::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(HookSubscriptions::PersonId))
+                    .col(string(HookSubscriptions::WorkspaceId))
+                    .col(string(HookSubscriptions::Event))
+                    .col(text(HookSubscriptions::TargetUrl))
+                    .col(
+                        ColumnDef::new(HookSubscriptions::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_hook_subscriptions_person")
+                            .from(HookSubscriptions::Table, HookSubscriptions::PersonId)
+                            .to(Persons::Table, Persons::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_hook_subscriptions_workspace")
+                            .from(HookSubscriptions::Table, HookSubscriptions::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_hook_subscriptions_workspace_event")
+                    .table(HookSubscriptions::Table)
+                    .col(HookSubscriptions::WorkspaceId)
+                    .col(HookSubscriptions::Event)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(HookSubscriptions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum HookSubscriptions {
+    Table,
+    Id,
+    PersonId,
+    WorkspaceId,
+    Event,
+    TargetUrl,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Persons {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+}
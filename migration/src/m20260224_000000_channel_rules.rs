@@ -0,0 +1,137 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChannelRules::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChannelRules::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(ChannelRules::WorkspaceId))
+                    // Slack channel id, e.g. "C024BE91L"
+                    .col(string(ChannelRules::Channel))
+                    .col(string_null(ChannelRules::AssigneeId))
+                    .col(ColumnDef::new(ChannelRules::Labels).json().not_null())
+                    .col(string_null(ChannelRules::Priority))
+                    .col(boolean(ChannelRules::Ignored).default(false))
+                    .col(
+                        ColumnDef::new(ChannelRules::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_channel_rules_workspace")
+                            .from(ChannelRules::Table, ChannelRules::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_channel_rules_assignee")
+                            .from(ChannelRules::Table, ChannelRules::AssigneeId)
+                            .to(Persons::Table, Persons::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_channel_rules_workspace_channel")
+                    .table(ChannelRules::Table)
+                    .col(ChannelRules::WorkspaceId)
+                    .col(ChannelRules::Channel)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .add_column(string_null(Tasks::Priority))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .add_column(json_null(Tasks::Labels))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .drop_column(Tasks::Labels)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .drop_column(Tasks::Priority)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ChannelRules::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ChannelRules {
+    Table,
+    Id,
+    WorkspaceId,
+    Channel,
+    AssigneeId,
+    Labels,
+    Priority,
+    Ignored,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Tasks {
+    Table,
+    Priority,
+    Labels,
+}
+
+#[derive(DeriveIden)]
+enum Persons {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+}
@@ -0,0 +1,83 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Cursor pages seek on (created_at, id) within their filter: (index name, table, columns)
+const INDEXES: [(&str, &str, &[&str]); 4] = [
+    (
+        "idx_messages_created_at_id",
+        "messages",
+        &["created_at", "id"],
+    ),
+    (
+        "idx_audit_logs_created_at_id",
+        "audit_logs",
+        &["created_at", "id"],
+    ),
+    (
+        "idx_tasks_assigned_to_created_at_id",
+        "tasks",
+        &["assigned_to", "created_at", "id"],
+    ),
+    (
+        "idx_workspace_links_workspace_created_at_id",
+        "workspace_links",
+        &["workspace_id", "created_at", "id"],
+    ),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only adds columns with a constant default; existing messages get the
+        // migration time instead
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Messages::Table)
+                    .add_column(timestamp(Messages::CreatedAt).default("1970-01-01 00:00:00"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .get_connection()
+            .execute_unprepared("UPDATE messages SET created_at = CURRENT_TIMESTAMP")
+            .await?;
+
+        for (name, table, columns) in INDEXES {
+            let mut index = Index::create();
+            index.name(name).table(Alias::new(table)).if_not_exists();
+            for column in columns {
+                index.col(Alias::new(*column));
+            }
+
+            manager.create_index(index).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (name, table, _) in INDEXES {
+            manager
+                .drop_index(Index::drop().name(name).table(Alias::new(table)).to_owned())
+                .await?;
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Messages::Table)
+                    .drop_column(Messages::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Messages {
+    Table,
+    CreatedAt,
+}
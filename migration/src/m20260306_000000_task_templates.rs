@@ -0,0 +1,80 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TaskTemplates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TaskTemplates::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(TaskTemplates::WorkspaceId))
+                    .col(string(TaskTemplates::Name))
+                    .col(text(TaskTemplates::TitlePattern))
+                    .col(json(TaskTemplates::Labels))
+                    .col(string_null(TaskTemplates::Priority))
+                    .col(json(TaskTemplates::Checklist))
+                    .col(
+                        ColumnDef::new(TaskTemplates::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_task_templates_workspace")
+                            .from(TaskTemplates::Table, TaskTemplates::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_task_templates_workspace_name")
+                    .table(TaskTemplates::Table)
+                    .col(TaskTemplates::WorkspaceId)
+                    .col(TaskTemplates::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TaskTemplates::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TaskTemplates {
+    Table,
+    Id,
+    WorkspaceId,
+    Name,
+    TitlePattern,
+    Labels,
+    Priority,
+    Checklist,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BoardViews::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BoardViews::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(BoardViews::PersonId))
+                    .col(string(BoardViews::Name))
+                    .col(json(BoardViews::Filter))
+                    .col(
+                        ColumnDef::new(BoardViews::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_board_views_person")
+                            .from(BoardViews::Table, BoardViews::PersonId)
+                            .to(Persons::Table, Persons::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_board_views_person_name")
+                    .table(BoardViews::Table)
+                    .col(BoardViews::PersonId)
+                    .col(BoardViews::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BoardViews::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BoardViews {
+    Table,
+    Id,
+    PersonId,
+    Name,
+    Filter,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Persons {
+    Table,
+    Id,
+}
@@ -0,0 +1,89 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Reminders::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Reminders::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(Reminders::TaskId))
+                    .col(string(Reminders::PersonId))
+                    .col(timestamp(Reminders::RemindAt))
+                    .col(timestamp_null(Reminders::SentAt))
+                    .col(timestamp_null(Reminders::DoneAt))
+                    .col(
+                        ColumnDef::new(Reminders::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reminders_task")
+                            .from(Reminders::Table, Reminders::TaskId)
+                            .to(Tasks::Table, Tasks::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reminders_person")
+                            .from(Reminders::Table, Reminders::PersonId)
+                            .to(Persons::Table, Persons::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_reminders_remind_at")
+                    .table(Reminders::Table)
+                    .col(Reminders::RemindAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Reminders::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Reminders {
+    Table,
+    Id,
+    TaskId,
+    PersonId,
+    RemindAt,
+    SentAt,
+    DoneAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Tasks {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Persons {
+    Table,
+    Id,
+}
@@ -0,0 +1,96 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TaskSequences::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TaskSequences::WorkspaceId)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(TaskSequences::Prefix))
+                    .col(integer(TaskSequences::LastNumber).default(0))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_task_sequences_workspace")
+                            .from(TaskSequences::Table, TaskSequences::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing tasks get their keys on the next start, see `services::task_keys`
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .add_column(string_null(Tasks::Key))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tasks_key")
+                    .table(Tasks::Table)
+                    .col(Tasks::Key)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_tasks_key")
+                    .table(Tasks::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .drop_column(Tasks::Key)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(TaskSequences::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TaskSequences {
+    Table,
+    WorkspaceId,
+    Prefix,
+    LastNumber,
+}
+
+#[derive(DeriveIden)]
+enum Tasks {
+    Table,
+    Key,
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Availability::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Availability::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(Availability::PersonId))
+                    .col(date(Availability::StartsOn))
+                    .col(date(Availability::EndsOn))
+                    .col(string_null(Availability::Note))
+                    .col(
+                        ColumnDef::new(Availability::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_availability_person")
+                            .from(Availability::Table, Availability::PersonId)
+                            .to(Persons::Table, Persons::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_availability_person_id")
+                    .table(Availability::Table)
+                    .col(Availability::PersonId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Availability::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Availability {
+    Table,
+    Id,
+    PersonId,
+    StartsOn,
+    EndsOn,
+    Note,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Persons {
+    Table,
+    Id,
+}
@@ -0,0 +1,89 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SlackStatusSettings::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SlackStatusSettings::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(SlackStatusSettings::PersonId))
+                    .col(string(SlackStatusSettings::WorkspaceId))
+                    .col(string(SlackStatusSettings::UserToken))
+                    .col(boolean(SlackStatusSettings::Enabled).default(true))
+                    .col(
+                        ColumnDef::new(SlackStatusSettings::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_slack_status_settings_person")
+                            .from(SlackStatusSettings::Table, SlackStatusSettings::PersonId)
+                            .to(Persons::Table, Persons::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_slack_status_settings_workspace")
+                            .from(SlackStatusSettings::Table, SlackStatusSettings::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_slack_status_settings_person_workspace")
+                    .table(SlackStatusSettings::Table)
+                    .col(SlackStatusSettings::PersonId)
+                    .col(SlackStatusSettings::WorkspaceId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SlackStatusSettings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SlackStatusSettings {
+    Table,
+    Id,
+    PersonId,
+    WorkspaceId,
+    UserToken,
+    Enabled,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Persons {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+}
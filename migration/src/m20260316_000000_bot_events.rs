@@ -0,0 +1,87 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BotEvents::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BotEvents::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(BotEvents::WorkspaceId))
+                    .col(string(BotEvents::EnvelopeType))
+                    .col(string(BotEvents::EventType))
+                    .col(string_null(BotEvents::Subtype))
+                    .col(string_null(BotEvents::Channel))
+                    .col(string_null(BotEvents::Ts))
+                    .col(string_null(BotEvents::UserId))
+                    .col(string_null(BotEvents::Reaction))
+                    .col(string(BotEvents::Outcome))
+                    .col(text_null(BotEvents::Error))
+                    .col(
+                        ColumnDef::new(BotEvents::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_bot_events_workspace")
+                            .from(BotEvents::Table, BotEvents::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_bot_events_workspace_created_at")
+                    .table(BotEvents::Table)
+                    .col(BotEvents::WorkspaceId)
+                    .col(BotEvents::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BotEvents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BotEvents {
+    Table,
+    Id,
+    WorkspaceId,
+    EnvelopeType,
+    EventType,
+    Subtype,
+    Channel,
+    Ts,
+    UserId,
+    Reaction,
+    Outcome,
+    Error,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+}
@@ -0,0 +1,108 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only adds columns with a constant default; existing tasks count as
+        // updated when they were created
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .add_column(timestamp(Tasks::UpdatedAt).default("1970-01-01 00:00:00"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .get_connection()
+            .execute_unprepared("UPDATE tasks SET updated_at = created_at")
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tasks_updated_at")
+                    .table(Tasks::Table)
+                    .col(Tasks::UpdatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TaskTombstones::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TaskTombstones::TaskId)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string_null(TaskTombstones::WorkspaceId))
+                    .col(string(TaskTombstones::AssignedTo))
+                    .col(string_null(TaskTombstones::AssignedBy))
+                    .col(
+                        ColumnDef::new(TaskTombstones::DeletedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_task_tombstones_deleted_at")
+                    .table(TaskTombstones::Table)
+                    .col(TaskTombstones::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TaskTombstones::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_tasks_updated_at")
+                    .table(Tasks::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .drop_column(Tasks::UpdatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Tasks {
+    Table,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum TaskTombstones {
+    Table,
+    TaskId,
+    WorkspaceId,
+    AssignedTo,
+    AssignedBy,
+    DeletedAt,
+}
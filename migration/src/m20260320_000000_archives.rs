@@ -0,0 +1,150 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Same columns as changes, see services::archive
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChangesArchive::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChangesArchive::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(ChangesArchive::Old))
+                    .col(string(ChangesArchive::New))
+                    .col(integer(ChangesArchive::Index))
+                    .col(string(ChangesArchive::TaskId))
+                    .col(timestamp_null(ChangesArchive::CreatedAt))
+                    .col(
+                        ColumnDef::new(ChangesArchive::ArchivedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_changes_archive_tasks")
+                            .from(ChangesArchive::Table, ChangesArchive::TaskId)
+                            .to(Tasks::Table, Tasks::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_changes_archive_task_id")
+                    .table(ChangesArchive::Table)
+                    .col(ChangesArchive::TaskId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Same columns as messages; only messages no task points to are archived
+        manager
+            .create_table(
+                Table::create()
+                    .table(MessagesArchive::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MessagesArchive::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(MessagesArchive::Content))
+                    .col(boolean(MessagesArchive::ContentEncrypted).default(false))
+                    .col(string(MessagesArchive::ExternalId))
+                    .col(string(MessagesArchive::PersonId))
+                    .col(string(MessagesArchive::Channel))
+                    .col(string(MessagesArchive::Timestamp))
+                    .col(string_null(MessagesArchive::MergedIntoTaskId))
+                    .col(timestamp(MessagesArchive::CreatedAt))
+                    .col(
+                        ColumnDef::new(MessagesArchive::ArchivedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        for (name, column) in [
+            (
+                "idx_messages_archive_external_id",
+                MessagesArchive::ExternalId,
+            ),
+            ("idx_messages_archive_person_id", MessagesArchive::PersonId),
+            (
+                "idx_messages_archive_merged_into_task_id",
+                MessagesArchive::MergedIntoTaskId,
+            ),
+        ] {
+            manager
+                .create_index(
+                    Index::create()
+                        .name(name)
+                        .table(MessagesArchive::Table)
+                        .col(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MessagesArchive::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ChangesArchive::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Tasks {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum ChangesArchive {
+    Table,
+    Id,
+    Old,
+    New,
+    Index,
+    TaskId,
+    CreatedAt,
+    ArchivedAt,
+}
+
+#[derive(DeriveIden)]
+enum MessagesArchive {
+    Table,
+    Id,
+    Content,
+    ContentEncrypted,
+    ExternalId,
+    PersonId,
+    Channel,
+    Timestamp,
+    MergedIntoTaskId,
+    CreatedAt,
+    ArchivedAt,
+}
@@ -0,0 +1,36 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Hooks subscribed before deliveries were signed have no secret until rotated
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(HookSubscriptions::Table)
+                    .add_column(text_null(HookSubscriptions::Secret))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(HookSubscriptions::Table)
+                    .drop_column(HookSubscriptions::Secret)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum HookSubscriptions {
+    Table,
+    Secret,
+}
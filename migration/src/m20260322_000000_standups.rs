@@ -0,0 +1,126 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WorkspaceSettings::Table)
+                    .add_column(json_null(WorkspaceSettings::Standup))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(StandupEntries::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StandupEntries::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(StandupEntries::WorkspaceId))
+                    .col(string(StandupEntries::PersonId))
+                    .col(date(StandupEntries::Day))
+                    .col(text(StandupEntries::Yesterday))
+                    .col(text(StandupEntries::Today))
+                    .col(text_null(StandupEntries::Blockers))
+                    .col(
+                        ColumnDef::new(StandupEntries::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(StandupEntries::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_standup_entries_workspace")
+                            .from(StandupEntries::Table, StandupEntries::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_standup_entries_person")
+                            .from(StandupEntries::Table, StandupEntries::PersonId)
+                            .to(Persons::Table, Persons::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One answer per member and day; answering again replaces it
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_standup_entries_workspace_day_person")
+                    .table(StandupEntries::Table)
+                    .col(StandupEntries::WorkspaceId)
+                    .col(StandupEntries::Day)
+                    .col(StandupEntries::PersonId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StandupEntries::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WorkspaceSettings::Table)
+                    .drop_column(WorkspaceSettings::Standup)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StandupEntries {
+    Table,
+    Id,
+    WorkspaceId,
+    PersonId,
+    Day,
+    Yesterday,
+    Today,
+    Blockers,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum WorkspaceSettings {
+    Table,
+    Standup,
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Persons {
+    Table,
+    Id,
+}
@@ -0,0 +1,84 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WorkspaceSettings::Table)
+                    .add_column(string(WorkspaceSettings::PrivateChannelPolicy).default("allow"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Messages::Table)
+                    .add_column(boolean(Messages::ChannelHidden).default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Archived messages keep the flag, so unarchiving them hides the channel again
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MessagesArchive::Table)
+                    .add_column(boolean(MessagesArchive::ChannelHidden).default(false))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MessagesArchive::Table)
+                    .drop_column(MessagesArchive::ChannelHidden)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Messages::Table)
+                    .drop_column(Messages::ChannelHidden)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WorkspaceSettings::Table)
+                    .drop_column(WorkspaceSettings::PrivateChannelPolicy)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WorkspaceSettings {
+    Table,
+    PrivateChannelPolicy,
+}
+
+#[derive(DeriveIden)]
+enum Messages {
+    Table,
+    ChannelHidden,
+}
+
+#[derive(DeriveIden)]
+enum MessagesArchive {
+    Table,
+    ChannelHidden,
+}
@@ -0,0 +1,107 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WorkspaceCredentials::Table)
+                    .add_column(string_null(WorkspaceCredentials::EnterpriseId))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WorkspaceLinks::Table)
+                    .add_column(string_null(WorkspaceLinks::SlackTeamId))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Messages::Table)
+                    .add_column(string_null(Messages::SlackTeamId))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MessagesArchive::Table)
+                    .add_column(string_null(MessagesArchive::SlackTeamId))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MessagesArchive::Table)
+                    .drop_column(MessagesArchive::SlackTeamId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Messages::Table)
+                    .drop_column(Messages::SlackTeamId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WorkspaceLinks::Table)
+                    .drop_column(WorkspaceLinks::SlackTeamId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WorkspaceCredentials::Table)
+                    .drop_column(WorkspaceCredentials::EnterpriseId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WorkspaceCredentials {
+    Table,
+    EnterpriseId,
+}
+
+#[derive(DeriveIden)]
+enum WorkspaceLinks {
+    Table,
+    SlackTeamId,
+}
+
+#[derive(DeriveIden)]
+enum Messages {
+    Table,
+    SlackTeamId,
+}
+
+#[derive(DeriveIden)]
+enum MessagesArchive {
+    Table,
+    SlackTeamId,
+}
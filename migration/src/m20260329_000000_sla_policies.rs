@@ -0,0 +1,145 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SlaPolicies::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SlaPolicies::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(SlaPolicies::WorkspaceId))
+                    .col(string(SlaPolicies::Name))
+                    .col(string(SlaPolicies::Status))
+                    .col(integer(SlaPolicies::MaxHours))
+                    .col(
+                        ColumnDef::new(SlaPolicies::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_sla_policies_workspace")
+                            .from(SlaPolicies::Table, SlaPolicies::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sla_policies_workspace_name")
+                    .table(SlaPolicies::Table)
+                    .col(SlaPolicies::WorkspaceId)
+                    .col(SlaPolicies::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SlaBreaches::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SlaBreaches::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(SlaBreaches::PolicyId))
+                    .col(string(SlaBreaches::TaskId))
+                    .col(string(SlaBreaches::Status))
+                    .col(timestamp(SlaBreaches::EnteredAt))
+                    .col(timestamp(SlaBreaches::BreachedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_sla_breaches_policy")
+                            .from(SlaBreaches::Table, SlaBreaches::PolicyId)
+                            .to(SlaPolicies::Table, SlaPolicies::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_sla_breaches_task")
+                            .from(SlaBreaches::Table, SlaBreaches::TaskId)
+                            .to(Tasks::Table, Tasks::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One breach per policy and stay of a task in a status
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sla_breaches_policy_task_entered")
+                    .table(SlaBreaches::Table)
+                    .col(SlaBreaches::PolicyId)
+                    .col(SlaBreaches::TaskId)
+                    .col(SlaBreaches::EnteredAt)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SlaBreaches::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(SlaPolicies::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SlaPolicies {
+    Table,
+    Id,
+    WorkspaceId,
+    Name,
+    Status,
+    MaxHours,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum SlaBreaches {
+    Table,
+    Id,
+    PolicyId,
+    TaskId,
+    Status,
+    EnteredAt,
+    BreachedAt,
+}
+
+#[derive(DeriveIden)]
+enum Workspaces {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Tasks {
+    Table,
+    Id,
+}